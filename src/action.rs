@@ -1,33 +1,161 @@
+use std::fmt;
+use std::ops::{Index, IndexMut};
+use std::str::FromStr;
+
 use serde::{Deserialize, Serialize};
 
-use crate::card::BUILD_PILE_COUNT;
+use crate::card::{BUILD_PILE_COUNT, DISCARD_PILE_COUNT, HAND_SIZE, MAX_DISCARD_PILES};
+
+/// Defines a typed zero-based index. The wrapper serializes as a plain number, converts
+/// to and from `usize`, and indexes slices, arrays and vectors directly.
+macro_rules! typed_index {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        #[derive(
+            Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+        )]
+        #[serde(transparent)]
+        pub struct $name(pub usize);
+
+        impl $name {
+            #[inline]
+            pub const fn index(self) -> usize {
+                self.0
+            }
+        }
+
+        impl From<usize> for $name {
+            fn from(index: usize) -> Self {
+                Self(index)
+            }
+        }
+
+        impl From<$name> for usize {
+            fn from(id: $name) -> usize {
+                id.0
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                self.0.fmt(f)
+            }
+        }
+
+        impl<T> Index<$name> for [T] {
+            type Output = T;
+
+            #[inline]
+            fn index(&self, id: $name) -> &T {
+                &self[id.0]
+            }
+        }
+
+        impl<T> IndexMut<$name> for [T] {
+            #[inline]
+            fn index_mut(&mut self, id: $name) -> &mut T {
+                &mut self[id.0]
+            }
+        }
+
+        impl<T> Index<$name> for Vec<T> {
+            type Output = T;
+
+            #[inline]
+            fn index(&self, id: $name) -> &T {
+                &self[id.0]
+            }
+        }
+
+        impl<T> IndexMut<$name> for Vec<T> {
+            #[inline]
+            fn index_mut(&mut self, id: $name) -> &mut T {
+                &mut self[id.0]
+            }
+        }
+    };
+}
+
+typed_index!(
+    /// Zero-based index of a player within the game.
+    PlayerId
+);
 
-/// Zero-based index of a player within the game.
-pub type PlayerId = usize;
+typed_index!(
+    /// One of the four shared build piles.
+    BuildPileId
+);
+
+typed_index!(
+    /// One of the acting player's four discard piles.
+    DiscardPileId
+);
+
+impl PlayerId {
+    /// Players of a game with `count` seats, in turn order.
+    pub fn all(count: usize) -> impl Iterator<Item = PlayerId> {
+        (0..count).map(PlayerId)
+    }
+
+    /// The player `seats` places after this one in a game of `players`.
+    pub fn offset(self, seats: usize, players: usize) -> PlayerId {
+        PlayerId((self.0 + seats) % players.max(1))
+    }
+
+    /// How many seats after this player `other` sits in a game of `players`.
+    pub fn seats_until(self, other: PlayerId, players: usize) -> usize {
+        let players = players.max(1);
+        (other.0 + players - self.0 % players) % players
+    }
+}
+
+impl BuildPileId {
+    pub const ALL: [BuildPileId; BUILD_PILE_COUNT] = {
+        let mut all = [BuildPileId(0); BUILD_PILE_COUNT];
+        let mut i = 0;
+        while i < BUILD_PILE_COUNT {
+            all[i] = BuildPileId(i);
+            i += 1;
+        }
+        all
+    };
+}
+
+impl DiscardPileId {
+    pub const ALL: [DiscardPileId; DISCARD_PILE_COUNT] = {
+        let mut all = [DiscardPileId(0); DISCARD_PILE_COUNT];
+        let mut i = 0;
+        while i < DISCARD_PILE_COUNT {
+            all[i] = DiscardPileId(i);
+            i += 1;
+        }
+        all
+    };
+}
 
 /// Location a card can be taken from when performing a play action.
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub enum CardSource {
     /// Card taken from the active player's hand by index.
     Hand(usize),
     /// Card taken from the active player's stock pile (top card).
     Stock,
     /// Card taken from one of the active player's discard piles (top card).
-    Discard(usize),
+    Discard(DiscardPileId),
 }
 
 /// Action available to an agent during its turn.
-#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub enum Action {
     /// Play a card from a source onto the specified build pile.
     Play {
         source: CardSource,
-        build_pile: usize,
+        build_pile: BuildPileId,
     },
     /// Discard a card from the hand onto one of the four personal discard piles.
     Discard {
         hand_index: usize,
-        discard_pile: usize,
+        discard_pile: DiscardPileId,
     },
     /// Finish the turn when the hand is empty.
     EndTurn,
@@ -35,7 +163,7 @@ pub enum Action {
 
 impl Action {
     /// Returns the build pile index if the action is a play.
-    pub fn build_pile(&self) -> Option<usize> {
+    pub fn build_pile(&self) -> Option<BuildPileId> {
         match self {
             Action::Play { build_pile, .. } => Some(*build_pile),
             _ => None,
@@ -45,7 +173,199 @@ impl Action {
     /// Validates whether the build pile index lies within range.
     pub fn build_pile_in_range(&self) -> bool {
         self.build_pile()
-            .map(|idx| idx < BUILD_PILE_COUNT)
+            .map(|idx| idx.0 < BUILD_PILE_COUNT)
             .unwrap_or(true)
     }
 }
+
+/// Compact notation: `H3` (hand slot 3), `S` (stock), `D2` (discard pile 2).
+impl fmt::Display for CardSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CardSource::Hand(i) => write!(f, "H{i}"),
+            CardSource::Stock => f.write_str("S"),
+            CardSource::Discard(d) => write!(f, "D{d}"),
+        }
+    }
+}
+
+impl FromStr for CardSource {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let index = |digits: &str| {
+            digits
+                .parse::<usize>()
+                .map_err(|_| format!("invalid card source: {s}"))
+        };
+        match s.get(..1).map(str::to_ascii_uppercase).as_deref() {
+            Some("S") if s.len() == 1 => Ok(CardSource::Stock),
+            Some("H") => index(&s[1..]).map(CardSource::Hand),
+            Some("D") => index(&s[1..]).map(|d| CardSource::Discard(DiscardPileId(d))),
+            _ => Err(format!("invalid card source: {s}")),
+        }
+    }
+}
+
+/// Compact notation used by replays and scripts: `H3>B1` plays hand slot 3 onto build
+/// pile 1, `D2>B0` and `S>B3` play from a discard pile or the stock, `X4>D2` discards
+/// hand slot 4 onto discard pile 2 and `END` ends the turn. Parsing is case-insensitive
+/// and ignores surrounding whitespace.
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Action::Play { source, build_pile } => write!(f, "{source}>B{build_pile}"),
+            Action::Discard {
+                hand_index,
+                discard_pile,
+            } => write!(f, "X{hand_index}>D{discard_pile}"),
+            Action::EndTurn => f.write_str("END"),
+        }
+    }
+}
+
+impl FromStr for Action {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let text = s.trim().to_ascii_uppercase();
+        if text == "END" {
+            return Ok(Action::EndTurn);
+        }
+        let invalid = || format!("invalid action: {}", s.trim());
+        let (from, to) = text.split_once('>').ok_or_else(invalid)?;
+        let (from, to) = (from.trim(), to.trim());
+        let target = |prefix: char| {
+            to.strip_prefix(prefix)
+                .and_then(|digits| digits.parse::<usize>().ok())
+                .ok_or_else(invalid)
+        };
+        if let Some(hand) = from.strip_prefix('X') {
+            let hand_index = hand.parse::<usize>().map_err(|_| invalid())?;
+            return Ok(Action::Discard {
+                hand_index,
+                discard_pile: DiscardPileId(target('D')?),
+            });
+        }
+        let source = from.parse::<CardSource>().map_err(|_| invalid())?;
+        Ok(Action::Play {
+            source,
+            build_pile: BuildPileId(target('B')?),
+        })
+    }
+}
+
+/// Play sources in index order: hand slots, stock, discard piles.
+const PLAY_SOURCES: usize = HAND_SIZE + 1 + MAX_DISCARD_PILES;
+const PLAY_ACTIONS: usize = PLAY_SOURCES * BUILD_PILE_COUNT;
+const DISCARD_ACTIONS: usize = HAND_SIZE * MAX_DISCARD_PILES;
+
+/// Number of distinct actions a player can take with a full hand: every play source onto
+/// every build pile, every hand slot onto every discard pile (counting a handicap seat's
+/// extra pile), and ending the turn.
+pub const ACTION_SPACE: usize = PLAY_ACTIONS + DISCARD_ACTIONS + 1;
+
+/// Version of the [`Action::index`] table. Bump whenever an index changes meaning, so
+/// policies and datasets recorded against an older table are rejected.
+pub const ACTION_SPACE_VERSION: u32 = 1;
+
+impl Action {
+    /// Dense index of the action in `0..ACTION_SPACE`, or `None` when an index is out of
+    /// range for the standard table.
+    pub fn index(&self) -> Option<usize> {
+        match *self {
+            Action::Play { source, build_pile } => {
+                let source = match source {
+                    CardSource::Hand(i) if i < HAND_SIZE => i,
+                    CardSource::Stock => HAND_SIZE,
+                    CardSource::Discard(d) if d.0 < MAX_DISCARD_PILES => HAND_SIZE + 1 + d.0,
+                    _ => return None,
+                };
+                (build_pile.0 < BUILD_PILE_COUNT)
+                    .then_some(source * BUILD_PILE_COUNT + build_pile.0)
+            }
+            Action::Discard {
+                hand_index,
+                discard_pile,
+            } => (hand_index < HAND_SIZE && discard_pile.0 < MAX_DISCARD_PILES)
+                .then_some(PLAY_ACTIONS + hand_index * MAX_DISCARD_PILES + discard_pile.0),
+            Action::EndTurn => Some(ACTION_SPACE - 1),
+        }
+    }
+
+    /// Inverse of [`Action::index`].
+    pub fn from_index(index: usize) -> Option<Action> {
+        if index < PLAY_ACTIONS {
+            let (source, build_pile) = (index / BUILD_PILE_COUNT, index % BUILD_PILE_COUNT);
+            let source = match source {
+                i if i < HAND_SIZE => CardSource::Hand(i),
+                HAND_SIZE => CardSource::Stock,
+                d => CardSource::Discard(DiscardPileId(d - HAND_SIZE - 1)),
+            };
+            Some(Action::Play {
+                source,
+                build_pile: BuildPileId(build_pile),
+            })
+        } else if index < PLAY_ACTIONS + DISCARD_ACTIONS {
+            let offset = index - PLAY_ACTIONS;
+            Some(Action::Discard {
+                hand_index: offset / MAX_DISCARD_PILES,
+                discard_pile: DiscardPileId(offset % MAX_DISCARD_PILES),
+            })
+        } else if index == ACTION_SPACE - 1 {
+            Some(Action::EndTurn)
+        } else {
+            None
+        }
+    }
+}
+
+/// Set of actions stored as a bitset over [`Action::index`], with O(1) membership tests.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct LegalSet(u128);
+
+const _: () = assert!(ACTION_SPACE <= u128::BITS as usize);
+
+impl LegalSet {
+    pub fn from_actions(actions: &[Action]) -> Self {
+        let mut set = Self::default();
+        for action in actions {
+            set.insert(action);
+        }
+        set
+    }
+
+    /// Adds `action`; returns `false` if it has no index and cannot be stored.
+    pub fn insert(&mut self, action: &Action) -> bool {
+        match action.index() {
+            Some(index) => {
+                self.0 |= 1 << index;
+                true
+            }
+            None => false,
+        }
+    }
+
+    #[inline]
+    pub fn contains(&self, action: &Action) -> bool {
+        action
+            .index()
+            .is_some_and(|index| self.0 & (1 << index) != 0)
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Actions in index order (plays, then discards, then end turn).
+    pub fn iter(&self) -> impl Iterator<Item = Action> + '_ {
+        (0..ACTION_SPACE)
+            .filter(|index| self.0 & (1 << index) != 0)
+            .filter_map(Action::from_index)
+    }
+}
@@ -0,0 +1,94 @@
+//! Determinizations: complete games consistent with what one player can see.
+//!
+//! Search bots for hidden-information games sample a full game from the viewer's
+//! information, search it as if it were the real one and average over samples. A sample
+//! keeps every public card (build piles, discard piles, stock tops, the viewer's hand)
+//! and every pile size, and deals the remaining cards of the standard deck uniformly
+//! into the slots the viewer cannot see.
+
+use rand::Rng;
+use rand::seq::SliceRandom;
+
+use crate::bots::planning::unseen_cards;
+use crate::error::GameError;
+use crate::game::Game;
+use crate::state::{FEW_STOCK_CARDS, GameStateView, GameStatus, StockVisibility};
+
+/// Sample a game consistent with `view`: the cards the viewer has not seen, shuffled
+/// into opponents' hands, the stocks below their tops, the draw pile and the recycle
+/// pile. The sample's view for `view.self_player` shows the same cards and counts as
+/// `view`, apart from the action history.
+///
+/// When the game only shows binned or hidden stock counts, the stock sizes are drawn
+/// too: every stock keeps a size its reported count allows, and the sizes add up to
+/// the cards left over once the hands, draw pile and recycle pile are dealt.
+///
+/// Assumes the standard deck; fails when the view holds cards that deck cannot account
+/// for.
+pub fn sample_determinization<R: Rng + ?Sized>(
+    view: &GameStateView,
+    rng: &mut R,
+) -> Result<Game, GameError> {
+    let mut hidden = unseen_cards(view);
+    let mut view = view.clone();
+    resolve_stock_counts(&mut view, hidden.len(), rng)?;
+    hidden.shuffle(rng);
+    Game::from_view(&view, hidden, rng.next_u64())
+}
+
+/// Replace each reported stock count with a size consistent with it, such that the
+/// hidden slots of `view` add up to `hidden` cards.
+fn resolve_stock_counts<R: Rng + ?Sized>(
+    view: &mut GameStateView,
+    hidden: usize,
+    rng: &mut R,
+) -> Result<(), GameError> {
+    let inconsistent = || GameError::InvalidConfiguration("view hides a different number of cards");
+    let self_player = view.self_player;
+    let hands: usize = view
+        .players
+        .iter()
+        .filter(|p| p.id != self_player)
+        .map(|p| p.hand_size)
+        .sum();
+    let stock_cards = (hidden
+        + view
+            .players
+            .iter()
+            .filter(|p| p.stock_top.is_some())
+            .count())
+    .checked_sub(hands + view.draw_pile_count + view.recycle_pile_count)
+    .ok_or_else(inconsistent)?;
+
+    let stock_size = view.settings.stock_size;
+    let visibility = if view.status == GameStatus::Ongoing {
+        view.settings.stock_visibility
+    } else {
+        StockVisibility::Exact
+    };
+    let smallest = |reported: usize| match visibility {
+        StockVisibility::Exact => reported,
+        StockVisibility::Binned if reported <= 1 => reported,
+        StockVisibility::Binned if reported == FEW_STOCK_CARDS.min(stock_size) => 2,
+        StockVisibility::Binned => FEW_STOCK_CARDS + 1,
+        StockVisibility::Hidden => reported.min(1),
+    };
+    let mut counts: Vec<usize> = view.players.iter().map(|p| p.stock_count).collect();
+    let mut excess = counts
+        .iter()
+        .sum::<usize>()
+        .checked_sub(stock_cards)
+        .ok_or_else(inconsistent)?;
+    while excess > 0 {
+        let shrinkable: Vec<usize> = (0..counts.len())
+            .filter(|&i| counts[i] > smallest(view.players[i].stock_count))
+            .collect();
+        let &i = shrinkable.choose(rng).ok_or_else(inconsistent)?;
+        counts[i] -= 1;
+        excess -= 1;
+    }
+    for (player, count) in view.players.iter_mut().zip(counts) {
+        player.stock_count = count;
+    }
+    Ok(())
+}
@@ -0,0 +1,144 @@
+//! Behavioral fingerprints: how a bot plays, independent of how often it wins.
+//!
+//! [`BehaviorCounts`] is filled decision by decision while a game runs and can be summed
+//! across games. [`Fingerprint`] turns the counts into comparable rates.
+
+use serde::{Deserialize, Serialize};
+
+use crate::action::{Action, CardSource};
+use crate::bots::planning::next_build_value;
+use crate::card::DISCARD_PILE_COUNT;
+use crate::state::GameStateView;
+
+/// Whether any opponent's stock top fits a build pile, optionally after `action` is
+/// applied. Discards and end-turn actions leave the build piles unchanged.
+pub fn unlocks_opponent(state: &GameStateView, action: Option<&Action>) -> bool {
+    let mut next: Vec<u8> = state.build_piles.iter().map(|p| p.next_value).collect();
+    if let Some(&Action::Play { build_pile, .. }) = action {
+        match next.get_mut(build_pile.0) {
+            Some(value) => *value = next_build_value(*value),
+            None => return false,
+        }
+    }
+    state
+        .players
+        .iter()
+        .filter(|p| p.id != state.self_player)
+        .filter_map(|p| p.stock_top)
+        .any(|top| next.iter().any(|&value| top.matches_value(value)))
+}
+
+/// Raw decision counts for one seat (or one bot summed over many seats).
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BehaviorCounts {
+    pub decisions: u64,
+    /// Turns ended by a discard or by ending the turn with an empty hand.
+    pub turns: u64,
+    pub plays: u64,
+    /// Decisions where playing the stock top was legal.
+    pub stock_chances: u64,
+    /// Of those, decisions where the stock top was played.
+    pub stock_plays: u64,
+    /// Discards per discard pile.
+    pub discards: [u64; DISCARD_PILE_COUNT],
+    /// Decisions where some legal action unlocks an opponent's stock and another does not.
+    pub block_chances: u64,
+    /// Of those, decisions where the chosen action kept every opponent's stock locked.
+    pub blocks: u64,
+}
+
+impl BehaviorCounts {
+    /// Record one decision of the acting player.
+    pub fn record(&mut self, state: &GameStateView, legal_actions: &[Action], action: &Action) {
+        self.decisions += 1;
+        match action {
+            Action::Play { source, .. } => {
+                self.plays += 1;
+                if matches!(source, CardSource::Stock) {
+                    self.stock_plays += 1;
+                }
+            }
+            Action::Discard { discard_pile, .. } => {
+                self.turns += 1;
+                if let Some(count) = self.discards.get_mut(discard_pile.0) {
+                    *count += 1;
+                }
+            }
+            Action::EndTurn => self.turns += 1,
+        }
+        if legal_actions.iter().any(|a| {
+            matches!(
+                a,
+                Action::Play {
+                    source: CardSource::Stock,
+                    ..
+                }
+            )
+        }) {
+            self.stock_chances += 1;
+        }
+        let unlocks: Vec<bool> = legal_actions
+            .iter()
+            .map(|a| unlocks_opponent(state, Some(a)))
+            .collect();
+        if unlocks.contains(&true) && unlocks.contains(&false) {
+            self.block_chances += 1;
+            if !unlocks_opponent(state, Some(action)) {
+                self.blocks += 1;
+            }
+        }
+    }
+
+    pub fn merge(&mut self, other: &BehaviorCounts) {
+        self.decisions += other.decisions;
+        self.turns += other.turns;
+        self.plays += other.plays;
+        self.stock_chances += other.stock_chances;
+        self.stock_plays += other.stock_plays;
+        for (total, count) in self.discards.iter_mut().zip(other.discards) {
+            *total += count;
+        }
+        self.block_chances += other.block_chances;
+        self.blocks += other.blocks;
+    }
+}
+
+/// Rates describing a bot's style.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Fingerprint {
+    /// Share of stock-play opportunities taken immediately.
+    pub stock_aggressiveness: f64,
+    /// Shannon entropy of discard pile choice, normalized to `[0, 1]` (1 = uniform).
+    pub discard_entropy: f64,
+    /// Share of blocking opportunities where the bot avoided unlocking an opponent.
+    pub blocking_frequency: f64,
+    pub plays_per_turn: f64,
+}
+
+impl Fingerprint {
+    pub fn from_counts(counts: &BehaviorCounts) -> Self {
+        let ratio = |part: u64, whole: u64| {
+            if whole > 0 {
+                part as f64 / whole as f64
+            } else {
+                0.0
+            }
+        };
+        let total: u64 = counts.discards.iter().sum();
+        let entropy: f64 = counts
+            .discards
+            .iter()
+            .filter(|&&c| c > 0)
+            .map(|&c| {
+                let p = c as f64 / total as f64;
+                -p * p.log2()
+            })
+            .sum();
+        Self {
+            stock_aggressiveness: ratio(counts.stock_plays, counts.stock_chances),
+            discard_entropy: entropy / (DISCARD_PILE_COUNT as f64).log2(),
+            blocking_frequency: ratio(counts.blocks, counts.block_chances),
+            plays_per_turn: ratio(counts.plays, counts.turns),
+        }
+    }
+}
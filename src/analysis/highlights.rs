@@ -0,0 +1,199 @@
+//! Highlights: the moments of a recorded game worth looking at first.
+//!
+//! [`find_highlights`] narrates a [`GameRecord`] with [`Commentary`] and picks out the turn
+//! with the biggest swing in win probability, the turn with the longest chain of plays
+//! and every successful block. Each [`Highlight`] carries the turn, the index of its first
+//! move (for jumping to it in a replay viewer) and the turn's commentary as a snippet.
+
+use std::error::Error;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::action::{Action, PlayerId};
+use crate::commentary::{Commentary, Pattern, TurnCommentary};
+use crate::game::Game;
+use crate::replay::GameRecord;
+use crate::state::GameStatus;
+
+use super::rollout::RolloutEstimator;
+
+/// What makes a turn a highlight.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum HighlightKind {
+    /// Over this turn the acting player's win probability moved the most of any turn,
+    /// from `before` to `after`.
+    Swing { before: f64, after: f64 },
+    /// The most build-pile plays of any turn.
+    Chain { plays: usize },
+    /// The player built past `opponent`'s stock card, which the opponent then could not
+    /// play on their next turn.
+    Block { opponent: PlayerId },
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Highlight {
+    /// One-based turn, as numbered in the record.
+    pub turn: usize,
+    /// Index of the turn's first move in [`GameRecord::moves`].
+    pub first_move: usize,
+    pub player: PlayerId,
+    pub kind: HighlightKind,
+    /// The commentary of the turn.
+    pub snippet: String,
+}
+
+impl fmt::Display for Highlight {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Turn {} (move {}): ", self.turn, self.first_move + 1)?;
+        match &self.kind {
+            HighlightKind::Swing { before, after } => write!(
+                f,
+                "biggest swing, Player {} {:.0}% -> {:.0}%",
+                self.player,
+                before * 100.0,
+                after * 100.0
+            )?,
+            HighlightKind::Chain { plays } => write!(f, "longest chain, {plays} plays")?,
+            HighlightKind::Block { opponent } => write!(f, "blocks Player {opponent}")?,
+        }
+        write!(f, "\n  {}", self.snippet)
+    }
+}
+
+/// How [`find_highlights`] estimates win probabilities.
+#[derive(Clone, Debug)]
+pub struct HighlightConfig {
+    /// One bot spec per seat for the rollouts; empty skips the swing.
+    pub rollout_bots: Vec<String>,
+    /// Rollouts per turn; 0 skips the swing.
+    pub samples: usize,
+    pub seed: u64,
+}
+
+impl Default for HighlightConfig {
+    fn default() -> Self {
+        Self {
+            rollout_bots: Vec::new(),
+            samples: 32,
+            seed: 0x4816_4C17,
+        }
+    }
+}
+
+/// Highlights of `record` in turn order. The swing estimates each turn from the acting
+/// player's information with rollouts of `config.rollout_bots`, so it costs
+/// `config.samples` games per turn.
+pub fn find_highlights(
+    record: &GameRecord,
+    config: &HighlightConfig,
+) -> Result<Vec<Highlight>, Box<dyn Error>> {
+    let commentary = Commentary::from_record(record)?;
+    let turns = &commentary.turns;
+    let mut highlights = Vec::new();
+
+    if config.samples > 0 && !config.rollout_bots.is_empty() {
+        let starts = turn_starts(record, turns)?;
+        let estimator = RolloutEstimator::new(config.samples).with_seed(config.seed);
+        let mut chances = Vec::with_capacity(starts.len());
+        for game in &starts {
+            let estimate = estimator
+                .with_perspective(game.current_player())
+                .estimate(game, &config.rollout_bots)?;
+            chances.push(estimate);
+        }
+        let swing = turns
+            .iter()
+            .enumerate()
+            .map(|(index, turn)| {
+                let before = chances[index].probability(turn.player);
+                let after = match chances.get(index + 1) {
+                    Some(estimate) => estimate.probability(turn.player),
+                    None => final_chance(commentary.result, turn.player),
+                };
+                (turn, before, after)
+            })
+            .reduce(|best, next| {
+                if (next.2 - next.1).abs() > (best.2 - best.1).abs() {
+                    next
+                } else {
+                    best
+                }
+            });
+        if let Some((turn, before, after)) = swing {
+            highlights.push(highlight(turn, HighlightKind::Swing { before, after }));
+        }
+    }
+
+    let chain = turns
+        .iter()
+        .enumerate()
+        .map(|(index, turn)| {
+            let end = turns
+                .get(index + 1)
+                .map_or(record.moves.len(), |next| next.first_move);
+            let plays = record.moves[turn.first_move..end]
+                .iter()
+                .filter(|action| matches!(action, Action::Play { .. }))
+                .count();
+            (turn, plays)
+        })
+        .reduce(|best, next| if next.1 > best.1 { next } else { best });
+    if let Some((turn, plays)) = chain
+        && plays > 0
+    {
+        highlights.push(highlight(turn, HighlightKind::Chain { plays }));
+    }
+
+    for (index, turn) in turns.iter().enumerate() {
+        for pattern in &turn.patterns {
+            let Pattern::Block { opponent } = *pattern else {
+                continue;
+            };
+            let reply = turns[index + 1..]
+                .iter()
+                .find(|later| later.player == opponent);
+            if reply.is_some_and(|reply| !reply.patterns.contains(&Pattern::Unlock)) {
+                highlights.push(highlight(turn, HighlightKind::Block { opponent }));
+            }
+        }
+    }
+
+    highlights.sort_by_key(|highlight| highlight.turn);
+    Ok(highlights)
+}
+
+fn highlight(turn: &TurnCommentary, kind: HighlightKind) -> Highlight {
+    Highlight {
+        turn: turn.turn,
+        first_move: turn.first_move,
+        player: turn.player,
+        kind,
+        snippet: turn.text.clone(),
+    }
+}
+
+/// The game as it stood before the first move of every turn.
+fn turn_starts(record: &GameRecord, turns: &[TurnCommentary]) -> Result<Vec<Game>, Box<dyn Error>> {
+    let mut starts = Vec::with_capacity(turns.len());
+    let mut ply = 0;
+    record.replay_with(|game, _, _| {
+        if turns
+            .get(starts.len())
+            .is_some_and(|turn| turn.first_move == ply)
+        {
+            starts.push(game.clone());
+        }
+        ply += 1;
+    })?;
+    Ok(starts)
+}
+
+fn final_chance(result: GameStatus, player: PlayerId) -> f64 {
+    if result == (GameStatus::Finished { winner: player }) {
+        1.0
+    } else {
+        0.0
+    }
+}
@@ -0,0 +1,14 @@
+//! Post-hoc analysis of games and bot behavior.
+
+pub mod determinize;
+pub mod fingerprint;
+pub mod highlights;
+pub mod positions;
+pub mod rollout;
+pub mod snapshots;
+
+pub use determinize::sample_determinization;
+pub use fingerprint::{BehaviorCounts, Fingerprint, unlocks_opponent};
+pub use highlights::{Highlight, HighlightConfig, HighlightKind, find_highlights};
+pub use positions::{Criterion, CriticalPosition, append_position, load_positions, mine_positions};
+pub use rollout::{PlayerEstimate, RolloutEstimator, WinEstimate, estimate_win_probability};
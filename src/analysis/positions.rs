@@ -0,0 +1,254 @@
+//! Database of critical positions with known acceptable moves, used as a regression suite
+//! for strong bots.
+//!
+//! Positions are stored one JSON object per line so new ones can be appended without
+//! rewriting the file. [`mine_positions`] extracts candidates from simulated games; mined
+//! positions should be reviewed before they are added to the curated set.
+
+use std::collections::HashSet;
+use std::error::Error;
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::action::{Action, CardSource};
+use crate::bot::Bot;
+use crate::bots::planning::TurnPosition;
+use crate::card::Card;
+use crate::state::GameStateView;
+
+/// A decision point together with the moves considered correct there.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CriticalPosition {
+    pub name: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub note: String,
+    /// View of the player to move.
+    pub state: GameStateView,
+    /// Any of these moves passes the position.
+    pub acceptable: Vec<Action>,
+}
+
+impl CriticalPosition {
+    /// Ask `bot` for its move. Returns the move when it is not acceptable.
+    pub fn check(&self, bot: &mut dyn Bot) -> Result<(), Action> {
+        let legal = self.state.legal_actions();
+        let action = bot.select_action(&self.state, &legal);
+        if self.acceptable.contains(&action) {
+            Ok(())
+        } else {
+            Err(action)
+        }
+    }
+}
+
+/// Read every position from a JSON-lines file. Blank lines and lines starting with `//`
+/// are skipped.
+pub fn load_positions(path: &Path) -> Result<Vec<CriticalPosition>, Box<dyn Error>> {
+    let text = fs::read_to_string(path)?;
+    let mut positions = Vec::new();
+    for (line_no, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with("//") {
+            continue;
+        }
+        let position: CriticalPosition = serde_json::from_str(line)
+            .map_err(|err| format!("{}:{}: {err}", path.display(), line_no + 1))?;
+        positions.push(position);
+    }
+    Ok(positions)
+}
+
+/// Append a position to a JSON-lines file, creating it if needed.
+pub fn append_position(path: &Path, position: &CriticalPosition) -> Result<(), Box<dyn Error>> {
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", serde_json::to_string(position)?)?;
+    Ok(())
+}
+
+/// Kinds of critical decisions [`mine_positions`] looks for.
+///
+/// A position matches when some legal moves keep the goal reachable this turn and others
+/// give it up; the former are the acceptable moves. Reachability is searched over plays
+/// visible to the player, so hidden stock reveals and hand refills count as unknown.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Criterion {
+    /// The stock can still be emptied this turn.
+    FinishStock,
+    /// The turn can still end with every opponent's stock card locked. Stock reveals and
+    /// refills are treated as safe since their continuation is unknown.
+    MustBlock,
+    /// The hand can still be emptied (drawing five new cards) or a stock card played.
+    EmptyHand,
+}
+
+/// Search budget per legal move when checking reachability.
+const REACH_NODE_LIMIT: usize = 20_000;
+
+impl Criterion {
+    pub const ALL: [Criterion; 3] = [
+        Criterion::FinishStock,
+        Criterion::MustBlock,
+        Criterion::EmptyHand,
+    ];
+
+    pub fn tag(self) -> &'static str {
+        match self {
+            Criterion::FinishStock => "finish-stock",
+            Criterion::MustBlock => "must-block",
+            Criterion::EmptyHand => "can-empty-hand",
+        }
+    }
+
+    /// Acceptable moves if `state` matches this criterion.
+    pub fn acceptable(self, state: &GameStateView, legal: &[Action]) -> Option<Vec<Action>> {
+        let root = TurnPosition::from_view(state)?;
+        let stock_playable = legal.iter().any(plays_stock);
+        if stock_playable && self != Criterion::FinishStock {
+            return None;
+        }
+        let opponent_tops: Vec<Card> = state
+            .players
+            .iter()
+            .filter(|p| p.id != state.self_player)
+            .filter_map(|p| p.stock_top)
+            .collect();
+        let mut acceptable = Vec::new();
+        for action in legal {
+            let mut position = root.clone();
+            if !position.apply(action) {
+                continue;
+            }
+            let ended = !matches!(action, Action::Play { .. });
+            let mut reach = Reach {
+                criterion: self,
+                opponent_tops: &opponent_tops,
+                visited: HashSet::new(),
+            };
+            if reach.search(&position, ended) {
+                acceptable.push(action.clone());
+            }
+        }
+        (!acceptable.is_empty() && acceptable.len() < legal.len()).then_some(acceptable)
+    }
+}
+
+struct Reach<'a> {
+    criterion: Criterion,
+    opponent_tops: &'a [Card],
+    visited: HashSet<TurnPosition>,
+}
+
+impl Reach<'_> {
+    fn safe(&self, position: &TurnPosition) -> bool {
+        !self.opponent_tops.iter().any(|top| {
+            position
+                .build_next
+                .iter()
+                .any(|&next| top.matches_value(next))
+        })
+    }
+
+    /// Whether the goal holds at `position` or after further plays this turn.
+    fn search(&mut self, position: &TurnPosition, ended: bool) -> bool {
+        let reached = match self.criterion {
+            Criterion::FinishStock => position.stock_count == 0,
+            // Stock progress is worth more than a refill.
+            Criterion::EmptyHand => position.hand.is_empty() || position.stock_played > 0,
+            Criterion::MustBlock => {
+                let unknown = position.stock_played > 0 && position.stock_top.is_none();
+                unknown || (ended && self.safe(position))
+            }
+        };
+        if reached {
+            return true;
+        }
+        if ended || self.visited.len() >= REACH_NODE_LIMIT {
+            return false;
+        }
+        if !self.visited.insert(position.clone()) {
+            return false;
+        }
+        if self.criterion == Criterion::MustBlock
+            && (position.hand.is_empty() || self.safe(position))
+        {
+            // Discarding here leaves the piles as they are; an empty hand refills with
+            // unknown cards.
+            return true;
+        }
+        position.plays().into_iter().any(|play| {
+            let mut child = position.clone();
+            child.apply(&play) && self.search(&child, false)
+        })
+    }
+}
+
+impl fmt::Display for Criterion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.tag())
+    }
+}
+
+impl FromStr for Criterion {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Criterion::ALL
+            .into_iter()
+            .find(|c| c.tag() == s.trim())
+            .ok_or_else(|| format!("unknown criterion: {s}"))
+    }
+}
+
+fn plays_stock(action: &Action) -> bool {
+    matches!(
+        action,
+        Action::Play {
+            source: CardSource::Stock,
+            ..
+        }
+    )
+}
+
+/// Collect positions matching any of `criteria` from a sequence of decisions. `decisions`
+/// yields the view and legal actions of every decision in the games being mined; `name`
+/// labels each mined position.
+pub fn mine_positions<'a, I, N>(
+    decisions: I,
+    criteria: &[Criterion],
+    mut name: N,
+) -> Vec<CriticalPosition>
+where
+    I: IntoIterator<Item = (&'a GameStateView, &'a [Action])>,
+    N: FnMut(Criterion) -> String,
+{
+    let mut mined = Vec::new();
+    // Consecutive decisions of one turn usually repeat the same situation; keep the first.
+    let mut matched_last = vec![false; criteria.len()];
+    for (state, legal) in decisions {
+        for (&criterion, matched) in criteria.iter().zip(&mut matched_last) {
+            let acceptable = criterion.acceptable(state, legal);
+            let repeat = *matched;
+            *matched = acceptable.is_some();
+            if repeat {
+                continue;
+            }
+            if let Some(acceptable) = acceptable {
+                mined.push(CriticalPosition {
+                    name: name(criterion),
+                    tags: vec![criterion.tag().to_string()],
+                    note: String::from("mined; review before curating"),
+                    state: state.clone(),
+                    acceptable,
+                });
+            }
+        }
+    }
+    mined
+}
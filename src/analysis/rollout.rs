@@ -0,0 +1,155 @@
+//! Monte Carlo estimates of game outcomes from a position.
+//!
+//! Each rollout copies the game, optionally redeals the cards hidden from one player
+//! (so the estimate only uses that player's information), seats fresh bots built from
+//! their specs and plays to the end.
+
+use std::error::Error;
+
+use serde::{Deserialize, Serialize};
+
+use crate::action::PlayerId;
+use crate::bot::Bot;
+use crate::bots::create_bot_from_spec;
+use crate::game::Game;
+use crate::runner::{mix_seed, play_out};
+
+/// z-score of the two-sided 95% confidence interval.
+const Z_95: f64 = 1.96;
+
+/// Win estimate for one player.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PlayerEstimate {
+    pub player: PlayerId,
+    pub wins: usize,
+    pub probability: f64,
+    /// Lower bound of the 95% Wilson score interval.
+    pub ci_low: f64,
+    /// Upper bound of the 95% Wilson score interval.
+    pub ci_high: f64,
+}
+
+/// Result of [`RolloutEstimator::estimate`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WinEstimate {
+    pub samples: usize,
+    /// Rollouts that ended in a draw or hit the action cap.
+    pub unfinished: usize,
+    pub players: Vec<PlayerEstimate>,
+}
+
+impl WinEstimate {
+    pub fn probability(&self, player: PlayerId) -> f64 {
+        self.players
+            .iter()
+            .find(|p| p.player == player)
+            .map_or(0.0, |p| p.probability)
+    }
+}
+
+/// Rollout settings.
+#[derive(Clone, Copy, Debug)]
+pub struct RolloutEstimator {
+    pub samples: usize,
+    pub seed: u64,
+    /// Cap on actions per rollout; longer rollouts count as unfinished.
+    pub max_actions: usize,
+    /// Player whose information the estimate is based on. `None` plays out the true
+    /// hidden cards, which only varies the bots' own randomness.
+    pub perspective: Option<PlayerId>,
+}
+
+impl RolloutEstimator {
+    pub fn new(samples: usize) -> Self {
+        Self {
+            samples,
+            seed: 0x0E57_1A7E,
+            max_actions: 2000,
+            perspective: None,
+        }
+    }
+
+    pub fn with_perspective(mut self, perspective: PlayerId) -> Self {
+        self.perspective = Some(perspective);
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Play `samples` rollouts from `game` with one bot spec per seat.
+    pub fn estimate(&self, game: &Game, bots: &[String]) -> Result<WinEstimate, Box<dyn Error>> {
+        let players = game.settings().num_players;
+        if bots.len() != players {
+            return Err(format!("expected {players} bot specs, received {}", bots.len()).into());
+        }
+        if self.samples == 0 {
+            return Err("rollout samples must be positive".into());
+        }
+        let mut wins = vec![0usize; players];
+        let mut unfinished = 0usize;
+        for sample in 0..self.samples {
+            let mut rollout = game.clone();
+            if let Some(perspective) = self.perspective {
+                rollout.redeal_hidden(perspective, mix_seed(self.seed, sample as u64, 0))?;
+            }
+            let mut seats: Vec<Box<dyn Bot>> = Vec::with_capacity(players);
+            for (seat, spec) in bots.iter().enumerate() {
+                let bot_seed = mix_seed(self.seed, sample as u64, seat as u64 + 1);
+                seats.push(create_bot_from_spec(spec, PlayerId(seat), bot_seed)?);
+            }
+            play_out(&mut rollout, &mut seats, self.max_actions, |_| {})?;
+            match rollout.winner() {
+                Some(winner) => wins[winner] += 1,
+                None => unfinished += 1,
+            }
+        }
+        let players = wins
+            .iter()
+            .enumerate()
+            .map(|(player, &wins)| {
+                let (ci_low, ci_high) = wilson_interval(wins, self.samples);
+                PlayerEstimate {
+                    player: PlayerId(player),
+                    wins,
+                    probability: wins as f64 / self.samples as f64,
+                    ci_low,
+                    ci_high,
+                }
+            })
+            .collect();
+        Ok(WinEstimate {
+            samples: self.samples,
+            unfinished,
+            players,
+        })
+    }
+}
+
+/// 95% Wilson score interval for `successes` out of `trials`.
+fn wilson_interval(successes: usize, trials: usize) -> (f64, f64) {
+    if trials == 0 {
+        return (0.0, 1.0);
+    }
+    let n = trials as f64;
+    let p = successes as f64 / n;
+    let z2 = Z_95 * Z_95;
+    let denom = 1.0 + z2 / n;
+    let center = (p + z2 / (2.0 * n)) / denom;
+    let half = Z_95 * (p * (1.0 - p) / n + z2 / (4.0 * n * n)).sqrt() / denom;
+    ((center - half).max(0.0), (center + half).min(1.0))
+}
+
+/// Estimate each player's win probability from `game` using the current player's
+/// information, with `samples` rollouts of the given bot specs.
+pub fn estimate_win_probability(
+    game: &Game,
+    bots: &[String],
+    samples: usize,
+) -> Result<WinEstimate, Box<dyn Error>> {
+    RolloutEstimator::new(samples)
+        .with_perspective(game.current_player())
+        .estimate(game, bots)
+}
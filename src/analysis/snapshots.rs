@@ -0,0 +1,115 @@
+//! Action snapshots: the move each bot picks in a fixed set of positions, stored as text
+//! so a refactor of shared planning code that changes a bot's behavior shows up as a
+//! diff.
+//!
+//! The positions are sampled once from seeded games ([`sample_positions`]) and stored as
+//! JSON lines, so they stay put when the bots that played those games change. A snapshot
+//! holds one line per position, `<position> <action>`, with the action in the compact
+//! notation of [`Action`]'s `Display`.
+//!
+//! [`Action`]: crate::action::Action
+
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::action::PlayerId;
+use crate::bot::Bot;
+use crate::bots::create_bot_from_spec;
+use crate::game::Game;
+use crate::runner::{mix_seed, play_out};
+use crate::state::GameStateView;
+
+/// A named decision point of a snapshot suite.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotPosition {
+    pub name: String,
+    /// View of the player to move.
+    pub state: GameStateView,
+}
+
+/// Every `every`-th decision of `games` seeded self-play games of `spec`, alternating
+/// between two and four players so both kinds of table are covered.
+pub fn sample_positions(
+    spec: &str,
+    games: usize,
+    every: usize,
+    seed: u64,
+) -> Result<Vec<SnapshotPosition>, Box<dyn Error>> {
+    let mut positions = Vec::new();
+    for game_index in 0..games {
+        let players = if game_index % 2 == 0 { 2 } else { 4 };
+        let mut bots: Vec<Box<dyn Bot>> = PlayerId::all(players)
+            .map(|seat| create_bot_from_spec(spec, seat, mix_seed(seed, game_index as u64, 1)))
+            .collect::<Result<_, _>>()?;
+        let mut game = Game::builder(players)?
+            .with_seed(mix_seed(seed, game_index as u64, 0))
+            .build()?;
+        let mut decision = 0usize;
+        play_out(&mut game, &mut bots, 2000, |d| {
+            if decision.is_multiple_of(every.max(1)) {
+                positions.push(SnapshotPosition {
+                    name: format!("g{game_index}-d{decision:04}"),
+                    state: d.state.clone(),
+                });
+            }
+            decision += 1;
+        })?;
+    }
+    Ok(positions)
+}
+
+/// Read positions written by [`save_positions`].
+pub fn load_positions(path: &Path) -> Result<Vec<SnapshotPosition>, Box<dyn Error>> {
+    fs::read_to_string(path)?
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(number, line)| {
+            serde_json::from_str(line)
+                .map_err(|err| format!("{}:{}: {err}", path.display(), number + 1).into())
+        })
+        .collect()
+}
+
+/// Write positions as JSON lines.
+pub fn save_positions(path: &Path, positions: &[SnapshotPosition]) -> Result<(), Box<dyn Error>> {
+    let mut out = BufWriter::new(File::create(path)?);
+    for position in positions {
+        writeln!(out, "{}", serde_json::to_string(position)?)?;
+    }
+    out.flush()?;
+    Ok(())
+}
+
+/// The snapshot of `spec`: a fresh bot (seed 0) picks a move in every position.
+pub fn take_snapshot(spec: &str, positions: &[SnapshotPosition]) -> Result<String, Box<dyn Error>> {
+    let mut snapshot = String::new();
+    for position in positions {
+        let mut bot = create_bot_from_spec(spec, position.state.self_player, 0)?;
+        let legal = position.state.legal_actions();
+        let action = bot.select_action(&position.state, &legal);
+        snapshot.push_str(&format!("{} {action}\n", position.name));
+    }
+    Ok(snapshot)
+}
+
+/// Lines on which two snapshots disagree, as `(expected, actual)`; a missing line is
+/// empty.
+pub fn diff_snapshots(expected: &str, actual: &str) -> Vec<(String, String)> {
+    let (mut expected, mut actual) = (expected.lines(), actual.lines());
+    let mut diff = Vec::new();
+    loop {
+        match (expected.next(), actual.next()) {
+            (None, None) => return diff,
+            (e, a) if e != a => diff.push((
+                e.unwrap_or_default().to_string(),
+                a.unwrap_or_default().to_string(),
+            )),
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,498 @@
+//! Compact binary storage for many [`GameRecord`]s.
+//!
+//! A record is encoded as varints: players, seed, rule flags, hand size (0 for the
+//! standard hand), tags, result and then the moves, each as the zigzag delta of its
+//! [`Action::index`] from the previous move. An archive file holds thousands of such
+//! records in blocks of [`BLOCK_RECORDS`], each block optionally compressed with zstd (the
+//! `zstd` feature; single records are too small to compress well), followed by an index
+//! so any game can be read on its own:
+//!
+//! ```text
+//! "SKBA" version compression | block ... |
+//! index: (block offset u64, block length u32, start u32, length u32) per game |
+//! index offset u64, game count u64, "SKBI"
+//! ```
+//!
+//! `start` and `length` locate the record inside its uncompressed block. Integers in the
+//! header, index and footer are little-endian.
+
+use std::error::Error;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use crate::action::{Action, PlayerId};
+use crate::game::{LoopGuard, SeatRules};
+use crate::replay::GameRecord;
+use crate::state::{GameStatus, StockVisibility};
+
+const MAGIC: &[u8; 4] = b"SKBA";
+const INDEX_MAGIC: &[u8; 4] = b"SKBI";
+const VERSION: u8 = 2;
+const HEADER_LEN: u64 = 6;
+const FOOTER_LEN: i64 = 20;
+/// Bytes per index entry.
+const INDEX_ENTRY_LEN: usize = 20;
+/// Records per compressed block.
+pub const BLOCK_RECORDS: usize = 128;
+
+/// Where a record lives: its block in the file and its bytes inside the block.
+#[derive(Clone, Copy, Debug)]
+struct IndexEntry {
+    block_offset: u64,
+    block_len: u32,
+    start: u32,
+    len: u32,
+}
+
+/// How the records of an archive are compressed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Compression {
+    #[default]
+    None,
+    /// zstd at the given level; needs the `zstd` feature to write or read.
+    Zstd(i32),
+}
+
+impl Compression {
+    fn tag(self) -> u8 {
+        match self {
+            Compression::None => 0,
+            Compression::Zstd(_) => 1,
+        }
+    }
+
+    fn from_tag(tag: u8) -> Result<Self, Box<dyn Error>> {
+        match tag {
+            0 => Ok(Compression::None),
+            1 => Ok(Compression::Zstd(0)),
+            _ => Err(format!("unknown archive compression {tag}").into()),
+        }
+    }
+
+    /// Fail early when the build cannot handle this compression.
+    fn check_supported(self) -> Result<(), Box<dyn Error>> {
+        match self {
+            #[cfg(not(feature = "zstd"))]
+            Compression::Zstd(_) => Err(NO_ZSTD.into()),
+            _ => Ok(()),
+        }
+    }
+
+    fn compress(self, bytes: Vec<u8>) -> Result<Vec<u8>, Box<dyn Error>> {
+        match self {
+            Compression::None => Ok(bytes),
+            #[cfg(feature = "zstd")]
+            Compression::Zstd(level) => Ok(zstd::encode_all(bytes.as_slice(), level)?),
+            #[cfg(not(feature = "zstd"))]
+            Compression::Zstd(_) => Err(NO_ZSTD.into()),
+        }
+    }
+
+    fn decompress(self, bytes: Vec<u8>) -> Result<Vec<u8>, Box<dyn Error>> {
+        match self {
+            Compression::None => Ok(bytes),
+            #[cfg(feature = "zstd")]
+            Compression::Zstd(_) => Ok(zstd::decode_all(bytes.as_slice())?),
+            #[cfg(not(feature = "zstd"))]
+            Compression::Zstd(_) => Err(NO_ZSTD.into()),
+        }
+    }
+}
+
+#[cfg(not(feature = "zstd"))]
+const NO_ZSTD: &str = "zstd archives need the `zstd` feature";
+
+/// Encode one record in the binary record format.
+pub fn encode_record(record: &GameRecord) -> Result<Vec<u8>, Box<dyn Error>> {
+    let mut out = Vec::with_capacity(32 + record.moves.len());
+    put_varint(&mut out, record.players as u64);
+    put_varint(&mut out, record.seed);
+    let visibility = match record.stock_visibility {
+        StockVisibility::Exact => 0,
+        StockVisibility::Binned => 1,
+        StockVisibility::Hidden => 2,
+    };
+    let loop_guard = match record.loop_guard {
+        LoopGuard::Off => 0,
+        LoopGuard::Forbid => 1,
+        LoopGuard::EndTurn => 2,
+    };
+    let flags = u8::from(record.stock_size.is_some())
+        | u8::from(record.must_play_stock) << 1
+        | visibility << 2
+        | u8::from(!record.seat_rules.is_empty()) << 4
+        | loop_guard << 5
+        | u8::from(record.max_actions_per_turn.is_some()) << 7;
+    out.push(flags);
+    put_varint(&mut out, record.hand_size.unwrap_or(0) as u64);
+    if let Some(stock) = record.stock_size {
+        put_varint(&mut out, stock as u64);
+    }
+    if let Some(limit) = record.max_actions_per_turn {
+        put_varint(&mut out, limit as u64);
+    }
+    if !record.seat_rules.is_empty() {
+        put_varint(&mut out, record.seat_rules.len() as u64);
+        for rules in &record.seat_rules {
+            out.push(
+                u8::from(rules.extra_discard_pile)
+                    | u8::from(rules.weak_draws) << 1
+                    | u8::from(rules.stock_wilds.is_some()) << 2,
+            );
+            if let Some(wilds) = rules.stock_wilds {
+                put_varint(&mut out, wilds as u64);
+            }
+        }
+    }
+    put_varint(&mut out, record.tags.len() as u64);
+    for (key, value) in &record.tags {
+        put_str(&mut out, key);
+        put_str(&mut out, value);
+    }
+    put_varint(
+        &mut out,
+        match record.result {
+            GameStatus::Ongoing => 0,
+            GameStatus::Draw => 1,
+            GameStatus::Finished { winner } => 2 + winner.0 as u64,
+        },
+    );
+    put_varint(&mut out, record.moves.len() as u64);
+    let mut previous = 0i64;
+    for action in &record.moves {
+        let index = action
+            .index()
+            .ok_or_else(|| format!("move {action} has no action index"))?
+            as i64;
+        let delta = index - previous;
+        put_varint(&mut out, ((delta << 1) ^ (delta >> 63)) as u64);
+        previous = index;
+    }
+    Ok(out)
+}
+
+/// Inverse of [`encode_record`].
+pub fn decode_record(bytes: &[u8]) -> Result<GameRecord, Box<dyn Error>> {
+    let mut input = bytes;
+    let players = get_varint(&mut input)? as usize;
+    let seed = get_varint(&mut input)?;
+    let flags = get_byte(&mut input)?;
+    let hand_size = match get_varint(&mut input)? {
+        0 => None,
+        hand => Some(hand as usize),
+    };
+    let stock_size = if flags & 1 != 0 {
+        Some(get_varint(&mut input)? as usize)
+    } else {
+        None
+    };
+    let max_actions_per_turn = if flags & (1 << 7) != 0 {
+        Some(get_varint(&mut input)? as usize)
+    } else {
+        None
+    };
+    let stock_visibility = match (flags >> 2) & 3 {
+        0 => StockVisibility::Exact,
+        1 => StockVisibility::Binned,
+        2 => StockVisibility::Hidden,
+        other => return Err(format!("unknown stock visibility {other}").into()),
+    };
+    let loop_guard = match (flags >> 5) & 3 {
+        0 => LoopGuard::Off,
+        1 => LoopGuard::Forbid,
+        2 => LoopGuard::EndTurn,
+        other => return Err(format!("unknown loop guard {other}").into()),
+    };
+    let mut seat_rules = Vec::new();
+    if flags & (1 << 4) != 0 {
+        for _ in 0..get_varint(&mut input)? {
+            let rule_flags = get_byte(&mut input)?;
+            seat_rules.push(SeatRules {
+                extra_discard_pile: rule_flags & 1 != 0,
+                weak_draws: rule_flags & 2 != 0,
+                stock_wilds: if rule_flags & 4 != 0 {
+                    Some(get_varint(&mut input)? as usize)
+                } else {
+                    None
+                },
+            });
+        }
+    }
+    let mut tags = Vec::new();
+    for _ in 0..get_varint(&mut input)? {
+        let key = get_str(&mut input)?;
+        tags.push((key, get_str(&mut input)?));
+    }
+    let result = match get_varint(&mut input)? {
+        0 => GameStatus::Ongoing,
+        1 => GameStatus::Draw,
+        n => GameStatus::Finished {
+            winner: PlayerId(n as usize - 2),
+        },
+    };
+    let count = get_varint(&mut input)? as usize;
+    let mut moves = Vec::with_capacity(count.min(input.len()));
+    let mut previous = 0i64;
+    for _ in 0..count {
+        let zigzag = get_varint(&mut input)?;
+        let index = previous + ((zigzag >> 1) as i64 ^ -((zigzag & 1) as i64));
+        let action = usize::try_from(index)
+            .ok()
+            .and_then(Action::from_index)
+            .ok_or_else(|| format!("invalid action index {index}"))?;
+        moves.push(action);
+        previous = index;
+    }
+    if !input.is_empty() {
+        return Err("trailing bytes after record".into());
+    }
+    Ok(GameRecord {
+        players,
+        seed,
+        stock_size,
+        hand_size,
+        seat_rules,
+        must_play_stock: flags & 2 != 0,
+        stock_visibility,
+        loop_guard,
+        max_actions_per_turn,
+        tags,
+        moves,
+        result,
+    })
+}
+
+/// Appends records to an archive; [`ArchiveWriter::finish`] writes the index.
+pub struct ArchiveWriter<W: Write> {
+    out: W,
+    compression: Compression,
+    offset: u64,
+    /// Uncompressed records of the block being filled.
+    block: Vec<u8>,
+    index: Vec<IndexEntry>,
+}
+
+impl ArchiveWriter<BufWriter<File>> {
+    pub fn create(path: &Path, compression: Compression) -> Result<Self, Box<dyn Error>> {
+        Self::new(BufWriter::new(File::create(path)?), compression)
+    }
+}
+
+impl<W: Write> ArchiveWriter<W> {
+    pub fn new(mut out: W, compression: Compression) -> Result<Self, Box<dyn Error>> {
+        compression.check_supported()?;
+        out.write_all(MAGIC)?;
+        out.write_all(&[VERSION, compression.tag()])?;
+        Ok(Self {
+            out,
+            compression,
+            offset: HEADER_LEN,
+            block: Vec::new(),
+            index: Vec::new(),
+        })
+    }
+
+    pub fn push(&mut self, record: &GameRecord) -> Result<(), Box<dyn Error>> {
+        let bytes = encode_record(record)?;
+        let too_large = || "archive block too large";
+        self.index.push(IndexEntry {
+            block_offset: self.offset,
+            block_len: 0,
+            start: u32::try_from(self.block.len()).map_err(|_| too_large())?,
+            len: u32::try_from(bytes.len()).map_err(|_| too_large())?,
+        });
+        self.block.extend_from_slice(&bytes);
+        if self.index.len().is_multiple_of(BLOCK_RECORDS) {
+            self.flush_block()?;
+        }
+        Ok(())
+    }
+
+    /// Records written so far.
+    pub fn len(&self) -> usize {
+        self.index.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.index.is_empty()
+    }
+
+    /// Write the index and footer and hand back the underlying writer.
+    pub fn finish(mut self) -> Result<W, Box<dyn Error>> {
+        self.flush_block()?;
+        for entry in &self.index {
+            self.out.write_all(&entry.block_offset.to_le_bytes())?;
+            self.out.write_all(&entry.block_len.to_le_bytes())?;
+            self.out.write_all(&entry.start.to_le_bytes())?;
+            self.out.write_all(&entry.len.to_le_bytes())?;
+        }
+        self.out.write_all(&self.offset.to_le_bytes())?;
+        self.out
+            .write_all(&(self.index.len() as u64).to_le_bytes())?;
+        self.out.write_all(INDEX_MAGIC)?;
+        self.out.flush()?;
+        Ok(self.out)
+    }
+
+    fn flush_block(&mut self) -> Result<(), Box<dyn Error>> {
+        if self.block.is_empty() {
+            return Ok(());
+        }
+        let bytes = self.compression.compress(std::mem::take(&mut self.block))?;
+        let len = u32::try_from(bytes.len()).map_err(|_| "archive block too large")?;
+        self.out.write_all(&bytes)?;
+        for entry in self.index.iter_mut().rev() {
+            if entry.block_offset != self.offset {
+                break;
+            }
+            entry.block_len = len;
+        }
+        self.offset += u64::from(len);
+        Ok(())
+    }
+}
+
+/// Random access to the records of an archive.
+pub struct ArchiveReader<R: Read + Seek> {
+    input: R,
+    compression: Compression,
+    index: Vec<IndexEntry>,
+    /// The last block read, by offset, so reading in order decompresses each block once.
+    cached: Option<(u64, Vec<u8>)>,
+}
+
+impl ArchiveReader<BufReader<File>> {
+    pub fn open(path: &Path) -> Result<Self, Box<dyn Error>> {
+        Self::new(BufReader::new(File::open(path)?))
+            .map_err(|err| format!("{}: {err}", path.display()).into())
+    }
+}
+
+impl<R: Read + Seek> ArchiveReader<R> {
+    pub fn new(mut input: R) -> Result<Self, Box<dyn Error>> {
+        let mut header = [0u8; HEADER_LEN as usize];
+        input.read_exact(&mut header)?;
+        if &header[..4] != MAGIC {
+            return Err("not a skipbot archive".into());
+        }
+        if header[4] != VERSION {
+            return Err(format!("unsupported archive version {}", header[4]).into());
+        }
+        let compression = Compression::from_tag(header[5])?;
+        compression.check_supported()?;
+
+        input.seek(SeekFrom::End(-FOOTER_LEN))?;
+        let mut footer = [0u8; FOOTER_LEN as usize];
+        input.read_exact(&mut footer)?;
+        if &footer[16..] != INDEX_MAGIC {
+            return Err("archive has no index (was it finished?)".into());
+        }
+        let index_offset = u64::from_le_bytes(footer[..8].try_into()?);
+        let count = u64::from_le_bytes(footer[8..16].try_into()?) as usize;
+        input.seek(SeekFrom::Start(index_offset))?;
+        let mut entries = vec![0u8; count * INDEX_ENTRY_LEN];
+        input.read_exact(&mut entries)?;
+        let u32_at = |entry: &[u8], at: usize| {
+            u32::from_le_bytes(entry[at..at + 4].try_into().expect("4 bytes"))
+        };
+        let index = entries
+            .chunks_exact(INDEX_ENTRY_LEN)
+            .map(|entry| IndexEntry {
+                block_offset: u64::from_le_bytes(entry[..8].try_into().expect("8 bytes")),
+                block_len: u32_at(entry, 8),
+                start: u32_at(entry, 12),
+                len: u32_at(entry, 16),
+            })
+            .collect();
+        Ok(Self {
+            input,
+            compression,
+            index,
+            cached: None,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.index.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.index.is_empty()
+    }
+
+    /// Read record `game` (in the order they were written).
+    pub fn get(&mut self, game: usize) -> Result<GameRecord, Box<dyn Error>> {
+        let entry = *self
+            .index
+            .get(game)
+            .ok_or_else(|| format!("archive has no game {game}"))?;
+        let block = self.block(entry)?;
+        let start = entry.start as usize;
+        let bytes = block
+            .get(start..start + entry.len as usize)
+            .ok_or_else(|| format!("game {game} lies outside its block"))?;
+        decode_record(bytes).map_err(|err| format!("game {game}: {err}").into())
+    }
+
+    /// Every record in order.
+    pub fn records(&mut self) -> impl Iterator<Item = Result<GameRecord, Box<dyn Error>>> + '_ {
+        (0..self.len()).map(|game| self.get(game))
+    }
+
+    fn block(&mut self, entry: IndexEntry) -> Result<&[u8], Box<dyn Error>> {
+        if self
+            .cached
+            .as_ref()
+            .is_none_or(|(offset, _)| *offset != entry.block_offset)
+        {
+            self.input.seek(SeekFrom::Start(entry.block_offset))?;
+            let mut bytes = vec![0u8; entry.block_len as usize];
+            self.input.read_exact(&mut bytes)?;
+            let block = self.compression.decompress(bytes)?;
+            self.cached = Some((entry.block_offset, block));
+        }
+        Ok(&self.cached.as_ref().expect("block was just cached").1)
+    }
+}
+
+fn put_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn put_str(out: &mut Vec<u8>, text: &str) {
+    put_varint(out, text.len() as u64);
+    out.extend_from_slice(text.as_bytes());
+}
+
+fn get_byte(input: &mut &[u8]) -> Result<u8, Box<dyn Error>> {
+    let (&byte, rest) = input.split_first().ok_or("record ends early")?;
+    *input = rest;
+    Ok(byte)
+}
+
+fn get_varint(input: &mut &[u8]) -> Result<u64, Box<dyn Error>> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let byte = get_byte(input)?;
+        value |= u64::from(byte & 0x7F) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err("varint too long".into())
+}
+
+fn get_str(input: &mut &[u8]) -> Result<String, Box<dyn Error>> {
+    let len = get_varint(input)? as usize;
+    if len > input.len() {
+        return Err("record ends early".into());
+    }
+    let (text, rest) = input.split_at(len);
+    *input = rest;
+    Ok(String::from_utf8(text.to_vec())?)
+}
@@ -0,0 +1,186 @@
+//! Engine throughput benchmark behind `simulate --bench-engine`.
+//!
+//! Random bots spend next to nothing per decision, so the measured time is the engine's
+//! own: dealing, views, legal action generation and applying actions. With the
+//! `alloc-count` feature a binary can install `CountingAllocator` to add the number of
+//! heap allocations. Results are saved as JSON so a later run can be compared against
+//! them with [`EngineBench::compare`].
+
+use std::error::Error;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+
+use crate::action::PlayerId;
+use crate::bot::Bot;
+use crate::bots::create_bot_from_spec;
+use crate::game::Game;
+use crate::runner::{mix_seed, play_out};
+
+/// Safety cap on actions per benchmark game.
+const MAX_ACTIONS: usize = 5000;
+
+/// Throughput of one benchmark run.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct EngineBench {
+    pub games: usize,
+    pub players: usize,
+    pub actions: usize,
+    pub seconds: f64,
+    /// Heap allocations during the run; `None` unless `CountingAllocator` is installed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allocations: Option<u64>,
+}
+
+impl EngineBench {
+    pub fn games_per_sec(&self) -> f64 {
+        self.games as f64 / self.seconds.max(f64::EPSILON)
+    }
+
+    pub fn actions_per_sec(&self) -> f64 {
+        self.actions as f64 / self.seconds.max(f64::EPSILON)
+    }
+
+    pub fn allocations_per_action(&self) -> Option<f64> {
+        self.allocations
+            .map(|count| count as f64 / self.actions.max(1) as f64)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Table of this run next to `baseline`, with the relative change of every rate.
+    pub fn compare(&self, baseline: &EngineBench) -> String {
+        fn row(out: &mut String, label: &str, now: f64, then: f64, higher_is_better: bool) {
+            let change = (now / then.max(f64::EPSILON) - 1.0) * 100.0;
+            let verdict = match (change > 0.0) == higher_is_better {
+                _ if change.abs() < 2.0 => "same",
+                true => "better",
+                false => "worse",
+            };
+            let _ = writeln!(
+                out,
+                "  {label:<18} {now:>12.1} {then:>12.1} {change:>+8.1}%  {verdict}"
+            );
+        }
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "  {:<18} {:>12} {:>12} {:>9}",
+            "", "now", "baseline", "change"
+        );
+        row(
+            &mut out,
+            "games/sec",
+            self.games_per_sec(),
+            baseline.games_per_sec(),
+            true,
+        );
+        row(
+            &mut out,
+            "actions/sec",
+            self.actions_per_sec(),
+            baseline.actions_per_sec(),
+            true,
+        );
+        if let (Some(now), Some(then)) = (
+            self.allocations_per_action(),
+            baseline.allocations_per_action(),
+        ) {
+            row(&mut out, "allocs/action", now, then, false);
+        }
+        if self.games != baseline.games || self.players != baseline.players {
+            let _ = writeln!(
+                out,
+                "  (baseline ran {} games with {} players)",
+                baseline.games, baseline.players
+            );
+        }
+        out
+    }
+}
+
+/// Play `games` seeded games between `players` random bots and time them.
+pub fn bench_engine(
+    games: usize,
+    players: usize,
+    seed: u64,
+) -> Result<EngineBench, Box<dyn Error>> {
+    let allocations_before = allocation_count();
+    let start = Instant::now();
+    let mut actions = 0;
+    for game_index in 0..games as u64 {
+        let mut game = Game::builder(players)?
+            .with_seed(mix_seed(seed, game_index, 0xBE7C))
+            .build()?;
+        let mut bots: Vec<Box<dyn Bot>> = PlayerId::all(players)
+            .map(|seat| {
+                let bot_seed = mix_seed(seed, game_index, seat.0 as u64 + 1);
+                create_bot_from_spec("random", seat, bot_seed)
+            })
+            .collect::<Result<_, _>>()?;
+        actions += play_out(&mut game, &mut bots, MAX_ACTIONS, |_| {})?;
+    }
+    let seconds = start.elapsed().as_secs_f64();
+    Ok(EngineBench {
+        games,
+        players,
+        actions,
+        seconds,
+        allocations: allocation_count()
+            .zip(allocations_before)
+            .map(|(after, before)| after - before),
+    })
+}
+
+#[cfg(feature = "alloc-count")]
+static ALLOCATIONS: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+
+/// Heap allocations since start-up when the `alloc-count` feature is enabled and a
+/// binary installed `CountingAllocator` as its global allocator.
+pub fn allocation_count() -> Option<u64> {
+    #[cfg(feature = "alloc-count")]
+    {
+        Some(ALLOCATIONS.load(std::sync::atomic::Ordering::Relaxed))
+    }
+    #[cfg(not(feature = "alloc-count"))]
+    {
+        None
+    }
+}
+
+/// System allocator that counts allocations, for `#[global_allocator]`.
+#[cfg(feature = "alloc-count")]
+pub struct CountingAllocator;
+
+#[cfg(feature = "alloc-count")]
+unsafe impl std::alloc::GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: std::alloc::Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        // SAFETY: forwarded unchanged to the system allocator.
+        unsafe { std::alloc::System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: std::alloc::Layout) {
+        // SAFETY: `ptr` was allocated by `alloc` above, i.e. by the system allocator.
+        unsafe { std::alloc::System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: std::alloc::Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        // SAFETY: as for `alloc` and `dealloc`.
+        unsafe { std::alloc::System.realloc(ptr, layout, new_size) }
+    }
+}
@@ -0,0 +1,303 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::process;
+
+use clap::{ArgAction, Parser};
+
+use skipbot::analysis::unlocks_opponent;
+use skipbot::bots::available_specs;
+use skipbot::bots::completion::CompletionEffect;
+use skipbot::visualize::describe_completion;
+use skipbot::{
+    Action, Bot, CardSource, Game, GameError, GameStateView, PlayerId, SeedPool, SeedSplit,
+    create_bot_from_spec, describe_action, label_for_spec, render_state,
+};
+
+/// Default base seed for deterministic runs.
+const DEFAULT_SEED: u64 = 0xA9A1_7515_5EED_0001;
+
+#[derive(Parser, Debug)]
+#[command(
+    name = "analyze-policy",
+    about = "Replay games and report states where a candidate bot disagrees with a reference heuristic"
+)]
+struct Args {
+    /// Bot spec under analysis (the one whose actions are played)
+    #[arg(short = 'c', long = "candidate", default_value = "beam")]
+    candidate: String,
+
+    /// Reference bot spec asked for its choice on every candidate decision
+    #[arg(short = 'r', long = "reference", default_value = "heuristic13")]
+    reference: String,
+
+    /// Opponent bot specs filling the remaining seats (1-5)
+    #[arg(long = "opponent", default_values_t = vec![String::from("heuristic13")])]
+    opponents: Vec<String>,
+
+    /// Number of games to replay
+    #[arg(short = 'g', long = "games", default_value_t = 20)]
+    games: usize,
+
+    /// Base RNG seed
+    #[arg(short = 's', long = "seed", default_value_t = DEFAULT_SEED)]
+    seed: u64,
+
+    /// Seed universe the decks are drawn from
+    #[arg(long = "seed-split", default_value_t = SeedSplit::Validation)]
+    seed_split: SeedSplit,
+
+    /// Rendered examples printed per phase
+    #[arg(short = 'e', long = "examples", default_value_t = 2)]
+    examples: usize,
+
+    /// Safety cap on actions per game
+    #[arg(long = "max-turns", default_value_t = 2000)]
+    max_turns: usize,
+
+    /// Optional override for per-player stock size
+    #[arg(long = "stock-size")]
+    stock_size: Option<usize>,
+
+    /// List the bot specs and exit
+    #[arg(long = "list-bots", exclusive = true, action = ArgAction::SetTrue)]
+    list_bots: bool,
+}
+
+/// Situation a disagreement happened in, in order of precedence.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Phase {
+    /// The stock top fits a build pile.
+    StockPlay,
+    /// An opponent's stock card fits a build pile, or one of the choices makes it fit.
+    Blocking,
+    /// At least one bot chose to discard.
+    Discard,
+    /// Any other play from hand or discard piles.
+    Play,
+}
+
+impl fmt::Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Phase::StockPlay => "stock play",
+            Phase::Blocking => "blocking",
+            Phase::Discard => "discard",
+            Phase::Play => "play",
+        };
+        f.write_str(name)
+    }
+}
+
+struct Disagreement {
+    game: usize,
+    turn: usize,
+    state: GameStateView,
+    candidate: Action,
+    reference: Action,
+}
+
+#[derive(Default)]
+struct PhaseStats {
+    decisions: usize,
+    disagreements: Vec<Disagreement>,
+}
+
+fn main() {
+    let args = Args::parse();
+    if let Err(err) = run(args) {
+        eprintln!("Error: {err}");
+        process::exit(1);
+    }
+}
+
+fn run(args: Args) -> Result<(), Box<dyn Error>> {
+    if args.list_bots {
+        for spec in available_specs() {
+            println!("{spec}");
+        }
+        return Ok(());
+    }
+    let players = args.opponents.len() + 1;
+    if !(2..=6).contains(&players) {
+        return Err(format!(
+            "expected between 1 and 5 opponents, received {}",
+            players - 1
+        )
+        .into());
+    }
+    if [&args.candidate, &args.reference]
+        .into_iter()
+        .chain(&args.opponents)
+        .any(|s| s.to_ascii_lowercase().starts_with("human"))
+    {
+        return Err("human players are not supported in policy analysis".into());
+    }
+
+    let pool = SeedPool::new(args.seed);
+    let mut stats: BTreeMap<Phase, PhaseStats> = BTreeMap::new();
+    for game_index in 0..args.games {
+        replay_game(&args, &pool, game_index, &mut stats)?;
+    }
+    print_report(&args, &stats);
+    Ok(())
+}
+
+/// Play one game with the candidate in a rotating seat, asking the reference bot for its
+/// choice on every candidate decision. Only the candidate's actions are applied.
+fn replay_game(
+    args: &Args,
+    pool: &SeedPool,
+    game_index: usize,
+    stats: &mut BTreeMap<Phase, PhaseStats>,
+) -> Result<(), Box<dyn Error>> {
+    let players = args.opponents.len() + 1;
+    let deck_seed = pool.seed(args.seed_split, game_index as u64);
+    let mut builder = Game::builder(players)?.with_seed(deck_seed);
+    if let Some(stock) = args.stock_size {
+        builder = builder.with_stock_size(stock);
+    }
+    let mut game = builder.build()?;
+
+    let candidate_seat = game_index % players;
+    let mut bots: Vec<Box<dyn Bot>> = Vec::with_capacity(players);
+    let mut opponents = args.opponents.iter();
+    for seat in 0..players {
+        let spec = if seat == candidate_seat {
+            &args.candidate
+        } else {
+            opponents.next().expect("one opponent per remaining seat")
+        };
+        bots.push(create_bot_from_spec(
+            spec,
+            PlayerId(seat),
+            deck_seed ^ seat as u64,
+        )?);
+    }
+    let mut reference = create_bot_from_spec(&args.reference, PlayerId(candidate_seat), deck_seed)?;
+
+    let mut turns = 0usize;
+    while !game.is_finished() && turns < args.max_turns {
+        let current = game.current_player();
+        let state = game.state_view(current)?;
+        let legal_actions = game.legal_actions(current)?;
+        if legal_actions.is_empty() {
+            return Err(GameError::InvalidConfiguration(
+                "no legal actions available for current player",
+            )
+            .into());
+        }
+        let action = bots[current].select_action(&state, &legal_actions);
+        if current == PlayerId(candidate_seat) {
+            let expected = reference.select_action(&state, &legal_actions);
+            let phase = classify(&state, &legal_actions, &action, &expected);
+            let entry = stats.entry(phase).or_default();
+            entry.decisions += 1;
+            if expected != action {
+                entry.disagreements.push(Disagreement {
+                    game: game_index,
+                    turn: turns,
+                    state: state.clone(),
+                    candidate: action.clone(),
+                    reference: expected,
+                });
+            }
+        }
+        game.apply_action(current, action)?;
+        turns += 1;
+    }
+    Ok(())
+}
+
+fn classify(
+    state: &GameStateView,
+    legal_actions: &[Action],
+    candidate: &Action,
+    reference: &Action,
+) -> Phase {
+    let plays_stock = |action: &Action| {
+        matches!(
+            action,
+            Action::Play {
+                source: CardSource::Stock,
+                ..
+            }
+        )
+    };
+    if legal_actions.iter().any(plays_stock) {
+        return Phase::StockPlay;
+    }
+    if unlocks_opponent(state, None)
+        || unlocks_opponent(state, Some(candidate))
+        || unlocks_opponent(state, Some(reference))
+    {
+        return Phase::Blocking;
+    }
+    if matches!(candidate, Action::Discard { .. }) || matches!(reference, Action::Discard { .. }) {
+        return Phase::Discard;
+    }
+    Phase::Play
+}
+
+fn print_report(args: &Args, stats: &BTreeMap<Phase, PhaseStats>) {
+    println!(
+        "Candidate {} vs reference {} over {} games ({} seeds)\n",
+        label_for_spec(&args.candidate),
+        label_for_spec(&args.reference),
+        args.games,
+        args.seed_split
+    );
+    let decisions: usize = stats.values().map(|s| s.decisions).sum();
+    let disagreements: usize = stats.values().map(|s| s.disagreements.len()).sum();
+    println!(
+        "{:<12} {:>10} {:>14} {:>8}",
+        "phase", "decisions", "disagreements", "rate"
+    );
+    for (phase, entry) in stats {
+        println!(
+            "{:<12} {:>10} {:>14} {:>7.1}%",
+            phase.to_string(),
+            entry.decisions,
+            entry.disagreements.len(),
+            percent(entry.disagreements.len(), entry.decisions)
+        );
+    }
+    println!(
+        "{:<12} {:>10} {:>14} {:>7.1}%",
+        "total",
+        decisions,
+        disagreements,
+        percent(disagreements, decisions)
+    );
+
+    for (phase, entry) in stats {
+        for example in entry.disagreements.iter().take(args.examples) {
+            println!(
+                "\n=== {phase} disagreement (game {}, action {}) ===",
+                example.game, example.turn
+            );
+            println!("{}", render_state(&example.state));
+            println!(
+                "Candidate: {}",
+                describe_action(&example.state, &example.candidate)
+            );
+            println!(
+                "Reference: {}",
+                describe_action(&example.state, &example.reference)
+            );
+            for action in [&example.candidate, &example.reference] {
+                if let Some(effect) = CompletionEffect::of_action(&example.state, action) {
+                    println!("  {}: {}", action, describe_completion(&effect));
+                }
+            }
+        }
+    }
+}
+
+fn percent(part: usize, whole: usize) -> f64 {
+    if whole == 0 {
+        0.0
+    } else {
+        part as f64 * 100.0 / whole as f64
+    }
+}
@@ -0,0 +1,86 @@
+use std::error::Error;
+use std::path::PathBuf;
+use std::process;
+
+use clap::{ArgAction, Parser};
+
+use skipbot::archive::ArchiveReader;
+use skipbot::bots::available_specs;
+use skipbot::dataset::QualityReport;
+use skipbot::public::{anonymize, save_public};
+use skipbot::{GameRecord, SavedGame};
+
+#[derive(Parser, Debug)]
+#[command(
+    name = "anonymize",
+    about = "Strip seeds, hidden cards and player names from recorded games so they can be shared"
+)]
+struct Args {
+    /// Replay archive to export (repeatable), e.g. from `winrate --archive`
+    #[arg(short = 'a', long = "archive")]
+    archives: Vec<PathBuf>,
+
+    /// Game record to export (repeatable), e.g. from `simulate --record`
+    #[arg(short = 'r', long = "record")]
+    records: Vec<PathBuf>,
+
+    /// Saved interactive game to export (repeatable), e.g. a `simulate --autosave` file
+    #[arg(long = "saved")]
+    saved: Vec<PathBuf>,
+
+    /// Write the public samples as JSON lines
+    #[arg(short = 'o', long = "out", default_value = "public.jsonl")]
+    out: PathBuf,
+
+    /// List the bot specs and exit
+    #[arg(long = "list-bots", exclusive = true, action = ArgAction::SetTrue)]
+    list_bots: bool,
+}
+
+fn main() {
+    let args = Args::parse();
+    if let Err(err) = run(args) {
+        eprintln!("Error: {err}");
+        process::exit(1);
+    }
+}
+
+fn run(args: Args) -> Result<(), Box<dyn Error>> {
+    if args.list_bots {
+        for spec in available_specs() {
+            println!("{spec}");
+        }
+        return Ok(());
+    }
+    let mut records = Vec::new();
+    for path in &args.archives {
+        let mut archive = ArchiveReader::open(path)?;
+        for record in archive.records() {
+            records.push(record.map_err(|err| format!("{}: {err}", path.display()))?);
+        }
+    }
+    for path in &args.records {
+        records.push(GameRecord::load(path)?);
+    }
+    for path in &args.saved {
+        let record = SavedGame::load(path)?
+            .record
+            .parse()
+            .map_err(|err| format!("{}: {err}", path.display()))?;
+        records.push(record);
+    }
+    if records.is_empty() {
+        return Err("give at least one --archive, --record or --saved game".into());
+    }
+
+    let samples = anonymize(&records)?;
+    save_public(&samples, &args.out)?;
+    println!(
+        "{} moves of {} games written to {}",
+        samples.len(),
+        records.len(),
+        args.out.display()
+    );
+    print!("{}", QualityReport::from_samples(&samples));
+    Ok(())
+}
@@ -0,0 +1,168 @@
+use std::error::Error;
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+
+use clap::{ArgAction, Parser};
+
+use skipbot::SeedSplit;
+use skipbot::bots::PlanWeights;
+use skipbot::bots::available_specs;
+use skipbot::cem::{CemConfig, CemTrainer, win_rate};
+use skipbot::experiments::{DEFAULT_ROOT, Experiment, ExperimentConfig};
+
+/// Default base seed for deterministic runs.
+const DEFAULT_SEED: u64 = 0xCE11_0000_5EED;
+
+#[derive(Parser, Debug)]
+#[command(
+    name = "cem",
+    about = "Tune the heuristic19 plan weights with the cross-entropy method"
+)]
+struct Args {
+    /// Number of generations
+    #[arg(short = 'n', long = "generations", default_value_t = 10)]
+    generations: usize,
+
+    /// Candidates per generation
+    #[arg(short = 'p', long = "population", default_value_t = 16)]
+    population: usize,
+
+    /// Share of each generation kept as elite
+    #[arg(long = "elite", default_value_t = 0.25)]
+    elite: f64,
+
+    /// Games per candidate and generation (each deal is played from both seats)
+    #[arg(short = 'g', long = "games", default_value_t = 40)]
+    games: usize,
+
+    /// Opponent bot spec
+    #[arg(short = 'o', long = "opponent", default_value = "heuristic13")]
+    opponent: String,
+
+    /// Base RNG seed
+    #[arg(short = 's', long = "seed", default_value_t = DEFAULT_SEED)]
+    seed: u64,
+
+    /// Initial standard deviation, relative to each starting weight
+    #[arg(long = "std", default_value_t = 0.3)]
+    std: f64,
+
+    /// Lower bound on the relative standard deviation
+    #[arg(long = "min-std", default_value_t = 0.02)]
+    min_std: f64,
+
+    /// Start from weights in this JSON file instead of the defaults
+    #[arg(long = "start")]
+    start: Option<PathBuf>,
+
+    /// Write the final mean weights as JSON
+    #[arg(long = "out")]
+    out: Option<PathBuf>,
+
+    /// Run as a named experiment and write the weights as its next numbered checkpoint
+    #[arg(long = "experiment", conflicts_with = "out")]
+    experiment: Option<String>,
+
+    /// Root directory of --experiment runs
+    #[arg(long = "experiments-dir", default_value = DEFAULT_ROOT)]
+    experiments_dir: PathBuf,
+
+    /// Validation games comparing the start and final weights (0 to skip)
+    #[arg(long = "validate", default_value_t = 200)]
+    validate: usize,
+
+    /// List the bot specs and exit
+    #[arg(long = "list-bots", exclusive = true, action = ArgAction::SetTrue)]
+    list_bots: bool,
+}
+
+fn main() {
+    let args = Args::parse();
+    if let Err(err) = run(args) {
+        eprintln!("Error: {err}");
+        process::exit(1);
+    }
+}
+
+fn run(args: Args) -> Result<(), Box<dyn Error>> {
+    if args.list_bots {
+        for spec in available_specs() {
+            println!("{spec}");
+        }
+        return Ok(());
+    }
+    let start = match &args.start {
+        Some(path) => serde_json::from_str(&fs::read_to_string(path)?)?,
+        None => PlanWeights::default(),
+    };
+    let config = CemConfig {
+        population: args.population,
+        elite_fraction: args.elite,
+        games: args.games,
+        initial_std: args.std,
+        min_std: args.min_std,
+        ..CemConfig::new(&args.opponent, args.seed)
+    };
+    let max_actions = config.max_actions;
+    let out = match &args.experiment {
+        Some(name) => {
+            let bots = vec!["heuristic19".to_string(), config.opponent.clone()];
+            let settings = serde_json::json!({
+                "cem": config,
+                "generations": args.generations,
+                "start": start,
+            });
+            let experiment = Experiment::open(
+                &args.experiments_dir,
+                ExperimentConfig::new(name, bots, vec![config.seed]).with_settings(&settings)?,
+            )?;
+            println!("Experiment {}\n", experiment.dir().display());
+            Some(experiment.next_checkpoint("plan-weights", "json"))
+        }
+        None => args.out.clone(),
+    };
+    let mut trainer = CemTrainer::new(config, start)?;
+
+    println!("{:>4}  {:>8}  {:>8}  {:>8}", "gen", "best", "elite", "mean");
+    for _ in 0..args.generations {
+        let report = trainer.step()?;
+        println!(
+            "{:>4}  {:>7.1}%  {:>7.1}%  {:>7.1}%",
+            report.generation,
+            report.best_win_rate * 100.0,
+            report.elite_win_rate * 100.0,
+            report.mean_win_rate * 100.0
+        );
+    }
+
+    let tuned = trainer.mean();
+    let json = serde_json::to_string_pretty(&tuned)?;
+    match &out {
+        Some(path) => {
+            fs::write(path, &json)?;
+            println!("\nWeights written to {}", path.display());
+        }
+        None => println!("\n{json}"),
+    }
+
+    if args.validate > 0 {
+        println!(
+            "\nValidation vs {} over {} games:",
+            args.opponent, args.validate
+        );
+        for (label, weights) in [("start", start), ("tuned", tuned)] {
+            let rate = win_rate(
+                weights,
+                &args.opponent,
+                SeedSplit::Validation,
+                args.seed,
+                0,
+                args.validate,
+                max_actions,
+            )?;
+            println!("  {label}: {:.1}%", rate * 100.0);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,128 @@
+use std::error::Error;
+use std::process;
+use std::sync::{Arc, Mutex};
+
+use clap::{ArgAction, Parser};
+
+use skipbot::bots::available_specs;
+use skipbot::chat::{ChatBot, IrcTransport, SharedTransport};
+use skipbot::runner::play_out;
+use skipbot::{Bot, Game, PlayerId, create_bot_from_spec, describe_action};
+
+/// Default base seed for deterministic runs.
+const DEFAULT_SEED: u64 = 0xC4A7_B81D_5EED;
+
+#[derive(Parser, Debug)]
+#[command(
+    name = "chat-bridge",
+    about = "Play a game on IRC: chat users send their moves as !play commands"
+)]
+struct Args {
+    /// IRC server as host:port (plain TCP)
+    #[arg(long = "server", required_unless_present = "list_bots")]
+    server: Option<String>,
+
+    /// Channel to play in, e.g. #skipbo
+    #[arg(long = "channel", required_unless_present = "list_bots")]
+    channel: Option<String>,
+
+    /// Nickname of the bridge
+    #[arg(long = "nick", default_value = "skipbot")]
+    nick: String,
+
+    /// Seed of the deal
+    #[arg(short = 's', long = "seed", default_value_t = DEFAULT_SEED)]
+    seed: u64,
+
+    /// Safety cap on actions per game
+    #[arg(long = "max-turns", default_value_t = 2000)]
+    max_turns: usize,
+
+    /// One entry per seat (2-6): chat:NICK for a chat user, otherwise a bot spec
+    seats: Vec<String>,
+
+    /// List the bot specs and exit
+    #[arg(long = "list-bots", exclusive = true, action = ArgAction::SetTrue)]
+    list_bots: bool,
+}
+
+fn main() {
+    let args = Args::parse();
+    if let Err(err) = run(args) {
+        eprintln!("Error: {err}");
+        process::exit(1);
+    }
+}
+
+fn run(args: Args) -> Result<(), Box<dyn Error>> {
+    if args.list_bots {
+        for spec in available_specs() {
+            println!("{spec}");
+        }
+        return Ok(());
+    }
+    let (Some(server), Some(channel)) = (&args.server, &args.channel) else {
+        return Err("--server and --channel are required".into());
+    };
+    if args.seats.len() < 2 || args.seats.len() > 6 {
+        return Err(format!(
+            "expected between 2 and 6 seats, received {}",
+            args.seats.len()
+        )
+        .into());
+    }
+    let transport: SharedTransport = Arc::new(Mutex::new(IrcTransport::connect(
+        server, &args.nick, channel,
+    )?));
+    let mut names = Vec::with_capacity(args.seats.len());
+    let mut bots: Vec<Box<dyn Bot>> = Vec::with_capacity(args.seats.len());
+    for (seat, spec) in args.seats.iter().enumerate() {
+        match spec.strip_prefix("chat:") {
+            Some(nick) => {
+                names.push(nick.trim().to_string());
+                bots.push(Box::new(ChatBot::new(nick.trim(), transport.clone())));
+            }
+            None => {
+                names.push(spec.clone());
+                bots.push(create_bot_from_spec(spec, PlayerId(seat), args.seed)?);
+            }
+        }
+    }
+    println!("Joined {channel} on {server} as {}", args.nick);
+
+    let announce = |text: &str| {
+        if let Err(err) = transport
+            .lock()
+            .expect("chat transport lock")
+            .send(None, text)
+        {
+            eprintln!("Error: sending to {channel}: {err}");
+        }
+    };
+    let seating: Vec<String> = names
+        .iter()
+        .enumerate()
+        .map(|(seat, name)| format!("{seat}: {name}"))
+        .collect();
+    announce(&format!(
+        "New Skip-Bo game ({}). Chat players: send !help when it is your turn.",
+        seating.join(", ")
+    ));
+    let mut game = Game::builder(bots.len())?.with_seed(args.seed).build()?;
+    play_out(&mut game, &mut bots, args.max_turns, |decision| {
+        announce(&format!(
+            "{}: {} ({})",
+            names[decision.player],
+            decision.action,
+            describe_action(decision.state, decision.action)
+        ));
+    })?;
+    let result = match game.winner() {
+        Some(winner) if game.is_finished() => format!("{} wins!", names[winner]),
+        _ if game.is_finished() => "The game is a draw.".to_string(),
+        _ => format!("Stopped after {} actions without a winner.", args.max_turns),
+    };
+    announce(&result);
+    println!("{result}");
+    Ok(())
+}
@@ -0,0 +1,71 @@
+use std::error::Error;
+use std::path::PathBuf;
+use std::process;
+
+use clap::{ArgAction, Parser};
+
+use skipbot::bots::available_specs;
+use skipbot::dashboard::{CheckpointReport, Dashboard};
+
+#[derive(Parser, Debug)]
+#[command(
+    name = "dashboard-export",
+    about = "Consolidate match and league reports into one timeseries JSON for dashboards"
+)]
+struct Args {
+    /// Bot label to report win rates against; repeat for several baselines
+    #[arg(short = 'b', long = "baseline")]
+    baselines: Vec<String>,
+
+    /// Bot label rated 0 at every checkpoint (defaults to the first baseline)
+    #[arg(long = "anchor")]
+    anchor: Option<String>,
+
+    /// Write the timeseries JSON here
+    #[arg(short = 'o', long = "out", default_value = "dashboard.json")]
+    out: PathBuf,
+
+    /// Match or league reports, oldest first; a directory adds its .json files by name
+    #[arg(required = true)]
+    reports: Vec<PathBuf>,
+
+    /// List the bot specs and exit
+    #[arg(long = "list-bots", exclusive = true, action = ArgAction::SetTrue)]
+    list_bots: bool,
+}
+
+fn main() {
+    let args = Args::parse();
+    if let Err(err) = run(args) {
+        eprintln!("Error: {err}");
+        process::exit(1);
+    }
+}
+
+fn run(args: Args) -> Result<(), Box<dyn Error>> {
+    if args.list_bots {
+        for spec in available_specs() {
+            println!("{spec}");
+        }
+        return Ok(());
+    }
+    let checkpoints = CheckpointReport::load_all(&args.reports)?;
+    if checkpoints.is_empty() {
+        return Err("no reports found".into());
+    }
+    let dashboard = Dashboard::build(&checkpoints, &args.baselines, args.anchor.as_deref());
+    if let Some(anchor) = &dashboard.anchor
+        && !dashboard.ratings.iter().any(|series| &series.bot == anchor)
+    {
+        eprintln!("Warning: anchor {anchor} played in no report; ratings are left unshifted");
+    }
+    dashboard.save(&args.out)?;
+    println!(
+        "{} checkpoints, {} rating series and {} win-rate series written to {}",
+        dashboard.checkpoints.len(),
+        dashboard.ratings.len(),
+        dashboard.win_rates.len(),
+        args.out.display()
+    );
+    Ok(())
+}
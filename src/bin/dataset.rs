@@ -0,0 +1,88 @@
+use std::error::Error;
+use std::path::PathBuf;
+use std::process;
+
+use clap::{ArgAction, Parser};
+
+use skipbot::archive::ArchiveReader;
+use skipbot::bots::available_specs;
+use skipbot::dataset::{PolicyDataset, QualityReport};
+use skipbot::runner::{MatchReport, ObjectiveWeights};
+
+#[derive(Parser, Debug)]
+#[command(
+    name = "dataset",
+    about = "Turn a tournament report and its replay archive into labelled policy samples"
+)]
+struct Args {
+    /// Match report written by `winrate --report`
+    #[arg(long = "report", required_unless_present = "list_bots")]
+    report: Option<PathBuf>,
+
+    /// Replay archive written by the same run with `winrate --archive`
+    #[arg(long = "archive", required_unless_present = "list_bots")]
+    archive: Option<PathBuf>,
+
+    /// Write samples as JSON lines
+    #[arg(short = 'o', long = "out", default_value = "dataset.jsonl")]
+    out: PathBuf,
+
+    /// Keep only the moves of this many best-rated bots (all when unset)
+    #[arg(long = "top-bots")]
+    top_bots: Option<usize>,
+
+    /// Keep only moves from games the acting bot won
+    #[arg(long = "winners-only", action = ArgAction::SetTrue)]
+    winners_only: bool,
+
+    /// Keep only moves made on this zero-based turn or later
+    #[arg(long = "from-turn")]
+    from_turn: Option<usize>,
+
+    /// Composite objective weights used as bot ratings, e.g. win=1,margin=0.5
+    #[arg(long = "objective-weights", default_value = "")]
+    weights: ObjectiveWeights,
+
+    /// List the bot specs and exit
+    #[arg(long = "list-bots", exclusive = true, action = ArgAction::SetTrue)]
+    list_bots: bool,
+}
+
+fn main() {
+    let args = Args::parse();
+    if let Err(err) = run(args) {
+        eprintln!("Error: {err}");
+        process::exit(1);
+    }
+}
+
+fn run(args: Args) -> Result<(), Box<dyn Error>> {
+    if args.list_bots {
+        for spec in available_specs() {
+            println!("{spec}");
+        }
+        return Ok(());
+    }
+    let (Some(report), Some(archive)) = (&args.report, &args.archive) else {
+        return Err("--report and --archive are required".into());
+    };
+    let report = MatchReport::load(report)?;
+    let mut archive = ArchiveReader::open(archive)?;
+    let mut dataset = PolicyDataset::from_tournament(&report, &mut archive, &args.weights)?;
+    let total = dataset.len();
+    if args.top_bots.is_some() || args.winners_only {
+        dataset = dataset.top_rated(args.top_bots.unwrap_or(usize::MAX), args.winners_only);
+    }
+    if let Some(turn) = args.from_turn {
+        dataset = dataset.from_turn(turn);
+    }
+    dataset.save(&args.out)?;
+    println!(
+        "{} of {total} samples from {} games written to {}",
+        dataset.len(),
+        archive.len(),
+        args.out.display()
+    );
+    print!("{}", QualityReport::from_samples(&dataset.samples));
+    Ok(())
+}
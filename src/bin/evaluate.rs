@@ -0,0 +1,204 @@
+use std::error::Error;
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+use std::time::Instant;
+
+use clap::{ArgAction, Parser};
+use serde::Serialize;
+
+use skipbot::archive::ArchiveReader;
+use skipbot::bots::available_specs;
+use skipbot::bots::hybrid::DEFAULT_TEMPERATURE;
+use skipbot::label_for_spec;
+use skipbot::metrics::{MetricsConfig, PolicyMetrics, TemperatureFit, record_decisions};
+
+/// Default base seed for deterministic runs.
+const DEFAULT_SEED: u64 = 0xE7A1_0A7E_5EED;
+
+#[derive(Parser, Debug)]
+#[command(
+    name = "evaluate",
+    about = "Score bots and checkpoints against the moves of recorded games, without playing any"
+)]
+struct Args {
+    /// Replay archive whose moves are the reference policy, e.g. from `winrate --archive`
+    #[arg(short = 'a', long = "archive", required_unless_present = "list_bots")]
+    archive: Option<PathBuf>,
+
+    /// Bot spec to score (repeatable)
+    #[arg(short = 'p', long = "policy")]
+    policies: Vec<String>,
+
+    /// Checkpoint to score (repeatable), as --checkpoint-spec with {} replaced by its path
+    #[arg(short = 'c', long = "checkpoint")]
+    checkpoints: Vec<PathBuf>,
+
+    /// Spec checkpoints are loaded with, e.g. heuristic19:checkpoint={} for cem weights
+    #[arg(long = "checkpoint-spec", default_value = "beam:value={}")]
+    checkpoint_spec: String,
+
+    /// Score only the seats this bot played, by spec or label from the records' Bots tag
+    #[arg(long = "teacher")]
+    teacher: Option<String>,
+
+    /// Score only the first this many games of the archive
+    #[arg(short = 'g', long = "games")]
+    games: Option<usize>,
+
+    /// Softmax temperature over the bots' move scores
+    #[arg(long = "temperature", default_value_t = DEFAULT_TEMPERATURE)]
+    temperature: f64,
+
+    /// Base RNG seed
+    #[arg(short = 's', long = "seed", default_value_t = DEFAULT_SEED)]
+    seed: u64,
+
+    /// Bins of the top-move calibration (ECE)
+    #[arg(long = "bins", default_value_t = 10)]
+    bins: usize,
+
+    /// Fit each policy's softmax temperature on the archive and store it in the metadata
+    /// of its checkpoint, where hybrid bots find it
+    #[arg(long = "fit-temperature", action = ArgAction::SetTrue)]
+    fit_temperature: bool,
+
+    /// Also write the metrics of every policy as JSON
+    #[arg(long = "json")]
+    json: Option<PathBuf>,
+
+    /// List the bot specs and exit
+    #[arg(long = "list-bots", exclusive = true, action = ArgAction::SetTrue)]
+    list_bots: bool,
+}
+
+#[derive(Serialize)]
+struct Scored {
+    spec: String,
+    metrics: PolicyMetrics,
+    ece: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    fit: Option<TemperatureFit>,
+}
+
+fn main() {
+    let args = Args::parse();
+    if let Err(err) = run(args) {
+        eprintln!("Error: {err}");
+        process::exit(1);
+    }
+}
+
+fn run(args: Args) -> Result<(), Box<dyn Error>> {
+    if args.list_bots {
+        for spec in available_specs() {
+            println!("{spec}");
+        }
+        return Ok(());
+    }
+    let Some(archive) = &args.archive else {
+        return Err("--archive is required".into());
+    };
+    if !args.checkpoints.is_empty() && !args.checkpoint_spec.contains("{}") {
+        return Err(format!(
+            "--checkpoint-spec needs a {{}} for the path: {}",
+            args.checkpoint_spec
+        )
+        .into());
+    }
+    let specs: Vec<(String, Option<&PathBuf>)> = args
+        .policies
+        .iter()
+        .map(|spec| (spec.clone(), None))
+        .chain(args.checkpoints.iter().map(|path| {
+            let spec = args
+                .checkpoint_spec
+                .replace("{}", &path.display().to_string());
+            (spec, Some(path))
+        }))
+        .collect();
+    if specs.is_empty() {
+        return Err("give at least one --policy or --checkpoint".into());
+    }
+
+    let mut reader = ArchiveReader::open(archive)?;
+    let records = reader
+        .records()
+        .take(args.games.unwrap_or(usize::MAX))
+        .collect::<Result<Vec<_>, _>>()?;
+    let config = MetricsConfig {
+        temperature: args.temperature,
+        seed: args.seed,
+        teacher: args.teacher.clone(),
+    };
+    println!(
+        "Reference: {} games of {}{}\n",
+        records.len(),
+        archive.display(),
+        args.teacher
+            .as_ref()
+            .map_or(String::new(), |teacher| format!(", seats of {teacher}"))
+    );
+
+    let mut scored = Vec::with_capacity(specs.len());
+    for (spec, checkpoint) in specs {
+        let started = Instant::now();
+        let decisions =
+            record_decisions(&spec, &records, &config).map_err(|err| format!("{spec}: {err}"))?;
+        let metrics = decisions.metrics(args.temperature);
+        let ece = decisions.calibration(args.temperature, args.bins).ece;
+        println!(
+            "== {} ({:.1?}) ==",
+            label_for_spec(&spec),
+            started.elapsed()
+        );
+        print!("{metrics}");
+        println!("Top-move calibration: ECE {ece:.4}");
+        let fit = args
+            .fit_temperature
+            .then(|| TemperatureFit::fit(&spec, &decisions, args.temperature, args.bins));
+        if let Some(fit) = &fit {
+            println!(
+                "Fitted temperature {:.4}: log loss {:.4} -> {:.4}, ECE {:.4} -> {:.4}",
+                fit.temperature,
+                fit.log_loss_before,
+                fit.log_loss_after,
+                fit.ece_before,
+                fit.ece_after
+            );
+            if let Some(checkpoint) = checkpoint {
+                let path = fit.save(checkpoint)?;
+                println!("Temperature stored in {}", path.display());
+            }
+        }
+        println!();
+        scored.push(Scored {
+            spec,
+            metrics,
+            ece,
+            fit,
+        });
+    }
+
+    if scored.len() > 1 {
+        println!(
+            "{:<32} {:>8} {:>8} {:>10} {:>8}",
+            "policy", "top-1", "top-3", "perplexity", "ECE"
+        );
+        for entry in &scored {
+            println!(
+                "{:<32} {:>7.1}% {:>7.1}% {:>10.3} {:>8.4}",
+                label_for_spec(&entry.spec),
+                entry.metrics.top1_rate() * 100.0,
+                entry.metrics.top3_rate() * 100.0,
+                entry.metrics.perplexity(),
+                entry.ece
+            );
+        }
+    }
+    if let Some(path) = &args.json {
+        fs::write(path, serde_json::to_string_pretty(&scored)?)?;
+        println!("Metrics written to {}", path.display());
+    }
+    Ok(())
+}
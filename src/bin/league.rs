@@ -0,0 +1,185 @@
+use std::error::Error;
+use std::path::PathBuf;
+use std::process;
+
+use clap::{ArgAction, Parser};
+
+use skipbot::bots::BotsConfig;
+use skipbot::bots::available_specs;
+use skipbot::interrupt::Interrupter;
+use skipbot::league::{Attribution, League, LeagueConfig, LeagueReport, Matchmaking};
+use skipbot::reload::{ReloadTrigger, WeightReloader};
+
+/// Default base seed for deterministic runs.
+const DEFAULT_SEED: u64 = 0x1EA6_0E00_5EED;
+
+#[derive(Parser, Debug)]
+#[command(
+    name = "league",
+    about = "Rate a pool of bots from balanced multi-player tables"
+)]
+struct Args {
+    /// Seats per table (2-6)
+    #[arg(short = 't', long = "table-size", default_value_t = 4)]
+    table_size: usize,
+
+    /// Number of tables (games) to play; an upper bound with --target-error
+    #[arg(short = 'g', long = "games", default_value_t = 200)]
+    games: usize,
+
+    /// Base RNG seed
+    #[arg(short = 's', long = "seed", default_value_t = DEFAULT_SEED)]
+    seed: u64,
+
+    /// Safety cap on actions per game
+    #[arg(long = "max-turns", default_value_t = 2000)]
+    max_turns: usize,
+
+    /// Stock pile size (defaults to the standard size for the table size)
+    #[arg(long = "stock-size")]
+    stock_size: Option<usize>,
+
+    /// Table results that feed the ratings: winner (the winner beat the table) or finish
+    /// (the whole finishing order by stock left)
+    #[arg(long = "attribution", default_value_t = Attribution::Winner)]
+    attribution: Attribution,
+
+    /// How tables are picked: balanced (even games, seats and opponents) or active (the
+    /// least certain bots against those rated closest)
+    #[arg(long = "matchmaking", default_value_t = Matchmaking::Balanced)]
+    matchmaking: Matchmaking,
+
+    /// Stop once every rating's standard error is at most this many Elo points (bots
+    /// three errors away from all others excepted)
+    #[arg(long = "target-error")]
+    target_error: Option<f64>,
+
+    /// Read the weight files of heuristic19 `checkpoint=` specs again between tables:
+    /// modified (when the file changes) or signal (after SIGHUP)
+    #[arg(long = "reload-weights")]
+    reload_weights: Option<ReloadTrigger>,
+
+    /// Write the schedule, games, metrics and ratings as JSON
+    #[arg(long = "report")]
+    report: Option<PathBuf>,
+
+    /// Read the pool from a TOML or JSON file instead of spec arguments
+    #[arg(long = "bots-config", conflicts_with = "bots")]
+    bots_config: Option<PathBuf>,
+
+    /// Bot specs of the pool; may be larger than a table
+    bots: Vec<String>,
+
+    /// List the bot specs and exit
+    #[arg(long = "list-bots", exclusive = true, action = ArgAction::SetTrue)]
+    list_bots: bool,
+}
+
+fn main() {
+    let args = Args::parse();
+    if let Err(err) = run(args) {
+        eprintln!("Error: {err}");
+        process::exit(1);
+    }
+}
+
+fn run(args: Args) -> Result<(), Box<dyn Error>> {
+    if args.list_bots {
+        for spec in available_specs() {
+            println!("{spec}");
+        }
+        return Ok(());
+    }
+    let bots = match &args.bots_config {
+        Some(path) => BotsConfig::load(path)?.specs(),
+        None => args.bots.clone(),
+    };
+    if bots
+        .iter()
+        .any(|s| s.to_ascii_lowercase().starts_with("human"))
+    {
+        return Err("human players are not supported in leagues".into());
+    }
+    let config = LeagueConfig {
+        max_turns: args.max_turns,
+        stock_size: args.stock_size,
+        attribution: args.attribution,
+        matchmaking: args.matchmaking,
+        target_error: args.target_error,
+        ..LeagueConfig::new(bots, args.table_size, args.games, args.seed)
+    };
+    let interrupter = Interrupter::ctrl_c();
+    let mut league = League::new(config)?.with_interrupter(interrupter.clone());
+    if let Some(trigger) = args.reload_weights {
+        league = league.with_weight_reloader(WeightReloader::sighup(trigger));
+    }
+    let report = league.run()?;
+    if interrupter.is_cancelled() {
+        eprintln!("Interrupted; rating the tables finished so far.\n");
+    }
+    print_report(&report);
+    if let Some(path) = &args.report {
+        report.save(path)?;
+        println!("\nLeague report written to {}", path.display());
+    }
+    Ok(())
+}
+
+fn print_report(report: &LeagueReport) {
+    let balance = report.balance();
+    println!(
+        "{} tables of {} from a pool of {} ({} matchmaking, {:.1}s)",
+        report.games.len(),
+        report.config.table_size,
+        report.config.bots.len(),
+        report.config.matchmaking,
+        report.elapsed_ms / 1_000.0
+    );
+    if let Some(target) = report.config.target_error {
+        let open = report.open_error();
+        let verdict = if open <= target {
+            "reached"
+        } else {
+            "not reached"
+        };
+        println!(
+            "Target error of {target:.0} {verdict} after {} of at most {} tables (largest open error {open:.0})",
+            report.games.len(),
+            report.config.tables
+        );
+    }
+    println!(
+        "Balance: games per bot differ by {}, seats per bot by {}, shared tables per pair by {}",
+        balance.game_spread(),
+        balance.seat_spread(),
+        balance.pair_spread()
+    );
+    println!(
+        "\nPlackett-Luce ratings ({} attribution):",
+        report.config.attribution
+    );
+    println!(
+        "  {:<24} {:>8} {:>6} {:>6} {:>6} {:>8}",
+        "bot", "rating", "error", "games", "wins", "win rate"
+    );
+    for rating in &report.ratings {
+        let win_rate = rating.wins as f64 / rating.games.max(1) as f64;
+        println!(
+            "  {:<24} {:>8.0} {:>6.0} {:>6} {:>6} {:>7.1}%",
+            rating.label,
+            rating.rating,
+            rating.error,
+            rating.games,
+            rating.wins,
+            win_rate * 100.0
+        );
+    }
+    for (table, game) in report.games.iter().enumerate() {
+        if !game.reloaded.is_empty() {
+            println!(
+                "Weights reloaded before table {table}: {}",
+                game.reloaded.join(", ")
+            );
+        }
+    }
+}
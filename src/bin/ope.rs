@@ -0,0 +1,189 @@
+use std::error::Error;
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+use std::time::Instant;
+
+use clap::{ArgAction, Parser, Subcommand};
+use serde::Serialize;
+
+use skipbot::archive::ArchiveReader;
+use skipbot::bots::available_specs;
+use skipbot::label_for_spec;
+use skipbot::ope::{BehaviorLog, OpeConfig, OpeEstimate, estimate_policy};
+use skipbot::runner::MatchReport;
+use skipbot::value::ValueEnsemble;
+
+/// Default base seed for deterministic runs.
+const DEFAULT_SEED: u64 = 0x0FE0_5EED;
+
+#[derive(Parser, Debug)]
+#[command(
+    name = "ope",
+    about = "Estimate candidate policies' win rates from logged tournament games, without playing any"
+)]
+struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// List the bot specs and exit
+    #[arg(long = "list-bots", exclusive = true, action = ArgAction::SetTrue)]
+    list_bots: bool,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Log the move probabilities of a tournament's softmax bots
+    Log {
+        /// Match report written by `winrate --report`
+        #[arg(long = "report")]
+        report: PathBuf,
+
+        /// Replay archive written by the same run with `winrate --archive`
+        #[arg(long = "archive")]
+        archive: PathBuf,
+
+        /// Write the log as JSON lines
+        #[arg(short = 'o', long = "out", default_value = "behavior.jsonl")]
+        out: PathBuf,
+    },
+    /// Estimate the win rate of candidates standing in for a logged bot
+    Estimate {
+        /// Behavior log written by `ope log`
+        #[arg(long = "log")]
+        log: PathBuf,
+
+        /// The replay archive the log was made from
+        #[arg(long = "archive")]
+        archive: PathBuf,
+
+        /// Label of the logged bot the candidates stand in for; the only one when unset
+        #[arg(long = "behavior")]
+        behavior: Option<String>,
+
+        /// Candidate bot spec (repeatable)
+        #[arg(short = 'p', long = "policy", required = true)]
+        policies: Vec<String>,
+
+        /// Softmax temperature over the candidates' move scores
+        #[arg(long = "temperature", default_value_t = 1.0)]
+        temperature: f64,
+
+        /// Value-net checkpoints for the doubly robust estimate, comma-separated
+        #[arg(long = "value")]
+        value: Option<String>,
+
+        /// Base RNG seed
+        #[arg(short = 's', long = "seed", default_value_t = DEFAULT_SEED)]
+        seed: u64,
+
+        /// Also write the estimates as JSON
+        #[arg(long = "json")]
+        json: Option<PathBuf>,
+    },
+}
+
+#[derive(Serialize)]
+struct Estimated {
+    spec: String,
+    estimate: OpeEstimate,
+}
+
+fn main() {
+    let args = Args::parse();
+    if let Err(err) = run(args) {
+        eprintln!("Error: {err}");
+        process::exit(1);
+    }
+}
+
+fn run(args: Args) -> Result<(), Box<dyn Error>> {
+    if args.list_bots {
+        for spec in available_specs() {
+            println!("{spec}");
+        }
+        return Ok(());
+    }
+    let Some(command) = args.command else {
+        return Err("expected a subcommand: log or estimate (see --help)".into());
+    };
+    match command {
+        Command::Log {
+            report,
+            archive,
+            out,
+        } => {
+            let report = MatchReport::load(&report)?;
+            let mut archive = ArchiveReader::open(&archive)?;
+            let log = BehaviorLog::from_tournament(&report, &mut archive)?;
+            log.save(&out)?;
+            println!(
+                "{} moves from {} games written to {}",
+                log.len(),
+                archive.len(),
+                out.display()
+            );
+            for (bot, moves) in log.bots() {
+                println!("  {bot:<24} {moves:>8}");
+            }
+            Ok(())
+        }
+        Command::Estimate {
+            log,
+            archive,
+            behavior,
+            policies,
+            temperature,
+            value,
+            seed,
+            json,
+        } => {
+            let log = BehaviorLog::load(&log)?;
+            let behavior = match behavior {
+                Some(behavior) => behavior,
+                None => {
+                    let bots = log.bots();
+                    let mut labels = bots.keys();
+                    match (labels.next(), labels.next()) {
+                        (Some(label), None) => label.to_string(),
+                        _ => {
+                            return Err("the log has several bots; pick one with --behavior".into());
+                        }
+                    }
+                }
+            };
+            let value = value
+                .map(|list| {
+                    ValueEnsemble::load(&list.split(',').map(str::trim).collect::<Vec<_>>())
+                })
+                .transpose()?;
+            let records = ArchiveReader::open(&archive)?
+                .records()
+                .collect::<Result<Vec<_>, _>>()?;
+            let config = OpeConfig {
+                behavior,
+                temperature,
+                seed,
+            };
+            println!("Behavior: {} in {}\n", config.behavior, archive.display());
+            let mut estimated = Vec::with_capacity(policies.len());
+            for spec in policies {
+                let started = Instant::now();
+                let estimate = estimate_policy(&spec, &log, &records, &config, value.as_ref())
+                    .map_err(|err| format!("{spec}: {err}"))?;
+                println!(
+                    "== {} ({:.1?}) ==",
+                    label_for_spec(&spec),
+                    started.elapsed()
+                );
+                println!("{estimate}");
+                estimated.push(Estimated { spec, estimate });
+            }
+            if let Some(path) = &json {
+                fs::write(path, serde_json::to_string_pretty(&estimated)?)?;
+                println!("Estimates written to {}", path.display());
+            }
+            Ok(())
+        }
+    }
+}
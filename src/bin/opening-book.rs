@@ -0,0 +1,91 @@
+use std::error::Error;
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+use std::time::Instant;
+
+use clap::{ArgAction, Parser};
+
+use skipbot::bots::{Heuristic19Bot, OpeningBook, PlanWeights, available_specs};
+
+/// Default base seed for deterministic runs.
+const DEFAULT_SEED: u64 = 0x0B00_C000_5EED;
+
+#[derive(Parser, Debug)]
+#[command(
+    name = "opening-book",
+    about = "Warm an opening book of heuristic19 first-turn plans (load it with heuristic19:book=PATH)"
+)]
+struct Args {
+    /// Deals whose first round is added to the book
+    #[arg(short = 'g', long = "games", default_value_t = 10_000)]
+    games: usize,
+
+    /// Players per game
+    #[arg(short = 'p', long = "players", default_value_t = 2)]
+    players: usize,
+
+    /// Base RNG seed
+    #[arg(short = 's', long = "seed", default_value_t = DEFAULT_SEED)]
+    seed: u64,
+
+    /// Plan with the weights in this JSON file; the book is only right for bots using them
+    #[arg(long = "checkpoint")]
+    checkpoint: Option<PathBuf>,
+
+    /// Write the book here
+    #[arg(short = 'o', long = "out", default_value = "opening-book.json")]
+    out: PathBuf,
+
+    /// Add to the book at --out instead of starting a new one
+    #[arg(long = "extend", action = ArgAction::SetTrue)]
+    extend: bool,
+
+    /// List the bot specs and exit
+    #[arg(long = "list-bots", exclusive = true, action = ArgAction::SetTrue)]
+    list_bots: bool,
+}
+
+fn main() {
+    let args = Args::parse();
+    if let Err(err) = run(args) {
+        eprintln!("Error: {err}");
+        process::exit(1);
+    }
+}
+
+fn run(args: Args) -> Result<(), Box<dyn Error>> {
+    if args.list_bots {
+        for spec in available_specs() {
+            println!("{spec}");
+        }
+        return Ok(());
+    }
+    let weights: PlanWeights = match &args.checkpoint {
+        Some(path) => serde_json::from_str(&fs::read_to_string(path)?)?,
+        None => PlanWeights::default(),
+    };
+    let mut book = if args.extend && args.out.exists() {
+        OpeningBook::load(&args.out)?
+    } else {
+        OpeningBook::new()
+    };
+    let mut planner = Heuristic19Bot::new().with_weights(weights);
+    let started = Instant::now();
+    let added = book.warm(
+        args.games,
+        args.players,
+        args.seed,
+        |_| Box::new(Heuristic19Bot::new().with_weights(weights)),
+        |state, legal| planner.plan_turn(state, legal),
+    )?;
+    book.save(&args.out)?;
+    println!(
+        "{added} new positions ({} in total) from {} games in {:.1?}, written to {}",
+        book.len(),
+        args.games,
+        started.elapsed(),
+        args.out.display()
+    );
+    Ok(())
+}
@@ -0,0 +1,222 @@
+use std::error::Error;
+use std::path::PathBuf;
+use std::process;
+
+use clap::{ArgAction, Parser, Subcommand};
+
+use skipbot::analysis::{Criterion, append_position, load_positions, mine_positions};
+use skipbot::bots::available_specs;
+use skipbot::{
+    Action, Bot, Game, GameError, GameStateView, PlayerId, SeedPool, SeedSplit,
+    create_bot_from_spec, describe_action, label_for_spec,
+};
+
+/// Default base seed for deterministic mining runs.
+const DEFAULT_SEED: u64 = 0x905_1710_5EED;
+/// Curated position set checked by the test suite.
+const DEFAULT_FILE: &str = "scenarios/critical.jsonl";
+
+#[derive(Parser, Debug)]
+#[command(
+    name = "positions",
+    about = "Mine critical positions from simulated games and check bots against them"
+)]
+struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// List the bot specs and exit
+    #[arg(long = "list-bots", exclusive = true, action = ArgAction::SetTrue)]
+    list_bots: bool,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Play games and append positions matching the criteria to a position file
+    Mine {
+        /// Number of games to play
+        #[arg(short = 'g', long = "games", default_value_t = 10)]
+        games: usize,
+
+        /// Base RNG seed
+        #[arg(short = 's', long = "seed", default_value_t = DEFAULT_SEED)]
+        seed: u64,
+
+        /// Criteria to look for (finish-stock, must-block, can-empty-hand); all when omitted
+        #[arg(short = 'c', long = "criterion")]
+        criteria: Vec<Criterion>,
+
+        /// Keep only positions this bot spec gets wrong
+        #[arg(long = "failing-for")]
+        failing_for: Option<String>,
+
+        /// Maximum number of positions to append
+        #[arg(short = 'n', long = "limit", default_value_t = 20)]
+        limit: usize,
+
+        /// Position file to append to
+        #[arg(short = 'o', long = "out", default_value = DEFAULT_FILE)]
+        out: PathBuf,
+
+        /// Optional override for per-player stock size
+        #[arg(long = "stock-size")]
+        stock_size: Option<usize>,
+
+        /// Player bot specs (2-6)
+        #[arg(required = true, num_args = 2..=6)]
+        bots: Vec<String>,
+    },
+    /// Report how bots fare on a position file; fails if any position is missed
+    Check {
+        /// Position file to check
+        #[arg(short = 'f', long = "file", default_value = DEFAULT_FILE)]
+        file: PathBuf,
+
+        /// Bot specs to check
+        #[arg(required = true)]
+        bots: Vec<String>,
+    },
+}
+
+fn main() {
+    let args = Args::parse();
+    if let Err(err) = run(args) {
+        eprintln!("Error: {err}");
+        process::exit(1);
+    }
+}
+
+fn run(args: Args) -> Result<(), Box<dyn Error>> {
+    if args.list_bots {
+        for spec in available_specs() {
+            println!("{spec}");
+        }
+        return Ok(());
+    }
+    let Some(command) = args.command else {
+        return Err("expected a subcommand: mine or check (see --help)".into());
+    };
+    match command {
+        Command::Mine {
+            games,
+            seed,
+            criteria,
+            failing_for,
+            limit,
+            out,
+            stock_size,
+            bots,
+        } => {
+            let criteria = if criteria.is_empty() {
+                Criterion::ALL.to_vec()
+            } else {
+                criteria
+            };
+            let pool = SeedPool::new(seed);
+            let mut appended = 0usize;
+            for game_index in 0..games {
+                if appended >= limit {
+                    break;
+                }
+                let deck_seed = pool.seed(SeedSplit::Validation, game_index as u64);
+                let decisions = play_game(&bots, deck_seed, stock_size)?;
+                let mut counter = 0usize;
+                let mined = mine_positions(
+                    decisions.iter().map(|(s, l)| (s, l.as_slice())),
+                    &criteria,
+                    |criterion| {
+                        counter += 1;
+                        format!("{criterion}-{deck_seed:016x}-{counter}")
+                    },
+                );
+                for position in mined {
+                    if appended >= limit {
+                        break;
+                    }
+                    if let Some(spec) = &failing_for {
+                        let mut bot = create_bot_from_spec(spec, position.state.self_player, seed)?;
+                        if position.check(bot.as_mut()).is_ok() {
+                            continue;
+                        }
+                    }
+                    append_position(&out, &position)?;
+                    appended += 1;
+                }
+            }
+            println!("Appended {appended} position(s) to {}", out.display());
+            Ok(())
+        }
+        Command::Check { file, bots } => {
+            let positions = load_positions(&file)?;
+            let mut failures = 0usize;
+            for spec in &bots {
+                let mut passed = 0usize;
+                for position in &positions {
+                    let mut bot = create_bot_from_spec(spec, position.state.self_player, 0)?;
+                    match position.check(bot.as_mut()) {
+                        Ok(()) => passed += 1,
+                        Err(action) => {
+                            failures += 1;
+                            println!(
+                                "  {:<12} FAIL {} [{}]: chose {}",
+                                label_for_spec(spec),
+                                position.name,
+                                position.tags.join(", "),
+                                describe_action(&position.state, &action)
+                            );
+                        }
+                    }
+                }
+                println!(
+                    "{:<12} {passed}/{} positions passed",
+                    label_for_spec(spec),
+                    positions.len()
+                );
+            }
+            if failures > 0 {
+                return Err(format!("{failures} position check(s) failed").into());
+            }
+            Ok(())
+        }
+    }
+}
+
+/// View and legal actions of one decision.
+type Decision = (GameStateView, Vec<Action>);
+
+/// Play one game and return every decision's view and legal actions.
+fn play_game(
+    specs: &[String],
+    deck_seed: u64,
+    stock_size: Option<usize>,
+) -> Result<Vec<Decision>, Box<dyn Error>> {
+    let mut builder = Game::builder(specs.len())?.with_seed(deck_seed);
+    if let Some(stock) = stock_size {
+        builder = builder.with_stock_size(stock);
+    }
+    let mut game = builder.build()?;
+    let mut bots: Vec<Box<dyn Bot>> = Vec::with_capacity(specs.len());
+    for (seat, spec) in specs.iter().enumerate() {
+        bots.push(create_bot_from_spec(
+            spec,
+            PlayerId(seat),
+            deck_seed ^ seat as u64,
+        )?);
+    }
+    let mut decisions = Vec::new();
+    while !game.is_finished() && decisions.len() < 2000 {
+        let current = game.current_player();
+        let state = game.state_view(current)?;
+        let legal = game.legal_actions(current)?;
+        if legal.is_empty() {
+            return Err(GameError::InvalidConfiguration(
+                "no legal actions available for current player",
+            )
+            .into());
+        }
+        let action = bots[current].select_action(&state, &legal);
+        decisions.push((state, legal));
+        game.apply_action(current, action)?;
+    }
+    Ok(decisions)
+}
@@ -0,0 +1,176 @@
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+use std::process;
+
+use clap::{ArgAction, Parser};
+
+use skipbot::bots::available_specs;
+use skipbot::compat::write_header;
+use skipbot::dataset::QualityReport;
+use skipbot::selfplay::{
+    CriticView, RuleRandomization, RuleRange, SelfPlayConfig, StockCurriculum, collect_self_play,
+};
+use skipbot::{LoopGuard, StockVisibility};
+
+/// Default base seed for deterministic runs.
+const DEFAULT_SEED: u64 = 0x5E1F_9A7E_5EED;
+
+#[derive(Parser, Debug)]
+#[command(
+    name = "selfplay",
+    about = "Collect self-play decisions with one bot shared by every seat"
+)]
+struct Args {
+    /// Bot spec controlling every seat
+    #[arg(short = 'b', long = "bot", default_value = "heuristic19")]
+    bot: String,
+
+    /// Number of players (2-6)
+    #[arg(short = 'p', long = "players", default_value_t = 2)]
+    players: usize,
+
+    /// Number of games to play
+    #[arg(short = 'g', long = "games", default_value_t = 100)]
+    games: usize,
+
+    /// Base RNG seed
+    #[arg(short = 's', long = "seed", default_value_t = DEFAULT_SEED)]
+    seed: u64,
+
+    /// Safety cap on actions per game
+    #[arg(long = "max-turns", default_value_t = 2000)]
+    max_turns: usize,
+
+    /// Shared bot plays one seat per game; the others get frozen copies of the spec
+    #[arg(long = "freeze-opponents", action = ArgAction::SetTrue)]
+    freeze_opponents: bool,
+
+    /// How precisely bots see stock sizes: exact, binned (last/few/many) or hidden
+    #[arg(long = "stock-visibility", default_value_t = StockVisibility::Exact)]
+    stock_visibility: StockVisibility,
+
+    /// Also record critic features (training only; the bots still play from their own
+    /// views): none, open-hands (the opponents' hands revealed) or joint (every seat's
+    /// privileged view, for a centralized critic)
+    #[arg(long = "critic", default_value_t = CriticView::None)]
+    critic: CriticView,
+
+    /// Draw every game's stock size from N or MIN-MAX (domain randomization)
+    #[arg(long = "stock-size")]
+    stock_size: Option<RuleRange>,
+
+    /// Raise the stock size from MIN to MAX over the run in --curriculum-stages equal steps;
+    /// the schedule is written next to --out as .meta.json
+    #[arg(long = "stock-curriculum", conflicts_with = "stock_size")]
+    stock_curriculum: Option<RuleRange>,
+
+    /// Steps of --stock-curriculum
+    #[arg(long = "curriculum-stages", default_value_t = 4)]
+    curriculum_stages: usize,
+
+    /// Draw every game's hand size from N or MIN-MAX (at most 5)
+    #[arg(long = "hand-size")]
+    hand_size: Option<RuleRange>,
+
+    /// Draw the number of Skip-Bo cards in every game's deck from N or MIN-MAX (at most 18)
+    #[arg(long = "skip-bo-cards")]
+    skip_bo_cards: Option<RuleRange>,
+
+    /// What the engine does when a turn returns to a position it was already in: off,
+    /// forbid (the repeating play) or end-turn
+    #[arg(long = "loop-guard", default_value_t = LoopGuard::Off)]
+    loop_guard: LoopGuard,
+
+    /// Write samples as JSON lines
+    #[arg(short = 'o', long = "out", default_value = "selfplay.jsonl")]
+    out: PathBuf,
+
+    /// List the bot specs and exit
+    #[arg(long = "list-bots", exclusive = true, action = ArgAction::SetTrue)]
+    list_bots: bool,
+}
+
+fn main() {
+    let args = Args::parse();
+    if let Err(err) = run(args) {
+        eprintln!("Error: {err}");
+        process::exit(1);
+    }
+}
+
+fn run(args: Args) -> Result<(), Box<dyn Error>> {
+    if args.list_bots {
+        for spec in available_specs() {
+            println!("{spec}");
+        }
+        return Ok(());
+    }
+    if !(2..=6).contains(&args.players) {
+        return Err(format!(
+            "expected between 2 and 6 players, received {}",
+            args.players
+        )
+        .into());
+    }
+    let config = SelfPlayConfig {
+        max_actions: args.max_turns,
+        freeze_opponents: args.freeze_opponents,
+        stock_visibility: args.stock_visibility,
+        critic: args.critic,
+        randomize: RuleRandomization {
+            stock_size: args.stock_size,
+            hand_size: args.hand_size,
+            skip_bo_cards: args.skip_bo_cards,
+        },
+        loop_guard: args.loop_guard,
+        curriculum: args.stock_curriculum.map(|stock_size| StockCurriculum {
+            stock_size,
+            stages: args.curriculum_stages,
+        }),
+        ..SelfPlayConfig::new(&args.bot, args.players, args.games, args.seed)
+    };
+
+    let mut out = BufWriter::new(File::create(&args.out)?);
+    write_header(&mut out)?;
+    let mut learner = 0usize;
+    let mut quality = QualityReport::new();
+    let mut written: Result<(), Box<dyn Error>> = Ok(());
+    let total = collect_self_play(&config, |sample| {
+        learner += usize::from(sample.learner);
+        quality.add(&sample);
+        if written.is_ok() {
+            written = serde_json::to_writer(&mut out, &sample)
+                .map_err(Into::into)
+                .and_then(|()| Ok(out.write_all(b"\n")?));
+        }
+    })?;
+    written?;
+    out.flush()?;
+
+    println!(
+        "{total} samples ({learner} from the shared bot) from {} games written to {}",
+        args.games,
+        args.out.display()
+    );
+    print!("{quality}");
+
+    let schedule = config.curriculum_schedule();
+    if !schedule.is_empty() {
+        println!("\nStock curriculum:");
+        for stage in &schedule {
+            println!(
+                "  games {}-{}: stock {}",
+                stage.first_game + 1,
+                stage.first_game + stage.games,
+                stage.stock_size
+            );
+        }
+        let path = args.out.with_extension("meta.json");
+        let metadata = serde_json::json!({ "config": config, "curriculum": schedule });
+        fs::write(&path, serde_json::to_string_pretty(&metadata)?)?;
+        println!("Metadata written to {}", path.display());
+    }
+    Ok(())
+}
@@ -1,10 +1,30 @@
 use std::env;
 use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
 use std::process;
 
-use skipbot::{Bot, Game, GameError, create_bot_from_spec, describe_action, render_state};
+use skipbot::analysis::{HighlightConfig, estimate_win_probability, find_highlights};
+use skipbot::bench::{EngineBench, bench_engine};
+use skipbot::bots::{BotsConfig, available_specs, human_name};
+use skipbot::commentary::Commentary;
+use skipbot::daily::{DailyChallenge, DailyResult, Date};
+use skipbot::runner::{Handicap, parse_handicap_entry};
+use skipbot::visualize::render_state_with_options;
+use skipbot::{
+    Bot, Game, GameError, GameRecord, HumanBot, PlayerId, SavedGame, SeatRules, VisualOptions,
+    create_bot_from_spec, describe_action,
+};
 
 const DEFAULT_SEED: u64 = 0xDEC0_1DED_5EED_F00D;
+/// Autosave file name in the temp directory for games with human players.
+const AUTOSAVE_FILE: &str = "skipbot-autosave.json";
+/// Stored `--bench-engine` result that new runs are compared against.
+const BENCH_BASELINE: &str = "research/engine_baseline.json";
+
+#[cfg(feature = "alloc-count")]
+#[global_allocator]
+static ALLOCATOR: skipbot::bench::CountingAllocator = skipbot::bench::CountingAllocator;
 
 fn main() {
     if let Err(err) = run() {
@@ -18,10 +38,56 @@ fn run() -> Result<(), Box<dyn Error>> {
     let mut visualize = false;
     let mut seed = DEFAULT_SEED;
     let mut max_turns: Option<usize> = None;
+    let mut eval_samples = 0usize;
+    let mut record: Option<PathBuf> = None;
+    let mut autosave: Option<PathBuf> = None;
+    let mut resume: Option<PathBuf> = None;
+    let mut highlights: Option<PathBuf> = None;
+    let mut handicaps: Vec<Vec<Handicap>> = Vec::new();
+    let mut must_play_stock = false;
+    let mut bench_games: Option<usize> = None;
+    let mut bench_players = 2usize;
+    let mut bench_baseline = PathBuf::from(BENCH_BASELINE);
+    let mut save_baseline = false;
+    let mut daily = false;
+    let mut date: Option<Date> = None;
     let mut bot_specs: Vec<String> = Vec::new();
     while let Some(arg) = args.next() {
         match arg.as_str() {
             "--visualize" => visualize = true,
+            "--must-play-stock" => must_play_stock = true,
+            "--save-baseline" => save_baseline = true,
+            "--daily" => daily = true,
+            "--date" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--date requires a date (YYYY-MM-DD)".to_string())?;
+                date = Some(value.parse()?);
+            }
+            "--bench-engine" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--bench-engine requires a number of games".to_string())?;
+                bench_games = Some(
+                    value
+                        .parse::<usize>()
+                        .map_err(|_| format!("invalid bench-engine value: {value}"))?,
+                );
+            }
+            "--bench-players" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--bench-players requires a value".to_string())?;
+                bench_players = value
+                    .parse::<usize>()
+                    .map_err(|_| format!("invalid bench-players value: {value}"))?;
+            }
+            "--baseline" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--baseline requires a file path".to_string())?;
+                bench_baseline = PathBuf::from(value);
+            }
             "--seed" => {
                 let value = args
                     .next()
@@ -40,46 +106,221 @@ fn run() -> Result<(), Box<dyn Error>> {
                         .map_err(|_| format!("invalid max-turns value: {value}"))?,
                 );
             }
+            "--eval-samples" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--eval-samples requires a value".to_string())?;
+                eval_samples = value
+                    .parse::<usize>()
+                    .map_err(|_| format!("invalid eval-samples value: {value}"))?;
+            }
+            "--record" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--record requires a file path".to_string())?;
+                record = Some(PathBuf::from(value));
+            }
+            "--autosave" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--autosave requires a file path".to_string())?;
+                autosave = Some(PathBuf::from(value));
+            }
+            "--handicap" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--handicap requires SEAT:HANDICAP".to_string())?;
+                let (seat, handicap) = parse_handicap_entry(&value)?;
+                if handicaps.len() <= seat {
+                    handicaps.resize(seat + 1, Vec::new());
+                }
+                handicaps[seat].push(handicap);
+            }
+            "--bots-config" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--bots-config requires a file path".to_string())?;
+                bot_specs.extend(BotsConfig::load(Path::new(&value))?.specs());
+            }
+            "--commentary" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--commentary requires a file path".to_string())?;
+                let record = GameRecord::load(Path::new(&value))?;
+                print!("{}", Commentary::from_record(&record)?);
+                return Ok(());
+            }
+            "--highlights" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--highlights requires a file path".to_string())?;
+                highlights = Some(PathBuf::from(value));
+            }
+            "--continue" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--continue requires a file path".to_string())?;
+                resume = Some(PathBuf::from(value));
+            }
             "--help" => {
                 print_usage();
                 return Ok(());
             }
+            "--list-bots" => {
+                for spec in available_specs() {
+                    println!("{spec}");
+                }
+                return Ok(());
+            }
             other => bot_specs.push(other.to_string()),
         }
     }
 
-    if bot_specs.is_empty() {
-        bot_specs = vec![String::from("human"), String::from("random")];
+    if let Some(games) = bench_games {
+        return run_bench(games, bench_players, seed, &bench_baseline, save_baseline);
     }
-    if bot_specs.len() < 2 || bot_specs.len() > 6 {
-        return Err(format!(
-            "expected between 2 and 6 players, received {}",
-            bot_specs.len()
-        )
-        .into());
+
+    if let Some(path) = &highlights {
+        let record = GameRecord::load(path)?;
+        if bot_specs.is_empty() {
+            bot_specs = record
+                .tag("Bots")
+                .map(|bots| bots.split_whitespace().map(String::from).collect())
+                .unwrap_or_default();
+        }
+        let mut config = HighlightConfig {
+            rollout_bots: bot_specs.iter().map(|spec| rollout_spec(spec)).collect(),
+            ..HighlightConfig::default()
+        };
+        if eval_samples > 0 {
+            config.samples = eval_samples;
+        }
+        for highlight in find_highlights(&record, &config)? {
+            println!("{highlight}");
+        }
+        return Ok(());
     }
 
+    if date.is_some() && !daily {
+        return Err("--date picks the day of --daily".into());
+    }
+    let challenge = daily.then(|| DailyChallenge::for_date(date.unwrap_or_else(Date::today)));
+    let mut game = match &resume {
+        Some(path) => {
+            if !bot_specs.is_empty() || !handicaps.is_empty() || must_play_stock || daily {
+                return Err("--continue takes the players and rules from the saved game".into());
+            }
+            let saved = SavedGame::load(path)?;
+            bot_specs = saved.bots;
+            handicaps = saved.handicaps;
+            seed = saved.game.config().seed;
+            // Keep saving to the file the game was resumed from.
+            autosave.get_or_insert_with(|| path.clone());
+            saved.game
+        }
+        None if challenge.is_some() => {
+            let challenge = challenge.as_ref().expect("checked above");
+            if bot_specs.len() > 1 || !handicaps.is_empty() || must_play_stock {
+                return Err(
+                    "--daily takes one player (default: human); the rules are the challenge's"
+                        .into(),
+                );
+            }
+            if bot_specs.is_empty() {
+                bot_specs.push(String::from("human"));
+            }
+            bot_specs.push(challenge.opponent.clone());
+            seed = challenge.seed;
+            println!(
+                "Daily challenge #{} ({}) against {}.",
+                challenge.number(),
+                challenge.date,
+                challenge.opponent
+            );
+            challenge.game()?
+        }
+        None => {
+            if bot_specs.is_empty() {
+                bot_specs = vec![String::from("human"), String::from("random")];
+            }
+            if bot_specs.len() < 2 || bot_specs.len() > 6 {
+                return Err(format!(
+                    "expected between 2 and 6 players, received {}",
+                    bot_specs.len()
+                )
+                .into());
+            }
+            if handicaps.len() > bot_specs.len() {
+                return Err("--handicap seat is outside the game".into());
+            }
+            let mut rules = vec![SeatRules::default(); bot_specs.len()];
+            for (seat, seat_handicaps) in handicaps.iter().enumerate() {
+                for handicap in seat_handicaps {
+                    handicap.apply_rules(PlayerId(seat), &mut rules);
+                }
+            }
+            let mut builder = Game::builder(bot_specs.len())?
+                .with_seed(seed)
+                .with_must_play_stock(must_play_stock);
+            for (seat, rules) in rules.into_iter().enumerate() {
+                builder = builder.with_seat_rules(PlayerId(seat), rules);
+            }
+            builder.build()?
+        }
+    };
     let num_players = bot_specs.len();
-    let builder = Game::builder(num_players)?.with_seed(seed);
-    let mut game = builder.build()?;
+
+    let humans: Vec<Option<String>> = bot_specs
+        .iter()
+        .enumerate()
+        .map(|(seat, spec)| human_name(spec, PlayerId(seat)))
+        .collect();
+    // Only interactive games are worth recovering.
+    if humans.iter().any(Option::is_some) && autosave.is_none() {
+        autosave = Some(env::temp_dir().join(AUTOSAVE_FILE));
+    }
+    // Players sharing a terminal must not see each other's hands.
+    let hot_seat = humans.iter().flatten().count() > 1;
 
     let mut bots: Vec<Box<dyn Bot>> = Vec::with_capacity(num_players);
     for (index, spec) in bot_specs.iter().enumerate() {
-        let bot = create_bot_from_spec(spec, index, seed)?;
+        let mut bot = match &humans[index] {
+            Some(name) if hot_seat => Box::new(HumanBot::new(name).with_privacy_screen()),
+            _ => create_bot_from_spec(spec, PlayerId(index), seed)?,
+        };
+        for handicap in handicaps.get(index).into_iter().flatten() {
+            handicap.apply_bot(bot.as_mut());
+        }
         bots.push(bot);
     }
 
-    println!("Starting Skip-Bo simulation with {num_players} players.\n");
+    let rollout_specs: Vec<String> = bot_specs.iter().map(|spec| rollout_spec(spec)).collect();
+
+    if resume.is_some() {
+        println!(
+            "Resuming Skip-Bo game with {num_players} players at turn {}.\n",
+            game.turn_number() + 1
+        );
+    } else {
+        println!("Starting Skip-Bo simulation with {num_players} players.\n");
+    }
+    if let Some(path) = &autosave {
+        save_game(&game, &bot_specs, &handicaps, path)?;
+        println!(
+            "Autosaving to {0}; resume with --continue {0}\n",
+            path.display()
+        );
+    }
     let mut turns = 0usize;
     loop {
         if game.is_finished() {
             break;
         }
-        if let Some(limit) = max_turns {
-            if turns >= limit {
-                println!("Max turn limit {limit} reached. Stopping simulation.");
-                break;
-            }
+        if let Some(limit) = max_turns
+            && turns >= limit
+        {
+            println!("Max turn limit {limit} reached. Stopping simulation.");
+            break;
         }
         let current = game.current_player();
         let state = game.state_view(current)?;
@@ -90,15 +331,29 @@ fn run() -> Result<(), Box<dyn Error>> {
             )
             .into());
         }
-        if visualize {
-            println!("{}", render_state(&state));
+        // A hot-seat human only sees their hand behind the privacy screen.
+        if visualize && !(hot_seat && humans[current].is_some()) {
+            let mut options = VisualOptions::default();
+            if eval_samples > 0 {
+                let estimate = estimate_win_probability(&game, &rollout_specs, eval_samples)?;
+                let probabilities: Vec<f64> =
+                    estimate.players.iter().map(|p| p.probability).collect();
+                options = options.with_win_probabilities(&probabilities);
+            }
+            println!("{}", render_state_with_options(&state, options));
+        }
+        if bots[current].wants_full_state() {
+            bots[current].observe_full_state(&game.full_state_view(current)?);
         }
         let action = bots[current].select_action(&state, &legal_actions);
-        if visualize {
+        if visualize && !(hot_seat && humans[current].is_some()) {
             println!("Chosen action: {}\n", describe_action(&state, &action));
         }
         game.apply_action(current, action)?;
         turns += 1;
+        if let Some(path) = &autosave {
+            save_game(&game, &bot_specs, &handicaps, path)?;
+        }
     }
 
     if game.is_finished() {
@@ -107,23 +362,120 @@ fn run() -> Result<(), Box<dyn Error>> {
         } else {
             println!("Game finished in a draw.");
         }
+        // Nothing left to resume.
+        if let Some(path) = &autosave {
+            fs::remove_file(path)?;
+        }
     } else {
         println!("Simulation stopped before completion.");
     }
 
+    if let Some(challenge) = &challenge {
+        println!("\n{}", DailyResult::from_game(challenge, &game)?.share());
+    }
+
+    if let Some(path) = record {
+        GameRecord::from_game(&game)?
+            .with_tag("Bots", &bot_specs.join(" "))
+            .save(&path)?;
+        println!("Game record written to {}", path.display());
+    }
+
+    Ok(())
+}
+
+fn run_bench(
+    games: usize,
+    players: usize,
+    seed: u64,
+    baseline_path: &Path,
+    save_baseline: bool,
+) -> Result<(), Box<dyn Error>> {
+    let bench = bench_engine(games, players, seed)?;
+    println!("Engine benchmark: {games} games, {players} random bots");
+    println!("  games/sec:     {:>12.1}", bench.games_per_sec());
+    println!("  actions/sec:   {:>12.1}", bench.actions_per_sec());
+    match bench.allocations_per_action() {
+        Some(per_action) => println!(
+            "  allocations:   {:>12} ({per_action:.1} per action)",
+            bench.allocations.unwrap_or_default()
+        ),
+        None => println!("  allocations:   not counted (build with --features alloc-count)"),
+    }
+    if save_baseline {
+        bench.save(baseline_path)?;
+        println!("\nBaseline written to {}", baseline_path.display());
+    } else if baseline_path.exists() {
+        let baseline = EngineBench::load(baseline_path)?;
+        println!("\nCompared to {}:", baseline_path.display());
+        print!("{}", bench.compare(&baseline));
+    } else {
+        println!(
+            "\nNo baseline at {}; store one with --save-baseline",
+            baseline_path.display()
+        );
+    }
     Ok(())
 }
 
+fn save_game(
+    game: &Game,
+    bot_specs: &[String],
+    handicaps: &[Vec<Handicap>],
+    path: &Path,
+) -> Result<(), Box<dyn Error>> {
+    let mut saved = SavedGame::new(game, bot_specs)?;
+    saved.handicaps = handicaps.to_vec();
+    saved.save(path)
+}
+
+/// Rollouts cannot wait on a human, so human seats are played by a heuristic.
+fn rollout_spec(spec: &str) -> String {
+    if spec.to_ascii_lowercase().starts_with("human") {
+        String::from("heuristic13")
+    } else {
+        spec.to_string()
+    }
+}
+
 fn print_usage() {
     println!("Usage: simulate [OPTIONS] [BOT ...]");
     println!("  --visualize           Show the game state and chosen actions each turn");
     println!("  --seed <u64>          Seed for shuffling (default: {DEFAULT_SEED:#x})");
     println!("  --max-turns <usize>   Stop after the specified number of turns");
+    println!(
+        "  --eval-samples <n>    With --visualize, show a win-probability bar from n rollouts"
+    );
+    println!("  --record <file>       Write the game in portable notation when it ends");
+    println!(
+        "  --autosave <file>     Save after every move (default for human games: a temp file)"
+    );
+    println!("  --continue <file>     Resume a game from its autosave");
+    println!("  --commentary <file>   Narrate a recorded game turn by turn and exit");
+    println!("  --highlights <file>   List the highlights of a recorded game; the swing uses");
+    println!("                        rollouts of the given bots or the record's Bots tag");
+    println!("  --bots-config <file>  Read the bots of every seat from a TOML or JSON file");
+    println!("  --handicap <seat:h>   Handicap a seat: weak-draws, opponent-extra-discard,");
+    println!("                        nodes=N or stock-wilds=N");
+    println!("  --must-play-stock     House rule: a playable stock card must be played first");
+    println!("  --daily               Play today's challenge (UTC): the same deal and opponent");
+    println!("                        for everyone, with a result to share when it ends");
+    println!("  --date <YYYY-MM-DD>   With --daily, play the challenge of another day");
+    println!("  --bench-engine <n>    Time n games of random bots and compare to a baseline");
+    println!("  --bench-players <n>   Players per benchmark game (default: 2)");
+    println!("  --baseline <file>     Benchmark baseline (default: {BENCH_BASELINE})");
+    println!("  --save-baseline       Store the benchmark result as the new baseline");
+    println!("  --list-bots           List the bot specs and exit");
     println!("  --help                Show this help message");
     println!("Bot entries (2-6 total):");
-    println!("  human[:name]          Interactive human-controlled player");
+    println!("  human[:name]          Interactive human-controlled player; with several, each");
+    println!("                        turn starts behind a privacy screen (hot-seat)");
     println!("  random[:seed]         Random bot with optional per-bot seed");
     println!("  heuristic             Deterministic rule-based baseline bot");
     println!("  heuristic2            Improved heuristic with stock-first planning");
+    println!("  heuristic19[:strength=N]  Turn planner; strength 1-10 sets the difficulty");
+    println!("  beam[:width[:depth]][:eval=NAME]  Beam search with a named evaluator");
+    println!("  oracle[:nodes]        Cheating planner that sees every hidden card");
+    println!("Any bot entry may add :label=NAME and :seed=N.");
     println!("If no bots are provided, defaults to one human and one random bot.");
 }
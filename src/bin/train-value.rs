@@ -0,0 +1,355 @@
+use std::error::Error;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+
+use clap::{ArgAction, Parser};
+
+use skipbot::bots::available_specs;
+use skipbot::charts::render_training_charts;
+use skipbot::compat::FeatureShim;
+use skipbot::experiments::{Artifact, DEFAULT_ROOT, Experiment, ExperimentConfig, RunSummary};
+use skipbot::interrupt::Interrupter;
+use skipbot::runner::mix_seed;
+use skipbot::value::{
+    Calibration, EpochMetrics, Selection, ValueTrainConfig, load_value_samples_with,
+    train_value_net_until,
+};
+
+/// Default base seed for deterministic runs.
+const DEFAULT_SEED: u64 = 0x7A11_0E5E_5EED;
+
+#[derive(Parser, Debug)]
+#[command(
+    name = "train-value",
+    about = "Fit a win-probability model to replay or self-play samples and report its calibration"
+)]
+struct Args {
+    /// JSON lines of samples with game, features and outcome (repeatable), e.g. from
+    /// `dataset` or `selfplay`
+    #[arg(short = 'd', long = "data", required = true)]
+    data: Vec<PathBuf>,
+
+    /// Samples stamped with another encoder version: strict refuses them, resize pads or
+    /// truncates their features
+    #[arg(long = "feature-shim", default_value_t = FeatureShim::Strict)]
+    feature_shim: FeatureShim,
+
+    /// Hidden units
+    #[arg(long = "hidden", default_value_t = 32)]
+    hidden: usize,
+
+    /// Passes over the training games
+    #[arg(short = 'e', long = "epochs", default_value_t = 20)]
+    epochs: usize,
+
+    /// Samples per gradient step
+    #[arg(long = "batch-size", default_value_t = 64)]
+    batch_size: usize,
+
+    /// Adam learning rate
+    #[arg(long = "lr", default_value_t = 1e-3)]
+    learning_rate: f32,
+
+    /// Share of games held out for validation
+    #[arg(long = "validation", default_value_t = 0.2)]
+    validation: f64,
+
+    /// Bins of the reliability diagram
+    #[arg(long = "bins", default_value_t = 10)]
+    bins: usize,
+
+    /// Base RNG seed
+    #[arg(short = 's', long = "seed", default_value_t = DEFAULT_SEED)]
+    seed: u64,
+
+    /// Write the trained model as JSON
+    #[arg(short = 'o', long = "out", default_value = "value.json")]
+    out: PathBuf,
+
+    /// Draw loss, evaluation win rate and learning rate charts into this directory after
+    /// training
+    #[arg(long = "charts")]
+    charts: Option<PathBuf>,
+
+    /// Run as a named experiment: the model becomes its next numbered checkpoint, with
+    /// metrics under reports and charts under charts
+    #[arg(long = "experiment", conflicts_with_all = ["out", "metrics", "charts"])]
+    experiment: Option<String>,
+
+    /// Root directory of --experiment runs
+    #[arg(long = "experiments-dir", default_value = DEFAULT_ROOT)]
+    experiments_dir: PathBuf,
+
+    /// Write the losses and calibration metrics as JSON
+    #[arg(long = "metrics")]
+    metrics: Option<PathBuf>,
+
+    /// Games the current net plays (in a beam search bot) against --eval-opponent on
+    /// evaluation epochs; 0 disables game-play evaluation
+    #[arg(long = "eval-games", default_value_t = 0)]
+    eval_games: usize,
+
+    /// Epochs between evaluation games (the last epoch is always evaluated)
+    #[arg(long = "eval-every", default_value_t = 5)]
+    eval_every: usize,
+
+    /// Opponent bot spec of the evaluation games
+    #[arg(long = "eval-opponent", default_value = "heuristic13")]
+    eval_opponent: String,
+
+    /// Keep the best epoch's net instead of the last: validation-loss or win-rate (needs
+    /// --eval-games of at least --min-eval-games)
+    #[arg(long = "best-by")]
+    best_by: Option<Selection>,
+
+    /// Fewest evaluation games per epoch that --best-by win-rate accepts
+    #[arg(long = "min-eval-games", default_value_t = 20)]
+    min_eval_games: usize,
+
+    /// Train on the samples in a canonical order (by game, then content) so the model does
+    /// not depend on the order of --data files or of their lines
+    #[arg(long = "deterministic", action = ArgAction::SetTrue)]
+    deterministic: bool,
+
+    /// Train this many runs that differ only in their seed (the first uses --seed) and
+    /// summarize each epoch's metrics over them; the models get numbered file names, and
+    /// --metrics receives the summary
+    #[arg(long = "runs", default_value_t = 1)]
+    runs: usize,
+
+    /// Append each epoch's metrics to this file as a JSON line while training, with the
+    /// run's seed under --runs
+    #[arg(long = "log")]
+    log: Option<PathBuf>,
+
+    /// List the bot specs and exit
+    #[arg(long = "list-bots", exclusive = true, action = ArgAction::SetTrue)]
+    list_bots: bool,
+}
+
+fn main() {
+    let args = Args::parse();
+    if let Err(err) = run(args) {
+        eprintln!("Error: {err}");
+        process::exit(1);
+    }
+}
+
+fn run(args: Args) -> Result<(), Box<dyn Error>> {
+    if args.list_bots {
+        for spec in available_specs() {
+            println!("{spec}");
+        }
+        return Ok(());
+    }
+    let mut samples = Vec::new();
+    for path in &args.data {
+        samples.extend(load_value_samples_with(path, args.feature_shim)?);
+    }
+    let config = ValueTrainConfig {
+        hidden: args.hidden,
+        epochs: args.epochs,
+        batch_size: args.batch_size,
+        learning_rate: args.learning_rate,
+        validation_fraction: args.validation,
+        bins: args.bins,
+        seed: args.seed,
+        eval_games: args.eval_games,
+        eval_every: args.eval_every,
+        eval_opponent: args.eval_opponent.clone(),
+        best_by: args.best_by,
+        min_eval_games: args.min_eval_games,
+        deterministic: args.deterministic,
+    };
+    let runs = args.runs.max(1);
+    let seeds: Vec<u64> = (0..runs as u64)
+        .map(|run| match run {
+            0 => args.seed,
+            _ => mix_seed(args.seed, run, 0x5EED_5EED),
+        })
+        .collect();
+    let experiment = match &args.experiment {
+        Some(name) => {
+            let bots = if config.eval_games > 0 {
+                vec![config.eval_opponent.clone()]
+            } else {
+                Vec::new()
+            };
+            let settings = serde_json::json!({ "train": config, "data": args.data });
+            let experiment = Experiment::open(
+                &args.experiments_dir,
+                ExperimentConfig::new(name, bots, seeds.clone()).with_settings(&settings)?,
+            )?;
+            println!("Experiment {}\n", experiment.dir().display());
+            Some(experiment)
+        }
+        None => None,
+    };
+    let mut log = match &args.log {
+        Some(path) => Some(OpenOptions::new().create(true).append(true).open(path)?),
+        None => None,
+    };
+
+    // Ctrl-C finishes the epoch in progress and keeps it; a second Ctrl-C exits at once.
+    let interrupter = Interrupter::ctrl_c();
+    let mut histories = Vec::with_capacity(runs);
+    let mut first_stem = None;
+    for (run, &seed) in seeds.iter().enumerate() {
+        let config = ValueTrainConfig {
+            seed,
+            ..config.clone()
+        };
+        let mut out = args.out.clone();
+        let mut metrics_path = args.metrics.clone().filter(|_| runs == 1);
+        let mut charts = args.charts.clone();
+        if runs > 1 {
+            println!("== run {}/{runs} (seed {seed}) ==", run + 1);
+            out = suffixed(&args.out, &(run + 1).to_string());
+            charts = charts.map(|dir| dir.join(format!("run-{}", run + 1)));
+        }
+        if let Some(experiment) = &experiment {
+            out = experiment.next_checkpoint("value", "json");
+            let stem = out
+                .file_stem()
+                .expect("checkpoints have a file name")
+                .to_string_lossy()
+                .into_owned();
+            metrics_path =
+                Some(experiment.new_artifact(Artifact::Report, &format!("{stem}.json"))?);
+            charts = Some(experiment.path(Artifact::Chart, &stem));
+            first_stem.get_or_insert(stem);
+        }
+        let mut log_error = None;
+        let training = train_value_net_until(&samples, &config, &interrupter, |metrics| {
+            let win_rate = metrics
+                .win_rate
+                .map(|rate| format!("  win {:>5.1}%", rate * 100.0))
+                .unwrap_or_default();
+            let best = if metrics.best { "  *" } else { "" };
+            println!(
+                "epoch {:>3}: loss {:.4}  validation {:.4}{win_rate}{best}",
+                metrics.epoch, metrics.loss, metrics.validation_loss
+            );
+            if let Some(file) = &mut log
+                && let Err(err) = log_line(metrics, (runs > 1).then_some(seed))
+                    .and_then(|line| writeln!(file, "{line}"))
+            {
+                log_error.get_or_insert(err);
+            }
+        })?;
+        if let Some(err) = log_error {
+            return Err(format!("writing the training log: {err}").into());
+        }
+        if training.interrupted {
+            out = suffixed(&out, "interrupted");
+            println!(
+                "interrupted after epoch {} of {}",
+                training.epochs.len(),
+                config.epochs
+            );
+        }
+        print_calibration("train", &training.train);
+        print_calibration("validation", &training.validation);
+
+        training.net.save(&out)?;
+        println!("\nModel written to {}", out.display());
+        if let Some(best) = &training.best {
+            let win_rate = best
+                .win_rate
+                .map(|rate| {
+                    format!(
+                        ", win rate {:.1}% over {} games",
+                        rate * 100.0,
+                        best.eval_games
+                    )
+                })
+                .unwrap_or_default();
+            println!(
+                "  from epoch {} (by {}): validation loss {:.4}{win_rate}",
+                best.epoch, best.selected_by, best.validation_loss
+            );
+            let path = out.with_extension("meta.json");
+            fs::write(&path, serde_json::to_string_pretty(best)?)?;
+            println!("Checkpoint metadata written to {}", path.display());
+        }
+        if let Some(path) = &metrics_path {
+            let metrics = serde_json::json!({
+                "config": config,
+                "epochs": training.epochs,
+                "best": training.best,
+                "train": training.train,
+                "validation": training.validation,
+                "interrupted": training.interrupted,
+            });
+            fs::write(path, serde_json::to_string_pretty(&metrics)?)?;
+            println!("Metrics written to {}", path.display());
+        }
+        if let Some(dir) = &charts {
+            fs::create_dir_all(dir)?;
+            let charts = render_training_charts(dir, &training.epochs)?;
+            println!("{} charts written to {}", charts.len(), dir.display());
+        }
+        if runs > 1 {
+            println!();
+        }
+        histories.push(training.epochs);
+        if training.interrupted {
+            return Ok(());
+        }
+    }
+
+    if runs > 1 {
+        let summary = RunSummary::new(&seeds, &histories)?;
+        print!("{summary}");
+        let path = match (&experiment, &first_stem) {
+            (Some(experiment), Some(stem)) => {
+                Some(experiment.new_artifact(Artifact::Report, &format!("{stem}-summary.json"))?)
+            }
+            _ => args.metrics.clone(),
+        };
+        if let Some(path) = path {
+            fs::write(&path, serde_json::to_string_pretty(&summary)?)?;
+            println!("Summary written to {}", path.display());
+        }
+    }
+    Ok(())
+}
+
+/// `path` with `-<suffix>` appended to its file stem: `value.json` becomes `value-2.json`
+/// or `value-interrupted.json`.
+fn suffixed(path: &Path, suffix: &str) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let name = match path.extension() {
+        Some(extension) => format!("{stem}-{suffix}.{}", extension.to_string_lossy()),
+        None => format!("{stem}-{suffix}"),
+    };
+    path.with_file_name(name)
+}
+
+/// An epoch's line in the training log, with the run's seed when several runs share it.
+fn log_line(metrics: &EpochMetrics, seed: Option<u64>) -> io::Result<String> {
+    let mut line = serde_json::to_value(metrics)?;
+    if let (Some(seed), Some(object)) = (seed, line.as_object_mut()) {
+        object.insert("seed".to_string(), seed.into());
+    }
+    Ok(line.to_string())
+}
+
+fn print_calibration(name: &str, calibration: &Calibration) {
+    println!(
+        "\n{name}: {} samples, Brier {:.4}, log loss {:.4}, ECE {:.4}",
+        calibration.samples, calibration.brier, calibration.log_loss, calibration.ece
+    );
+    println!(
+        "  {:<11} {:>7} {:>10} {:>9}",
+        "bin", "count", "predicted", "observed"
+    );
+    for bin in calibration.bins.iter().filter(|bin| bin.count > 0) {
+        println!(
+            "  {:.2}-{:.2}   {:>7} {:>10.3} {:>9.3}",
+            bin.lower, bin.upper, bin.count, bin.mean_predicted, bin.observed
+        );
+    }
+}
@@ -1,31 +1,36 @@
-use std::collections::HashMap;
 use std::error::Error;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process;
-use std::time::Instant;
 
 use clap::{ArgAction, Parser, ValueEnum};
 use plotters::prelude::*;
-use rand::SeedableRng;
-use rand::rngs::StdRng;
-use rand::seq::SliceRandom;
-// no need to import Shift when drawing inline
 
-use skipbot::winner_points;
-use skipbot::{Bot, Game, GameError};
-use skipbot::{create_bot_from_spec, label_for_spec};
+use skipbot::archive::{ArchiveWriter, Compression};
+use skipbot::bots::BotsConfig;
+use skipbot::bots::available_specs;
+use skipbot::experiments::{Artifact, DEFAULT_ROOT, Experiment, ExperimentConfig};
+use skipbot::interrupt::{Interrupted, Interrupter};
+use skipbot::reload::{ReloadTrigger, WeightReloader};
+use skipbot::runner::{
+    FaultPolicy, Handicap, MatchConfig, MatchReport, MatchRunner, ObjectiveWeights, RunState,
+    parse_handicap_entry,
+};
+use skipbot::{SeedSplit, StockVisibility};
+
+/// Faults the summary lists one by one; the rest are only counted.
+const MAX_LISTED_FAULTS: usize = 10;
 
 /// Default base seed for deterministic runs.
 const DEFAULT_SEED: u64 = 0xC0FFEE_u64 << 32 | 0x5EED_u64;
 
 /// Output format for the generated chart. Currently only PNG is supported.
-#[derive(Clone, Debug, ValueEnum)]
+#[derive(Clone, Copy, Debug, ValueEnum)]
 enum ChartFormat {
     Png,
 }
 
 impl ChartFormat {
-    fn from_path(path: &PathBuf) -> Option<Self> {
+    fn from_path(path: &Path) -> Option<Self> {
         match path
             .extension()
             .and_then(|s| s.to_str())
@@ -37,6 +42,13 @@ impl ChartFormat {
     }
 }
 
+/// Ordering of the summary table.
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum SortBy {
+    WinRate,
+    Composite,
+}
+
 #[derive(Parser, Debug)]
 #[command(
     name = "winrate",
@@ -72,8 +84,106 @@ struct Args {
     #[arg(long = "stock-size")]
     stock_size: Option<usize>,
 
+    /// Draw deck seeds from a disjoint seed universe (train, validation or test)
+    #[arg(long = "seed-split")]
+    seed_split: Option<SeedSplit>,
+
+    /// Write the full match report (config, per-game results, metrics) as JSON
+    #[arg(long = "report")]
+    report: Option<PathBuf>,
+
+    /// Checkpoint progress to this file and resume from it if it already exists
+    #[arg(long = "resume-state")]
+    resume_state: Option<PathBuf>,
+
+    /// Games between checkpoints when --resume-state is set
+    #[arg(long = "checkpoint-every", default_value_t = 50)]
+    checkpoint_every: usize,
+
+    /// Store every game in this binary replay archive
+    #[arg(long = "archive", conflicts_with = "resume_state")]
+    archive: Option<PathBuf>,
+
+    /// zstd level for --archive records (needs the zstd feature); 0 stores them raw
+    #[arg(long = "archive-zstd", default_value_t = 0)]
+    archive_zstd: i32,
+
+    /// Render the summary and chart from an existing match report instead of simulating
+    #[arg(long = "from-report", conflicts_with = "bots")]
+    from_report: Option<PathBuf>,
+
+    /// Order the summary by raw win rate or by the weighted composite objective
+    #[arg(long = "sort-by", value_enum, default_value = "win-rate")]
+    sort_by: SortBy,
+
+    /// Composite objective weights, e.g. win=1,margin=0.5,turns_to_win=0.1,loss_stock=0.25
+    #[arg(long = "objective-weights", default_value = "")]
+    weights: ObjectiveWeights,
+
+    /// Fail unless LABEL's composite score is at least VALUE (repeatable), e.g. heuristic13=0.4
+    #[arg(long = "min-composite", value_parser = parse_gate)]
+    min_composite: Vec<(String, f64)>,
+
+    /// Handicap a bot, given as INDEX:HANDICAP with INDEX into the bot specs (repeatable):
+    /// weak-draws, opponent-extra-discard, nodes=N or stock-wilds=N
+    #[arg(long = "handicap", value_parser = parse_handicap_entry)]
+    handicaps: Vec<(usize, Handicap)>,
+
+    /// Play under the house rule that a playable stock card must be played first
+    #[arg(long = "must-play-stock", action = ArgAction::SetTrue)]
+    must_play_stock: bool,
+
+    /// How precisely bots see stock sizes: exact, binned (last/few/many) or hidden
+    #[arg(long = "stock-visibility", default_value_t = StockVisibility::Exact)]
+    stock_visibility: StockVisibility,
+
+    /// Time budget per decision in milliseconds; search bots play their best move so far
+    /// once it runs out (results then depend on the machine)
+    #[arg(long = "move-time")]
+    move_time: Option<u64>,
+
+    /// Hard limit per decision in milliseconds; a slower decision is a fault
+    #[arg(long = "time-limit")]
+    time_limit: Option<u64>,
+
+    /// What a bot that picks an illegal action or overruns the time limit gets: abort
+    /// (stop the run), forfeit (lose the game) or random-move
+    #[arg(long = "on-fault", default_value_t = FaultPolicy::Abort)]
+    on_fault: FaultPolicy,
+
+    /// What a bot that panics gets: random-move (play on with a random legal action),
+    /// forfeit (lose the game) or abort (stop the run)
+    #[arg(long = "on-panic", default_value_t = FaultPolicy::RandomMove)]
+    on_panic: FaultPolicy,
+
+    /// Read the weight files of heuristic19 `checkpoint=` specs again between games:
+    /// modified (when the file changes) or signal (after SIGHUP); without it every game
+    /// reads the file as it finds it
+    #[arg(long = "reload-weights", conflicts_with = "from_report")]
+    reload_weights: Option<ReloadTrigger>,
+
+    /// Read the bots of every seat from a TOML or JSON file instead of spec arguments
+    #[arg(long = "bots-config", conflicts_with_all = ["bots", "from_report"])]
+    bots_config: Option<PathBuf>,
+
+    /// Run as a named experiment: the report, chart and replays go to a directory named
+    /// after the configuration hash, and an existing report is never overwritten
+    #[arg(
+        long = "experiment",
+        conflicts_with_all = ["out", "report", "resume_state", "archive", "from_report"]
+    )]
+    experiment: Option<String>,
+
+    /// Root directory of --experiment runs
+    #[arg(long = "experiments-dir", default_value = DEFAULT_ROOT)]
+    experiments_dir: PathBuf,
+
     /// Player bot specs: e.g., heuristic random (2-6 total)
     bots: Vec<String>,
+
+    /// List the bot specs and exit
+    #[arg(long = "list-bots", exclusive = true, action = ArgAction::SetTrue)]
+    list_bots: bool,
 }
 
 fn main() {
@@ -84,244 +194,288 @@ fn main() {
     }
 }
 
-fn run(args: Args) -> Result<(), Box<dyn Error>> {
-    if args.bots.is_empty() {
-        return Err("please provide between 2 and 6 bot specs (e.g., heuristic random)".into());
+fn run(mut args: Args) -> Result<(), Box<dyn Error>> {
+    if args.list_bots {
+        for spec in available_specs() {
+            println!("{spec}");
+        }
+        return Ok(());
     }
-    if args.bots.len() < 2 || args.bots.len() > 6 {
-        return Err(format!(
-            "expected between 2 and 6 players, received {}",
-            args.bots.len()
-        )
-        .into());
+    if let Some(name) = args.experiment.clone() {
+        let config = match_config(&args)?;
+        let seeds = vec![config.seed];
+        let experiment = Experiment::open(
+            &args.experiments_dir,
+            ExperimentConfig::new(&name, config.bots.clone(), seeds).with_settings(&config)?,
+        )?;
+        println!("Experiment {}\n", experiment.dir().display());
+        args.report = Some(experiment.new_artifact(Artifact::Report, "report.json")?);
+        args.archive = Some(experiment.new_artifact(Artifact::Replay, "games.skba")?);
+        args.out = experiment.path(Artifact::Chart, "winrates.png");
     }
 
-    // Disallow human in batch sims; it would block waiting for input.
-    if args
-        .bots
-        .iter()
-        .any(|s| s.to_ascii_lowercase().starts_with("human"))
-    {
-        return Err("human players are not supported in winrate runs".into());
+    let report = if let Some(path) = &args.from_report {
+        MatchReport::load(path)?
+    } else {
+        simulate(&args)?
+    };
+
+    if let Some(path) = &args.report {
+        report.save(path)?;
+        println!("Match report written to {}\n", path.display());
     }
 
-    if let Some(stock) = args.stock_size {
-        if stock == 0 {
-            return Err("stock-size must be positive".into());
+    print_summary(&report, &args);
+    print_behavior(&report);
+    print_deck(&report);
+
+    if !args.no_chart {
+        let format = args
+            .format
+            .or_else(|| ChartFormat::from_path(&args.out))
+            .unwrap_or(ChartFormat::Png);
+        if !matches!(format, ChartFormat::Png) {
+            return Err("only PNG output is supported currently; use --out with .png".into());
         }
+        render_bar_chart(&args.out, &report)?;
+        println!("\nChart written to {}", args.out.display());
     }
 
-    // Aggregate counts across all games.
-    let mut wins_per_label: HashMap<String, usize> = HashMap::new();
-    let mut seats_per_label: HashMap<String, usize> = HashMap::new();
-    let mut points_per_label: HashMap<String, u64> = HashMap::new();
-    let mut aborted_games: usize = 0;
-
-    // Decision-time accounting per bot label.
-    // Accumulates total nanoseconds spent inside select_action and counts of decisions.
-    let mut decision_time_ns: HashMap<String, u128> = HashMap::new();
-    let mut decision_counts: HashMap<String, usize> = HashMap::new();
-
-    let base_seed = args.seed;
-    let players_per_game = args.bots.len();
-
-    // Precompute labels for specs to avoid recomputing.
-    let labels_for_spec: Vec<String> = args.bots.iter().map(|s| label_for_spec(s)).collect();
-
-    for game_idx in 0..args.games {
-        // Permute seating each game for fairness.
-        let mut indices: Vec<usize> = (0..players_per_game).collect();
-        let mut seat_rng = StdRng::seed_from_u64(base_seed ^ 0x9E37_79B9 ^ (game_idx as u64));
-        indices.shuffle(&mut seat_rng);
-
-        // Create game with mixed seed.
-        let deck_seed = mix_seed(base_seed, game_idx as u64, 0x5EED_15);
-        let mut builder = Game::builder(players_per_game)?.with_seed(deck_seed);
-        if let Some(stock) = args.stock_size {
-            builder = builder.with_stock_size(stock);
-        }
-        let mut game = builder.build()?;
-
-        // Build and seat bots.
-        let mut bots: Vec<Box<dyn Bot>> = Vec::with_capacity(players_per_game);
-        let mut labels: Vec<String> = Vec::with_capacity(players_per_game);
-        for (seat, src_idx) in indices.iter().enumerate() {
-            let spec = &args.bots[*src_idx];
-            let label = labels_for_spec[*src_idx].clone();
-            let bot_seed = mix_seed(base_seed, game_idx as u64, seat as u64);
-            let bot = create_bot(spec, seat, bot_seed)?;
-            bots.push(bot);
-            labels.push(label);
-        }
+    print_timing(&report);
+    check_gates(&report, &args)
+}
 
-        // Increment seat counts per label for this game.
-        for label in &labels {
-            *seats_per_label.entry(label.clone()).or_default() += 1;
-        }
+fn parse_gate(s: &str) -> Result<(String, f64), String> {
+    let (label, value) = s
+        .rsplit_once('=')
+        .ok_or_else(|| format!("expected LABEL=VALUE, got {s}"))?;
+    let value = value
+        .parse::<f64>()
+        .map_err(|_| format!("invalid composite threshold: {value}"))?;
+    Ok((label.to_string(), value))
+}
 
-        // Run the game to completion (or until max turn cap).
-        let mut turns = 0usize;
-        loop {
-            if game.is_finished() {
-                break;
-            }
-            if turns >= args.max_turns {
-                break;
-            }
-            let current = game.current_player();
-            let state = game.state_view(current)?;
-            let legal = game.legal_actions(current)?;
-            if legal.is_empty() {
-                return Err(GameError::InvalidConfiguration("no legal actions available").into());
-            }
-            // Time the bot's decision for this move.
-            let label_for_current = labels[current].clone();
-            let t0 = Instant::now();
-            let action = bots[current].select_action(&state, &legal);
-            let dt = t0.elapsed();
-            *decision_time_ns
-                .entry(label_for_current.clone())
-                .or_default() += dt.as_nanos();
-            *decision_counts.entry(label_for_current).or_default() += 1;
-            game.apply_action(current, action)?;
-            turns += 1;
+/// Regression gate: every `--min-composite` bot must reach its threshold.
+fn check_gates(report: &MatchReport, args: &Args) -> Result<(), Box<dyn Error>> {
+    for (label, min) in &args.min_composite {
+        let bot = report
+            .bots
+            .iter()
+            .find(|bot| &bot.label == label)
+            .ok_or_else(|| format!("--min-composite: no bot labelled {label} in this run"))?;
+        let score = report.composite_score(bot, &args.weights);
+        if score < *min {
+            return Err(
+                format!("{label} composite {score:.4} is below the required {min:.4}").into(),
+            );
         }
+    }
+    Ok(())
+}
 
-        if let Some(winner) = game.winner() {
-            let label = labels[winner].clone();
-            *wins_per_label.entry(label.clone()).or_default() += 1;
-
-            // Compute scoring: 25 points + 5 points per card in opponents' stock piles
-            // Use a final state view from the winner's perspective.
-            let view = game.state_view(winner)?;
-            let pts = winner_points(&view, winner) as u64;
-            *points_per_label.entry(label).or_default() += pts;
-        } else {
-            aborted_games += 1;
-        }
+fn match_config(args: &Args) -> Result<MatchConfig, Box<dyn Error>> {
+    let bots = match &args.bots_config {
+        Some(path) => BotsConfig::load(path)?.specs(),
+        None => args.bots.clone(),
+    };
+    if bots.is_empty() {
+        return Err("please provide between 2 and 6 bot specs (e.g., heuristic random)".into());
     }
 
-    // Compute per-seat win probability per label.
-    let mut results: Vec<(String, f64, usize, usize)> = Vec::new();
-    for (label, &wins) in &wins_per_label {
-        let seats = *seats_per_label.get(label).unwrap_or(&0);
-        let rate = if seats > 0 {
-            wins as f64 / seats as f64
-        } else {
-            0.0
-        };
-        results.push((label.clone(), rate, wins, seats));
+    // Disallow human in batch sims; it would block waiting for input.
+    if bots
+        .iter()
+        .any(|s| s.to_ascii_lowercase().starts_with("human"))
+    {
+        return Err("human players are not supported in winrate runs".into());
     }
 
-    // Include labels that never won so they appear with 0%.
-    for (label, &seats) in &seats_per_label {
-        if !wins_per_label.contains_key(label) {
-            results.push((label.clone(), 0.0, 0, seats));
-        }
+    let mut config = MatchConfig::new(bots, args.games, args.seed);
+    config.max_turns = args.max_turns;
+    config.stock_size = args.stock_size;
+    config.must_play_stock = args.must_play_stock;
+    config.stock_visibility = args.stock_visibility;
+    config.seed_split = args.seed_split;
+    config.move_time_ms = args.move_time;
+    config.time_limit_ms = args.time_limit;
+    config.on_fault = args.on_fault;
+    config.on_panic = args.on_panic;
+    for &(index, handicap) in &args.handicaps {
+        config.add_handicap(index, handicap);
     }
+    Ok(config)
+}
 
-    // Sort by rate desc, then by label.
-    results.sort_by(|a, b| {
-        b.1.partial_cmp(&a.1)
-            .unwrap_or(std::cmp::Ordering::Equal)
-            .then_with(|| a.0.cmp(&b.0))
-    });
+/// Play the batch. Ctrl-C stops it cleanly: archives keep the finished games, resumable
+/// runs keep their checkpoint, and plain runs report the games finished so far.
+fn simulate(args: &Args) -> Result<MatchReport, Box<dyn Error>> {
+    let mut runner = MatchRunner::new(match_config(args)?)?.with_interrupter(Interrupter::ctrl_c());
+    if let Some(trigger) = args.reload_weights {
+        runner = runner.with_weight_reloader(WeightReloader::sighup(trigger));
+    }
+    if let Some(path) = &args.archive {
+        let compression = match args.archive_zstd {
+            0 => Compression::None,
+            level => Compression::Zstd(level),
+        };
+        let mut archive = ArchiveWriter::create(path, compression)?;
+        let report = runner.run_archived(&mut archive);
+        archive.finish()?;
+        return report;
+    }
+    if let Some(path) = &args.resume_state {
+        return runner
+            .run_resumable(path, args.checkpoint_every)
+            .map_err(|err| match err.downcast::<Interrupted>() {
+                Ok(_) => format!(
+                    "interrupted; progress is saved in {}, rerun to resume",
+                    path.display()
+                )
+                .into(),
+                Err(err) => err,
+            });
+    }
+    let mut state = RunState::new(runner.config().clone());
+    if let Err(err) = runner.advance(&mut state, args.games) {
+        if !err.is::<Interrupted>() {
+            return Err(err);
+        }
+        eprintln!(
+            "Interrupted after {} of {} games; reporting the finished ones.\n",
+            state.games.len(),
+            args.games
+        );
+    }
+    Ok(state.into_report())
+}
 
-    // Print textual summary.
+fn print_summary(report: &MatchReport, args: &Args) {
+    let mut ranked = report.ranked_by(&args.weights);
+    if matches!(args.sort_by, SortBy::WinRate) {
+        // `report.bots` is already ordered by win rate.
+        ranked.sort_by_key(|(bot, _)| {
+            report
+                .bots
+                .iter()
+                .position(|b| b.label == bot.label)
+                .unwrap_or(usize::MAX)
+        });
+    }
     println!("Win rates (per-seat) with scoring:");
-    for (label, rate, wins, seats) in &results {
-        let total_points = *points_per_label.get(label).unwrap_or(&0u64);
-        let avg_points = if *seats > 0 {
-            total_points as f64 / (*seats as f64)
-        } else {
-            0.0
-        };
+    for (bot, composite) in ranked {
+        println!(
+            "  {:<12}  {}/{}  ({:.2}%)   avg pts: {:>6.2}   total pts: {}",
+            bot.label,
+            bot.wins,
+            bot.seats,
+            bot.win_rate() * 100.0,
+            bot.avg_points(),
+            bot.total_points
+        );
         println!(
-            "  {label:<12}  {wins}/{seats}  ({:.2}%)   avg pts: {:>6.2}   total pts: {}",
-            rate * 100.0,
-            avg_points,
-            total_points
+            "  {:<12}  margin: {:>+6.2}   turns to win: {:>6.1} ({:.1} actions)   stock left on loss: {:>5.2}   composite: {composite:.4}",
+            "",
+            bot.avg_margin(),
+            bot.avg_turns_to_win(),
+            bot.avg_actions_to_win(),
+            bot.avg_stock_left_when_losing()
         );
     }
-    if aborted_games > 0 {
-        println!("\nNote: {aborted_games} game(s) ended without a winner (draws or timeouts).");
+    if report.aborted_games > 0 {
+        println!(
+            "\nNote: {} game(s) ended without a winner (draws or timeouts).",
+            report.aborted_games
+        );
     }
-
-    if !args.no_chart {
-        let format = args
-            .format
-            .or_else(|| ChartFormat::from_path(&args.out))
-            .unwrap_or(ChartFormat::Png);
-        if !matches!(format, ChartFormat::Png) {
-            return Err("only PNG output is supported currently; use --out with .png".into());
+    if report.bots.iter().any(|bot| bot.faults > 0) {
+        println!("\nFaults:");
+        for bot in report.bots.iter().filter(|bot| bot.faults > 0) {
+            println!(
+                "  {:<12}  {} fault(s), {} panic(s), {} forfeit(s)",
+                bot.label, bot.faults, bot.panics, bot.forfeits
+            );
         }
-        render_bar_chart(&args.out, &results)?;
-        println!("\nChart written to {}", args.out.display());
-    }
-
-    // Print timing summary (per bot label)
-    if !decision_counts.is_empty() {
-        println!("\nDecision time (per bot label):");
-        // Follow the same ordering as win-rate results when possible; otherwise fall back to map order.
-        let mut printed: HashMap<String, bool> = HashMap::new();
-        for (label, _rate, _wins, _seats) in &results {
-            if let Some(&count) = decision_counts.get(label) {
-                let total_ns = *decision_time_ns.get(label).unwrap_or(&0u128);
-                let total_ms = (total_ns as f64) / 1.0e6;
-                let avg_ms = if count > 0 {
-                    total_ms / (count as f64)
-                } else {
-                    0.0
-                };
-                println!(
-                    "  {label:<12}  decisions: {count:<7}  total: {total_ms:.3} ms  avg: {avg_ms:.3} ms"
-                );
-                printed.insert(label.clone(), true);
-            }
+        let faults = report
+            .games
+            .iter()
+            .flat_map(|game| game.faults.iter().map(move |fault| (game, fault)));
+        for (game, fault) in faults.take(MAX_LISTED_FAULTS) {
+            println!(
+                "  game {} {}: {fault}",
+                game.game_index, game.seats[fault.seat]
+            );
         }
-        // Print any labels that didn't appear in results (shouldn't happen, but safe).
-        for (label, &count) in &decision_counts {
-            if printed.get(label).copied().unwrap_or(false) {
-                continue;
-            }
-            let total_ns = *decision_time_ns.get(label).unwrap_or(&0u128);
-            let total_ms = (total_ns as f64) / 1.0e6;
-            let avg_ms = if count > 0 {
-                total_ms / (count as f64)
-            } else {
-                0.0
-            };
+    }
+    let reloads: Vec<_> = report
+        .games
+        .iter()
+        .filter(|game| !game.reloaded.is_empty())
+        .collect();
+    if !reloads.is_empty() {
+        println!("\nWeights reloaded:");
+        for game in reloads {
             println!(
-                "  {label:<12}  decisions: {count:<7}  total: {total_ms:.3} ms  avg: {avg_ms:.3} ms"
+                "  before game {}: {}",
+                game.game_index,
+                game.reloaded.join(", ")
             );
         }
     }
+}
 
-    Ok(())
+fn print_behavior(report: &MatchReport) {
+    println!("\nBehavior fingerprints:");
+    for bot in &report.bots {
+        let fp = &bot.fingerprint;
+        println!(
+            "  {:<12}  stock aggressiveness: {:>5.1}%   discard entropy: {:.2}   blocking: {:>5.1}%   plays/turn: {:.2}",
+            bot.label,
+            fp.stock_aggressiveness * 100.0,
+            fp.discard_entropy,
+            fp.blocking_frequency * 100.0,
+            fp.plays_per_turn
+        );
+    }
 }
 
-fn mix_seed(base: u64, a: u64, b: u64) -> u64 {
-    // Simple reversible mixer (xorshift-like mix).
-    let mut z =
-        base ^ (a.wrapping_mul(0x9E37_79B97F4A7C15)) ^ (b.wrapping_mul(0xBF58_476D1CE4E5B9));
-    z ^= z >> 12;
-    z ^= z << 25;
-    z ^= z >> 27;
-    z
+fn print_deck(report: &MatchReport) {
+    let deck = report.deck_stats();
+    // Reports written before deck statistics existed carry none.
+    if deck.turns == 0 {
+        return;
+    }
+    let games = report.games.len().max(1) as f64;
+    println!(
+        "\nDeck: {:.2} reshuffles/game ({:.1} cards each), draw pile {:.1} cards on average at turn start (min {}), {} starved refills ({} cards short)",
+        deck.reshuffles as f64 / games,
+        deck.reshuffled_cards as f64 / deck.reshuffles.max(1) as f64,
+        deck.avg_draw_pile(),
+        deck.draw_pile_min,
+        deck.starved_refills,
+        deck.starved_cards
+    );
 }
 
-fn create_bot(spec: &str, index: usize, seed: u64) -> Result<Box<dyn Bot>, Box<dyn Error>> {
-    // Centralized creation ensures parity with simulate CLI.
-    create_bot_from_spec(spec, index, seed)
+fn print_timing(report: &MatchReport) {
+    if report.bots.iter().all(|bot| bot.decisions == 0) {
+        return;
+    }
+    println!("\nDecision time (per bot label):");
+    for bot in report.bots.iter().filter(|bot| bot.decisions > 0) {
+        let total_ms = bot.decision_time_ns as f64 / 1.0e6;
+        println!(
+            "  {:<12}  decisions: {:<7}  total: {total_ms:.3} ms  avg: {:.3} ms",
+            bot.label,
+            bot.decisions,
+            bot.avg_decision_ms()
+        );
+    }
 }
 
-fn render_bar_chart(
-    out: &PathBuf,
-    data: &[(String, f64, usize, usize)],
-) -> Result<(), Box<dyn Error>> {
+fn render_bar_chart(out: &PathBuf, report: &MatchReport) -> Result<(), Box<dyn Error>> {
     // Prepare values and labels
-    let labels: Vec<String> = data.iter().map(|(l, _, _, _)| l.clone()).collect();
-    let values: Vec<f64> = data.iter().map(|(_, r, _, _)| r * 100.0).collect();
+    let labels: Vec<String> = report.bots.iter().map(|b| b.label.clone()).collect();
+    let values: Vec<f64> = report.bots.iter().map(|b| b.win_rate() * 100.0).collect();
     let max_value = values
         .iter()
         .cloned()
@@ -356,7 +510,7 @@ fn render_bar_chart(
             }
         })
         .y_label_formatter(&|v| format!("{v:.0}"))
-        .light_line_style(&WHITE.mix(0.0))
+        .light_line_style(WHITE.mix(0.0))
         .draw()
         .map_err(|e| format!("{e}"))?;
 
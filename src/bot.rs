@@ -1,7 +1,125 @@
-use crate::action::Action;
-use crate::state::GameStateView;
+use crate::action::{Action, PlayerId};
+use crate::interrupt::Interrupter;
+use crate::state::{FullStateView, GameStateView};
 
 /// Interface for defining custom Skip-Bo bots.
-pub trait Bot {
+///
+/// Only [`Bot::select_action`] is required. The lifecycle hooks default to doing nothing;
+/// stateful bots use them to reset caches, follow opponents across turns or learn during
+/// a match. Match drivers call them in order: `on_game_start` once per seat, then
+/// `on_turn_start` before the first decision of each of the bot's turns, and
+/// `on_game_end` once when the game finishes or is cut off.
+///
+/// Bots are `Send` so a collector can move each game, bots included, to a worker thread.
+pub trait Bot: Send {
+    /// The game is about to start; `state` is the bot's own view of the deal.
+    fn on_game_start(&mut self, _state: &GameStateView) {}
+
+    /// One of the bot's turns begins; `state` is the view of its first decision.
+    fn on_turn_start(&mut self, _state: &GameStateView) {}
+
     fn select_action(&mut self, state: &GameStateView, legal_actions: &[Action]) -> Action;
+
+    /// Every legal action with a preference score, best first. The default ranks the
+    /// action [`Bot::select_action`] picks first with score 1.0, followed by the other
+    /// legal actions in their given order with score 0.0. Like `select_action`, this counts
+    /// as the bot's decision for the view.
+    fn rank_actions(
+        &mut self,
+        state: &GameStateView,
+        legal_actions: &[Action],
+    ) -> Vec<(Action, f32)> {
+        let chosen = self.select_action(state, legal_actions);
+        let mut ranked = Vec::with_capacity(legal_actions.len());
+        ranked.push((chosen.clone(), 1.0));
+        ranked.extend(
+            legal_actions
+                .iter()
+                .filter(|action| **action != chosen)
+                .map(|action| (action.clone(), 0.0)),
+        );
+        ranked
+    }
+
+    /// Cap search effort at roughly `nodes` simulated positions per decision, e.g. as a
+    /// handicap. Bots that do not search ignore it.
+    fn limit_search(&mut self, _nodes: usize) {}
+
+    /// The [`Interrupter`] for the coming decision, given right before `select_action`.
+    /// Search bots poll it and, once it fires, settle for the best action found so far.
+    /// Bots that do not search ignore it.
+    fn interrupt_with(&mut self, _interrupter: &Interrupter) {}
+
+    /// Whether the bot cheats by looking at hidden cards. Drivers then pass it the
+    /// [`FullStateView`] before each of its decisions. Only for upper-bound baselines.
+    fn wants_full_state(&self) -> bool {
+        false
+    }
+
+    /// Every hidden card, given right before `select_action` to bots that
+    /// [want it](Bot::wants_full_state).
+    fn observe_full_state(&mut self, _state: &FullStateView) {}
+
+    /// The game is over. `winner` is `None` for draws and games stopped at the turn cap.
+    fn on_game_end(&mut self, _state: &GameStateView, _winner: Option<PlayerId>) {}
+}
+
+/// Ranking for [`Bot::rank_actions`] from per-action scores: `chosen` first, then the rest
+/// by descending score (stable, so ties keep their given order). A `chosen` action missing
+/// from `scored` is ranked first with the top score.
+pub(crate) fn rank_scored(chosen: Action, mut scored: Vec<(Action, f32)>) -> Vec<(Action, f32)> {
+    scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+    match scored.iter().position(|(action, _)| *action == chosen) {
+        Some(index) => {
+            let entry = scored.remove(index);
+            scored.insert(0, entry);
+        }
+        None => {
+            let top = scored.first().map_or(1.0, |(_, score)| *score);
+            scored.insert(0, (chosen, top));
+        }
+    }
+    scored
+}
+
+impl<B: Bot + ?Sized> Bot for Box<B> {
+    fn on_game_start(&mut self, state: &GameStateView) {
+        (**self).on_game_start(state);
+    }
+
+    fn on_turn_start(&mut self, state: &GameStateView) {
+        (**self).on_turn_start(state);
+    }
+
+    fn select_action(&mut self, state: &GameStateView, legal_actions: &[Action]) -> Action {
+        (**self).select_action(state, legal_actions)
+    }
+
+    fn rank_actions(
+        &mut self,
+        state: &GameStateView,
+        legal_actions: &[Action],
+    ) -> Vec<(Action, f32)> {
+        (**self).rank_actions(state, legal_actions)
+    }
+
+    fn limit_search(&mut self, nodes: usize) {
+        (**self).limit_search(nodes);
+    }
+
+    fn interrupt_with(&mut self, interrupter: &Interrupter) {
+        (**self).interrupt_with(interrupter);
+    }
+
+    fn wants_full_state(&self) -> bool {
+        (**self).wants_full_state()
+    }
+
+    fn observe_full_state(&mut self, state: &FullStateView) {
+        (**self).observe_full_state(state);
+    }
+
+    fn on_game_end(&mut self, state: &GameStateView, winner: Option<PlayerId>) {
+        (**self).on_game_end(state, winner);
+    }
 }
@@ -0,0 +1,340 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use rand::Rng;
+use rand::seq::SliceRandom;
+
+use crate::action::{Action, DiscardPileId, PlayerId};
+use crate::bot::{Bot, rank_scored};
+use crate::bots::endgame::{EndgameSolution, EndgameSolver};
+use crate::bots::eval::{EvalFn, Evaluator};
+use crate::bots::planning::{TurnPosition, next_build_value, unseen_cards};
+use crate::bots::search::SharedTable;
+use crate::bots::strength::Strength;
+use crate::card::Card;
+use crate::interrupt::Interrupter;
+use crate::state::GameStateView;
+
+/// A line explored by [`BeamSearchBot`].
+#[derive(Clone, Debug)]
+pub struct SearchNode {
+    pub position: TurnPosition,
+    /// First action of the line; this is what the bot ends up playing.
+    pub first: Option<Action>,
+    /// Actions taken along the line.
+    pub depth: usize,
+    /// Whether the line ended the turn (discard or end turn).
+    pub finished: bool,
+    /// Opponents whose stock card became playable in the modelled response.
+    pub opponent_unlocks: usize,
+    /// Cursor into the determinized draw sequence.
+    draw_cursor: usize,
+}
+
+/// Beam-search planner sitting between the heuristics and full tree search.
+///
+/// For each decision the bot samples `samples` determinizations of the hidden cards
+/// (uniformly from the cards it cannot see). In every determinization it runs a beam
+/// search of `width` lines for up to `depth` actions, revealing stock cards and hand
+/// refills from the sampled sequence. Once a line ends the turn, opponents respond with
+/// a simple model: each plays their stock top if it fits a build pile. Lines are scored
+/// with a configurable [`EvalFn`] ([`Evaluator::Balanced`] by default), duplicate
+/// positions within a layer are pruned via their hash, and the first action with the best
+/// average score is played. Near the end of the stock the exact [`EndgameSolver`] is
+/// consulted first. Below full [`Strength`] the beam is narrower and the move is sampled
+/// from the averaged scores. Once the decision's [`Interrupter`] fires, the bot stops
+/// sampling and deepening and plays the best action of what it searched (always at least
+/// one layer of one sample).
+pub struct BeamSearchBot<R: Rng> {
+    rng: R,
+    pub width: usize,
+    pub depth: usize,
+    pub samples: usize,
+    evaluation: Arc<dyn EvalFn>,
+    endgame: EndgameSolver,
+    strength: Strength,
+    interrupter: Interrupter,
+}
+
+impl<R: Rng> BeamSearchBot<R> {
+    pub const DEFAULT_WIDTH: usize = 8;
+    pub const DEFAULT_DEPTH: usize = 12;
+    pub const DEFAULT_SAMPLES: usize = 4;
+
+    pub fn new(rng: R, width: usize, depth: usize) -> Self {
+        Self {
+            rng,
+            width: width.max(1),
+            depth: depth.max(1),
+            samples: Self::DEFAULT_SAMPLES,
+            evaluation: Arc::new(Evaluator::Balanced),
+            endgame: EndgameSolver::default(),
+            strength: Strength::MAX,
+            interrupter: Interrupter::new(),
+        }
+    }
+
+    /// Score difference treated as a clear preference when sampling moves.
+    const SCORE_SCALE: f64 = 100.0;
+
+    pub fn with_strength(mut self, strength: Strength) -> Self {
+        self.width = strength.node_budget(self.width);
+        self.endgame.node_limit = strength.node_budget(self.endgame.node_limit);
+        self.strength = strength;
+        self
+    }
+
+    pub fn with_samples(mut self, samples: usize) -> Self {
+        self.samples = samples.max(1);
+        self
+    }
+
+    pub fn with_evaluation(mut self, evaluation: impl EvalFn + 'static) -> Self {
+        self.evaluation = Arc::new(evaluation);
+        self
+    }
+
+    /// Use an evaluator shared with other bots.
+    pub fn with_shared_evaluation(mut self, evaluation: Arc<dyn EvalFn>) -> Self {
+        self.evaluation = evaluation;
+        self
+    }
+
+    /// Keep solved endgames in `table`, e.g. one shared with other bots.
+    pub fn with_endgame_table(mut self, table: SharedTable<Option<EndgameSolution>>) -> Self {
+        self.endgame = self.endgame.with_table(table);
+        self
+    }
+
+    /// Reveal hidden cards from the determinized sequence where the engine would.
+    fn reveal(node: &mut SearchNode, draws: &[Card], hand_size: usize) {
+        let position = &mut node.position;
+        if position.stock_top.is_none()
+            && position.stock_count > 0
+            && node.depth > 0
+            && let Some(card) = draws.get(node.draw_cursor)
+        {
+            position.stock_top = Some(*card);
+            node.draw_cursor += 1;
+        }
+        if position.hand.is_empty() && !node.finished {
+            while position.hand.len() < hand_size {
+                let Some(card) = draws.get(node.draw_cursor) else {
+                    break;
+                };
+                position.hand.push(*card);
+                node.draw_cursor += 1;
+            }
+        }
+    }
+
+    /// Model opponents' immediate stock plays after the line ends the turn.
+    fn opponent_response(state: &GameStateView, node: &mut SearchNode) {
+        let n = state.settings.num_players;
+        for offset in 1..n {
+            let id: PlayerId = state.self_player.offset(offset, n);
+            let Some(player) = state.players.iter().find(|p| p.id == id) else {
+                continue;
+            };
+            let Some(top) = player.stock_top else {
+                continue;
+            };
+            let target = node
+                .position
+                .build_next
+                .iter()
+                .position(|&next| top.matches_value(next));
+            if let Some(pile) = target {
+                node.opponent_unlocks += 1;
+                node.position.build_next[pile] = next_build_value(node.position.build_next[pile]);
+            }
+        }
+    }
+
+    /// Evaluate a line as if the turn ended here and opponents responded.
+    fn score(&self, state: &GameStateView, node: &SearchNode) -> f32 {
+        if node.position.stock_count == 0 {
+            return self
+                .evaluation
+                .evaluate(state, &node.position, node.opponent_unlocks);
+        }
+        let mut ended = node.clone();
+        Self::opponent_response(state, &mut ended);
+        self.evaluation
+            .evaluate(state, &ended.position, ended.opponent_unlocks)
+    }
+
+    fn children(node: &SearchNode) -> Vec<SearchNode> {
+        let position = &node.position;
+        let mut actions = position.plays();
+        if position.hand.is_empty() {
+            actions.push(Action::EndTurn);
+        } else {
+            let mut seen: Vec<Card> = Vec::new();
+            for (hand_index, card) in position.hand.iter().enumerate() {
+                if seen.contains(card) {
+                    continue;
+                }
+                seen.push(*card);
+                for discard_pile in DiscardPileId::ALL {
+                    actions.push(Action::Discard {
+                        hand_index,
+                        discard_pile,
+                    });
+                }
+            }
+        }
+        let mut out = Vec::with_capacity(actions.len());
+        for action in actions {
+            let mut child = node.clone();
+            if !child.position.apply(&action) {
+                continue;
+            }
+            child.finished = !matches!(action, Action::Play { .. });
+            child.first.get_or_insert(action);
+            child.depth += 1;
+            out.push(child);
+        }
+        out
+    }
+
+    /// Run one beam search on a determinization; returns best score per first action.
+    fn search(&self, state: &GameStateView, draws: &[Card]) -> HashMap<Action, f32> {
+        let mut best: HashMap<Action, f32> = HashMap::new();
+        let Some(position) = TurnPosition::from_view(state) else {
+            return best;
+        };
+        let mut beam = vec![SearchNode {
+            position,
+            first: None,
+            depth: 0,
+            finished: false,
+            opponent_unlocks: 0,
+            draw_cursor: 0,
+        }];
+        let mut record = |state: &GameStateView, node: &SearchNode| {
+            if let Some(first) = &node.first {
+                let score = self.score(state, node);
+                let entry = best.entry(first.clone()).or_insert(f32::MIN);
+                if score > *entry {
+                    *entry = score;
+                }
+            }
+        };
+        for layer_depth in 0..self.depth {
+            if layer_depth > 0 && self.interrupter.should_stop() {
+                break;
+            }
+            let mut layer: Vec<(f32, SearchNode)> = Vec::new();
+            let mut seen: HashSet<(TurnPosition, Option<Action>)> = HashSet::new();
+            for node in &beam {
+                for mut child in Self::children(node) {
+                    if child.finished || child.position.stock_count == 0 {
+                        record(state, &child);
+                        continue;
+                    }
+                    Self::reveal(&mut child, draws, state.settings.hand_size);
+                    // Transposition pruning: identical positions reached through the same
+                    // first action are interchangeable.
+                    if !seen.insert((child.position.clone(), child.first.clone())) {
+                        continue;
+                    }
+                    layer.push((self.score(state, &child), child));
+                }
+            }
+            if layer.is_empty() {
+                break;
+            }
+            layer.sort_by(|a, b| b.0.total_cmp(&a.0));
+            layer.truncate(self.width);
+            beam = layer.into_iter().map(|(_, node)| node).collect();
+        }
+        for node in &beam {
+            record(state, node);
+        }
+        best
+    }
+}
+
+impl<R: Rng + Send> Bot for BeamSearchBot<R> {
+    fn on_turn_start(&mut self, _state: &GameStateView) {
+        self.endgame.new_search();
+    }
+
+    fn limit_search(&mut self, nodes: usize) {
+        // Each sample expands up to `width` lines per layer.
+        let per_layer = nodes / (self.samples * self.depth);
+        self.width = self.width.min(per_layer.max(1));
+        self.endgame.node_limit = nodes;
+    }
+
+    fn interrupt_with(&mut self, interrupter: &Interrupter) {
+        self.interrupter = interrupter.clone();
+        self.endgame.interrupter = interrupter.clone();
+    }
+
+    fn select_action(&mut self, state: &GameStateView, legal_actions: &[Action]) -> Action {
+        self.rank_actions(state, legal_actions).swap_remove(0).0
+    }
+
+    /// Legal actions by their averaged search score, after the pick. Forced moves and
+    /// endgame wins rank first with score 1.0 and the rest 0.0; actions the search never
+    /// reached come last.
+    fn rank_actions(
+        &mut self,
+        state: &GameStateView,
+        legal_actions: &[Action],
+    ) -> Vec<(Action, f32)> {
+        assert!(
+            !legal_actions.is_empty(),
+            "beam search bot requires at least one legal action"
+        );
+        let forced = if legal_actions.len() == 1 {
+            Some(legal_actions[0].clone())
+        } else {
+            self.endgame.recommend(state, legal_actions)
+        };
+        if let Some(action) = forced {
+            let others = legal_actions
+                .iter()
+                .filter(|a| **a != action)
+                .map(|a| (a.clone(), 0.0))
+                .collect();
+            return rank_scored(action, others);
+        }
+        let unseen = unseen_cards(state);
+        let mut totals: HashMap<Action, (f32, usize)> = HashMap::new();
+        for sample in 0..self.samples {
+            if sample > 0 && self.interrupter.should_stop() {
+                break;
+            }
+            let mut draws = unseen.clone();
+            draws.shuffle(&mut self.rng);
+            for (action, score) in self.search(state, &draws) {
+                let entry = totals.entry(action).or_insert((0.0, 0));
+                entry.0 += score;
+                entry.1 += 1;
+            }
+        }
+        let mut scored: Vec<(Action, f32)> = legal_actions
+            .iter()
+            .filter_map(|action| {
+                totals
+                    .get(action)
+                    .map(|(sum, count)| (action.clone(), sum / *count as f32))
+            })
+            .collect();
+        let scores: Vec<f64> = scored.iter().map(|(_, s)| f64::from(*s)).collect();
+        let chosen = self
+            .strength
+            .choose(&mut self.rng, &scores, Self::SCORE_SCALE)
+            .map(|pick| scored[pick].0.clone())
+            .unwrap_or_else(|| legal_actions[0].clone());
+        for action in legal_actions {
+            if !totals.contains_key(action) {
+                scored.push((action.clone(), f32::NEG_INFINITY));
+            }
+        }
+        rank_scored(chosen, scored)
+    }
+}
@@ -0,0 +1,329 @@
+//! Block analysis: how likely the build piles a player leaves behind let opponents play
+//! their stock card before the player's next turn.
+//!
+//! Every opponent is weighed, not just the next one: closer seats act sooner (and see
+//! the piles before anyone else changes them) and opponents with few stock cards left are
+//! closer to winning. Whether a stock card becomes playable follows the rules: a pile
+//! takes the value it requires (or a Skip-Bo), wraps to 1 after 12, and an opponent may
+//! first play visible discard cards, top down, to bring a pile to their stock value.
+//! Opponents' hands are unknown and not counted as helpers by [`BlockAnalysis`];
+//! [`DiscardSafety`] samples them instead.
+
+use std::collections::HashMap;
+
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+
+use crate::action::{Action, PlayerId};
+use crate::bots::planning::{TurnPosition, greedy_chain, next_build_value, unseen_cards};
+use crate::card::{BUILD_PILE_COUNT, Card};
+use crate::state::{GameStateView, PlayerPublicState};
+
+/// Default number of discard plays an opponent may make before their stock card, used by
+/// [`BlockAnalysis::default`].
+pub const DEFAULT_HELPER_DEPTH: usize = 2;
+
+/// Stock count at or below which an opponent's threat counts double.
+pub const URGENT_STOCK_COUNT: usize = 5;
+
+/// Default number of sampled rounds per [`DiscardSafety`] estimate.
+pub const DEFAULT_SAFETY_SAMPLES: usize = 32;
+
+/// One opponent's chance to play their stock card on the analyzed build piles.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OpponentThreat {
+    pub player: PlayerId,
+    /// Turn-order distance from the analyzing player (1 = acts next).
+    pub seats_after: usize,
+    pub stock_count: usize,
+    /// Discard plays the opponent needs before the stock card fits, or `None` when it
+    /// cannot be reached within the analysis depth.
+    pub helpers: Option<usize>,
+    /// Proximity and urgency weight: 1 for the next player with more than
+    /// [`URGENT_STOCK_COUNT`] stock cards, divided by the seat distance and doubled
+    /// when the stock is nearly empty.
+    pub weight: f64,
+}
+
+impl OpponentThreat {
+    /// Weighted risk: the full weight when the stock card fits outright, less for each
+    /// discard play it needs first.
+    pub fn risk(&self) -> f64 {
+        self.helpers
+            .map_or(0.0, |helpers| self.weight / (1 + helpers) as f64)
+    }
+}
+
+/// Threats of every opponent that still has a visible stock card.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BlockRisk {
+    pub threats: Vec<OpponentThreat>,
+}
+
+impl BlockRisk {
+    /// Summed risk over all opponents.
+    pub fn score(&self) -> f64 {
+        self.threats.iter().map(OpponentThreat::risk).sum()
+    }
+}
+
+/// Block risk of build piles as seen from the acting player.
+#[derive(Clone, Copy, Debug)]
+pub struct BlockAnalysis {
+    /// Discard plays an opponent may chain before their stock card.
+    pub helper_depth: usize,
+}
+
+impl Default for BlockAnalysis {
+    fn default() -> Self {
+        Self::new(DEFAULT_HELPER_DEPTH)
+    }
+}
+
+impl BlockAnalysis {
+    pub fn new(helper_depth: usize) -> Self {
+        Self { helper_depth }
+    }
+
+    /// Risk of leaving the build piles requiring `build_next`.
+    pub fn risk(&self, state: &GameStateView, build_next: &[u8; BUILD_PILE_COUNT]) -> BlockRisk {
+        let players = state.settings.num_players;
+        let threats = state
+            .players
+            .iter()
+            .filter(|p| p.id != state.self_player && !p.has_won)
+            .filter_map(|opponent| {
+                let stock = opponent.stock_top?;
+                let seats_after = state.self_player.seats_until(opponent.id, players).max(1);
+                let urgency = if opponent.stock_count <= URGENT_STOCK_COUNT {
+                    2.0
+                } else {
+                    1.0
+                };
+                Some(OpponentThreat {
+                    player: opponent.id,
+                    seats_after,
+                    stock_count: opponent.stock_count,
+                    helpers: self.helpers_needed(build_next, stock, opponent),
+                    weight: urgency / seats_after as f64,
+                })
+            })
+            .collect();
+        BlockRisk { threats }
+    }
+
+    /// Risk of the build piles as they are now.
+    pub fn current_risk(&self, state: &GameStateView) -> BlockRisk {
+        let mut build_next = [0u8; BUILD_PILE_COUNT];
+        for (next, pile) in build_next.iter_mut().zip(&state.build_piles) {
+            *next = pile.next_value;
+        }
+        self.risk(state, &build_next)
+    }
+
+    /// Change in risk from making `action` now: positive when the play opens a pile to
+    /// an opponent's stock card. Zero for anything but a possible play.
+    pub fn play_risk(&self, state: &GameStateView, action: &Action) -> f64 {
+        if !matches!(action, Action::Play { .. }) {
+            return 0.0;
+        }
+        let Some(mut position) = TurnPosition::from_view(state) else {
+            return 0.0;
+        };
+        let before = self.risk(state, &position.build_next).score();
+        if !position.apply(action) {
+            return 0.0;
+        }
+        self.risk(state, &position.build_next).score() - before
+    }
+
+    /// Fewest discard plays (at most the helper depth) `opponent` needs before `stock`
+    /// fits on a pile.
+    fn helpers_needed(
+        &self,
+        build_next: &[u8; BUILD_PILE_COUNT],
+        stock: Card,
+        opponent: &PlayerPublicState,
+    ) -> Option<usize> {
+        let mut piles = *build_next;
+        let mut popped = vec![0; opponent.discard_piles.len()];
+        (0..=self.helper_depth)
+            .find(|&helpers| reaches_stock(&mut piles, &mut popped, opponent, stock, helpers))
+    }
+}
+
+/// Rollout estimate of the chance that an opponent plays their stock card before the
+/// acting player's next turn, for each way the acting player can end the turn.
+///
+/// A discard ends the turn without touching the build piles, so what a candidate discard
+/// risks is the piles left by the plays made before it. Each sampled round deals every
+/// opponent, in turn order, a full hand from the cards the acting player cannot see
+/// ([`unseen_cards`]) and lets them play greedily towards their stock card; each opponent
+/// sees the piles the previous one left. Unlike [`BlockAnalysis`] this counts opponents'
+/// hands and the plays of earlier opponents, at the cost of the rollouts.
+#[derive(Clone, Copy, Debug)]
+pub struct DiscardSafety {
+    pub samples: usize,
+    /// Seed of the sampled hands; every call draws the same ones, so the chances of
+    /// different build piles are comparable.
+    pub seed: u64,
+}
+
+impl Default for DiscardSafety {
+    fn default() -> Self {
+        Self::new(DEFAULT_SAFETY_SAMPLES)
+    }
+}
+
+impl DiscardSafety {
+    pub fn new(samples: usize) -> Self {
+        Self {
+            samples: samples.max(1),
+            seed: 0x5AFE_D15C,
+        }
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Chance that some opponent plays their stock card within the round when the acting
+    /// player ends the turn with the build piles requiring `build_next`.
+    pub fn unlock_chance(&self, state: &GameStateView, build_next: &[u8; BUILD_PILE_COUNT]) -> f64 {
+        let players = state.settings.num_players;
+        let mut opponents: Vec<&PlayerPublicState> = state
+            .players
+            .iter()
+            .filter(|p| p.id != state.self_player && !p.has_won && p.stock_top.is_some())
+            .collect();
+        if opponents.is_empty() {
+            return 0.0;
+        }
+        opponents.sort_by_key(|p| state.self_player.seats_until(p.id, players));
+        let mut unseen = unseen_cards(state);
+        let hand_size = state.settings.hand_size;
+        let dealt = (hand_size * opponents.len()).min(unseen.len());
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let mut unlocks = 0;
+        for _ in 0..self.samples {
+            let (cards, _) = unseen.partial_shuffle(&mut rng, dealt);
+            let mut hands = cards.chunks(hand_size);
+            let mut piles = *build_next;
+            for opponent in &opponents {
+                let mut position = TurnPosition {
+                    build_next: piles,
+                    hand: hands.next().unwrap_or_default().to_vec(),
+                    discard_piles: opponent.discard_piles.to_vec(),
+                    stock_top: opponent.stock_top,
+                    stock_count: opponent.stock_count,
+                    stock_played: 0,
+                    piles_completed: 0,
+                    wilds_spent: 0,
+                };
+                if greedy_chain(&mut position).1 {
+                    unlocks += 1;
+                    break;
+                }
+                piles = position.build_next;
+            }
+        }
+        f64::from(unlocks) / self.samples as f64
+    }
+
+    /// Memoized [`unlock_chance`](Self::unlock_chance) in `state`: many turn plans
+    /// leave the same build piles.
+    pub fn chances<'a>(&self, state: &'a GameStateView) -> UnlockChances<'a> {
+        UnlockChances {
+            safety: *self,
+            state,
+            cache: HashMap::new(),
+        }
+    }
+
+    /// Chance for the discard `action` made in `position`, the acting player's position
+    /// reached from `state`; `None` unless `action` is a discard possible there.
+    pub fn discard_risk(
+        &self,
+        state: &GameStateView,
+        position: &TurnPosition,
+        action: &Action,
+    ) -> Option<f64> {
+        if !matches!(action, Action::Discard { .. }) {
+            return None;
+        }
+        let mut after = position.clone();
+        after
+            .apply(action)
+            .then(|| self.unlock_chance(state, &after.build_next))
+    }
+
+    /// Chance after each discard the viewing player can make now, in the order of
+    /// `legal`.
+    pub fn score_discards(&self, state: &GameStateView, legal: &[Action]) -> Vec<(Action, f64)> {
+        let Some(position) = TurnPosition::from_view(state) else {
+            return Vec::new();
+        };
+        legal
+            .iter()
+            .filter_map(|action| {
+                let risk = self.discard_risk(state, &position, action)?;
+                Some((action.clone(), risk))
+            })
+            .collect()
+    }
+}
+
+/// [`DiscardSafety::unlock_chance`] for one view, computed once per set of build piles.
+pub struct UnlockChances<'a> {
+    safety: DiscardSafety,
+    state: &'a GameStateView,
+    cache: HashMap<[u8; BUILD_PILE_COUNT], f64>,
+}
+
+impl UnlockChances<'_> {
+    pub fn get(&mut self, build_next: &[u8; BUILD_PILE_COUNT]) -> f64 {
+        *self
+            .cache
+            .entry(*build_next)
+            .or_insert_with(|| self.safety.unlock_chance(self.state, build_next))
+    }
+}
+
+/// Whether `stock` fits on a pile after at most `helpers` discard plays, each taking the
+/// highest card not yet played from one of the opponent's discard piles.
+fn reaches_stock(
+    piles: &mut [u8; BUILD_PILE_COUNT],
+    popped: &mut [usize],
+    opponent: &PlayerPublicState,
+    stock: Card,
+    helpers: usize,
+) -> bool {
+    if piles.iter().any(|&next| stock.matches_value(next)) {
+        return true;
+    }
+    if helpers == 0 {
+        return false;
+    }
+    for (d, pile) in opponent.discard_piles.iter().enumerate() {
+        let Some(&card) = pile.len().checked_sub(popped[d] + 1).map(|i| &pile[i]) else {
+            continue;
+        };
+        for b in 0..BUILD_PILE_COUNT {
+            let required = piles[b];
+            if !card.matches_value(required) || piles[..b].contains(&required) {
+                continue;
+            }
+            piles[b] = next_build_value(required);
+            popped[d] += 1;
+            let reached = reaches_stock(piles, popped, opponent, stock, helpers - 1);
+            popped[d] -= 1;
+            piles[b] = required;
+            if reached {
+                return true;
+            }
+        }
+    }
+    false
+}
@@ -0,0 +1,123 @@
+//! Pile completion and recycle timing: how finishing a build pile, which then starts
+//! over at 1, moves every player's stock card closer or further from playable.
+//!
+//! A pile requiring 12 is one card from completion. Completing it turns the stock card a
+//! player could reach soonest on that pile into one far away, and the other way round,
+//! so whether to complete a pile now, or leave it for later, depends on whose stock
+//! cards the reset helps. [`unlock_balance`] sums those distances from the acting
+//! player's side and [`CompletionEffect`] compares it before and after a completion.
+
+use crate::action::{Action, BuildPileId, PlayerId};
+use crate::card::{BUILD_PILE_COUNT, Card, MAX_CARD_VALUE, MIN_CARD_VALUE};
+use crate::state::GameStateView;
+pub use crate::state::unlock_distance;
+
+/// One player's unlock distance before and after a change of the build piles.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UnlockChange {
+    pub player: PlayerId,
+    /// Turn-order distance from the acting player (0 = the acting player).
+    pub seats_after: usize,
+    pub before: u8,
+    pub after: u8,
+}
+
+/// What completing one build pile does to every player with a visible stock card.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CompletionEffect {
+    pub build_pile: BuildPileId,
+    pub changes: Vec<UnlockChange>,
+    /// Change in [`unlock_balance`]: positive when the completion helps the acting
+    /// player.
+    pub value: f64,
+}
+
+impl CompletionEffect {
+    /// Effect of completing `build_pile` of piles requiring `build_next`; `None` unless
+    /// the pile requires 12.
+    pub fn of_pile(
+        state: &GameStateView,
+        build_next: &[u8; BUILD_PILE_COUNT],
+        build_pile: BuildPileId,
+    ) -> Option<Self> {
+        if build_next.get(build_pile.0) != Some(&MAX_CARD_VALUE) {
+            return None;
+        }
+        let own = state.self_player_state().and_then(|p| p.stock_top);
+        let mut after = *build_next;
+        after[build_pile.0] = MIN_CARD_VALUE;
+        let changes = players_with_stock(state)
+            .map(|(player, seats_after, stock)| UnlockChange {
+                player,
+                seats_after,
+                before: unlock_distance(build_next, stock),
+                after: unlock_distance(&after, stock),
+            })
+            .collect();
+        Some(Self {
+            build_pile,
+            changes,
+            value: unlock_balance(state, &after, own) - unlock_balance(state, build_next, own),
+        })
+    }
+
+    /// Effect of `action` in the current view if it completes a build pile.
+    pub fn of_action(state: &GameStateView, action: &Action) -> Option<Self> {
+        let Action::Play { build_pile, .. } = *action else {
+            return None;
+        };
+        Self::of_pile(state, &build_next(state), build_pile)
+    }
+}
+
+/// Effects of completing each build pile that requires 12.
+pub fn completion_effects(
+    state: &GameStateView,
+    build_next: &[u8; BUILD_PILE_COUNT],
+) -> Vec<CompletionEffect> {
+    BuildPileId::ALL
+        .into_iter()
+        .filter_map(|pile| CompletionEffect::of_pile(state, build_next, pile))
+        .collect()
+}
+
+/// Opponents' unlock distances, each divided by its seat distance, minus the acting
+/// player's own for `own_stock` (the stock top of a simulated line may differ from the
+/// view's): higher is better for the acting player.
+pub fn unlock_balance(
+    state: &GameStateView,
+    build_next: &[u8; BUILD_PILE_COUNT],
+    own_stock: Option<Card>,
+) -> f64 {
+    let opponents: f64 = players_with_stock(state)
+        .filter(|&(_, seats_after, _)| seats_after > 0)
+        .map(|(_, seats_after, stock)| {
+            f64::from(unlock_distance(build_next, stock)) / seats_after as f64
+        })
+        .sum();
+    let own = own_stock.map_or(0.0, |stock| f64::from(unlock_distance(build_next, stock)));
+    opponents - own
+}
+
+/// Values the view's build piles require.
+fn build_next(state: &GameStateView) -> [u8; BUILD_PILE_COUNT] {
+    let mut build_next = [MIN_CARD_VALUE; BUILD_PILE_COUNT];
+    for (next, pile) in build_next.iter_mut().zip(&state.build_piles) {
+        *next = pile.next_value;
+    }
+    build_next
+}
+
+/// Players still in the game with a visible stock card, with their seat distance from
+/// the acting player.
+fn players_with_stock(state: &GameStateView) -> impl Iterator<Item = (PlayerId, usize, Card)> + '_ {
+    let players = state.settings.num_players;
+    state
+        .players
+        .iter()
+        .filter(|p| !p.has_won)
+        .filter_map(move |p| {
+            let seats_after = state.self_player.seats_until(p.id, players);
+            Some((p.id, seats_after, p.stock_top?))
+        })
+}
@@ -0,0 +1,181 @@
+//! Bot line-ups read from TOML or JSON files.
+//!
+//! Long spec strings such as `heuristic19:strength=7:weights=...` are hard to review, so
+//! the CLI tools also accept `--bots-config FILE` with one entry per seat:
+//!
+//! ```toml
+//! [[bots]]
+//! type = "heuristic19"
+//! label = "h19-tuned"
+//! strength = 7
+//! checkpoint = "weights/cem.json"
+//!
+//! [[bots]]
+//! type = "beam"
+//! params = ["8", "12"]
+//! options = { eval = "tempo" }
+//! seed = 42
+//! ```
+//!
+//! Each entry compiles to an ordinary spec string ([`BotConfig::to_spec`]), so reports,
+//! checkpoints and replays record exactly what was played. Relative checkpoint paths are
+//! resolved against the directory of the config file.
+
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::bots::heuristic_19::PlanWeights;
+
+/// One seat of a [`BotsConfig`].
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BotConfig {
+    /// Bot type: the head of a spec (`heuristic19`, `beam`, `noisy`, ...).
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// Label in reports; defaults to the type.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// Positional spec parameters, e.g. the width and depth of `beam`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub params: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub strength: Option<u8>,
+    /// Fixed seed for the bot's own randomness in every game.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+    /// File with trained parameters, e.g. plan weights written by `cem --out`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checkpoint: Option<PathBuf>,
+    /// Inline plan weights.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weights: Option<PlanWeights>,
+    /// Further `key=value` spec options, e.g. `eval`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub options: BTreeMap<String, String>,
+}
+
+impl BotConfig {
+    /// The equivalent spec string for [`create_bot_from_spec`](crate::create_bot_from_spec).
+    pub fn to_spec(&self) -> String {
+        let mut parts = vec![self.kind.trim().to_string()];
+        parts.extend(self.params.iter().cloned());
+        if let Some(strength) = self.strength {
+            parts.push(format!("strength={strength}"));
+        }
+        if let Some(checkpoint) = &self.checkpoint {
+            parts.push(format!("checkpoint={}", checkpoint.display()));
+        }
+        if let Some(weights) = &self.weights {
+            let values: Vec<String> = weights.to_vec().iter().map(f64::to_string).collect();
+            parts.push(format!("weights={}", values.join(",")));
+        }
+        parts.extend(
+            self.options
+                .iter()
+                .map(|(key, value)| format!("{key}={value}")),
+        );
+        if let Some(seed) = self.seed {
+            parts.push(format!("seed={seed}"));
+        }
+        if let Some(label) = &self.label {
+            parts.push(format!("label={label}"));
+        }
+        parts.join(":")
+    }
+
+    /// Check the entry compiles to a well-formed spec; errors name it `bot {seat}`.
+    pub(crate) fn validate(&self, seat: usize) -> Result<(), Box<dyn Error>> {
+        if self.kind.trim().is_empty() {
+            return Err(format!("bot {seat}: missing type").into());
+        }
+        if self.checkpoint.is_some() && self.weights.is_some() {
+            return Err(format!("bot {seat}: give either checkpoint or weights, not both").into());
+        }
+        // Spec strings separate parts with ':' and keys from values with '='.
+        let plain = self
+            .params
+            .iter()
+            .chain(self.options.keys())
+            .chain(&self.label);
+        for text in plain {
+            if text.contains([':', '=']) {
+                return Err(format!("bot {seat}: '{text}' may not contain ':' or '='").into());
+            }
+        }
+        if let Some(value) = self.options.values().find(|value| value.contains(':')) {
+            return Err(format!("bot {seat}: option value '{value}' may not contain ':'").into());
+        }
+        Ok(())
+    }
+
+    /// Resolve a relative checkpoint path against `dir`, the directory of the file the
+    /// entry was read from.
+    pub(crate) fn resolve_checkpoint(&mut self, dir: &Path) {
+        if let Some(checkpoint) = &mut self.checkpoint
+            && checkpoint.is_relative()
+        {
+            *checkpoint = dir.join(&*checkpoint);
+        }
+    }
+}
+
+/// The bots of every seat, in seat order.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BotsConfig {
+    pub bots: Vec<BotConfig>,
+}
+
+impl BotsConfig {
+    /// Parse TOML, or JSON when `path` ends in `.json`, and resolve relative checkpoint
+    /// paths against the file's directory.
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let text = fs::read_to_string(path).map_err(|err| format!("{}: {err}", path.display()))?;
+        let json = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+        let mut config = if json {
+            Self::from_json(&text)
+        } else {
+            Self::from_toml(&text)
+        }
+        .map_err(|err| format!("{}: {err}", path.display()))?;
+        let dir = path.parent().unwrap_or(Path::new(""));
+        for bot in &mut config.bots {
+            bot.resolve_checkpoint(dir);
+        }
+        Ok(config)
+    }
+
+    pub fn from_toml(text: &str) -> Result<Self, Box<dyn Error>> {
+        let config: Self = toml::from_str(text)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn from_json(text: &str) -> Result<Self, Box<dyn Error>> {
+        let config: Self = serde_json::from_str(text)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Spec strings of every seat.
+    pub fn specs(&self) -> Vec<String> {
+        self.bots.iter().map(BotConfig::to_spec).collect()
+    }
+
+    fn validate(&self) -> Result<(), Box<dyn Error>> {
+        if self.bots.is_empty() {
+            return Err("bots config lists no bots".into());
+        }
+        for (seat, bot) in self.bots.iter().enumerate() {
+            bot.validate(seat)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,275 @@
+//! Exact endgame search for positions where the acting player's stock is nearly empty.
+//!
+//! With only a few stock cards left, the chance of emptying the stock during the current
+//! turn can be computed exactly: the search maximizes over the player's plays and averages
+//! over the unseen cards whenever a stock play reveals the next stock card. Hand refills
+//! are not modelled (an emptied hand simply stays empty), so the reported probability is a
+//! lower bound whenever emptying the hand would draw new cards.
+
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use crate::action::{Action, LegalSet};
+use crate::bots::planning::{TurnPosition, unseen_cards};
+use crate::bots::search::{SharedTable, TranspositionTable, lock_table};
+use crate::card::{Card, MAX_CARD_VALUE};
+use crate::interrupt::{Interrupter, POLL_INTERVAL};
+use crate::state::GameStateView;
+
+/// Stock size at or below which the solver is consulted by default.
+pub const DEFAULT_MAX_STOCK: usize = 3;
+/// Search budget per solve; positions beyond it are reported as unsolved.
+pub const DEFAULT_NODE_LIMIT: usize = 200_000;
+/// Win probability at which bots trust the solver over their heuristics.
+pub const DEFAULT_MIN_PROBABILITY: f64 = 0.3;
+
+/// Result of solving an endgame position.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EndgameSolution {
+    /// First action of the line with the highest win probability.
+    pub action: Action,
+    /// Probability of emptying the stock this turn when following the best line.
+    pub win_probability: f64,
+}
+
+/// Exact win-this-turn solver with a transposition table keyed by
+/// [`GameStateView::position_hash`] and the legal actions it may choose from. Clones
+/// share the table; solves cut short by `interrupter` or by `node_limit` count as unsolved
+/// and are not stored, so a solver with a smaller budget never hides a solution from one
+/// with a larger budget.
+#[derive(Clone, Debug)]
+pub struct EndgameSolver {
+    pub max_stock: usize,
+    pub node_limit: usize,
+    pub min_probability: f64,
+    pub interrupter: Interrupter,
+    cache: SharedTable<Option<EndgameSolution>>,
+}
+
+impl Default for EndgameSolver {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_STOCK)
+    }
+}
+
+type UnseenCounts = [u8; MAX_CARD_VALUE as usize + 1];
+
+struct Search<'a> {
+    memo: HashMap<(TurnPosition, UnseenCounts), f64>,
+    nodes: usize,
+    node_limit: usize,
+    interrupter: &'a Interrupter,
+    cut_short: bool,
+}
+
+impl EndgameSolver {
+    pub fn new(max_stock: usize) -> Self {
+        Self {
+            max_stock,
+            node_limit: DEFAULT_NODE_LIMIT,
+            min_probability: DEFAULT_MIN_PROBABILITY,
+            interrupter: Interrupter::new(),
+            cache: TranspositionTable::default().shared(),
+        }
+    }
+
+    /// Solve into `table`, e.g. one shared with the solvers of other bots.
+    pub fn with_table(mut self, table: SharedTable<Option<EndgameSolution>>) -> Self {
+        self.cache = table;
+        self
+    }
+
+    pub fn table(&self) -> &SharedTable<Option<EndgameSolution>> {
+        &self.cache
+    }
+
+    /// Whether the position is small enough to be handed to the solver.
+    pub fn applies(&self, state: &GameStateView) -> bool {
+        state
+            .self_player_state()
+            .is_some_and(|p| p.stock_count > 0 && p.stock_count <= self.max_stock)
+    }
+
+    /// Action a bot should play instead of its heuristic choice, if the solver found a
+    /// line winning this turn with at least `min_probability`.
+    pub fn recommend(&mut self, state: &GameStateView, legal_actions: &[Action]) -> Option<Action> {
+        let min_probability = self.min_probability;
+        self.solve(state, legal_actions)
+            .filter(|solution| solution.win_probability >= min_probability)
+            .map(|solution| solution.action)
+    }
+
+    /// Number of cached positions.
+    pub fn cached(&self) -> usize {
+        lock_table(&self.cache).len()
+    }
+
+    pub fn clear_cache(&mut self) {
+        lock_table(&self.cache).clear();
+    }
+
+    /// Age the stored solves so that new ones may take their slots, e.g. once per turn.
+    pub fn new_search(&self) {
+        lock_table(&self.cache).new_search();
+    }
+
+    /// Solve the position if it qualifies. Returns `None` when the stock is too large, the
+    /// budget runs out, the interrupter fires, or no line can win this turn.
+    pub fn solve(
+        &mut self,
+        state: &GameStateView,
+        legal_actions: &[Action],
+    ) -> Option<EndgameSolution> {
+        if !self.applies(state) {
+            return None;
+        }
+        let key = solve_key(state, legal_actions);
+        if let Some(cached) = lock_table(&self.cache).probe(key) {
+            return cached.clone();
+        }
+        let (solution, nodes, cut_short) = self.solve_uncached(state, legal_actions);
+        if !cut_short {
+            let depth = u32::try_from(nodes).unwrap_or(u32::MAX);
+            lock_table(&self.cache).store(key, depth, solution.clone());
+        }
+        solution
+    }
+
+    /// The solution, the nodes searched for it and whether the interrupter or the node
+    /// budget cut the search short.
+    fn solve_uncached(
+        &self,
+        state: &GameStateView,
+        legal_actions: &[Action],
+    ) -> (Option<EndgameSolution>, usize, bool) {
+        let Some(root) = TurnPosition::from_view(state) else {
+            return (None, 0, false);
+        };
+        let mut unseen: UnseenCounts = [0; MAX_CARD_VALUE as usize + 1];
+        for card in unseen_cards(state) {
+            unseen[card_slot(card)] += 1;
+        }
+        let mut search = Search {
+            memo: HashMap::new(),
+            nodes: 0,
+            node_limit: self.node_limit,
+            interrupter: &self.interrupter,
+            cut_short: false,
+        };
+        let legal = LegalSet::from_actions(legal_actions);
+        let mut best: Option<EndgameSolution> = None;
+        for action in root.plays() {
+            if !legal.contains(&action) {
+                continue;
+            }
+            let mut child = root.clone();
+            if !child.apply(&action) {
+                continue;
+            }
+            let Some(value) = search.after_play(&child, &unseen) else {
+                return (None, search.nodes, search.cut_short);
+            };
+            if best.as_ref().is_none_or(|b| value > b.win_probability) {
+                best = Some(EndgameSolution {
+                    action,
+                    win_probability: value,
+                });
+            }
+        }
+        (
+            best.filter(|b| b.win_probability > 0.0),
+            search.nodes,
+            false,
+        )
+    }
+}
+
+/// Table key of a solve: the position and the actions the caller allows, so a restricted
+/// legal set never gets a plan starting with an action outside it.
+fn solve_key(state: &GameStateView, legal_actions: &[Action]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    state.position_hash().hash(&mut hasher);
+    LegalSet::from_actions(legal_actions).hash(&mut hasher);
+    hasher.finish()
+}
+
+fn card_slot(card: Card) -> usize {
+    match card {
+        Card::Number(v) => v as usize,
+        Card::SkipBo => 0,
+    }
+}
+
+fn slot_card(slot: usize) -> Card {
+    if slot == 0 {
+        Card::SkipBo
+    } else {
+        Card::Number(slot as u8)
+    }
+}
+
+impl Search<'_> {
+    /// Value of a position right after a play, resolving a stock reveal if needed.
+    fn after_play(&mut self, position: &TurnPosition, unseen: &UnseenCounts) -> Option<f64> {
+        if position.stock_count == 0 {
+            return Some(1.0);
+        }
+        if position.stock_top.is_some() {
+            return self.value(position, unseen);
+        }
+        let total: u32 = unseen.iter().map(|&c| c as u32).sum();
+        if total == 0 {
+            return Some(0.0);
+        }
+        let mut expected = 0.0;
+        for slot in 0..unseen.len() {
+            let count = unseen[slot];
+            if count == 0 {
+                continue;
+            }
+            let mut revealed = position.clone();
+            revealed.stock_top = Some(slot_card(slot));
+            let mut remaining = *unseen;
+            remaining[slot] -= 1;
+            expected += (count as f64 / total as f64) * self.value(&revealed, &remaining)?;
+        }
+        Some(expected)
+    }
+
+    /// Best achievable win probability from a decision node.
+    fn value(&mut self, position: &TurnPosition, unseen: &UnseenCounts) -> Option<f64> {
+        let key = (position.clone(), *unseen);
+        if let Some(value) = self.memo.get(&key) {
+            return Some(*value);
+        }
+        self.nodes += 1;
+        if self.nodes > self.node_limit
+            || (self.nodes.is_multiple_of(POLL_INTERVAL) && self.interrupter.should_stop())
+        {
+            self.cut_short = true;
+            return None;
+        }
+        let mut best = 0.0f64;
+        for action in position.plays() {
+            let mut child = position.clone();
+            if !child.apply(&action) {
+                continue;
+            }
+            let value = self.after_play(&child, unseen)?;
+            if value > best {
+                best = value;
+                if best >= 1.0 {
+                    break;
+                }
+            }
+        }
+        self.memo.insert(key, best);
+        Some(best)
+    }
+}
+
+/// Convenience wrapper: solve once without keeping a cache.
+pub fn solve_endgame(state: &GameStateView, legal_actions: &[Action]) -> Option<EndgameSolution> {
+    EndgameSolver::default().solve(state, legal_actions)
+}
@@ -0,0 +1,197 @@
+//! Hand-written evaluation functions shared by the search bots.
+//!
+//! A search bot simulates lines of play as [`TurnPosition`]s and asks an [`EvalFn`] how
+//! good the end of each line is for the searching player. Keeping evaluators behind one
+//! trait lets every search bot use every evaluator, and [`Evaluator`] names the built-in
+//! ones so a bot spec can pick one (`beam:8:12:eval=tempo`) and runs can compare them.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::bots::completion::unlock_balance;
+use crate::bots::planning::TurnPosition;
+use crate::card::Card;
+use crate::state::{self, GameStateView};
+
+/// Scores the end of a simulated line from the searching player's perspective. Higher
+/// is better.
+pub trait EvalFn: Send + Sync {
+    /// `state` is the view the search started from, `position` the searching player's
+    /// position at the end of the line and `opponent_unlocks` the number of opponent
+    /// stock cards the modelled response played.
+    fn evaluate(
+        &self,
+        state: &GameStateView,
+        position: &TurnPosition,
+        opponent_unlocks: usize,
+    ) -> f32;
+}
+
+impl<F> EvalFn for F
+where
+    F: Fn(&GameStateView, &TurnPosition, usize) -> f32 + Send + Sync,
+{
+    fn evaluate(
+        &self,
+        state: &GameStateView,
+        position: &TurnPosition,
+        opponent_unlocks: usize,
+    ) -> f32 {
+        self(state, position, opponent_unlocks)
+    }
+}
+
+/// Score of a line that empties the stock, above anything else an evaluator returns.
+pub const WIN_SCORE: f32 = 1.0e9;
+
+/// The built-in evaluators.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Evaluator {
+    /// Stock progress first, then unlock distance, opponent unlocks, held wilds and
+    /// discard pile depth. The beam search bot's original evaluation.
+    #[default]
+    Balanced,
+    /// Our stock left against the closest opponent's.
+    StockDifferential,
+    /// How many cards stand between the stock top and a build pile, and whether the hand
+    /// and discard tops can bridge the gap.
+    UnlockDistance,
+    /// Cards moved this turn: stock plays, hand cards spent towards a refill and
+    /// completed piles, against what the opponents get to play.
+    Tempo,
+    /// Stock progress, then the unlock balance of the build piles left behind: opponents'
+    /// distances to their stock cards (nearer seats weigh more) against our own, so
+    /// completing a pile counts for or against the line depending on whom the reset
+    /// helps.
+    Recycle,
+}
+
+impl Evaluator {
+    pub const ALL: [Evaluator; 5] = [
+        Evaluator::Balanced,
+        Evaluator::StockDifferential,
+        Evaluator::UnlockDistance,
+        Evaluator::Tempo,
+        Evaluator::Recycle,
+    ];
+}
+
+/// Cards the build piles must advance before `card` can be played on one of them.
+fn unlock_distance(position: &TurnPosition, card: Option<Card>) -> Option<u8> {
+    card.map(|card| state::unlock_distance(&position.build_next, card))
+}
+
+fn wilds_in_hand(position: &TurnPosition) -> usize {
+    position.hand.iter().filter(|c| c.is_skip_bo()).count()
+}
+
+impl EvalFn for Evaluator {
+    fn evaluate(
+        &self,
+        state: &GameStateView,
+        position: &TurnPosition,
+        opponent_unlocks: usize,
+    ) -> f32 {
+        if position.stock_count == 0 {
+            return WIN_SCORE;
+        }
+        let stock = position.stock_played as f32 * 1_000.0;
+        match self {
+            Evaluator::Balanced => {
+                let mut score = stock;
+                if let Some(Card::Number(_)) = position.stock_top {
+                    score -=
+                        unlock_distance(position, position.stock_top).unwrap_or(0) as f32 * 25.0;
+                }
+                score -= opponent_unlocks as f32 * 400.0;
+                score += wilds_in_hand(position) as f32 * 60.0;
+                score -= position.wilds_spent as f32 * 120.0;
+                // Fewer cards kept means a larger refill next turn.
+                score -= position.hand.len() as f32 * 30.0;
+                let depth: usize = position.discard_piles.iter().map(Vec::len).sum();
+                score - depth as f32 * 5.0
+            }
+            Evaluator::StockDifferential => {
+                let closest = state
+                    .players
+                    .iter()
+                    .filter(|p| p.id != state.self_player)
+                    .map(|p| p.stock_count)
+                    .min()
+                    .unwrap_or(0);
+                // The modelled response plays at most one card per opponent; charge it
+                // to the closest one.
+                let closest = closest.saturating_sub(opponent_unlocks) as f32;
+                let lead = closest - position.stock_count as f32;
+                let mut score = lead * 1_000.0;
+                if closest <= 2.0 {
+                    score -= opponent_unlocks as f32 * 2_000.0;
+                }
+                score + wilds_in_hand(position) as f32 * 60.0 - position.wilds_spent as f32 * 120.0
+            }
+            Evaluator::UnlockDistance => {
+                let mut score = stock;
+                let Some(distance) = unlock_distance(position, position.stock_top) else {
+                    return score - opponent_unlocks as f32 * 400.0;
+                };
+                score -= distance as f32 * 60.0;
+                // Cards we hold that could be played on the way towards the stock top.
+                let tops = position.discard_piles.iter().filter_map(|pile| pile.last());
+                let bridges = position
+                    .hand
+                    .iter()
+                    .chain(tops)
+                    .filter(|&&card| {
+                        card.is_skip_bo()
+                            || unlock_distance(position, Some(card)).is_some_and(|d| d < distance)
+                    })
+                    .count()
+                    .min(distance as usize);
+                score += bridges as f32 * 40.0;
+                score - opponent_unlocks as f32 * 400.0 - position.wilds_spent as f32 * 80.0
+            }
+            Evaluator::Tempo => {
+                let hand_size = state.settings.hand_size;
+                let spent = hand_size.saturating_sub(position.hand.len()) as f32;
+                let mut score = stock + spent * 45.0 + position.piles_completed as f32 * 150.0;
+                if position.hand.is_empty() {
+                    // A fresh hand next turn.
+                    score += 120.0;
+                }
+                score - opponent_unlocks as f32 * 500.0 - position.wilds_spent as f32 * 100.0
+            }
+            Evaluator::Recycle => {
+                let balance = unlock_balance(state, &position.build_next, position.stock_top);
+                stock + balance as f32 * 40.0
+                    - opponent_unlocks as f32 * 400.0
+                    - position.wilds_spent as f32 * 100.0
+            }
+        }
+    }
+}
+
+impl fmt::Display for Evaluator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Evaluator::Balanced => "balanced",
+            Evaluator::StockDifferential => "stock-diff",
+            Evaluator::UnlockDistance => "unlock",
+            Evaluator::Tempo => "tempo",
+            Evaluator::Recycle => "recycle",
+        })
+    }
+}
+
+impl FromStr for Evaluator {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|eval| eval.to_string().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| {
+                let names: Vec<String> = Self::ALL.iter().map(Evaluator::to_string).collect();
+                format!("unknown evaluator {s} (expected {})", names.join(", "))
+            })
+    }
+}
@@ -1,7 +1,7 @@
 use crate::action::{Action, CardSource};
-use crate::bot::Bot;
+use crate::bot::{Bot, rank_scored};
 use crate::card::{Card, MAX_CARD_VALUE};
-use crate::state::{GameStateView, PlayerPublicState};
+use crate::state::GameStateView;
 
 /// Rule-based bot that plays "sensible" moves without search or learning.
 ///
@@ -21,14 +21,6 @@ impl HeuristicBot {
         Self
     }
 
-    fn self_player<'a>(state: &'a GameStateView) -> &'a PlayerPublicState {
-        state
-            .players
-            .iter()
-            .find(|player| player.id == state.self_player)
-            .expect("self player state must be present")
-    }
-
     fn card_priority(card: Card) -> i32 {
         match card {
             Card::Number(value) => value as i32,
@@ -48,12 +40,16 @@ impl HeuristicBot {
         };
         let card = match source {
             CardSource::Hand(index) => state.hand.get(index).copied(),
-            CardSource::Stock => Self::self_player(state).stock_top,
+            CardSource::Stock => state
+                .self_player_state()
+                .and_then(|player| player.stock_top),
             CardSource::Discard(index) => {
-                let player = Self::self_player(state);
+                let Some(player) = state.self_player_state() else {
+                    return i32::MIN / 2;
+                };
                 player
                     .discard_piles
-                    .get(index)
+                    .get(index.0)
                     .and_then(|pile| pile.last())
                     .copied()
             }
@@ -100,7 +96,9 @@ impl HeuristicBot {
         let Some(card) = state.hand.get(hand_index).copied() else {
             return i32::MIN / 2;
         };
-        let player = Self::self_player(state);
+        let Some(player) = state.self_player_state() else {
+            return i32::MIN / 2;
+        };
         let existing_top = player
             .discard_piles
             .get(discard_pile)
@@ -125,11 +123,11 @@ impl HeuristicBot {
     /// Map a legal action to a score: prefer plays and good discards; avoid ending turn early.
     fn score_action(state: &GameStateView, action: &Action) -> i32 {
         match action {
-            Action::Play { source, build_pile } => Self::score_play(state, *source, *build_pile),
+            Action::Play { source, build_pile } => Self::score_play(state, *source, build_pile.0),
             Action::Discard {
                 hand_index,
                 discard_pile,
-            } => Self::score_discard(state, *hand_index, *discard_pile),
+            } => Self::score_discard(state, *hand_index, discard_pile.0),
             // Strong penalty: if any useful move exists, don't end the turn yet.
             Action::EndTurn => -5_000,
         }
@@ -154,4 +152,17 @@ impl Bot for HeuristicBot {
             .cloned()
             .unwrap_or_else(|| legal_actions[0].clone())
     }
+
+    fn rank_actions(
+        &mut self,
+        state: &GameStateView,
+        legal_actions: &[Action],
+    ) -> Vec<(Action, f32)> {
+        let chosen = self.select_action(state, legal_actions);
+        let scored = legal_actions
+            .iter()
+            .map(|action| (action.clone(), Self::score_action(state, action) as f32))
+            .collect();
+        rank_scored(chosen, scored)
+    }
 }
@@ -1,7 +1,8 @@
-use crate::action::{Action, CardSource};
+use crate::action::{Action, BuildPileId, CardSource, DiscardPileId, LegalSet};
 use crate::bot::Bot;
-use crate::card::{Card, MAX_CARD_VALUE, MIN_CARD_VALUE};
-use crate::state::{GameStateView, PlayerPublicState};
+use crate::bots::planning::TurnContext;
+use crate::card::{Card, MAX_CARD_VALUE, MAX_DISCARD_PILES, MIN_CARD_VALUE};
+use crate::state::GameStateView;
 
 /// Heuristic 11 bot (based on Heuristic 10)
 /// Add-on behavior:
@@ -9,24 +10,18 @@ use crate::state::{GameStateView, PlayerPublicState};
 ///   played in sequence without using stock/discards (using Skip-Bo as wilds).
 ///   If such a sequence exists, play the first action of that sequence to trigger
 ///   an immediate hand refill when the hand empties.
-pub struct Heuristic11Bot;
+///
+/// The hand-empty sequence is kept in a [`TurnContext`] and replayed on the following
+/// decisions while the position matches, instead of searching again after every play.
+pub struct Heuristic11Bot {
+    context: TurnContext,
+}
 
 impl Heuristic11Bot {
     pub fn new() -> Self {
-        Self
-    }
-
-    fn self_player<'a>(state: &'a GameStateView) -> &'a PlayerPublicState {
-        state
-            .players
-            .iter()
-            .find(|p| p.id == state.self_player)
-            .expect("self player state must be present")
-    }
-
-    fn next_player<'a>(state: &'a GameStateView) -> Option<&'a PlayerPublicState> {
-        let next_id = (state.current_player + 1) % state.settings.num_players;
-        state.players.iter().find(|p| p.id == next_id)
+        Self {
+            context: TurnContext::new(),
+        }
     }
 
     /// Returns true if the given play action would set the pile to a next_value
@@ -46,13 +41,16 @@ impl Heuristic11Bot {
                 None => return false,
             },
             CardSource::Discard(d) => {
-                match Self::self_player(state).discard_piles[d].last().copied() {
+                match state
+                    .self_player_state()
+                    .and_then(|p| p.discard_piles[d].last().copied())
+                {
                     Some(Card::Number(v)) => v,
                     Some(Card::SkipBo) => pile.next_value,
                     None => return false,
                 }
             }
-            CardSource::Stock => match Self::self_player(state).stock_top {
+            CardSource::Stock => match state.self_player_state().and_then(|p| p.stock_top) {
                 Some(Card::Number(v)) => v,
                 Some(Card::SkipBo) => pile.next_value,
                 None => return false,
@@ -64,7 +62,7 @@ impl Heuristic11Bot {
         } else {
             played_value + 1
         };
-        if let Some(next_player) = Self::next_player(state) {
+        if let Some(next_player) = state.next_player_state() {
             matches!(next_player.stock_top, Some(Card::Number(v)) if v == above)
         } else {
             false
@@ -76,7 +74,9 @@ impl Heuristic11Bot {
         let Some(card) = state.hand.get(hand_index).copied() else {
             return i32::MIN / 2;
         };
-        let player = Self::self_player(state);
+        let Some(player) = state.self_player_state() else {
+            return i32::MIN / 2;
+        };
         let existing_top = player
             .discard_piles
             .get(discard_pile)
@@ -121,7 +121,7 @@ impl Heuristic11Bot {
     /// Plan minimal prerequisite plays to make stock playable; return first action if feasible.
     /// (Identical logic to Heuristic 9/10; no blocking in this phase.)
     fn can_play_stock(state: &GameStateView, legal_actions: &[Action]) -> Option<Action> {
-        let player = Self::self_player(state);
+        let player = state.self_player_state()?;
         let stock = player.stock_top?;
 
         // Fast path: immediate stock play.
@@ -134,14 +134,14 @@ impl Heuristic11Bot {
             {
                 let action = Action::Play {
                     source: CardSource::Stock,
-                    build_pile: best_idx,
+                    build_pile: BuildPileId(best_idx),
                 };
                 if legal_actions.contains(&action) {
                     return Some(action);
                 }
             }
-        } else if let Card::Number(s) = stock {
-            if let Some(best_idx) = state
+        } else if let Card::Number(s) = stock
+            && let Some(best_idx) = state
                 .build_piles
                 .iter()
                 .enumerate()
@@ -149,14 +149,13 @@ impl Heuristic11Bot {
                 .map(|(i, pile)| (i, pile.cards.len()))
                 .max_by_key(|(_, len)| *len)
                 .map(|(i, _)| i)
-            {
-                let action = Action::Play {
-                    source: CardSource::Stock,
-                    build_pile: best_idx,
-                };
-                if legal_actions.contains(&action) {
-                    return Some(action);
-                }
+        {
+            let action = Action::Play {
+                source: CardSource::Stock,
+                build_pile: BuildPileId(best_idx),
+            };
+            if legal_actions.contains(&action) {
+                return Some(action);
             }
         }
 
@@ -176,7 +175,7 @@ impl Heuristic11Bot {
                 Card::SkipBo => skipbo_hands.push(idx),
             }
         }
-        for (d_idx, pile) in Self::self_player(state).discard_piles.iter().enumerate() {
+        for (d_idx, pile) in state.self_player_state()?.discard_piles.iter().enumerate() {
             if let Some(card) = pile.last().copied() {
                 match card {
                     Card::Number(v) => by_value[v as usize].push(SourceKind::Discard(d_idx)),
@@ -189,7 +188,7 @@ impl Heuristic11Bot {
         for (pile_idx, pile) in state.build_piles.iter().enumerate() {
             let required = Self::required_values_for_pile(pile.next_value, stock);
             let mut used_hand: Vec<bool> = vec![false; state.hand.len()];
-            let mut used_discard: [bool; 4] = [false; 4];
+            let mut used_discard = [false; MAX_DISCARD_PILES];
             let skipbo_discards_left = skipbo_discards.clone();
             let skipbo_hands_left = skipbo_hands.clone();
             let mut actions: Vec<Action> = Vec::new();
@@ -198,20 +197,20 @@ impl Heuristic11Bot {
             for need in required.iter().copied() {
                 let mut picked: Option<SourceKind> = None;
                 for &src in &by_value[need as usize] {
-                    if let SourceKind::Discard(d) = src {
-                        if !used_discard[d] {
-                            picked = Some(src);
-                            break;
-                        }
+                    if let SourceKind::Discard(d) = src
+                        && !used_discard[d]
+                    {
+                        picked = Some(src);
+                        break;
                     }
                 }
                 if picked.is_none() {
                     for &src in &by_value[need as usize] {
-                        if let SourceKind::Hand(h) = src {
-                            if !used_hand[h] {
-                                picked = Some(src);
-                                break;
-                            }
+                        if let SourceKind::Hand(h) = src
+                            && !used_hand[h]
+                        {
+                            picked = Some(src);
+                            break;
                         }
                     }
                 }
@@ -236,15 +235,15 @@ impl Heuristic11Bot {
                     SourceKind::Discard(d) => {
                         used_discard[d] = true;
                         actions.push(Action::Play {
-                            source: CardSource::Discard(d),
-                            build_pile: pile_idx,
+                            source: CardSource::Discard(DiscardPileId(d)),
+                            build_pile: BuildPileId(pile_idx),
                         });
                     }
                     SourceKind::Hand(h) => {
                         used_hand[h] = true;
                         actions.push(Action::Play {
                             source: CardSource::Hand(h),
-                            build_pile: pile_idx,
+                            build_pile: BuildPileId(pile_idx),
                         });
                     }
                 }
@@ -271,7 +270,7 @@ impl Heuristic11Bot {
             if actions.is_empty() {
                 let action = Action::Play {
                     source: CardSource::Stock,
-                    build_pile: pile_idx,
+                    build_pile: BuildPileId(pile_idx),
                 };
                 if legal_actions.contains(&action) {
                     return Some(action);
@@ -287,8 +286,11 @@ impl Heuristic11Bot {
     }
 
     /// Attempt to find a sequence that plays ALL current hand cards (ignoring stock/discards).
-    /// If such a sequence exists, return the first play action in that sequence.
-    fn can_play_all_hand(state: &GameStateView, legal_actions: &[Action]) -> Option<Action> {
+    /// Returns the whole sequence (in engine hand indices) if feasible.
+    pub(crate) fn can_play_all_hand(
+        state: &GameStateView,
+        legal_actions: &[Action],
+    ) -> Option<Vec<Action>> {
         let hand_len = state.hand.len();
         if hand_len == 0 {
             return None;
@@ -296,7 +298,7 @@ impl Heuristic11Bot {
 
         // Quick feasibility: if there are no playable piles for any card, skip.
         let mut any_playable = false;
-        for (_i, card) in state.hand.iter().enumerate() {
+        for card in state.hand.iter() {
             match card {
                 Card::SkipBo => {
                     any_playable = true;
@@ -325,7 +327,7 @@ impl Heuristic11Bot {
         let is_first_action_legal = |hand_idx: usize, pile_idx: usize| -> bool {
             legal_actions.contains(&Action::Play {
                 source: CardSource::Hand(hand_idx),
-                build_pile: pile_idx,
+                build_pile: BuildPileId(pile_idx),
             })
         };
 
@@ -400,16 +402,29 @@ impl Heuristic11Bot {
             &state.hand,
             &mut path,
             &is_first_action_legal,
-        ) {
-            if let Some((hi, pi)) = path.first().copied() {
-                let action = Action::Play {
-                    source: CardSource::Hand(hi),
-                    build_pile: pi,
-                };
-                if legal_actions.contains(&action) {
-                    return Some(action);
-                }
-            }
+        ) && let Some(&(hi, pi)) = path.first()
+            && legal_actions.contains(&Action::Play {
+                source: CardSource::Hand(hi),
+                build_pile: BuildPileId(pi),
+            })
+        {
+            // Hand indices in the path refer to the original hand; the engine shifts the
+            // remaining cards down after each play.
+            let plan = path
+                .iter()
+                .enumerate()
+                .map(|(k, &(hi, pi))| {
+                    let shift = path[..k]
+                        .iter()
+                        .filter(|(earlier, _)| *earlier < hi)
+                        .count();
+                    Action::Play {
+                        source: CardSource::Hand(hi - shift),
+                        build_pile: BuildPileId(pi),
+                    }
+                })
+                .collect();
+            return Some(plan);
         }
         None
     }
@@ -423,12 +438,15 @@ impl Heuristic11Bot {
                     Card::SkipBo => None,
                 },
                 CardSource::Discard(d) => {
-                    match Self::self_player(state).discard_piles[d].last().copied()? {
+                    match state.self_player_state()?.discard_piles[d]
+                        .last()
+                        .copied()?
+                    {
                         Card::Number(v) => Some(v),
                         Card::SkipBo => None,
                     }
                 }
-                CardSource::Stock => match Self::self_player(state).stock_top? {
+                CardSource::Stock => match state.self_player_state()?.stock_top? {
                     Card::Number(v) => Some(v),
                     Card::SkipBo => None,
                 },
@@ -463,6 +481,10 @@ impl Default for Heuristic11Bot {
 }
 
 impl Bot for Heuristic11Bot {
+    fn on_turn_start(&mut self, _state: &GameStateView) {
+        self.context.clear();
+    }
+
     fn select_action(&mut self, state: &GameStateView, legal_actions: &[Action]) -> Action {
         assert!(
             !legal_actions.is_empty(),
@@ -475,12 +497,21 @@ impl Bot for Heuristic11Bot {
         }
 
         // 2) Hand-empty plan: if we can play ALL current hand cards in sequence, do it.
-        if let Some(action) = Self::can_play_all_hand(state, legal_actions) {
+        if let Some(action) = self
+            .context
+            .next_action(state, &LegalSet::from_actions(legal_actions))
+        {
             return action;
         }
+        if let Some(plan) = Self::can_play_all_hand(state, legal_actions) {
+            return self
+                .context
+                .start_plan(state, plan)
+                .expect("hand-empty plan has a first action");
+        }
 
         // 3) Number play selection with duplication preservation (same as heuristic 10).
-        let stock_value = match Self::self_player(state).stock_top {
+        let stock_value = match state.self_player_state().and_then(|p| p.stock_top) {
             Some(Card::Number(v)) => v,
             Some(Card::SkipBo) | None => 1,
         };
@@ -492,23 +523,22 @@ impl Bot for Heuristic11Bot {
             if Self::breaks_pair_duplication(state, action) {
                 continue;
             }
-            if let Some(v) = Self::played_card_value(state, action) {
-                if v >= 6 && v > stock_value {
-                    let pile_len = match action {
-                        Action::Play { build_pile, .. } => {
-                            state.build_piles[*build_pile].cards.len()
-                        }
-                        _ => 0,
-                    };
-                    let better = match &best_play {
-                        None => true,
-                        Some((best_v, best_len, _)) => {
-                            v > *best_v || (v == *best_v && pile_len > *best_len)
-                        }
-                    };
-                    if better {
-                        best_play = Some((v, pile_len, action.clone()));
+            if let Some(v) = Self::played_card_value(state, action)
+                && v >= 6
+                && v > stock_value
+            {
+                let pile_len = match action {
+                    Action::Play { build_pile, .. } => state.build_piles[*build_pile].cards.len(),
+                    _ => 0,
+                };
+                let better = match &best_play {
+                    None => true,
+                    Some((best_v, best_len, _)) => {
+                        v > *best_v || (v == *best_v && pile_len > *best_len)
                     }
+                };
+                if better {
+                    best_play = Some((v, pile_len, action.clone()));
                 }
             }
         }
@@ -524,7 +554,7 @@ impl Bot for Heuristic11Bot {
                 discard_pile,
             } = *action
             {
-                let score = Self::score_discard(state, hand_index, discard_pile);
+                let score = Self::score_discard(state, hand_index, discard_pile.0);
                 if best.as_ref().map(|(s, _)| score > *s).unwrap_or(true) {
                     best = Some((score, action.clone()));
                 }
@@ -1,4 +1,4 @@
-use crate::action::{Action, CardSource};
+use crate::action::{Action, CardSource, DiscardPileId};
 use crate::bot::Bot;
 use crate::card::{Card, MAX_CARD_VALUE};
 use crate::state::{GameStateView, PlayerPublicState};
@@ -13,14 +13,6 @@ impl Heuristic16Bot {
         Self
     }
 
-    fn self_player<'a>(state: &'a GameStateView) -> &'a PlayerPublicState {
-        state
-            .players
-            .iter()
-            .find(|player| player.id == state.self_player)
-            .expect("self player state must be present")
-    }
-
     fn card_priority(card: Card) -> i32 {
         match card {
             Card::Number(value) => value as i32,
@@ -53,10 +45,10 @@ impl Heuristic16Bot {
     }
 
     fn best_discard_action(state: &GameStateView, legal_actions: &[Action]) -> Option<Action> {
-        let player = Self::self_player(state);
+        let player = state.self_player_state()?;
         let mut best_action: Option<&Action> = None;
         let mut best_score = i32::MIN;
-        let mut best_pile = usize::MAX;
+        let mut best_pile = DiscardPileId(usize::MAX);
         let mut best_hand_index = usize::MAX;
         for action in legal_actions {
             if let Action::Discard {
@@ -64,7 +56,7 @@ impl Heuristic16Bot {
                 discard_pile,
             } = action
             {
-                let score = Self::score_discard(state, player, *hand_index, *discard_pile);
+                let score = Self::score_discard(state, player, *hand_index, discard_pile.0);
                 let is_better = if score > best_score {
                     true
                 } else if score == best_score {
@@ -1,7 +1,7 @@
-use crate::action::{Action, CardSource};
+use crate::action::{Action, BuildPileId, CardSource};
 use crate::bot::Bot;
 use crate::card::{Card, MAX_CARD_VALUE};
-use crate::state::{GameStateView, PlayerPublicState};
+use crate::state::GameStateView;
 
 /// Heuristic 17 bot ("build-pile chooser"): play priority from Heuristic 15 with
 /// build-pile scoring so duplicate options pick the most promising pile.
@@ -12,21 +12,14 @@ impl Heuristic17Bot {
         Self
     }
 
-    fn self_player<'a>(state: &'a GameStateView) -> &'a PlayerPublicState {
-        state
-            .players
-            .iter()
-            .find(|player| player.id == state.self_player)
-            .expect("self player state must be present")
-    }
-
     fn card_from_source(state: &GameStateView, source: CardSource) -> Option<Card> {
         match source {
             CardSource::Hand(index) => state.hand.get(index).copied(),
-            CardSource::Stock => Self::self_player(state).stock_top,
-            CardSource::Discard(index) => Self::self_player(state)
+            CardSource::Stock => state.self_player_state()?.stock_top,
+            CardSource::Discard(index) => state
+                .self_player_state()?
                 .discard_piles
-                .get(index)
+                .get(index.0)
                 .and_then(|pile| pile.last())
                 .copied(),
         }
@@ -64,14 +57,14 @@ impl Heuristic17Bot {
     {
         let mut best_action: Option<&Action> = None;
         let mut best_score = i32::MIN;
-        let mut best_build = usize::MAX;
+        let mut best_build = BuildPileId(usize::MAX);
         let mut best_hand = usize::MAX;
         for action in legal_actions {
             if !filter(action) {
                 continue;
             }
             if let Action::Play { source, build_pile } = action {
-                let score = Self::score_play(state, *source, *build_pile);
+                let score = Self::score_play(state, *source, build_pile.0);
                 let hand_index = match source {
                     CardSource::Hand(index) => *index,
                     _ => usize::MAX,
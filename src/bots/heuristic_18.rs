@@ -1,4 +1,4 @@
-use crate::action::{Action, CardSource};
+use crate::action::{Action, BuildPileId, CardSource, DiscardPileId};
 use crate::bot::Bot;
 use crate::card::{Card, MAX_CARD_VALUE};
 use crate::state::{GameStateView, PlayerPublicState};
@@ -12,14 +12,6 @@ impl Heuristic18Bot {
         Self
     }
 
-    fn self_player<'a>(state: &'a GameStateView) -> &'a PlayerPublicState {
-        state
-            .players
-            .iter()
-            .find(|player| player.id == state.self_player)
-            .expect("self player state must be present")
-    }
-
     fn card_priority(card: Card) -> i32 {
         match card {
             Card::Number(value) => value as i32,
@@ -52,10 +44,10 @@ impl Heuristic18Bot {
     }
 
     fn best_discard_action(state: &GameStateView, legal_actions: &[Action]) -> Option<Action> {
-        let player = Self::self_player(state);
+        let player = state.self_player_state()?;
         let mut best_action: Option<&Action> = None;
         let mut best_score = i32::MIN;
-        let mut best_pile = usize::MAX;
+        let mut best_pile = DiscardPileId(usize::MAX);
         let mut best_hand_index = usize::MAX;
         for action in legal_actions {
             if let Action::Discard {
@@ -63,7 +55,7 @@ impl Heuristic18Bot {
                 discard_pile,
             } = action
             {
-                let score = Self::score_discard(state, player, *hand_index, *discard_pile);
+                let score = Self::score_discard(state, player, *hand_index, discard_pile.0);
                 let is_better = if score > best_score {
                     true
                 } else if score == best_score {
@@ -137,17 +129,17 @@ impl Heuristic18Bot {
     }
 
     fn best_stock_play(state: &GameStateView, legal_actions: &[Action]) -> Option<Action> {
-        let player = Self::self_player(state);
+        let player = state.self_player_state()?;
         let mut best_action: Option<&Action> = None;
         let mut best_score = i32::MIN;
-        let mut best_build = usize::MAX;
+        let mut best_build = BuildPileId(usize::MAX);
         for action in legal_actions {
             if let Action::Play {
                 source: CardSource::Stock,
                 build_pile,
             } = action
             {
-                let score = Self::score_stock_play(state, player, *build_pile);
+                let score = Self::score_stock_play(state, player, build_pile.0);
                 let is_better = if score > best_score {
                     true
                 } else if score == best_score {
@@ -0,0 +1,501 @@
+use std::sync::Arc;
+
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use serde::{Deserialize, Serialize};
+
+use crate::action::{Action, CardSource, LegalSet, PlayerId};
+use crate::bot::{Bot, rank_scored};
+use crate::bots::blocking::{DiscardSafety, UnlockChances};
+use crate::bots::endgame::{EndgameSolution, EndgameSolver};
+use crate::bots::opening::OpeningBook;
+use crate::bots::planning::{
+    RefillLookahead, TurnContext, TurnEnd, TurnEnumerator, TurnPlan, TurnPosition,
+};
+use crate::bots::search::SharedTable;
+use crate::bots::strength::Strength;
+use crate::bots::wilds::WildPolicy;
+use crate::card::Card;
+use crate::interrupt::Interrupter;
+use crate::state::{GameStateView, unlock_distance};
+
+/// Terms of the [`Heuristic19Bot`] plan score. Each is the score per unit of the term
+/// (per card, per pile step, ...); `discard_shape` and `unlock_risk` scale the bot's
+/// built-in shape bonus and unlock penalty. `refill_chain` is the score per card the
+/// [`RefillLookahead`] expects to be playable next turn; it is off (zero) by default as
+/// the lookahead samples every plan's refill. `round_unlock` is the penalty per unit of
+/// the [`DiscardSafety`] chance that an opponent plays their stock card before the next
+/// turn; it is off by default for the same reason. `wild_hold` is the penalty per unit of
+/// the [`WildPolicy`] hold value of the wilds a plan spends without stock progress; off by
+/// default, as it replays every plan.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PlanWeights {
+    pub stock_card: f64,
+    pub hand_play: f64,
+    pub discard_play: f64,
+    pub wild_spent: f64,
+    pub stock_distance: f64,
+    pub hand_emptied: f64,
+    pub stock_revealed: f64,
+    pub discard_shape: f64,
+    pub held_wild: f64,
+    pub unlock_risk: f64,
+    #[serde(default)]
+    pub refill_chain: f64,
+    #[serde(default)]
+    pub round_unlock: f64,
+    #[serde(default)]
+    pub wild_hold: f64,
+}
+
+impl PlanWeights {
+    /// Number of weights, the length of [`PlanWeights::to_vec`].
+    pub const LEN: usize = 13;
+
+    pub fn to_vec(&self) -> Vec<f64> {
+        vec![
+            self.stock_card,
+            self.hand_play,
+            self.discard_play,
+            self.wild_spent,
+            self.stock_distance,
+            self.hand_emptied,
+            self.stock_revealed,
+            self.discard_shape,
+            self.held_wild,
+            self.unlock_risk,
+            self.refill_chain,
+            self.round_unlock,
+            self.wild_hold,
+        ]
+    }
+
+    /// Inverse of [`PlanWeights::to_vec`]; `None` unless `values` has [`PlanWeights::LEN`]
+    /// entries.
+    pub fn from_slice(values: &[f64]) -> Option<Self> {
+        let &[
+            stock_card,
+            hand_play,
+            discard_play,
+            wild_spent,
+            stock_distance,
+            hand_emptied,
+            stock_revealed,
+            discard_shape,
+            held_wild,
+            unlock_risk,
+            refill_chain,
+            round_unlock,
+            wild_hold,
+        ] = values
+        else {
+            return None;
+        };
+        Some(Self {
+            stock_card,
+            hand_play,
+            discard_play,
+            wild_spent,
+            stock_distance,
+            hand_emptied,
+            stock_revealed,
+            discard_shape,
+            held_wild,
+            unlock_risk,
+            refill_chain,
+            round_unlock,
+            wild_hold,
+        })
+    }
+}
+
+impl Default for PlanWeights {
+    fn default() -> Self {
+        Self {
+            stock_card: 10_000.0,
+            hand_play: 10.0,
+            discard_play: 60.0,
+            wild_spent: 250.0,
+            stock_distance: 30.0,
+            hand_emptied: 800.0,
+            stock_revealed: 200.0,
+            discard_shape: 1.0,
+            held_wild: 150.0,
+            unlock_risk: 1.0,
+            refill_chain: 0.0,
+            round_unlock: 0.0,
+            wild_hold: 0.0,
+        }
+    }
+}
+
+/// Heuristic 19 bot ("turn planner"): chooses plays and the closing discard jointly.
+///
+/// Instead of picking plays first and discarding greedily afterwards, every reachable
+/// turn plan (plays..., discard) is enumerated and the position at the end of the turn
+/// is scored:
+/// - Stock progress dominates (playing stock cards, winning outright).
+/// - Hand quality for next turn: held Skip-Bo cards and an emptied hand (fresh draw)
+///   are rewarded; spending wilds without stock progress is penalized.
+/// - Discard shape: stacking duplicates or placing n below n+1 is rewarded, deep piles
+///   are penalized.
+/// - Opponent unlock risk: build piles left on an opponent's stock value are penalized,
+///   weighted by how soon that opponent acts.
+/// - Optionally, the kept hand: plays expected next turn after sampled refills.
+/// - Optionally, sampled opponent rounds: the chance that the piles a discard leaves let
+///   an opponent reach their stock card before the next turn.
+/// - Optionally, wild conservation: wilds spent short of the stock card cost what holding
+///   them is worth.
+///
+/// The best plan is kept in a [`TurnContext`] and played out one action per call; it is
+/// recomputed as soon as the view differs from what the plan predicted, so newly
+/// revealed cards are taken into account. With three or fewer stock cards left the
+/// exact [`EndgameSolver`] takes over whenever it finds a likely win this turn. With an
+/// [`OpeningBook`], the first turn is played from the book when it knows the position.
+///
+/// Below full [`Strength`] the plan is sampled by softmax over plan scores (or picked at
+/// random on a blunder) and both searches get a smaller budget.
+pub struct Heuristic19Bot {
+    enumerator: TurnEnumerator,
+    weights: PlanWeights,
+    endgame: EndgameSolver,
+    context: TurnContext,
+    refill: RefillLookahead,
+    safety: DiscardSafety,
+    strength: Strength,
+    rng: StdRng,
+    book: Option<Arc<OpeningBook>>,
+}
+
+impl Heuristic19Bot {
+    /// Plan score difference treated as a clear preference when sampling plans.
+    const SCORE_SCALE: f64 = 300.0;
+
+    pub fn new() -> Self {
+        Self {
+            enumerator: TurnEnumerator::default(),
+            weights: PlanWeights::default(),
+            endgame: EndgameSolver::default(),
+            context: TurnContext::new(),
+            refill: RefillLookahead::default(),
+            safety: DiscardSafety::default(),
+            strength: Strength::MAX,
+            rng: StdRng::seed_from_u64(0x0019_5EED),
+            book: None,
+        }
+    }
+
+    /// Limit the number of simulated positions per decision.
+    pub fn with_node_limit(node_limit: usize) -> Self {
+        Self {
+            enumerator: TurnEnumerator::new(node_limit),
+            ..Self::new()
+        }
+    }
+
+    /// Play at a reduced strength (full strength when `strength` is [`Strength::MAX`]).
+    pub fn with_strength(mut self, strength: Strength) -> Self {
+        self.enumerator.node_limit = strength.node_budget(self.enumerator.node_limit);
+        self.endgame.node_limit = strength.node_budget(self.endgame.node_limit);
+        self.strength = strength;
+        self
+    }
+
+    /// Score plans with `weights` instead of the hand-tuned defaults.
+    pub fn with_weights(mut self, weights: PlanWeights) -> Self {
+        self.weights = weights;
+        self
+    }
+
+    pub fn weights(&self) -> &PlanWeights {
+        &self.weights
+    }
+
+    /// Seed the randomness used below full strength.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = StdRng::seed_from_u64(seed);
+        self
+    }
+
+    /// Keep solved endgames in `table`, e.g. one shared with other bots.
+    pub fn with_endgame_table(mut self, table: SharedTable<Option<EndgameSolution>>) -> Self {
+        self.endgame = self.endgame.with_table(table);
+        self
+    }
+
+    /// Play the first turn from `book` where it knows the position (full strength only).
+    pub fn with_opening_book(mut self, book: Arc<OpeningBook>) -> Self {
+        self.book = Some(book);
+        self
+    }
+
+    /// The whole turn the bot would plan from `state`, without playing it; what
+    /// [`OpeningBook::warm`] stores.
+    pub fn plan_turn(
+        &mut self,
+        state: &GameStateView,
+        legal_actions: &[Action],
+    ) -> Option<Vec<Action>> {
+        self.best_plan(state, &LegalSet::from_actions(legal_actions))
+    }
+
+    /// Turn-order distance from `from` to `to` (1 = acts next).
+    fn seats_after(state: &GameStateView, from: PlayerId, to: PlayerId) -> usize {
+        from.seats_until(to, state.settings.num_players)
+    }
+
+    /// Penalty for leaving build piles on values that let opponents play their stock.
+    fn unlock_risk(state: &GameStateView, position: &TurnPosition) -> i32 {
+        let mut risk = 0;
+        for opponent in state.players.iter().filter(|p| p.id != state.self_player) {
+            let Some(top) = opponent.stock_top else {
+                continue;
+            };
+            let distance = Self::seats_after(state, state.self_player, opponent.id).max(1) as i32;
+            let weight = 600 / distance;
+            let playable = position
+                .build_next
+                .iter()
+                .any(|&next| top.matches_value(next));
+            if playable && !top.is_skip_bo() {
+                // Fewer stock cards left means each unlock is closer to a loss.
+                let urgency = if opponent.stock_count <= 5 { 2 } else { 1 };
+                risk += weight * urgency;
+            }
+        }
+        risk
+    }
+
+    /// Reward a discard placement that keeps piles playable in sequence.
+    pub(crate) fn discard_shape(position: &TurnPosition, discard_pile: usize) -> i32 {
+        let pile = &position.discard_piles[discard_pile];
+        let Some(&card) = pile.last() else {
+            return 0;
+        };
+        let below = pile.len().checked_sub(2).map(|i| pile[i]);
+        let mut score = -(pile.len() as i32) * 25;
+        match (card, below) {
+            (_, None) => score += 60,
+            (Card::Number(v), Some(Card::Number(b))) if v == b => score += 400,
+            (Card::Number(v), Some(Card::Number(b))) if v + 1 == b => score += 300,
+            _ => {}
+        }
+        if card.is_skip_bo() {
+            // Burying a wild on a discard pile wastes its flexibility.
+            score -= 500;
+        }
+        score
+    }
+
+    /// Distance (in build steps) from the closest pile to the stock top.
+    pub(crate) fn stock_distance(position: &TurnPosition) -> i32 {
+        position.stock_top.map_or(0, |stock| {
+            i32::from(unlock_distance(&position.build_next, stock))
+        })
+    }
+
+    /// [`WildPolicy::spend_penalty`] summed over the plays of `actions` from the view.
+    fn wild_spend(state: &GameStateView, actions: &[Action]) -> f64 {
+        let Some(mut position) = TurnPosition::from_view(state) else {
+            return 0.0;
+        };
+        let policy = WildPolicy::new();
+        let mut spend = 0.0;
+        for action in actions {
+            spend += policy.spend_penalty(&position, action);
+            if !position.apply(action) {
+                break;
+            }
+        }
+        spend
+    }
+
+    fn score_plan(
+        weights: &PlanWeights,
+        refill: &RefillLookahead,
+        chances: &mut UnlockChances<'_>,
+        state: &GameStateView,
+        plan: &TurnPlan<'_>,
+    ) -> i32 {
+        let position = plan.position;
+        if plan.end == TurnEnd::Won {
+            return i32::MAX;
+        }
+        let count_plays = |from: fn(&CardSource) -> bool| {
+            plan.actions
+                .iter()
+                .filter(|a| matches!(a, Action::Play { source, .. } if from(source)))
+                .count() as f64
+        };
+        let played_from_hand = count_plays(|source| matches!(source, CardSource::Hand(_)));
+        let played_from_discard = count_plays(|source| matches!(source, CardSource::Discard(_)));
+        let mut score = position.stock_played as f64 * weights.stock_card;
+        score += played_from_hand * weights.hand_play + played_from_discard * weights.discard_play;
+        if position.stock_played == 0 {
+            score -= position.wilds_spent as f64 * weights.wild_spent;
+            score -= f64::from(Self::stock_distance(position)) * weights.stock_distance;
+        }
+        score += match plan.end {
+            TurnEnd::HandEmptied => weights.hand_emptied,
+            TurnEnd::StockRevealed => weights.stock_revealed,
+            _ => 0.0,
+        };
+        if let Some(Action::Discard { discard_pile, .. }) = plan.actions.last() {
+            score +=
+                f64::from(Self::discard_shape(position, discard_pile.0)) * weights.discard_shape;
+        }
+        score += position.hand.iter().filter(|c| c.is_skip_bo()).count() as f64 * weights.held_wild;
+        score -= f64::from(Self::unlock_risk(state, position)) * weights.unlock_risk;
+        if weights.refill_chain != 0.0 && plan.end == TurnEnd::Discard {
+            score += refill.outlook(state, position).playable * weights.refill_chain;
+        }
+        if weights.round_unlock != 0.0 && plan.end == TurnEnd::Discard {
+            score -= chances.get(&position.build_next) * weights.round_unlock;
+        }
+        if weights.wild_hold != 0.0 {
+            score -= Self::wild_spend(state, plan.actions) * weights.wild_hold;
+        }
+        score.round() as i32
+    }
+
+    fn best_plan(&mut self, state: &GameStateView, legal: &LegalSet) -> Option<Vec<Action>> {
+        if !self.strength.is_max() {
+            return self.sampled_plan(state, legal);
+        }
+        let mut best: Option<(i32, Vec<Action>)> = None;
+        let weights = self.weights;
+        let refill = self.refill;
+        let mut chances = self.safety.chances(state);
+        self.enumerator.for_each_plan(state, |plan| {
+            let Some(first) = plan.actions.first() else {
+                return;
+            };
+            let score = Self::score_plan(&weights, &refill, &mut chances, state, &plan);
+            if best.as_ref().is_none_or(|(s, _)| score > *s) && legal.contains(first) {
+                best = Some((score, plan.actions.to_vec()));
+            }
+        });
+        best.map(|(_, plan)| plan)
+    }
+
+    /// Plan chosen by [`Strength::choose`] among every legal plan.
+    fn sampled_plan(&mut self, state: &GameStateView, legal: &LegalSet) -> Option<Vec<Action>> {
+        let mut plans = Vec::new();
+        let mut scores = Vec::new();
+        let weights = self.weights;
+        let refill = self.refill;
+        let mut chances = self.safety.chances(state);
+        self.enumerator.for_each_plan(state, |plan| {
+            if plan
+                .actions
+                .first()
+                .is_some_and(|first| legal.contains(first))
+            {
+                scores.push(f64::from(Self::score_plan(
+                    &weights,
+                    &refill,
+                    &mut chances,
+                    state,
+                    &plan,
+                )));
+                plans.push(plan.actions.to_vec());
+            }
+        });
+        let pick = self
+            .strength
+            .choose(&mut self.rng, &scores, Self::SCORE_SCALE)?;
+        Some(plans.swap_remove(pick))
+    }
+
+    /// Score of the best plan starting with each legal first action.
+    fn first_action_scores(
+        &mut self,
+        state: &GameStateView,
+        legal: &LegalSet,
+    ) -> Vec<(Action, f32)> {
+        let mut best: Vec<(Action, f32)> = Vec::new();
+        let weights = self.weights;
+        let refill = self.refill;
+        let mut chances = self.safety.chances(state);
+        self.enumerator.for_each_plan(state, |plan| {
+            let Some(first) = plan.actions.first().filter(|first| legal.contains(first)) else {
+                return;
+            };
+            let score = Self::score_plan(&weights, &refill, &mut chances, state, &plan) as f32;
+            match best.iter_mut().find(|(action, _)| action == first) {
+                Some(entry) => entry.1 = entry.1.max(score),
+                None => best.push((first.clone(), score)),
+            }
+        });
+        best
+    }
+}
+
+impl Default for Heuristic19Bot {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Bot for Heuristic19Bot {
+    fn on_turn_start(&mut self, _state: &GameStateView) {
+        self.context.clear();
+        self.endgame.new_search();
+    }
+
+    fn limit_search(&mut self, nodes: usize) {
+        self.enumerator.node_limit = nodes;
+        self.endgame.node_limit = nodes;
+    }
+
+    fn interrupt_with(&mut self, interrupter: &Interrupter) {
+        self.enumerator.interrupter = interrupter.clone();
+        self.endgame.interrupter = interrupter.clone();
+    }
+
+    fn select_action(&mut self, state: &GameStateView, legal_actions: &[Action]) -> Action {
+        assert!(
+            !legal_actions.is_empty(),
+            "heuristic 19 bot requires at least one legal action"
+        );
+        if let Some(action) = self.endgame.recommend(state, legal_actions) {
+            self.context.clear();
+            return action;
+        }
+        let legal = LegalSet::from_actions(legal_actions);
+        if let Some(action) = self.context.next_action(state, &legal) {
+            return action;
+        }
+        if self.strength.is_max()
+            && let Some(plan) = self
+                .book
+                .as_ref()
+                .and_then(|book| book.lookup(state, &legal))
+            && let Some(action) = self.context.start_plan(state, plan)
+        {
+            return action;
+        }
+        self.best_plan(state, &legal)
+            .and_then(|plan| self.context.start_plan(state, plan))
+            .unwrap_or_else(|| legal_actions[0].clone())
+    }
+
+    /// Legal actions by the score of the best plan they start, after the action
+    /// [`Bot::select_action`] picks (which may continue an earlier plan or come from the
+    /// endgame solver). Actions that start no enumerated plan come last.
+    fn rank_actions(
+        &mut self,
+        state: &GameStateView,
+        legal_actions: &[Action],
+    ) -> Vec<(Action, f32)> {
+        let chosen = self.select_action(state, legal_actions);
+        let legal = LegalSet::from_actions(legal_actions);
+        let mut scored = self.first_action_scores(state, &legal);
+        for action in legal_actions {
+            if !scored.iter().any(|(a, _)| a == action) {
+                scored.push((action.clone(), f32::NEG_INFINITY));
+            }
+        }
+        rank_scored(chosen, scored)
+    }
+}
@@ -1,7 +1,7 @@
-use crate::action::{Action, CardSource};
+use crate::action::{Action, BuildPileId, CardSource, DiscardPileId};
 use crate::bot::Bot;
-use crate::card::{Card, MAX_CARD_VALUE, MIN_CARD_VALUE};
-use crate::state::{GameStateView, PlayerPublicState};
+use crate::card::{Card, MAX_CARD_VALUE, MAX_DISCARD_PILES, MIN_CARD_VALUE};
+use crate::state::GameStateView;
 
 /// Heuristic 3 bot
 /// Strategy:
@@ -17,20 +17,14 @@ impl Heuristic3Bot {
         Self
     }
 
-    fn self_player<'a>(state: &'a GameStateView) -> &'a PlayerPublicState {
-        state
-            .players
-            .iter()
-            .find(|p| p.id == state.self_player)
-            .expect("self player state must be present")
-    }
-
     /// Discard scoring: identical to heuristic_2 except we IGNORE card priority.
     fn score_discard(state: &GameStateView, hand_index: usize, discard_pile: usize) -> i32 {
         let Some(card) = state.hand.get(hand_index).copied() else {
             return i32::MIN / 2;
         };
-        let player = Self::self_player(state);
+        let Some(player) = state.self_player_state() else {
+            return i32::MIN / 2;
+        };
         let existing_top = player
             .discard_piles
             .get(discard_pile)
@@ -78,7 +72,7 @@ impl Heuristic3Bot {
     /// Try to plan a minimal sequence of plays (using hand + discard tops) to make the stock
     /// card playable, and return the first action of that plan if it's feasible.
     fn can_play_stock(state: &GameStateView, legal_actions: &[Action]) -> Option<Action> {
-        let player = Self::self_player(state);
+        let player = state.self_player_state()?;
         let stock = player.stock_top?;
 
         // Fast path: if stock is immediately playable on any pile, play it.
@@ -91,7 +85,7 @@ impl Heuristic3Bot {
                 .max_by_key(|(_, pile)| (pile.next_value, pile.cards.len() as u8))?;
             let action = Action::Play {
                 source: CardSource::Stock,
-                build_pile: best_idx,
+                build_pile: BuildPileId(best_idx),
             };
             if legal_actions.contains(&action) {
                 return Some(action);
@@ -109,7 +103,7 @@ impl Heuristic3Bot {
             {
                 let action = Action::Play {
                     source: CardSource::Stock,
-                    build_pile: best_idx,
+                    build_pile: BuildPileId(best_idx),
                 };
                 if legal_actions.contains(&action) {
                     return Some(action);
@@ -158,7 +152,7 @@ impl Heuristic3Bot {
 
             // Prepare working copies of availability for this pile's planning attempt.
             let mut used_hand: Vec<bool> = vec![false; state.hand.len()];
-            let mut used_discard: [bool; 4] = [false; 4];
+            let mut used_discard = [false; MAX_DISCARD_PILES];
             let skipbo_discards_left = skipbo_discards.clone();
             let skipbo_hands_left = skipbo_hands.clone();
 
@@ -183,11 +177,11 @@ impl Heuristic3Bot {
                 // Then try exact from hand.
                 if picked.is_none() {
                     for &src in &by_value[need as usize] {
-                        if let SourceKind::Hand(h) = src {
-                            if !used_hand[h] {
-                                picked = Some(src);
-                                break;
-                            }
+                        if let SourceKind::Hand(h) = src
+                            && !used_hand[h]
+                        {
+                            picked = Some(src);
+                            break;
                         }
                     }
                 }
@@ -217,15 +211,15 @@ impl Heuristic3Bot {
                     SourceKind::Discard(d) => {
                         used_discard[d] = true;
                         actions.push(Action::Play {
-                            source: CardSource::Discard(d),
-                            build_pile: pile_idx,
+                            source: CardSource::Discard(DiscardPileId(d)),
+                            build_pile: BuildPileId(pile_idx),
                         });
                     }
                     SourceKind::Hand(h) => {
                         used_hand[h] = true;
                         actions.push(Action::Play {
                             source: CardSource::Hand(h),
-                            build_pile: pile_idx,
+                            build_pile: BuildPileId(pile_idx),
                         });
                     }
                 }
@@ -259,7 +253,7 @@ impl Heuristic3Bot {
             if actions.is_empty() {
                 let action = Action::Play {
                     source: CardSource::Stock,
-                    build_pile: pile_idx,
+                    build_pile: BuildPileId(pile_idx),
                 };
                 if legal_actions.contains(&action) {
                     return Some(action);
@@ -303,7 +297,7 @@ impl Bot for Heuristic3Bot {
                 discard_pile,
             } = *action
             {
-                let score = Self::score_discard(state, hand_index, discard_pile);
+                let score = Self::score_discard(state, hand_index, discard_pile.0);
                 if best.as_ref().map(|(s, _)| score > *s).unwrap_or(true) {
                     best = Some((score, action.clone()));
                 }
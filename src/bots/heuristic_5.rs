@@ -1,7 +1,7 @@
-use crate::action::{Action, CardSource};
+use crate::action::{Action, BuildPileId, CardSource, DiscardPileId};
 use crate::bot::Bot;
-use crate::card::{Card, MAX_CARD_VALUE, MIN_CARD_VALUE};
-use crate::state::{GameStateView, PlayerPublicState};
+use crate::card::{Card, MAX_CARD_VALUE, MAX_DISCARD_PILES, MIN_CARD_VALUE};
+use crate::state::GameStateView;
 
 /// Heuristic 5 bot
 /// Strategy:
@@ -10,7 +10,7 @@ use crate::state::{GameStateView, PlayerPublicState};
 /// 2) Otherwise, only play a number card if BOTH:
 ///    - its value is strictly above the current stock value, and
 ///    - its value is greater than or equal to 6.
-///    Skip-Bo cards are ignored for this rule.
+///      Skip-Bo cards are ignored for this rule.
 /// 3) If no such plays exist, select a discard using the heuristic-3 style score (ignoring
 ///    card priority). If none available, fall back to the first legal action.
 pub struct Heuristic5Bot;
@@ -20,20 +20,14 @@ impl Heuristic5Bot {
         Self
     }
 
-    fn self_player<'a>(state: &'a GameStateView) -> &'a PlayerPublicState {
-        state
-            .players
-            .iter()
-            .find(|p| p.id == state.self_player)
-            .expect("self player state must be present")
-    }
-
     /// Discard scoring: identical to heuristic_3 except we IGNORE card priority.
     fn score_discard(state: &GameStateView, hand_index: usize, discard_pile: usize) -> i32 {
         let Some(card) = state.hand.get(hand_index).copied() else {
             return i32::MIN / 2;
         };
-        let player = Self::self_player(state);
+        let Some(player) = state.self_player_state() else {
+            return i32::MIN / 2;
+        };
         let existing_top = player
             .discard_piles
             .get(discard_pile)
@@ -80,7 +74,7 @@ impl Heuristic5Bot {
     /// Try to plan a minimal sequence of plays (using hand + discard tops) to make the stock
     /// card playable, and return the first action of that plan if it's feasible.
     fn can_play_stock(state: &GameStateView, legal_actions: &[Action]) -> Option<Action> {
-        let player = Self::self_player(state);
+        let player = state.self_player_state()?;
         let stock = player.stock_top?;
 
         // Fast path: if stock is immediately playable on any pile, play it.
@@ -93,7 +87,7 @@ impl Heuristic5Bot {
                 .max_by_key(|(_, pile)| (pile.next_value, pile.cards.len() as u8))?;
             let action = Action::Play {
                 source: CardSource::Stock,
-                build_pile: best_idx,
+                build_pile: BuildPileId(best_idx),
             };
             if legal_actions.contains(&action) {
                 return Some(action);
@@ -111,7 +105,7 @@ impl Heuristic5Bot {
             {
                 let action = Action::Play {
                     source: CardSource::Stock,
-                    build_pile: best_idx,
+                    build_pile: BuildPileId(best_idx),
                 };
                 if legal_actions.contains(&action) {
                     return Some(action);
@@ -142,7 +136,7 @@ impl Heuristic5Bot {
                 Card::SkipBo => skipbo_hands.push(idx),
             }
         }
-        for (d_idx, pile) in Self::self_player(state).discard_piles.iter().enumerate() {
+        for (d_idx, pile) in state.self_player_state()?.discard_piles.iter().enumerate() {
             if let Some(card) = pile.last().copied() {
                 match card {
                     Card::Number(v) => by_value[v as usize].push(SourceKind::Discard(d_idx)),
@@ -160,7 +154,7 @@ impl Heuristic5Bot {
 
             // Prepare working copies of availability for this pile's planning attempt.
             let mut used_hand: Vec<bool> = vec![false; state.hand.len()];
-            let mut used_discard: [bool; 4] = [false; 4];
+            let mut used_discard = [false; MAX_DISCARD_PILES];
             let skipbo_discards_left = skipbo_discards.clone();
             let skipbo_hands_left = skipbo_hands.clone();
 
@@ -185,11 +179,11 @@ impl Heuristic5Bot {
                 // Then try exact from hand.
                 if picked.is_none() {
                     for &src in &by_value[need as usize] {
-                        if let SourceKind::Hand(h) = src {
-                            if !used_hand[h] {
-                                picked = Some(src);
-                                break;
-                            }
+                        if let SourceKind::Hand(h) = src
+                            && !used_hand[h]
+                        {
+                            picked = Some(src);
+                            break;
                         }
                     }
                 }
@@ -218,15 +212,15 @@ impl Heuristic5Bot {
                     SourceKind::Discard(d) => {
                         used_discard[d] = true;
                         actions.push(Action::Play {
-                            source: CardSource::Discard(d),
-                            build_pile: pile_idx,
+                            source: CardSource::Discard(DiscardPileId(d)),
+                            build_pile: BuildPileId(pile_idx),
                         });
                     }
                     SourceKind::Hand(h) => {
                         used_hand[h] = true;
                         actions.push(Action::Play {
                             source: CardSource::Hand(h),
-                            build_pile: pile_idx,
+                            build_pile: BuildPileId(pile_idx),
                         });
                     }
                 }
@@ -260,7 +254,7 @@ impl Heuristic5Bot {
             if actions.is_empty() {
                 let action = Action::Play {
                     source: CardSource::Stock,
-                    build_pile: pile_idx,
+                    build_pile: BuildPileId(pile_idx),
                 };
                 if legal_actions.contains(&action) {
                     return Some(action);
@@ -286,7 +280,10 @@ impl Heuristic5Bot {
                     Card::SkipBo => None,
                 },
                 CardSource::Discard(d) => {
-                    match Self::self_player(state).discard_piles[d].last().copied()? {
+                    match state.self_player_state()?.discard_piles[d]
+                        .last()
+                        .copied()?
+                    {
                         Card::Number(v) => Some(v),
                         Card::SkipBo => None,
                     }
@@ -318,30 +315,29 @@ impl Bot for Heuristic5Bot {
         }
 
         // 2) Otherwise, play only number cards where value > stock value AND value >= 6.
-        let stock_value = match Self::self_player(state).stock_top {
+        let stock_value = match state.self_player_state().and_then(|p| p.stock_top) {
             Some(Card::Number(v)) => v,
             Some(Card::SkipBo) | None => 1, // treat Skip-Bo/None as minimal numeric baseline
         };
 
         let mut best_play: Option<(u8, usize, Action)> = None; // (card_value, pile_len, action)
         for action in legal_actions.iter() {
-            if let Some(v) = Self::played_card_value(state, action) {
-                if v >= 6 && v > stock_value {
-                    let pile_len = match action {
-                        Action::Play { build_pile, .. } => {
-                            state.build_piles[*build_pile].cards.len()
-                        }
-                        _ => 0,
-                    };
-                    let better = match &best_play {
-                        None => true,
-                        Some((best_v, best_len, _)) => {
-                            v > *best_v || (v == *best_v && pile_len > *best_len)
-                        }
-                    };
-                    if better {
-                        best_play = Some((v, pile_len, action.clone()));
+            if let Some(v) = Self::played_card_value(state, action)
+                && v >= 6
+                && v > stock_value
+            {
+                let pile_len = match action {
+                    Action::Play { build_pile, .. } => state.build_piles[*build_pile].cards.len(),
+                    _ => 0,
+                };
+                let better = match &best_play {
+                    None => true,
+                    Some((best_v, best_len, _)) => {
+                        v > *best_v || (v == *best_v && pile_len > *best_len)
                     }
+                };
+                if better {
+                    best_play = Some((v, pile_len, action.clone()));
                 }
             }
         }
@@ -357,7 +353,7 @@ impl Bot for Heuristic5Bot {
                 discard_pile,
             } = *action
             {
-                let score = Self::score_discard(state, hand_index, discard_pile);
+                let score = Self::score_discard(state, hand_index, discard_pile.0);
                 if best.as_ref().map(|(s, _)| score > *s).unwrap_or(true) {
                     best = Some((score, action.clone()));
                 }
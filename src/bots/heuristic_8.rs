@@ -1,7 +1,8 @@
-use crate::action::{Action, CardSource};
+use crate::action::{Action, BuildPileId, CardSource};
 use crate::bot::Bot;
-use crate::card::{Card, MAX_CARD_VALUE, MIN_CARD_VALUE};
-use crate::state::{GameStateView, PlayerPublicState};
+use crate::bots::planning::StockPlan;
+use crate::card::{Card, MAX_CARD_VALUE};
+use crate::state::GameStateView;
 
 /// Heuristic 8 bot (based on Heuristic 5)
 /// Difference from Heuristic 5:
@@ -15,19 +16,6 @@ impl Heuristic8Bot {
         Self
     }
 
-    fn self_player<'a>(state: &'a GameStateView) -> &'a PlayerPublicState {
-        state
-            .players
-            .iter()
-            .find(|p| p.id == state.self_player)
-            .expect("self player state must be present")
-    }
-
-    fn next_player<'a>(state: &'a GameStateView) -> Option<&'a PlayerPublicState> {
-        let next_id = (state.current_player + 1) % state.settings.num_players;
-        state.players.iter().find(|p| p.id == next_id)
-    }
-
     /// Returns true if the given play action would set the pile to a next_value
     /// that matches the next player's numeric stock-top card; such plays should
     /// be avoided by this heuristic.
@@ -45,13 +33,16 @@ impl Heuristic8Bot {
                 None => return false,
             },
             CardSource::Discard(d) => {
-                match Self::self_player(state).discard_piles[d].last().copied() {
+                match state
+                    .self_player_state()
+                    .and_then(|p| p.discard_piles[d].last().copied())
+                {
                     Some(Card::Number(v)) => v,
                     Some(Card::SkipBo) => pile.next_value,
                     None => return false,
                 }
             }
-            CardSource::Stock => match Self::self_player(state).stock_top {
+            CardSource::Stock => match state.self_player_state().and_then(|p| p.stock_top) {
                 Some(Card::Number(v)) => v,
                 Some(Card::SkipBo) => pile.next_value,
                 None => return false,
@@ -63,7 +54,7 @@ impl Heuristic8Bot {
         } else {
             played_value + 1
         };
-        if let Some(next_player) = Self::next_player(state) {
+        if let Some(next_player) = state.next_player_state() {
             matches!(next_player.stock_top, Some(Card::Number(v)) if v == above)
         } else {
             false
@@ -75,7 +66,9 @@ impl Heuristic8Bot {
         let Some(card) = state.hand.get(hand_index).copied() else {
             return i32::MIN / 2;
         };
-        let player = Self::self_player(state);
+        let Some(player) = state.self_player_state() else {
+            return i32::MIN / 2;
+        };
         let existing_top = player
             .discard_piles
             .get(discard_pile)
@@ -91,38 +84,10 @@ impl Heuristic8Bot {
         1_000 + duplicate_bonus - spacing_penalty - (hand_index as i32 * 10)
     }
 
-    /// Compute the sequence of required values that must be played (in order)
-    /// on a specific build pile so that the stock card becomes playable on that pile.
-    /// Returns empty list when stock can be played immediately.
-    fn required_values_for_pile(next_value: u8, stock: Card) -> Vec<u8> {
-        match stock {
-            Card::SkipBo => Vec::new(),
-            Card::Number(s) => {
-                if next_value == s {
-                    return Vec::new();
-                }
-                let mut req = Vec::new();
-                if next_value < s {
-                    for v in next_value..s {
-                        req.push(v);
-                    }
-                } else {
-                    for v in next_value..=MAX_CARD_VALUE {
-                        req.push(v);
-                    }
-                    for v in MIN_CARD_VALUE..s {
-                        req.push(v);
-                    }
-                }
-                req
-            }
-        }
-    }
-
     /// Try to plan a minimal sequence of plays (using hand + discard tops) to make the stock
     /// card playable, and return the first action of that plan if it's feasible.
     fn can_play_stock(state: &GameStateView, legal_actions: &[Action]) -> Option<Action> {
-        let player = Self::self_player(state);
+        let player = state.self_player_state()?;
         let stock = player.stock_top?;
 
         // Fast path: if stock is immediately playable on any pile, play it.
@@ -136,7 +101,7 @@ impl Heuristic8Bot {
             {
                 let action = Action::Play {
                     source: CardSource::Stock,
-                    build_pile: best_idx,
+                    build_pile: BuildPileId(best_idx),
                 };
                 if legal_actions.contains(&action) && !Self::should_block_play(state, &action) {
                     return Some(action);
@@ -155,7 +120,7 @@ impl Heuristic8Bot {
             {
                 let action = Action::Play {
                     source: CardSource::Stock,
-                    build_pile: best_idx,
+                    build_pile: BuildPileId(best_idx),
                 };
                 if legal_actions.contains(&action) && !Self::should_block_play(state, &action) {
                     return Some(action);
@@ -163,170 +128,10 @@ impl Heuristic8Bot {
             }
         }
 
-        // Build counts and index maps for available cards: hand and discard tops.
-        #[derive(Clone, Copy)]
-        enum SourceKind {
-            Hand(usize),
-            Discard(usize),
-        }
-
-        // For quick lookup by required value, store lists of sources that can satisfy that value.
-        // We'll prefer exact-number matches from discards, then hand, then Skip-Bo (discard first).
-        let mut by_value: [Vec<SourceKind>; (MAX_CARD_VALUE as usize) + 1] = Default::default();
-        // Index 0 unused; values 1..=12 used.
-
-        // Track skip-bo sources separately for prioritization.
-        let mut skipbo_discards: Vec<usize> = Vec::new();
-        let mut skipbo_hands: Vec<usize> = Vec::new();
-
-        for (idx, card) in state.hand.iter().copied().enumerate() {
-            match card {
-                Card::Number(v) => by_value[v as usize].push(SourceKind::Hand(idx)),
-                Card::SkipBo => skipbo_hands.push(idx),
-            }
-        }
-        for (d_idx, pile) in Self::self_player(state).discard_piles.iter().enumerate() {
-            if let Some(card) = pile.last().copied() {
-                match card {
-                    Card::Number(v) => by_value[v as usize].push(SourceKind::Discard(d_idx)),
-                    Card::SkipBo => skipbo_discards.push(d_idx),
-                }
-            }
-        }
-
-        // Helper to attempt planning for a specific pile. Returns planned actions
-        // (excluding the final stock play), or None if impossible.
-        let mut best_plan: Option<(usize, Vec<Action>)> = None;
-
-        for (pile_idx, pile) in state.build_piles.iter().enumerate() {
-            let required = Self::required_values_for_pile(pile.next_value, stock);
-
-            // Prepare working copies of availability for this pile's planning attempt.
-            let mut used_hand: Vec<bool> = vec![false; state.hand.len()];
-            let mut used_discard: [bool; 4] = [false; 4];
-            let skipbo_discards_left = skipbo_discards.clone();
-            let skipbo_hands_left = skipbo_hands.clone();
-
-            let mut actions: Vec<Action> = Vec::new();
-            let mut possible = true;
-
-            // Current next_value progresses as we plan.
-            let mut _sim_next = pile.next_value;
-
-            for need in required.iter().copied() {
-                // Try exact from discard first among those not used yet.
-                let mut picked: Option<SourceKind> = None;
-                for &src in &by_value[need as usize] {
-                    match src {
-                        SourceKind::Discard(d) if !used_discard[d] => {
-                            picked = Some(src);
-                            break;
-                        }
-                        _ => {}
-                    }
-                }
-                // Then try exact from hand.
-                if picked.is_none() {
-                    for &src in &by_value[need as usize] {
-                        if let SourceKind::Hand(h) = src {
-                            if !used_hand[h] {
-                                picked = Some(src);
-                                break;
-                            }
-                        }
-                    }
-                }
-                // Then use a Skip-Bo from discard, else hand.
-                if picked.is_none() {
-                    if let Some(d) = skipbo_discards_left
-                        .iter()
-                        .copied()
-                        .find(|&d| !used_discard[d])
-                    {
-                        picked = Some(SourceKind::Discard(d));
-                    } else if let Some(h) =
-                        skipbo_hands_left.iter().copied().find(|&h| !used_hand[h])
-                    {
-                        picked = Some(SourceKind::Hand(h));
-                    }
-                }
-
-                let Some(src) = picked else {
-                    possible = false;
-                    break;
-                };
-
-                // Record action and mark usage.
-                match src {
-                    SourceKind::Discard(d) => {
-                        used_discard[d] = true;
-                        let action = Action::Play {
-                            source: CardSource::Discard(d),
-                            build_pile: pile_idx,
-                        };
-                        if Self::should_block_play(state, &action) {
-                            possible = false;
-                            break;
-                        }
-                        actions.push(action);
-                    }
-                    SourceKind::Hand(h) => {
-                        used_hand[h] = true;
-                        let action = Action::Play {
-                            source: CardSource::Hand(h),
-                            build_pile: pile_idx,
-                        };
-                        if Self::should_block_play(state, &action) {
-                            possible = false;
-                            break;
-                        }
-                        actions.push(action);
-                    }
-                }
-
-                // Advance simulated next value.
-                _sim_next = if _sim_next == MAX_CARD_VALUE {
-                    1
-                } else {
-                    _sim_next + 1
-                };
-            }
-
-            if !possible {
-                continue;
-            }
-
-            // Choose best plan by fewest required actions; tie-breaker: more progressed piles.
-            match &mut best_plan {
-                None => best_plan = Some((pile_idx, actions)),
-                Some((_best_idx, best_actions)) => {
-                    if actions.len() < best_actions.len() {
-                        *best_actions = actions;
-                        *_best_idx = pile_idx;
-                    }
-                }
-            }
-        }
-
-        // If we have a plan, return the first action (or the stock play if no prerequisites).
-        if let Some((pile_idx, actions)) = best_plan {
-            if actions.is_empty() {
-                let action = Action::Play {
-                    source: CardSource::Stock,
-                    build_pile: pile_idx,
-                };
-                if legal_actions.contains(&action) && !Self::should_block_play(state, &action) {
-                    return Some(action);
-                }
-            } else {
-                let first = actions[0].clone();
-                if legal_actions.contains(&first) && !Self::should_block_play(state, &first) {
-                    return Some(first);
-                }
-            }
-        }
-
-        None
+        let plan = StockPlan::best_with(state, |a| !Self::should_block_play(state, a))?;
+        let first = plan.first_action();
+        (legal_actions.contains(first) && !Self::should_block_play(state, first))
+            .then(|| first.clone())
     }
 
     /// Extract the numeric card tied to a legal play action, if any.
@@ -339,12 +144,15 @@ impl Heuristic8Bot {
                     Card::SkipBo => None,
                 },
                 CardSource::Discard(d) => {
-                    match Self::self_player(state).discard_piles[d].last().copied()? {
+                    match state.self_player_state()?.discard_piles[d]
+                        .last()
+                        .copied()?
+                    {
                         Card::Number(v) => Some(v),
                         Card::SkipBo => None,
                     }
                 }
-                CardSource::Stock => match Self::self_player(state).stock_top? {
+                CardSource::Stock => match state.self_player_state()?.stock_top? {
                     Card::Number(v) => Some(v),
                     Card::SkipBo => None,
                 },
@@ -374,7 +182,7 @@ impl Bot for Heuristic8Bot {
         }
 
         // 2) Otherwise, play only number cards where value > stock value AND value >= 6.
-        let stock_value = match Self::self_player(state).stock_top {
+        let stock_value = match state.self_player_state().and_then(|p| p.stock_top) {
             Some(Card::Number(v)) => v,
             Some(Card::SkipBo) | None => 1, // treat Skip-Bo/None as minimal numeric baseline
         };
@@ -387,23 +195,22 @@ impl Bot for Heuristic8Bot {
             if Self::should_block_play(state, action) {
                 continue;
             }
-            if let Some(v) = Self::played_card_value(state, action) {
-                if v >= 6 && v > stock_value {
-                    let pile_len = match action {
-                        Action::Play { build_pile, .. } => {
-                            state.build_piles[*build_pile].cards.len()
-                        }
-                        _ => 0,
-                    };
-                    let better = match &best_play {
-                        None => true,
-                        Some((best_v, best_len, _)) => {
-                            v > *best_v || (v == *best_v && pile_len > *best_len)
-                        }
-                    };
-                    if better {
-                        best_play = Some((v, pile_len, action.clone()));
+            if let Some(v) = Self::played_card_value(state, action)
+                && v >= 6
+                && v > stock_value
+            {
+                let pile_len = match action {
+                    Action::Play { build_pile, .. } => state.build_piles[*build_pile].cards.len(),
+                    _ => 0,
+                };
+                let better = match &best_play {
+                    None => true,
+                    Some((best_v, best_len, _)) => {
+                        v > *best_v || (v == *best_v && pile_len > *best_len)
                     }
+                };
+                if better {
+                    best_play = Some((v, pile_len, action.clone()));
                 }
             }
         }
@@ -419,7 +226,7 @@ impl Bot for Heuristic8Bot {
                 discard_pile,
             } = *action
             {
-                let score = Self::score_discard(state, hand_index, discard_pile);
+                let score = Self::score_discard(state, hand_index, discard_pile.0);
                 if best.as_ref().map(|(s, _)| score > *s).unwrap_or(true) {
                     best = Some((score, action.clone()));
                 }
@@ -5,14 +5,46 @@ use crate::bot::Bot;
 use crate::state::GameStateView;
 use crate::visualize::{describe_action, render_state};
 
+/// Clears the terminal, its scrollback included, and homes the cursor.
+const CLEAR_SCREEN: &str = "\x1b[2J\x1b[3J\x1b[H";
+
 /// Interactive bot that queries a human via standard input.
 pub struct HumanBot {
     name: String,
+    privacy_screen: bool,
+    /// Turn number of the last decision, to spot the start of a turn.
+    turn: Option<usize>,
 }
 
 impl HumanBot {
     pub fn new(name: impl Into<String>) -> Self {
-        Self { name: name.into() }
+        Self {
+            name: name.into(),
+            privacy_screen: false,
+            turn: None,
+        }
+    }
+
+    /// For hot-seat games on a shared terminal: clear the screen when this player's turn
+    /// ends, and at the start of their next turn wait for Enter behind a blank screen
+    /// before showing their hand.
+    pub fn with_privacy_screen(mut self) -> Self {
+        self.privacy_screen = true;
+        self
+    }
+
+    fn pass_terminal(&self) {
+        print!("{CLEAR_SCREEN}");
+        println!("Pass the laptop to {}, then press Enter.", self.name);
+        let mut input = String::new();
+        if io::stdin().read_line(&mut input).is_err() {
+            eprintln!("failed to read input");
+        }
+        if input.trim().eq_ignore_ascii_case("q") || input.trim().eq_ignore_ascii_case("quit") {
+            println!("Exiting game at user's request.");
+            std::process::exit(0);
+        }
+        print!("{CLEAR_SCREEN}");
     }
 }
 
@@ -28,6 +60,10 @@ impl Bot for HumanBot {
             !legal_actions.is_empty(),
             "at least one legal action must exist"
         );
+        if self.privacy_screen && self.turn != Some(state.turn_number) {
+            self.pass_terminal();
+        }
+        self.turn = Some(state.turn_number);
         loop {
             println!(
                 "\n=== {}'s turn (player {}) ===",
@@ -65,6 +101,11 @@ impl Bot for HumanBot {
             if let Some(action) = legal_actions.get(choice) {
                 let action = action.clone();
                 println!("You selected: {}", describe_action(state, &action));
+                if self.privacy_screen && !matches!(action, Action::Play { .. }) {
+                    // Discarding or ending the turn hands over to the next player.
+                    print!("{CLEAR_SCREEN}");
+                    let _ = io::stdout().flush();
+                }
                 return action;
             }
             println!("Index out of range. Please choose a valid option.");
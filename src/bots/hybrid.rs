@@ -0,0 +1,201 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::action::{Action, PlayerId};
+use crate::bot::Bot;
+use crate::interrupt::Interrupter;
+use crate::state::{FullStateView, GameStateView};
+
+/// Softmax temperature for primary bots whose scores are logits.
+pub const DEFAULT_TEMPERATURE: f64 = 1.0;
+
+/// When a [`HybridBot`] trusts its primary bot.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Confidence {
+    /// The top action's probability is at least this.
+    MinProb(f64),
+    /// The entropy (in nats) of the action distribution is at most this.
+    MaxEntropy(f64),
+}
+
+impl Default for Confidence {
+    fn default() -> Self {
+        Confidence::MinProb(0.5)
+    }
+}
+
+impl Confidence {
+    /// Whether `probs`, a distribution over the legal actions with the top action first,
+    /// is confident enough.
+    pub fn accepts(self, probs: &[f64]) -> bool {
+        match self {
+            Confidence::MinProb(min) => probs.first().is_some_and(|&p| p >= min),
+            Confidence::MaxEntropy(max) => entropy(probs) <= max,
+        }
+    }
+}
+
+impl fmt::Display for Confidence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Confidence::MinProb(min) => write!(f, "min_prob={min}"),
+            Confidence::MaxEntropy(max) => write!(f, "max_entropy={max}"),
+        }
+    }
+}
+
+impl FromStr for Confidence {
+    type Err = String;
+
+    /// `min_prob=P` (0 to 1) or `max_entropy=H` (nats, non-negative).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (key, value) = s
+            .split_once('=')
+            .ok_or_else(|| format!("expected min_prob=P or max_entropy=H, got {s}"))?;
+        let value: f64 = value
+            .trim()
+            .parse()
+            .map_err(|_| format!("invalid confidence threshold: {s}"))?;
+        match key.trim().to_ascii_lowercase().as_str() {
+            "min_prob" if (0.0..=1.0).contains(&value) => Ok(Confidence::MinProb(value)),
+            "max_entropy" if value >= 0.0 => Ok(Confidence::MaxEntropy(value)),
+            _ => Err(format!("invalid confidence threshold: {s}")),
+        }
+    }
+}
+
+/// Softmax of `scores` divided by `temperature`, in the given order.
+pub fn softmax(scores: &[f32], temperature: f64) -> Vec<f64> {
+    let temperature = temperature.max(f64::MIN_POSITIVE);
+    let Some(top) = scores.iter().copied().map(f64::from).reduce(f64::max) else {
+        return Vec::new();
+    };
+    let weights: Vec<f64> = scores
+        .iter()
+        .map(|&s| ((f64::from(s) - top) / temperature).exp())
+        .collect();
+    let total: f64 = weights.iter().sum();
+    weights.into_iter().map(|w| w / total).collect()
+}
+
+/// Entropy of a distribution in nats.
+pub fn entropy(probs: &[f64]) -> f64 {
+    probs
+        .iter()
+        .filter(|&&p| p > 0.0)
+        .map(|&p| -p * p.ln())
+        .sum()
+}
+
+/// Plays a primary bot where it is confident and defers to a fallback where it is not.
+///
+/// The primary's [`Bot::rank_actions`] scores over the legal actions are turned into a
+/// distribution by a softmax at `temperature`, which should match the scale of the
+/// primary's scores. The primary's choice stands when the distribution passes the
+/// [`Confidence`] test; otherwise the fallback decides. Meant for shipping a learned or
+/// search bot that is strong on average but blunders in unfamiliar positions, with a
+/// strong heuristic behind it. Forced moves never defer.
+pub struct HybridBot<P: Bot, F: Bot> {
+    primary: P,
+    fallback: F,
+    confidence: Confidence,
+    temperature: f64,
+    decisions: usize,
+    deferred: usize,
+}
+
+impl<P: Bot, F: Bot> HybridBot<P, F> {
+    pub fn new(primary: P, fallback: F, confidence: Confidence) -> Self {
+        Self {
+            primary,
+            fallback,
+            confidence,
+            temperature: DEFAULT_TEMPERATURE,
+            decisions: 0,
+            deferred: 0,
+        }
+    }
+
+    pub fn with_temperature(mut self, temperature: f64) -> Self {
+        self.temperature = temperature;
+        self
+    }
+
+    pub fn primary(&self) -> &P {
+        &self.primary
+    }
+
+    pub fn fallback(&self) -> &F {
+        &self.fallback
+    }
+
+    /// Decisions made so far and how many of them went to the fallback.
+    pub fn deferrals(&self) -> (usize, usize) {
+        (self.decisions, self.deferred)
+    }
+}
+
+impl<P: Bot, F: Bot> Bot for HybridBot<P, F> {
+    fn on_game_start(&mut self, state: &GameStateView) {
+        self.primary.on_game_start(state);
+        self.fallback.on_game_start(state);
+    }
+
+    fn on_turn_start(&mut self, state: &GameStateView) {
+        self.primary.on_turn_start(state);
+        self.fallback.on_turn_start(state);
+    }
+
+    fn select_action(&mut self, state: &GameStateView, legal_actions: &[Action]) -> Action {
+        self.decisions += 1;
+        if legal_actions.len() < 2 {
+            return self.primary.select_action(state, legal_actions);
+        }
+        let ranked = self.primary.rank_actions(state, legal_actions);
+        let scores: Vec<f32> = ranked
+            .iter()
+            .filter(|(action, _)| legal_actions.contains(action))
+            .map(|(_, score)| *score)
+            .collect();
+        let probs = softmax(&scores, self.temperature);
+        match ranked.into_iter().next() {
+            Some((action, _))
+                if legal_actions.contains(&action) && self.confidence.accepts(&probs) =>
+            {
+                action
+            }
+            _ => {
+                self.deferred += 1;
+                self.fallback.select_action(state, legal_actions)
+            }
+        }
+    }
+
+    fn limit_search(&mut self, nodes: usize) {
+        self.primary.limit_search(nodes);
+        self.fallback.limit_search(nodes);
+    }
+
+    fn interrupt_with(&mut self, interrupter: &Interrupter) {
+        self.primary.interrupt_with(interrupter);
+        self.fallback.interrupt_with(interrupter);
+    }
+
+    fn wants_full_state(&self) -> bool {
+        self.primary.wants_full_state() || self.fallback.wants_full_state()
+    }
+
+    fn observe_full_state(&mut self, state: &FullStateView) {
+        if self.primary.wants_full_state() {
+            self.primary.observe_full_state(state);
+        }
+        if self.fallback.wants_full_state() {
+            self.fallback.observe_full_state(state);
+        }
+    }
+
+    fn on_game_end(&mut self, state: &GameStateView, winner: Option<PlayerId>) {
+        self.primary.on_game_end(state, winner);
+        self.fallback.on_game_end(state, winner);
+    }
+}
@@ -0,0 +1,332 @@
+use std::sync::Arc;
+
+use rand::Rng;
+use rand::seq::SliceRandom;
+
+use crate::action::{Action, CardSource, PlayerId};
+use crate::analysis::sample_determinization;
+use crate::bot::{Bot, rank_scored};
+use crate::bots::eval::{EvalFn, Evaluator};
+use crate::bots::heuristic_13::Heuristic13Bot;
+use crate::bots::planning::TurnPosition;
+use crate::bots::strength::Strength;
+use crate::game::Game;
+use crate::interrupt::Interrupter;
+use crate::state::GameStateView;
+
+/// Evaluation gain over the position the search started from that scores a rollout at
+/// about 0.73 (the reward is its logistic).
+const REWARD_SCALE: f32 = 100.0;
+
+/// Actions a rollout may take before it is scored as it stands.
+const MAX_ROLLOUT_ACTIONS: usize = 400;
+
+/// A decision of the acting player: the actions tried from it, shared by every
+/// determinization that reached it.
+#[derive(Debug, Default)]
+struct Node {
+    children: Vec<Child>,
+}
+
+#[derive(Debug)]
+struct Child {
+    action: Action,
+    node: usize,
+    visits: u32,
+    /// Times the action was legal when its parent was visited.
+    available: u32,
+    reward: f64,
+}
+
+impl Child {
+    fn ucb(&self, exploration: f64) -> f64 {
+        let mean = self.reward / f64::from(self.visits);
+        mean + exploration * (f64::from(self.available).ln() / f64::from(self.visits)).sqrt()
+    }
+}
+
+/// Information-set Monte Carlo tree search (single-observer IS-MCTS).
+///
+/// Every iteration samples a determinization of the hidden cards with
+/// [`sample_determinization`] and walks one tree whose nodes are the acting player's
+/// information sets in the current turn, identified by the actions taken so far. Only
+/// the children legal in the sampled game compete, by UCB over how often each was
+/// available, so statistics are shared across determinizations instead of building a
+/// tree per sample as plain determinized MCTS does. Below the tree [`Heuristic13Bot`]
+/// finishes the turn and plays the opponents' replies in the sampled game. The position
+/// we are left with is scored by an [`EvalFn`] ([`Evaluator::Balanced`] by default),
+/// counting the opponents' stock plays as unlocks, and the reward is the logistic of its
+/// gain over the starting position: 1 for a win, 0 for a loss. The most visited first
+/// action is played. Below full [`Strength`] the bot runs fewer iterations, and once the
+/// decision's [`Interrupter`] fires it plays the best action found so far.
+pub struct IsMctsBot<R: Rng> {
+    rng: R,
+    /// Determinizations (one tree walk each) per decision.
+    pub iterations: usize,
+    pub exploration: f64,
+    evaluation: Arc<dyn EvalFn>,
+    interrupter: Interrupter,
+}
+
+impl<R: Rng> IsMctsBot<R> {
+    pub const DEFAULT_ITERATIONS: usize = 400;
+    pub const DEFAULT_EXPLORATION: f64 = 0.5;
+
+    pub fn new(rng: R, iterations: usize) -> Self {
+        Self {
+            rng,
+            iterations: iterations.max(1),
+            exploration: Self::DEFAULT_EXPLORATION,
+            evaluation: Arc::new(Evaluator::Balanced),
+            interrupter: Interrupter::new(),
+        }
+    }
+
+    pub fn with_exploration(mut self, exploration: f64) -> Self {
+        self.exploration = exploration.max(0.0);
+        self
+    }
+
+    pub fn with_evaluation(mut self, evaluation: impl EvalFn + 'static) -> Self {
+        self.evaluation = Arc::new(evaluation);
+        self
+    }
+
+    pub fn with_strength(mut self, strength: Strength) -> Self {
+        self.iterations = strength.node_budget(self.iterations);
+        self
+    }
+
+    /// Visits of each first action tried, after running the search from `state`.
+    fn search(&mut self, state: &GameStateView) -> Vec<(Action, u32)> {
+        let Some(root) = TurnPosition::from_view(state) else {
+            return Vec::new();
+        };
+        let baseline = self.evaluation.evaluate(state, &root, 0);
+        let mut tree = vec![Node::default()];
+        for iteration in 0..self.iterations {
+            if iteration > 0 && self.interrupter.should_stop() {
+                break;
+            }
+            let Ok(game) = sample_determinization(state, &mut self.rng) else {
+                break;
+            };
+            let mut line = Line {
+                game,
+                me: state.self_player,
+                position: root.clone(),
+            };
+            let path = self.descend(&mut tree, &mut line);
+            let reward = line.finish(state, self.evaluation.as_ref(), baseline);
+            let mut node = 0;
+            for index in path {
+                let child = &mut tree[node].children[index];
+                child.visits += 1;
+                child.reward += reward;
+                node = child.node;
+            }
+        }
+        tree.swap_remove(0)
+            .children
+            .into_iter()
+            .map(|child| (child.action, child.visits))
+            .collect()
+    }
+
+    /// Select and expand down the tree while it is our turn; returns the child index
+    /// taken at each level.
+    fn descend(&mut self, tree: &mut Vec<Node>, line: &mut Line) -> Vec<usize> {
+        let mut path = Vec::new();
+        let mut node = 0;
+        while line.our_turn() {
+            let Ok(legal) = line.game.legal_actions(line.me) else {
+                break;
+            };
+            let mut untried: Vec<&Action> = legal.iter().collect();
+            for child in &mut tree[node].children {
+                if let Some(pos) = untried.iter().position(|a| **a == child.action) {
+                    untried.swap_remove(pos);
+                    child.available += 1;
+                }
+            }
+            let index = if let Some(&action) = untried.choose(&mut self.rng) {
+                let child = tree.len();
+                tree.push(Node::default());
+                tree[node].children.push(Child {
+                    action: action.clone(),
+                    node: child,
+                    visits: 0,
+                    available: 1,
+                    reward: 0.0,
+                });
+                tree[node].children.len() - 1
+            } else {
+                let children = &tree[node].children;
+                let Some(best) = (0..children.len())
+                    .filter(|&i| legal.contains(&children[i].action))
+                    .max_by(|&a, &b| {
+                        let ucb = |i: usize| children[i].ucb(self.exploration);
+                        ucb(a).total_cmp(&ucb(b))
+                    })
+                else {
+                    break;
+                };
+                best
+            };
+            let child = &tree[node].children[index];
+            if !line.play(child.action.clone()) {
+                break;
+            }
+            path.push(index);
+            let expanded = child.visits == 0;
+            node = child.node;
+            if expanded {
+                break;
+            }
+        }
+        path
+    }
+}
+
+/// One determinization being played, with our position tracked alongside it so the
+/// evaluation sees what the turn achieved.
+pub(crate) struct Line {
+    pub(crate) game: Game,
+    pub(crate) me: PlayerId,
+    pub(crate) position: TurnPosition,
+}
+
+impl Line {
+    pub(crate) fn our_turn(&self) -> bool {
+        !self.game.is_finished() && self.game.current_player() == self.me
+    }
+
+    /// Apply our `action` to the game and the position, revealing the stock card and
+    /// hand refills the sampled game deals.
+    pub(crate) fn play(&mut self, action: Action) -> bool {
+        if !self.position.apply(&action) || self.game.apply_action(self.me, action).is_err() {
+            return false;
+        }
+        if let Ok(view) = self.game.state_view(self.me)
+            && let Some(me) = view.self_player_state()
+        {
+            self.position.stock_top = me.stock_top;
+            self.position.stock_count = me.stock_count;
+            if self.our_turn() {
+                self.position.hand = view.hand.to_vec();
+            }
+        }
+        true
+    }
+
+    /// Finish our turn and the opponents' replies with [`Heuristic13Bot`], then reward
+    /// the line against the `baseline` evaluation of the starting position `state`: 1 for
+    /// a win, 0 for a loss, otherwise the logistic of the evaluation's gain.
+    pub(crate) fn finish(
+        &mut self,
+        state: &GameStateView,
+        evaluation: &dyn EvalFn,
+        baseline: f32,
+    ) -> f64 {
+        let mut policies: Vec<Heuristic13Bot> = (0..state.settings.num_players)
+            .map(|_| Heuristic13Bot::new())
+            .collect();
+        let mut opponent_unlocks = 0;
+        let mut replied = false;
+        for _ in 0..MAX_ROLLOUT_ACTIONS {
+            if self.game.is_finished() {
+                break;
+            }
+            let current = self.game.current_player();
+            if current != self.me {
+                replied = true;
+            } else if replied {
+                break;
+            }
+            let (Ok(view), Ok(legal)) = (
+                self.game.state_view(current),
+                self.game.legal_actions(current),
+            ) else {
+                break;
+            };
+            if legal.is_empty() {
+                break;
+            }
+            let action = policies[current.0].select_action(&view, &legal);
+            if current == self.me {
+                if !self.play(action) {
+                    break;
+                }
+                continue;
+            }
+            if matches!(
+                action,
+                Action::Play {
+                    source: CardSource::Stock,
+                    ..
+                }
+            ) {
+                opponent_unlocks += 1;
+            }
+            if self.game.apply_action(current, action).is_err() {
+                break;
+            }
+        }
+        if let Some(winner) = self.game.winner() {
+            return f64::from(u8::from(winner == self.me));
+        }
+        if let Ok(view) = self.game.state_view(self.me) {
+            self.position.build_next = std::array::from_fn(|i| view.build_piles[i].next_value);
+        }
+        let score = evaluation.evaluate(state, &self.position, opponent_unlocks);
+        1.0 / (1.0 + f64::from((baseline - score) / REWARD_SCALE).exp())
+    }
+}
+
+impl<R: Rng + Send> Bot for IsMctsBot<R> {
+    fn limit_search(&mut self, nodes: usize) {
+        self.iterations = self.iterations.min(nodes.max(1));
+    }
+
+    fn interrupt_with(&mut self, interrupter: &Interrupter) {
+        self.interrupter = interrupter.clone();
+    }
+
+    fn select_action(&mut self, state: &GameStateView, legal_actions: &[Action]) -> Action {
+        self.rank_actions(state, legal_actions).swap_remove(0).0
+    }
+
+    /// Legal actions by their share of the root visits, the most visited first. Forced
+    /// moves rank first with score 1.0.
+    fn rank_actions(
+        &mut self,
+        state: &GameStateView,
+        legal_actions: &[Action],
+    ) -> Vec<(Action, f32)> {
+        assert!(
+            !legal_actions.is_empty(),
+            "IS-MCTS bot requires at least one legal action"
+        );
+        if legal_actions.len() == 1 {
+            return vec![(legal_actions[0].clone(), 1.0)];
+        }
+        let visits = self.search(state);
+        let total = visits.iter().map(|(_, v)| v).sum::<u32>().max(1) as f32;
+        let scored: Vec<(Action, f32)> = legal_actions
+            .iter()
+            .map(|action| {
+                let visits = visits
+                    .iter()
+                    .find(|(a, _)| a == action)
+                    .map_or(0, |(_, v)| *v);
+                (action.clone(), visits as f32 / total)
+            })
+            .collect();
+        let chosen = scored
+            .iter()
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(action, _)| action.clone())
+            .expect("legal actions are not empty");
+        rank_scored(chosen, scored)
+    }
+}
@@ -1,3 +1,9 @@
+pub mod beam_search;
+pub mod blocking;
+pub mod completion;
+pub mod config;
+pub mod endgame;
+pub mod eval;
 pub mod heuristic_1;
 pub mod heuristic_10;
 pub mod heuristic_11;
@@ -8,6 +14,7 @@ pub mod heuristic_15;
 pub mod heuristic_16;
 pub mod heuristic_17;
 pub mod heuristic_18;
+pub mod heuristic_19;
 pub mod heuristic_2;
 pub mod heuristic_3;
 pub mod heuristic_4;
@@ -17,9 +24,24 @@ pub mod heuristic_7;
 pub mod heuristic_8;
 pub mod heuristic_9;
 pub mod human;
+pub mod hybrid;
+pub mod ismcts;
+pub mod noisy;
+pub mod opening;
+pub mod oracle;
+pub mod planning;
+pub mod puct;
 pub mod random;
 pub mod registry;
+pub mod router;
+pub mod search;
+pub mod softmax;
+pub mod strength;
+pub mod wilds;
 
+pub use beam_search::BeamSearchBot;
+pub use config::{BotConfig, BotsConfig};
+pub use eval::{EvalFn, Evaluator};
 pub use heuristic_1::HeuristicBot;
 pub use heuristic_2::Heuristic2Bot;
 pub use heuristic_3::Heuristic3Bot;
@@ -38,6 +60,20 @@ pub use heuristic_15::Heuristic15Bot;
 pub use heuristic_16::Heuristic16Bot;
 pub use heuristic_17::Heuristic17Bot;
 pub use heuristic_18::Heuristic18Bot;
+pub use heuristic_19::{Heuristic19Bot, PlanWeights};
 pub use human::HumanBot;
+pub use hybrid::HybridBot;
+pub use ismcts::IsMctsBot;
+pub use noisy::NoisyBot;
+pub use opening::OpeningBook;
+pub use oracle::OracleBot;
+pub use puct::PuctBot;
 pub use random::RandomBot;
-pub use registry::{create_bot_from_spec, label_for_spec};
+pub use registry::{
+    SpecInfo, available_specs, create_bot_from_spec, human_name, label_for_spec,
+    sampling_temperature,
+};
+pub use router::{PolicyRouterBot, RouterManifest};
+pub use search::{ReplacementPolicy, SharedTable, TableStats, TranspositionTable};
+pub use softmax::SoftmaxBot;
+pub use strength::Strength;
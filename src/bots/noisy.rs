@@ -0,0 +1,103 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::action::{Action, PlayerId};
+use crate::bot::Bot;
+use crate::interrupt::Interrupter;
+use crate::state::{FullStateView, GameStateView};
+
+/// Decorator that makes an inner bot err like a human: with probability `noise` a
+/// decision is replaced by the inner bot's second or third choice from
+/// [`Bot::rank_actions`]. Used to model imperfect opponents, e.g. for training policies
+/// that hold up against them.
+pub struct NoisyBot<B: Bot> {
+    inner: B,
+    noise: f64,
+    /// How far down the ranking a mistake may reach (2 = second choice only).
+    max_rank: usize,
+    rng: StdRng,
+}
+
+impl<B: Bot> NoisyBot<B> {
+    pub const DEFAULT_MAX_RANK: usize = 3;
+
+    /// Wrap `inner`; `noise` is clamped to `0.0..=1.0`.
+    pub fn new(inner: B, noise: f64, seed: u64) -> Self {
+        Self {
+            inner,
+            noise: noise.clamp(0.0, 1.0),
+            max_rank: Self::DEFAULT_MAX_RANK,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    /// Let mistakes pick any choice from the second down to `max_rank`.
+    pub fn with_max_rank(mut self, max_rank: usize) -> Self {
+        self.max_rank = max_rank.max(2);
+        self
+    }
+
+    pub fn inner(&self) -> &B {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> B {
+        self.inner
+    }
+}
+
+impl<B: Bot> Bot for NoisyBot<B> {
+    fn on_game_start(&mut self, state: &GameStateView) {
+        self.inner.on_game_start(state);
+    }
+
+    fn on_turn_start(&mut self, state: &GameStateView) {
+        self.inner.on_turn_start(state);
+    }
+
+    fn select_action(&mut self, state: &GameStateView, legal_actions: &[Action]) -> Action {
+        if legal_actions.len() < 2 || !self.rng.gen_bool(self.noise) {
+            return self.inner.select_action(state, legal_actions);
+        }
+        let mut ranked = self.inner.rank_actions(state, legal_actions);
+        let worst = self.max_rank.min(ranked.len());
+        let pick = if worst >= 2 {
+            self.rng.gen_range(1..worst)
+        } else {
+            0
+        };
+        if pick < ranked.len() {
+            ranked.swap_remove(pick).0
+        } else {
+            legal_actions[0].clone()
+        }
+    }
+
+    fn rank_actions(
+        &mut self,
+        state: &GameStateView,
+        legal_actions: &[Action],
+    ) -> Vec<(Action, f32)> {
+        self.inner.rank_actions(state, legal_actions)
+    }
+
+    fn limit_search(&mut self, nodes: usize) {
+        self.inner.limit_search(nodes);
+    }
+
+    fn interrupt_with(&mut self, interrupter: &Interrupter) {
+        self.inner.interrupt_with(interrupter);
+    }
+
+    fn wants_full_state(&self) -> bool {
+        self.inner.wants_full_state()
+    }
+
+    fn observe_full_state(&mut self, state: &FullStateView) {
+        self.inner.observe_full_state(state);
+    }
+
+    fn on_game_end(&mut self, state: &GameStateView, winner: Option<PlayerId>) {
+        self.inner.on_game_end(state, winner);
+    }
+}
@@ -0,0 +1,299 @@
+//! Opening book: turn plans for the first turn of a game, looked up instead of searched.
+//!
+//! A player's first turn starts with empty discard piles, low build piles and a fresh
+//! hand, so the same positions come up in game after game, up to the order of the hand
+//! cards and of the build piles. [`OpeningKey`] is that position with both orders
+//! sorted away, and an [`OpeningBook`] maps keys to the plan a strong bot chose there.
+//! Plans are stored by card and pile rank rather than by slot, so one entry serves every
+//! ordering of the same position.
+//!
+//! Books are warmed offline with [`OpeningBook::warm`] (the `opening-book` binary) and
+//! loaded by [`Heuristic19Bot::with_opening_book`], which then skips the search on its
+//! first turn whenever the book knows the position.
+//!
+//! [`Heuristic19Bot::with_opening_book`]: crate::bots::Heuristic19Bot::with_opening_book
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::action::{Action, BuildPileId, CardSource, DiscardPileId, LegalSet, PlayerId};
+use crate::bot::Bot;
+use crate::bots::planning::TurnPosition;
+use crate::card::{BUILD_PILE_COUNT, Card};
+use crate::game::Game;
+use crate::runner::mix_seed;
+use crate::state::{GameStateView, GameStatus};
+
+/// Format version written to book files.
+pub const BOOK_VERSION: u32 = 1;
+
+/// A first-turn position with the hand and build piles in canonical order. Card values
+/// are 1-12, with 0 for Skip-Bo.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct OpeningKey {
+    pub players: usize,
+    /// Hand cards in ascending order.
+    pub hand: Vec<u8>,
+    pub stock_top: u8,
+    pub stock_count: usize,
+    /// Value each build pile requires next, in ascending order.
+    pub build_next: [u8; BUILD_PILE_COUNT],
+    /// Each opponent's stock top and stock size, in turn order after the player.
+    pub opponents: Vec<(Option<u8>, usize)>,
+}
+
+/// One action of a stored plan. Build piles are given by rank in the key's sorted
+/// `build_next`; hand cards by value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BookStep {
+    Hand { card: u8, pile: usize },
+    Stock { pile: usize },
+    Discard { card: u8, pile: usize },
+}
+
+impl OpeningKey {
+    /// The key of `state`, if it is the start of the viewing player's first turn: their
+    /// turn, nothing played yet and every discard pile empty.
+    pub fn from_view(state: &GameStateView) -> Option<Self> {
+        let me = state.self_player_state()?;
+        if state.status != GameStatus::Ongoing
+            || state.current_player != state.self_player
+            || state.actions_taken_this_turn > 0
+            || me.discard_piles.iter().any(|pile| !pile.is_empty())
+        {
+            return None;
+        }
+        let mut hand: Vec<u8> = state.hand.iter().map(|card| card_value(*card)).collect();
+        hand.sort_unstable();
+        let mut build_next = state.build_piles.clone().map(|pile| pile.next_value);
+        build_next.sort_unstable();
+        let players = state.settings.num_players;
+        let opponents = (1..players)
+            .filter_map(|offset| {
+                let id = state.self_player.offset(offset, players);
+                state.players.iter().find(|player| player.id == id)
+            })
+            .map(|player| (player.stock_top.map(card_value), player.stock_count))
+            .collect();
+        Some(Self {
+            players,
+            hand,
+            stock_top: card_value(me.stock_top?),
+            stock_count: me.stock_count,
+            build_next,
+            opponents,
+        })
+    }
+}
+
+fn card_value(card: Card) -> u8 {
+    card.value().unwrap_or(0)
+}
+
+/// Build pile indices of `state` in the canonical order of [`OpeningKey::build_next`].
+fn pile_order(state: &GameStateView) -> [usize; BUILD_PILE_COUNT] {
+    let mut order: [usize; BUILD_PILE_COUNT] = std::array::from_fn(|pile| pile);
+    order.sort_by_key(|&pile| state.build_piles[pile].next_value);
+    order
+}
+
+/// `plan` from `state` in book terms.
+fn encode(state: &GameStateView, plan: &[Action]) -> Option<(OpeningKey, Vec<BookStep>)> {
+    let key = OpeningKey::from_view(state)?;
+    let mut position = TurnPosition::from_view(state)?;
+    let order = pile_order(state);
+    let rank = |pile: BuildPileId| order.iter().position(|&p| p == pile.0);
+    let mut steps = Vec::with_capacity(plan.len());
+    for action in plan {
+        let step = match *action {
+            Action::Play {
+                source: CardSource::Hand(index),
+                build_pile,
+            } => BookStep::Hand {
+                card: card_value(*position.hand.get(index)?),
+                pile: rank(build_pile)?,
+            },
+            Action::Play {
+                source: CardSource::Stock,
+                build_pile,
+            } => BookStep::Stock {
+                pile: rank(build_pile)?,
+            },
+            Action::Discard {
+                hand_index,
+                discard_pile,
+            } => BookStep::Discard {
+                card: card_value(*position.hand.get(hand_index)?),
+                pile: discard_pile.0,
+            },
+            // Discard piles are empty at book positions and turns end with a discard.
+            _ => return None,
+        };
+        if !position.apply(action) {
+            return None;
+        }
+        steps.push(step);
+    }
+    Some((key, steps))
+}
+
+#[derive(Serialize, Deserialize)]
+struct BookFile {
+    version: u32,
+    entries: Vec<BookEntry>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct BookEntry {
+    key: OpeningKey,
+    plan: Vec<BookStep>,
+}
+
+/// Plans by [`OpeningKey`]; see the [module docs](self).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct OpeningBook {
+    entries: HashMap<OpeningKey, Vec<BookStep>>,
+}
+
+impl OpeningBook {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Remember `plan`, played from `state`. Returns `false` when `state` is not a book
+    /// position or the plan does not fit it.
+    pub fn insert(&mut self, state: &GameStateView, plan: &[Action]) -> bool {
+        let Some((key, steps)) = encode(state, plan) else {
+            return false;
+        };
+        self.entries.insert(key, steps);
+        true
+    }
+
+    /// The stored plan for `state` as actions on its hand and piles, if the book knows
+    /// the position and the plan's first action is legal.
+    pub fn lookup(&self, state: &GameStateView, legal: &LegalSet) -> Option<Vec<Action>> {
+        let steps = self.entries.get(&OpeningKey::from_view(state)?)?;
+        let mut position = TurnPosition::from_view(state)?;
+        let order = pile_order(state);
+        let hand_index = |position: &TurnPosition, card: u8| {
+            position
+                .hand
+                .iter()
+                .position(|held| card_value(*held) == card)
+        };
+        let mut plan = Vec::with_capacity(steps.len());
+        for step in steps {
+            let action = match *step {
+                BookStep::Hand { card, pile } => Action::Play {
+                    source: CardSource::Hand(hand_index(&position, card)?),
+                    build_pile: BuildPileId(*order.get(pile)?),
+                },
+                BookStep::Stock { pile } => Action::Play {
+                    source: CardSource::Stock,
+                    build_pile: BuildPileId(*order.get(pile)?),
+                },
+                BookStep::Discard { card, pile } => Action::Discard {
+                    hand_index: hand_index(&position, card)?,
+                    discard_pile: DiscardPileId(pile),
+                },
+            };
+            if !position.apply(&action) {
+                return None;
+            }
+            plan.push(action);
+        }
+        plan.first()
+            .is_some_and(|first| legal.contains(first))
+            .then_some(plan)
+    }
+
+    /// Play the first round of `games` seeded deals with one `bot` per seat and store
+    /// the plan `plan_for` gives at every book position. `plan_for` is typically the full
+    /// search of the bot the book is meant for.
+    pub fn warm<B, P>(
+        &mut self,
+        games: usize,
+        players: usize,
+        seed: u64,
+        mut bot: B,
+        mut plan_for: P,
+    ) -> Result<usize, Box<dyn Error>>
+    where
+        B: FnMut(PlayerId) -> Box<dyn Bot>,
+        P: FnMut(&GameStateView, &[Action]) -> Option<Vec<Action>>,
+    {
+        let before = self.len();
+        for game_index in 0..games {
+            let mut game = Game::builder(players)?
+                .with_seed(mix_seed(seed, game_index as u64, 0))
+                .build()?;
+            let mut seats: Vec<Box<dyn Bot>> = PlayerId::all(players).map(&mut bot).collect();
+            while !game.is_finished() && game.turn_number() < players {
+                let player = game.current_player();
+                let state = game.state_view(player)?;
+                let legal = game.legal_actions(player)?;
+                if OpeningKey::from_view(&state).is_some()
+                    && let Some(plan) = plan_for(&state, &legal)
+                {
+                    self.insert(&state, &plan);
+                }
+                let action = seats[player.0].select_action(&state, &legal);
+                game.apply_action(player, action)?;
+            }
+        }
+        Ok(self.len() - before)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        let mut entries: Vec<BookEntry> = self
+            .entries
+            .iter()
+            .map(|(key, plan)| BookEntry {
+                key: key.clone(),
+                plan: plan.clone(),
+            })
+            .collect();
+        // A stable order keeps rewarmed books diffable.
+        entries.sort_by(|a, b| a.key.cmp(&b.key));
+        let file = BookFile {
+            version: BOOK_VERSION,
+            entries,
+        };
+        fs::write(path, serde_json::to_string(&file)?)?;
+        Ok(())
+    }
+
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let text = fs::read_to_string(path).map_err(|err| format!("{}: {err}", path.display()))?;
+        let file: BookFile = serde_json::from_str(&text)?;
+        if file.version != BOOK_VERSION {
+            return Err(format!(
+                "{}: opening book version {} (expected {BOOK_VERSION})",
+                path.display(),
+                file.version
+            )
+            .into());
+        }
+        Ok(Self {
+            entries: file
+                .entries
+                .into_iter()
+                .map(|entry| (entry.key, entry.plan))
+                .collect(),
+        })
+    }
+}
@@ -0,0 +1,337 @@
+//! Cheating upper-bound baseline that sees every hidden card.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::action::{Action, CardSource, DiscardPileId, LegalSet, PlayerId};
+use crate::bot::Bot;
+use crate::bots::heuristic_19::Heuristic19Bot;
+use crate::bots::planning::{DEFAULT_NODE_LIMIT, TurnContext, TurnPosition};
+use crate::card::BUILD_PILE_COUNT;
+use crate::interrupt::{Interrupter, POLL_INTERVAL};
+use crate::state::{FullStateView, GameStateView};
+
+/// Positions searched per opponent when measuring how far it gets next turn.
+const OPPONENT_NODE_LIMIT: usize = 500;
+
+/// Oracle bot: a turn planner that plays with all cards face up.
+///
+/// Through [`Bot::observe_full_state`] it knows every hand, stock and the draw order, so
+/// its search reveals the real next stock card after each stock play and the real refill
+/// when the hand runs out. Every way to end the turn is scored like
+/// [`Heuristic19Bot`] scores plans, except that the opponent risk is exact: a search over
+/// each opponent's actual hand (and, for the next player, the cards they will draw)
+/// counts the stock cards it could play on the build piles we leave behind.
+///
+/// It is a measuring stick, not a fair player: the gap between it and an honest bot
+/// bounds what hidden information costs. Without a full state (a driver that does not
+/// provide one) it falls back to [`Heuristic19Bot`].
+pub struct OracleBot {
+    pub node_limit: usize,
+    interrupter: Interrupter,
+    full: Option<FullStateView>,
+    context: TurnContext,
+    fallback: Heuristic19Bot,
+}
+
+impl OracleBot {
+    pub fn new() -> Self {
+        Self::with_node_limit(DEFAULT_NODE_LIMIT)
+    }
+
+    /// Limit the number of searched positions per decision.
+    pub fn with_node_limit(node_limit: usize) -> Self {
+        Self {
+            node_limit,
+            interrupter: Interrupter::new(),
+            full: None,
+            context: TurnContext::new(),
+            fallback: Heuristic19Bot::with_node_limit(node_limit),
+        }
+    }
+
+    /// Best full-information plan for the acting player, or `None` when nothing was found.
+    fn best_plan(&self, full: &FullStateView) -> Option<Vec<Action>> {
+        let me = full.view.self_player;
+        let line = Line::start(full, me, 0)?;
+        let mut search = Search {
+            full,
+            node_limit: self.node_limit,
+            interrupter: &self.interrupter,
+            nodes: 0,
+            visited: HashSet::new(),
+            threats: HashMap::new(),
+            path: Vec::new(),
+            best: None,
+        };
+        search.visit(line);
+        search.best.map(|(_, plan)| plan)
+    }
+}
+
+impl Default for OracleBot {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A simulated turn with cursors into the hidden cards it has revealed so far.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct Line {
+    player: PlayerId,
+    position: TurnPosition,
+    /// Index into the player's stock (top first) of the current stock top.
+    stock_cursor: usize,
+    /// Next card of the draw pile to be drawn.
+    draw_cursor: usize,
+}
+
+impl Line {
+    /// `player`'s position at the start of their turn, drawing up to a full hand from
+    /// `draw_cursor` (the acting player already holds their hand).
+    fn start(full: &FullStateView, player: PlayerId, draw_cursor: usize) -> Option<Self> {
+        let view = &full.view;
+        let public = view.players.iter().find(|p| p.id == player)?;
+        let stock = full.stocks.get(player.0)?;
+        let mut line = Self {
+            player,
+            position: TurnPosition {
+                build_next: std::array::from_fn(|i| view.build_piles[i].next_value),
+                hand: full.hands.get(player.0)?.clone(),
+                discard_piles: public.discard_piles.to_vec(),
+                stock_top: stock.first().copied(),
+                stock_count: stock.len(),
+                stock_played: 0,
+                piles_completed: 0,
+                wilds_spent: 0,
+            },
+            stock_cursor: 0,
+            draw_cursor,
+        };
+        if player != view.self_player {
+            line.draw(full);
+        }
+        Some(line)
+    }
+
+    fn draw(&mut self, full: &FullStateView) {
+        while self.position.hand.len() < full.view.settings.hand_size {
+            let Some(card) = full.draw_pile.get(self.draw_cursor) else {
+                break;
+            };
+            self.position.hand.push(*card);
+            self.draw_cursor += 1;
+        }
+    }
+
+    /// Apply a play and reveal what the engine would: the next stock card and, when the
+    /// last hand card is played, a refill.
+    fn play(&mut self, full: &FullStateView, action: &Action) -> bool {
+        let from_hand = matches!(
+            action,
+            Action::Play {
+                source: CardSource::Hand(_),
+                ..
+            }
+        );
+        if !self.position.apply(action) {
+            return false;
+        }
+        if self.position.stock_top.is_none() && self.position.stock_count > 0 {
+            self.stock_cursor += 1;
+            self.position.stock_top = full.stocks[self.player.0].get(self.stock_cursor).copied();
+        }
+        if from_hand && self.position.hand.is_empty() {
+            self.draw(full);
+        }
+        true
+    }
+}
+
+struct Search<'a> {
+    full: &'a FullStateView,
+    node_limit: usize,
+    interrupter: &'a Interrupter,
+    nodes: usize,
+    visited: HashSet<Line>,
+    /// Opponent risk by the build piles and draw cursor a turn leaves behind.
+    threats: HashMap<([u8; BUILD_PILE_COUNT], usize), i32>,
+    path: Vec<Action>,
+    best: Option<(i32, Vec<Action>)>,
+}
+
+impl Search<'_> {
+    fn visit(&mut self, line: Line) {
+        if self.nodes >= self.node_limit
+            || (self.nodes > 0
+                && self.nodes.is_multiple_of(POLL_INTERVAL)
+                && self.interrupter.should_stop())
+            || !self.visited.insert(line.clone())
+        {
+            return;
+        }
+        self.nodes += 1;
+        if line.position.stock_count == 0 {
+            self.record(i32::MAX);
+            return;
+        }
+        for action in line.position.plays() {
+            let mut child = line.clone();
+            if child.play(self.full, &action) {
+                self.path.push(action);
+                self.visit(child);
+                self.path.pop();
+            }
+        }
+        if line.position.hand.is_empty() {
+            self.path.push(Action::EndTurn);
+            let score = self.score(&line, None);
+            self.record(score);
+            self.path.pop();
+            return;
+        }
+        let mut seen = Vec::new();
+        for (hand_index, card) in line.position.hand.iter().enumerate() {
+            if seen.contains(card) {
+                continue;
+            }
+            seen.push(*card);
+            for pile in 0..line.position.discard_piles.len() {
+                let action = Action::Discard {
+                    hand_index,
+                    discard_pile: DiscardPileId(pile),
+                };
+                let mut ended = line.clone();
+                ended.position.apply(&action);
+                self.path.push(action);
+                let score = self.score(&ended, Some(pile));
+                self.record(score);
+                self.path.pop();
+            }
+        }
+    }
+
+    fn record(&mut self, score: i32) {
+        if self.best.as_ref().is_none_or(|(best, _)| score > *best) {
+            self.best = Some((score, self.path.clone()));
+        }
+    }
+
+    /// Score the position at the end of the turn, mirroring the heuristic 19 plan score.
+    fn score(&mut self, line: &Line, discard_pile: Option<usize>) -> i32 {
+        let position = &line.position;
+        let mut score = position.stock_played as i32 * 10_000;
+        if position.stock_played == 0 {
+            score -= position.wilds_spent as i32 * 250;
+            score -= Heuristic19Bot::stock_distance(position) * 30;
+        }
+        if let Some(pile) = discard_pile {
+            score += Heuristic19Bot::discard_shape(position, pile);
+        }
+        score += position.hand.iter().filter(|c| c.is_skip_bo()).count() as i32 * 150;
+        score.saturating_sub(self.threat(position.build_next, line.draw_cursor))
+    }
+
+    /// Penalty for what the opponents can do on `build_next`, nearest opponents first.
+    fn threat(&mut self, build_next: [u8; BUILD_PILE_COUNT], draw_cursor: usize) -> i32 {
+        if let Some(&threat) = self.threats.get(&(build_next, draw_cursor)) {
+            return threat;
+        }
+        let view = &self.full.view;
+        let players = view.settings.num_players;
+        let mut threat = 0i32;
+        for offset in 1..players {
+            let opponent = view.self_player.offset(offset, players);
+            // Only the next player's draws are known in order; later players are
+            // judged on the hand they hold now.
+            let cursor = if offset == 1 {
+                draw_cursor
+            } else {
+                self.full.draw_pile.len()
+            };
+            let Some(mut line) = Line::start(self.full, opponent, cursor) else {
+                continue;
+            };
+            line.position.build_next = build_next;
+            let (played, won) = best_stock_run(self.full, line);
+            let weight = 3_000 / offset as i32;
+            threat = threat.saturating_add(if won {
+                weight.saturating_mul(100)
+            } else {
+                weight * played as i32
+            });
+        }
+        self.threats.insert((build_next, draw_cursor), threat);
+        threat
+    }
+}
+
+/// Most stock cards the player of `line` can play this turn, and whether they can win.
+fn best_stock_run(full: &FullStateView, line: Line) -> (usize, bool) {
+    fn visit(
+        full: &FullStateView,
+        line: Line,
+        visited: &mut HashSet<Line>,
+        best: &mut usize,
+    ) -> bool {
+        if line.position.stock_count == 0 {
+            return true;
+        }
+        if visited.len() >= OPPONENT_NODE_LIMIT || !visited.insert(line.clone()) {
+            return false;
+        }
+        *best = (*best).max(line.position.stock_played);
+        for action in line.position.plays() {
+            let mut child = line.clone();
+            if child.play(full, &action) && visit(full, child, visited, best) {
+                return true;
+            }
+        }
+        false
+    }
+    let mut best = 0;
+    let won = visit(full, line, &mut HashSet::new(), &mut best);
+    (best, won)
+}
+
+impl Bot for OracleBot {
+    fn wants_full_state(&self) -> bool {
+        true
+    }
+
+    fn observe_full_state(&mut self, state: &FullStateView) {
+        self.full = Some(state.clone());
+    }
+
+    fn on_turn_start(&mut self, state: &GameStateView) {
+        self.context.clear();
+        self.fallback.on_turn_start(state);
+    }
+
+    fn limit_search(&mut self, nodes: usize) {
+        self.node_limit = nodes;
+        self.fallback.limit_search(nodes);
+    }
+
+    fn interrupt_with(&mut self, interrupter: &Interrupter) {
+        self.interrupter = interrupter.clone();
+        self.fallback.interrupt_with(interrupter);
+    }
+
+    fn select_action(&mut self, state: &GameStateView, legal_actions: &[Action]) -> Action {
+        assert!(
+            !legal_actions.is_empty(),
+            "oracle bot requires at least one legal action"
+        );
+        let Some(full) = self.full.take().filter(|full| full.view == *state) else {
+            return self.fallback.select_action(state, legal_actions);
+        };
+        let legal = LegalSet::from_actions(legal_actions);
+        if let Some(action) = self.context.next_action(state, &legal) {
+            return action;
+        }
+        self.best_plan(&full)
+            .filter(|plan| legal.contains(&plan[0]))
+            .and_then(|plan| self.context.start_plan(state, plan))
+            .unwrap_or_else(|| legal_actions[0].clone())
+    }
+}
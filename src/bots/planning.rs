@@ -0,0 +1,1084 @@
+//! Shared planning utilities that simulate the acting player's turn on top of a
+//! [`GameStateView`].
+//!
+//! Only information visible to the acting player is used: the stock card below the
+//! current top is unknown, and so are the cards drawn when the hand empties. The
+//! simulation therefore stops at those points instead of guessing.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+
+use crate::action::{Action, BuildPileId, CardSource, DiscardPileId, LegalSet};
+use crate::card::{BUILD_PILE_COUNT, Card, MAX_CARD_VALUE, MIN_CARD_VALUE, full_deck};
+use crate::interrupt::{Interrupter, POLL_INTERVAL};
+use crate::state::GameStateView;
+
+/// Default node budget used by [`TurnEnumerator::default`].
+pub const DEFAULT_NODE_LIMIT: usize = 20_000;
+
+/// Returns the value that follows `value` on a build pile (wrapping after 12).
+#[inline]
+pub fn next_build_value(value: u8) -> u8 {
+    if value == MAX_CARD_VALUE {
+        1
+    } else {
+        value + 1
+    }
+}
+
+/// Cards the viewing player cannot see: opponents' hands, hidden stock cards, the draw
+/// pile and the recycle pile, as a multiset drawn from the standard deck.
+pub fn unseen_cards(state: &GameStateView) -> Vec<Card> {
+    let mut unseen = full_deck();
+    let mut remove = |card: Card| {
+        if let Some(pos) = unseen.iter().position(|c| *c == card) {
+            unseen.swap_remove(pos);
+        }
+    };
+    for card in state.hand.iter() {
+        remove(*card);
+    }
+    for pile in &state.build_piles {
+        for card in pile.cards.iter() {
+            remove(*card);
+        }
+    }
+    for player in &state.players {
+        if let Some(top) = player.stock_top {
+            remove(top);
+        }
+        for pile in player.discard_piles.iter() {
+            for card in pile {
+                remove(*card);
+            }
+        }
+    }
+    unseen
+}
+
+/// Values that have to go onto a build pile requiring `next_value` before `stock` fits
+/// on it, in play order (wrapping after 12). Empty when the stock fits already or is a
+/// Skip-Bo.
+pub fn required_values_for_pile(next_value: u8, stock: Card) -> Vec<u8> {
+    match stock {
+        Card::SkipBo => Vec::new(),
+        Card::Number(s) if next_value <= s => (next_value..s).collect(),
+        Card::Number(s) => (next_value..=MAX_CARD_VALUE)
+            .chain(MIN_CARD_VALUE..s)
+            .collect(),
+    }
+}
+
+/// The acting player's hand cards and discard tops, indexed by the value they can be
+/// played as.
+#[derive(Clone, Debug, Default)]
+pub struct SourceIndex {
+    /// Number cards by value: hand positions first, then discard piles.
+    by_value: [Vec<CardSource>; MAX_CARD_VALUE as usize + 1],
+    /// Discard piles topped by a Skip-Bo.
+    skip_bo_discards: Vec<usize>,
+    /// Hand positions holding a Skip-Bo.
+    skip_bo_hands: Vec<usize>,
+}
+
+impl SourceIndex {
+    pub fn new(hand: &[Card], discard_piles: &[Vec<Card>]) -> Self {
+        let mut index = Self::default();
+        for (h, card) in hand.iter().enumerate() {
+            match *card {
+                Card::Number(v) => index.by_value[v as usize].push(CardSource::Hand(h)),
+                Card::SkipBo => index.skip_bo_hands.push(h),
+            }
+        }
+        for (d, pile) in discard_piles.iter().enumerate() {
+            match pile.last() {
+                Some(Card::Number(v)) => {
+                    index.by_value[*v as usize].push(CardSource::Discard(DiscardPileId(d)))
+                }
+                Some(Card::SkipBo) => index.skip_bo_discards.push(d),
+                None => {}
+            }
+        }
+        index
+    }
+
+    /// Index the viewing player's hand and discard tops. Returns `None` when the view
+    /// does not contain the viewing player.
+    pub fn from_view(state: &GameStateView) -> Option<Self> {
+        let me = state.self_player_state()?;
+        Some(Self::new(&state.hand, &me.discard_piles))
+    }
+
+    /// Number cards of `value`, hand positions first, then discard piles.
+    pub fn numbers(&self, value: u8) -> &[CardSource] {
+        self.by_value
+            .get(value as usize)
+            .map_or(&[], |sources| sources.as_slice())
+    }
+
+    /// Skip-Bo cards, discard piles first, then hand positions.
+    pub fn skip_bos(&self) -> impl Iterator<Item = CardSource> + '_ {
+        let discards = self
+            .skip_bo_discards
+            .iter()
+            .map(|&d| CardSource::Discard(DiscardPileId(d)));
+        discards.chain(self.skip_bo_hands.iter().map(|&h| CardSource::Hand(h)))
+    }
+}
+
+/// Plays that bring a build pile up to the stock card, found greedily from a
+/// [`SourceIndex`].
+///
+/// For every value the pile needs, an unused number card from a discard top is taken
+/// first, then one from the hand, then a Skip-Bo (discard tops before the hand). Discard
+/// piles are only used for their top card, so the plan never digs into a pile.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StockPlan {
+    pub build_pile: BuildPileId,
+    /// Every play of the plan, ending with the stock card.
+    pub actions: Vec<Action>,
+    /// Hand and discard cards played before the stock card.
+    pub cost: usize,
+    /// Skip-Bo cards among those.
+    pub wilds: usize,
+}
+
+impl StockPlan {
+    /// Plan for the build pile `build_pile`, which requires `next_value`. Each play
+    /// before the stock card has to pass `allow`. Returns `None` when the sources run out
+    /// or a play is refused.
+    pub fn for_pile(
+        sources: &SourceIndex,
+        build_pile: BuildPileId,
+        next_value: u8,
+        stock: Card,
+        mut allow: impl FnMut(&Action) -> bool,
+    ) -> Option<Self> {
+        let mut used = HashSet::new();
+        let mut actions = Vec::new();
+        let mut wilds = 0;
+        for need in required_values_for_pile(next_value, stock) {
+            let exact = sources.numbers(need);
+            let discard = exact
+                .iter()
+                .find(|src| matches!(src, CardSource::Discard(_)) && !used.contains(*src));
+            let hand = || {
+                exact
+                    .iter()
+                    .find(|src| matches!(src, CardSource::Hand(_)) && !used.contains(*src))
+            };
+            let source = match discard.or_else(hand) {
+                Some(&source) => source,
+                None => {
+                    wilds += 1;
+                    sources.skip_bos().find(|src| !used.contains(src))?
+                }
+            };
+            used.insert(source);
+            let action = Action::Play { source, build_pile };
+            if !allow(&action) {
+                return None;
+            }
+            actions.push(action);
+        }
+        let cost = actions.len();
+        actions.push(Action::Play {
+            source: CardSource::Stock,
+            build_pile,
+        });
+        Some(Self {
+            build_pile,
+            actions,
+            cost,
+            wilds,
+        })
+    }
+
+    /// Cheapest plan over all build piles for the viewing player's stock card (the first
+    /// pile on ties), or `None` without a visible stock card or a feasible plan.
+    pub fn best(state: &GameStateView) -> Option<Self> {
+        Self::best_with(state, |_| true)
+    }
+
+    /// [`best`](Self::best) where every play before the stock card has to pass `allow`.
+    pub fn best_with(
+        state: &GameStateView,
+        mut allow: impl FnMut(&Action) -> bool,
+    ) -> Option<Self> {
+        let me = state.self_player_state()?;
+        let stock = me.stock_top?;
+        let sources = SourceIndex::new(&state.hand, &me.discard_piles);
+        let mut best: Option<Self> = None;
+        for (i, pile) in state.build_piles.iter().enumerate() {
+            let Some(plan) =
+                Self::for_pile(&sources, BuildPileId(i), pile.next_value, stock, &mut allow)
+            else {
+                continue;
+            };
+            if best.as_ref().is_none_or(|best| plan.cost < best.cost) {
+                best = Some(plan);
+            }
+        }
+        best
+    }
+
+    /// The play to make now.
+    pub fn first_action(&self) -> &Action {
+        &self.actions[0]
+    }
+}
+
+/// Default node budget used by [`HandEmptySolver::default`].
+pub const DEFAULT_HAND_EMPTY_NODE_LIMIT: usize = 200_000;
+
+/// Answer of [`HandEmptySolver::solve`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum HandEmptyOutcome {
+    /// Plays that empty the hand, in engine hand indices, with as few discard pile plays
+    /// as possible.
+    Plan(Vec<Action>),
+    /// No order of plays empties the hand.
+    Infeasible,
+    /// The node budget ran out first.
+    Unknown,
+}
+
+impl HandEmptyOutcome {
+    pub fn is_feasible(&self) -> bool {
+        matches!(self, HandEmptyOutcome::Plan(_))
+    }
+
+    /// The play to make now, if the hand can be emptied.
+    pub fn first_action(&self) -> Option<&Action> {
+        match self {
+            HandEmptyOutcome::Plan(actions) => actions.first(),
+            _ => None,
+        }
+    }
+
+    pub fn into_plan(self) -> Option<Vec<Action>> {
+        match self {
+            HandEmptyOutcome::Plan(actions) => Some(actions),
+            _ => None,
+        }
+    }
+}
+
+/// Decides whether the acting player can play every hand card this turn, using their
+/// discard piles as helpers, and finds the plan that needs the fewest discard plays.
+///
+/// The search deepens the number of discard plays it allows one at a time, so the first
+/// plan found is optimal, and it stops as soon as an iteration never ran short of them,
+/// which proves the hand cannot be emptied. Positions are remembered with build piles
+/// sorted and the hand as a multiset, so piles requiring the same value and equal hand
+/// cards are explored once.
+#[derive(Clone, Copy, Debug)]
+pub struct HandEmptySolver {
+    node_limit: usize,
+    deep_discards: bool,
+}
+
+impl Default for HandEmptySolver {
+    fn default() -> Self {
+        Self::new(DEFAULT_HAND_EMPTY_NODE_LIMIT)
+    }
+}
+
+impl HandEmptySolver {
+    /// A solver that may play discard cards below the tops.
+    pub fn new(node_limit: usize) -> Self {
+        Self {
+            node_limit: node_limit.max(1),
+            deep_discards: true,
+        }
+    }
+
+    /// Whether cards below a discard pile's top may be played once the cards above them
+    /// are; otherwise each pile contributes at most its top card.
+    pub fn with_deep_discards(mut self, deep_discards: bool) -> Self {
+        self.deep_discards = deep_discards;
+        self
+    }
+
+    /// Search from the viewing player's position. An empty hand has nothing to play and
+    /// is [`HandEmptyOutcome::Infeasible`].
+    pub fn solve(&self, state: &GameStateView) -> HandEmptyOutcome {
+        let Some(me) = state.self_player_state() else {
+            return HandEmptyOutcome::Infeasible;
+        };
+        if state.hand.is_empty() {
+            return HandEmptyOutcome::Infeasible;
+        }
+        let mut build_next = [0u8; BUILD_PILE_COUNT];
+        for (next, pile) in build_next.iter_mut().zip(&state.build_piles) {
+            *next = pile.next_value;
+        }
+        let usable = |pile: &Vec<Card>| {
+            if self.deep_discards {
+                pile.len()
+            } else {
+                pile.len().min(1)
+            }
+        };
+        let mut search = HandEmptySearch {
+            hand: &state.hand,
+            discards: &me.discard_piles,
+            usable: me.discard_piles.iter().map(usable).collect(),
+            node_limit: self.node_limit,
+            nodes: 0,
+            failed: HashMap::new(),
+            build_next,
+            used_hand: vec![false; state.hand.len()],
+            popped: vec![0; me.discard_piles.len()],
+            path: Vec::new(),
+            hand_left: state.hand.len(),
+            short_of_helpers: false,
+            aborted: false,
+        };
+        let max_helpers: usize = search.usable.iter().sum();
+        for helpers in 0..=max_helpers {
+            search.short_of_helpers = false;
+            if search.dfs(helpers) {
+                return HandEmptyOutcome::Plan(search.plan());
+            }
+            if search.aborted {
+                return HandEmptyOutcome::Unknown;
+            }
+            if !search.short_of_helpers {
+                break;
+            }
+        }
+        HandEmptyOutcome::Infeasible
+    }
+
+    /// Whether the viewing player can empty their hand; `false` when undecided.
+    pub fn is_feasible(&self, state: &GameStateView) -> bool {
+        self.solve(state).is_feasible()
+    }
+
+    /// First play of an optimal hand-emptying plan.
+    pub fn first_action(&self, state: &GameStateView) -> Option<Action> {
+        self.solve(state).first_action().cloned()
+    }
+}
+
+/// Sorted build pile values, remaining hand cards per value (Skip-Bo at 0) and cards
+/// taken from each discard pile.
+type HandEmptyKey = (
+    [u8; BUILD_PILE_COUNT],
+    [u8; MAX_CARD_VALUE as usize + 1],
+    Vec<usize>,
+);
+
+struct HandEmptySearch<'a> {
+    hand: &'a [Card],
+    discards: &'a [Vec<Card>],
+    /// Cards each discard pile may give up.
+    usable: Vec<usize>,
+    node_limit: usize,
+    nodes: usize,
+    /// Largest discard play allowance each position is known to fail with; `usize::MAX`
+    /// when it fails with any.
+    failed: HashMap<HandEmptyKey, usize>,
+    build_next: [u8; BUILD_PILE_COUNT],
+    used_hand: Vec<bool>,
+    popped: Vec<usize>,
+    /// Plays so far, with hand indices into the original hand.
+    path: Vec<(CardSource, usize)>,
+    hand_left: usize,
+    /// Whether a discard play was available but over the allowance.
+    short_of_helpers: bool,
+    aborted: bool,
+}
+
+impl HandEmptySearch<'_> {
+    fn dfs(&mut self, helpers: usize) -> bool {
+        if self.hand_left == 0 {
+            return true;
+        }
+        if self.nodes >= self.node_limit {
+            self.aborted = true;
+            return false;
+        }
+        self.nodes += 1;
+        let key = self.key();
+        if let Some(&failed) = self.failed.get(&key)
+            && failed >= helpers
+        {
+            // Only positions that never ran short of discard plays fail for good.
+            self.short_of_helpers |= failed != usize::MAX;
+            return false;
+        }
+        let short_before = std::mem::take(&mut self.short_of_helpers);
+
+        for h in 0..self.hand.len() {
+            let card = self.hand[h];
+            let duplicate = (0..h).any(|e| !self.used_hand[e] && self.hand[e] == card);
+            if self.used_hand[h] || duplicate {
+                continue;
+            }
+            self.used_hand[h] = true;
+            self.hand_left -= 1;
+            let found = self.try_piles(CardSource::Hand(h), card, helpers);
+            self.hand_left += 1;
+            self.used_hand[h] = false;
+            if found {
+                return true;
+            }
+            if self.aborted {
+                return false;
+            }
+        }
+
+        for d in 0..self.discards.len() {
+            if self.popped[d] >= self.usable[d] {
+                continue;
+            }
+            let pile = &self.discards[d];
+            let card = pile[pile.len() - 1 - self.popped[d]];
+            if helpers == 0 {
+                self.short_of_helpers |= self.fits_anywhere(card);
+                continue;
+            }
+            self.popped[d] += 1;
+            let found = self.try_piles(CardSource::Discard(DiscardPileId(d)), card, helpers - 1);
+            self.popped[d] -= 1;
+            if found {
+                return true;
+            }
+            if self.aborted {
+                return false;
+            }
+        }
+
+        let limit = if self.short_of_helpers {
+            helpers
+        } else {
+            usize::MAX
+        };
+        let failed = self.failed.entry(key).or_insert(limit);
+        *failed = (*failed).max(limit);
+        self.short_of_helpers |= short_before;
+        false
+    }
+
+    /// Play `card` from `source` on each build pile it fits, skipping piles that require
+    /// the same value as an earlier one.
+    fn try_piles(&mut self, source: CardSource, card: Card, helpers: usize) -> bool {
+        for b in 0..BUILD_PILE_COUNT {
+            let required = self.build_next[b];
+            if !card.is_skip_bo() && card.value() != Some(required) {
+                continue;
+            }
+            if self.build_next[..b].contains(&required) {
+                continue;
+            }
+            self.build_next[b] = next_build_value(required);
+            self.path.push((source, b));
+            if self.dfs(helpers) {
+                return true;
+            }
+            self.path.pop();
+            self.build_next[b] = required;
+            if self.aborted {
+                return false;
+            }
+        }
+        false
+    }
+
+    fn fits_anywhere(&self, card: Card) -> bool {
+        card.is_skip_bo()
+            || self
+                .build_next
+                .iter()
+                .any(|&next| card.value() == Some(next))
+    }
+
+    fn key(&self) -> HandEmptyKey {
+        let mut build_next = self.build_next;
+        build_next.sort_unstable();
+        let mut hand = [0u8; MAX_CARD_VALUE as usize + 1];
+        for (card, _) in self
+            .hand
+            .iter()
+            .zip(&self.used_hand)
+            .filter(|(_, used)| !**used)
+        {
+            hand[card.value().unwrap_or(0) as usize] += 1;
+        }
+        (build_next, hand, self.popped.clone())
+    }
+
+    /// The path as actions; the engine shifts the remaining hand cards down after each
+    /// hand play.
+    fn plan(&self) -> Vec<Action> {
+        let path = &self.path;
+        path.iter()
+            .enumerate()
+            .map(|(k, &(source, b))| {
+                let source = match source {
+                    CardSource::Hand(h) => {
+                        let shift = path[..k]
+                            .iter()
+                            .filter(|(earlier, _)| matches!(earlier, CardSource::Hand(e) if *e < h))
+                            .count();
+                        CardSource::Hand(h - shift)
+                    }
+                    other => other,
+                };
+                Action::Play {
+                    source,
+                    build_pile: BuildPileId(b),
+                }
+            })
+            .collect()
+    }
+}
+
+/// The acting player's position while simulating a turn.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TurnPosition {
+    /// Value each build pile requires next.
+    pub build_next: [u8; BUILD_PILE_COUNT],
+    pub hand: Vec<Card>,
+    pub discard_piles: Vec<Vec<Card>>,
+    /// Known stock top; `None` once the top has been played and the next card is hidden.
+    pub stock_top: Option<Card>,
+    pub stock_count: usize,
+    /// Stock cards played since the simulation started.
+    pub stock_played: usize,
+    /// Build piles completed (and recycled) since the simulation started.
+    pub piles_completed: usize,
+    /// Skip-Bo cards spent from hand or discard piles since the simulation started.
+    pub wilds_spent: usize,
+}
+
+impl TurnPosition {
+    /// Snapshot the viewing player's position. Returns `None` when the view does not
+    /// contain the viewing player.
+    pub fn from_view(state: &GameStateView) -> Option<Self> {
+        let me = state.self_player_state()?;
+        Some(Self {
+            build_next: std::array::from_fn(|i| state.build_piles[i].next_value),
+            hand: state.hand.to_vec(),
+            discard_piles: me.discard_piles.to_vec(),
+            stock_top: me.stock_top,
+            stock_count: me.stock_count,
+            stock_played: 0,
+            piles_completed: 0,
+            wilds_spent: 0,
+        })
+    }
+
+    /// Card currently available from `source`, if any.
+    pub fn card_at(&self, source: CardSource) -> Option<Card> {
+        match source {
+            CardSource::Hand(i) => self.hand.get(i).copied(),
+            CardSource::Stock => self.stock_top,
+            CardSource::Discard(d) => self.discard_piles.get(d.0).and_then(|p| p.last()).copied(),
+        }
+    }
+
+    /// Plays available in this position, mirroring the engine's ordering (hand, stock,
+    /// discard piles). Duplicate hand cards only produce one play per build pile.
+    pub fn plays(&self) -> Vec<Action> {
+        let mut actions = Vec::new();
+        let mut seen_hand: Vec<Card> = Vec::with_capacity(self.hand.len());
+        for (hand_index, card) in self.hand.iter().enumerate() {
+            if seen_hand.contains(card) {
+                continue;
+            }
+            seen_hand.push(*card);
+            self.push_plays(&mut actions, *card, CardSource::Hand(hand_index));
+        }
+        if let Some(card) = self.stock_top {
+            self.push_plays(&mut actions, card, CardSource::Stock);
+        }
+        for (d, pile) in self.discard_piles.iter().enumerate() {
+            if let Some(card) = pile.last() {
+                self.push_plays(&mut actions, *card, CardSource::Discard(DiscardPileId(d)));
+            }
+        }
+        actions
+    }
+
+    fn push_plays(&self, actions: &mut Vec<Action>, card: Card, source: CardSource) {
+        for (build_pile, required) in self.build_next.iter().enumerate() {
+            // Piles requiring the same value are interchangeable for planning.
+            if self.build_next[..build_pile].contains(required) {
+                continue;
+            }
+            if card.matches_value(*required) {
+                actions.push(Action::Play {
+                    source,
+                    build_pile: BuildPileId(build_pile),
+                });
+            }
+        }
+    }
+
+    /// Apply a play or discard. Returns `false` (leaving the position untouched) when
+    /// the action is not possible here.
+    pub fn apply(&mut self, action: &Action) -> bool {
+        match *action {
+            Action::Play { source, build_pile } => {
+                let Some(required) = self.build_next.get(build_pile.0).copied() else {
+                    return false;
+                };
+                let Some(card) = self.card_at(source) else {
+                    return false;
+                };
+                if !card.matches_value(required) {
+                    return false;
+                }
+                match source {
+                    CardSource::Hand(i) => {
+                        self.hand.remove(i);
+                    }
+                    CardSource::Stock => {
+                        self.stock_top = None;
+                        self.stock_count = self.stock_count.saturating_sub(1);
+                        self.stock_played += 1;
+                    }
+                    CardSource::Discard(d) => {
+                        self.discard_piles[d].pop();
+                    }
+                }
+                if card.is_skip_bo() && !matches!(source, CardSource::Stock) {
+                    self.wilds_spent += 1;
+                }
+                if required == MAX_CARD_VALUE {
+                    self.piles_completed += 1;
+                }
+                self.build_next[build_pile] = next_build_value(required);
+                true
+            }
+            Action::Discard {
+                hand_index,
+                discard_pile,
+            } => {
+                if hand_index >= self.hand.len() || discard_pile.0 >= self.discard_piles.len() {
+                    return false;
+                }
+                let card = self.hand.remove(hand_index);
+                self.discard_piles[discard_pile].push(card);
+                true
+            }
+            Action::EndTurn => self.hand.is_empty(),
+        }
+    }
+
+    fn key(&self) -> PositionKey {
+        let mut hand = self.hand.clone();
+        hand.sort_by_key(|card| card.value().unwrap_or(0));
+        (
+            self.build_next,
+            hand,
+            self.discard_piles.iter().map(Vec::len).collect(),
+            self.stock_top.is_some(),
+        )
+    }
+}
+
+type PositionKey = ([u8; BUILD_PILE_COUNT], Vec<Card>, Vec<usize>, bool);
+
+/// Why a simulated turn plan stops.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TurnEnd {
+    /// The plan finishes with a discard, ending the turn.
+    Discard,
+    /// The hand was empty with nothing left to draw, so the plan ends the turn.
+    EndTurn,
+    /// A stock card was played and the newly revealed card is unknown.
+    StockRevealed,
+    /// The hand was emptied; the engine refills it with unknown cards.
+    HandEmptied,
+    /// The last stock card was played.
+    Won,
+}
+
+/// A complete (or information-limited) sequence of actions for the current turn.
+#[derive(Clone, Copy, Debug)]
+pub struct TurnPlan<'a> {
+    pub actions: &'a [Action],
+    pub position: &'a TurnPosition,
+    pub end: TurnEnd,
+}
+
+/// Enumerates turn plans (plays followed by a discard) reachable from a view.
+///
+/// Plans are reported through a callback to avoid materializing every combination of
+/// play sequence and discard. Transpositions of the same plays are visited once. The
+/// search stops early, keeping what it visited, once `interrupter` fires.
+#[derive(Clone, Debug)]
+pub struct TurnEnumerator {
+    pub node_limit: usize,
+    pub interrupter: Interrupter,
+}
+
+impl Default for TurnEnumerator {
+    fn default() -> Self {
+        Self::new(DEFAULT_NODE_LIMIT)
+    }
+}
+
+impl TurnEnumerator {
+    pub fn new(node_limit: usize) -> Self {
+        Self {
+            node_limit,
+            interrupter: Interrupter::new(),
+        }
+    }
+
+    /// Visit every plan reachable from `state` within the node budget (or until
+    /// interrupted). Returns the number
+    /// of positions expanded.
+    pub fn for_each_plan<F>(&self, state: &GameStateView, mut visit: F) -> usize
+    where
+        F: FnMut(TurnPlan<'_>),
+    {
+        let Some(mut position) = TurnPosition::from_view(state) else {
+            return 0;
+        };
+        let can_refill = state.draw_pile_count + state.recycle_pile_count > 0;
+        let mut actions = Vec::new();
+        let mut visited = HashSet::new();
+        let mut nodes = 0usize;
+        self.expand(
+            &mut position,
+            can_refill,
+            &mut actions,
+            &mut visited,
+            &mut nodes,
+            &mut visit,
+        );
+        nodes
+    }
+
+    fn expand<F>(
+        &self,
+        position: &mut TurnPosition,
+        can_refill: bool,
+        actions: &mut Vec<Action>,
+        visited: &mut HashSet<PositionKey>,
+        nodes: &mut usize,
+        visit: &mut F,
+    ) where
+        F: FnMut(TurnPlan<'_>),
+    {
+        if *nodes >= self.node_limit
+            || (*nodes > 0 && nodes.is_multiple_of(POLL_INTERVAL) && self.interrupter.should_stop())
+            || !visited.insert(position.key())
+        {
+            return;
+        }
+        *nodes += 1;
+
+        if position.stock_count == 0 && position.stock_played > 0 {
+            visit(TurnPlan {
+                actions,
+                position,
+                end: TurnEnd::Won,
+            });
+            return;
+        }
+        if position.stock_played > 0 && position.stock_top.is_none() {
+            visit(TurnPlan {
+                actions,
+                position,
+                end: TurnEnd::StockRevealed,
+            });
+            return;
+        }
+        if position.hand.is_empty() {
+            if !actions.is_empty() && can_refill {
+                visit(TurnPlan {
+                    actions,
+                    position,
+                    end: TurnEnd::HandEmptied,
+                });
+                return;
+            }
+            actions.push(Action::EndTurn);
+            visit(TurnPlan {
+                actions,
+                position,
+                end: TurnEnd::EndTurn,
+            });
+            actions.pop();
+        } else {
+            self.visit_discards(position, actions, visit);
+        }
+
+        for play in position.plays() {
+            let snapshot = position.clone();
+            if position.apply(&play) {
+                actions.push(play);
+                self.expand(position, can_refill, actions, visited, nodes, visit);
+                actions.pop();
+            }
+            *position = snapshot;
+        }
+    }
+
+    fn visit_discards<F>(
+        &self,
+        position: &mut TurnPosition,
+        actions: &mut Vec<Action>,
+        visit: &mut F,
+    ) where
+        F: FnMut(TurnPlan<'_>),
+    {
+        let mut seen_hand: Vec<Card> = Vec::with_capacity(position.hand.len());
+        for hand_index in 0..position.hand.len() {
+            let card = position.hand[hand_index];
+            if seen_hand.contains(&card) {
+                continue;
+            }
+            seen_hand.push(card);
+            for discard_pile in (0..position.discard_piles.len()).map(DiscardPileId) {
+                let discard = Action::Discard {
+                    hand_index,
+                    discard_pile,
+                };
+                position.apply(&discard);
+                actions.push(discard);
+                visit(TurnPlan {
+                    actions,
+                    position,
+                    end: TurnEnd::Discard,
+                });
+                actions.pop();
+                let card = position.discard_piles[discard_pile]
+                    .pop()
+                    .expect("discarded card must be on top");
+                position.hand.insert(hand_index, card);
+            }
+        }
+    }
+}
+
+/// Remainder of a turn plan that a bot keeps between decisions.
+///
+/// Planners work out a whole sequence of plays but the engine asks for one action at a
+/// time. The context stores the rest of the sequence together with the position it
+/// predicts, and hands out the next action while the view still matches that prediction.
+/// Anything the simulation cannot foresee (a revealed stock card, a refilled hand, another
+/// player's turn) changes the position and drops the plan; bots also clear it from
+/// [`Bot::on_turn_start`](crate::bot::Bot::on_turn_start).
+#[derive(Clone, Debug, Default)]
+pub struct TurnContext {
+    expected: Option<TurnPosition>,
+    remaining: VecDeque<Action>,
+    hits: usize,
+}
+
+impl TurnContext {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Next action of the stored plan if `state` is the position the plan predicted and
+    /// the action is legal. Otherwise the plan is dropped.
+    pub fn next_action(&mut self, state: &GameStateView, legal: &LegalSet) -> Option<Action> {
+        let expected = self.expected.take()?;
+        let mut position = TurnPosition::from_view(state)?;
+        if position != expected {
+            self.remaining.clear();
+            return None;
+        }
+        let action = self.remaining.pop_front()?;
+        if !legal.contains(&action) || !position.apply(&action) {
+            self.remaining.clear();
+            return None;
+        }
+        self.expect(position);
+        self.hits += 1;
+        Some(action)
+    }
+
+    /// Store `plan`, which starts at `state`, and return its first action.
+    pub fn start_plan(&mut self, state: &GameStateView, plan: Vec<Action>) -> Option<Action> {
+        self.clear();
+        let mut plan = VecDeque::from(plan);
+        let first = plan.pop_front()?;
+        if let Some(mut position) = TurnPosition::from_view(state)
+            && position.apply(&first)
+        {
+            self.remaining = plan;
+            self.expect(position);
+        }
+        Some(first)
+    }
+
+    /// Forget the stored plan.
+    pub fn clear(&mut self) {
+        self.expected = None;
+        self.remaining.clear();
+    }
+
+    /// Whether a plan is stored.
+    pub fn has_plan(&self) -> bool {
+        self.expected.is_some()
+    }
+
+    /// Decisions answered from a stored plan so far.
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+
+    fn expect(&mut self, mut position: TurnPosition) {
+        if self.remaining.is_empty() {
+            self.expected = None;
+            return;
+        }
+        // A fresh view starts its counters at zero.
+        position.stock_played = 0;
+        position.piles_completed = 0;
+        position.wilds_spent = 0;
+        self.expected = Some(position);
+    }
+}
+
+/// Default number of sampled refills used by [`RefillLookahead::default`].
+pub const DEFAULT_REFILL_SAMPLES: usize = 24;
+
+/// What the cards kept at the end of a turn promise for the next one.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct RefillOutlook {
+    /// Expected cards played in a row next turn, the stock card included.
+    pub playable: f64,
+    /// Chance that those plays reach the stock card.
+    pub stock_chance: f64,
+}
+
+/// Scores the position a turn ends in by the turn after it: the hand is refilled with
+/// cards sampled from those the player cannot see ([`unseen_cards`]) and a greedy chain
+/// of plays is run from each refill. Build piles are assumed unchanged; the opponents'
+/// turns in between are not simulated.
+///
+/// Comparing the outlook after each possible discard tells which hand is worth keeping,
+/// where a static bonus for duplicates or sequences only looks at the discard pile.
+#[derive(Clone, Copy, Debug)]
+pub struct RefillLookahead {
+    pub samples: usize,
+    /// Seed of the sampled refills; every call draws the same ones, so scores of
+    /// different positions are comparable.
+    pub seed: u64,
+}
+
+impl Default for RefillLookahead {
+    fn default() -> Self {
+        Self::new(DEFAULT_REFILL_SAMPLES)
+    }
+}
+
+impl RefillLookahead {
+    pub fn new(samples: usize) -> Self {
+        Self {
+            samples: samples.max(1),
+            seed: 0x4EF1_1BAD,
+        }
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Outlook of ending the turn in `position`, reached from `state` by the acting
+    /// player's own actions.
+    pub fn outlook(&self, state: &GameStateView, position: &TurnPosition) -> RefillOutlook {
+        let mut unseen = unseen_cards(state);
+        let draws = state
+            .settings
+            .hand_size
+            .saturating_sub(position.hand.len())
+            .min(unseen.len());
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let mut outlook = RefillOutlook::default();
+        for _ in 0..self.samples {
+            let (drawn, _) = unseen.partial_shuffle(&mut rng, draws);
+            let mut next = position.clone();
+            next.hand.extend_from_slice(drawn);
+            let (plays, stock) = greedy_chain(&mut next);
+            outlook.playable += plays as f64;
+            outlook.stock_chance += f64::from(u8::from(stock));
+        }
+        outlook.playable /= self.samples as f64;
+        outlook.stock_chance /= self.samples as f64;
+        outlook
+    }
+
+    /// Outlook after each discard the viewing player can make now, in the order of
+    /// `legal`.
+    pub fn score_discards(
+        &self,
+        state: &GameStateView,
+        legal: &[Action],
+    ) -> Vec<(Action, RefillOutlook)> {
+        let Some(position) = TurnPosition::from_view(state) else {
+            return Vec::new();
+        };
+        legal
+            .iter()
+            .filter(|action| matches!(action, Action::Discard { .. }))
+            .filter_map(|action| {
+                let mut next = position.clone();
+                next.apply(action)
+                    .then(|| (action.clone(), self.outlook(state, &next)))
+            })
+            .collect()
+    }
+}
+
+/// Plays made greedily from `position` and whether they reach the stock card: the stock
+/// card whenever it fits, else a number card, else a Skip-Bo that lets a number card or
+/// the stock card follow. Stops after the first stock play, leaving `position` where the
+/// chain ended.
+pub(crate) fn greedy_chain(position: &mut TurnPosition) -> (usize, bool) {
+    let is_wild = |position: &TurnPosition, action: &Action| match action {
+        Action::Play { source, .. } => position.card_at(*source).is_some_and(|c| c.is_skip_bo()),
+        _ => false,
+    };
+    let from_stock = |action: &Action| {
+        matches!(
+            action,
+            Action::Play {
+                source: CardSource::Stock,
+                ..
+            }
+        )
+    };
+    let mut plays = 0;
+    loop {
+        let candidates = position.plays();
+        let pick = candidates
+            .iter()
+            .find(|a| from_stock(a))
+            .or_else(|| candidates.iter().find(|a| !is_wild(position, a)))
+            .or_else(|| {
+                candidates.iter().find(|a| {
+                    let mut after = position.clone();
+                    after.apply(a)
+                        && after
+                            .plays()
+                            .iter()
+                            .any(|next| from_stock(next) || !is_wild(&after, next))
+                })
+            })
+            .cloned();
+        let Some(action) = pick else {
+            return (plays, false);
+        };
+        position.apply(&action);
+        plays += 1;
+        if from_stock(&action) {
+            return (plays, true);
+        }
+    }
+}
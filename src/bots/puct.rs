@@ -0,0 +1,315 @@
+use std::sync::Arc;
+
+use rand::Rng;
+
+use crate::action::{Action, PlayerId};
+use crate::analysis::sample_determinization;
+use crate::bot::{Bot, rank_scored};
+use crate::bots::eval::{EvalFn, Evaluator};
+use crate::bots::hybrid::softmax;
+use crate::bots::ismcts::Line;
+use crate::bots::planning::TurnPosition;
+use crate::bots::strength::Strength;
+use crate::interrupt::Interrupter;
+use crate::state::GameStateView;
+use crate::value::ValueEnsemble;
+
+/// Softmax temperature for the prior bot's scores; suits [`HeuristicBot`]'s scores, which
+/// are thousands apart between kinds of moves.
+///
+/// [`HeuristicBot`]: crate::HeuristicBot
+pub const DEFAULT_PRIOR_TEMPERATURE: f64 = 1_000.0;
+
+/// A decision of the acting player with its running value, which stands in for the
+/// value of children not tried yet.
+#[derive(Debug, Default)]
+struct Node {
+    children: Vec<Child>,
+    visits: u32,
+    reward: f64,
+}
+
+impl Node {
+    fn mean(&self) -> f64 {
+        if self.visits == 0 {
+            0.5
+        } else {
+            self.reward / f64::from(self.visits)
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Child {
+    action: Action,
+    node: usize,
+    prior: f64,
+    visits: u32,
+    reward: f64,
+}
+
+/// PUCT search as in AlphaZero: tree search guided by a policy's priors and scored by a
+/// value estimate instead of long rollouts.
+///
+/// The tree is the one [`IsMctsBot`] searches: each simulation samples a determinization
+/// of the hidden cards and walks the acting player's decisions of the current turn.
+/// Children get their priors when first legal, from the `prior` bot's
+/// [`Bot::rank_actions`] scores turned into probabilities by a softmax at `temperature`,
+/// and selection maximizes `Q + c·P·√N / (1 + n)` with untried children valued at their
+/// parent's mean. Each simulation expands one child and scores the position it leads to
+/// by the win probability of the given value nets, or without them by finishing the
+/// turn and the opponents' replies as [`IsMctsBot`] does. The most visited first action
+/// is played. Below full [`Strength`] the bot runs fewer simulations, and once the
+/// decision's [`Interrupter`] fires it plays the best action found so far.
+///
+/// [`IsMctsBot`]: crate::bots::IsMctsBot
+pub struct PuctBot<R: Rng, P: Bot> {
+    rng: R,
+    prior: P,
+    pub simulations: usize,
+    /// The `c` of the selection rule.
+    pub exploration: f64,
+    pub temperature: f64,
+    value: Option<ValueEnsemble>,
+    evaluation: Arc<dyn EvalFn>,
+    interrupter: Interrupter,
+}
+
+impl<R: Rng, P: Bot> PuctBot<R, P> {
+    pub const DEFAULT_SIMULATIONS: usize = 400;
+    pub const DEFAULT_EXPLORATION: f64 = 1.5;
+
+    pub fn new(rng: R, prior: P, simulations: usize) -> Self {
+        Self {
+            rng,
+            prior,
+            simulations: simulations.max(1),
+            exploration: Self::DEFAULT_EXPLORATION,
+            temperature: DEFAULT_PRIOR_TEMPERATURE,
+            value: None,
+            evaluation: Arc::new(Evaluator::Balanced),
+            interrupter: Interrupter::new(),
+        }
+    }
+
+    pub fn with_exploration(mut self, exploration: f64) -> Self {
+        self.exploration = exploration.max(0.0);
+        self
+    }
+
+    pub fn with_temperature(mut self, temperature: f64) -> Self {
+        self.temperature = temperature;
+        self
+    }
+
+    /// Score leaves with the win probability of `value` rather than by rollouts.
+    pub fn with_value(mut self, value: ValueEnsemble) -> Self {
+        self.value = Some(value);
+        self
+    }
+
+    /// Evaluation of rollouts when no value nets are given.
+    pub fn with_evaluation(mut self, evaluation: impl EvalFn + 'static) -> Self {
+        self.evaluation = Arc::new(evaluation);
+        self
+    }
+
+    pub fn with_strength(mut self, strength: Strength) -> Self {
+        self.simulations = strength.node_budget(self.simulations);
+        self
+    }
+
+    pub fn prior(&self) -> &P {
+        &self.prior
+    }
+
+    /// Visits of each first action tried, after running the search from `state`.
+    fn search(&mut self, state: &GameStateView) -> Vec<(Action, u32)> {
+        let Some(root) = TurnPosition::from_view(state) else {
+            return Vec::new();
+        };
+        let baseline = self.evaluation.evaluate(state, &root, 0);
+        let mut tree = vec![Node::default()];
+        for simulation in 0..self.simulations {
+            if simulation > 0 && self.interrupter.should_stop() {
+                break;
+            }
+            let Ok(game) = sample_determinization(state, &mut self.rng) else {
+                break;
+            };
+            let mut line = Line {
+                game,
+                me: state.self_player,
+                position: root.clone(),
+            };
+            let path = self.descend(&mut tree, &mut line);
+            let reward = self.leaf_value(state, &mut line, baseline);
+            tree[0].visits += 1;
+            tree[0].reward += reward;
+            let mut node = 0;
+            for index in path {
+                let child = &mut tree[node].children[index];
+                child.visits += 1;
+                child.reward += reward;
+                node = child.node;
+                tree[node].visits += 1;
+                tree[node].reward += reward;
+            }
+        }
+        tree.swap_remove(0)
+            .children
+            .into_iter()
+            .map(|child| (child.action, child.visits))
+            .collect()
+    }
+
+    /// Select down the tree while it is our turn, stopping after the first child not
+    /// visited before; returns the child index taken at each level.
+    fn descend(&mut self, tree: &mut Vec<Node>, line: &mut Line) -> Vec<usize> {
+        let mut path = Vec::new();
+        let mut node = 0;
+        while line.our_turn() {
+            let (Ok(view), Ok(legal)) = (
+                line.game.state_view(line.me),
+                line.game.legal_actions(line.me),
+            ) else {
+                break;
+            };
+            if legal
+                .iter()
+                .any(|action| tree[node].children.iter().all(|c| c.action != *action))
+            {
+                self.add_children(tree, node, &view, &legal);
+            }
+            let parent = &tree[node];
+            let sqrt_visits = f64::from(parent.visits.max(1)).sqrt();
+            let puct = |child: &Child| {
+                let mean = if child.visits == 0 {
+                    parent.mean()
+                } else {
+                    child.reward / f64::from(child.visits)
+                };
+                mean + self.exploration * child.prior * sqrt_visits / f64::from(1 + child.visits)
+            };
+            let Some(index) = (0..parent.children.len())
+                .filter(|&i| legal.contains(&parent.children[i].action))
+                .max_by(|&a, &b| puct(&parent.children[a]).total_cmp(&puct(&parent.children[b])))
+            else {
+                break;
+            };
+            let child = &tree[node].children[index];
+            if !line.play(child.action.clone()) {
+                break;
+            }
+            path.push(index);
+            let expanded = child.visits == 0;
+            node = child.node;
+            if expanded {
+                break;
+            }
+        }
+        path
+    }
+
+    /// Add the legal actions `node` has no child for yet, with their priors at `view`.
+    fn add_children(
+        &mut self,
+        tree: &mut Vec<Node>,
+        node: usize,
+        view: &GameStateView,
+        legal: &[Action],
+    ) {
+        let ranked = self.prior.rank_actions(view, legal);
+        let scores: Vec<f32> = ranked.iter().map(|(_, score)| *score).collect();
+        let priors = softmax(&scores, self.temperature);
+        for ((action, _), prior) in ranked.into_iter().zip(priors) {
+            if !legal.contains(&action) || tree[node].children.iter().any(|c| c.action == action) {
+                continue;
+            }
+            let child = tree.len();
+            tree.push(Node::default());
+            tree[node].children.push(Child {
+                action,
+                node: child,
+                prior,
+                visits: 0,
+                reward: 0.0,
+            });
+        }
+    }
+
+    /// Reward of the position a simulation stopped at: 1 for a win, 0 for a loss,
+    /// otherwise the value nets' win probability or the rollout reward.
+    fn leaf_value(&self, state: &GameStateView, line: &mut Line, baseline: f32) -> f64 {
+        if let Some(winner) = line.game.winner() {
+            return f64::from(u8::from(winner == line.me));
+        }
+        match &self.value {
+            Some(value) => line
+                .game
+                .state_view(line.me)
+                .map_or(0.5, |view| f64::from(value.evaluate(&view))),
+            None => line.finish(state, self.evaluation.as_ref(), baseline),
+        }
+    }
+}
+
+impl<R: Rng + Send, P: Bot> Bot for PuctBot<R, P> {
+    fn on_game_start(&mut self, state: &GameStateView) {
+        self.prior.on_game_start(state);
+    }
+
+    fn on_turn_start(&mut self, state: &GameStateView) {
+        self.prior.on_turn_start(state);
+    }
+
+    fn limit_search(&mut self, nodes: usize) {
+        self.simulations = self.simulations.min(nodes.max(1));
+    }
+
+    fn interrupt_with(&mut self, interrupter: &Interrupter) {
+        self.interrupter = interrupter.clone();
+    }
+
+    fn select_action(&mut self, state: &GameStateView, legal_actions: &[Action]) -> Action {
+        self.rank_actions(state, legal_actions).swap_remove(0).0
+    }
+
+    /// Legal actions by their share of the root visits, the most visited first. Forced
+    /// moves rank first with score 1.0.
+    fn rank_actions(
+        &mut self,
+        state: &GameStateView,
+        legal_actions: &[Action],
+    ) -> Vec<(Action, f32)> {
+        assert!(
+            !legal_actions.is_empty(),
+            "PUCT bot requires at least one legal action"
+        );
+        if legal_actions.len() == 1 {
+            return vec![(legal_actions[0].clone(), 1.0)];
+        }
+        let visits = self.search(state);
+        let total = visits.iter().map(|(_, v)| v).sum::<u32>().max(1) as f32;
+        let scored: Vec<(Action, f32)> = legal_actions
+            .iter()
+            .map(|action| {
+                let visits = visits
+                    .iter()
+                    .find(|(a, _)| a == action)
+                    .map_or(0, |(_, v)| *v);
+                (action.clone(), visits as f32 / total)
+            })
+            .collect();
+        let chosen = scored
+            .iter()
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(action, _)| action.clone())
+            .expect("legal actions are not empty");
+        rank_scored(chosen, scored)
+    }
+
+    fn on_game_end(&mut self, state: &GameStateView, winner: Option<PlayerId>) {
+        self.prior.on_game_end(state, winner);
+    }
+}
@@ -16,11 +16,26 @@ impl<R: Rng> RandomBot<R> {
     }
 }
 
-impl<R: Rng> Bot for RandomBot<R> {
+impl<R: Rng + Send> Bot for RandomBot<R> {
     fn select_action(&mut self, _state: &GameStateView, legal_actions: &[Action]) -> Action {
         legal_actions
             .choose(&mut self.rng)
             .cloned()
             .expect("at least one legal action must be available")
     }
+
+    /// All legal actions in random order with equal scores.
+    fn rank_actions(
+        &mut self,
+        _state: &GameStateView,
+        legal_actions: &[Action],
+    ) -> Vec<(Action, f32)> {
+        let score = 1.0 / legal_actions.len() as f32;
+        let mut ranked: Vec<(Action, f32)> = legal_actions
+            .iter()
+            .map(|action| (action.clone(), score))
+            .collect();
+        ranked.shuffle(&mut self.rng);
+        ranked
+    }
 }
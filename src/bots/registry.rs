@@ -1,9 +1,19 @@
+use std::collections::BTreeMap;
 use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
 
 use rand::SeedableRng;
 use rand::rngs::StdRng;
+use serde::Serialize;
 
 use crate::Bot;
+use crate::action::PlayerId;
+use crate::bots::beam_search::BeamSearchBot;
+use crate::bots::eval::Evaluator;
 use crate::bots::heuristic_2::Heuristic2Bot;
 use crate::bots::heuristic_3::Heuristic3Bot;
 use crate::bots::heuristic_4::Heuristic4Bot;
@@ -21,10 +31,40 @@ use crate::bots::heuristic_15::Heuristic15Bot;
 use crate::bots::heuristic_16::Heuristic16Bot;
 use crate::bots::heuristic_17::Heuristic17Bot;
 use crate::bots::heuristic_18::Heuristic18Bot;
+use crate::bots::heuristic_19::{Heuristic19Bot, PlanWeights};
+use crate::bots::hybrid::{self, Confidence, HybridBot};
+use crate::bots::ismcts::IsMctsBot;
+use crate::bots::noisy::NoisyBot;
+use crate::bots::opening::OpeningBook;
+use crate::bots::oracle::OracleBot;
+use crate::bots::puct::{self, PuctBot};
+use crate::bots::router::{PolicyRouterBot, RouterManifest};
+use crate::bots::softmax::SoftmaxBot;
+use crate::bots::strength::Strength;
+use crate::encoder::STATE_FEATURES;
+use crate::metrics::TemperatureFit;
+use crate::value::ValueEnsemble;
 use crate::{HeuristicBot, HumanBot, RandomBot};
 
-/// Returns a normalized label for a bot spec (the head token before any ':').
+/// The player name of a `human[:NAME]` spec seated at `seat`, `Human N` without one;
+/// `None` for bots.
+pub fn human_name(spec: &str, seat: PlayerId) -> Option<String> {
+    if !spec.trim().to_ascii_lowercase().starts_with("human") {
+        return None;
+    }
+    let name = spec
+        .split_once(':')
+        .map(|(_, name)| name.trim().to_string())
+        .filter(|name| !name.is_empty());
+    Some(name.unwrap_or_else(|| format!("Human {seat}")))
+}
+
+/// Returns a normalized label for a bot spec: its `label=NAME` option, or else the head
+/// token before any ':'.
 pub fn label_for_spec(spec: &str) -> String {
+    if let Some(label) = option_value(spec, "label") {
+        return label.to_string();
+    }
     spec.split(':')
         .next()
         .unwrap_or(spec)
@@ -32,10 +72,348 @@ pub fn label_for_spec(spec: &str) -> String {
         .to_ascii_lowercase()
 }
 
-/// Create a bot instance from a CLI-style spec.
+/// Temperature a `softmax:<temperature>:<spec>` bot samples its moves at; `None` for
+/// every other spec, whose bots do not sample from their ranking.
+pub fn sampling_temperature(spec: &str) -> Option<f64> {
+    let (kind, rest) = spec.trim().split_once(':')?;
+    if !kind.trim().eq_ignore_ascii_case("softmax") {
+        return None;
+    }
+    rest.split(':')
+        .next()?
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|t| *t > 0.0)
+}
+
+/// Value of the `key=value` option `key` of a spec, if present (the last one wins).
+fn option_value<'a>(spec: &'a str, key: &str) -> Option<&'a str> {
+    spec.split(':')
+        .skip(1)
+        .filter_map(|option| option.split_once('='))
+        .filter(|(k, _)| k.trim().eq_ignore_ascii_case(key))
+        .map(|(_, value)| value.trim())
+        .last()
+}
+
+/// Strip the options every spec may carry, `label=NAME` and `seed=N`, returning the rest
+/// of the spec and the seed.
+fn common_options(spec: &str) -> Result<(String, Option<u64>), Box<dyn Error>> {
+    let seed = option_value(spec, "seed")
+        .map(|value| {
+            value
+                .parse::<u64>()
+                .map_err(|_| format!("invalid seed in bot spec: {value}"))
+        })
+        .transpose()?;
+    let rest: Vec<&str> = spec
+        .split(':')
+        .enumerate()
+        .filter(|(i, part)| {
+            *i == 0
+                || !part.split_once('=').is_some_and(|(key, _)| {
+                    ["label", "seed"].contains(&key.trim().to_ascii_lowercase().as_str())
+                })
+        })
+        .map(|(_, part)| part)
+        .collect();
+    Ok((rest.join(":"), seed))
+}
+
+/// `strength=N` option of a spec (see [`Strength`]); full strength when absent. Other
+/// `key=value` options are rejected unless listed in `extra`; parameters without `=` are
+/// left to the caller.
+fn strength_option(spec: &str, extra: &[&str]) -> Result<Strength, Box<dyn Error>> {
+    let mut strength = Strength::MAX;
+    for option in spec.split(':').skip(1).filter(|p| p.contains('=')) {
+        match option.split_once('=') {
+            Some((key, value)) if key.trim().eq_ignore_ascii_case("strength") => {
+                strength = value.parse()?;
+            }
+            Some((key, _)) if extra.iter().any(|e| key.trim().eq_ignore_ascii_case(e)) => {}
+            _ => return Err(format!("unknown option '{option}' in bot spec: {spec}").into()),
+        }
+    }
+    Ok(strength)
+}
+
+/// `eval=NAME` option of a spec (see [`Evaluator`]); the default evaluator when absent.
+fn eval_option(spec: &str) -> Result<Evaluator, Box<dyn Error>> {
+    Ok(option_value(spec, "eval")
+        .map(str::parse)
+        .transpose()?
+        .unwrap_or_default())
+}
+
+/// `value=PATH,PATH,...` option of a spec: the [`ValueEnsemble`] of those checkpoints.
+fn value_option(spec: &str) -> Result<Option<ValueEnsemble>, Box<dyn Error>> {
+    option_value(spec, "value").map(value_ensemble).transpose()
+}
+
+/// The [`ValueEnsemble`] of the comma-separated checkpoint paths in `list`, checked
+/// against the current encoder.
+fn value_ensemble(list: &str) -> Result<ValueEnsemble, Box<dyn Error>> {
+    let paths: Vec<&str> = list.split(',').map(str::trim).collect();
+    let ensemble = ValueEnsemble::load(&paths).map_err(|err| format!("value nets: {err}"))?;
+    if ensemble.inputs() != STATE_FEATURES {
+        return Err(format!(
+            "value nets take {} inputs, the encoder produces {STATE_FEATURES}",
+            ensemble.inputs()
+        )
+        .into());
+    }
+    Ok(ensemble)
+}
+
+/// The softmax temperature fitted for `spec` (see [`TemperatureFit`]), if the metadata of
+/// its checkpoint (the first `value` net, or its `checkpoint`) holds a fit for exactly this
+/// spec.
+fn calibrated_temperature(spec: &str) -> Result<Option<f64>, Box<dyn Error>> {
+    let checkpoint = option_value(spec, "value")
+        .and_then(|list| list.split(',').next())
+        .or_else(|| option_value(spec, "checkpoint"));
+    let Some(checkpoint) = checkpoint.map(str::trim) else {
+        return Ok(None);
+    };
+    Ok(TemperatureFit::load(Path::new(checkpoint))?
+        .filter(|fit| fit.spec.trim() == spec.trim())
+        .map(|fit| fit.temperature))
+}
+
+/// The opening book at `path`, read once and shared by every bot created from a spec
+/// naming it, however the path is spelled. The file is read again once it has been
+/// modified or has changed size.
+fn shared_book(path: &str) -> Result<Arc<OpeningBook>, Box<dyn Error>> {
+    type Loaded = ((SystemTime, u64), Arc<OpeningBook>);
+    static BOOKS: Mutex<BTreeMap<PathBuf, Loaded>> = Mutex::new(BTreeMap::new());
+    let (canonical, stamp) = fs::canonicalize(path)
+        .and_then(|canonical| {
+            let meta = fs::metadata(&canonical)?;
+            Ok((canonical, (meta.modified()?, meta.len())))
+        })
+        .map_err(|err| format!("{path}: {err}"))?;
+    let mut books = BOOKS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    if let Some((loaded, book)) = books.get(&canonical)
+        && *loaded == stamp
+    {
+        return Ok(book.clone());
+    }
+    let book = Arc::new(OpeningBook::load(&canonical)?);
+    books.insert(canonical, (stamp, book.clone()));
+    Ok(book)
+}
+
+/// Plan weights of a spec: `weights=W1,W2,...` (see [`PlanWeights::to_vec`]) or
+/// `checkpoint=PATH` to a JSON file such as `cem --out` writes.
+fn plan_weights_option(spec: &str) -> Result<Option<PlanWeights>, Box<dyn Error>> {
+    if let Some(path) = option_value(spec, "checkpoint") {
+        let json = fs::read_to_string(path).map_err(|err| format!("{path}: {err}"))?;
+        return Ok(Some(serde_json::from_str(&json)?));
+    }
+    let Some(list) = option_value(spec, "weights") else {
+        return Ok(None);
+    };
+    let values = list
+        .split(',')
+        .map(|w| w.trim().parse::<f64>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| format!("invalid plan weights: {list}"))?;
+    PlanWeights::from_slice(&values).map(Some).ok_or_else(|| {
+        format!(
+            "expected {} plan weights, got {}",
+            PlanWeights::LEN,
+            values.len()
+        )
+        .into()
+    })
+}
+
+/// A kind of bot [`create_bot_from_spec`] builds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct SpecInfo {
+    /// Head of the spec, e.g. `beam`.
+    pub name: &'static str,
+    /// What may follow the name; brackets mark optional parts.
+    pub params: &'static str,
+    pub description: &'static str,
+}
+
+/// `NAME PARAMS` padded to a column, then the description.
+impl fmt::Display for SpecInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let usage = format!("{}{}", self.name, self.params);
+        if usage.len() < 24 {
+            write!(f, "{usage:<24}{}", self.description)
+        } else {
+            write!(f, "{usage}\n{:<24}{}", "", self.description)
+        }
+    }
+}
+
+const SPECS: &[SpecInfo] = &[
+    SpecInfo {
+        name: "human",
+        params: "[:NAME]",
+        description: "Asks on the terminal for every move.",
+    },
+    SpecInfo {
+        name: "random",
+        params: "[:SEED]",
+        description: "Picks uniformly among the legal actions.",
+    },
+    SpecInfo {
+        name: "heuristic",
+        params: "",
+        description: "Rule-based baseline: stock first, then discards, then hand.",
+    },
+    SpecInfo {
+        name: "heuristic2",
+        params: "",
+        description: "Plays the shortest sequence that reaches the stock card this turn.",
+    },
+    SpecInfo {
+        name: "heuristic3",
+        params: "",
+        description: "heuristic2, discarding without card priorities.",
+    },
+    SpecInfo {
+        name: "heuristic4",
+        params: "",
+        description: "heuristic3, also playing cards at or above the stock value.",
+    },
+    SpecInfo {
+        name: "heuristic5",
+        params: "",
+        description: "heuristic4, playing only cards above the stock value and at least 6.",
+    },
+    SpecInfo {
+        name: "heuristic6",
+        params: "",
+        description: "heuristic5 with a minimum of 5.",
+    },
+    SpecInfo {
+        name: "heuristic7",
+        params: "",
+        description: "heuristic5 with a minimum of 7.",
+    },
+    SpecInfo {
+        name: "heuristic8",
+        params: "",
+        description: "heuristic5, never setting up the next player's stock card.",
+    },
+    SpecInfo {
+        name: "heuristic9",
+        params: "",
+        description: "heuristic8, blocking only in the final fallback.",
+    },
+    SpecInfo {
+        name: "heuristic10",
+        params: "",
+        description: "heuristic9, keeping build piles that share a next value.",
+    },
+    SpecInfo {
+        name: "heuristic11",
+        params: "",
+        description: "heuristic10, emptying the hand when all of it can be played.",
+    },
+    SpecInfo {
+        name: "heuristic12",
+        params: "",
+        description: "heuristic11, discarding a card just below a pile's top.",
+    },
+    SpecInfo {
+        name: "heuristic13",
+        params: "",
+        description: "heuristic11, also using discard tops to empty the hand.",
+    },
+    SpecInfo {
+        name: "heuristic14",
+        params: "",
+        description: "Always plays when it can: stock, then discards, then hand.",
+    },
+    SpecInfo {
+        name: "heuristic15",
+        params: "",
+        description: "heuristic14 preferring hand plays over discard plays.",
+    },
+    SpecInfo {
+        name: "heuristic16",
+        params: "",
+        description: "heuristic14 with scored discard destinations.",
+    },
+    SpecInfo {
+        name: "heuristic17",
+        params: "",
+        description: "heuristic15 choosing the most promising build pile.",
+    },
+    SpecInfo {
+        name: "heuristic18",
+        params: "",
+        description: "heuristic16 with a stock-play chooser that looks at upcoming matches.",
+    },
+    SpecInfo {
+        name: "heuristic19",
+        params: "[:strength=N][:weights=W1,W2,...|:checkpoint=PATH][:book=PATH]",
+        description: "Turn planner scoring every sequence of plays and closing discard.",
+    },
+    SpecInfo {
+        name: "beam",
+        params: "[:WIDTH[:DEPTH]][:strength=N][:eval=NAME|:value=PATH,...]",
+        description: "Beam search over sampled hidden cards.",
+    },
+    SpecInfo {
+        name: "ismcts",
+        params: "[:ITERATIONS][:exploration=C][:eval=NAME][:strength=N]",
+        description: "Information-set Monte Carlo tree search with rollouts.",
+    },
+    SpecInfo {
+        name: "puct",
+        params: "[:PATH,...][:sims=N][:cpuct=C][:prior=NAME][:temperature=T][:eval=NAME][:strength=N]",
+        description: "Tree search guided by a prior bot, scored by value nets or rollouts.",
+    },
+    SpecInfo {
+        name: "oracle",
+        params: "[:NODES]",
+        description: "Cheating turn planner that sees every hidden card.",
+    },
+    SpecInfo {
+        name: "noisy",
+        params: ":PROBABILITY:SPEC",
+        description: "Plays SPEC's second or third choice with the given probability.",
+    },
+    SpecInfo {
+        name: "softmax",
+        params: ":TEMPERATURE:SPEC",
+        description: "Samples moves from a softmax over SPEC's move scores.",
+    },
+    SpecInfo {
+        name: "hybrid",
+        params: "[:min_prob=P|:max_entropy=H][:temperature=T]:PRIMARY|FALLBACK",
+        description: "Plays PRIMARY unless its ranking is uncertain, then FALLBACK.",
+    },
+    SpecInfo {
+        name: "router",
+        params: ":PATH",
+        description: "Routes each game to a bot by player count and stock size.",
+    },
+];
+
+/// Every kind of bot [`create_bot_from_spec`] builds. Any spec also takes `label=NAME`
+/// and `seed=N`.
+pub fn available_specs() -> Vec<SpecInfo> {
+    SPECS.to_vec()
+}
+
+/// Create a bot instance from a CLI-style spec ([`available_specs`] lists them at
+/// runtime).
 /// Supported specs:
 /// - human[:name]
 /// - random[:seed]
+/// - beam[:width[:depth]][:strength=N][:eval=NAME|:value=PATH,...] (see [`Evaluator`];
+///   `value` evaluates with the mean logit of the listed value-net checkpoints)
 /// - heuristic
 /// - heuristic2
 /// - heuristic3
@@ -54,60 +432,270 @@ pub fn label_for_spec(spec: &str) -> String {
 /// - heuristic16
 /// - heuristic17
 /// - heuristic18
+/// - heuristic19[:strength=N][:weights=W1,W2,...|:checkpoint=PATH][:book=PATH] (see
+///   [`PlanWeights`]; `book` plays first turns from an [`OpeningBook`])
+/// - hybrid[:min_prob=P|:max_entropy=H][:temperature=T]:<primary>|<fallback> (plays
+///   `primary` unless its ranking is uncertain, then `fallback`, see [`HybridBot`];
+///   default min_prob=0.5, and the temperature fitted for `primary` by `evaluate
+///   --fit-temperature` when its checkpoint has one)
+/// - ismcts[:iterations][:exploration=C][:eval=NAME][:strength=N] (see [`IsMctsBot`])
+/// - noisy:<probability>:<spec> (wraps `spec` in a [`NoisyBot`])
+/// - oracle[:nodes] (cheats: sees every hidden card, see [`OracleBot`])
+/// - puct[:PATH,...][:sims=N][:cpuct=C][:prior=NAME][:temperature=T][:eval=NAME]
+///   [:strength=N] (see [`PuctBot`]; priors from the `prior` bot, default heuristic, and
+///   leaves scored by the listed value-net checkpoints, or by rollouts without them)
+/// - router:PATH (plays each game with the bot a [`RouterManifest`] routes its player
+///   count and stock size to, see [`PolicyRouterBot`])
+/// - softmax:<temperature>:<spec> (samples from `spec`'s ranking, see [`SoftmaxBot`])
+///
+/// `strength` (1-10, default 10) weakens the searching bots for difficulty settings.
+/// Any spec may also carry `label=NAME` (see [`label_for_spec`]) and `seed=N`, a fixed
+/// seed that replaces `seed` in every game.
 pub fn create_bot_from_spec(
     spec: &str,
-    index: usize,
+    seat: PlayerId,
     seed: u64,
 ) -> Result<Box<dyn Bot>, Box<dyn Error>> {
+    let (spec, fixed_seed) = common_options(spec)?;
+    create_bot(&spec, seat, fixed_seed.unwrap_or(seed))
+}
+
+fn create_bot(spec: &str, seat: PlayerId, seed: u64) -> Result<Box<dyn Bot>, Box<dyn Error>> {
     let spec_lower = spec.to_ascii_lowercase();
-    if spec_lower.starts_with("human") {
-        let name = spec
+    if spec_lower.starts_with("noisy") {
+        let (noise, inner) = spec
             .split_once(':')
-            .map(|(_, name)| name.trim().to_string());
-        let name = name.unwrap_or_else(|| format!("Human {index}"));
+            .and_then(|(_, rest)| rest.split_once(':'))
+            .ok_or_else(|| format!("expected noisy:<probability>:<spec>, got {spec}"))?;
+        let noise = noise
+            .trim()
+            .parse::<f64>()
+            .ok()
+            .filter(|p| (0.0..=1.0).contains(p))
+            .ok_or_else(|| format!("invalid noise probability: {noise}"))?;
+        let inner = create_bot_from_spec(inner, seat, seed)?;
+        let seed = seed ^ ((seat.0 as u64 + 1) * 0x0A15_E5EE);
+        Ok(Box::new(NoisyBot::new(inner, noise, seed)))
+    } else if spec_lower.starts_with("softmax") {
+        let temperature = sampling_temperature(spec)
+            .ok_or_else(|| format!("expected softmax:<temperature>:<spec>, got {spec}"))?;
+        let (_, inner) = spec
+            .split_once(':')
+            .and_then(|(_, rest)| rest.split_once(':'))
+            .ok_or_else(|| format!("expected softmax:<temperature>:<spec>, got {spec}"))?;
+        let inner = create_bot_from_spec(inner, seat, seed)?;
+        let seed = seed ^ ((seat.0 as u64 + 1) * 0x50F7_3A8E);
+        Ok(Box::new(SoftmaxBot::new(inner, temperature, seed)))
+    } else if spec_lower.starts_with("hybrid") {
+        let usage = || format!("expected hybrid[:options]:<primary>|<fallback>, got {spec}");
+        let (_, rest) = spec.split_once(':').ok_or_else(usage)?;
+        let (mut rest, fallback) = rest.split_once('|').ok_or_else(usage)?;
+        let mut confidence = Confidence::default();
+        let mut temperature = None;
+        while let Some((option, tail)) = rest.split_once(':') {
+            let Some((key, value)) = option.split_once('=') else {
+                break;
+            };
+            match key.trim().to_ascii_lowercase().as_str() {
+                "min_prob" | "max_entropy" => confidence = option.parse()?,
+                "temperature" => {
+                    temperature = Some(
+                        value
+                            .trim()
+                            .parse::<f64>()
+                            .ok()
+                            .filter(|t| *t > 0.0)
+                            .ok_or_else(|| format!("invalid hybrid temperature: {value}"))?,
+                    );
+                }
+                _ => break,
+            }
+            rest = tail;
+        }
+        let temperature = match temperature {
+            Some(temperature) => temperature,
+            None => calibrated_temperature(rest)?.unwrap_or(hybrid::DEFAULT_TEMPERATURE),
+        };
+        let primary = create_bot_from_spec(rest, seat, seed)?;
+        let fallback = create_bot_from_spec(fallback, seat, seed)?;
+        Ok(Box::new(
+            HybridBot::new(primary, fallback, confidence).with_temperature(temperature),
+        ))
+    } else if let Some(name) = human_name(spec, seat) {
         Ok(Box::new(HumanBot::new(name)))
     } else if spec_lower.starts_with("random") {
         let custom_seed = spec
             .split_once(':')
             .and_then(|(_, value)| value.parse::<u64>().ok())
-            .unwrap_or(seed ^ ((index as u64 + 1) * 0x9E37_79B9));
+            .unwrap_or(seed ^ ((seat.0 as u64 + 1) * 0x9E37_79B9));
         Ok(Box::new(RandomBot::new(StdRng::seed_from_u64(custom_seed))))
+    } else if spec_lower.starts_with("oracle") {
+        match spec.split(':').nth(1).map(str::trim) {
+            Some(value) => {
+                let nodes = value
+                    .parse::<usize>()
+                    .map_err(|_| format!("invalid oracle node limit: {value}"))?;
+                Ok(Box::new(OracleBot::with_node_limit(nodes)))
+            }
+            None => Ok(Box::new(OracleBot::new())),
+        }
+    } else if spec_lower.starts_with("beam") {
+        let strength = strength_option(spec, &["eval", "value"])?;
+        let evaluator = eval_option(spec)?;
+        let ensemble = value_option(spec)?;
+        if ensemble.is_some() && option_value(spec, "eval").is_some() {
+            return Err(format!("give either eval or value, not both: {spec}").into());
+        }
+        let mut params = spec
+            .split(':')
+            .skip(1)
+            .map(str::trim)
+            .filter(|p| !p.contains('='));
+        let width = match params.next() {
+            Some(value) => value
+                .parse::<usize>()
+                .map_err(|_| format!("invalid beam width: {value}"))?,
+            None => BeamSearchBot::<StdRng>::DEFAULT_WIDTH,
+        };
+        let depth = match params.next() {
+            Some(value) => value
+                .parse::<usize>()
+                .map_err(|_| format!("invalid beam depth: {value}"))?,
+            None => BeamSearchBot::<StdRng>::DEFAULT_DEPTH,
+        };
+        let rng = StdRng::seed_from_u64(seed ^ ((seat.0 as u64 + 1) * 0x9E37_79B9));
+        let bot = BeamSearchBot::new(rng, width, depth).with_strength(strength);
+        Ok(match ensemble {
+            Some(ensemble) => Box::new(bot.with_evaluation(ensemble)),
+            None => Box::new(bot.with_evaluation(evaluator)),
+        })
+    } else if spec_lower.starts_with("ismcts") {
+        let strength = strength_option(spec, &["exploration", "eval"])?;
+        let iterations = match spec
+            .split(':')
+            .skip(1)
+            .map(str::trim)
+            .find(|p| !p.contains('='))
+        {
+            Some(value) => value
+                .parse::<usize>()
+                .map_err(|_| format!("invalid IS-MCTS iterations: {value}"))?,
+            None => IsMctsBot::<StdRng>::DEFAULT_ITERATIONS,
+        };
+        let rng = StdRng::seed_from_u64(seed ^ ((seat.0 as u64 + 1) * 0x9E37_79B9));
+        let mut bot = IsMctsBot::new(rng, iterations).with_evaluation(eval_option(spec)?);
+        if let Some(value) = option_value(spec, "exploration") {
+            let exploration = value
+                .parse::<f64>()
+                .ok()
+                .filter(|c| *c >= 0.0)
+                .ok_or_else(|| format!("invalid IS-MCTS exploration: {value}"))?;
+            bot = bot.with_exploration(exploration);
+        }
+        Ok(Box::new(bot.with_strength(strength)))
+    } else if spec_lower.starts_with("puct") {
+        let strength = strength_option(spec, &["sims", "cpuct", "prior", "temperature", "eval"])?;
+        let mut params = spec
+            .split(':')
+            .skip(1)
+            .map(str::trim)
+            .filter(|p| !p.contains('='));
+        let ensemble = params.next().map(value_ensemble).transpose()?;
+        if let Some(extra) = params.next() {
+            return Err(format!("unexpected parameter '{extra}' in bot spec: {spec}").into());
+        }
+        if ensemble.is_some() && option_value(spec, "eval").is_some() {
+            return Err(format!("give either value nets or eval, not both: {spec}").into());
+        }
+        let simulations = match option_value(spec, "sims") {
+            Some(value) => value
+                .parse::<usize>()
+                .map_err(|_| format!("invalid PUCT simulations: {value}"))?,
+            None => PuctBot::<StdRng, Box<dyn Bot>>::DEFAULT_SIMULATIONS,
+        };
+        let prior = option_value(spec, "prior").unwrap_or("heuristic");
+        let prior = create_bot_from_spec(prior, seat, seed)?;
+        let rng = StdRng::seed_from_u64(seed ^ ((seat.0 as u64 + 1) * 0x9E37_79B9));
+        let mut bot = PuctBot::new(rng, prior, simulations).with_evaluation(eval_option(spec)?);
+        if let Some(ensemble) = ensemble {
+            bot = bot.with_value(ensemble);
+        }
+        if let Some(value) = option_value(spec, "cpuct") {
+            let exploration = value
+                .parse::<f64>()
+                .ok()
+                .filter(|c| *c >= 0.0)
+                .ok_or_else(|| format!("invalid PUCT exploration: {value}"))?;
+            bot = bot.with_exploration(exploration);
+        }
+        let temperature = match option_value(spec, "temperature") {
+            Some(value) => value
+                .parse::<f64>()
+                .ok()
+                .filter(|t| *t > 0.0)
+                .ok_or_else(|| format!("invalid PUCT prior temperature: {value}"))?,
+            None => puct::DEFAULT_PRIOR_TEMPERATURE,
+        };
+        Ok(Box::new(
+            bot.with_temperature(temperature).with_strength(strength),
+        ))
+    } else if spec_lower.starts_with("router") {
+        let path = spec
+            .split_once(':')
+            .map(|(_, path)| path.trim())
+            .filter(|path| !path.is_empty())
+            .ok_or_else(|| format!("expected router:PATH, got {spec}"))?;
+        let manifest = RouterManifest::load(path.as_ref())?;
+        Ok(Box::new(PolicyRouterBot::from_manifest(
+            &manifest, seat, seed,
+        )?))
     } else if spec_lower.starts_with("heuristic2") {
-        Ok(Box::new(Heuristic2Bot::default()))
+        Ok(Box::new(Heuristic2Bot))
     } else if spec_lower.starts_with("heuristic3") {
-        Ok(Box::new(Heuristic3Bot::default()))
+        Ok(Box::new(Heuristic3Bot))
     } else if spec_lower.starts_with("heuristic4") {
-        Ok(Box::new(Heuristic4Bot::default()))
+        Ok(Box::new(Heuristic4Bot))
     } else if spec_lower.starts_with("heuristic5") {
-        Ok(Box::new(Heuristic5Bot::default()))
+        Ok(Box::new(Heuristic5Bot))
     } else if spec_lower.starts_with("heuristic6") {
-        Ok(Box::new(Heuristic6Bot::default()))
+        Ok(Box::new(Heuristic6Bot))
     } else if spec_lower.starts_with("heuristic7") {
-        Ok(Box::new(Heuristic7Bot::default()))
+        Ok(Box::new(Heuristic7Bot))
     } else if spec_lower.starts_with("heuristic8") {
-        Ok(Box::new(Heuristic8Bot::default()))
+        Ok(Box::new(Heuristic8Bot))
     } else if spec_lower.starts_with("heuristic9") {
-        Ok(Box::new(Heuristic9Bot::default()))
+        Ok(Box::new(Heuristic9Bot))
     } else if spec_lower.starts_with("heuristic10") {
-        Ok(Box::new(Heuristic10Bot::default()))
+        Ok(Box::new(Heuristic10Bot))
     } else if spec_lower.starts_with("heuristic11") {
-        Ok(Box::new(Heuristic11Bot::default()))
+        Ok(Box::new(Heuristic11Bot::new()))
     } else if spec_lower.starts_with("heuristic12") {
-        Ok(Box::new(Heuristic12Bot::default()))
+        Ok(Box::new(Heuristic12Bot::new()))
     } else if spec_lower.starts_with("heuristic13") {
-        Ok(Box::new(Heuristic13Bot::default()))
+        Ok(Box::new(Heuristic13Bot::new()))
     } else if spec_lower.starts_with("heuristic14") {
-        Ok(Box::new(Heuristic14Bot::default()))
+        Ok(Box::new(Heuristic14Bot))
     } else if spec_lower.starts_with("heuristic15") {
-        Ok(Box::new(Heuristic15Bot::default()))
+        Ok(Box::new(Heuristic15Bot))
     } else if spec_lower.starts_with("heuristic16") {
-        Ok(Box::new(Heuristic16Bot::default()))
+        Ok(Box::new(Heuristic16Bot))
     } else if spec_lower.starts_with("heuristic17") {
-        Ok(Box::new(Heuristic17Bot::default()))
+        Ok(Box::new(Heuristic17Bot))
     } else if spec_lower.starts_with("heuristic18") {
-        Ok(Box::new(Heuristic18Bot::default()))
+        Ok(Box::new(Heuristic18Bot))
+    } else if spec_lower.starts_with("heuristic19") {
+        let strength = strength_option(spec, &["weights", "checkpoint", "book"])?;
+        let seed = seed ^ ((seat.0 as u64 + 1) * 0x9E37_79B9);
+        let mut bot = Heuristic19Bot::new();
+        if let Some(weights) = plan_weights_option(spec)? {
+            bot = bot.with_weights(weights);
+        }
+        if let Some(path) = option_value(spec, "book") {
+            bot = bot.with_opening_book(shared_book(path)?);
+        }
+        Ok(Box::new(bot.with_strength(strength).with_seed(seed)))
     } else if spec_lower.starts_with("heuristic") {
-        Ok(Box::new(HeuristicBot::default()))
+        Ok(Box::new(HeuristicBot))
     } else {
         Err(format!("unrecognized bot spec: {spec}").into())
     }
@@ -0,0 +1,282 @@
+//! Policies routed by game configuration.
+//!
+//! A policy trained at one player count or stock size rarely plays its best at another.
+//! [`PolicyRouterBot`] holds one bot per configuration and hands every game to the bot
+//! whose [`Route`] matches the game's settings. The mapping is a manifest in TOML, or
+//! JSON when the file ends in `.json`, with the bot entries of a
+//! [`BotsConfig`](crate::bots::BotsConfig):
+//!
+//! ```toml
+//! [[routes]]
+//! players = 2
+//! bot = { type = "beam", options = { value = "nets/two-players.json" } }
+//!
+//! [[routes]]
+//! players = 4
+//! stock_size = [1, 15]
+//! bot = { type = "heuristic19", checkpoint = "weights/short-stocks.json" }
+//!
+//! [default]
+//! type = "heuristic13"
+//! ```
+//!
+//! The first matching route wins; games no route matches go to `default`. Bot specs take
+//! the manifest as `router:PATH`.
+
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::action::{Action, PlayerId};
+use crate::bot::Bot;
+use crate::bots::config::BotConfig;
+use crate::bots::create_bot_from_spec;
+use crate::interrupt::Interrupter;
+use crate::state::{FullStateView, GameSettings, GameStateView};
+
+/// Games a routed bot plays: those with this player count and a stock size in this
+/// inclusive range. Unset keys match every game.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Route {
+    pub players: Option<usize>,
+    pub stock_size: Option<(usize, usize)>,
+}
+
+impl Route {
+    pub fn matches(&self, settings: &GameSettings) -> bool {
+        self.players
+            .is_none_or(|players| players == settings.num_players)
+            && self
+                .stock_size
+                .is_none_or(|(min, max)| (min..=max).contains(&settings.stock_size))
+    }
+}
+
+/// `players=N stock=MIN-MAX`, or `any` for a route matching every game.
+impl fmt::Display for Route {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts = Vec::new();
+        if let Some(players) = self.players {
+            parts.push(format!("players={players}"));
+        }
+        if let Some((min, max)) = self.stock_size {
+            parts.push(format!("stock={min}-{max}"));
+        }
+        if parts.is_empty() {
+            f.write_str("any")
+        } else {
+            f.write_str(&parts.join(" "))
+        }
+    }
+}
+
+/// One route of a [`RouterManifest`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RouteConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub players: Option<usize>,
+    /// Inclusive `[MIN, MAX]` stock sizes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stock_size: Option<[usize; 2]>,
+    pub bot: BotConfig,
+}
+
+impl RouteConfig {
+    pub fn route(&self) -> Route {
+        Route {
+            players: self.players,
+            stock_size: self.stock_size.map(|[min, max]| (min, max)),
+        }
+    }
+}
+
+/// Routes in priority order and the bot for games none of them match.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RouterManifest {
+    #[serde(default)]
+    pub routes: Vec<RouteConfig>,
+    pub default: BotConfig,
+}
+
+impl RouterManifest {
+    /// Parse TOML, or JSON when `path` ends in `.json`, and resolve relative checkpoint
+    /// paths against the file's directory.
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let text = fs::read_to_string(path).map_err(|err| format!("{}: {err}", path.display()))?;
+        let json = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+        let mut manifest = if json {
+            Self::from_json(&text)
+        } else {
+            Self::from_toml(&text)
+        }
+        .map_err(|err| format!("{}: {err}", path.display()))?;
+        let dir = path.parent().unwrap_or(Path::new(""));
+        for route in &mut manifest.routes {
+            route.bot.resolve_checkpoint(dir);
+        }
+        manifest.default.resolve_checkpoint(dir);
+        Ok(manifest)
+    }
+
+    pub fn from_toml(text: &str) -> Result<Self, Box<dyn Error>> {
+        let manifest: Self = toml::from_str(text)?;
+        manifest.validate()?;
+        Ok(manifest)
+    }
+
+    pub fn from_json(text: &str) -> Result<Self, Box<dyn Error>> {
+        let manifest: Self = serde_json::from_str(text)?;
+        manifest.validate()?;
+        Ok(manifest)
+    }
+
+    fn validate(&self) -> Result<(), Box<dyn Error>> {
+        for (index, route) in self.routes.iter().enumerate() {
+            route.bot.validate(index)?;
+            if let Some([min, max]) = route.stock_size
+                && min > max
+            {
+                return Err(format!("route {index}: stock size {min} is above {max}").into());
+            }
+        }
+        self.default.validate(self.routes.len())
+    }
+}
+
+/// Plays each game with the bot routed to its settings (see the [module docs](self)).
+///
+/// Every call is dispatched by the settings of the view it gets, so one game always goes
+/// to the same bot, and that bot sees all of the game's hooks. Search limits and
+/// interrupters go to every bot.
+pub struct PolicyRouterBot {
+    routes: Vec<(Route, Box<dyn Bot>)>,
+    default: Box<dyn Bot>,
+    /// Games started per route, the default bot's last.
+    games: Vec<usize>,
+}
+
+impl PolicyRouterBot {
+    pub fn new(default: Box<dyn Bot>) -> Self {
+        Self {
+            routes: Vec::new(),
+            default,
+            games: vec![0],
+        }
+    }
+
+    /// Add a route, matched after the routes added before it.
+    pub fn with_route(mut self, route: Route, bot: Box<dyn Bot>) -> Self {
+        self.routes.push((route, bot));
+        self.games.insert(self.routes.len() - 1, 0);
+        self
+    }
+
+    /// Build every bot of `manifest` for `seat`.
+    pub fn from_manifest(
+        manifest: &RouterManifest,
+        seat: PlayerId,
+        seed: u64,
+    ) -> Result<Self, Box<dyn Error>> {
+        let mut router = Self::new(create_bot_from_spec(
+            &manifest.default.to_spec(),
+            seat,
+            seed,
+        )?);
+        for route in &manifest.routes {
+            let bot = create_bot_from_spec(&route.bot.to_spec(), seat, seed)?;
+            router = router.with_route(route.route(), bot);
+        }
+        Ok(router)
+    }
+
+    pub fn routes(&self) -> impl Iterator<Item = &Route> {
+        self.routes.iter().map(|(route, _)| route)
+    }
+
+    /// Games started on each route, in route order, followed by the default bot's.
+    pub fn routed_games(&self) -> &[usize] {
+        &self.games
+    }
+
+    /// Index of the route `settings` go to; the default bot is `routes.len()`.
+    fn route_index(&self, settings: &GameSettings) -> usize {
+        self.routes
+            .iter()
+            .position(|(route, _)| route.matches(settings))
+            .unwrap_or(self.routes.len())
+    }
+
+    fn bot_for(&mut self, settings: &GameSettings) -> &mut dyn Bot {
+        match self.route_index(settings) {
+            index if index < self.routes.len() => self.routes[index].1.as_mut(),
+            _ => self.default.as_mut(),
+        }
+    }
+
+    fn bots(&mut self) -> impl Iterator<Item = &mut Box<dyn Bot>> {
+        self.routes
+            .iter_mut()
+            .map(|(_, bot)| bot)
+            .chain(std::iter::once(&mut self.default))
+    }
+}
+
+impl Bot for PolicyRouterBot {
+    fn on_game_start(&mut self, state: &GameStateView) {
+        let index = self.route_index(&state.settings);
+        self.games[index] += 1;
+        self.bot_for(&state.settings).on_game_start(state);
+    }
+
+    fn on_turn_start(&mut self, state: &GameStateView) {
+        self.bot_for(&state.settings).on_turn_start(state);
+    }
+
+    fn select_action(&mut self, state: &GameStateView, legal_actions: &[Action]) -> Action {
+        self.bot_for(&state.settings)
+            .select_action(state, legal_actions)
+    }
+
+    fn rank_actions(
+        &mut self,
+        state: &GameStateView,
+        legal_actions: &[Action],
+    ) -> Vec<(Action, f32)> {
+        self.bot_for(&state.settings)
+            .rank_actions(state, legal_actions)
+    }
+
+    fn limit_search(&mut self, nodes: usize) {
+        for bot in self.bots() {
+            bot.limit_search(nodes);
+        }
+    }
+
+    fn interrupt_with(&mut self, interrupter: &Interrupter) {
+        for bot in self.bots() {
+            bot.interrupt_with(interrupter);
+        }
+    }
+
+    fn wants_full_state(&self) -> bool {
+        self.routes.iter().any(|(_, bot)| bot.wants_full_state()) || self.default.wants_full_state()
+    }
+
+    fn observe_full_state(&mut self, state: &FullStateView) {
+        let bot = self.bot_for(&state.view.settings);
+        if bot.wants_full_state() {
+            bot.observe_full_state(state);
+        }
+    }
+
+    fn on_game_end(&mut self, state: &GameStateView, winner: Option<PlayerId>) {
+        self.bot_for(&state.settings).on_game_end(state, winner);
+    }
+}
@@ -0,0 +1,234 @@
+//! Search infrastructure shared between the search bots.
+//!
+//! A [`TranspositionTable`] remembers what a search learned about a position, keyed by
+//! [`GameStateView::position_hash`] so that transpositions share an entry: an evaluation,
+//! a plan or a solved endgame. It holds a fixed number of slots and each key maps to
+//! exactly one of them, so memory stays bounded; when two keys meet in a slot the
+//! [`ReplacementPolicy`] decides which one stays. Every entry stores its full key, so a lookup never returns another
+//! position's value.
+//!
+//! The table lives across decisions: [`TranspositionTable::new_search`] starts a new
+//! generation, after which entries of earlier searches are still found but give way to
+//! new ones. [`SharedTable`] lets several bots, or several seats of one bot, fill and read
+//! one table.
+//!
+//! [`GameStateView::position_hash`]: crate::state::GameStateView::position_hash
+
+use std::fmt;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use serde::Serialize;
+
+/// Slots of a table when the owner does not ask for a size.
+pub const DEFAULT_TABLE_CAPACITY: usize = 1 << 14;
+
+/// Which entry keeps a slot that two keys map to.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ReplacementPolicy {
+    /// The newest store always wins.
+    Always,
+    /// Keep the entry searched deeper, unless it is left over from an earlier search.
+    #[default]
+    DepthPreferred,
+}
+
+impl fmt::Display for ReplacementPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ReplacementPolicy::Always => "always",
+            ReplacementPolicy::DepthPreferred => "depth",
+        })
+    }
+}
+
+impl FromStr for ReplacementPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "always" => Ok(ReplacementPolicy::Always),
+            "depth" => Ok(ReplacementPolicy::DepthPreferred),
+            _ => Err(format!(
+                "invalid replacement policy: {s} (expected always or depth)"
+            )),
+        }
+    }
+}
+
+/// Counters since the table was created or last cleared.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct TableStats {
+    pub probes: u64,
+    pub hits: u64,
+    /// Probes that found the slot taken by a different key.
+    pub collisions: u64,
+    pub stores: u64,
+    /// Stores that evicted a different key.
+    pub evictions: u64,
+    /// Stores the replacement policy turned down.
+    pub rejected: u64,
+}
+
+impl TableStats {
+    pub fn hit_rate(&self) -> f64 {
+        if self.probes == 0 {
+            0.0
+        } else {
+            self.hits as f64 / self.probes as f64
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Slot<V> {
+    key: u64,
+    depth: u32,
+    generation: u32,
+    value: V,
+}
+
+/// Fixed-size map from position hashes to `V`; see the [module docs](self).
+#[derive(Clone, Debug)]
+pub struct TranspositionTable<V> {
+    /// Allocated on the first store, so an unused table costs nothing.
+    slots: Vec<Option<Slot<V>>>,
+    capacity: usize,
+    policy: ReplacementPolicy,
+    generation: u32,
+    len: usize,
+    stats: TableStats,
+}
+
+/// A table several bots read and fill together.
+pub type SharedTable<V> = Arc<Mutex<TranspositionTable<V>>>;
+
+impl<V: Clone> TranspositionTable<V> {
+    /// A table of `capacity` slots (at least one) with [`ReplacementPolicy::DepthPreferred`].
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            slots: Vec::new(),
+            capacity: capacity.max(1),
+            policy: ReplacementPolicy::default(),
+            generation: 0,
+            len: 0,
+            stats: TableStats::default(),
+        }
+    }
+
+    pub fn with_policy(mut self, policy: ReplacementPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Wrap the table for sharing between bots.
+    pub fn shared(self) -> SharedTable<V> {
+        Arc::new(Mutex::new(self))
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn policy(&self) -> ReplacementPolicy {
+        self.policy
+    }
+
+    /// Occupied slots.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn stats(&self) -> TableStats {
+        self.stats
+    }
+
+    fn index(&self, key: u64) -> usize {
+        (key % self.capacity as u64) as usize
+    }
+
+    /// The value stored for `key`, if its slot still holds it.
+    pub fn probe(&mut self, key: u64) -> Option<&V> {
+        self.stats.probes += 1;
+        let index = self.index(key);
+        match self.slots.get(index).and_then(Option::as_ref) {
+            Some(slot) if slot.key == key => {
+                self.stats.hits += 1;
+                self.slots[index].as_ref().map(|slot| &slot.value)
+            }
+            Some(_) => {
+                self.stats.collisions += 1;
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// Store `value` for `key`, found by a search of `depth` (any measure of effort where
+    /// more is better). Returns whether the policy let it in.
+    pub fn store(&mut self, key: u64, depth: u32, value: V) -> bool {
+        if self.slots.is_empty() {
+            self.slots = vec![None; self.capacity];
+        }
+        let index = self.index(key);
+        let generation = self.generation;
+        let replace = match &self.slots[index] {
+            None => true,
+            Some(slot) => match self.policy {
+                ReplacementPolicy::Always => true,
+                ReplacementPolicy::DepthPreferred => {
+                    slot.generation != generation || depth >= slot.depth
+                }
+            },
+        };
+        if !replace {
+            self.stats.rejected += 1;
+            return false;
+        }
+        match &self.slots[index] {
+            None => self.len += 1,
+            Some(slot) if slot.key != key => self.stats.evictions += 1,
+            Some(_) => {}
+        }
+        self.stats.stores += 1;
+        self.slots[index] = Some(Slot {
+            key,
+            depth,
+            generation,
+            value,
+        });
+        true
+    }
+
+    /// Start a new search, e.g. the next decision of the game. Entries stored so far stay
+    /// readable but yield their slot to the new search's entries.
+    pub fn new_search(&mut self) {
+        self.generation = self.generation.wrapping_add(1);
+    }
+
+    /// Drop every entry and reset the statistics, e.g. between games.
+    pub fn clear(&mut self) {
+        self.slots.clear();
+        self.len = 0;
+        self.generation = 0;
+        self.stats = TableStats::default();
+    }
+}
+
+impl<V: Clone> Default for TranspositionTable<V> {
+    fn default() -> Self {
+        Self::new(DEFAULT_TABLE_CAPACITY)
+    }
+}
+
+/// Lock a shared table. Stores replace whole slots, so a holder that panicked leaves
+/// nothing half-written and the table stays usable.
+pub fn lock_table<V>(table: &SharedTable<V>) -> MutexGuard<'_, TranspositionTable<V>> {
+    table
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
@@ -0,0 +1,101 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::action::{Action, PlayerId};
+use crate::bot::Bot;
+use crate::bots::hybrid::softmax;
+use crate::interrupt::Interrupter;
+use crate::state::{FullStateView, GameStateView};
+
+/// Decorator that samples every move from the inner bot's [`Bot::rank_actions`] scores,
+/// turned into probabilities by a softmax at `temperature`. The games it plays record how
+/// likely each of its moves was, which off-policy evaluation ([`crate::ope`]) needs to
+/// reweigh them for another policy.
+///
+/// Every decision asks the inner bot for one ranking, forced ones included, so replaying
+/// a logged game through [`Bot::rank_actions`] reproduces the rankings it sampled from.
+pub struct SoftmaxBot<B: Bot> {
+    inner: B,
+    temperature: f64,
+    rng: StdRng,
+}
+
+impl<B: Bot> SoftmaxBot<B> {
+    pub fn new(inner: B, temperature: f64, seed: u64) -> Self {
+        Self {
+            inner,
+            temperature,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    pub fn temperature(&self) -> f64 {
+        self.temperature
+    }
+
+    pub fn inner(&self) -> &B {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> B {
+        self.inner
+    }
+}
+
+impl<B: Bot> Bot for SoftmaxBot<B> {
+    fn on_game_start(&mut self, state: &GameStateView) {
+        self.inner.on_game_start(state);
+    }
+
+    fn on_turn_start(&mut self, state: &GameStateView) {
+        self.inner.on_turn_start(state);
+    }
+
+    fn select_action(&mut self, state: &GameStateView, legal_actions: &[Action]) -> Action {
+        let ranked: Vec<(Action, f32)> = self
+            .inner
+            .rank_actions(state, legal_actions)
+            .into_iter()
+            .filter(|(action, _)| legal_actions.contains(action))
+            .collect();
+        let scores: Vec<f32> = ranked.iter().map(|(_, score)| *score).collect();
+        let mut left = self.rng.gen_range(0.0..1.0);
+        for ((action, _), p) in ranked.iter().zip(softmax(&scores, self.temperature)) {
+            left -= p;
+            if left < 0.0 {
+                return action.clone();
+            }
+        }
+        ranked
+            .last()
+            .map_or_else(|| legal_actions[0].clone(), |(action, _)| action.clone())
+    }
+
+    fn rank_actions(
+        &mut self,
+        state: &GameStateView,
+        legal_actions: &[Action],
+    ) -> Vec<(Action, f32)> {
+        self.inner.rank_actions(state, legal_actions)
+    }
+
+    fn limit_search(&mut self, nodes: usize) {
+        self.inner.limit_search(nodes);
+    }
+
+    fn interrupt_with(&mut self, interrupter: &Interrupter) {
+        self.inner.interrupt_with(interrupter);
+    }
+
+    fn wants_full_state(&self) -> bool {
+        self.inner.wants_full_state()
+    }
+
+    fn observe_full_state(&mut self, state: &FullStateView) {
+        self.inner.observe_full_state(state);
+    }
+
+    fn on_game_end(&mut self, state: &GameStateView, winner: Option<PlayerId>) {
+        self.inner.on_game_end(state, winner);
+    }
+}
@@ -0,0 +1,114 @@
+//! Difficulty levels shared by the searching bots.
+//!
+//! A [`Strength`] from 1 to 10 maps to three knobs: the share of the default search
+//! budget, a softmax temperature over the bot's move scores, and a rate of deliberately
+//! random moves. Level 10 disables all three, so a bot at full strength plays exactly as
+//! it would without a strength setting.
+
+use std::fmt;
+use std::str::FromStr;
+
+use rand::Rng;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Strength(u8);
+
+impl Strength {
+    pub const MIN: Strength = Strength(1);
+    pub const MAX: Strength = Strength(10);
+
+    pub fn new(level: u8) -> Result<Self, String> {
+        if (Self::MIN.0..=Self::MAX.0).contains(&level) {
+            Ok(Self(level))
+        } else {
+            Err(format!("strength must be between 1 and 10, got {level}"))
+        }
+    }
+
+    pub fn level(self) -> u8 {
+        self.0
+    }
+
+    pub fn is_max(self) -> bool {
+        self == Self::MAX
+    }
+
+    /// Levels below the maximum, scaled to `0.0..1.0` (0 at full strength).
+    fn weakness(self) -> f64 {
+        f64::from(Self::MAX.0 - self.0) / f64::from(Self::MAX.0 - Self::MIN.0)
+    }
+
+    /// Search budget for a bot whose full-strength budget is `full`. Falls off with the
+    /// square of the level, down to 1% at level 1.
+    pub fn node_budget(self, full: usize) -> usize {
+        let share = f64::from(self.0).powi(2) / f64::from(Self::MAX.0).powi(2);
+        ((full as f64 * share) as usize).max(1)
+    }
+
+    /// Softmax temperature, as a multiple of `score_scale` (the score difference a bot
+    /// considers a clear preference). Zero at full strength.
+    pub fn temperature(self, score_scale: f64) -> f64 {
+        self.weakness() * score_scale
+    }
+
+    /// Chance of playing a uniformly random move, up to 25% at level 1.
+    pub fn blunder_rate(self) -> f64 {
+        self.weakness() * 0.25
+    }
+
+    /// Pick one of the candidates with the given scores: a random one on a blunder,
+    /// otherwise by softmax at this level's temperature. At full strength this is the
+    /// best score (the last one on ties, like [`Iterator::max_by`]) and `rng` is unused.
+    pub fn choose<R: Rng>(self, rng: &mut R, scores: &[f64], score_scale: f64) -> Option<usize> {
+        if scores.is_empty() {
+            return None;
+        }
+        let best = scores
+            .iter()
+            .enumerate()
+            .fold(0, |best, (i, &s)| if s >= scores[best] { i } else { best });
+        if self.is_max() {
+            return Some(best);
+        }
+        if rng.gen_bool(self.blunder_rate()) {
+            return Some(rng.gen_range(0..scores.len()));
+        }
+        let temperature = self.temperature(score_scale).max(f64::MIN_POSITIVE);
+        let weights: Vec<f64> = scores
+            .iter()
+            .map(|&s| ((s - scores[best]) / temperature).exp())
+            .collect();
+        let mut pick = rng.gen_range(0.0..weights.iter().sum::<f64>());
+        for (i, weight) in weights.iter().enumerate() {
+            if pick < *weight {
+                return Some(i);
+            }
+            pick -= weight;
+        }
+        Some(best)
+    }
+}
+
+impl Default for Strength {
+    fn default() -> Self {
+        Self::MAX
+    }
+}
+
+impl fmt::Display for Strength {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for Strength {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let level = s
+            .trim()
+            .parse::<u8>()
+            .map_err(|_| format!("invalid strength: {s}"))?;
+        Self::new(level)
+    }
+}
@@ -0,0 +1,96 @@
+//! Skip-Bo conservation: what a wild card is worth held back, so heuristics can weigh
+//! spending one now against keeping it for a later turn.
+//!
+//! A wild is worth most when it can bridge the last gap to the stock card: the closer
+//! the nearest build pile is to the stock value, the more likely one held wild turns into
+//! a stock play next turn. Build piles that all require the same value leave few number
+//! cards playable, which makes a wild more valuable still; every further wild the player
+//! holds makes the next one worth less.
+
+use crate::action::{Action, CardSource};
+use crate::bots::planning::TurnPosition;
+use crate::card::{BUILD_PILE_COUNT, Card};
+use crate::state::{GameStateView, unlock_distance};
+
+/// Hold value of a wild when the stock card already fits, relative to a wild one step
+/// away from it.
+const STOCK_FITS_HOLD_VALUE: f64 = 0.25;
+
+/// Estimates the marginal value of a Skip-Bo card and the cost of spending one.
+///
+/// Values are relative: 1.0 is a single wild held one step short of the stock card with
+/// the build piles spread over four values. Scale them by a heuristic's own weight.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct WildPolicy;
+
+impl WildPolicy {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Value of holding one more wild in `position`.
+    pub fn hold_value(&self, position: &TurnPosition) -> f64 {
+        let proximity = match Self::stock_distance(position) {
+            0 => STOCK_FITS_HOLD_VALUE,
+            distance => 1.0 / f64::from(distance),
+        };
+        let held = position.hand.iter().filter(|c| c.is_skip_bo()).count()
+            + position
+                .discard_piles
+                .iter()
+                .filter(|pile| pile.last().is_some_and(Card::is_skip_bo))
+                .count();
+        proximity * Self::spread_factor(position) / (1 + held) as f64
+    }
+
+    /// Cost of making `action` in `position`: the hold value of the wild it spends, or
+    /// zero when it plays no wild or is stock progress (the stock card itself, or a play
+    /// after which the stock card fits).
+    pub fn spend_penalty(&self, position: &TurnPosition, action: &Action) -> f64 {
+        let Action::Play { source, .. } = *action else {
+            return 0.0;
+        };
+        if source == CardSource::Stock || !position.card_at(source).is_some_and(|c| c.is_skip_bo())
+        {
+            return 0.0;
+        }
+        let mut after = position.clone();
+        if !after.apply(action) || Self::stock_distance(&after) == 0 {
+            return 0.0;
+        }
+        // The spent wild no longer counts as held.
+        let mut holding = position.clone();
+        match source {
+            CardSource::Hand(i) => {
+                holding.hand.remove(i);
+            }
+            CardSource::Discard(d) => {
+                holding.discard_piles[d.0].pop();
+            }
+            CardSource::Stock => {}
+        }
+        self.hold_value(&holding)
+    }
+
+    /// [`spend_penalty`](Self::spend_penalty) in the viewing player's current position.
+    pub fn spend_penalty_in_view(&self, state: &GameStateView, action: &Action) -> f64 {
+        TurnPosition::from_view(state).map_or(0.0, |position| self.spend_penalty(&position, action))
+    }
+
+    /// Build steps from the closest pile to the stock card; 0 when it fits, is a wild or
+    /// is unknown.
+    pub fn stock_distance(position: &TurnPosition) -> u8 {
+        position
+            .stock_top
+            .map_or(0, |stock| unlock_distance(&position.build_next, stock))
+    }
+
+    /// From 1.0 with four distinct pile values up to 1.75 with a single one.
+    fn spread_factor(position: &TurnPosition) -> f64 {
+        let mut values = position.build_next;
+        values.sort_unstable();
+        let mut distinct = values.to_vec();
+        distinct.dedup();
+        1.0 + 0.25 * (BUILD_PILE_COUNT - distinct.len()) as f64
+    }
+}
@@ -15,6 +15,10 @@ pub const SKIP_BO_COUNT: usize = 18;
 pub const COPIES_PER_VALUE: usize = 12;
 pub const HAND_SIZE: usize = 5;
 pub const DISCARD_PILE_COUNT: usize = 4;
+/// Discard piles a seat can have, including the extra pile of [`SeatRules::extra_discard_pile`].
+///
+/// [`SeatRules::extra_discard_pile`]: crate::game::SeatRules::extra_discard_pile
+pub const MAX_DISCARD_PILES: usize = DISCARD_PILE_COUNT + 1;
 pub const BUILD_PILE_COUNT: usize = 4;
 pub const MAX_PLAYERS: usize = 6;
 
@@ -37,7 +41,7 @@ impl Card {
     /// Checks whether the card can legally satisfy the requested value.
     #[inline]
     pub fn matches_value(&self, value: u8) -> bool {
-        debug_assert!(value >= MIN_CARD_VALUE && value <= MAX_CARD_VALUE);
+        debug_assert!((MIN_CARD_VALUE..=MAX_CARD_VALUE).contains(&value));
         matches!(self, Card::SkipBo) || self.value() == Some(value)
     }
 }
@@ -50,6 +54,6 @@ pub fn full_deck() -> Vec<Card> {
             deck.push(Card::Number(value));
         }
     }
-    deck.extend(std::iter::repeat(Card::SkipBo).take(SKIP_BO_COUNT));
+    deck.extend(std::iter::repeat_n(Card::SkipBo, SKIP_BO_COUNT));
     deck
 }
@@ -0,0 +1,206 @@
+//! Cross-entropy method (CEM) tuning of the [`Heuristic19Bot`] plan weights.
+//!
+//! Each generation samples candidate [`PlanWeights`] from a diagonal Gaussian, scores
+//! every candidate by its win rate over seeded games against a fixed opponent, and refits
+//! the Gaussian to the elite fraction. All candidates of a generation play the same deals
+//! from the [`SeedSplit::Train`] universe with the same seats, so differences in win rate
+//! come from the weights rather than the cards. Being gradient-free, it serves as a
+//! baseline for learned policies.
+
+use std::error::Error;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::bots::{Heuristic19Bot, PlanWeights};
+use crate::runner::{head_to_head, mix_seed};
+use crate::seeds::SeedSplit;
+
+/// Settings for a [`CemTrainer`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CemConfig {
+    /// Candidates sampled per generation.
+    pub population: usize,
+    /// Share of the population (best win rates) the distribution is refitted to.
+    pub elite_fraction: f64,
+    /// Games each candidate plays per generation, each deal from both seats.
+    pub games: usize,
+    /// Bot spec of the opponent.
+    pub opponent: String,
+    pub seed: u64,
+    /// Initial standard deviation of each weight, relative to its starting magnitude.
+    pub initial_std: f64,
+    /// Floor on the relative standard deviation, keeping some exploration.
+    pub min_std: f64,
+    /// Safety cap on actions per game; capped games count as losses.
+    pub max_actions: usize,
+}
+
+impl CemConfig {
+    pub fn new(opponent: impl Into<String>, seed: u64) -> Self {
+        Self {
+            population: 16,
+            elite_fraction: 0.25,
+            games: 40,
+            opponent: opponent.into(),
+            seed,
+            initial_std: 0.3,
+            min_std: 0.02,
+            max_actions: 2000,
+        }
+    }
+}
+
+/// Result of one [`CemTrainer::step`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GenerationReport {
+    pub generation: usize,
+    /// Best candidate of the generation and its win rate.
+    pub best: PlanWeights,
+    pub best_win_rate: f64,
+    /// Mean win rate over the whole population and over the elite.
+    pub mean_win_rate: f64,
+    pub elite_win_rate: f64,
+    /// Distribution mean after the refit.
+    pub mean: PlanWeights,
+}
+
+pub struct CemTrainer {
+    config: CemConfig,
+    mean: Vec<f64>,
+    std: Vec<f64>,
+    /// Magnitude of each starting weight; standard deviations are relative to it.
+    scale: Vec<f64>,
+    generation: usize,
+    rng: StdRng,
+}
+
+impl CemTrainer {
+    /// Start the search at `start`.
+    pub fn new(config: CemConfig, start: PlanWeights) -> Result<Self, String> {
+        if config.population < 2 {
+            return Err("population must be at least 2".into());
+        }
+        if !(config.elite_fraction > 0.0 && config.elite_fraction <= 1.0) {
+            return Err("elite fraction must be in (0, 1]".into());
+        }
+        if config.games == 0 {
+            return Err("games per candidate must be positive".into());
+        }
+        let mean = start.to_vec();
+        let scale: Vec<f64> = mean.iter().map(|w| w.abs().max(1.0)).collect();
+        let std = scale.iter().map(|s| s * config.initial_std).collect();
+        let rng = StdRng::seed_from_u64(mix_seed(config.seed, 0, 0xCE_u64));
+        Ok(Self {
+            config,
+            mean,
+            std,
+            scale,
+            generation: 0,
+            rng,
+        })
+    }
+
+    pub fn config(&self) -> &CemConfig {
+        &self.config
+    }
+
+    /// Generations completed so far.
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    /// Current distribution mean, the trainer's best estimate.
+    pub fn mean(&self) -> PlanWeights {
+        PlanWeights::from_slice(&self.mean).expect("mean has one entry per weight")
+    }
+
+    fn elite_count(&self) -> usize {
+        ((self.config.population as f64 * self.config.elite_fraction).ceil() as usize)
+            .clamp(1, self.config.population)
+    }
+
+    /// Sample, evaluate and refit one generation.
+    pub fn step(&mut self) -> Result<GenerationReport, Box<dyn Error>> {
+        let generation = self.generation;
+        let first_deal = (generation * self.config.games) as u64;
+        let mut candidates = Vec::with_capacity(self.config.population);
+        for _ in 0..self.config.population {
+            let values: Vec<f64> = self
+                .mean
+                .iter()
+                .zip(&self.std)
+                .map(|(mean, std)| mean + std * standard_normal(&mut self.rng))
+                .collect();
+            let weights = PlanWeights::from_slice(&values).expect("one value per weight");
+            let win_rate = win_rate(
+                weights,
+                &self.config.opponent,
+                SeedSplit::Train,
+                self.config.seed,
+                first_deal,
+                self.config.games,
+                self.config.max_actions,
+            )?;
+            candidates.push((values, win_rate));
+        }
+        // Stable sort: ties keep sampling order, so runs are reproducible.
+        candidates.sort_by(|a, b| b.1.total_cmp(&a.1));
+        let elite = &candidates[..self.elite_count()];
+
+        for i in 0..self.mean.len() {
+            let mean = elite.iter().map(|(v, _)| v[i]).sum::<f64>() / elite.len() as f64;
+            let variance = elite
+                .iter()
+                .map(|(v, _)| (v[i] - mean).powi(2))
+                .sum::<f64>()
+                / elite.len() as f64;
+            self.mean[i] = mean;
+            self.std[i] = variance.sqrt().max(self.scale[i] * self.config.min_std);
+        }
+        self.generation += 1;
+
+        let mean_of = |rates: &[(Vec<f64>, f64)]| {
+            rates.iter().map(|(_, r)| r).sum::<f64>() / rates.len() as f64
+        };
+        Ok(GenerationReport {
+            generation,
+            best: PlanWeights::from_slice(&candidates[0].0).expect("one value per weight"),
+            best_win_rate: candidates[0].1,
+            mean_win_rate: mean_of(&candidates),
+            elite_win_rate: mean_of(elite),
+            mean: self.mean(),
+        })
+    }
+}
+
+/// Win rate of a [`Heuristic19Bot`] with `weights` against `opponent` over `games`
+/// two-player games dealt from seeds `first_deal..` of `split`. Each deal is played twice,
+/// once from each seat.
+pub fn win_rate(
+    weights: PlanWeights,
+    opponent: &str,
+    split: SeedSplit,
+    seed: u64,
+    first_deal: u64,
+    games: usize,
+    max_actions: usize,
+) -> Result<f64, Box<dyn Error>> {
+    head_to_head(
+        |_, _| Box::new(Heuristic19Bot::new().with_weights(weights)),
+        opponent,
+        split,
+        seed,
+        first_deal,
+        games,
+        max_actions,
+    )
+}
+
+/// Standard normal sample (Box-Muller).
+fn standard_normal<R: Rng>(rng: &mut R) -> f64 {
+    let u1: f64 = rng.gen_range(f64::MIN_POSITIVE..1.0);
+    let u2: f64 = rng.gen_range(0.0..1.0);
+    (-2.0 * u1.ln()).sqrt() * (std::f64::consts::TAU * u2).cos()
+}
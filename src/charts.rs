@@ -0,0 +1,156 @@
+//! Training charts drawn with plotters: loss curves per epoch, the evaluation win rate
+//! over the evaluated epochs and the learning rate schedule, one PNG each.
+
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+use plotters::prelude::*;
+
+use crate::value::EpochMetrics;
+
+const CHART_SIZE: (u32, u32) = (1000, 600);
+
+/// One line of a chart: a legend label and its `(x, y)` points.
+struct Line<'a> {
+    label: &'a str,
+    color: RGBColor,
+    points: Vec<(f64, f64)>,
+}
+
+/// Draw the charts of a training run into `dir` as `loss.png`, `win-rate.png` (only with
+/// evaluated epochs) and `learning-rate.png`. Returns the files written.
+pub fn render_training_charts(
+    dir: &Path,
+    epochs: &[EpochMetrics],
+) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    if epochs.is_empty() {
+        return Err("no epochs to chart".into());
+    }
+    let epoch = |metrics: &EpochMetrics| metrics.epoch as f64;
+    let mut written = Vec::new();
+
+    let path = dir.join("loss.png");
+    line_chart(
+        &path,
+        "Loss per epoch",
+        "Cross-entropy",
+        &[
+            Line {
+                label: "train",
+                color: BLUE,
+                points: epochs.iter().map(|m| (epoch(m), m.loss)).collect(),
+            },
+            Line {
+                label: "validation",
+                color: RED,
+                points: epochs
+                    .iter()
+                    .map(|m| (epoch(m), m.validation_loss))
+                    .collect(),
+            },
+        ],
+    )?;
+    written.push(path);
+
+    let win_rates: Vec<(f64, f64)> = epochs
+        .iter()
+        .filter_map(|m| m.win_rate.map(|rate| (epoch(m), rate * 100.0)))
+        .collect();
+    if !win_rates.is_empty() {
+        let path = dir.join("win-rate.png");
+        line_chart(
+            &path,
+            "Evaluation win rate",
+            "Win rate (%)",
+            &[Line {
+                label: "win rate",
+                color: GREEN,
+                points: win_rates,
+            }],
+        )?;
+        written.push(path);
+    }
+
+    let path = dir.join("learning-rate.png");
+    line_chart(
+        &path,
+        "Learning rate",
+        "Learning rate",
+        &[Line {
+            label: "learning rate",
+            color: MAGENTA,
+            points: epochs.iter().map(|m| (epoch(m), m.learning_rate)).collect(),
+        }],
+    )?;
+    written.push(path);
+    Ok(written)
+}
+
+fn line_chart(
+    out: &Path,
+    caption: &str,
+    y_desc: &str,
+    lines: &[Line<'_>],
+) -> Result<(), Box<dyn Error>> {
+    let points = lines.iter().flat_map(|line| &line.points);
+    let (x_min, x_max) = padded_range(points.clone().map(|p| p.0));
+    let (y_min, y_max) = padded_range(points.map(|p| p.1));
+
+    let root = BitMapBackend::new(out, CHART_SIZE).into_drawing_area();
+    root.fill(&WHITE).map_err(|e| format!("{e}"))?;
+    let mut chart = ChartBuilder::on(&root)
+        .caption(caption, ("sans-serif", 28).into_font())
+        .margin(20)
+        .x_label_area_size(50)
+        .y_label_area_size(80)
+        .build_cartesian_2d(x_min..x_max, y_min..y_max)
+        .map_err(|e| format!("{e}"))?;
+    chart
+        .configure_mesh()
+        .x_desc("Epoch")
+        .y_desc(y_desc)
+        .draw()
+        .map_err(|e| format!("{e}"))?;
+    for line in lines {
+        let color = line.color;
+        chart
+            .draw_series(LineSeries::new(line.points.iter().copied(), &color))
+            .map_err(|e| format!("{e}"))?
+            .label(line.label)
+            .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], color));
+        chart
+            .draw_series(
+                line.points
+                    .iter()
+                    .map(|&point| Circle::new(point, 3, color.filled())),
+            )
+            .map_err(|e| format!("{e}"))?;
+    }
+    chart
+        .configure_series_labels()
+        .position(SeriesLabelPosition::UpperRight)
+        .background_style(WHITE.mix(0.8))
+        .border_style(BLACK)
+        .draw()
+        .map_err(|e| format!("{e}"))?;
+    root.present().map_err(|e| format!("{e}"))?;
+    Ok(())
+}
+
+/// The span of `values` with a margin, widened when every value is the same.
+fn padded_range(values: impl Iterator<Item = f64>) -> (f64, f64) {
+    let (min, max) = values.fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), v| {
+        (lo.min(v), hi.max(v))
+    });
+    if !min.is_finite() || !max.is_finite() {
+        return (0.0, 1.0);
+    }
+    let margin = if max > min {
+        (max - min) * 0.05
+    } else if min != 0.0 {
+        min.abs() * 0.1
+    } else {
+        1.0
+    };
+    (min - margin, max + margin)
+}
@@ -0,0 +1,224 @@
+//! Remote human players over chat (feature `chat-bridge`).
+//!
+//! A [`ChatBot`] seats a chat user. At each of their decisions it sends them their view of
+//! the game as a text block in a direct message, then waits for one of their commands,
+//! sent in the channel or in private:
+//!
+//! - `!play H3>B1` makes a move in the [`Action`] notation, `!play 4` the move listed
+//!   as `[4]`;
+//! - `!moves` repeats the view and the list of moves, `!help` explains the commands.
+//!
+//! Messages from anyone else are ignored while the bot waits. The chat service sits behind
+//! a [`ChatTransport`], which any number of seats share; [`IrcTransport`] is a minimal IRC
+//! client over plain TCP. Services with HTTP APIs, such as Discord, need a transport of
+//! their own. The crate's bots are synchronous, so a waiting [`ChatBot`] blocks its game.
+
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::TcpStream;
+use std::sync::{Arc, Mutex};
+
+use crate::action::Action;
+use crate::bot::Bot;
+use crate::state::GameStateView;
+use crate::visualize::{describe_action, render_state};
+
+/// Reply to `!help`.
+pub const HELP: &str = "Commands: !play MOVE (e.g. !play H3>B1, !play S>B0, !play X2>D1, \
+                        !play END) or !play N for move [N] of the list; !moves shows your \
+                        view again.";
+
+/// A message received from the chat.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChatMessage {
+    /// Nickname of the sender.
+    pub from: String,
+    pub text: String,
+}
+
+/// A chat service the bridge talks through.
+pub trait ChatTransport: Send {
+    /// Send `text` to the channel, or privately to the user `to`. `text` may span lines.
+    fn send(&mut self, to: Option<&str>, text: &str) -> io::Result<()>;
+
+    /// Wait for the next message; `None` once the chat is closed.
+    fn receive(&mut self) -> io::Result<Option<ChatMessage>>;
+}
+
+/// A transport shared by every seat of a game.
+pub type SharedTransport = Arc<Mutex<dyn ChatTransport>>;
+
+/// What a chat message asks for.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ChatCommand {
+    Play(Action),
+    /// Move `[N]` of the listed moves.
+    Pick(usize),
+    Moves,
+    Help,
+}
+
+impl ChatCommand {
+    /// The command of `text`: `None` when it is not a command (it does not start with
+    /// `!`), an error when it is a malformed one.
+    pub fn parse(text: &str) -> Option<Result<Self, String>> {
+        let text = text.trim().strip_prefix('!')?;
+        let (name, argument) = text.split_once(char::is_whitespace).unwrap_or((text, ""));
+        let argument = argument.trim();
+        Some(match name.to_ascii_lowercase().as_str() {
+            "play" if argument.is_empty() => Err("!play needs a move, e.g. !play H3>B1".into()),
+            "play" => match argument.parse::<usize>() {
+                Ok(index) => Ok(ChatCommand::Pick(index)),
+                Err(_) => argument.parse().map(ChatCommand::Play),
+            },
+            "moves" => Ok(ChatCommand::Moves),
+            "help" => Ok(ChatCommand::Help),
+            other => Err(format!("unknown command !{other}; try !help")),
+        })
+    }
+}
+
+/// `state` as a fenced text block followed by the numbered `legal` moves in notation.
+pub fn render_block(state: &GameStateView, legal: &[Action]) -> String {
+    let mut out = format!("```\n{}```\nYour moves:\n", render_state(state));
+    for (index, action) in legal.iter().enumerate() {
+        out.push_str(&format!(
+            "  [{index}] {action}  {}\n",
+            describe_action(state, action)
+        ));
+    }
+    out.push_str("Reply with !play MOVE or !play N.");
+    out
+}
+
+/// A seat played by the chat user `nick`; see the [module docs](self).
+pub struct ChatBot {
+    nick: String,
+    transport: SharedTransport,
+}
+
+impl ChatBot {
+    pub fn new(nick: impl Into<String>, transport: SharedTransport) -> Self {
+        Self {
+            nick: nick.into(),
+            transport,
+        }
+    }
+
+    pub fn nick(&self) -> &str {
+        &self.nick
+    }
+}
+
+impl Bot for ChatBot {
+    /// Waits for a legal move from the user. Once the chat is closed, plays the first
+    /// legal move.
+    fn select_action(&mut self, state: &GameStateView, legal_actions: &[Action]) -> Action {
+        let mut chat = self.transport.lock().expect("chat transport lock");
+        let nick = self.nick.as_str();
+        let reply = |chat: &mut dyn ChatTransport, text: &str| {
+            // A failed send leaves the user to ask again with !moves.
+            let _ = chat.send(Some(nick), text);
+        };
+        reply(&mut *chat, &render_block(state, legal_actions));
+        loop {
+            let message = match chat.receive() {
+                Ok(Some(message)) => message,
+                Ok(None) | Err(_) => return legal_actions[0].clone(),
+            };
+            if !message.from.eq_ignore_ascii_case(nick) {
+                continue;
+            }
+            let action = match ChatCommand::parse(&message.text) {
+                None => continue,
+                Some(Err(err)) => {
+                    reply(&mut *chat, &err);
+                    continue;
+                }
+                Some(Ok(ChatCommand::Help)) => {
+                    reply(&mut *chat, HELP);
+                    continue;
+                }
+                Some(Ok(ChatCommand::Moves)) => {
+                    reply(&mut *chat, &render_block(state, legal_actions));
+                    continue;
+                }
+                Some(Ok(ChatCommand::Pick(index))) => legal_actions.get(index).cloned(),
+                Some(Ok(ChatCommand::Play(action))) => {
+                    legal_actions.contains(&action).then_some(action)
+                }
+            };
+            match action {
+                Some(action) => return action,
+                None => reply(&mut *chat, "That move is not legal now; !moves lists them."),
+            }
+        }
+    }
+}
+
+/// A minimal IRC client: joins one channel and relays `PRIVMSG`s. Plain TCP only.
+pub struct IrcTransport {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+    channel: String,
+}
+
+impl IrcTransport {
+    /// Connect to `address` (`host:port`), register as `nick` and join `channel`.
+    pub fn connect(address: &str, nick: &str, channel: &str) -> io::Result<Self> {
+        let writer = TcpStream::connect(address)?;
+        let mut irc = Self {
+            reader: BufReader::new(writer.try_clone()?),
+            writer,
+            channel: channel.to_string(),
+        };
+        irc.command(&format!("NICK {nick}"))?;
+        irc.command(&format!("USER {nick} 0 * :skipbot"))?;
+        irc.command(&format!("JOIN {channel}"))?;
+        Ok(irc)
+    }
+
+    fn command(&mut self, line: &str) -> io::Result<()> {
+        write!(self.writer, "{line}\r\n")?;
+        self.writer.flush()
+    }
+}
+
+impl ChatTransport for IrcTransport {
+    /// One `PRIVMSG` per line; code fences, which IRC does not render, are dropped.
+    fn send(&mut self, to: Option<&str>, text: &str) -> io::Result<()> {
+        let target = to.unwrap_or(&self.channel).to_string();
+        for line in text.lines().filter(|line| !line.starts_with("```")) {
+            let line = if line.is_empty() { " " } else { line };
+            self.command(&format!("PRIVMSG {target} :{line}"))?;
+        }
+        Ok(())
+    }
+
+    /// Answers server pings while it waits.
+    fn receive(&mut self) -> io::Result<Option<ChatMessage>> {
+        let mut line = String::new();
+        loop {
+            line.clear();
+            if self.reader.read_line(&mut line)? == 0 {
+                return Ok(None);
+            }
+            let line = line.trim_end_matches(['\r', '\n']);
+            if let Some(token) = line.strip_prefix("PING") {
+                self.command(&format!("PONG{token}"))?;
+            } else if let Some(message) = parse_privmsg(line) {
+                return Ok(Some(message));
+            }
+        }
+    }
+}
+
+/// The sender and text of `:nick!user@host PRIVMSG target :text`.
+fn parse_privmsg(line: &str) -> Option<ChatMessage> {
+    let (prefix, rest) = line.strip_prefix(':')?.split_once(' ')?;
+    let (_target, text) = rest.strip_prefix("PRIVMSG ")?.split_once(" :")?;
+    let from = prefix.split('!').next()?;
+    Some(ChatMessage {
+        from: from.to_string(),
+        text: text.to_string(),
+    })
+}
@@ -0,0 +1,307 @@
+//! Prose commentary on recorded games, for reviews and write-ups.
+//!
+//! [`Commentary::from_record`] replays a [`GameRecord`] and narrates every turn in a
+//! sentence or two, e.g. "Player 1 unlocks their stock by building 7-8-9 on pile 2 and
+//! discards 11 onto pile 0." Beyond the moves themselves it calls out the patterns a
+//! reader would look for ([`Pattern`]): stock plays, blocking an opponent's stock, playing
+//! out the whole hand, completing a pile and winning.
+
+use std::error::Error;
+use std::fmt;
+
+use crate::action::{Action, BuildPileId, CardSource, PlayerId};
+use crate::card::Card;
+use crate::replay::GameRecord;
+use crate::state::{GameStateView, GameStatus, TurnEvent};
+
+/// Something notable that happened in a turn.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Pattern {
+    /// The player played their stock card.
+    Unlock,
+    /// During the turn a build pile needed `opponent`'s stock card, but the player built
+    /// past it and no pile needs it at the end of the turn.
+    Block { opponent: PlayerId },
+    /// The player played out their hand and drew a fresh one.
+    Dump,
+    /// A build pile reached 12 and was cleared.
+    Completion { pile: BuildPileId },
+    /// The player emptied their stock and won.
+    Win,
+}
+
+/// The narration of one turn.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TurnCommentary {
+    /// One-based, matching the turn numbers of the record.
+    pub turn: usize,
+    /// Index of the turn's first move in [`GameRecord::moves`].
+    pub first_move: usize,
+    pub player: PlayerId,
+    pub patterns: Vec<Pattern>,
+    pub text: String,
+}
+
+/// Turn-by-turn commentary of a whole game. `Display` writes one paragraph per turn.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Commentary {
+    pub turns: Vec<TurnCommentary>,
+    pub result: GameStatus,
+}
+
+impl Commentary {
+    /// Replay `record` and narrate it. Fails when the record does not replay.
+    pub fn from_record(record: &GameRecord) -> Result<Self, Box<dyn Error>> {
+        let mut steps: Vec<(GameStateView, Action)> = Vec::with_capacity(record.moves.len());
+        let mut view_error = None;
+        let game = record.replay_with(|game, player, action| match game.state_view(player) {
+            Ok(view) => steps.push((view, action.clone())),
+            Err(err) => {
+                view_error.get_or_insert(err);
+            }
+        })?;
+        if let Some(err) = view_error {
+            return Err(err.into());
+        }
+
+        let mut turns = Vec::new();
+        let mut start = 0;
+        while start < steps.len() {
+            let turn_number = steps[start].0.turn_number;
+            let end = steps[start..]
+                .iter()
+                .position(|(view, _)| view.turn_number != turn_number)
+                .map_or(steps.len(), |len| start + len);
+            let player = steps[start].0.current_player;
+            let after = match steps.get(end) {
+                Some((view, _)) => view.clone(),
+                None => game.state_view(player)?,
+            };
+            turns.push(narrate_turn(
+                turns.len() + 1,
+                start,
+                &steps[start..end],
+                &after,
+            ));
+            start = end;
+        }
+        Ok(Self {
+            turns,
+            result: game.status(),
+        })
+    }
+}
+
+impl fmt::Display for Commentary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for turn in &self.turns {
+            writeln!(f, "{}. {}", turn.turn, turn.text)?;
+        }
+        match self.result {
+            GameStatus::Finished { winner } => writeln!(f, "Player {winner} wins."),
+            GameStatus::Draw => writeln!(f, "The game ends in a draw."),
+            GameStatus::Ongoing => writeln!(f, "The record stops here."),
+        }
+    }
+}
+
+/// Consecutive plays of one turn onto the same build pile, ending at a stock card.
+struct Run {
+    pile: BuildPileId,
+    cards: Vec<String>,
+    from_stock: bool,
+    completes: bool,
+    /// The previous run of the turn ended on the same pile.
+    continued: bool,
+}
+
+fn narrate_turn(
+    turn: usize,
+    first_move: usize,
+    steps: &[(GameStateView, Action)],
+    after: &GameStateView,
+) -> TurnCommentary {
+    let first = &steps[0].0;
+    let player = first.current_player;
+    let mut patterns = Vec::new();
+    let mut clauses = Vec::new();
+    let mut run: Option<Run> = None;
+    let mut last_pile = None;
+    for (index, (before, action)) in steps.iter().enumerate() {
+        let next = steps.get(index + 1).map_or(after, |(view, _)| view);
+        let Action::Play { source, build_pile } = action else {
+            flush_run(&mut run, &mut clauses, &mut patterns);
+            match action {
+                Action::Discard {
+                    hand_index,
+                    discard_pile,
+                } => {
+                    let card = before.hand.get(*hand_index).copied();
+                    clauses.push(format!(
+                        "discards {} onto pile {discard_pile}",
+                        card.map_or_else(|| String::from("a card"), card_name)
+                    ));
+                }
+                _ => clauses.push(String::from("ends the turn")),
+            }
+            continue;
+        };
+        let value = before.build_piles[build_pile.0].next_value;
+        let card = played_card(before, *source);
+        let label = match card {
+            Some(Card::SkipBo) => format!("SB({value})"),
+            _ => value.to_string(),
+        };
+        if run.as_ref().is_some_and(|run| run.pile != *build_pile) {
+            flush_run(&mut run, &mut clauses, &mut patterns);
+        }
+        let current = run.get_or_insert_with(|| Run {
+            pile: *build_pile,
+            cards: Vec::new(),
+            from_stock: false,
+            completes: false,
+            continued: last_pile == Some(*build_pile),
+        });
+        current.cards.push(label);
+        current.from_stock |= *source == CardSource::Stock;
+        current.completes = new_events(before, next)
+            .any(|event| matches!(event, TurnEvent::PileCompleted { pile } if pile == build_pile));
+        let drew = new_events(before, next).find_map(|event| match event {
+            TurnEvent::Drew { player: p, cards } if *p == player => Some(*cards),
+            _ => None,
+        });
+        let emptied_hand = drew.filter(|_| matches!(source, CardSource::Hand(_)));
+        // A run ends at a stock card, a completed pile or an emptied hand; only after a
+        // stock card does the next run on the pile read as a continuation.
+        let ends_run = current.from_stock || current.completes || emptied_hand.is_some();
+        last_pile = (!current.completes && emptied_hand.is_none()).then_some(*build_pile);
+        if ends_run {
+            flush_run(&mut run, &mut clauses, &mut patterns);
+        }
+        if let Some(cards) = emptied_hand {
+            patterns.push(Pattern::Dump);
+            clauses.push(format!("empties their hand to draw {cards} more"));
+        }
+    }
+    flush_run(&mut run, &mut clauses, &mut patterns);
+
+    let won = after.status == (GameStatus::Finished { winner: player });
+    if won {
+        patterns.push(Pattern::Win);
+        clauses.push(String::from("wins with the last stock card"));
+    }
+    let mut text = format!("Player {player} {}.", join_clauses(&clauses));
+    let blocked = if won {
+        Vec::new()
+    } else {
+        blocked_opponents(steps, after, player)
+    };
+    for (opponent, value) in blocked {
+        patterns.push(Pattern::Block { opponent });
+        text.push_str(&format!(
+            " That builds past the {value} on Player {opponent}'s stock, keeping it blocked."
+        ));
+    }
+    TurnCommentary {
+        turn,
+        first_move,
+        player,
+        patterns,
+        text,
+    }
+}
+
+fn flush_run(run: &mut Option<Run>, clauses: &mut Vec<String>, patterns: &mut Vec<Pattern>) {
+    let Some(run) = run.take() else {
+        return;
+    };
+    let cards = run.cards.join("-");
+    let mut clause = if run.from_stock {
+        patterns.push(Pattern::Unlock);
+        if run.continued {
+            format!("continues with {cards} off the stock")
+        } else if run.cards.len() == 1 {
+            format!("plays their stock {cards} on pile {}", run.pile)
+        } else {
+            format!(
+                "unlocks their stock by building {cards} on pile {}",
+                run.pile
+            )
+        }
+    } else if run.continued {
+        format!("continues with {cards}")
+    } else {
+        format!("builds {cards} on pile {}", run.pile)
+    };
+    if run.completes {
+        patterns.push(Pattern::Completion { pile: run.pile });
+        clause.push_str(", completing it");
+    }
+    clauses.push(clause);
+}
+
+/// Engine events between two consecutive views of the same turn.
+fn new_events<'a>(
+    before: &GameStateView,
+    after: &'a GameStateView,
+) -> impl Iterator<Item = &'a TurnEvent> {
+    let seen = if after.turn_number == before.turn_number {
+        before.turn_events.len()
+    } else {
+        0
+    };
+    after.turn_events.iter().skip(seen)
+}
+
+fn played_card(view: &GameStateView, source: CardSource) -> Option<Card> {
+    let own = view.self_player_state()?;
+    match source {
+        CardSource::Hand(index) => view.hand.get(index).copied(),
+        CardSource::Stock => own.stock_top,
+        CardSource::Discard(pile) => own.discard_piles.get(pile.0)?.last().copied(),
+    }
+}
+
+/// Opponents, with their stock value, whose numbered stock card fit a build pile at some
+/// point of the turn but fits none at its end.
+fn blocked_opponents(
+    steps: &[(GameStateView, Action)],
+    after: &GameStateView,
+    player: PlayerId,
+) -> Vec<(PlayerId, u8)> {
+    let fits = |view: &GameStateView, value: u8| {
+        view.build_piles.iter().any(|pile| pile.next_value == value)
+    };
+    after
+        .players
+        .iter()
+        .filter(|opponent| opponent.id != player)
+        .filter_map(|opponent| match opponent.stock_top {
+            Some(Card::Number(value))
+                if !fits(after, value)
+                    && steps.iter().any(|(view, _)| {
+                        view.players[opponent.id.0].stock_top == opponent.stock_top
+                            && fits(view, value)
+                    }) =>
+            {
+                Some((opponent.id, value))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+fn card_name(card: Card) -> String {
+    match card {
+        Card::Number(value) => value.to_string(),
+        Card::SkipBo => String::from("a Skip-Bo"),
+    }
+}
+
+fn join_clauses(clauses: &[String]) -> String {
+    match clauses {
+        [] => String::from("passes"),
+        [only] => only.clone(),
+        [init @ .., last] => format!("{} and {last}", init.join(", ")),
+    }
+}
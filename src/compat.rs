@@ -0,0 +1,173 @@
+//! Versioned representations for learned artifacts.
+//!
+//! Models and datasets depend on two layouts that keep evolving: the feature vector of
+//! [`StateEncoder`] and the action indices of [`Action::index`]. Both carry a version
+//! ([`ENCODER_VERSION`], [`ACTION_SPACE_VERSION`]), and every artifact written from now on
+//! is stamped with a [`Representation`]: value-net checkpoints in a `representation`
+//! field, JSON-lines datasets in a header line. Loaders compare the stamp with the
+//! current layout and fail on a mismatch, unless the caller asks for the
+//! [`FeatureShim::Resize`] shim, which pads (with zeros) or truncates feature vectors to
+//! the current length. The shim is only sound when features were appended or dropped at
+//! the end; action-space changes cannot be shimmed.
+//!
+//! Artifacts written before stamping carry no representation and load as they are.
+//!
+//! [`Action::index`]: crate::action::Action::index
+//! [`StateEncoder`]: crate::encoder::StateEncoder
+
+use std::error::Error;
+use std::fmt;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+use std::str::FromStr;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::action::{ACTION_SPACE, ACTION_SPACE_VERSION};
+use crate::encoder::{ENCODER_VERSION, STATE_FEATURES};
+
+/// The feature and action layouts an artifact was produced with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Representation {
+    pub encoder_version: u32,
+    /// Length of the feature vectors.
+    pub features: usize,
+    pub action_space_version: u32,
+    pub actions: usize,
+}
+
+impl Representation {
+    /// The layouts of this build.
+    pub fn current() -> Self {
+        Self::with_features(STATE_FEATURES)
+    }
+
+    /// The current layouts, but with `features`-long vectors (e.g. a model over custom
+    /// features).
+    pub fn with_features(features: usize) -> Self {
+        Self {
+            encoder_version: ENCODER_VERSION,
+            features,
+            action_space_version: ACTION_SPACE_VERSION,
+            actions: ACTION_SPACE,
+        }
+    }
+
+    /// Whether an artifact stamped `self` can be used by this build with `shim`.
+    pub fn check(&self, shim: FeatureShim) -> Result<(), String> {
+        let current = Self::current();
+        if self.action_space_version != current.action_space_version
+            || self.actions != current.actions
+        {
+            return Err(format!(
+                "action space v{} ({} actions) does not match v{} ({} actions)",
+                self.action_space_version,
+                self.actions,
+                current.action_space_version,
+                current.actions
+            ));
+        }
+        let matches =
+            self.encoder_version == current.encoder_version && self.features == current.features;
+        if !matches && shim == FeatureShim::Strict {
+            return Err(format!(
+                "encoder v{} ({} features) does not match v{} ({} features); \
+                 the resize shim pads or truncates features",
+                self.encoder_version, self.features, current.encoder_version, current.features
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// What loaders do with features of another encoder version.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FeatureShim {
+    /// Refuse them.
+    #[default]
+    Strict,
+    /// Pad with zeros or truncate to [`STATE_FEATURES`].
+    Resize,
+}
+
+impl fmt::Display for FeatureShim {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            FeatureShim::Strict => "strict",
+            FeatureShim::Resize => "resize",
+        })
+    }
+}
+
+impl FromStr for FeatureShim {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "strict" => Ok(FeatureShim::Strict),
+            "resize" => Ok(FeatureShim::Resize),
+            _ => Err(format!(
+                "invalid feature shim: {s} (expected strict or resize)"
+            )),
+        }
+    }
+}
+
+/// Samples carrying a [`StateEncoder`](crate::encoder::StateEncoder) feature vector.
+pub trait FeatureSample {
+    fn features_mut(&mut self) -> &mut Vec<f32>;
+}
+
+/// First line of a stamped JSON-lines dataset.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Header {
+    representation: Representation,
+}
+
+/// Write the header line stamping a dataset with the current layouts.
+pub fn write_header<W: Write>(out: &mut W) -> Result<(), Box<dyn Error>> {
+    let header = Header {
+        representation: Representation::current(),
+    };
+    serde_json::to_writer(&mut *out, &header)?;
+    out.write_all(b"\n")?;
+    Ok(())
+}
+
+/// Read JSON-lines samples, checking the header stamp (if any) and applying `shim` to
+/// every feature vector.
+pub fn read_samples<T>(path: &Path, shim: FeatureShim) -> Result<Vec<T>, Box<dyn Error>>
+where
+    T: DeserializeOwned + FeatureSample,
+{
+    let mut samples = Vec::new();
+    let mut stamped = false;
+    for (number, line) in BufReader::new(File::open(path)?).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if samples.is_empty()
+            && !stamped
+            && let Ok(header) = serde_json::from_str::<Header>(&line)
+        {
+            header
+                .representation
+                .check(shim)
+                .map_err(|err| format!("{}: {err}", path.display()))?;
+            stamped = true;
+            continue;
+        }
+        let mut sample: T = serde_json::from_str(&line)
+            .map_err(|err| format!("{}:{}: {err}", path.display(), number + 1))?;
+        if stamped && shim == FeatureShim::Resize {
+            sample.features_mut().resize(STATE_FEATURES, 0.0);
+        }
+        samples.push(sample);
+    }
+    Ok(samples)
+}
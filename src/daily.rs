@@ -0,0 +1,287 @@
+//! Daily challenge: one deal per calendar day for everyone, and a short result to share.
+//!
+//! [`DailyChallenge::for_date`] derives everything about the game from the date alone:
+//! the deal, the stock size and the opponent with its seed. Players on different machines
+//! therefore get the same cards, and an opponent that answers the same moves the same way.
+//! The challenger sits at seat 0. [`DailyResult::from_game`] summarizes the challenger's
+//! game from its history, and [`DailyResult::share`] renders the summary as a few lines of
+//! text:
+//!
+//! ```text
+//! Skip-Bo daily #20744 (2026-10-18)
+//! Won in 14 turns: 10/10 stock cards, 55 points
+//! ⬜⬜🟨⬜🟩🟩🟨🟩⬜🟩
+//! 🟩🟩🟩🏆
+//! ```
+//!
+//! Each square is one of the challenger's turns: 🟩 played from the stock, 🟨 played
+//! other cards only, ⬜ played nothing. 🏆 is the winning turn.
+//!
+//! The seed derivation is part of the format: changing [`DAILY_SEED_BASE`], the opponent
+//! or the stock size gives every day a different game and makes old results incomparable.
+
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::action::{Action, CardSource, PlayerId};
+use crate::bot::Bot;
+use crate::bots::create_bot_from_spec;
+use crate::game::Game;
+use crate::runner::mix_seed;
+use crate::score::score_all;
+
+/// Base of every daily seed.
+pub const DAILY_SEED_BASE: u64 = 0xDA11_5EED_0000_5B0B;
+/// Opponent of every daily challenge.
+pub const DAILY_OPPONENT: &str = "heuristic19";
+/// Stock pile size of every daily challenge, short enough for a coffee break.
+pub const DAILY_STOCK_SIZE: usize = 10;
+/// Seat of the challenger.
+pub const CHALLENGER: PlayerId = PlayerId(0);
+/// Squares per line of [`DailyResult::share`].
+const SQUARES_PER_LINE: usize = 10;
+
+/// A calendar date (proleptic Gregorian), written `YYYY-MM-DD`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    pub year: i32,
+    pub month: u32,
+    pub day: u32,
+}
+
+impl Date {
+    /// `None` unless the day exists.
+    pub fn new(year: i32, month: u32, day: u32) -> Option<Self> {
+        let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+        let days_in_month = match month {
+            1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+            4 | 6 | 9 | 11 => 30,
+            2 if leap => 29,
+            2 => 28,
+            _ => return None,
+        };
+        (1..=days_in_month)
+            .contains(&day)
+            .then_some(Self { year, month, day })
+    }
+
+    /// Today in UTC, so that every time zone plays the same challenge at the same moment.
+    pub fn today() -> Self {
+        let seconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+        Self::from_days(seconds.div_euclid(86_400) as i64)
+    }
+
+    /// Days since 1970-01-01.
+    pub fn days_since_epoch(&self) -> i64 {
+        // Howard Hinnant's days_from_civil.
+        let year = i64::from(self.year) - i64::from(self.month <= 2);
+        let era = year.div_euclid(400);
+        let year_of_era = year - era * 400;
+        let month = i64::from(self.month);
+        let day_of_year =
+            (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + i64::from(self.day) - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        era * 146_097 + day_of_era - 719_468
+    }
+
+    /// The date `days` after 1970-01-01.
+    pub fn from_days(days: i64) -> Self {
+        // Howard Hinnant's civil_from_days.
+        let days = days + 719_468;
+        let era = days.div_euclid(146_097);
+        let day_of_era = days - era * 146_097;
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let month_index = (5 * day_of_year + 2) / 153;
+        let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+        let month = if month_index < 10 {
+            month_index + 3
+        } else {
+            month_index - 9
+        } as u32;
+        let year = year_of_era + era * 400 + i64::from(month <= 2);
+        Self {
+            year: year as i32,
+            month,
+            day,
+        }
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+impl FromStr for Date {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid date: {s} (expected YYYY-MM-DD)");
+        let mut parts = s.trim().splitn(3, '-');
+        let mut next = || parts.next().ok_or_else(invalid);
+        let (year, month, day) = (next()?, next()?, next()?);
+        Date::new(
+            year.parse().map_err(|_| invalid())?,
+            month.parse().map_err(|_| invalid())?,
+            day.parse().map_err(|_| invalid())?,
+        )
+        .ok_or_else(invalid)
+    }
+}
+
+/// The seed of `date`'s challenge.
+pub fn daily_seed(date: Date) -> u64 {
+    mix_seed(DAILY_SEED_BASE, date.days_since_epoch() as u64, 0xDA1)
+}
+
+/// Everything about one day's game; see the [module docs](self).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DailyChallenge {
+    pub date: Date,
+    pub seed: u64,
+    pub opponent: String,
+    pub stock_size: usize,
+}
+
+impl DailyChallenge {
+    pub fn for_date(date: Date) -> Self {
+        Self {
+            date,
+            seed: daily_seed(date),
+            opponent: DAILY_OPPONENT.to_string(),
+            stock_size: DAILY_STOCK_SIZE,
+        }
+    }
+
+    pub fn today() -> Self {
+        Self::for_date(Date::today())
+    }
+
+    /// Challenge number: days since 1970-01-01.
+    pub fn number(&self) -> i64 {
+        self.date.days_since_epoch()
+    }
+
+    /// The deal, with the challenger to move first.
+    pub fn game(&self) -> Result<Game, Box<dyn Error>> {
+        Ok(Game::builder(2)?
+            .with_seed(self.seed)
+            .with_stock_size(self.stock_size)
+            .build()?)
+    }
+
+    /// The opponent at seat 1, built from its spec with the challenge seed. Drivers that
+    /// build their own bots must do the same to face the same opponent.
+    pub fn opponent(&self) -> Result<Box<dyn Bot>, Box<dyn Error>> {
+        create_bot_from_spec(&self.opponent, PlayerId(1), self.seed)
+    }
+}
+
+/// What one of the challenger's turns did, shown as a square of the share string.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TurnKind {
+    /// Played at least one stock card.
+    Stock,
+    /// Played cards, none of them from the stock.
+    Play,
+    /// Played no card.
+    Pass,
+}
+
+impl TurnKind {
+    pub fn square(self) -> &'static str {
+        match self {
+            TurnKind::Stock => "🟩",
+            TurnKind::Play => "🟨",
+            TurnKind::Pass => "⬜",
+        }
+    }
+}
+
+/// The challenger's result of one daily challenge.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DailyResult {
+    /// `YYYY-MM-DD`.
+    pub date: String,
+    pub number: i64,
+    pub finished: bool,
+    pub won: bool,
+    /// Turns the challenger took.
+    pub turns: Vec<TurnKind>,
+    pub stock_played: usize,
+    pub stock_size: usize,
+    /// Points of a win; 0 otherwise.
+    pub points: usize,
+}
+
+impl DailyResult {
+    /// Summarize `game`, a game of `challenge` as far as it was played.
+    pub fn from_game(challenge: &DailyChallenge, game: &Game) -> Result<Self, Box<dyn Error>> {
+        let mut turns = Vec::new();
+        let mut previous = None;
+        for (player, action) in game.history() {
+            if *player == CHALLENGER {
+                if previous != Some(CHALLENGER) {
+                    turns.push(TurnKind::Pass);
+                }
+                let turn = turns.last_mut().expect("a turn was started");
+                if let Action::Play { source, .. } = action {
+                    *turn = match (source, *turn) {
+                        (CardSource::Stock, _) | (_, TurnKind::Stock) => TurnKind::Stock,
+                        _ => TurnKind::Play,
+                    };
+                }
+            }
+            previous = Some(*player);
+        }
+        let score = score_all(&game.state_view(CHALLENGER)?)
+            .into_iter()
+            .find(|score| score.player == CHALLENGER)
+            .ok_or("the challenger has no score")?;
+        Ok(Self {
+            date: challenge.date.to_string(),
+            number: challenge.number(),
+            finished: game.is_finished(),
+            won: score.is_winner,
+            turns,
+            stock_played: score.cards_played,
+            stock_size: challenge.stock_size,
+            points: score.points,
+        })
+    }
+
+    /// The result as text to paste into a chat; see the [module docs](self).
+    pub fn share(&self) -> String {
+        let turns = self.turns.len();
+        let outcome = match (self.finished, self.won) {
+            (true, true) => format!("Won in {turns} turns"),
+            (true, false) => format!("Lost after {turns} turns"),
+            (false, _) => format!("Unfinished after {turns} turns"),
+        };
+        let mut out = format!(
+            "Skip-Bo daily #{} ({})\n{outcome}: {}/{} stock cards, {} points",
+            self.number, self.date, self.stock_played, self.stock_size, self.points
+        );
+        let mut squares: Vec<&str> = self.turns.iter().map(|turn| turn.square()).collect();
+        if self.won
+            && let Some(last) = squares.last_mut()
+        {
+            *last = "🏆";
+        }
+        for line in squares.chunks(SQUARES_PER_LINE) {
+            out.push('\n');
+            out.push_str(&line.concat());
+        }
+        out
+    }
+}
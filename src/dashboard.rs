@@ -0,0 +1,265 @@
+//! Timeseries export of evaluation history for external dashboards.
+//!
+//! Every saved [`MatchReport`] or [`LeagueReport`] is one checkpoint, in the order given.
+//! For each checkpoint the export holds every bot's Plackett-Luce rating (refitted from
+//! the report's games exactly as a league rates its pool) and its win rate against each
+//! baseline over the games they shared. Ratings of separate runs share no scale, so they
+//! are shifted to put the anchor bot (the first baseline by default) at 0 wherever it
+//! played.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::league::{Attribution, LeagueReport, Ranking, elo_ratings};
+use crate::runner::{GameResult, MatchReport};
+
+/// Version of the [`Dashboard`] layout.
+pub const DASHBOARD_VERSION: u32 = 1;
+
+/// The games of one saved report.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CheckpointReport {
+    /// Name shown on the time axis, by default the report's file stem.
+    pub name: String,
+    pub source: PathBuf,
+    pub kind: ReportKind,
+    pub games: Vec<GameResult>,
+    /// How the games feed the ratings; match reports use [`Attribution::Winner`].
+    pub attribution: Attribution,
+}
+
+/// Which tool wrote a report.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ReportKind {
+    Match,
+    League,
+}
+
+impl CheckpointReport {
+    /// Read a match or league report.
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let json = fs::read_to_string(path)?;
+        let name = path.file_stem().map_or_else(
+            || path.display().to_string(),
+            |s| s.to_string_lossy().into(),
+        );
+        let (kind, games, attribution) = match MatchReport::from_json(&json) {
+            Ok(report) => (ReportKind::Match, report.games, Attribution::Winner),
+            Err(match_err) => match serde_json::from_str::<LeagueReport>(&json) {
+                Ok(report) => (ReportKind::League, report.games, report.config.attribution),
+                Err(league_err) => {
+                    return Err(format!(
+                        "{}: neither a match report ({match_err}) nor a league report ({league_err})",
+                        path.display()
+                    )
+                    .into());
+                }
+            },
+        };
+        Ok(Self {
+            name,
+            source: path.to_path_buf(),
+            kind,
+            games,
+            attribution,
+        })
+    }
+
+    /// Checkpoints from `paths` in order; a directory contributes its `.json` files
+    /// sorted by name.
+    pub fn load_all(paths: &[PathBuf]) -> Result<Vec<Self>, Box<dyn Error>> {
+        let mut checkpoints = Vec::new();
+        for path in paths {
+            if path.is_dir() {
+                let mut files: Vec<PathBuf> = fs::read_dir(path)?
+                    .map(|entry| entry.map(|e| e.path()))
+                    .collect::<Result<_, _>>()?;
+                files.retain(|file| file.extension().is_some_and(|ext| ext == "json"));
+                files.sort();
+                for file in files {
+                    checkpoints.push(Self::load(&file)?);
+                }
+            } else {
+                checkpoints.push(Self::load(path)?);
+            }
+        }
+        Ok(checkpoints)
+    }
+}
+
+/// One point of the time axis.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub index: usize,
+    pub name: String,
+    pub source: PathBuf,
+    pub kind: ReportKind,
+    pub games: usize,
+}
+
+/// A bot's rating at one checkpoint.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RatingPoint {
+    pub checkpoint: usize,
+    /// Elo-scale rating relative to the anchor (or to the fit's reference without one).
+    pub rating: f64,
+    /// Standard error of `rating`.
+    pub error: f64,
+    pub games: usize,
+    pub wins: usize,
+}
+
+/// A bot's ratings over the checkpoints it played in.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RatingSeries {
+    pub bot: String,
+    pub points: Vec<RatingPoint>,
+}
+
+/// A bot's results against one baseline at one checkpoint.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WinRatePoint {
+    pub checkpoint: usize,
+    /// Games both played in.
+    pub games: usize,
+    /// Those games the bot won.
+    pub wins: usize,
+    pub win_rate: f64,
+}
+
+/// A bot's win rate against one baseline over the checkpoints where they met.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WinRateSeries {
+    pub bot: String,
+    pub baseline: String,
+    pub points: Vec<WinRatePoint>,
+}
+
+/// The consolidated timeseries.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Dashboard {
+    pub version: u32,
+    pub anchor: Option<String>,
+    pub baselines: Vec<String>,
+    pub checkpoints: Vec<Checkpoint>,
+    /// One series per bot label, sorted by label.
+    pub ratings: Vec<RatingSeries>,
+    /// One series per bot and baseline that met, sorted by bot then baseline.
+    pub win_rates: Vec<WinRateSeries>,
+}
+
+impl Dashboard {
+    /// Consolidate `checkpoints`. `anchor` defaults to the first of `baselines`.
+    pub fn build(
+        checkpoints: &[CheckpointReport],
+        baselines: &[String],
+        anchor: Option<&str>,
+    ) -> Self {
+        let anchor = anchor.or(baselines.first().map(String::as_str));
+        let mut ratings: BTreeMap<String, Vec<RatingPoint>> = BTreeMap::new();
+        let mut win_rates: BTreeMap<(String, String), Vec<WinRatePoint>> = BTreeMap::new();
+        for (index, checkpoint) in checkpoints.iter().enumerate() {
+            let labels: Vec<&str> = checkpoint
+                .games
+                .iter()
+                .flat_map(|game| game.seats.iter().map(String::as_str))
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .collect();
+            let pool = |label: &str| labels.binary_search(&label).expect("label of a game");
+            let rankings: Vec<Ranking> = checkpoint
+                .games
+                .iter()
+                .map(|game| {
+                    let seats: Vec<usize> = game.seats.iter().map(|l| pool(l)).collect();
+                    Ranking::from_result(game, &seats, checkpoint.attribution)
+                })
+                .collect();
+            let (elo, errors) = elo_ratings(&rankings, labels.len());
+            let shift = anchor
+                .and_then(|anchor| labels.binary_search(&anchor).ok())
+                .map_or(0.0, |bot| elo[bot]);
+            for (bot, label) in labels.iter().enumerate() {
+                let played: Vec<&GameResult> = checkpoint
+                    .games
+                    .iter()
+                    .filter(|game| game.seats.iter().any(|s| s == label))
+                    .collect();
+                let won = |games: &[&GameResult]| {
+                    games
+                        .iter()
+                        .filter(|game| game.winner_label() == Some(label))
+                        .count()
+                };
+                ratings
+                    .entry(label.to_string())
+                    .or_default()
+                    .push(RatingPoint {
+                        checkpoint: index,
+                        rating: elo[bot] - shift,
+                        error: errors[bot],
+                        games: played.len(),
+                        wins: won(&played),
+                    });
+                for baseline in baselines.iter().filter(|b| *b != label) {
+                    let shared: Vec<&GameResult> = played
+                        .iter()
+                        .copied()
+                        .filter(|game| game.seats.contains(baseline))
+                        .collect();
+                    if shared.is_empty() {
+                        continue;
+                    }
+                    let wins = won(&shared);
+                    win_rates
+                        .entry((label.to_string(), baseline.clone()))
+                        .or_default()
+                        .push(WinRatePoint {
+                            checkpoint: index,
+                            games: shared.len(),
+                            wins,
+                            win_rate: wins as f64 / shared.len() as f64,
+                        });
+                }
+            }
+        }
+        Self {
+            version: DASHBOARD_VERSION,
+            anchor: anchor.map(String::from),
+            baselines: baselines.to_vec(),
+            checkpoints: checkpoints
+                .iter()
+                .enumerate()
+                .map(|(index, checkpoint)| Checkpoint {
+                    index,
+                    name: checkpoint.name.clone(),
+                    source: checkpoint.source.clone(),
+                    kind: checkpoint.kind,
+                    games: checkpoint.games.len(),
+                })
+                .collect(),
+            ratings: ratings
+                .into_iter()
+                .map(|(bot, points)| RatingSeries { bot, points })
+                .collect(),
+            win_rates: win_rates
+                .into_iter()
+                .map(|((bot, baseline), points)| WinRateSeries {
+                    bot,
+                    baseline,
+                    points,
+                })
+                .collect(),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,573 @@
+//! Imitation data mined from tournaments.
+//!
+//! A tournament run with `winrate --report R --archive A` leaves a [`MatchReport`] and an
+//! archive of its games. [`PolicyDataset::from_tournament`] replays every archived game
+//! and turns each move into a [`PolicySample`] labelled with two things the move itself
+//! cannot tell: how the game ended for the acting seat, and how strong the acting bot was
+//! over the whole tournament (its composite score under [`ObjectiveWeights`]). Filtering
+//! on those labels ([`PolicyDataset::top_rated`]) keeps the strongest observed play of
+//! the whole bot population for a policy to imitate. Each sample also carries its
+//! [`Provenance`], so training can be narrowed to, say, late-game moves
+//! ([`PolicyDataset::from_turn`]) and a bad sample traced back to its deal.
+//!
+//! Moves of one game share most of their position, so held-out sets are drawn whole games
+//! at a time: [`PolicyDataset::split`] into train, validation and test sets, and
+//! [`PolicyDataset::k_fold`] for cross-validation.
+//!
+//! [`QualityReport`] summarizes a collected dataset, from either source, before any
+//! training time is spent on it: the mix of action types, how many decisions were forced,
+//! how each teacher fared and how the sample weights spread, with
+//! [`QualityReport::warnings`] for the shapes that usually mean a degenerate run.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::error::Error;
+use std::fmt;
+use std::fs::File;
+use std::io::{BufWriter, Read, Seek, Write};
+use std::path::Path;
+
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+
+use crate::action::{Action, CardSource, PlayerId};
+use crate::archive::ArchiveReader;
+use crate::compat::{FeatureSample, FeatureShim, read_samples, write_header};
+use crate::encoder::StateEncoder;
+use crate::runner::{MatchReport, ObjectiveWeights};
+use crate::score::seat_outcome;
+
+/// One archived move.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PolicySample {
+    /// Index of the game in the tournament.
+    pub game: usize,
+    pub seat: PlayerId,
+    /// Label of the bot that made the move.
+    pub bot: String,
+    /// [`StateEncoder::encode`] of the acting seat's view.
+    pub features: Vec<f32>,
+    /// [`Action::index`] of every legal action and of the chosen one.
+    pub legal: Vec<usize>,
+    pub action: usize,
+    /// Final result for `seat`; see [`seat_outcome`].
+    pub outcome: f32,
+    /// Tournament composite score of `bot`.
+    pub rating: f64,
+    /// Where the move was made; absent when the game is not known, as for anonymized
+    /// samples.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<Provenance>,
+}
+
+/// The deal and moment of a [`PolicySample`]. The acting player and the teacher are the
+/// sample's `seat` and `bot`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Provenance {
+    /// Seed the game's deck was shuffled with.
+    pub deck_seed: u64,
+    /// Zero-based turn of the move, counted over all players.
+    pub turn: usize,
+    /// Position of the move in the game.
+    pub ply: usize,
+}
+
+impl FeatureSample for PolicySample {
+    fn features_mut(&mut self) -> &mut Vec<f32> {
+        &mut self.features
+    }
+}
+
+impl LabelledSample for PolicySample {
+    fn teacher(&self) -> &str {
+        &self.bot
+    }
+
+    fn game(&self) -> usize {
+        self.game
+    }
+
+    fn seat(&self) -> PlayerId {
+        self.seat
+    }
+
+    fn legal(&self) -> &[usize] {
+        &self.legal
+    }
+
+    fn action(&self) -> usize {
+        self.action
+    }
+
+    fn outcome(&self) -> f32 {
+        self.outcome
+    }
+
+    /// The teacher's tournament rating.
+    fn weight(&self) -> f64 {
+        self.rating
+    }
+}
+
+/// Moves of a tournament with outcome and rating labels.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PolicyDataset {
+    pub samples: Vec<PolicySample>,
+}
+
+/// A [`PolicyDataset`] divided whole games at a time; see [`PolicyDataset::split`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DatasetSplit {
+    pub train: PolicyDataset,
+    pub validation: PolicyDataset,
+    pub test: PolicyDataset,
+}
+
+impl PolicyDataset {
+    /// Replay every game of `archive` and label its moves from `report`. Archived games
+    /// are matched to the report by their `Game` tag (falling back to their position),
+    /// and must have been played by the seats the report lists.
+    pub fn from_tournament<R: Read + Seek>(
+        report: &MatchReport,
+        archive: &mut ArchiveReader<R>,
+        weights: &ObjectiveWeights,
+    ) -> Result<Self, Box<dyn Error>> {
+        let ratings: HashMap<&str, f64> = report
+            .bots
+            .iter()
+            .map(|bot| (bot.label.as_str(), report.composite_score(bot, weights)))
+            .collect();
+        let mut samples = Vec::new();
+        for position in 0..archive.len() {
+            let record = archive.get(position)?;
+            let game_index = match record.tag("Game") {
+                Some(tag) => tag
+                    .parse()
+                    .map_err(|_| format!("archived game {position}: bad Game tag {tag}"))?,
+                None => position,
+            };
+            let result = report
+                .games
+                .iter()
+                .find(|game| game.game_index == game_index)
+                .ok_or_else(|| format!("report has no game {game_index}"))?;
+            if record
+                .tag("Bots")
+                .is_some_and(|bots| bots != result.seats.join(" "))
+            {
+                return Err(format!("game {game_index} was played by other bots").into());
+            }
+            let first = samples.len();
+            let mut ply = 0;
+            record
+                .replay_with(|game, seat, action| {
+                    let legal = game.legal_actions(seat).unwrap_or_default();
+                    let view = game
+                        .state_view_ref(seat)
+                        .expect("the acting player is seated");
+                    let bot = &result.seats[seat.0];
+                    samples.push(PolicySample {
+                        game: game_index,
+                        seat,
+                        bot: bot.clone(),
+                        features: StateEncoder::encode(&view).to_vec(),
+                        legal: legal.iter().filter_map(Action::index).collect(),
+                        action: action.index().expect("archived moves have an index"),
+                        outcome: 0.0,
+                        rating: ratings.get(bot.as_str()).copied().unwrap_or(0.0),
+                        provenance: Some(Provenance {
+                            deck_seed: result.deck_seed,
+                            turn: game.turn_number(),
+                            ply,
+                        }),
+                    });
+                    ply += 1;
+                })
+                .map_err(|err| format!("game {game_index}: {err}"))?;
+            for sample in &mut samples[first..] {
+                sample.outcome = seat_outcome(result.winner, sample.seat);
+            }
+        }
+        Ok(Self { samples })
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Moves of the `bots` best-rated bots, only from games they won when
+    /// `winners_only` is set.
+    pub fn top_rated(&self, bots: usize, winners_only: bool) -> Self {
+        let mut ranked: Vec<(&str, f64)> = Vec::new();
+        for sample in &self.samples {
+            if !ranked.iter().any(|(label, _)| *label == sample.bot) {
+                ranked.push((&sample.bot, sample.rating));
+            }
+        }
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        ranked.truncate(bots);
+        let samples = self
+            .samples
+            .iter()
+            .filter(|sample| ranked.iter().any(|(label, _)| *label == sample.bot))
+            .filter(|sample| !winners_only || sample.outcome > 0.0)
+            .cloned()
+            .collect();
+        Self { samples }
+    }
+
+    /// The samples `keep` accepts.
+    pub fn filter(&self, keep: impl Fn(&PolicySample) -> bool) -> Self {
+        Self {
+            samples: self.samples.iter().filter(|s| keep(s)).cloned().collect(),
+        }
+    }
+
+    /// Moves made on turn `turn` or later; samples without provenance are dropped.
+    pub fn from_turn(&self, turn: usize) -> Self {
+        self.filter(|sample| sample.provenance.as_ref().is_some_and(|p| p.turn >= turn))
+    }
+
+    /// Divide the games into train, validation and test sets, the latter two holding the
+    /// `validation` and `test` shares of the games (rounded to whole games). Which game
+    /// goes where depends only on `seed` and the games present, and every sample of a
+    /// game lands in the same set.
+    pub fn split(
+        &self,
+        validation: f64,
+        test: f64,
+        seed: u64,
+    ) -> Result<DatasetSplit, Box<dyn Error>> {
+        if !(0.0..=1.0).contains(&validation) || !(0.0..=1.0).contains(&test) {
+            return Err(format!("invalid split fractions {validation} and {test}").into());
+        }
+        if validation + test >= 1.0 {
+            return Err("validation and test leave no games to train on".into());
+        }
+        let games = self.shuffled_games(seed);
+        let validation = (games.len() as f64 * validation).round() as usize;
+        let test = (games.len() as f64 * test).round() as usize;
+        let sets: HashMap<usize, usize> = games
+            .iter()
+            .enumerate()
+            .map(|(position, &game)| {
+                let set = if position < validation {
+                    1
+                } else if position < validation + test {
+                    2
+                } else {
+                    0
+                };
+                (game, set)
+            })
+            .collect();
+        let [train, validation, test] = self.partition(|game| sets[&game]);
+        Ok(DatasetSplit {
+            train,
+            validation,
+            test,
+        })
+    }
+
+    /// `k` pairs of `(train, validation)` sets for cross-validation: the games are shuffled
+    /// by `seed` and dealt into `k` folds of nearly equal size, and each fold is the
+    /// validation set of one pair.
+    pub fn k_fold(&self, k: usize, seed: u64) -> Result<Vec<(Self, Self)>, Box<dyn Error>> {
+        let games = self.shuffled_games(seed);
+        if k < 2 || k > games.len() {
+            return Err(format!("cannot make {k} folds of {} games", games.len()).into());
+        }
+        let folds: HashMap<usize, usize> = games
+            .iter()
+            .enumerate()
+            .map(|(position, &game)| (game, position % k))
+            .collect();
+        Ok((0..k)
+            .map(|fold| {
+                let [train, validation] = self.partition(|game| usize::from(folds[&game] == fold));
+                (train, validation)
+            })
+            .collect())
+    }
+
+    /// The distinct games of the samples, in an order shuffled by `seed`.
+    fn shuffled_games(&self, seed: u64) -> Vec<usize> {
+        let mut games: Vec<usize> = self.samples.iter().map(|sample| sample.game).collect();
+        games.sort_unstable();
+        games.dedup();
+        games.shuffle(&mut StdRng::seed_from_u64(seed));
+        games
+    }
+
+    /// The samples in `N` sets, each in the set `set_of` picks for its game, in order.
+    fn partition<const N: usize>(&self, set_of: impl Fn(usize) -> usize) -> [Self; N] {
+        let mut sets: [Self; N] = std::array::from_fn(|_| Self::default());
+        for sample in &self.samples {
+            sets[set_of(sample.game)].samples.push(sample.clone());
+        }
+        sets
+    }
+
+    /// Write the samples as JSON lines after a [`Representation`](crate::compat::Representation)
+    /// header.
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        let mut out = BufWriter::new(File::create(path)?);
+        write_header(&mut out)?;
+        for sample in &self.samples {
+            serde_json::to_writer(&mut out, sample)?;
+            out.write_all(b"\n")?;
+        }
+        out.flush()?;
+        Ok(())
+    }
+
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        Self::load_with(path, FeatureShim::Strict)
+    }
+
+    /// [`PolicyDataset::load`], adapting features of another encoder version with `shim`.
+    pub fn load_with(path: &Path, shim: FeatureShim) -> Result<Self, Box<dyn Error>> {
+        Ok(Self {
+            samples: read_samples(path, shim)?,
+        })
+    }
+}
+
+/// A recorded decision as [`QualityReport`] sees it.
+pub trait LabelledSample {
+    /// Label of whoever made the decision.
+    fn teacher(&self) -> &str;
+    fn game(&self) -> usize;
+    fn seat(&self) -> PlayerId;
+    /// [`Action::index`] of every legal action.
+    fn legal(&self) -> &[usize];
+    /// [`Action::index`] of the chosen action.
+    fn action(&self) -> usize;
+    /// Final result for the seat: 1.0 win, -1.0 loss, 0.0 draw or capped game.
+    fn outcome(&self) -> f32;
+    /// Weight of the sample in training; 1.0 unless the dataset weighs its samples.
+    fn weight(&self) -> f64 {
+        1.0
+    }
+}
+
+/// Fraction of single-legal-action samples above which [`QualityReport::warnings`]
+/// complains.
+pub const MAX_FORCED_FRACTION: f64 = 0.5;
+
+/// Share of one action type above which [`QualityReport::warnings`] complains.
+pub const MAX_ACTION_TYPE_SHARE: f64 = 0.95;
+
+/// Decisions and results of one teacher.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TeacherStats {
+    pub teacher: String,
+    pub samples: usize,
+    /// Seats the teacher played (a game counts once per seat).
+    pub seats: usize,
+    pub wins: usize,
+}
+
+impl TeacherStats {
+    /// Wins per seat played.
+    pub fn win_rate(&self) -> f64 {
+        self.wins as f64 / self.seats.max(1) as f64
+    }
+}
+
+/// Summary statistics of a collected dataset; feed it samples with
+/// [`QualityReport::add`] or build it at once with [`QualityReport::from_samples`].
+#[derive(Clone, Debug, Default)]
+pub struct QualityReport {
+    pub samples: usize,
+    /// Chosen actions by type: `stock play`, `discard play`, `hand play`, `discard`,
+    /// `end turn` (and `invalid` for indices outside the action table).
+    pub action_types: BTreeMap<&'static str, usize>,
+    legal_total: usize,
+    single_legal: usize,
+    /// Teachers in order of first appearance.
+    pub teachers: Vec<TeacherStats>,
+    seats_seen: HashSet<(usize, PlayerId)>,
+    weights: Vec<f64>,
+}
+
+impl QualityReport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_samples<'a, S: LabelledSample + 'a>(
+        samples: impl IntoIterator<Item = &'a S>,
+    ) -> Self {
+        let mut report = Self::new();
+        for sample in samples {
+            report.add(sample);
+        }
+        report
+    }
+
+    pub fn add(&mut self, sample: &impl LabelledSample) {
+        self.samples += 1;
+        *self
+            .action_types
+            .entry(action_type(sample.action()))
+            .or_default() += 1;
+        self.legal_total += sample.legal().len();
+        self.single_legal += usize::from(sample.legal().len() == 1);
+        self.weights.push(sample.weight());
+        let index = match self
+            .teachers
+            .iter()
+            .position(|t| t.teacher == sample.teacher())
+        {
+            Some(index) => index,
+            None => {
+                self.teachers.push(TeacherStats {
+                    teacher: sample.teacher().to_string(),
+                    ..TeacherStats::default()
+                });
+                self.teachers.len() - 1
+            }
+        };
+        let teacher = &mut self.teachers[index];
+        teacher.samples += 1;
+        if self.seats_seen.insert((sample.game(), sample.seat())) {
+            teacher.seats += 1;
+            teacher.wins += usize::from(sample.outcome() > 0.0);
+        }
+    }
+
+    /// Mean number of legal actions per sample.
+    pub fn mean_legal(&self) -> f64 {
+        self.legal_total as f64 / self.samples.max(1) as f64
+    }
+
+    /// Fraction of samples with a single legal action, which teach nothing.
+    pub fn forced_fraction(&self) -> f64 {
+        self.single_legal as f64 / self.samples.max(1) as f64
+    }
+
+    /// Sample weights in `bins` equal-width bins from the smallest to the largest weight,
+    /// as `(low, high, count)`; a single bin when every weight is the same.
+    pub fn weight_histogram(&self, bins: usize) -> Vec<(f64, f64, usize)> {
+        let Some(low) = self.weights.iter().copied().reduce(f64::min) else {
+            return Vec::new();
+        };
+        let high = self.weights.iter().copied().fold(low, f64::max);
+        let bins = if high > low { bins.max(1) } else { 1 };
+        let width = (high - low) / bins as f64;
+        let mut counts = vec![0; bins];
+        for &weight in &self.weights {
+            let bin = if width > 0.0 {
+                (((weight - low) / width) as usize).min(bins - 1)
+            } else {
+                0
+            };
+            counts[bin] += 1;
+        }
+        counts
+            .into_iter()
+            .enumerate()
+            .map(|(i, count)| {
+                let from = low + width * i as f64;
+                (from, from + width, count)
+            })
+            .collect()
+    }
+
+    /// Signs of a degenerate dataset, empty when none shows.
+    pub fn warnings(&self) -> Vec<String> {
+        if self.samples == 0 {
+            return vec!["no samples".into()];
+        }
+        let mut warnings = Vec::new();
+        if self.forced_fraction() > MAX_FORCED_FRACTION {
+            warnings.push(format!(
+                "{:.0}% of samples have a single legal action",
+                self.forced_fraction() * 100.0
+            ));
+        }
+        for (kind, &count) in &self.action_types {
+            let share = count as f64 / self.samples as f64;
+            if share > MAX_ACTION_TYPE_SHARE {
+                warnings.push(format!("{:.0}% of actions are {kind}", share * 100.0));
+            }
+        }
+        if self.action_types.contains_key("invalid") {
+            warnings.push("some actions are outside the action table".into());
+        }
+        for teacher in &self.teachers {
+            if teacher.wins == 0 {
+                warnings.push(format!(
+                    "{} never won in {} seats",
+                    teacher.teacher, teacher.seats
+                ));
+            }
+        }
+        warnings
+    }
+}
+
+impl fmt::Display for QualityReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Samples: {}", self.samples)?;
+        writeln!(
+            f,
+            "Legal actions: {:.2} on average, {:.1}% forced",
+            self.mean_legal(),
+            self.forced_fraction() * 100.0
+        )?;
+        writeln!(f, "Action types:")?;
+        for (kind, count) in &self.action_types {
+            let share = *count as f64 / self.samples.max(1) as f64;
+            writeln!(f, "  {kind:<14} {count:>8} {:>6.1}%", share * 100.0)?;
+        }
+        writeln!(f, "Teachers:")?;
+        writeln!(
+            f,
+            "  {:<24} {:>8} {:>6} {:>6} {:>8}",
+            "teacher", "samples", "seats", "wins", "win rate"
+        )?;
+        for teacher in &self.teachers {
+            writeln!(
+                f,
+                "  {:<24} {:>8} {:>6} {:>6} {:>7.1}%",
+                teacher.teacher,
+                teacher.samples,
+                teacher.seats,
+                teacher.wins,
+                teacher.win_rate() * 100.0
+            )?;
+        }
+        writeln!(f, "Sample weights:")?;
+        for (low, high, count) in self.weight_histogram(10) {
+            writeln!(f, "  {low:>10.3} .. {high:<10.3} {count:>8}")?;
+        }
+        for warning in self.warnings() {
+            writeln!(f, "Warning: {warning}")?;
+        }
+        Ok(())
+    }
+}
+
+pub(crate) fn action_type(index: usize) -> &'static str {
+    match Action::from_index(index) {
+        Some(Action::Play {
+            source: CardSource::Stock,
+            ..
+        }) => "stock play",
+        Some(Action::Play {
+            source: CardSource::Discard(_),
+            ..
+        }) => "discard play",
+        Some(Action::Play { .. }) => "hand play",
+        Some(Action::Discard { .. }) => "discard",
+        Some(Action::EndTurn) => "end turn",
+        None => "invalid",
+    }
+}
@@ -0,0 +1,327 @@
+//! Flat numeric encoding of a game view for learning and analysis tools. Anything
+//! implementing [`StateView`] can be encoded: the owned
+//! [`GameStateView`](crate::state::GameStateView) or the borrowed
+//! [`GameStateViewRef`](crate::game::GameStateViewRef) that copies nothing.
+//!
+//! The layout is fixed and described feature by feature by
+//! [`StateEncoder::feature_names`]. Seats are ordered relative to the viewing player
+//! (`seat0` is always the viewer) and padded up to [`MAX_PLAYERS`]; the `role.*`
+//! features carry the viewer's absolute seat instead. Cards are bucketed
+//! as `skip_bo` or their face value. Every feature is scaled to `[0, 1]`.
+//!
+//! The `settings.*` features at the end describe the rules of the game (stock size,
+//! hand size, the viewer's discard piles, house rules). Together with `num_players` they
+//! let a single policy play across configurations rather than one per configuration;
+//! the stock and hand features are scaled by those sizes and cannot tell them apart.
+//!
+//! [`StateEncoder::encode_privileged`] extends the layout with the opponents' hands,
+//! read from a [`FullStateView`]. It is for open-hand training only: a critic may learn
+//! from what no player can see while the actor keeps the standard features.
+//! [`StateEncoder::encode_joint`] concatenates the privileged encodings of every seat
+//! into a single input for a centralized critic (as in MAPPO).
+//!
+//! [`StateEncoder::encode_public`] goes the other way and zeroes the viewer's own hand,
+//! leaving only what every player and spectator sees. Shared datasets carry it so they
+//! reveal no hidden cards.
+
+use std::sync::OnceLock;
+
+use crate::action::{BuildPileId, PlayerId};
+use crate::card::{
+    BUILD_PILE_COUNT, Card, DISCARD_PILE_COUNT, HAND_SIZE, MAX_CARD_VALUE, MAX_DISCARD_PILES,
+    MAX_PLAYERS,
+};
+use crate::error::GameError;
+use crate::game::Game;
+use crate::state::{FullStateView, StateView, StockVisibility};
+
+/// Card buckets: Skip-Bo followed by values 1-12.
+const CARD_BUCKETS: usize = MAX_CARD_VALUE as usize + 1;
+/// Features per discard pile: top value, top is Skip-Bo, depth.
+const DISCARD_FEATURES: usize = 3;
+/// Features per seat: present, stock count, stock top bucket, discard piles, hand size.
+const SEAT_FEATURES: usize = 2 + CARD_BUCKETS + DISCARD_PILE_COUNT * DISCARD_FEATURES + 1;
+/// Features describing the whole table: draw pile, recycle pile, player count, turn
+/// number, actions taken this turn, stock visibility.
+const GLOBAL_FEATURES: usize = 6;
+/// Features describing the viewer's role: absolute seat (one-hot) and position after the
+/// dealer. They let one policy shared by every seat condition on where it sits.
+const ROLE_FEATURES: usize = MAX_PLAYERS + 1;
+/// Features describing the rules: stock size, hand size, the viewer's discard pile count,
+/// must-play-stock.
+const SETTINGS_FEATURES: usize = 4;
+
+/// Version of the feature layout. Bump whenever a feature is added, removed, moved or
+/// rescaled, so models and experiments trained on an older layout can tell.
+pub const ENCODER_VERSION: u32 = 2;
+
+/// Length of the encoded feature vector.
+pub const STATE_FEATURES: usize = GLOBAL_FEATURES
+    + ROLE_FEATURES
+    + BUILD_PILE_COUNT
+    + CARD_BUCKETS
+    + MAX_PLAYERS * SEAT_FEATURES
+    + SETTINGS_FEATURES;
+
+/// Length of [`StateEncoder::encode_privileged`]: the standard features followed by the
+/// hand of every other seat, in the same relative seat order.
+pub const PRIVILEGED_FEATURES: usize = STATE_FEATURES + (MAX_PLAYERS - 1) * CARD_BUCKETS;
+
+/// Length of [`StateEncoder::encode_joint`]: one privileged block per seat.
+pub const JOINT_FEATURES: usize = MAX_PLAYERS * PRIVILEGED_FEATURES;
+
+/// Number of cards in a standard deck, used to scale pile counts.
+const DECK_SIZE: f32 = 162.0;
+/// Discard depth treated as "full" when scaling.
+const DISCARD_DEPTH_SCALE: f32 = 12.0;
+/// Turn number treated as "late" when scaling.
+const TURN_SCALE: f32 = 200.0;
+/// Actions in one turn treated as "many" when scaling.
+const TURN_ACTIONS_SCALE: f32 = 20.0;
+/// Stock size treated as "large" when scaling; the standard rules deal at most 30.
+const STOCK_SIZE_SCALE: f32 = 50.0;
+
+/// Encodes views into `[f32; STATE_FEATURES]`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StateEncoder;
+
+impl StateEncoder {
+    /// Encode `state` from the perspective of its viewing player.
+    pub fn encode<V: StateView + ?Sized>(state: &V) -> [f32; STATE_FEATURES] {
+        let mut out = [0.0; STATE_FEATURES];
+        let mut i = 0;
+        let mut push = |value: f32| {
+            out[i] = value;
+            i += 1;
+        };
+
+        let settings = state.settings();
+        let self_player = state.self_player();
+        push(state.draw_pile_count() as f32 / DECK_SIZE);
+        push(state.recycle_pile_count() as f32 / DECK_SIZE);
+        push(settings.num_players as f32 / MAX_PLAYERS as f32);
+        push((state.turn_number() as f32 / TURN_SCALE).min(1.0));
+        push((state.actions_taken_this_turn() as f32 / TURN_ACTIONS_SCALE).min(1.0));
+        // Tells a policy how far to trust the `stock_count` features.
+        push(match settings.stock_visibility {
+            StockVisibility::Exact => 0.0,
+            StockVisibility::Binned => 0.5,
+            StockVisibility::Hidden => 1.0,
+        });
+
+        let num_players = settings.num_players.max(1);
+        for seat in 0..MAX_PLAYERS {
+            push(if self_player.0 == seat { 1.0 } else { 0.0 });
+        }
+        // Seat 0 opens and the last seat deals: 1/n for the opener up to 1.0 for the dealer.
+        push((self_player.0 + 1).min(num_players) as f32 / num_players as f32);
+
+        for pile in BuildPileId::ALL {
+            push(state.build_next(pile) as f32 / MAX_CARD_VALUE as f32);
+        }
+
+        let hand_size = settings.hand_size.max(1) as f32;
+        let mut hand = [0usize; CARD_BUCKETS];
+        for card in state.hand() {
+            hand[bucket(*card)] += 1;
+        }
+        for count in hand {
+            push(count as f32 / hand_size);
+        }
+
+        let stock_size = settings.stock_size.max(1) as f32;
+        for offset in 0..MAX_PLAYERS {
+            if offset >= num_players {
+                for _ in 0..SEAT_FEATURES {
+                    push(0.0);
+                }
+                continue;
+            }
+            let player = self_player.offset(offset, num_players);
+            push(1.0);
+            push(state.stock_count(player) as f32 / stock_size);
+            let top = state.stock_top(player).map(bucket);
+            for b in 0..CARD_BUCKETS {
+                push(if top == Some(b) { 1.0 } else { 0.0 });
+            }
+            // The layout is fixed, so a handicap seat's extra pile is left out.
+            for pile in state.discard_piles(player).iter().take(DISCARD_PILE_COUNT) {
+                let top = pile.last();
+                push(top.and_then(Card::value).unwrap_or(0) as f32 / MAX_CARD_VALUE as f32);
+                push(if top.is_some_and(Card::is_skip_bo) {
+                    1.0
+                } else {
+                    0.0
+                });
+                push((pile.len() as f32 / DISCARD_DEPTH_SCALE).min(1.0));
+            }
+            push(state.hand_size(player) as f32 / hand_size);
+        }
+
+        push((settings.stock_size as f32 / STOCK_SIZE_SCALE).min(1.0));
+        push((settings.hand_size as f32 / HAND_SIZE as f32).min(1.0));
+        push(state.discard_piles(self_player).len() as f32 / MAX_DISCARD_PILES as f32);
+        push(if settings.must_play_stock { 1.0 } else { 0.0 });
+
+        debug_assert_eq!(
+            i, STATE_FEATURES,
+            "encoder wrote an unexpected feature count"
+        );
+        debug_assert_eq!(
+            Self::feature_names().len(),
+            STATE_FEATURES,
+            "feature names out of sync with encode"
+        );
+        out
+    }
+
+    /// [`StateEncoder::encode`] with the viewer's hand (`hand.*`) zeroed. The hand sizes
+    /// of the `seat*.hand_size` features stay, as everyone can count them.
+    pub fn encode_public<V: StateView + ?Sized>(state: &V) -> [f32; STATE_FEATURES] {
+        let mut out = Self::encode(state);
+        let hand = GLOBAL_FEATURES + ROLE_FEATURES + BUILD_PILE_COUNT;
+        out[hand..hand + CARD_BUCKETS].fill(0.0);
+        out
+    }
+
+    /// [`StateEncoder::encode`] of `full.view` followed by the opponents' hands
+    /// (`seat1.hand.*` onwards), scaled like the viewer's own hand. Empty seats are zero.
+    pub fn encode_privileged(full: &FullStateView) -> [f32; PRIVILEGED_FEATURES] {
+        let mut out = [0.0; PRIVILEGED_FEATURES];
+        out[..STATE_FEATURES].copy_from_slice(&Self::encode(&full.view));
+        let settings = full.view.settings;
+        let num_players = settings.num_players.max(1);
+        let hand_size = settings.hand_size.max(1) as f32;
+        for offset in 1..num_players.min(MAX_PLAYERS) {
+            let player = full.view.self_player.offset(offset, num_players);
+            let start = STATE_FEATURES + (offset - 1) * CARD_BUCKETS;
+            for card in full.hands.get(player.0).into_iter().flatten() {
+                out[start + bucket(*card)] += 1.0 / hand_size;
+            }
+        }
+        out
+    }
+
+    /// [`StateEncoder::encode_privileged`] of every seat's full view, concatenated in
+    /// seat order relative to `perspective` (`view0` is its own) and zero-padded up to
+    /// [`MAX_PLAYERS`]. Every seat of a table gets the same blocks, rotated.
+    pub fn encode_joint(
+        game: &Game,
+        perspective: PlayerId,
+    ) -> Result<[f32; JOINT_FEATURES], GameError> {
+        let mut out = [0.0; JOINT_FEATURES];
+        let own = game.full_state_view(perspective)?;
+        out[..PRIVILEGED_FEATURES].copy_from_slice(&Self::encode_privileged(&own));
+        let num_players = own.view.settings.num_players;
+        for offset in 1..num_players.min(MAX_PLAYERS) {
+            let full = game.full_state_view(perspective.offset(offset, num_players))?;
+            let start = offset * PRIVILEGED_FEATURES;
+            out[start..start + PRIVILEGED_FEATURES]
+                .copy_from_slice(&Self::encode_privileged(&full));
+        }
+        Ok(out)
+    }
+
+    /// Name of every feature written by [`StateEncoder::encode`], in order.
+    pub fn feature_names() -> Vec<&'static str> {
+        static NAMES: OnceLock<Vec<&'static str>> = OnceLock::new();
+        NAMES.get_or_init(build_names).clone()
+    }
+
+    /// Name of every feature written by [`StateEncoder::encode_privileged`], in order.
+    pub fn privileged_feature_names() -> Vec<&'static str> {
+        static NAMES: OnceLock<Vec<&'static str>> = OnceLock::new();
+        NAMES.get_or_init(build_privileged_names).clone()
+    }
+
+    /// Name of every feature written by [`StateEncoder::encode_joint`], in order, e.g.
+    /// `"view1.seat0.hand.7"`.
+    pub fn joint_feature_names() -> Vec<&'static str> {
+        static NAMES: OnceLock<Vec<&'static str>> = OnceLock::new();
+        NAMES.get_or_init(build_joint_names).clone()
+    }
+
+    /// Position of a named feature, e.g. `"build2.next_value"` or `"hand.7"`.
+    pub fn feature_index(name: &str) -> Option<usize> {
+        Self::feature_names().iter().position(|n| *n == name)
+    }
+}
+
+fn bucket(card: Card) -> usize {
+    match card {
+        Card::SkipBo => 0,
+        Card::Number(v) => v as usize,
+    }
+}
+
+fn bucket_name(bucket: usize) -> String {
+    if bucket == 0 {
+        String::from("skip_bo")
+    } else {
+        bucket.to_string()
+    }
+}
+
+fn build_privileged_names() -> Vec<&'static str> {
+    let mut names = StateEncoder::feature_names();
+    for seat in 1..MAX_PLAYERS {
+        for b in 0..CARD_BUCKETS {
+            names.push(leak(format!("seat{seat}.hand.{}", bucket_name(b))));
+        }
+    }
+    names
+}
+
+fn build_joint_names() -> Vec<&'static str> {
+    let names = StateEncoder::privileged_feature_names();
+    (0..MAX_PLAYERS)
+        .flat_map(|view| {
+            names
+                .iter()
+                .map(move |name| leak(format!("view{view}.{name}")))
+        })
+        .collect()
+}
+
+fn leak(name: String) -> &'static str {
+    Box::leak(name.into_boxed_str())
+}
+
+fn build_names() -> Vec<&'static str> {
+    let mut names: Vec<String> = Vec::with_capacity(STATE_FEATURES);
+    names.push("draw_pile.count".into());
+    names.push("recycle_pile.count".into());
+    names.push("num_players".into());
+    names.push("turn_number".into());
+    names.push("actions_taken_this_turn".into());
+    names.push("stock_visibility".into());
+    for seat in 0..MAX_PLAYERS {
+        names.push(format!("role.seat{seat}"));
+    }
+    names.push("role.after_dealer".into());
+    for pile in 0..BUILD_PILE_COUNT {
+        names.push(format!("build{pile}.next_value"));
+    }
+    for b in 0..CARD_BUCKETS {
+        names.push(format!("hand.{}", bucket_name(b)));
+    }
+    for seat in 0..MAX_PLAYERS {
+        names.push(format!("seat{seat}.present"));
+        names.push(format!("seat{seat}.stock_count"));
+        for b in 0..CARD_BUCKETS {
+            names.push(format!("seat{seat}.stock_top.{}", bucket_name(b)));
+        }
+        for pile in 0..DISCARD_PILE_COUNT {
+            names.push(format!("seat{seat}.discard{pile}.top_value"));
+            names.push(format!("seat{seat}.discard{pile}.top_skip_bo"));
+            names.push(format!("seat{seat}.discard{pile}.depth"));
+        }
+        names.push(format!("seat{seat}.hand_size"));
+    }
+    names.push("settings.stock_size".into());
+    names.push("settings.hand_size".into());
+    names.push("settings.discard_piles".into());
+    names.push("settings.must_play_stock".into());
+    // Leaked once per process; the table lives as long as the program anyway.
+    names.into_iter().map(leak).collect()
+}
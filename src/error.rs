@@ -1,6 +1,6 @@
 use thiserror::Error;
 
-use crate::action::PlayerId;
+use crate::action::{BuildPileId, DiscardPileId, PlayerId};
 
 /// Errors that can occur when manipulating the game state.
 #[derive(Debug, Error)]
@@ -23,9 +23,9 @@ pub enum InvalidAction {
     #[error("hand index {0} is out of range")]
     HandIndex(usize),
     #[error("discard pile index {0} is out of range")]
-    DiscardIndex(usize),
+    DiscardIndex(DiscardPileId),
     #[error("build pile index {0} is out of range")]
-    BuildPileIndex(usize),
+    BuildPileIndex(BuildPileId),
     #[error("no card available in the selected source")]
     NoCardAvailable,
     #[error("card does not match required value {required}")]
@@ -34,4 +34,10 @@ pub enum InvalidAction {
     MustDiscard,
     #[error("player cannot discard because hand is empty")]
     EmptyHand,
+    #[error("the stock card is playable and must be played first")]
+    MustPlayStock,
+    #[error("the play repeats a position of this turn")]
+    RepeatsPosition,
+    #[error("the turn has reached its limit of {limit} actions; discard to end it")]
+    TurnActionLimit { limit: usize },
 }
@@ -0,0 +1,353 @@
+//! Named experiments with a configuration hash and a fixed artifact layout.
+//!
+//! An [`ExperimentConfig`] holds everything that determines a run's results: the engine
+//! and encoder versions, bot specs, seeds and the tool's own settings. Its hash (FNV-1a
+//! over canonical JSON, stable across builds) makes the experiment ID
+//! `<name>-<12 hex digits>`, and the run's outputs live under
+//!
+//! ```text
+//! <root>/<id>/config.json
+//!            /checkpoints/  /reports/  /charts/  /replays/
+//! ```
+//!
+//! Changing any setting changes the ID, so a modified run never writes into the
+//! directory of an earlier one, and [`Experiment::new_artifact`] refuses to replace an
+//! existing file.
+//!
+//! One training run says little about a configuration: two seeds of the same settings
+//! can differ by more than the change being tested. [`RunSummary`] aggregates the epoch
+//! metrics of several runs that differ only in their seed into a mean and standard
+//! deviation per epoch, the spread an improvement has to clear.
+
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::encoder::ENCODER_VERSION;
+use crate::value::EpochMetrics;
+
+/// Version of the engine, recorded in every experiment.
+pub const ENGINE_VERSION: &str = env!("CARGO_PKG_VERSION");
+/// Default root directory of experiments.
+pub const DEFAULT_ROOT: &str = "experiments";
+const CONFIG_FILE: &str = "config.json";
+
+/// Everything that determines an experiment's results.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ExperimentConfig {
+    pub name: String,
+    pub engine_version: String,
+    pub encoder_version: u32,
+    pub bots: Vec<String>,
+    pub seeds: Vec<u64>,
+    /// The tool's own configuration, e.g. a [`MatchConfig`](crate::runner::MatchConfig).
+    pub settings: Value,
+}
+
+impl ExperimentConfig {
+    pub fn new(name: &str, bots: Vec<String>, seeds: Vec<u64>) -> Self {
+        Self {
+            name: name.to_string(),
+            engine_version: ENGINE_VERSION.to_string(),
+            encoder_version: ENCODER_VERSION,
+            bots,
+            seeds,
+            settings: Value::Null,
+        }
+    }
+
+    pub fn with_settings<T: Serialize>(mut self, settings: &T) -> Result<Self, Box<dyn Error>> {
+        self.settings = serde_json::to_value(settings)?;
+        Ok(self)
+    }
+
+    /// Hash of everything but the name.
+    pub fn hash(&self) -> u64 {
+        let mut value = serde_json::to_value(self).expect("experiment configs serialize");
+        if let Value::Object(map) = &mut value {
+            map.remove("name");
+        }
+        fnv1a(canonical(&value).to_string().as_bytes())
+    }
+
+    /// `<name>-<hash>`, with the name reduced to characters that are safe in paths.
+    pub fn id(&self) -> String {
+        let slug: String = self
+            .name
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                    c.to_ascii_lowercase()
+                } else {
+                    '-'
+                }
+            })
+            .collect();
+        let slug = slug.trim_matches('-');
+        let hash = format!("{:012x}", self.hash() >> 16);
+        if slug.is_empty() {
+            hash
+        } else {
+            format!("{slug}-{hash}")
+        }
+    }
+}
+
+/// Object keys sorted at every level, so equal configurations print identically.
+fn canonical(value: &Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort();
+            Value::Object(
+                keys.into_iter()
+                    .map(|key| (key.clone(), canonical(&map[key])))
+                    .collect(),
+            )
+        }
+        Value::Array(items) => Value::Array(items.iter().map(canonical).collect()),
+        other => other.clone(),
+    }
+}
+
+/// 64-bit FNV-1a.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Kinds of experiment output, each with its own subdirectory.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Artifact {
+    Checkpoint,
+    Report,
+    Chart,
+    Replay,
+}
+
+impl Artifact {
+    pub const ALL: [Artifact; 4] = [
+        Artifact::Checkpoint,
+        Artifact::Report,
+        Artifact::Chart,
+        Artifact::Replay,
+    ];
+
+    pub fn dir_name(self) -> &'static str {
+        match self {
+            Artifact::Checkpoint => "checkpoints",
+            Artifact::Report => "reports",
+            Artifact::Chart => "charts",
+            Artifact::Replay => "replays",
+        }
+    }
+}
+
+impl fmt::Display for Artifact {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.dir_name())
+    }
+}
+
+/// An experiment's directory.
+#[derive(Clone, Debug)]
+pub struct Experiment {
+    dir: PathBuf,
+    config: ExperimentConfig,
+}
+
+impl Experiment {
+    /// Create the directory of `config` under `root`, or reopen it when the same
+    /// configuration ran before. Fails when the directory belongs to a different
+    /// configuration.
+    pub fn open(root: &Path, config: ExperimentConfig) -> Result<Self, Box<dyn Error>> {
+        let dir = root.join(config.id());
+        let config_path = dir.join(CONFIG_FILE);
+        if config_path.exists() {
+            let stored: ExperimentConfig =
+                serde_json::from_str(&fs::read_to_string(&config_path)?)?;
+            if stored != config {
+                return Err(format!(
+                    "{} holds a different configuration; refusing to reuse it",
+                    dir.display()
+                )
+                .into());
+            }
+        } else {
+            fs::create_dir_all(&dir)?;
+            fs::write(&config_path, serde_json::to_string_pretty(&config)?)?;
+        }
+        for artifact in Artifact::ALL {
+            fs::create_dir_all(dir.join(artifact.dir_name()))?;
+        }
+        Ok(Self { dir, config })
+    }
+
+    pub fn id(&self) -> String {
+        self.config.id()
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn config(&self) -> &ExperimentConfig {
+        &self.config
+    }
+
+    /// Path of `file` among the experiment's `artifact`s, whether or not it exists.
+    pub fn path(&self, artifact: Artifact, file: &str) -> PathBuf {
+        self.dir.join(artifact.dir_name()).join(file)
+    }
+
+    /// Path for a new `file`; fails when it already exists.
+    pub fn new_artifact(&self, artifact: Artifact, file: &str) -> Result<PathBuf, Box<dyn Error>> {
+        let path = self.path(artifact, file);
+        if path.exists() {
+            return Err(format!(
+                "{} already exists; refusing to overwrite it",
+                path.display()
+            )
+            .into());
+        }
+        Ok(path)
+    }
+
+    /// First free checkpoint path `<stem>-NNNN.<extension>`, counting from 1.
+    pub fn next_checkpoint(&self, stem: &str, extension: &str) -> PathBuf {
+        (1..)
+            .map(|n| self.path(Artifact::Checkpoint, &format!("{stem}-{n:04}.{extension}")))
+            .find(|path| !path.exists())
+            .expect("some checkpoint number is free")
+    }
+}
+
+/// Mean and sample standard deviation of one metric over runs.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Spread {
+    pub mean: f64,
+    /// 0 for a single run.
+    pub stdev: f64,
+    pub runs: usize,
+}
+
+impl Spread {
+    /// Spread of `values`; `None` when there are none.
+    pub fn of(values: impl IntoIterator<Item = f64>) -> Option<Self> {
+        let values: Vec<f64> = values.into_iter().collect();
+        if values.is_empty() {
+            return None;
+        }
+        let runs = values.len();
+        let mean = values.iter().sum::<f64>() / runs as f64;
+        let squares: f64 = values.iter().map(|v| (v - mean) * (v - mean)).sum();
+        let stdev = if runs > 1 {
+            (squares / (runs - 1) as f64).sqrt()
+        } else {
+            0.0
+        };
+        Some(Self { mean, stdev, runs })
+    }
+
+    /// Standard error of the mean.
+    pub fn stderr(&self) -> f64 {
+        self.stdev / (self.runs.max(1) as f64).sqrt()
+    }
+}
+
+/// Metrics of one epoch over the runs that reached it.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct EpochSummary {
+    /// One-based epoch number.
+    pub epoch: usize,
+    pub loss: Spread,
+    pub validation_loss: Spread,
+    /// Over the runs that evaluated the epoch; `None` when none did.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub win_rate: Option<Spread>,
+}
+
+/// Epoch metrics of seeded runs of one configuration; see the [module docs](self).
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RunSummary {
+    /// Seed of each run, in the order the runs were given.
+    pub seeds: Vec<u64>,
+    pub epochs: Vec<EpochSummary>,
+    /// Win rate of each run's last evaluated epoch.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub final_win_rate: Option<Spread>,
+}
+
+impl RunSummary {
+    /// Aggregate `runs`, the epoch metrics of the run with the matching entry of `seeds`.
+    pub fn new(seeds: &[u64], runs: &[Vec<EpochMetrics>]) -> Result<Self, Box<dyn Error>> {
+        if seeds.len() != runs.len() {
+            return Err(format!("{} seeds for {} runs", seeds.len(), runs.len()).into());
+        }
+        let last = runs.iter().flatten().map(|m| m.epoch).max().unwrap_or(0);
+        let epochs = (1..=last)
+            .filter_map(|epoch| {
+                let metrics: Vec<&EpochMetrics> = runs
+                    .iter()
+                    .filter_map(|run| run.iter().find(|m| m.epoch == epoch))
+                    .collect();
+                Some(EpochSummary {
+                    epoch,
+                    loss: Spread::of(metrics.iter().map(|m| m.loss))?,
+                    validation_loss: Spread::of(metrics.iter().map(|m| m.validation_loss))?,
+                    win_rate: Spread::of(metrics.iter().filter_map(|m| m.win_rate)),
+                })
+            })
+            .collect();
+        let final_win_rate = Spread::of(
+            runs.iter()
+                .filter_map(|run| run.iter().rev().find_map(|m| m.win_rate)),
+        );
+        Ok(Self {
+            seeds: seeds.to_vec(),
+            epochs,
+            final_win_rate,
+        })
+    }
+}
+
+impl fmt::Display for RunSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} runs, seeds {:?}", self.seeds.len(), self.seeds)?;
+        writeln!(
+            f,
+            "{:>5} {:>17} {:>17} {:>17}",
+            "epoch", "loss", "validation", "win rate"
+        )?;
+        for epoch in &self.epochs {
+            let win_rate = epoch.win_rate.map_or(String::new(), |rate| {
+                format!("{:>6.1}% ± {:>5.1}%", rate.mean * 100.0, rate.stdev * 100.0)
+            });
+            let line = format!(
+                "{:>5} {:>8.4} ± {:<6.4} {:>8.4} ± {:<6.4} {win_rate:>17}",
+                epoch.epoch,
+                epoch.loss.mean,
+                epoch.loss.stdev,
+                epoch.validation_loss.mean,
+                epoch.validation_loss.stdev
+            );
+            writeln!(f, "{}", line.trim_end())?;
+        }
+        if let Some(rate) = self.final_win_rate {
+            writeln!(
+                f,
+                "Final win rate: {:.1}% ± {:.1}% (stdev), standard error {:.1}% over {} runs",
+                rate.mean * 100.0,
+                rate.stdev * 100.0,
+                rate.stderr() * 100.0,
+                rate.runs
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,106 @@
+//! Feature attribution for the win-probability model: which inputs moved an evaluation.
+//!
+//! [`saliency`] takes the gradient of the [`ValueNet`] logit with respect to each
+//! [`StateEncoder`] feature and multiplies it by the feature's value (gradient times
+//! input). A feature's contribution is then roughly how far it moves the log-odds away
+//! from an all-zero input. Contributions are listed by size under the encoder's feature
+//! names (`build2.next_value`, `hand.7`, ...), so a report shows what the net looks at
+//! in one position:
+//!
+//! ```text
+//! win probability 0.631
+//!   feature                      value  gradient  contribution
+//!   seat0.stock_count            0.233    -2.104        -0.490
+//!   hand.skip_bo                 0.200     1.317         0.263
+//! ```
+//!
+//! The gradient is local: it explains small changes around the position, not what the
+//! net would say with a feature removed outright.
+
+use std::fmt;
+
+use crate::encoder::{STATE_FEATURES, StateEncoder};
+use crate::state::StateView;
+use crate::value::ValueNet;
+
+/// One input feature's share of an evaluation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Attribution {
+    /// Encoder feature name, or `feature<i>` for nets over other inputs.
+    pub name: String,
+    pub value: f32,
+    /// Change of the logit per unit of the feature.
+    pub gradient: f32,
+    /// `gradient * value`.
+    pub contribution: f32,
+}
+
+/// Attribution of one evaluation, largest contribution first.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Saliency {
+    pub win_probability: f32,
+    pub features: Vec<Attribution>,
+}
+
+impl Saliency {
+    /// The `limit` features with the largest contributions, as a text table.
+    pub fn report(&self, limit: usize) -> String {
+        let width = self
+            .features
+            .iter()
+            .take(limit)
+            .map(|f| f.name.len())
+            .max()
+            .unwrap_or(0)
+            .max("feature".len());
+        let mut out = format!("win probability {:.3}\n", self.win_probability);
+        out.push_str(&format!(
+            "  {:<width$}  {:>7}  {:>8}  {:>12}\n",
+            "feature", "value", "gradient", "contribution"
+        ));
+        for feature in self.features.iter().take(limit) {
+            out.push_str(&format!(
+                "  {:<width$}  {:>7.3}  {:>8.3}  {:>12.3}\n",
+                feature.name, feature.value, feature.gradient, feature.contribution
+            ));
+        }
+        out
+    }
+}
+
+impl fmt::Display for Saliency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.report(self.features.len()))
+    }
+}
+
+/// Attribution of `net`'s evaluation of `state`, from its viewing player's perspective.
+pub fn saliency<V: StateView + ?Sized>(net: &ValueNet, state: &V) -> Saliency {
+    saliency_of(net, &StateEncoder::encode(state))
+}
+
+/// Attribution of `net`'s prediction for an encoded input. Features are named after the
+/// [`StateEncoder`] layout when the net has [`STATE_FEATURES`] inputs.
+pub fn saliency_of(net: &ValueNet, features: &[f32]) -> Saliency {
+    let names = (net.inputs == STATE_FEATURES).then(StateEncoder::feature_names);
+    let mut attributions: Vec<Attribution> = net
+        .input_gradient(features)
+        .into_iter()
+        .zip(features)
+        .enumerate()
+        .map(|(i, (gradient, &value))| Attribution {
+            name: match &names {
+                Some(names) => names[i].to_string(),
+                None => format!("feature{i}"),
+            },
+            value,
+            gradient,
+            contribution: gradient * value,
+        })
+        .collect();
+    attributions.sort_by(|a, b| b.contribution.abs().total_cmp(&a.contribution.abs()));
+    Saliency {
+        win_probability: net.predict(features),
+        features: attributions,
+    }
+}
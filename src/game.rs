@@ -1,26 +1,48 @@
 use std::array::from_fn;
+use std::collections::hash_map::DefaultHasher;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+use std::sync::{Arc, OnceLock};
 
 use rand::SeedableRng;
-use rand::rngs::StdRng;
 use rand::seq::SliceRandom;
+use rand_chacha::ChaCha12Rng;
+use serde::{Deserialize, Serialize};
 
-use crate::action::{Action, CardSource, PlayerId};
+use crate::action::{Action, BuildPileId, CardSource, DiscardPileId, LegalSet, PlayerId};
 use crate::card::{
     BUILD_PILE_COUNT, Card, DISCARD_PILE_COUNT, HAND_SIZE, MAX_CARD_VALUE, full_deck,
 };
 use crate::error::{GameError, InvalidAction};
 use crate::state::{
-    BuildPileView, GameSettings, GameStateView, GameStatus, PlayerPublicState, TurnPhase,
+    BuildPileView, FullStateView, GameSettings, GameStateView, GameStatus, PlayerPublicState,
+    StateView, StockVisibility, TurnEvent, TurnPhase,
 };
 
 const DEFAULT_SEED: u64 = 0x5EED_5EED_5EED_5EED;
 
 /// Configuration required to bootstrap a game instance.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub struct GameConfig {
     pub num_players: usize,
     pub seed: u64,
     pub stock_size: Option<usize>,
+    /// Cards a hand is refilled to; the standard five when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hand_size: Option<usize>,
+    /// See [`GameSettings::must_play_stock`].
+    #[serde(default)]
+    pub must_play_stock: bool,
+    /// See [`GameSettings::stock_visibility`].
+    #[serde(default)]
+    pub stock_visibility: StockVisibility,
+    /// See [`GameSettings::max_actions_per_turn`]; the default cap when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_actions_per_turn: Option<usize>,
+    /// What happens when a turn revisits a position.
+    #[serde(default)]
+    pub loop_guard: LoopGuard,
 }
 
 impl GameConfig {
@@ -30,14 +52,130 @@ impl GameConfig {
             num_players,
             seed,
             stock_size: None,
+            hand_size: None,
+            must_play_stock: false,
+            stock_visibility: StockVisibility::Exact,
+            max_actions_per_turn: None,
+            loop_guard: LoopGuard::Off,
         })
     }
 }
 
+/// Per-seat rule changes used as handicaps, e.g. to give a human an edge over a bot.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SeatRules {
+    /// The seat gets a fifth discard pile.
+    pub extra_discard_pile: bool,
+    /// Skip-Bo cards drawn by the seat are swapped with a random other card of the draw
+    /// pile, as long as it holds anything else.
+    pub weak_draws: bool,
+    /// The seat's stock is dealt with exactly this many Skip-Bo cards (0 keeps them out),
+    /// swapped in from or out to the draw pile at random positions.
+    #[serde(default)]
+    pub stock_wilds: Option<usize>,
+}
+
+impl SeatRules {
+    pub fn is_standard(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn discard_pile_count(&self) -> usize {
+        DISCARD_PILE_COUNT + usize::from(self.extra_discard_pile)
+    }
+}
+
+/// `standard`, or the changed rules joined by `+`, e.g. `extra-discard+stock-wilds=0`.
+impl fmt::Display for SeatRules {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts = Vec::new();
+        if self.extra_discard_pile {
+            parts.push("extra-discard".to_string());
+        }
+        if self.weak_draws {
+            parts.push("weak-draws".to_string());
+        }
+        if let Some(wilds) = self.stock_wilds {
+            parts.push(format!("stock-wilds={wilds}"));
+        }
+        if parts.is_empty() {
+            f.write_str("standard")
+        } else {
+            f.write_str(&parts.join("+"))
+        }
+    }
+}
+
+impl FromStr for SeatRules {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut rules = SeatRules::default();
+        for part in s.trim().split('+').map(str::trim) {
+            match part.to_ascii_lowercase().as_str() {
+                "standard" => {}
+                "extra-discard" => rules.extra_discard_pile = true,
+                "weak-draws" => rules.weak_draws = true,
+                other => {
+                    let wilds = other
+                        .strip_prefix("stock-wilds=")
+                        .and_then(|n| n.parse().ok())
+                        .ok_or_else(|| format!("unknown seat rule: {part}"))?;
+                    rules.stock_wilds = Some(wilds);
+                }
+            }
+        }
+        Ok(rules)
+    }
+}
+
+/// How the engine reacts when a play brings the current turn back to a position it has
+/// already been in this turn: the same cards in every stock, hand, discard pile, build
+/// pile, the draw pile and the recycle pile. Standard play never does, but a buggy engine
+/// change or a learning agent exploring odd lines could otherwise spin forever.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "kebab-case")]
+pub enum LoopGuard {
+    /// No detection, as in the standard game.
+    #[default]
+    Off,
+    /// The play that closed the loop becomes illegal from the position it was made in,
+    /// for the rest of the turn.
+    Forbid,
+    /// The turn ends right after the play that closed the loop, without a discard.
+    EndTurn,
+}
+
+impl fmt::Display for LoopGuard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            LoopGuard::Off => "off",
+            LoopGuard::Forbid => "forbid",
+            LoopGuard::EndTurn => "end-turn",
+        })
+    }
+}
+
+impl FromStr for LoopGuard {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "off" => Ok(LoopGuard::Off),
+            "forbid" => Ok(LoopGuard::Forbid),
+            "end-turn" => Ok(LoopGuard::EndTurn),
+            _ => Err(format!(
+                "invalid loop guard: {s} (expected off, forbid or end-turn)"
+            )),
+        }
+    }
+}
+
 /// Builder that enables deterministic deck injection for testing and RL experiments.
 pub struct GameBuilder {
     config: GameConfig,
     deck: Option<Vec<Card>>,
+    seat_rules: Vec<SeatRules>,
 }
 
 impl GameBuilder {
@@ -45,6 +183,7 @@ impl GameBuilder {
         Ok(Self {
             config: GameConfig::new(num_players, DEFAULT_SEED)?,
             deck: None,
+            seat_rules: vec![SeatRules::default(); num_players],
         })
     }
 
@@ -65,13 +204,60 @@ impl GameBuilder {
         self
     }
 
+    /// Override the number of cards hands are refilled to (standard: 5). `build` rejects
+    /// sizes above [`HAND_SIZE`], which actions cannot address.
+    pub fn with_hand_size(mut self, hand_size: usize) -> Self {
+        self.config.hand_size = Some(hand_size);
+        self
+    }
+
+    /// Force every player to play their stock top whenever it fits a build pile.
+    pub fn with_must_play_stock(mut self, must_play_stock: bool) -> Self {
+        self.config.must_play_stock = must_play_stock;
+        self
+    }
+
+    /// Coarsen or hide the stock counts shown in views.
+    pub fn with_stock_visibility(mut self, visibility: StockVisibility) -> Self {
+        self.config.stock_visibility = visibility;
+        self
+    }
+
+    /// Stop plays once a turn has taken `limit` actions; see
+    /// [`GameSettings::max_actions_per_turn`].
+    pub fn with_max_actions_per_turn(mut self, limit: usize) -> Self {
+        self.config.max_actions_per_turn = Some(limit);
+        self
+    }
+
+    /// Detect plays that bring a turn back to an earlier position; see [`LoopGuard`].
+    pub fn with_loop_guard(mut self, loop_guard: LoopGuard) -> Self {
+        self.config.loop_guard = loop_guard;
+        self
+    }
+
+    /// Change the rules for one seat. Seats outside the game are reported by `build`.
+    pub fn with_seat_rules(mut self, seat: PlayerId, rules: SeatRules) -> Self {
+        if seat.0 >= self.seat_rules.len() {
+            self.seat_rules.resize(seat.0 + 1, SeatRules::default());
+        }
+        self.seat_rules[seat] = rules;
+        self
+    }
+
     pub fn build(self) -> Result<Game, GameError> {
         Game::from_builder(self)
     }
 }
 
-/// Core Skip-Bo game engine.
+/// Core Skip-Bo game engine. Serializes to a complete snapshot, including the shuffle RNG,
+/// so a restored game continues exactly as the original would.
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Game {
+    config: GameConfig,
+    /// Whether every card follows from `config.seed`; false once a deck was injected or
+    /// hidden cards were redealt.
+    seeded: bool,
     settings: GameSettings,
     status: GameStatus,
     current_player: PlayerId,
@@ -80,10 +266,87 @@ pub struct Game {
     draw_pile: Vec<Card>,
     recycle_pile: Vec<Card>,
     turn_phase: TurnPhase,
-    rng: StdRng,
+    /// The generator behind `StdRng`, named directly so snapshots can serialize it.
+    rng: ChaCha12Rng,
     // Stalemate detection
     stale_turns: usize,
     played_this_turn: bool,
+    /// Legal actions of the current player, as a list and as a bitset. Every state change
+    /// goes through `&mut self`, which clears it, so it never needs to be keyed on a state
+    /// hash.
+    #[serde(skip)]
+    legal_cache: OnceLock<(Vec<Action>, LegalSet)>,
+    /// Turns completed so far; the current turn's zero-based index.
+    turn_number: usize,
+    /// Actions the current player has taken in the current turn.
+    actions_this_turn: usize,
+    /// Every action applied so far, in order.
+    history: Vec<(PlayerId, Action)>,
+    /// Length of `history` right after each player's most recent action.
+    seen_upto: Vec<usize>,
+    seat_rules: Vec<SeatRules>,
+    /// Engine events of the current turn.
+    #[serde(default)]
+    turn_events: Vec<TurnEvent>,
+    #[serde(default)]
+    deck_stats: DeckStats,
+    /// Hashes of the positions the current turn has passed through, while a
+    /// [`LoopGuard`] is on.
+    #[serde(default)]
+    turn_positions: Vec<u64>,
+    /// Plays [`LoopGuard::Forbid`] has ruled out this turn, by the position they were
+    /// made in.
+    #[serde(default)]
+    forbidden_plays: Vec<(u64, Action)>,
+    /// Loops the guard has broken so far.
+    #[serde(default)]
+    loops_broken: usize,
+}
+
+/// How well the draw pile kept up over a game: reshuffles of the recycle pile, the draw
+/// pile's size at the start of every turn and hand refills that ran out of cards.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeckStats {
+    /// Recycle piles shuffled into an empty draw pile.
+    pub reshuffles: usize,
+    /// Cards those reshuffles returned to the draw pile.
+    pub reshuffled_cards: usize,
+    /// Turns started, each sampling the draw pile before the hand is refilled.
+    pub turns: usize,
+    /// Sum of the sampled draw pile sizes.
+    pub draw_pile_total: u64,
+    /// Smallest sampled draw pile size.
+    pub draw_pile_min: usize,
+    /// Hand refills left short because the draw and recycle piles were both empty.
+    pub starved_refills: usize,
+    /// Cards those refills were short of.
+    pub starved_cards: usize,
+}
+
+impl DeckStats {
+    /// Mean draw pile size at the start of a turn.
+    pub fn avg_draw_pile(&self) -> f64 {
+        if self.turns > 0 {
+            self.draw_pile_total as f64 / self.turns as f64
+        } else {
+            0.0
+        }
+    }
+
+    /// Add the counts of `other`, e.g. another game's.
+    pub fn merge(&mut self, other: &DeckStats) {
+        self.draw_pile_min = match (self.turns, other.turns) {
+            (_, 0) => self.draw_pile_min,
+            (0, _) => other.draw_pile_min,
+            _ => self.draw_pile_min.min(other.draw_pile_min),
+        };
+        self.reshuffles += other.reshuffles;
+        self.reshuffled_cards += other.reshuffled_cards;
+        self.turns += other.turns;
+        self.draw_pile_total += other.draw_pile_total;
+        self.starved_refills += other.starved_refills;
+        self.starved_cards += other.starved_cards;
+    }
 }
 
 impl Game {
@@ -92,7 +355,12 @@ impl Game {
     }
 
     pub fn new(config: GameConfig) -> Result<Self, GameError> {
-        GameBuilder { config, deck: None }.build()
+        GameBuilder {
+            config,
+            deck: None,
+            seat_rules: vec![SeatRules::default(); config.num_players],
+        }
+        .build()
     }
 
     pub fn status(&self) -> GameStatus {
@@ -103,6 +371,22 @@ impl Game {
         self.settings
     }
 
+    /// Configuration the game was built with.
+    pub fn config(&self) -> GameConfig {
+        self.config
+    }
+
+    /// Whether rebuilding from [`Game::config`] and replaying [`Game::history`] reproduces
+    /// this game. False for injected decks and after [`Game::redeal_hidden`].
+    pub fn is_reproducible(&self) -> bool {
+        self.seeded
+    }
+
+    /// Rules in effect for `seat`; standard for seats outside the game.
+    pub fn seat_rules(&self, seat: PlayerId) -> SeatRules {
+        self.seat_rules.get(seat.0).copied().unwrap_or_default()
+    }
+
     pub fn current_player(&self) -> PlayerId {
         self.current_player
     }
@@ -111,8 +395,13 @@ impl Game {
         self.turn_phase
     }
 
+    /// Zero-based index of the current turn, counted over all players.
+    pub fn turn_number(&self) -> usize {
+        self.turn_number
+    }
+
     pub fn state_view(&self, perspective: PlayerId) -> Result<GameStateView, GameError> {
-        if perspective >= self.players.len() {
+        if perspective.0 >= self.players.len() {
             return Err(GameError::InvalidPlayer(perspective));
         }
         let build_piles = from_fn(|idx| self.build_piles[idx].as_view());
@@ -121,12 +410,12 @@ impl Game {
             .iter()
             .enumerate()
             .map(|(idx, player)| PlayerPublicState {
-                id: idx,
-                stock_count: player.stock.len(),
+                id: PlayerId(idx),
+                stock_count: self.visible_stock_count(player),
                 stock_top: player.stock.last().copied(),
-                discard_piles: from_fn(|i| player.discard_piles[i].clone()),
+                discard_piles: player.discard_piles.clone(),
                 hand_size: player.hand.len(),
-                is_current: idx == self.current_player,
+                is_current: idx == self.current_player.0,
                 has_won: player.has_won,
             })
             .collect();
@@ -142,88 +431,159 @@ impl Game {
             build_piles,
             players,
             hand: self.players[perspective].hand.clone(),
+            recent_actions: self.history[self.seen_upto[perspective]..].to_vec(),
+            turn_number: self.turn_number,
+            actions_taken_this_turn: self.actions_this_turn,
+            turn_events: self.turn_events.clone(),
+            forbidden_plays: self.forbidden_plays_for(perspective),
+        })
+    }
+
+    /// Borrowed view of `perspective` that reads the engine's piles in place. It shows
+    /// exactly what [`Game::state_view`] shows, without copying anything.
+    pub fn state_view_ref(&self, perspective: PlayerId) -> Result<GameStateViewRef<'_>, GameError> {
+        if perspective.0 >= self.players.len() {
+            return Err(GameError::InvalidPlayer(perspective));
+        }
+        Ok(GameStateViewRef {
+            game: self,
+            perspective,
+        })
+    }
+
+    /// Stock size of `player` as views show it.
+    fn visible_stock_count(&self, player: &PlayerState) -> usize {
+        if self.status == GameStatus::Ongoing {
+            self.settings
+                .stock_visibility
+                .observe(player.stock.len(), self.settings.stock_size)
+        } else {
+            player.stock.len()
+        }
+    }
+
+    /// The view of `perspective` together with every hidden card, for oracle baselines.
+    pub fn full_state_view(&self, perspective: PlayerId) -> Result<FullStateView, GameError> {
+        Ok(FullStateView {
+            view: self.state_view(perspective)?,
+            hands: self.players.iter().map(|p| p.hand.to_vec()).collect(),
+            stocks: self
+                .players
+                .iter()
+                .map(|p| p.stock.iter().rev().copied().collect())
+                .collect(),
+            draw_pile: self.draw_pile.iter().rev().copied().collect(),
+            recycle_pile: self.recycle_pile.clone(),
         })
     }
 
+    /// Every action applied so far with the player who took it.
+    pub fn history(&self) -> &[(PlayerId, Action)] {
+        &self.history
+    }
+
     pub fn legal_actions(&self, player: PlayerId) -> Result<Vec<Action>, GameError> {
         if self.is_finished() {
             return Ok(Vec::new());
         }
-        if player >= self.players.len() {
+        if player.0 >= self.players.len() {
             return Err(GameError::InvalidPlayer(player));
         }
         if player != self.current_player {
             return Err(GameError::NotPlayersTurn);
         }
-        let player_state = &self.players[player];
-        let mut actions = Vec::new();
-        let required_values: [u8; BUILD_PILE_COUNT] =
-            from_fn(|idx| self.build_piles[idx].next_value());
+        Ok(self.cached_legal().0.clone())
+    }
 
-        for (hand_index, card) in player_state.hand.iter().enumerate() {
-            for (build_index, required) in required_values.iter().enumerate() {
-                if card.matches_value(*required) {
-                    actions.push(Action::Play {
-                        source: CardSource::Hand(hand_index),
-                        build_pile: build_index,
-                    });
-                }
-            }
+    /// Legal actions of `player` as a bitset.
+    pub fn legal_set(&self, player: PlayerId) -> Result<LegalSet, GameError> {
+        if self.is_finished() {
+            return Ok(LegalSet::default());
         }
-
-        if let Some(card) = player_state.stock.last() {
-            for (build_index, required) in required_values.iter().enumerate() {
-                if card.matches_value(*required) {
-                    actions.push(Action::Play {
-                        source: CardSource::Stock,
-                        build_pile: build_index,
-                    });
-                }
-            }
+        if player.0 >= self.players.len() {
+            return Err(GameError::InvalidPlayer(player));
         }
-
-        for discard_index in 0..DISCARD_PILE_COUNT {
-            if let Some(card) = player_state.discard_piles[discard_index].last() {
-                for (build_index, required) in required_values.iter().enumerate() {
-                    if card.matches_value(*required) {
-                        actions.push(Action::Play {
-                            source: CardSource::Discard(discard_index),
-                            build_pile: build_index,
-                        });
-                    }
-                }
-            }
+        if player != self.current_player {
+            return Err(GameError::NotPlayersTurn);
         }
+        Ok(self.cached_legal().1)
+    }
 
-        if !player_state.hand.is_empty() {
-            for discard_index in 0..DISCARD_PILE_COUNT {
-                for hand_index in 0..player_state.hand.len() {
-                    actions.push(Action::Discard {
-                        hand_index,
-                        discard_pile: discard_index,
-                    });
-                }
-            }
-        } else {
-            actions.push(Action::EndTurn);
-        }
+    /// Whether the current player may take `action` now.
+    pub fn is_legal(&self, action: &Action) -> bool {
+        !self.is_finished() && self.cached_legal().1.contains(action)
+    }
 
-        Ok(actions)
+    fn cached_legal(&self) -> &(Vec<Action>, LegalSet) {
+        self.legal_cache.get_or_init(|| {
+            let player_state = &self.players[self.current_player];
+            let required_values: [u8; BUILD_PILE_COUNT] =
+                from_fn(|idx| self.build_piles[idx].next_value());
+            let actions = enumerate_actions(
+                &player_state.hand,
+                player_state.stock.last().copied(),
+                &player_state.discard_piles,
+                required_values,
+                self.settings.must_play_stock,
+                self.actions_this_turn < self.settings.max_actions_per_turn,
+            );
+            let mut actions = actions;
+            if !self.forbidden_plays.is_empty() {
+                let forbidden: Vec<&Action> = self.forbidden_here().collect();
+                actions.retain(|action| !forbidden.contains(&action));
+            }
+            let set = LegalSet::from_actions(&actions);
+            debug_assert_eq!(set.len(), actions.len(), "every legal action has an index");
+            (actions, set)
+        })
     }
 
     pub fn apply_action(&mut self, player: PlayerId, action: Action) -> Result<(), GameError> {
+        self.legal_cache.take();
+        #[cfg(feature = "invariants")]
+        let before = self.clone();
         if self.is_finished() {
             return Err(GameError::GameOver);
         }
-        if player >= self.players.len() {
+        if player.0 >= self.players.len() {
             return Err(GameError::InvalidPlayer(player));
         }
         if player != self.current_player {
             return Err(GameError::NotPlayersTurn);
         }
 
+        let limit = self.settings.max_actions_per_turn;
+        if self.actions_this_turn >= limit {
+            if matches!(action, Action::Play { .. }) {
+                return Err(InvalidAction::TurnActionLimit { limit }.into());
+            }
+        } else if self.settings.must_play_stock
+            && !matches!(
+                action,
+                Action::Play {
+                    source: CardSource::Stock,
+                    ..
+                }
+            )
+            && self.stock_playable()
+        {
+            return Err(InvalidAction::MustPlayStock.into());
+        }
+
+        let record = action.clone();
+        let turn = self.turn_number;
         match action {
-            Action::Play { source, build_pile } => self.play_card(build_pile, source)?,
+            Action::Play { source, build_pile } => {
+                let from = match self.config.loop_guard {
+                    LoopGuard::Off => 0,
+                    _ => self.position_hash(),
+                };
+                if self.forbidden_plays.contains(&(from, record.clone())) {
+                    return Err(InvalidAction::RepeatsPosition.into());
+                }
+                self.play_card(build_pile, source)?;
+                self.guard_loop(from, &record);
+            }
             Action::Discard {
                 hand_index,
                 discard_pile,
@@ -239,9 +599,96 @@ impl Game {
             }
         }
 
+        if self.turn_number == turn {
+            self.actions_this_turn += 1;
+        }
+        self.history.push((player, record));
+        self.seen_upto[player] = self.history.len();
+        #[cfg(feature = "invariants")]
+        self.check_invariants(&before);
         Ok(())
     }
 
+    /// Loops broken by the [`LoopGuard`] so far.
+    pub fn loops_broken(&self) -> usize {
+        self.loops_broken
+    }
+
+    /// The forbidden plays the view of `perspective` shows: those of the current position
+    /// when it is their turn, none otherwise.
+    fn forbidden_plays_for(&self, perspective: PlayerId) -> Vec<Action> {
+        if perspective == self.current_player {
+            self.forbidden_here().cloned().collect()
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// Plays [`LoopGuard::Forbid`] has ruled out in the current position.
+    fn forbidden_here(&self) -> impl Iterator<Item = &Action> {
+        let position = if self.forbidden_plays.is_empty() {
+            0
+        } else {
+            self.position_hash()
+        };
+        self.forbidden_plays
+            .iter()
+            .filter(move |(from, _)| *from == position)
+            .map(|(_, action)| action)
+    }
+
+    /// Hash of every card's place, ignoring counters and the RNG.
+    fn position_hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.current_player.hash(&mut hasher);
+        for player in &self.players {
+            player.stock.hash(&mut hasher);
+            player.hand.hash(&mut hasher);
+            player.discard_piles.hash(&mut hasher);
+        }
+        for pile in &self.build_piles {
+            pile.cards.hash(&mut hasher);
+        }
+        self.draw_pile.hash(&mut hasher);
+        self.recycle_pile.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// After the play `action` from the position hashed `from`, break the loop if the
+    /// turn has been in the new position before.
+    fn guard_loop(&mut self, from: u64, action: &Action) {
+        if self.config.loop_guard == LoopGuard::Off || self.is_finished() {
+            return;
+        }
+        let position = self.position_hash();
+        if !self.turn_positions.contains(&position) {
+            self.turn_positions.push(position);
+            return;
+        }
+        self.loops_broken += 1;
+        match self.config.loop_guard {
+            LoopGuard::Off => {}
+            LoopGuard::Forbid => {
+                // Start over from here, so only a new cycle counts as the next loop.
+                self.forbidden_plays.push((from, action.clone()));
+                self.turn_positions = vec![position];
+            }
+            LoopGuard::EndTurn => self.advance_turn(),
+        }
+    }
+
+    /// Whether the current player's stock top fits a build pile.
+    fn stock_playable(&self) -> bool {
+        self.players[self.current_player]
+            .stock
+            .last()
+            .is_some_and(|card| {
+                self.build_piles
+                    .iter()
+                    .any(|pile| card.matches_value(pile.next_value()))
+            })
+    }
+
     pub fn is_finished(&self) -> bool {
         !matches!(self.status, GameStatus::Ongoing)
     }
@@ -253,9 +700,167 @@ impl Game {
         }
     }
 
+    /// Draw pile statistics of the game so far.
+    pub fn deck_stats(&self) -> DeckStats {
+        self.deck_stats
+    }
+
+    /// Resample every card hidden from `perspective` (opponents' hands, stock cards below
+    /// each top, the draw pile and the recycle pile) and reseed the engine RNG. Pile sizes
+    /// and all visible cards are kept, so the view of `perspective` is unchanged. Used to
+    /// sample determinizations for rollouts.
+    pub fn redeal_hidden(&mut self, perspective: PlayerId, seed: u64) -> Result<(), GameError> {
+        if perspective.0 >= self.players.len() {
+            return Err(GameError::InvalidPlayer(perspective));
+        }
+        self.legal_cache.take();
+        let mut hidden: Vec<Card> = Vec::new();
+        for (id, player) in self.players.iter().enumerate() {
+            if id != perspective.0 {
+                hidden.extend(player.hand.iter());
+            }
+            let below_top = player.stock.len().saturating_sub(1);
+            hidden.extend(&player.stock[..below_top]);
+        }
+        hidden.extend(&self.draw_pile);
+        hidden.extend(&self.recycle_pile);
+
+        self.rng = ChaCha12Rng::seed_from_u64(seed);
+        self.seeded = false;
+        hidden.shuffle(&mut self.rng);
+        let mut deal = |slots: &mut [Card]| {
+            for slot in slots {
+                *slot = hidden.pop().expect("hidden cards are redealt one for one");
+            }
+        };
+        for (id, player) in self.players.iter_mut().enumerate() {
+            if id != perspective.0 {
+                deal(Arc::make_mut(&mut player.hand).as_mut_slice());
+            }
+            let below_top = player.stock.len().saturating_sub(1);
+            deal(&mut player.stock[..below_top]);
+        }
+        deal(&mut self.draw_pile);
+        deal(&mut self.recycle_pile);
+        Ok(())
+    }
+
+    /// Rebuild a game that `view` describes, taking the cards it hides from `hidden`:
+    /// opponents' hands in seat order, each stock below its top, the draw pile and then
+    /// the recycle pile. Stock counts are taken as exact, so callers resolve binned or
+    /// hidden counts first. `hidden` must hold exactly the missing cards. History and
+    /// seat rules other than the discard pile count are not part of a view and start
+    /// empty; the engine RNG is seeded from `seed`.
+    pub fn from_view(
+        view: &GameStateView,
+        hidden: Vec<Card>,
+        seed: u64,
+    ) -> Result<Self, GameError> {
+        let settings = view.settings;
+        if view.players.len() != settings.num_players
+            || view.self_player.0 >= settings.num_players
+            || view.current_player.0 >= settings.num_players
+        {
+            return Err(GameError::InvalidConfiguration(
+                "view does not describe every seat",
+            ));
+        }
+        let mut hidden = hidden.into_iter();
+        let mut deal = |count: usize| -> Result<Vec<Card>, GameError> {
+            let cards: Vec<Card> = hidden.by_ref().take(count).collect();
+            if cards.len() == count {
+                Ok(cards)
+            } else {
+                Err(GameError::InvalidConfiguration(
+                    "too few hidden cards for the view",
+                ))
+            }
+        };
+
+        let mut players = Vec::with_capacity(settings.num_players);
+        let mut seat_rules = Vec::with_capacity(settings.num_players);
+        for (id, public) in view.players.iter().enumerate() {
+            let hand = if id == view.self_player.0 {
+                view.hand.to_vec()
+            } else {
+                deal(public.hand_size)?
+            };
+            let mut stock = match public.stock_top {
+                Some(_) => deal(public.stock_count.saturating_sub(1))?,
+                None => Vec::new(),
+            };
+            stock.extend(public.stock_top);
+            players.push(PlayerState {
+                stock,
+                hand: Arc::new(hand),
+                discard_piles: public.discard_piles.clone(),
+                has_won: public.has_won,
+            });
+            seat_rules.push(SeatRules {
+                extra_discard_pile: public.discard_piles.len() > DISCARD_PILE_COUNT,
+                ..SeatRules::default()
+            });
+        }
+        let draw_pile = deal(view.draw_pile_count)?;
+        let recycle_pile = deal(view.recycle_pile_count)?;
+        if hidden.next().is_some() {
+            return Err(GameError::InvalidConfiguration(
+                "more hidden cards than the view hides",
+            ));
+        }
+
+        Ok(Game {
+            config: GameConfig {
+                num_players: settings.num_players,
+                seed,
+                stock_size: Some(settings.stock_size),
+                hand_size: Some(settings.hand_size),
+                must_play_stock: settings.must_play_stock,
+                stock_visibility: settings.stock_visibility,
+                max_actions_per_turn: Some(settings.max_actions_per_turn),
+                loop_guard: LoopGuard::Off,
+            },
+            seeded: false,
+            settings,
+            status: view.status,
+            current_player: view.current_player,
+            players,
+            build_piles: from_fn(|idx| BuildPile {
+                cards: view.build_piles[idx].cards.clone(),
+            }),
+            draw_pile,
+            recycle_pile,
+            turn_phase: view.phase,
+            rng: ChaCha12Rng::seed_from_u64(seed),
+            stale_turns: 0,
+            // Every action of a turn before the discard that ends it is a play.
+            played_this_turn: view.actions_taken_this_turn > 0,
+            legal_cache: OnceLock::new(),
+            turn_number: view.turn_number,
+            actions_this_turn: view.actions_taken_this_turn,
+            history: Vec::new(),
+            seen_upto: vec![0; settings.num_players],
+            seat_rules,
+            turn_events: view.turn_events.clone(),
+            deck_stats: DeckStats::default(),
+            turn_positions: Vec::new(),
+            forbidden_plays: Vec::new(),
+            loops_broken: 0,
+        })
+    }
+
     fn from_builder(builder: GameBuilder) -> Result<Self, GameError> {
-        let GameBuilder { config, deck } = builder;
+        let GameBuilder {
+            config,
+            deck,
+            seat_rules,
+        } = builder;
         let mut settings = GameSettings::new(config.num_players)?;
+        if seat_rules.len() != settings.num_players {
+            return Err(GameError::InvalidConfiguration(
+                "seat rules given for a seat outside the game",
+            ));
+        }
         if let Some(custom_stock) = config.stock_size {
             if custom_stock == 0 {
                 return Err(GameError::InvalidConfiguration(
@@ -264,7 +869,31 @@ impl Game {
             }
             settings.stock_size = custom_stock;
         }
-        let mut rng = StdRng::seed_from_u64(config.seed);
+        if let Some(hand_size) = config.hand_size {
+            if hand_size == 0 {
+                return Err(GameError::InvalidConfiguration(
+                    "hand size must be positive",
+                ));
+            }
+            if hand_size > HAND_SIZE {
+                return Err(GameError::InvalidConfiguration(
+                    "hand size exceeds the hand slots actions can address",
+                ));
+            }
+            settings.hand_size = hand_size;
+        }
+        if let Some(limit) = config.max_actions_per_turn {
+            if limit == 0 {
+                return Err(GameError::InvalidConfiguration(
+                    "actions per turn must be positive",
+                ));
+            }
+            settings.max_actions_per_turn = limit;
+        }
+        settings.must_play_stock = config.must_play_stock;
+        settings.stock_visibility = config.stock_visibility;
+        let mut rng = ChaCha12Rng::seed_from_u64(config.seed);
+        let deck_from_seed = deck.is_none();
         let mut deck = if let Some(deck) = deck {
             deck
         } else {
@@ -281,20 +910,27 @@ impl Game {
         }
 
         let mut players = Vec::with_capacity(settings.num_players);
-        for _ in 0..settings.num_players {
+        for rules in &seat_rules {
             let mut stock = Vec::with_capacity(settings.stock_size);
             for _ in 0..settings.stock_size {
                 stock.push(deck.pop().ok_or(GameError::InvalidConfiguration(
                     "deck exhausted while dealing stocks",
                 ))?);
             }
-            players.push(PlayerState::new(stock));
+            players.push(PlayerState::new(stock, rules.discard_pile_count()));
+        }
+        for (player, rules) in players.iter_mut().zip(&seat_rules) {
+            if let Some(wilds) = rules.stock_wilds {
+                set_stock_wilds(&mut player.stock, &mut deck, wilds, &mut rng)?;
+            }
         }
 
         let mut game = Game {
+            config,
+            seeded: deck_from_seed,
             settings,
             status: GameStatus::Ongoing,
-            current_player: 0,
+            current_player: PlayerId(0),
             players,
             build_piles: from_fn(|_| BuildPile::new()),
             draw_pile: deck,
@@ -303,6 +939,17 @@ impl Game {
             rng,
             stale_turns: 0,
             played_this_turn: false,
+            legal_cache: OnceLock::new(),
+            turn_number: 0,
+            actions_this_turn: 0,
+            history: Vec::new(),
+            seen_upto: vec![0; settings.num_players],
+            seat_rules,
+            turn_events: Vec::new(),
+            deck_stats: DeckStats::default(),
+            turn_positions: Vec::new(),
+            forbidden_plays: Vec::new(),
+            loops_broken: 0,
         };
 
         game.begin_turn();
@@ -316,13 +963,44 @@ impl Game {
         }
         self.turn_phase = TurnPhase::AwaitingAction;
         self.played_this_turn = false;
+        let stats = &mut self.deck_stats;
+        let pile = self.draw_pile.len();
+        stats.draw_pile_min = if stats.turns == 0 {
+            pile
+        } else {
+            stats.draw_pile_min.min(pile)
+        };
+        stats.turns += 1;
+        stats.draw_pile_total += pile as u64;
+        self.refill_hand();
+        self.forbidden_plays.clear();
+        self.turn_positions.clear();
+        if self.config.loop_guard != LoopGuard::Off {
+            self.turn_positions.push(self.position_hash());
+        }
+    }
+
+    /// Draw the current player back up to the hand size, as far as cards remain.
+    fn refill_hand(&mut self) {
         let current = self.current_player;
         let hand_target = self.settings.hand_size;
+        let mut drawn = 0;
         while self.players[current].hand.len() < hand_target {
             match self.draw_card() {
-                Some(card) => self.players[current].hand.push(card),
-                None => break,
+                Some(card) => Arc::make_mut(&mut self.players[current].hand).push(card),
+                None => {
+                    self.deck_stats.starved_refills += 1;
+                    self.deck_stats.starved_cards += hand_target - self.players[current].hand.len();
+                    break;
+                }
             }
+            drawn += 1;
+        }
+        if drawn > 0 {
+            self.turn_events.push(TurnEvent::Drew {
+                player: current,
+                cards: drawn,
+            });
         }
     }
 
@@ -345,12 +1023,19 @@ impl Game {
             self.turn_phase = TurnPhase::GameOver;
             return;
         }
-        self.current_player = (self.current_player + 1) % self.players.len();
+        self.current_player = PlayerId((self.current_player.0 + 1) % self.players.len());
+        self.turn_number += 1;
+        self.actions_this_turn = 0;
+        self.turn_events.clear();
         self.begin_turn();
     }
 
-    fn play_card(&mut self, build_pile_idx: usize, source: CardSource) -> Result<(), GameError> {
-        if build_pile_idx >= BUILD_PILE_COUNT {
+    fn play_card(
+        &mut self,
+        build_pile_idx: BuildPileId,
+        source: CardSource,
+    ) -> Result<(), GameError> {
+        if build_pile_idx.0 >= BUILD_PILE_COUNT {
             return Err(InvalidAction::BuildPileIndex(build_pile_idx).into());
         }
         if matches!(self.turn_phase, TurnPhase::GameOver) {
@@ -381,6 +1066,9 @@ impl Game {
         if self.build_piles[build_pile_idx].is_complete() {
             let completed = self.build_piles[build_pile_idx].take_cards();
             self.recycle_pile.extend(completed);
+            self.turn_events.push(TurnEvent::PileCompleted {
+                pile: build_pile_idx,
+            });
         }
         if self.players[self.current_player].stock.is_empty() {
             self.players[self.current_player].has_won = true;
@@ -392,43 +1080,54 @@ impl Game {
         // If the player just emptied their hand by playing their last card,
         // immediately draw back up to the hand size and continue the turn.
         if !self.is_finished() && hand_just_emptied {
-            let current = self.current_player;
-            let target = self.settings.hand_size;
-            while self.players[current].hand.len() < target {
-                match self.draw_card() {
-                    Some(next_card) => self.players[current].hand.push(next_card),
-                    None => break,
-                }
-            }
+            self.refill_hand();
         }
         Ok(())
     }
 
-    fn discard_card(&mut self, hand_index: usize, discard_index: usize) -> Result<(), GameError> {
-        if discard_index >= DISCARD_PILE_COUNT {
+    fn discard_card(
+        &mut self,
+        hand_index: usize,
+        discard_index: DiscardPileId,
+    ) -> Result<(), GameError> {
+        let player_state = &mut self.players[self.current_player];
+        if discard_index.0 >= player_state.discard_piles.len() {
             return Err(InvalidAction::DiscardIndex(discard_index).into());
         }
-        let player_state = &mut self.players[self.current_player];
         if hand_index >= player_state.hand.len() {
             return Err(InvalidAction::HandIndex(hand_index).into());
         }
-        let card = player_state.hand.remove(hand_index);
-        player_state.discard_piles[discard_index].push(card);
+        let card = Arc::make_mut(&mut player_state.hand).remove(hand_index);
+        Arc::make_mut(&mut player_state.discard_piles)[discard_index].push(card);
         Ok(())
     }
 
     fn draw_card(&mut self) -> Option<Card> {
-        if let Some(card) = self.draw_pile.pop() {
-            return Some(card);
+        if self.draw_pile.is_empty() {
+            if self.recycle_pile.is_empty() {
+                return None;
+            }
+            self.reshuffle_recycle();
         }
-        if self.recycle_pile.is_empty() {
-            return None;
+        let top = self.draw_pile.len() - 1;
+        if self.seat_rules[self.current_player].weak_draws && self.draw_pile[top].is_skip_bo() {
+            // Swap with a random other card so the Skip-Bo card stays evenly spread.
+            let others: Vec<usize> = (0..top)
+                .filter(|&i| !self.draw_pile[i].is_skip_bo())
+                .collect();
+            if let Some(&other) = others.choose(&mut self.rng) {
+                self.draw_pile.swap(other, top);
+            }
         }
-        self.reshuffle_recycle();
         self.draw_pile.pop()
     }
 
     fn reshuffle_recycle(&mut self) {
+        self.turn_events.push(TurnEvent::Reshuffled {
+            cards: self.recycle_pile.len(),
+        });
+        self.deck_stats.reshuffles += 1;
+        self.deck_stats.reshuffled_cards += self.recycle_pile.len();
         self.recycle_pile.shuffle(&mut self.rng);
         self.draw_pile.append(&mut self.recycle_pile);
     }
@@ -439,7 +1138,7 @@ impl Game {
         self.settings.num_players * 2
     }
 
-    fn peek_source<'a>(player: &'a PlayerState, source: CardSource) -> Result<&'a Card, GameError> {
+    fn peek_source(player: &PlayerState, source: CardSource) -> Result<&Card, GameError> {
         match source {
             CardSource::Hand(index) => player
                 .hand
@@ -449,17 +1148,10 @@ impl Game {
                 .stock
                 .last()
                 .ok_or(InvalidAction::NoCardAvailable.into()),
-            CardSource::Discard(index) => {
-                if index >= DISCARD_PILE_COUNT {
-                    Err(InvalidAction::DiscardIndex(index).into())
-                } else {
-                    player
-                        .discard_piles
-                        .get(index)
-                        .and_then(|pile| pile.last())
-                        .ok_or(InvalidAction::NoCardAvailable.into())
-                }
-            }
+            CardSource::Discard(index) => match player.discard_piles.get(index.0) {
+                Some(pile) => pile.last().ok_or(InvalidAction::NoCardAvailable.into()),
+                None => Err(InvalidAction::DiscardIndex(index).into()),
+            },
         }
     }
 
@@ -469,43 +1161,127 @@ impl Game {
                 if index >= player.hand.len() {
                     return Err(InvalidAction::HandIndex(index).into());
                 }
-                Ok(player.hand.remove(index))
+                Ok(Arc::make_mut(&mut player.hand).remove(index))
             }
             CardSource::Stock => player
                 .stock
                 .pop()
                 .ok_or(InvalidAction::NoCardAvailable.into()),
-            CardSource::Discard(index) => {
-                if index >= DISCARD_PILE_COUNT {
-                    Err(InvalidAction::DiscardIndex(index).into())
-                } else {
-                    player
-                        .discard_piles
-                        .get_mut(index)
-                        .and_then(|pile| pile.pop())
-                        .ok_or(InvalidAction::NoCardAvailable.into())
-                }
-            }
+            CardSource::Discard(index) => match player.discard_piles.get(index.0) {
+                Some(pile) if pile.is_empty() => Err(InvalidAction::NoCardAvailable.into()),
+                Some(_) => Ok(Arc::make_mut(&mut player.discard_piles)[index]
+                    .pop()
+                    .expect("pile checked to be non-empty")),
+                None => Err(InvalidAction::DiscardIndex(index).into()),
+            },
         }
     }
 }
 
-#[derive(Clone)]
+/// A [`Game`] seen by one player, borrowed from the engine; see [`Game::state_view_ref`].
+#[derive(Clone, Copy)]
+pub struct GameStateViewRef<'a> {
+    game: &'a Game,
+    perspective: PlayerId,
+}
+
+impl GameStateViewRef<'_> {
+    /// Copy into an owned view, e.g. to keep it past the next action.
+    pub fn to_owned_view(&self) -> GameStateView {
+        self.game
+            .state_view(self.perspective)
+            .expect("perspective was checked when the view was made")
+    }
+}
+
+impl StateView for GameStateViewRef<'_> {
+    fn settings(&self) -> GameSettings {
+        self.game.settings
+    }
+
+    fn status(&self) -> GameStatus {
+        self.game.status
+    }
+
+    fn self_player(&self) -> PlayerId {
+        self.perspective
+    }
+
+    fn current_player(&self) -> PlayerId {
+        self.game.current_player
+    }
+
+    fn draw_pile_count(&self) -> usize {
+        self.game.draw_pile.len()
+    }
+
+    fn recycle_pile_count(&self) -> usize {
+        self.game.recycle_pile.len()
+    }
+
+    fn turn_number(&self) -> usize {
+        self.game.turn_number
+    }
+
+    fn actions_taken_this_turn(&self) -> usize {
+        self.game.actions_this_turn
+    }
+
+    fn hand(&self) -> &[Card] {
+        &self.game.players[self.perspective].hand
+    }
+
+    fn build_pile(&self, pile: BuildPileId) -> &[Card] {
+        &self.game.build_piles[pile.0].cards
+    }
+
+    fn stock_count(&self, player: PlayerId) -> usize {
+        self.game.visible_stock_count(&self.game.players[player])
+    }
+
+    fn stock_top(&self, player: PlayerId) -> Option<Card> {
+        self.game.players[player].stock.last().copied()
+    }
+
+    fn discard_piles(&self, player: PlayerId) -> &[Vec<Card>] {
+        &self.game.players[player].discard_piles
+    }
+
+    fn hand_size(&self, player: PlayerId) -> usize {
+        self.game.players[player].hand.len()
+    }
+
+    fn recent_actions(&self) -> &[(PlayerId, Action)] {
+        &self.game.history[self.game.seen_upto[self.perspective]..]
+    }
+
+    fn turn_events(&self) -> &[TurnEvent] {
+        &self.game.turn_events
+    }
+
+    fn forbidden_plays(&self) -> Vec<Action> {
+        self.game.forbidden_plays_for(self.perspective)
+    }
+}
+
+/// Hand and discard piles sit behind [`Arc`]s shared with the views handed out; they are
+/// copied on write, and only when a view still holds them.
+#[derive(Clone, Serialize, Deserialize)]
 struct PlayerState {
     stock: Vec<Card>,
-    hand: Vec<Card>,
-    discard_piles: [Vec<Card>; DISCARD_PILE_COUNT],
+    hand: Arc<Vec<Card>>,
+    discard_piles: Arc<Vec<Vec<Card>>>,
     has_won: bool,
 }
 
 impl PlayerState {
-    fn new(mut stock: Vec<Card>) -> Self {
+    fn new(mut stock: Vec<Card>, discard_piles: usize) -> Self {
         // Reveal the top card (no-op in this representation because top is last).
         stock.shrink_to_fit();
         Self {
             stock,
-            hand: Vec::with_capacity(HAND_SIZE),
-            discard_piles: from_fn(|_| Vec::new()),
+            hand: Arc::new(Vec::with_capacity(HAND_SIZE)),
+            discard_piles: Arc::new(vec![Vec::new(); discard_piles]),
             has_won: false,
         }
     }
@@ -513,15 +1289,113 @@ impl PlayerState {
     // Removed obsolete helpers (discard_top/discard_tops/discard_counts) since full discard_piles are public.
 }
 
-#[derive(Clone)]
+/// Swap cards between a freshly dealt stock and the draw pile until the stock holds
+/// exactly `wilds` Skip-Bo cards. Both ends of every swap are picked at random.
+fn set_stock_wilds(
+    stock: &mut [Card],
+    draw_pile: &mut [Card],
+    wilds: usize,
+    rng: &mut ChaCha12Rng,
+) -> Result<(), GameError> {
+    if wilds > stock.len() {
+        return Err(GameError::InvalidConfiguration(
+            "more stock wilds than stock cards",
+        ));
+    }
+    loop {
+        let held = stock.iter().filter(|card| card.is_skip_bo()).count();
+        if held == wilds {
+            return Ok(());
+        }
+        let too_many = held > wilds;
+        let out: Vec<usize> = (0..stock.len())
+            .filter(|&i| stock[i].is_skip_bo() == too_many)
+            .collect();
+        let into: Vec<usize> = (0..draw_pile.len())
+            .filter(|&i| draw_pile[i].is_skip_bo() != too_many)
+            .collect();
+        let (Some(&out), Some(&into)) = (out.choose(rng), into.choose(rng)) else {
+            return Err(GameError::InvalidConfiguration(
+                "draw pile cannot supply the requested stock wilds",
+            ));
+        };
+        std::mem::swap(&mut stock[out], &mut draw_pile[into]);
+    }
+}
+
+#[cfg(feature = "invariants")]
+impl Game {
+    /// Engine invariants, checked after every successful [`Game::apply_action`] when the
+    /// `invariants` feature is enabled. `before` is the game before the action. Panics on
+    /// the first violation so a rule bug surfaces at the move that caused it.
+    fn check_invariants(&self, before: &Game) {
+        let cards = |game: &Game| {
+            let held: usize = game
+                .players
+                .iter()
+                .map(|p| {
+                    p.hand.len()
+                        + p.stock.len()
+                        + p.discard_piles.iter().map(Vec::len).sum::<usize>()
+                })
+                .sum();
+            let built: usize = game.build_piles.iter().map(|pile| pile.cards.len()).sum();
+            held + built + game.draw_pile.len() + game.recycle_pile.len()
+        };
+        assert_eq!(cards(self), cards(before), "cards were created or lost");
+
+        for (pile, build) in self.build_piles.iter().enumerate() {
+            assert!(
+                build.cards.len() < MAX_CARD_VALUE as usize,
+                "build pile {pile} was not cleared when complete"
+            );
+            for (depth, card) in build.cards.iter().enumerate() {
+                assert!(
+                    card.matches_value(depth as u8 + 1),
+                    "build pile {pile} holds {card:?} at position {}",
+                    depth + 1
+                );
+            }
+        }
+
+        for (id, (now, was)) in self.players.iter().zip(&before.players).enumerate() {
+            assert!(
+                now.hand.len() <= self.settings.hand_size,
+                "player {id} holds {} cards",
+                now.hand.len()
+            );
+            assert!(
+                now.stock.len() <= was.stock.len()
+                    && was.stock.len() - now.stock.len() <= 1
+                    && was.stock.starts_with(&now.stock),
+                "player {id}'s stock changed other than by playing its top"
+            );
+            for (pile, (now, was)) in now
+                .discard_piles
+                .iter()
+                .zip(was.discard_piles.iter())
+                .enumerate()
+            {
+                let kept = now.len().min(was.len());
+                assert!(
+                    now.len().abs_diff(was.len()) <= 1 && now[..kept] == was[..kept],
+                    "player {id}'s discard pile {pile} changed below its top"
+                );
+            }
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
 struct BuildPile {
-    cards: Vec<Card>,
+    /// Shared with views like the player piles; see [`PlayerState`].
+    cards: Arc<Vec<Card>>,
 }
 
 impl BuildPile {
     fn new() -> Self {
         Self {
-            cards: Vec::with_capacity(MAX_CARD_VALUE as usize),
+            cards: Arc::new(Vec::with_capacity(MAX_CARD_VALUE as usize)),
         }
     }
 
@@ -530,7 +1404,7 @@ impl BuildPile {
     }
 
     fn push(&mut self, card: Card) {
-        self.cards.push(card);
+        Arc::make_mut(&mut self.cards).push(card);
     }
 
     fn is_complete(&self) -> bool {
@@ -538,7 +1412,7 @@ impl BuildPile {
     }
 
     fn take_cards(&mut self) -> Vec<Card> {
-        std::mem::take(&mut self.cards)
+        std::mem::take(Arc::make_mut(&mut self.cards))
     }
 
     fn as_view(&self) -> BuildPileView {
@@ -548,3 +1422,82 @@ impl BuildPile {
         }
     }
 }
+
+/// Legal actions for a player holding `hand` with the given stock top and discard piles,
+/// facing build piles that require `required_values`; only discards (or ending the turn)
+/// unless `may_play`. Shared by [`Game::legal_actions`]
+/// and [`GameStateView::legal_actions`] so both always agree.
+pub(crate) fn enumerate_actions(
+    hand: &[Card],
+    stock_top: Option<Card>,
+    discard_piles: &[Vec<Card>],
+    required_values: [u8; BUILD_PILE_COUNT],
+    must_play_stock: bool,
+    may_play: bool,
+) -> Vec<Action> {
+    let mut actions = Vec::new();
+    for (hand_index, card) in hand.iter().enumerate() {
+        for (build_index, required) in required_values.iter().enumerate() {
+            if card.matches_value(*required) {
+                actions.push(Action::Play {
+                    source: CardSource::Hand(hand_index),
+                    build_pile: BuildPileId(build_index),
+                });
+            }
+        }
+    }
+
+    if let Some(card) = stock_top {
+        for (build_index, required) in required_values.iter().enumerate() {
+            if card.matches_value(*required) {
+                actions.push(Action::Play {
+                    source: CardSource::Stock,
+                    build_pile: BuildPileId(build_index),
+                });
+            }
+        }
+    }
+
+    for (discard_index, pile) in discard_piles.iter().enumerate() {
+        if let Some(card) = pile.last() {
+            for (build_index, required) in required_values.iter().enumerate() {
+                if card.matches_value(*required) {
+                    actions.push(Action::Play {
+                        source: CardSource::Discard(DiscardPileId(discard_index)),
+                        build_pile: BuildPileId(build_index),
+                    });
+                }
+            }
+        }
+    }
+
+    if !may_play {
+        actions.clear();
+    }
+    if !hand.is_empty() {
+        for discard_pile in (0..discard_piles.len()).map(DiscardPileId) {
+            for hand_index in 0..hand.len() {
+                actions.push(Action::Discard {
+                    hand_index,
+                    discard_pile,
+                });
+            }
+        }
+    } else {
+        actions.push(Action::EndTurn);
+    }
+
+    let is_stock_play = |action: &Action| {
+        matches!(
+            action,
+            Action::Play {
+                source: CardSource::Stock,
+                ..
+            }
+        )
+    };
+    if must_play_stock && actions.iter().any(is_stock_play) {
+        actions.retain(is_stock_play);
+    }
+    actions
+}
@@ -0,0 +1,127 @@
+//! Cooperative cancellation for matches and searches.
+//!
+//! An [`Interrupter`] is a cheap, cloneable token shared by everything working on one
+//! run. Cancelling it (from another thread, or on Ctrl-C via [`Interrupter::ctrl_c`])
+//! stops the run cleanly: search bots return the best action found so far, the game in
+//! progress is abandoned with an [`Interrupted`] error, and batch runners keep every game
+//! finished before it. A token may also carry a deadline, which is how the match runner
+//! hands bots a per-decision time budget; deadlines only cut searches short and never
+//! abandon a game.
+
+use std::fmt;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+/// Set by the SIGINT handler [`Interrupter::ctrl_c`] installs.
+static CTRL_C: AtomicBool = AtomicBool::new(false);
+
+/// Nodes a search expands between two polls of its interrupter.
+pub const POLL_INTERVAL: usize = 256;
+
+/// Cancellation flag plus an optional deadline.
+#[derive(Clone, Debug, Default)]
+pub struct Interrupter {
+    cancelled: Arc<AtomicBool>,
+    watch_ctrl_c: bool,
+    deadline: Option<Instant>,
+}
+
+impl Interrupter {
+    /// A token that only fires when cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A token that also fires on Ctrl-C. Installs the handler on first use; a second
+    /// Ctrl-C exits the process immediately, for runs that do not stop on their own.
+    pub fn ctrl_c() -> Self {
+        signal::install();
+        Self {
+            watch_ctrl_c: true,
+            ..Self::default()
+        }
+    }
+
+    /// Cancel this token and every clone of it.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+            || (self.watch_ctrl_c && CTRL_C.load(Ordering::Relaxed))
+    }
+
+    /// A clone that also fires at `deadline`.
+    pub fn with_deadline(&self, deadline: Instant) -> Self {
+        Self {
+            deadline: Some(deadline),
+            ..self.clone()
+        }
+    }
+
+    /// A clone that also fires `budget` from now.
+    pub fn with_budget(&self, budget: Duration) -> Self {
+        self.with_deadline(Instant::now() + budget)
+    }
+
+    /// Whether work should stop: the token was cancelled or its deadline passed.
+    pub fn should_stop(&self) -> bool {
+        self.is_cancelled()
+            || self
+                .deadline
+                .is_some_and(|deadline| Instant::now() >= deadline)
+    }
+
+    /// [`Err(Interrupted)`](Interrupted) once cancelled.
+    pub fn check(&self) -> Result<(), Interrupted> {
+        if self.is_cancelled() {
+            Err(Interrupted)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// A run stopped because its [`Interrupter`] was cancelled.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Interrupted;
+
+impl fmt::Display for Interrupted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("interrupted")
+    }
+}
+
+impl std::error::Error for Interrupted {}
+
+#[cfg(unix)]
+mod signal {
+    use std::sync::Once;
+    use std::sync::atomic::Ordering;
+
+    use super::CTRL_C;
+
+    extern "C" fn on_sigint(_: libc::c_int) {
+        if CTRL_C.swap(true, Ordering::Relaxed) {
+            // Only async-signal-safe calls here.
+            unsafe { libc::_exit(130) };
+        }
+    }
+
+    pub(super) fn install() {
+        static INSTALL: Once = Once::new();
+        INSTALL.call_once(|| {
+            let handler = on_sigint as extern "C" fn(libc::c_int);
+            // SAFETY: the handler only touches an atomic and calls `_exit`.
+            unsafe { libc::signal(libc::SIGINT, handler as libc::sighandler_t) };
+        });
+    }
+}
+
+#[cfg(not(unix))]
+mod signal {
+    /// Ctrl-C keeps its default behaviour (exiting) on other platforms.
+    pub(super) fn install() {}
+}
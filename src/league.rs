@@ -0,0 +1,721 @@
+//! Multi-player leagues for pools larger than one table.
+//!
+//! [`schedule_tables`] deals a pool of bots into 3- to 6-seat tables so that every bot
+//! plays about as many games, sits in every seat about equally often and meets every
+//! other bot about equally often. Tables are filled greedily: the bots with the fewest
+//! games first, preferring opponents they have met least, then seated by the permutation
+//! that evens out their seat counts. [`ScheduleBalance`] measures how even the result is.
+//!
+//! [`League`] plays the schedule and rates the pool with a Plackett-Luce model, which
+//! treats a table's result as bots being picked one by one, each with probability
+//! proportional to its strength among those still unpicked. [`Attribution`] decides how
+//! much of the result counts: only the winner, or the whole finishing order by stock
+//! left. Strengths are fit with Hunter's MM algorithm, with one virtual win and loss
+//! against a reference bot of strength 1 so bots without wins still get finite ratings.
+//! Ratings are reported on the Elo scale (`400 * log10(strength)`), with standard errors
+//! from the Fisher information of the fit.
+//!
+//! Balanced schedules spend as many games on settled pairings as on open ones. With
+//! [`Matchmaking::Active`] the league refits the ratings after every round and deals
+//! tables with [`Scheduler::next_active`]: uncertain bots whose ratings still overlap,
+//! against opponents close enough for the result to tell them apart. With
+//! [`LeagueConfig::target_error`] it stops as soon as every open rating is that precise,
+//! which for pools of 20+ bots takes far fewer games than a full rotation.
+
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Instant;
+
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+
+use crate::bots::label_for_spec;
+use crate::interrupt::{Interrupted, Interrupter};
+use crate::reload::WeightReloader;
+use crate::runner::{BotMetrics, GameResult, MatchConfig, MatchReport, MatchRunner, mix_seed};
+
+/// One scheduled game: the pool index of the bot at each seat.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Table {
+    pub seats: Vec<usize>,
+}
+
+/// Deal `tables` tables of `table_size` seats from a pool of `bots` bots.
+pub fn schedule_tables(
+    bots: usize,
+    table_size: usize,
+    tables: usize,
+    seed: u64,
+) -> Result<Vec<Table>, Box<dyn Error>> {
+    let mut scheduler = Scheduler::new(bots, table_size, seed)?;
+    Ok((0..tables).map(|_| scheduler.next_balanced()).collect())
+}
+
+/// Deals tables one at a time, tracking the balance of everything dealt so far.
+#[derive(Clone, Debug)]
+pub struct Scheduler {
+    table_size: usize,
+    rng: StdRng,
+    balance: ScheduleBalance,
+    seatings: Vec<Vec<usize>>,
+}
+
+impl Scheduler {
+    pub fn new(bots: usize, table_size: usize, seed: u64) -> Result<Self, Box<dyn Error>> {
+        if !(2..=6).contains(&table_size) {
+            return Err(format!("tables seat 2 to 6 players, not {table_size}").into());
+        }
+        if bots < table_size {
+            return Err(format!("{table_size}-seat tables need at least {table_size} bots").into());
+        }
+        Ok(Self {
+            table_size,
+            rng: StdRng::seed_from_u64(seed),
+            balance: ScheduleBalance::new(bots, table_size),
+            seatings: permutations(table_size),
+        })
+    }
+
+    pub fn balance(&self) -> &ScheduleBalance {
+        &self.balance
+    }
+
+    /// The bots with the fewest games, preferring opponents they have met least.
+    pub fn next_balanced(&mut self) -> Table {
+        let mut candidates = self.candidates();
+        let mut members: Vec<usize> = Vec::with_capacity(self.table_size);
+        while members.len() < self.table_size {
+            let balance = &self.balance;
+            let (position, _) = candidates
+                .iter()
+                .enumerate()
+                .min_by_key(|&(_, &bot)| (balance.games[bot], balance.met(bot, &members)))
+                .expect("enough candidates remain");
+            members.push(candidates.swap_remove(position));
+        }
+        self.seat(&members)
+    }
+
+    /// The table whose result is expected to settle the leaderboard most, dealt greedily
+    /// from the best pair. `ratings` and `errors` are Elo ratings and their standard
+    /// errors by pool index. A bot's expected variance reduction counts as much as its
+    /// rating still overlaps others, so bots far from the rest (a bot that never wins,
+    /// however uncertain its rating) are left alone.
+    pub fn next_active(&mut self, ratings: &[f64], errors: &[f64]) -> Table {
+        let candidates = self.candidates();
+        let contention: Vec<f64> = (0..ratings.len())
+            .map(|bot| {
+                (0..ratings.len())
+                    .filter(|&other| other != bot)
+                    .map(|other| (-0.5 * separation(ratings, errors, bot, other).powi(2)).exp())
+                    .sum()
+            })
+            .collect();
+        let gain = |members: &[usize]| -> f64 {
+            members
+                .iter()
+                .zip(expected_information(ratings, members))
+                .map(|(&bot, information)| {
+                    let variance = errors[bot] * errors[bot];
+                    contention[bot] * (variance - 1.0 / (1.0 / variance + information))
+                })
+                .sum()
+        };
+        let balance = &self.balance;
+        let fewer_games = |a: &[usize], b: &[usize]| {
+            let games =
+                |members: &[usize]| members.iter().map(|&m| balance.games[m]).sum::<usize>();
+            games(b).cmp(&games(a))
+        };
+        let mut members = candidates
+            .iter()
+            .enumerate()
+            .flat_map(|(i, &a)| candidates[i + 1..].iter().map(move |&b| vec![a, b]))
+            .max_by(|a, b| gain(a).total_cmp(&gain(b)).then_with(|| fewer_games(a, b)))
+            .expect("the pool has a pair");
+        while members.len() < self.table_size {
+            let extend = |bot: usize| [members.as_slice(), &[bot]].concat();
+            let next = candidates
+                .iter()
+                .filter(|bot| !members.contains(bot))
+                .map(|&bot| extend(bot))
+                .max_by(|a, b| gain(a).total_cmp(&gain(b)).then_with(|| fewer_games(a, b)))
+                .expect("enough candidates remain");
+            members = next;
+        }
+        self.seat(&members)
+    }
+
+    /// The pool in random order, so ties are broken at random instead of by pool index.
+    fn candidates(&mut self) -> Vec<usize> {
+        let mut candidates: Vec<usize> = (0..self.balance.games.len()).collect();
+        candidates.shuffle(&mut self.rng);
+        candidates
+    }
+
+    /// Seat `members` by the permutation that evens out their seat counts.
+    fn seat(&mut self, members: &[usize]) -> Table {
+        let balance = &self.balance;
+        let seating = self
+            .seatings
+            .iter()
+            .min_by_key(|seating| {
+                let counts = seating
+                    .iter()
+                    .enumerate()
+                    .map(|(seat, &member)| balance.seats[members[member]][seat]);
+                (counts.clone().sum::<usize>(), counts.max())
+            })
+            .expect("every table has a seating");
+        let table = Table {
+            seats: seating.iter().map(|&member| members[member]).collect(),
+        };
+        self.balance.add(&table);
+        table
+    }
+}
+
+/// Every ordering of `0..n`.
+fn permutations(n: usize) -> Vec<Vec<usize>> {
+    let mut all = vec![Vec::new()];
+    for next in 0..n {
+        all = all
+            .into_iter()
+            .flat_map(|partial: Vec<usize>| {
+                (0..=partial.len()).map(move |at| {
+                    let mut extended = partial.clone();
+                    extended.insert(at, next);
+                    extended
+                })
+            })
+            .collect();
+    }
+    all
+}
+
+/// How evenly a schedule spreads games, seats and opponents over the pool.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScheduleBalance {
+    /// Games per bot.
+    pub games: Vec<usize>,
+    /// Games per bot and seat.
+    pub seats: Vec<Vec<usize>>,
+    /// Games each pair of bots shared a table.
+    pub pairs: Vec<Vec<usize>>,
+}
+
+impl ScheduleBalance {
+    fn new(bots: usize, table_size: usize) -> Self {
+        Self {
+            games: vec![0; bots],
+            seats: vec![vec![0; table_size]; bots],
+            pairs: vec![vec![0; bots]; bots],
+        }
+    }
+
+    pub fn of(schedule: &[Table], bots: usize, table_size: usize) -> Self {
+        let mut balance = Self::new(bots, table_size);
+        for table in schedule {
+            balance.add(table);
+        }
+        balance
+    }
+
+    /// Tables `bot` has shared with `others`, summed.
+    fn met(&self, bot: usize, others: &[usize]) -> usize {
+        others.iter().map(|&other| self.pairs[bot][other]).sum()
+    }
+
+    fn add(&mut self, table: &Table) {
+        for (seat, &bot) in table.seats.iter().enumerate() {
+            self.games[bot] += 1;
+            self.seats[bot][seat] += 1;
+            for &other in &table.seats {
+                if other != bot {
+                    self.pairs[bot][other] += 1;
+                }
+            }
+        }
+    }
+
+    /// Most games minus fewest games over the pool.
+    pub fn game_spread(&self) -> usize {
+        spread(self.games.iter().copied())
+    }
+
+    /// Largest gap between a bot's most and least played seat.
+    pub fn seat_spread(&self) -> usize {
+        self.seats
+            .iter()
+            .map(|seats| spread(seats.iter().copied()))
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Most shared tables minus fewest over every pair of bots.
+    pub fn pair_spread(&self) -> usize {
+        spread(
+            self.pairs
+                .iter()
+                .enumerate()
+                .flat_map(|(a, row)| row.iter().skip(a + 1).copied()),
+        )
+    }
+}
+
+fn spread(values: impl Iterator<Item = usize>) -> usize {
+    let (min, max) = values.fold((usize::MAX, 0), |(min, max), v| (min.min(v), max.max(v)));
+    max.saturating_sub(min)
+}
+
+/// Which part of a table's result feeds the ratings.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Attribution {
+    /// The winner beat everyone else at the table; the others are not ranked.
+    #[default]
+    Winner,
+    /// The winner, then the others by fewest stock cards left, up to the first tie.
+    Finish,
+}
+
+impl fmt::Display for Attribution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Attribution::Winner => "winner",
+            Attribution::Finish => "finish",
+        })
+    }
+}
+
+impl FromStr for Attribution {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "winner" => Ok(Attribution::Winner),
+            "finish" => Ok(Attribution::Finish),
+            _ => Err(format!(
+                "invalid attribution: {s} (expected winner or finish)"
+            )),
+        }
+    }
+}
+
+/// A table's result as a ranking: `order` lists the first finishers, best first; the
+/// rest of `players` are unranked among themselves.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Ranking {
+    pub players: Vec<usize>,
+    pub order: Vec<usize>,
+}
+
+impl Ranking {
+    /// Ranking of the pool indices at `seats` from a game result. Games without a winner
+    /// rank nobody.
+    pub fn from_result(result: &GameResult, seats: &[usize], attribution: Attribution) -> Self {
+        let mut order = Vec::new();
+        if let Some(winner) = result.winner {
+            order.push(seats[winner.0]);
+            if attribution == Attribution::Finish {
+                let mut rest: Vec<(usize, usize)> = result
+                    .scores
+                    .iter()
+                    .filter(|score| score.player != winner)
+                    .map(|score| (score.stock_left, seats[score.player.0]))
+                    .collect();
+                rest.sort_unstable();
+                for (i, &(stock, bot)) in rest.iter().enumerate() {
+                    let tied = |other: Option<&(usize, usize)>| other.is_some_and(|o| o.0 == stock);
+                    if tied(i.checked_sub(1).and_then(|j| rest.get(j))) || tied(rest.get(i + 1)) {
+                        break;
+                    }
+                    order.push(bot);
+                }
+            }
+        }
+        // Picking the last remaining player carries no information.
+        order.truncate(seats.len() - 1);
+        Self {
+            players: seats.to_vec(),
+            order,
+        }
+    }
+}
+
+/// Plackett-Luce strengths of `players` bots from `rankings`; see the module docs.
+pub fn fit_plackett_luce(rankings: &[Ranking], players: usize, iterations: usize) -> Vec<f64> {
+    let mut strength = vec![1.0f64; players];
+    let mut wins = vec![1.0f64; players];
+    for ranking in rankings {
+        for &bot in &ranking.order {
+            wins[bot] += 1.0;
+        }
+    }
+    for _ in 0..iterations {
+        // Two virtual games against the reference bot of strength 1.
+        let mut denominators: Vec<f64> = strength.iter().map(|s| 2.0 / (s + 1.0)).collect();
+        for ranking in rankings {
+            let mut remaining: f64 = ranking.players.iter().map(|&bot| strength[bot]).sum();
+            let mut picked: Vec<usize> = Vec::with_capacity(ranking.order.len());
+            for &chosen in &ranking.order {
+                for &bot in ranking.players.iter().filter(|bot| !picked.contains(bot)) {
+                    denominators[bot] += 1.0 / remaining;
+                }
+                remaining -= strength[chosen];
+                picked.push(chosen);
+            }
+        }
+        for ((s, w), d) in strength.iter_mut().zip(&wins).zip(&denominators) {
+            *s = w / d;
+        }
+    }
+    strength
+}
+
+/// Standard errors of the natural-log strengths from [`fit_plackett_luce`], from the
+/// diagonal of the Fisher information (virtual games included).
+pub fn plackett_luce_errors(rankings: &[Ranking], strengths: &[f64]) -> Vec<f64> {
+    let mut information: Vec<f64> = strengths
+        .iter()
+        .map(|s| 2.0 * s / (s + 1.0).powi(2))
+        .collect();
+    for ranking in rankings {
+        let mut remaining: f64 = ranking.players.iter().map(|&bot| strengths[bot]).sum();
+        let mut picked: Vec<usize> = Vec::with_capacity(ranking.order.len());
+        for &chosen in &ranking.order {
+            for &bot in ranking.players.iter().filter(|bot| !picked.contains(bot)) {
+                let p = strengths[bot] / remaining;
+                information[bot] += p * (1.0 - p);
+            }
+            remaining -= strengths[chosen];
+            picked.push(chosen);
+        }
+    }
+    information.iter().map(|info| 1.0 / info.sqrt()).collect()
+}
+
+/// Elo points per unit of natural-log strength.
+const ELO_PER_LOG: f64 = 400.0 / std::f64::consts::LN_10;
+
+/// Elo ratings and their standard errors from `rankings`.
+pub fn elo_ratings(rankings: &[Ranking], players: usize) -> (Vec<f64>, Vec<f64>) {
+    let strengths = fit_plackett_luce(rankings, players, 200);
+    let errors = plackett_luce_errors(rankings, &strengths);
+    (
+        strengths.iter().map(|s| ELO_PER_LOG * s.ln()).collect(),
+        errors.iter().map(|e| ELO_PER_LOG * e).collect(),
+    )
+}
+
+/// Fisher information (per squared Elo point) each of `members` is expected to gain from
+/// one game at a table, counting only who wins: `p * (1 - p)` for win probability `p`.
+pub fn expected_information(ratings: &[f64], members: &[usize]) -> Vec<f64> {
+    let top = members
+        .iter()
+        .map(|&m| ratings[m])
+        .fold(f64::NEG_INFINITY, f64::max);
+    let strengths: Vec<f64> = members
+        .iter()
+        .map(|&m| ((ratings[m] - top) / ELO_PER_LOG).exp())
+        .collect();
+    let total: f64 = strengths.iter().sum();
+    strengths
+        .iter()
+        .map(|s| {
+            let p = s / total;
+            p * (1.0 - p) / (ELO_PER_LOG * ELO_PER_LOG)
+        })
+        .collect()
+}
+
+/// Rating gap between two bots in standard errors of the gap.
+fn separation(ratings: &[f64], errors: &[f64], a: usize, b: usize) -> f64 {
+    (ratings[a] - ratings[b]).abs() / errors[a].hypot(errors[b])
+}
+
+/// Separation (in standard errors) beyond which two bots' order counts as settled.
+const SETTLED_SEPARATION: f64 = 3.0;
+
+/// The largest standard error among bots whose order is not yet settled against every
+/// other bot; 0 once the whole leaderboard is.
+fn open_error(ratings: &[f64], errors: &[f64]) -> f64 {
+    (0..ratings.len())
+        .filter(|&bot| {
+            (0..ratings.len()).any(|other| {
+                other != bot && separation(ratings, errors, bot, other) < SETTLED_SEPARATION
+            })
+        })
+        .map(|bot| errors[bot])
+        .fold(0.0, f64::max)
+}
+
+/// How a league picks its tables.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Matchmaking {
+    /// [`Scheduler::next_balanced`]: even games, seats and opponents.
+    #[default]
+    Balanced,
+    /// [`Scheduler::next_active`]: the most informative tables under the current ratings,
+    /// refit after every round (one table per `table_size` bots of the pool).
+    Active,
+}
+
+impl fmt::Display for Matchmaking {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Matchmaking::Balanced => "balanced",
+            Matchmaking::Active => "active",
+        })
+    }
+}
+
+impl FromStr for Matchmaking {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "balanced" => Ok(Matchmaking::Balanced),
+            "active" => Ok(Matchmaking::Active),
+            _ => Err(format!(
+                "invalid matchmaking: {s} (expected balanced or active)"
+            )),
+        }
+    }
+}
+
+/// Settings of a league.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LeagueConfig {
+    /// Bot specs of the pool.
+    pub bots: Vec<String>,
+    pub table_size: usize,
+    pub tables: usize,
+    pub seed: u64,
+    pub max_turns: usize,
+    pub stock_size: Option<usize>,
+    #[serde(default)]
+    pub attribution: Attribution,
+    #[serde(default)]
+    pub matchmaking: Matchmaking,
+    /// Stop before `tables` once every rating's standard error (Elo) is at most this,
+    /// ignoring bots already more than three standard errors from every other bot.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_error: Option<f64>,
+}
+
+impl LeagueConfig {
+    pub fn new(bots: Vec<String>, table_size: usize, tables: usize, seed: u64) -> Self {
+        Self {
+            bots,
+            table_size,
+            tables,
+            seed,
+            max_turns: 2000,
+            stock_size: None,
+            attribution: Attribution::default(),
+            matchmaking: Matchmaking::default(),
+            target_error: None,
+        }
+    }
+}
+
+/// Rating of one bot of the pool.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BotRating {
+    pub label: String,
+    /// Elo-scale rating; 0 is the reference bot.
+    pub rating: f64,
+    /// Standard error of `rating`.
+    pub error: f64,
+    pub games: usize,
+    pub wins: usize,
+}
+
+/// Everything produced by a league.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LeagueReport {
+    pub config: LeagueConfig,
+    pub schedule: Vec<Table>,
+    pub games: Vec<GameResult>,
+    /// Per-bot metrics as in a [`MatchReport`].
+    pub bots: Vec<BotMetrics>,
+    /// Ratings, best first.
+    pub ratings: Vec<BotRating>,
+    pub elapsed_ms: f64,
+}
+
+impl LeagueReport {
+    pub fn balance(&self) -> ScheduleBalance {
+        ScheduleBalance::of(
+            &self.schedule,
+            self.config.bots.len(),
+            self.config.table_size,
+        )
+    }
+
+    /// The largest rating error that [`LeagueConfig::target_error`] still counts: that of
+    /// bots not yet settled against every other bot.
+    pub fn open_error(&self) -> f64 {
+        let ratings: Vec<f64> = self.ratings.iter().map(|r| r.rating).collect();
+        let errors: Vec<f64> = self.ratings.iter().map(|r| r.error).collect();
+        open_error(&ratings, &errors)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+/// Plays a schedule of multi-player tables over a pool of bots.
+pub struct League {
+    config: LeagueConfig,
+    labels: Vec<String>,
+    interrupter: Interrupter,
+    reloader: Option<WeightReloader>,
+}
+
+impl League {
+    pub fn new(config: LeagueConfig) -> Result<Self, Box<dyn Error>> {
+        let labels: Vec<String> = config
+            .bots
+            .iter()
+            .map(|spec| label_for_spec(spec))
+            .collect();
+        for (i, label) in labels.iter().enumerate() {
+            if labels[..i].contains(label) {
+                return Err(format!("two bots of the pool are labelled {label}").into());
+            }
+        }
+        if config.stock_size == Some(0) {
+            return Err("stock-size must be positive".into());
+        }
+        Ok(Self {
+            config,
+            labels,
+            interrupter: Interrupter::new(),
+            reloader: None,
+        })
+    }
+
+    /// Stop early when `interrupter` is cancelled: the table in progress is dropped and
+    /// the report rates the tables finished before.
+    pub fn with_interrupter(mut self, interrupter: Interrupter) -> Self {
+        self.interrupter = interrupter;
+        self
+    }
+
+    /// Share `reloader` between the tables, so weight files are read again between
+    /// tables when it says so.
+    pub fn with_weight_reloader(mut self, reloader: WeightReloader) -> Self {
+        self.reloader = Some(reloader);
+        self
+    }
+
+    pub fn run(&self) -> Result<LeagueReport, Box<dyn Error>> {
+        let config = &self.config;
+        let started = Instant::now();
+        let pool = config.bots.len();
+        let mut scheduler =
+            Scheduler::new(pool, config.table_size, mix_seed(config.seed, 0, 0x7AB1E))?;
+        let round = (pool / config.table_size).max(1);
+        let refit = config.matchmaking == Matchmaking::Active || config.target_error.is_some();
+        let (mut elo, mut errors) = elo_ratings(&[], pool);
+        let mut schedule = Vec::with_capacity(config.tables);
+        let mut decisions = BTreeMap::new();
+        let mut games = Vec::with_capacity(config.tables);
+        let mut rankings = Vec::with_capacity(config.tables);
+        while games.len() < config.tables {
+            if refit && games.len() % round == 0 {
+                if !games.is_empty() {
+                    (elo, errors) = elo_ratings(&rankings, pool);
+                }
+                let open = open_error(&elo, &errors);
+                if config.target_error.is_some_and(|target| open <= target) {
+                    break;
+                }
+            }
+            let table = match config.matchmaking {
+                Matchmaking::Balanced => scheduler.next_balanced(),
+                Matchmaking::Active => scheduler.next_active(&elo, &errors),
+            };
+            // Until the next refit, count on the information the table is expected to add.
+            for (&bot, information) in table
+                .seats
+                .iter()
+                .zip(expected_information(&elo, &table.seats))
+            {
+                errors[bot] = (1.0 / (1.0 / (errors[bot] * errors[bot]) + information)).sqrt();
+            }
+            let bots = table
+                .seats
+                .iter()
+                .map(|&bot| config.bots[bot].clone())
+                .collect();
+            let mut table_config = MatchConfig::new(bots, 1, config.seed);
+            table_config.max_turns = config.max_turns;
+            table_config.stock_size = config.stock_size;
+            let seating: Vec<usize> = (0..table.seats.len()).collect();
+            let mut runner =
+                MatchRunner::new(table_config)?.with_interrupter(self.interrupter.clone());
+            if let Some(reloader) = &self.reloader {
+                runner = runner.with_weight_reloader(reloader.clone());
+            }
+            let result = match runner.play_seated(games.len(), &seating, &mut decisions) {
+                Ok((result, _)) => result,
+                Err(err) if err.is::<Interrupted>() => break,
+                Err(err) => return Err(err),
+            };
+            rankings.push(Ranking::from_result(
+                &result,
+                &table.seats,
+                config.attribution,
+            ));
+            games.push(result);
+            schedule.push(table);
+        }
+
+        let (elo, errors) = elo_ratings(&rankings, pool);
+        let mut ratings: Vec<BotRating> = self
+            .labels
+            .iter()
+            .enumerate()
+            .map(|(bot, label)| BotRating {
+                label: label.clone(),
+                rating: elo[bot],
+                error: errors[bot],
+                games: schedule.iter().filter(|t| t.seats.contains(&bot)).count(),
+                wins: games
+                    .iter()
+                    .zip(&schedule)
+                    .filter(|(game, table)| game.winner.map(|w| table.seats[w.0]) == Some(bot))
+                    .count(),
+            })
+            .collect();
+        ratings.sort_by(|a, b| {
+            b.rating
+                .total_cmp(&a.rating)
+                .then_with(|| a.label.cmp(&b.label))
+        });
+
+        let elapsed_ms = started.elapsed().as_secs_f64() * 1_000.0;
+        let pool = MatchConfig::new(config.bots.clone(), games.len(), config.seed);
+        let bots = MatchReport::from_games(pool, games.clone(), &decisions, elapsed_ms).bots;
+        Ok(LeagueReport {
+            config: config.clone(),
+            schedule,
+            games,
+            bots,
+            ratings,
+            elapsed_ms,
+        })
+    }
+}
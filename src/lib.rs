@@ -1,25 +1,87 @@
 //! Skip-Bo game engine tailored for reinforcement learning workloads and bot experimentation.
+//!
+//! Bot crates should import from [`prelude`], whose versioned re-exports stay stable.
+//! Training and tooling modules are hidden from the docs and may change in any release.
 
 pub mod action;
+pub mod analysis;
+pub mod archive;
+#[doc(hidden)]
+pub mod bench;
 pub mod bot;
 pub mod bots;
 pub mod card;
+#[doc(hidden)]
+pub mod cem;
+#[doc(hidden)]
+pub mod charts;
+#[cfg(feature = "chat-bridge")]
+pub mod chat;
+pub mod commentary;
+pub mod compat;
+pub mod daily;
+#[doc(hidden)]
+pub mod dashboard;
+#[doc(hidden)]
+pub mod dataset;
+pub mod encoder;
 pub mod error;
+#[doc(hidden)]
+pub mod experiments;
+#[doc(hidden)]
+pub mod explain;
 pub mod game;
+pub mod interrupt;
+pub mod league;
+#[doc(hidden)]
+pub mod metrics;
+#[doc(hidden)]
+pub mod ope;
+pub mod prelude;
+#[doc(hidden)]
+pub mod public;
+pub mod reload;
+pub mod replay;
+pub mod runner;
 pub mod score;
+pub mod seeds;
+pub mod selfplay;
 pub mod state;
+#[doc(hidden)]
+pub mod value;
 pub mod visualize;
 
-pub use crate::action::{Action, CardSource};
+pub use crate::action::{Action, BuildPileId, CardSource, DiscardPileId, LegalSet, PlayerId};
 pub use crate::bot::Bot;
 pub use crate::bots::{Heuristic2Bot, HeuristicBot, HumanBot, RandomBot};
-pub use crate::bots::{create_bot_from_spec, label_for_spec};
+pub use crate::bots::{Strength, create_bot_from_spec, label_for_spec};
 pub use crate::card::Card;
+pub use crate::encoder::{
+    ENCODER_VERSION, JOINT_FEATURES, PRIVILEGED_FEATURES, STATE_FEATURES, StateEncoder,
+};
 pub use crate::error::{GameError, InvalidAction};
-pub use crate::game::{Game, GameBuilder, GameConfig};
+pub use crate::game::{
+    DeckStats, Game, GameBuilder, GameConfig, GameStateViewRef, LoopGuard, SeatRules,
+};
+pub use crate::replay::{GameRecord, SavedGame};
 
-pub use crate::score::winner_points;
+pub use crate::score::{PlayerScore, score_all, seat_outcome, winner_points};
+pub use crate::seeds::{SeedPool, SeedSplit};
 pub use crate::state::{
-    BuildPileView, GameSettings, GameStateView, GameStatus, PlayerPublicState, TurnPhase,
+    BuildPileView, FullStateView, GameSettings, GameStateView, GameStatus, PlayerPublicState,
+    StateView, StockVisibility, TurnEvent, TurnPhase,
 };
 pub use crate::visualize::{DescribeOptions, VisualOptions, describe_action, render_state};
+
+// Collectors move games and their bots to worker threads and share views between them.
+const _: () = {
+    const fn assert_send<T: Send>() {}
+    const fn assert_sync<T: Sync>() {}
+    assert_send::<Game>();
+    assert_sync::<Game>();
+    assert_send::<Box<dyn Bot>>();
+    assert_send::<GameStateView>();
+    assert_sync::<GameStateView>();
+    assert_send::<FullStateView>();
+    assert_sync::<FullStateView>();
+};
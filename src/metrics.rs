@@ -0,0 +1,452 @@
+//! Offline accuracy of a policy against recorded play.
+//!
+//! Win rates take hundreds of games per checkpoint and carry the luck of the deal.
+//! [`evaluate_policy`] instead replays recorded games and asks a bot for its
+//! [`Bot::rank_actions`] at every decision, scoring the ranking against the move that was
+//! actually played there, the reference policy. [`PolicyMetrics`] collects:
+//!
+//! - top-1 and top-3 agreement: how often the recorded move is the bot's first choice, or
+//!   among its first three;
+//! - top-1 agreement by the recorded move's action type (the types of
+//!   [`QualityReport`](crate::dataset::QualityReport));
+//! - masked perplexity: `exp` of the mean negative log-probability of the recorded move,
+//!   with the bot's scores turned into probabilities by a softmax over the legal actions
+//!   only. 1.0 means the bot is certain of every recorded move.
+//!
+//! Forced decisions, with a single legal action, are counted but not scored.
+//!
+//! Softmax probabilities are only as trustworthy as the temperature they are taken at.
+//! [`PolicyDecisions::fit_temperature`] picks the temperature that minimizes the masked log
+//! loss on a validation set of recorded decisions, and [`PolicyDecisions::calibration`]
+//! measures how well the bot's confidence in its top move matches how often that move is
+//! the recorded one (its ECE). A [`TemperatureFit`] is stored in the metadata file next to
+//! the checkpoint it was fitted for, where the hybrid and PUCT bots pick it up when their
+//! spec leaves the temperature unset.
+
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::action::{Action, PlayerId};
+use crate::bot::Bot;
+use crate::bots::hybrid::{DEFAULT_TEMPERATURE, softmax};
+use crate::bots::{create_bot_from_spec, label_for_spec};
+use crate::dataset::action_type;
+use crate::replay::GameRecord;
+use crate::value::Calibration;
+
+/// Probability given to a recorded move the bot did not rank, so one such move does not
+/// make the perplexity infinite.
+pub const MIN_PROBABILITY: f64 = 1e-9;
+
+/// Range [`PolicyDecisions::fit_temperature`] searches; wide enough for the heuristic
+/// bots' scores, which run into the thousands.
+pub const TEMPERATURE_RANGE: (f64, f64) = (1e-3, 1e5);
+
+/// Key of the [`TemperatureFit`] in a checkpoint's metadata file.
+pub const METADATA_KEY: &str = "policy_calibration";
+
+/// A scored decision: the bot's scores of the legal actions it ranked, best first, and
+/// where the recorded move came among them.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScoredDecision {
+    pub scores: Vec<f32>,
+    /// Position of the recorded move in `scores`; `None` when the bot did not rank it.
+    pub recorded: Option<usize>,
+    /// Action type of the recorded move.
+    pub kind: &'static str,
+}
+
+impl ScoredDecision {
+    /// The bot's `ranked` actions against the `recorded` move out of `legal`; `None` for
+    /// a forced decision.
+    pub fn new(ranked: &[(Action, f32)], legal: &[Action], recorded: &Action) -> Option<Self> {
+        if legal.len() < 2 {
+            return None;
+        }
+        let ranked: Vec<&(Action, f32)> = ranked
+            .iter()
+            .filter(|(action, _)| legal.contains(action))
+            .collect();
+        Some(Self {
+            scores: ranked.iter().map(|(_, score)| *score).collect(),
+            recorded: ranked.iter().position(|(action, _)| action == recorded),
+            kind: recorded.index().map_or("invalid", action_type),
+        })
+    }
+
+    /// Probability of the recorded move at `temperature`, at least [`MIN_PROBABILITY`].
+    pub fn probability(&self, temperature: f64) -> f64 {
+        self.recorded
+            .map_or(0.0, |rank| softmax(&self.scores, temperature)[rank])
+            .max(MIN_PROBABILITY)
+    }
+
+    /// Probability of the bot's top move at `temperature`.
+    pub fn confidence(&self, temperature: f64) -> f64 {
+        softmax(&self.scores, temperature)
+            .first()
+            .copied()
+            .unwrap_or(0.0)
+    }
+}
+
+/// Agreement on recorded moves of one action type.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct TypeAgreement {
+    pub decisions: usize,
+    pub top1: usize,
+}
+
+impl TypeAgreement {
+    pub fn rate(&self) -> f64 {
+        self.top1 as f64 / self.decisions.max(1) as f64
+    }
+}
+
+/// Accuracy of a policy on recorded decisions; see the [module docs](self).
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct PolicyMetrics {
+    /// Scored decisions.
+    pub decisions: usize,
+    /// Decisions with a single legal action, left out of every other number.
+    pub forced: usize,
+    pub top1: usize,
+    pub top3: usize,
+    /// Top-1 agreement by the type of the recorded move.
+    pub by_type: BTreeMap<&'static str, TypeAgreement>,
+    /// Sum of the negative log-probabilities of the recorded moves.
+    pub log_loss: f64,
+}
+
+impl PolicyMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Score one decision: the bot's `ranked` actions, best first, against the
+    /// `recorded` move out of `legal`. Scores become probabilities by a softmax at
+    /// `temperature` over the ranked actions that are legal.
+    pub fn add(
+        &mut self,
+        ranked: &[(Action, f32)],
+        legal: &[Action],
+        recorded: &Action,
+        temperature: f64,
+    ) {
+        match ScoredDecision::new(ranked, legal, recorded) {
+            Some(decision) => self.add_decision(&decision, temperature),
+            None => self.forced += 1,
+        }
+    }
+
+    /// Score one unforced decision at `temperature`.
+    pub fn add_decision(&mut self, decision: &ScoredDecision, temperature: f64) {
+        let rank = decision.recorded;
+        self.decisions += 1;
+        self.top1 += usize::from(rank == Some(0));
+        self.top3 += usize::from(rank.is_some_and(|rank| rank < 3));
+        self.log_loss -= decision.probability(temperature).ln();
+        let entry = self.by_type.entry(decision.kind).or_default();
+        entry.decisions += 1;
+        entry.top1 += usize::from(rank == Some(0));
+    }
+
+    pub fn top1_rate(&self) -> f64 {
+        self.top1 as f64 / self.decisions.max(1) as f64
+    }
+
+    pub fn top3_rate(&self) -> f64 {
+        self.top3 as f64 / self.decisions.max(1) as f64
+    }
+
+    /// Masked perplexity; 1.0 with no scored decisions.
+    pub fn perplexity(&self) -> f64 {
+        (self.log_loss / self.decisions.max(1) as f64).exp()
+    }
+}
+
+impl fmt::Display for PolicyMetrics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Decisions: {} scored, {} forced",
+            self.decisions, self.forced
+        )?;
+        writeln!(f, "Top-1 agreement: {:.1}%", self.top1_rate() * 100.0)?;
+        writeln!(f, "Top-3 agreement: {:.1}%", self.top3_rate() * 100.0)?;
+        writeln!(f, "Masked perplexity: {:.3}", self.perplexity())?;
+        writeln!(f, "Top-1 agreement by recorded action type:")?;
+        for (kind, agreement) in &self.by_type {
+            writeln!(
+                f,
+                "  {kind:<14} {:>8} {:>6.1}%",
+                agreement.decisions,
+                agreement.rate() * 100.0
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// How [`evaluate_policy`] sets up and reads the bot.
+#[derive(Clone, Debug)]
+pub struct MetricsConfig {
+    /// Softmax temperature over the bot's scores; should match their scale.
+    pub temperature: f64,
+    /// Seed the bot is built with.
+    pub seed: u64,
+    /// Score only the seats this bot played, by spec or label as listed in the records'
+    /// `Bots` tag; every seat when unset.
+    pub teacher: Option<String>,
+}
+
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        Self {
+            temperature: DEFAULT_TEMPERATURE,
+            seed: 0x3E7_12C5,
+            teacher: None,
+        }
+    }
+}
+
+/// Scored decisions of one bot; see [`record_decisions`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PolicyDecisions {
+    pub decisions: Vec<ScoredDecision>,
+    /// Decisions with a single legal action, which are not kept.
+    pub forced: usize,
+}
+
+impl PolicyDecisions {
+    pub fn metrics(&self, temperature: f64) -> PolicyMetrics {
+        let mut metrics = PolicyMetrics {
+            forced: self.forced,
+            ..PolicyMetrics::default()
+        };
+        for decision in &self.decisions {
+            metrics.add_decision(decision, temperature);
+        }
+        metrics
+    }
+
+    /// Mean negative log-probability of the recorded moves at `temperature`.
+    pub fn log_loss(&self, temperature: f64) -> f64 {
+        let total: f64 = self
+            .decisions
+            .iter()
+            .map(|decision| -decision.probability(temperature).ln())
+            .sum();
+        total / self.decisions.len().max(1) as f64
+    }
+
+    /// How the probability of the bot's top move at `temperature` matches how often it
+    /// was the recorded move, in `bins` probability bins.
+    pub fn calibration(&self, temperature: f64, bins: usize) -> Calibration {
+        Calibration::from_predictions(
+            self.decisions.iter().map(|decision| {
+                let hit = decision.recorded == Some(0);
+                (decision.confidence(temperature), f64::from(u8::from(hit)))
+            }),
+            bins,
+        )
+    }
+
+    /// The temperature in [`TEMPERATURE_RANGE`] with the lowest [`PolicyDecisions::log_loss`].
+    /// The loss is convex in the inverse temperature, so a golden-section search over its
+    /// logarithm finds the minimum.
+    pub fn fit_temperature(&self) -> f64 {
+        if self.decisions.is_empty() {
+            return DEFAULT_TEMPERATURE;
+        }
+        let loss = |x: f64| self.log_loss(x.exp());
+        let ratio = (5f64.sqrt() - 1.0) / 2.0;
+        let (mut low, mut high) = (TEMPERATURE_RANGE.0.ln(), TEMPERATURE_RANGE.1.ln());
+        let mut a = high - ratio * (high - low);
+        let mut b = low + ratio * (high - low);
+        let (mut loss_a, mut loss_b) = (loss(a), loss(b));
+        for _ in 0..60 {
+            if loss_a <= loss_b {
+                high = b;
+                b = a;
+                loss_b = loss_a;
+                a = high - ratio * (high - low);
+                loss_a = loss(a);
+            } else {
+                low = a;
+                a = b;
+                loss_a = loss_b;
+                b = low + ratio * (high - low);
+                loss_b = loss(b);
+            }
+        }
+        ((low + high) / 2.0).exp()
+    }
+}
+
+/// A temperature fitted for one bot spec, as stored in checkpoint metadata.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TemperatureFit {
+    /// Spec of the bot the temperature belongs to; it is applied only to that spec.
+    pub spec: String,
+    pub temperature: f64,
+    /// Validation decisions the fit was made on.
+    pub decisions: usize,
+    /// Masked log loss and ECE at the temperature the fit started from and at the fitted one.
+    pub log_loss_before: f64,
+    pub log_loss_after: f64,
+    pub ece_before: f64,
+    pub ece_after: f64,
+}
+
+impl TemperatureFit {
+    /// Fit `spec`'s temperature on `decisions`, comparing with `start` on `bins` ECE bins.
+    pub fn fit(spec: &str, decisions: &PolicyDecisions, start: f64, bins: usize) -> Self {
+        let temperature = decisions.fit_temperature();
+        Self {
+            spec: spec.to_string(),
+            temperature,
+            decisions: decisions.decisions.len(),
+            log_loss_before: decisions.log_loss(start),
+            log_loss_after: decisions.log_loss(temperature),
+            ece_before: decisions.calibration(start, bins).ece,
+            ece_after: decisions.calibration(temperature, bins).ece,
+        }
+    }
+
+    /// Store the fit in the metadata file of `checkpoint`, keeping what else it holds.
+    /// Returns the metadata file's path.
+    pub fn save(&self, checkpoint: &Path) -> Result<PathBuf, Box<dyn Error>> {
+        let path = metadata_path(checkpoint);
+        let mut metadata = match fs::read_to_string(&path) {
+            Ok(text) => serde_json::from_str(&text)?,
+            Err(_) => serde_json::Value::Object(Default::default()),
+        };
+        let object = metadata
+            .as_object_mut()
+            .ok_or_else(|| format!("{}: metadata is not a JSON object", path.display()))?;
+        object.insert(METADATA_KEY.to_string(), serde_json::to_value(self)?);
+        fs::write(&path, serde_json::to_string_pretty(&metadata)?)?;
+        Ok(path)
+    }
+
+    /// The fit stored for `checkpoint`, if its metadata file has one.
+    pub fn load(checkpoint: &Path) -> Result<Option<Self>, Box<dyn Error>> {
+        let path = metadata_path(checkpoint);
+        let Ok(text) = fs::read_to_string(&path) else {
+            return Ok(None);
+        };
+        let metadata: serde_json::Value =
+            serde_json::from_str(&text).map_err(|err| format!("{}: {err}", path.display()))?;
+        metadata
+            .get(METADATA_KEY)
+            .map(|fit| serde_json::from_value(fit.clone()))
+            .transpose()
+            .map_err(|err| format!("{}: {err}", path.display()).into())
+    }
+}
+
+/// Metadata file of a checkpoint, next to it: `value.json` has `value.meta.json`.
+pub fn metadata_path(checkpoint: &Path) -> PathBuf {
+    checkpoint.with_extension("meta.json")
+}
+
+/// Metrics of the bot `spec` on every decision of `records`; see [`record_decisions`].
+pub fn evaluate_policy(
+    spec: &str,
+    records: &[GameRecord],
+    config: &MetricsConfig,
+) -> Result<PolicyMetrics, Box<dyn Error>> {
+    Ok(record_decisions(spec, records, config)?.metrics(config.temperature))
+}
+
+/// Ask the bot `spec` to rank the legal actions at every decision of `records`, seating
+/// one instance per seat and driving its lifecycle hooks as if it had played the recorded
+/// moves itself.
+pub fn record_decisions(
+    spec: &str,
+    records: &[GameRecord],
+    config: &MetricsConfig,
+) -> Result<PolicyDecisions, Box<dyn Error>> {
+    if spec.trim().to_ascii_lowercase().starts_with("human") {
+        return Err("human players cannot be evaluated".into());
+    }
+    let mut decisions = PolicyDecisions::default();
+    let mut bots: Vec<Box<dyn Bot>> = Vec::new();
+    for (index, record) in records.iter().enumerate() {
+        while bots.len() < record.players {
+            let seat = PlayerId(bots.len());
+            bots.push(create_bot_from_spec(spec, seat, config.seed)?);
+        }
+        let scored = scored_seats(record, config.teacher.as_deref())
+            .map_err(|err| format!("game {index}: {err}"))?;
+        let mut hook_error = None;
+        let mut turn_player = None;
+        let game = record
+            .replay_with(|game, player, recorded| {
+                let mut decide = || -> Result<(), Box<dyn Error>> {
+                    if turn_player.is_none() {
+                        for seat in PlayerId::all(record.players) {
+                            bots[seat.0].on_game_start(&game.state_view(seat)?);
+                        }
+                    }
+                    let new_turn = turn_player != Some(player);
+                    turn_player = Some(player);
+                    if !scored[player.0] {
+                        return Ok(());
+                    }
+                    let state = game.state_view(player)?;
+                    let legal = game.legal_actions(player)?;
+                    let bot = &mut bots[player.0];
+                    if new_turn {
+                        bot.on_turn_start(&state);
+                    }
+                    if bot.wants_full_state() {
+                        bot.observe_full_state(&game.full_state_view(player)?);
+                    }
+                    let ranked = bot.rank_actions(&state, &legal);
+                    match ScoredDecision::new(&ranked, &legal, recorded) {
+                        Some(decision) => decisions.decisions.push(decision),
+                        None => decisions.forced += 1,
+                    }
+                    Ok(())
+                };
+                if hook_error.is_none()
+                    && let Err(err) = decide()
+                {
+                    hook_error = Some(err);
+                }
+            })
+            .map_err(|err| format!("game {index}: {err}"))?;
+        if let Some(err) = hook_error {
+            return Err(format!("game {index}: {err}").into());
+        }
+        for seat in PlayerId::all(record.players) {
+            bots[seat.0].on_game_end(&game.state_view(seat)?, game.winner());
+        }
+    }
+    Ok(decisions)
+}
+
+/// Which seats of `record` [`record_decisions`] scores.
+fn scored_seats(record: &GameRecord, teacher: Option<&str>) -> Result<Vec<bool>, String> {
+    let Some(teacher) = teacher else {
+        return Ok(vec![true; record.players]);
+    };
+    let bots = record
+        .tag("Bots")
+        .ok_or("no Bots tag to find the teacher by")?;
+    let seats: Vec<bool> = bots
+        .split_whitespace()
+        .map(|spec| spec == teacher || label_for_spec(spec) == teacher)
+        .collect();
+    if seats.len() != record.players {
+        return Err(format!("Bots tag lists {} seats: {bots}", seats.len()));
+    }
+    Ok(seats)
+}
@@ -0,0 +1,538 @@
+//! Off-policy evaluation of candidate policies on logged tournament games.
+//!
+//! Measuring a candidate's win rate online takes hundreds of games per point of a sweep.
+//! Off-policy evaluation estimates it from games already played by another, *behavior*,
+//! policy instead: each logged game is reweighed by how much more or less likely the
+//! candidate was to make the moves the behavior policy made there.
+//!
+//! That needs the probability the behavior policy gave each of its moves, so only seats
+//! played by a [`SoftmaxBot`](crate::bots::SoftmaxBot) (`softmax:<temperature>:<spec>`)
+//! can be logged: their moves were sampled from a softmax over the inner bot's
+//! [`Bot::rank_actions`]. [`BehaviorLog::from_tournament`] replays an archived tournament
+//! through those bots, seeded as the runner seeded them, and records each move's
+//! probability. The log is written once per tournament and reused for every candidate.
+//!
+//! [`estimate_policy`] then scores a candidate on the logged seats of one behavior bot,
+//! with the candidate's own probabilities from a softmax over its ranking:
+//!
+//! - importance sampling: the mean of each game's result times its weight, the product of
+//!   the candidate-to-behavior probability ratios of the seat's moves;
+//! - weighted importance sampling: the same normalized by the sum of the weights, biased
+//!   but far less noisy;
+//! - doubly robust, given value nets: the value nets' estimate of how the candidate would
+//!   do, corrected along each game by the weighted differences between what happened and
+//!   what the nets expected.
+//!
+//! The estimates are only as good as the overlap between the two policies. The effective
+//! sample size ([`OpeEstimate::effective_games`]) tells how many games the weights leave;
+//! far below the logged games, the estimate rests on a few lucky ones.
+
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::fmt;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Read, Seek, Write};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::action::{Action, PlayerId};
+use crate::archive::ArchiveReader;
+use crate::bot::Bot;
+use crate::bots::hybrid::softmax;
+use crate::bots::{create_bot_from_spec, label_for_spec, sampling_temperature};
+use crate::game::Game;
+use crate::interrupt::Interrupter;
+use crate::replay::GameRecord;
+use crate::runner::{MatchReport, mix_seed};
+use crate::score::seat_outcome;
+use crate::value::ValueEnsemble;
+
+/// One logged move of a sampling seat.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LoggedDecision {
+    /// Index of the game in the tournament.
+    pub game: usize,
+    /// Position of the move in the game's record.
+    pub ply: usize,
+    pub seat: PlayerId,
+    /// Label of the bot that made the move.
+    pub bot: String,
+    /// [`Action::index`] of the move.
+    pub action: usize,
+    /// Probability the bot gave the move.
+    pub propensity: f64,
+    /// Final result for `seat`; see [`seat_outcome`].
+    pub outcome: f32,
+}
+
+/// Logged moves of the sampling seats of a tournament; see the [module docs](self).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BehaviorLog {
+    pub decisions: Vec<LoggedDecision>,
+}
+
+impl BehaviorLog {
+    /// Replay every game of `archive` through the tournament's `softmax` bots and log the
+    /// probability each gave its moves. Games are matched to `report` as
+    /// [`PolicyDataset::from_tournament`](crate::dataset::PolicyDataset::from_tournament)
+    /// matches them. Games with faults are left out, since a fault replaces the bot's move.
+    pub fn from_tournament<R: Read + Seek>(
+        report: &MatchReport,
+        archive: &mut ArchiveReader<R>,
+    ) -> Result<Self, Box<dyn Error>> {
+        let config = &report.config;
+        if config.move_time_ms.is_some() {
+            return Err("games played under a move time cannot be replayed exactly".into());
+        }
+        let mut specs: HashMap<String, usize> = HashMap::new();
+        for (index, spec) in config.bots.iter().enumerate() {
+            if let Some(&other) = specs.get(&label_for_spec(spec))
+                && config.bots[other] != *spec
+            {
+                return Err(format!("bots {spec} and {} share a label", config.bots[other]).into());
+            }
+            specs.insert(label_for_spec(spec), index);
+        }
+        if !config
+            .bots
+            .iter()
+            .any(|spec| sampling_temperature(spec).is_some())
+        {
+            return Err("no softmax bots in the tournament, so no move has a probability".into());
+        }
+
+        let mut decisions = Vec::new();
+        for position in 0..archive.len() {
+            let record = archive.get(position)?;
+            let game_index = match record.tag("Game") {
+                Some(tag) => tag
+                    .parse()
+                    .map_err(|_| format!("archived game {position}: bad Game tag {tag}"))?,
+                None => position,
+            };
+            let result = report
+                .games
+                .iter()
+                .find(|game| game.game_index == game_index)
+                .ok_or_else(|| format!("report has no game {game_index}"))?;
+            if record
+                .tag("Bots")
+                .is_some_and(|bots| bots != result.seats.join(" "))
+            {
+                return Err(format!("game {game_index} was played by other bots").into());
+            }
+            if !result.faults.is_empty() {
+                continue;
+            }
+            let mut seats: Vec<Option<(Box<dyn Bot>, f64)>> = Vec::new();
+            for (seat, label) in result.seats.iter().enumerate() {
+                let index = *specs
+                    .get(label)
+                    .ok_or_else(|| format!("game {game_index}: no bot labelled {label}"))?;
+                let spec = &config.bots[index];
+                let Some(temperature) = sampling_temperature(spec) else {
+                    seats.push(None);
+                    continue;
+                };
+                let seed = mix_seed(config.seed, game_index as u64, seat as u64);
+                let mut bot = create_bot_from_spec(spec, PlayerId(seat), seed)?;
+                for handicap in config.handicaps.get(index).into_iter().flatten() {
+                    handicap.apply_bot(bot.as_mut());
+                }
+                seats.push(Some((bot, temperature)));
+            }
+            let mut ply = 0;
+            let mut error = None;
+            let mut turn_player = None;
+            record
+                .replay_with(|game, player, recorded| {
+                    let mut log = || -> Result<(), Box<dyn Error>> {
+                        let new_turn = turn_player != Some(player);
+                        if turn_player.is_none() {
+                            for (seat, entry) in seats.iter_mut().enumerate() {
+                                if let Some((bot, _)) = entry {
+                                    bot.on_game_start(&game.state_view(PlayerId(seat))?);
+                                }
+                            }
+                        }
+                        turn_player = Some(player);
+                        let Some((bot, temperature)) = &mut seats[player.0] else {
+                            return Ok(());
+                        };
+                        let probabilities = policy_probabilities(
+                            bot.as_mut(),
+                            game,
+                            player,
+                            new_turn,
+                            *temperature,
+                        )?;
+                        let propensity = probabilities
+                            .iter()
+                            .find(|(action, _)| action == recorded)
+                            .map_or(0.0, |(_, p)| *p);
+                        if propensity <= 0.0 {
+                            return Err(format!(
+                                "{} gives the recorded move {recorded} no probability; \
+                                 was the game played by this tournament's bots?",
+                                result.seats[player.0]
+                            )
+                            .into());
+                        }
+                        decisions.push(LoggedDecision {
+                            game: game_index,
+                            ply,
+                            seat: player,
+                            bot: result.seats[player.0].clone(),
+                            action: recorded.index().ok_or("archived move has no index")?,
+                            propensity,
+                            outcome: seat_outcome(result.winner, player),
+                        });
+                        Ok(())
+                    };
+                    if error.is_none()
+                        && let Err(err) = log()
+                    {
+                        error = Some((ply, err));
+                    }
+                    ply += 1;
+                })
+                .map_err(|err| format!("game {game_index}: {err}"))?;
+            if let Some((ply, err)) = error {
+                return Err(format!("game {game_index} move {}: {err}", ply + 1).into());
+            }
+        }
+        Ok(Self { decisions })
+    }
+
+    pub fn len(&self) -> usize {
+        self.decisions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.decisions.is_empty()
+    }
+
+    /// Labels of the logged bots with their number of moves.
+    pub fn bots(&self) -> BTreeMap<&str, usize> {
+        let mut bots = BTreeMap::new();
+        for decision in &self.decisions {
+            *bots.entry(decision.bot.as_str()).or_default() += 1;
+        }
+        bots
+    }
+
+    /// Write the log as JSON lines.
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        let mut out = BufWriter::new(File::create(path)?);
+        for decision in &self.decisions {
+            serde_json::to_writer(&mut out, decision)?;
+            out.write_all(b"\n")?;
+        }
+        out.flush()?;
+        Ok(())
+    }
+
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let mut decisions = Vec::new();
+        for (number, line) in BufReader::new(File::open(path)?).lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            decisions.push(
+                serde_json::from_str(&line)
+                    .map_err(|err| format!("{}:{}: {err}", path.display(), number + 1))?,
+            );
+        }
+        Ok(Self { decisions })
+    }
+}
+
+/// Drive `bot`'s hooks for its decision at `game` and turn its ranking of the legal
+/// actions into probabilities at `temperature`. Actions the bot leaves unranked get none.
+fn policy_probabilities(
+    bot: &mut dyn Bot,
+    game: &Game,
+    player: PlayerId,
+    new_turn: bool,
+    temperature: f64,
+) -> Result<Vec<(Action, f64)>, Box<dyn Error>> {
+    let state = game.state_view(player)?;
+    let legal = game.legal_actions(player)?;
+    if new_turn {
+        bot.on_turn_start(&state);
+    }
+    if bot.wants_full_state() {
+        bot.observe_full_state(&game.full_state_view(player)?);
+    }
+    bot.interrupt_with(&Interrupter::new());
+    let ranked: Vec<(Action, f32)> = bot
+        .rank_actions(&state, &legal)
+        .into_iter()
+        .filter(|(action, _)| legal.contains(action))
+        .collect();
+    let scores: Vec<f32> = ranked.iter().map(|(_, score)| *score).collect();
+    Ok(ranked
+        .into_iter()
+        .map(|(action, _)| action)
+        .zip(softmax(&scores, temperature))
+        .collect())
+}
+
+/// How [`estimate_policy`] sets up and reads the candidate.
+#[derive(Clone, Debug)]
+pub struct OpeConfig {
+    /// Label of the logged bot the candidate stands in for.
+    pub behavior: String,
+    /// Softmax temperature over the candidate's scores; low values make it close to its
+    /// greedy play.
+    pub temperature: f64,
+    /// Base seed the candidate is built with, per game and seat.
+    pub seed: u64,
+}
+
+/// Off-policy estimates of a candidate's win rate; see the [module docs](self).
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct OpeEstimate {
+    /// Logged seats (one per game the behavior bot played in each) and their moves.
+    pub games: usize,
+    pub decisions: usize,
+    /// Win rate of the behavior bot in the logged games.
+    pub behavior_win_rate: f64,
+    pub importance_sampling: f64,
+    pub weighted_importance_sampling: f64,
+    /// `None` without value nets.
+    pub doubly_robust: Option<f64>,
+    /// `(Σw)² / Σw²` of the games' weights.
+    pub effective_games: f64,
+    /// Largest weight of a game.
+    pub max_weight: f64,
+}
+
+impl fmt::Display for OpeEstimate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Logged: {} games, {} decisions, behavior win rate {:.1}%",
+            self.games,
+            self.decisions,
+            self.behavior_win_rate * 100.0
+        )?;
+        writeln!(
+            f,
+            "Importance sampling: {:.1}%",
+            self.importance_sampling * 100.0
+        )?;
+        writeln!(
+            f,
+            "Weighted importance sampling: {:.1}%",
+            self.weighted_importance_sampling * 100.0
+        )?;
+        if let Some(dr) = self.doubly_robust {
+            writeln!(f, "Doubly robust: {:.1}%", dr * 100.0)?;
+        }
+        writeln!(
+            f,
+            "Effective games: {:.1} (max weight {:.3})",
+            self.effective_games, self.max_weight
+        )
+    }
+}
+
+/// One logged seat as the candidate sees it.
+#[derive(Clone, Debug, Default)]
+struct Episode {
+    reward: f64,
+    /// Per move: candidate-to-behavior probability ratio, and with value nets the value
+    /// of the logged move and the candidate's expected value at the decision.
+    steps: Vec<(f64, Option<(f64, f64)>)>,
+}
+
+/// Estimate the win rate of the bot `spec` in the seats `log` has for
+/// [`OpeConfig::behavior`], replaying their games from `records` (matched by their
+/// `Game` tag, falling back to their position). With `value`, also the doubly robust
+/// estimate, whose value of a move is the nets' win probability after it.
+pub fn estimate_policy(
+    spec: &str,
+    log: &BehaviorLog,
+    records: &[GameRecord],
+    config: &OpeConfig,
+    value: Option<&ValueEnsemble>,
+) -> Result<OpeEstimate, Box<dyn Error>> {
+    if spec.trim().to_ascii_lowercase().starts_with("human") {
+        return Err("human players cannot be evaluated".into());
+    }
+    let mut logged: BTreeMap<usize, BTreeMap<usize, &LoggedDecision>> = BTreeMap::new();
+    for decision in log.decisions.iter().filter(|d| d.bot == config.behavior) {
+        logged
+            .entry(decision.game)
+            .or_default()
+            .insert(decision.ply, decision);
+    }
+    if logged.is_empty() {
+        return Err(format!("the log has no moves of {}", config.behavior).into());
+    }
+    let mut by_game: HashMap<usize, &GameRecord> = HashMap::new();
+    for (position, record) in records.iter().enumerate() {
+        let index = match record.tag("Game") {
+            Some(tag) => tag
+                .parse()
+                .map_err(|_| format!("game {position}: bad Game tag {tag}"))?,
+            None => position,
+        };
+        by_game.insert(index, record);
+    }
+
+    let mut episodes = Vec::new();
+    for (&game_index, moves) in &logged {
+        let record = by_game
+            .get(&game_index)
+            .ok_or_else(|| format!("no record of logged game {game_index}"))?;
+        let mut seats: Vec<Option<(Box<dyn Bot>, Episode)>> = Vec::new();
+        for seat in PlayerId::all(record.players) {
+            let played = moves.values().find(|decision| decision.seat == seat);
+            seats.push(match played {
+                Some(decision) => {
+                    let seed = mix_seed(config.seed, game_index as u64, seat.0 as u64);
+                    let episode = Episode {
+                        reward: f64::from(u8::from(decision.outcome > 0.0)),
+                        steps: Vec::new(),
+                    };
+                    Some((create_bot_from_spec(spec, seat, seed)?, episode))
+                }
+                None => None,
+            });
+        }
+        let mut ply = 0;
+        let mut error = None;
+        let mut turn_player = None;
+        record
+            .replay_with(|game, player, recorded| {
+                let mut score = || -> Result<(), Box<dyn Error>> {
+                    let new_turn = turn_player != Some(player);
+                    if turn_player.is_none() {
+                        for (seat, entry) in seats.iter_mut().enumerate() {
+                            if let Some((bot, _)) = entry {
+                                bot.on_game_start(&game.state_view(PlayerId(seat))?);
+                            }
+                        }
+                    }
+                    turn_player = Some(player);
+                    let Some((bot, episode)) = &mut seats[player.0] else {
+                        return Ok(());
+                    };
+                    let decision = moves
+                        .get(&ply)
+                        .filter(|decision| {
+                            decision.seat == player && recorded.index() == Some(decision.action)
+                        })
+                        .ok_or("the log does not match the archived game")?;
+                    let probabilities = policy_probabilities(
+                        bot.as_mut(),
+                        game,
+                        player,
+                        new_turn,
+                        config.temperature,
+                    )?;
+                    let probability = |action: &Action| {
+                        probabilities
+                            .iter()
+                            .find(|(ranked, _)| ranked == action)
+                            .map_or(0.0, |(_, p)| *p)
+                    };
+                    let ratio = probability(recorded) / decision.propensity;
+                    let values = match value {
+                        Some(value) => {
+                            let mut expected = 0.0;
+                            let mut logged = 0.0;
+                            for action in &game.legal_actions(player)? {
+                                let q = action_value(value, game, player, action)?;
+                                expected += probability(action) * q;
+                                if action == recorded {
+                                    logged = q;
+                                }
+                            }
+                            Some((logged, expected))
+                        }
+                        None => None,
+                    };
+                    episode.steps.push((ratio, values));
+                    Ok(())
+                };
+                if error.is_none()
+                    && let Err(err) = score()
+                {
+                    error = Some((ply, err));
+                }
+                ply += 1;
+            })
+            .map_err(|err| format!("game {game_index}: {err}"))?;
+        if let Some((ply, err)) = error {
+            return Err(format!("game {game_index} move {}: {err}", ply + 1).into());
+        }
+        episodes.extend(seats.into_iter().flatten().map(|(_, episode)| episode));
+    }
+    Ok(aggregate(&episodes, value.is_some()))
+}
+
+/// Win probability for `player` after `action`: the result when it ends the game,
+/// otherwise `value`'s estimate from the player's view.
+fn action_value(
+    value: &ValueEnsemble,
+    game: &Game,
+    player: PlayerId,
+    action: &Action,
+) -> Result<f64, Box<dyn Error>> {
+    let mut next = game.clone();
+    next.apply_action(player, action.clone())?;
+    if next.is_finished() {
+        return Ok(f64::from(u8::from(next.winner() == Some(player))));
+    }
+    Ok(f64::from(value.evaluate(&next.state_view(player)?)))
+}
+
+fn aggregate(episodes: &[Episode], doubly_robust: bool) -> OpeEstimate {
+    let games = episodes.len().max(1) as f64;
+    let weights: Vec<f64> = episodes
+        .iter()
+        .map(|episode| episode.steps.iter().map(|(ratio, _)| ratio).product())
+        .collect();
+    let total: f64 = weights.iter().sum();
+    let squares: f64 = weights.iter().map(|w| w * w).sum();
+    let weighted: f64 = episodes
+        .iter()
+        .zip(&weights)
+        .map(|(episode, w)| w * episode.reward)
+        .sum();
+    let doubly_robust = doubly_robust.then(|| {
+        let total: f64 = episodes
+            .iter()
+            .map(|episode| {
+                episode
+                    .steps
+                    .iter()
+                    .rev()
+                    .fold(episode.reward, |later, (ratio, values)| {
+                        let (logged, expected) = values.unwrap_or_default();
+                        expected + ratio * (later - logged)
+                    })
+            })
+            .sum();
+        total / games
+    });
+    OpeEstimate {
+        games: episodes.len(),
+        decisions: episodes.iter().map(|episode| episode.steps.len()).sum(),
+        behavior_win_rate: episodes.iter().map(|episode| episode.reward).sum::<f64>() / games,
+        importance_sampling: weighted / games,
+        weighted_importance_sampling: if total > 0.0 { weighted / total } else { 0.0 },
+        doubly_robust,
+        effective_games: if squares > 0.0 {
+            total * total / squares
+        } else {
+            0.0
+        },
+        max_weight: weights.iter().copied().fold(0.0, f64::max),
+    }
+}
@@ -0,0 +1,24 @@
+//! The stable surface for bot crates built on the engine: `use skipbot::prelude::*;`.
+//!
+//! Everything a bot sees or hands back (the views, actions and cards), the [`Bot`]
+//! trait and the engine that drives it is re-exported from a versioned module. Names in
+//! [`v1`] are only removed or changed in a breaking way by a new `v2` module, which then
+//! becomes the default while `v1` stays available. The rest of the crate, training and
+//! experiment code in particular, may change in any release.
+
+/// The first stable prelude; [`prelude`](self) re-exports it.
+pub mod v1 {
+    pub use crate::action::{Action, BuildPileId, CardSource, DiscardPileId, LegalSet, PlayerId};
+    pub use crate::bot::Bot;
+    pub use crate::bots::{create_bot_from_spec, label_for_spec};
+    pub use crate::card::Card;
+    pub use crate::error::{GameError, InvalidAction};
+    pub use crate::game::{Game, GameBuilder, GameConfig};
+    pub use crate::interrupt::Interrupter;
+    pub use crate::state::{
+        BuildPileView, FullStateView, GameSettings, GameStateView, GameStatus, PlayerPublicState,
+        StateView, StockVisibility, TurnEvent, TurnPhase,
+    };
+}
+
+pub use v1::*;
@@ -0,0 +1,234 @@
+//! Anonymized traces of recorded games, safe to share.
+//!
+//! A [`GameRecord`] carries the seed of its deal, and with it every hidden card of the
+//! game, plus tags that may name the people who played. [`anonymize`] keeps only what a
+//! spectator of the game saw: for every move, the acting seat's view with its hand
+//! hidden ([`StateEncoder::encode_public`]), the actions that may have been legal as far
+//! as a spectator can tell ([`public_legal`]), the move with the card it showed, and how
+//! the game ended. Players are reduced to `human` or their bot label.
+//!
+//! The samples are written as JSON lines behind a
+//! [`Representation`](crate::compat::Representation) header, like the other datasets,
+//! and [`load_public_dataset`] reads them back as a [`PolicyDataset`] for behavior
+//! cloning on public information.
+
+use std::error::Error;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::action::{Action, BuildPileId, CardSource, PlayerId};
+use crate::bots::label_for_spec;
+use crate::card::Card;
+use crate::compat::{FeatureSample, FeatureShim, read_samples, write_header};
+use crate::dataset::{LabelledSample, PolicyDataset, PolicySample};
+use crate::encoder::StateEncoder;
+use crate::game::Game;
+use crate::replay::GameRecord;
+use crate::score::seat_outcome;
+use crate::state::StateView;
+
+/// Label of every human seat.
+pub const HUMAN_LABEL: &str = "human";
+
+/// One move of an anonymized game.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PublicSample {
+    /// Position of the game in the export.
+    pub game: usize,
+    /// Position of the move in the game.
+    pub ply: usize,
+    pub seat: PlayerId,
+    /// [`HUMAN_LABEL`] for people, the bot's label otherwise.
+    pub player: String,
+    /// [`StateEncoder::encode_public`] of the acting seat's view.
+    pub features: Vec<f32>,
+    /// [`Action::index`] of every action in [`public_legal`] and of the chosen one.
+    pub legal: Vec<usize>,
+    pub action: usize,
+    /// The card the move played or discarded; `None` for ending the turn.
+    pub card: Option<Card>,
+    /// Final result for `seat`; see [`seat_outcome`].
+    pub outcome: f32,
+}
+
+impl FeatureSample for PublicSample {
+    fn features_mut(&mut self) -> &mut Vec<f32> {
+        &mut self.features
+    }
+}
+
+impl LabelledSample for PublicSample {
+    fn teacher(&self) -> &str {
+        &self.player
+    }
+
+    fn game(&self) -> usize {
+        self.game
+    }
+
+    fn seat(&self) -> PlayerId {
+        self.seat
+    }
+
+    fn legal(&self) -> &[usize] {
+        &self.legal
+    }
+
+    fn action(&self) -> usize {
+        self.action
+    }
+
+    fn outcome(&self) -> f32 {
+        self.outcome
+    }
+}
+
+impl From<PublicSample> for PolicySample {
+    /// A policy sample without a rating or provenance, since an anonymized game has no
+    /// tournament and no known deal.
+    fn from(sample: PublicSample) -> Self {
+        Self {
+            game: sample.game,
+            seat: sample.seat,
+            bot: sample.player,
+            features: sample.features,
+            legal: sample.legal,
+            action: sample.action,
+            outcome: sample.outcome,
+            rating: 0.0,
+            provenance: None,
+        }
+    }
+}
+
+/// The actions `legal` looks like to a spectator: every legal action that does not come
+/// from the hand, which only depends on face-up cards, and every hand slot onto every
+/// build pile, since the hand's cards are hidden. Sorted by [`Action::index`].
+pub fn public_legal<V: StateView + ?Sized>(state: &V, legal: &[Action]) -> Vec<usize> {
+    let hand_plays = (0..state.hand().len()).flat_map(|slot| {
+        BuildPileId::ALL
+            .into_iter()
+            .map(move |build_pile| Action::Play {
+                source: CardSource::Hand(slot),
+                build_pile,
+            })
+    });
+    let mut indices: Vec<usize> = legal
+        .iter()
+        .filter(|action| {
+            !matches!(
+                action,
+                Action::Play {
+                    source: CardSource::Hand(_),
+                    ..
+                }
+            )
+        })
+        .cloned()
+        .chain(hand_plays)
+        .filter_map(|action| action.index())
+        .collect();
+    indices.sort_unstable();
+    indices.dedup();
+    indices
+}
+
+/// Anonymize the moves of `records`; see the [module docs](self). Seats are labelled from
+/// each record's `Bots` tag, or `seatN` without one.
+pub fn anonymize(records: &[GameRecord]) -> Result<Vec<PublicSample>, Box<dyn Error>> {
+    let mut samples = Vec::new();
+    for (index, record) in records.iter().enumerate() {
+        let players = player_labels(record);
+        let first = samples.len();
+        let mut ply = 0;
+        let game = record
+            .replay_with(|game, seat, action| {
+                let view = game
+                    .state_view_ref(seat)
+                    .expect("the acting player is seated");
+                let legal = game.legal_actions(seat).unwrap_or_default();
+                samples.push(PublicSample {
+                    game: index,
+                    ply,
+                    seat,
+                    player: players[seat.0].clone(),
+                    features: StateEncoder::encode_public(&view).to_vec(),
+                    legal: public_legal(&view, &legal),
+                    action: action.index().expect("recorded moves have an index"),
+                    card: shown_card(game, seat, action),
+                    outcome: 0.0,
+                });
+                ply += 1;
+            })
+            .map_err(|err| format!("game {index}: {err}"))?;
+        for sample in &mut samples[first..] {
+            sample.outcome = seat_outcome(game.winner(), sample.seat);
+        }
+    }
+    Ok(samples)
+}
+
+/// Anonymized label of every seat of `record`.
+fn player_labels(record: &GameRecord) -> Vec<String> {
+    let specs: Vec<&str> = record
+        .tag("Bots")
+        .map(|bots| bots.split_whitespace().collect())
+        .unwrap_or_default();
+    (0..record.players)
+        .map(|seat| match specs.get(seat) {
+            Some(spec) if spec.to_ascii_lowercase().starts_with(HUMAN_LABEL) => {
+                HUMAN_LABEL.to_string()
+            }
+            Some(spec) => label_for_spec(spec),
+            None => format!("seat{seat}"),
+        })
+        .collect()
+}
+
+/// The card `action` turns face up or moves between face-up piles.
+fn shown_card(game: &Game, seat: PlayerId, action: &Action) -> Option<Card> {
+    let view = game.state_view_ref(seat).ok()?;
+    match *action {
+        Action::Play { source, .. } => match source {
+            CardSource::Hand(slot) => view.hand().get(slot).copied(),
+            CardSource::Stock => view.stock_top(seat),
+            CardSource::Discard(pile) => view.discard_piles(seat).get(pile.0)?.last().copied(),
+        },
+        Action::Discard { hand_index, .. } => view.hand().get(hand_index).copied(),
+        Action::EndTurn => None,
+    }
+}
+
+/// Write `samples` as JSON lines after a representation header.
+pub fn save_public(samples: &[PublicSample], path: &Path) -> Result<(), Box<dyn Error>> {
+    let mut out = BufWriter::new(File::create(path)?);
+    write_header(&mut out)?;
+    for sample in samples {
+        serde_json::to_writer(&mut out, sample)?;
+        out.write_all(b"\n")?;
+    }
+    out.flush()?;
+    Ok(())
+}
+
+/// Read samples [`save_public`] wrote, adapting features of another encoder version with
+/// `shim`.
+pub fn load_public(path: &Path, shim: FeatureShim) -> Result<Vec<PublicSample>, Box<dyn Error>> {
+    read_samples(path, shim)
+}
+
+/// [`load_public`] as a [`PolicyDataset`] for behavior cloning.
+pub fn load_public_dataset(
+    path: &Path,
+    shim: FeatureShim,
+) -> Result<PolicyDataset, Box<dyn Error>> {
+    Ok(PolicyDataset {
+        samples: load_public(path, shim)?
+            .into_iter()
+            .map(PolicySample::from)
+            .collect(),
+    })
+}
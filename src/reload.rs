@@ -0,0 +1,222 @@
+//! Reloading the weight files of parametric bots between games.
+//!
+//! A `heuristic19` spec with `checkpoint=PATH` plays the [`PlanWeights`] of a file, such
+//! as `cem --out` writes. Without a reloader, every game reads the file again, so a file
+//! rewritten mid-run changes the bot at some unrecorded game, and a file caught
+//! half-written aborts the run. A [`WeightReloader`] given to the match runner or the
+//! league instead reads each file once, hands the bots the stored weights inline, and
+//! only reads the file again between games when its [`ReloadTrigger`] says so:
+//!
+//! - [`ReloadTrigger::Modified`] when the file's modification time changed;
+//! - [`ReloadTrigger::Signal`] when [`WeightReloader::request`] was called or, for a
+//!   reloader made by [`WeightReloader::sighup`], the process received SIGHUP (Unix only).
+//!
+//! A file that cannot be read or parsed at a reload keeps its previous weights and is
+//! tried again before the next game. Games record the files reloaded before them in
+//! [`GameResult::reloaded`](crate::runner::GameResult::reloaded).
+
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+
+use crate::bots::PlanWeights;
+
+/// Set by the SIGHUP handler [`WeightReloader::sighup`] installs.
+static SIGHUP: AtomicBool = AtomicBool::new(false);
+
+/// When a [`WeightReloader`] reads a weight file again.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ReloadTrigger {
+    /// Before the first game after the file's modification time changed.
+    Modified,
+    /// Before the first game after a reload was requested.
+    Signal,
+}
+
+impl fmt::Display for ReloadTrigger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ReloadTrigger::Modified => "modified",
+            ReloadTrigger::Signal => "signal",
+        })
+    }
+}
+
+impl FromStr for ReloadTrigger {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "modified" | "watch" => Ok(ReloadTrigger::Modified),
+            "signal" => Ok(ReloadTrigger::Signal),
+            _ => Err(format!(
+                "invalid reload trigger: {s} (expected modified or signal)"
+            )),
+        }
+    }
+}
+
+/// Weights of one file as last read.
+#[derive(Clone, Debug)]
+struct Snapshot {
+    modified: Option<SystemTime>,
+    weights: PlanWeights,
+}
+
+/// Weight files shared by the games of a run; see the [module docs](self). Clones share
+/// the stored weights and pending requests.
+#[derive(Clone, Debug)]
+pub struct WeightReloader {
+    trigger: ReloadTrigger,
+    requested: Arc<AtomicBool>,
+    watch_sighup: bool,
+    snapshots: Arc<Mutex<BTreeMap<String, Snapshot>>>,
+}
+
+impl WeightReloader {
+    pub fn new(trigger: ReloadTrigger) -> Self {
+        Self {
+            trigger,
+            requested: Arc::new(AtomicBool::new(false)),
+            watch_sighup: false,
+            snapshots: Arc::default(),
+        }
+    }
+
+    /// A reloader that also treats SIGHUP as a request. Installs the handler on first use;
+    /// on other platforms only [`WeightReloader::request`] does.
+    pub fn sighup(trigger: ReloadTrigger) -> Self {
+        signal::install();
+        Self {
+            watch_sighup: true,
+            ..Self::new(trigger)
+        }
+    }
+
+    pub fn trigger(&self) -> ReloadTrigger {
+        self.trigger
+    }
+
+    /// Reload every file before the next game, whatever the trigger.
+    pub fn request(&self) {
+        self.requested.store(true, Ordering::Relaxed);
+    }
+
+    /// `specs` with the weight file of every `heuristic19` spec replaced by its stored
+    /// weights, reading the files that are new or due for a reload, and the paths of the
+    /// files reloaded. Fails only when a file cannot be read the first time.
+    pub fn resolve(&self, specs: &[String]) -> Result<(Vec<String>, Vec<String>), Box<dyn Error>> {
+        let requested = self.requested.swap(false, Ordering::Relaxed)
+            | (self.watch_sighup && SIGHUP.swap(false, Ordering::Relaxed));
+        let mut snapshots = self.snapshots.lock().expect("weight snapshots lock");
+        let mut reloaded = Vec::new();
+        let mut failed = false;
+        let mut resolved = Vec::with_capacity(specs.len());
+        for spec in specs {
+            let Some(path) = weight_file(spec) else {
+                resolved.push(spec.clone());
+                continue;
+            };
+            match snapshots.get(path) {
+                None => {
+                    let snapshot = read(path).map_err(|err| format!("{path}: {err}"))?;
+                    snapshots.insert(path.to_string(), snapshot);
+                }
+                Some(_) if reloaded.iter().any(|done| done == path) => {}
+                Some(snapshot) => {
+                    let due = match self.trigger {
+                        ReloadTrigger::Modified => modified(path) != snapshot.modified,
+                        ReloadTrigger::Signal => requested,
+                    };
+                    if due {
+                        match read(path) {
+                            Ok(snapshot) => {
+                                snapshots.insert(path.to_string(), snapshot);
+                                reloaded.push(path.to_string());
+                            }
+                            Err(_) => failed = true,
+                        }
+                    }
+                }
+            }
+            resolved.push(inline_weights(spec, &snapshots[path].weights));
+        }
+        if failed && self.trigger == ReloadTrigger::Signal {
+            self.request();
+        }
+        Ok((resolved, reloaded))
+    }
+}
+
+/// The `checkpoint` path of a `heuristic19` spec, including one wrapped by another bot.
+fn weight_file(spec: &str) -> Option<&str> {
+    let mut parts = spec.split(':').map(str::trim);
+    if !parts
+        .clone()
+        .any(|part| part.eq_ignore_ascii_case("heuristic19"))
+    {
+        return None;
+    }
+    parts.find_map(|part| part.strip_prefix("checkpoint="))
+}
+
+/// `spec` with its `checkpoint=PATH` option replaced by `weights=W1,W2,...`.
+fn inline_weights(spec: &str, weights: &PlanWeights) -> String {
+    let list: Vec<String> = weights.to_vec().iter().map(f64::to_string).collect();
+    let weights = format!("weights={}", list.join(","));
+    spec.split(':')
+        .map(|part| {
+            if part.trim().starts_with("checkpoint=") {
+                weights.as_str()
+            } else {
+                part
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+fn modified(path: &str) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
+
+fn read(path: &str) -> Result<Snapshot, Box<dyn Error>> {
+    let modified = modified(path);
+    let weights = serde_json::from_str(&fs::read_to_string(path)?)?;
+    Ok(Snapshot { modified, weights })
+}
+
+#[cfg(unix)]
+mod signal {
+    use std::sync::Once;
+    use std::sync::atomic::Ordering;
+
+    use super::SIGHUP;
+
+    extern "C" fn on_sighup(_: libc::c_int) {
+        SIGHUP.store(true, Ordering::Relaxed);
+    }
+
+    pub(super) fn install() {
+        static INSTALL: Once = Once::new();
+        INSTALL.call_once(|| {
+            let handler = on_sighup as extern "C" fn(libc::c_int);
+            // SAFETY: the handler only touches an atomic.
+            unsafe { libc::signal(libc::SIGHUP, handler as libc::sighandler_t) };
+        });
+    }
+}
+
+#[cfg(not(unix))]
+mod signal {
+    /// There is no SIGHUP on other platforms.
+    pub(super) fn install() {}
+}
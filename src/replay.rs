@@ -0,0 +1,418 @@
+//! Portable text records of complete games.
+//!
+//! A record is a header of `[Key "value"]` tags followed by the moves in [`Action`]
+//! notation, one numbered turn per line, and a result token:
+//!
+//! ```text
+//! [Players "2"]
+//! [Seed "42"]
+//! [StockSize "10"]
+//! [Bots "heuristic13 heuristic2"]
+//! [Result "P0"]
+//!
+//! 1. H0>B0 H3>B0 X1>D0
+//! 2. D0>B1 X4>D2
+//! P0
+//! ```
+//!
+//! `Players`, `Seed` and `Result` are required; `StockSize` and `HandSize` are written
+//! when the game overrode the standard stock or hand size, `SeatRules` (e.g.
+//! `"standard weak-draws"`) when some seat played with a handicap and `Rules` (e.g.
+//! `"must-play-stock stock-binned"`) under house rules, information variants, a loop
+//! guard (`loop-forbid`, `loop-end-turn`) or a custom cap on actions per turn
+//! (`turn-actions=N`). Any other tag is kept as free-form metadata. The result is `P<n>` for a win, `draw` or `*` for
+//! an unfinished game. Text after `;` on a line is a comment. The deal follows from the
+//! seed, so a record replays to the exact game.
+//!
+//! [`SavedGame`] pairs a record with a full snapshot of the engine; `simulate` writes one
+//! after every move of an interactive game so the session can be resumed.
+
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::action::{Action, PlayerId};
+use crate::game::{Game, LoopGuard, SeatRules};
+use crate::runner::Handicap;
+use crate::state::{GameStatus, StockVisibility};
+
+/// A complete or partial game in portable notation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GameRecord {
+    pub players: usize,
+    pub seed: u64,
+    pub stock_size: Option<usize>,
+    /// Cards per hand, when not the standard five.
+    pub hand_size: Option<usize>,
+    /// Rules per seat; empty when every seat plays by the standard rules.
+    pub seat_rules: Vec<SeatRules>,
+    /// Whether the game was played with the "must play stock" house rule.
+    pub must_play_stock: bool,
+    /// How precisely stock sizes were shown to the players.
+    pub stock_visibility: StockVisibility,
+    /// How loops within a turn were broken.
+    pub loop_guard: LoopGuard,
+    /// Actions per turn, when not the default cap.
+    pub max_actions_per_turn: Option<usize>,
+    /// Free-form tags in the order they are written, e.g. `("Bots", "heuristic13 random")`.
+    pub tags: Vec<(String, String)>,
+    pub moves: Vec<Action>,
+    pub result: GameStatus,
+}
+
+impl GameRecord {
+    /// Record every move of `game` so far. Fails when the game cannot be rebuilt from its
+    /// seed (injected decks, redealt hidden cards).
+    pub fn from_game(game: &Game) -> Result<Self, Box<dyn Error>> {
+        if !game.is_reproducible() {
+            return Err("game was not dealt from its seed and cannot be recorded".into());
+        }
+        let config = game.config();
+        let seat_rules: Vec<SeatRules> = PlayerId::all(config.num_players)
+            .map(|seat| game.seat_rules(seat))
+            .collect();
+        let handicapped = seat_rules.iter().any(|rules| !rules.is_standard());
+        Ok(Self {
+            players: config.num_players,
+            seed: config.seed,
+            stock_size: config.stock_size,
+            hand_size: config.hand_size,
+            seat_rules: if handicapped { seat_rules } else { Vec::new() },
+            must_play_stock: config.must_play_stock,
+            stock_visibility: config.stock_visibility,
+            loop_guard: config.loop_guard,
+            max_actions_per_turn: config.max_actions_per_turn,
+            tags: Vec::new(),
+            moves: game.history().iter().map(|(_, a)| a.clone()).collect(),
+            result: game.status(),
+        })
+    }
+
+    /// Add or replace a free-form tag.
+    pub fn with_tag(mut self, key: &str, value: &str) -> Self {
+        match self.tags.iter_mut().find(|(k, _)| k == key) {
+            Some(entry) => entry.1 = value.to_string(),
+            None => self.tags.push((key.to_string(), value.to_string())),
+        }
+        self
+    }
+
+    pub fn tag(&self, key: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Rebuild the game and apply every move. Fails on an illegal move or when the final
+    /// status does not match the recorded result.
+    pub fn replay(&self) -> Result<Game, Box<dyn Error>> {
+        self.replay_with(|_, _, _| {})
+    }
+
+    /// [`GameRecord::replay`], showing `on_move` the game, the acting player and their
+    /// move before each move is applied.
+    pub fn replay_with<F>(&self, mut on_move: F) -> Result<Game, Box<dyn Error>>
+    where
+        F: FnMut(&Game, PlayerId, &Action),
+    {
+        let mut builder = Game::builder(self.players)?
+            .with_seed(self.seed)
+            .with_must_play_stock(self.must_play_stock)
+            .with_stock_visibility(self.stock_visibility)
+            .with_loop_guard(self.loop_guard);
+        if let Some(stock) = self.stock_size {
+            builder = builder.with_stock_size(stock);
+        }
+        if let Some(hand) = self.hand_size {
+            builder = builder.with_hand_size(hand);
+        }
+        if let Some(limit) = self.max_actions_per_turn {
+            builder = builder.with_max_actions_per_turn(limit);
+        }
+        for (seat, rules) in self.seat_rules.iter().enumerate() {
+            builder = builder.with_seat_rules(PlayerId(seat), *rules);
+        }
+        let mut game = builder.build()?;
+        for (ply, action) in self.moves.iter().enumerate() {
+            let player = game.current_player();
+            on_move(&game, player, action);
+            game.apply_action(player, action.clone())
+                .map_err(|err| format!("move {} ({action}): {err}", ply + 1))?;
+        }
+        if game.status() != self.result {
+            return Err(format!(
+                "recorded result {} does not match replayed result {}",
+                result_token(self.result),
+                result_token(game.status())
+            )
+            .into());
+        }
+        Ok(game)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        fs::write(path, self.to_string())?;
+        Ok(())
+    }
+
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let text = fs::read_to_string(path)?;
+        text.parse::<Self>()
+            .map_err(|err| format!("{}: {err}", path.display()).into())
+    }
+}
+
+fn result_token(status: GameStatus) -> String {
+    match status {
+        GameStatus::Ongoing => String::from("*"),
+        GameStatus::Draw => String::from("draw"),
+        GameStatus::Finished { winner } => format!("P{winner}"),
+    }
+}
+
+fn parse_result(token: &str) -> Option<GameStatus> {
+    match token {
+        "*" => Some(GameStatus::Ongoing),
+        "draw" => Some(GameStatus::Draw),
+        _ => token
+            .strip_prefix('P')
+            .and_then(|n| n.parse::<usize>().ok())
+            .map(|winner| GameStatus::Finished {
+                winner: PlayerId(winner),
+            }),
+    }
+}
+
+impl fmt::Display for GameRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "[Players \"{}\"]", self.players)?;
+        writeln!(f, "[Seed \"{}\"]", self.seed)?;
+        if let Some(stock) = self.stock_size {
+            writeln!(f, "[StockSize \"{stock}\"]")?;
+        }
+        if let Some(hand) = self.hand_size {
+            writeln!(f, "[HandSize \"{hand}\"]")?;
+        }
+        if !self.seat_rules.is_empty() {
+            let rules: Vec<String> = self.seat_rules.iter().map(ToString::to_string).collect();
+            writeln!(f, "[SeatRules \"{}\"]", rules.join(" "))?;
+        }
+        let mut rules = Vec::new();
+        if self.must_play_stock {
+            rules.push("must-play-stock".to_string());
+        }
+        if self.stock_visibility != StockVisibility::Exact {
+            rules.push(format!("stock-{}", self.stock_visibility));
+        }
+        if self.loop_guard != LoopGuard::Off {
+            rules.push(format!("loop-{}", self.loop_guard));
+        }
+        if let Some(limit) = self.max_actions_per_turn {
+            rules.push(format!("turn-actions={limit}"));
+        }
+        if !rules.is_empty() {
+            writeln!(f, "[Rules \"{}\"]", rules.join(" "))?;
+        }
+        for (key, value) in &self.tags {
+            writeln!(f, "[{key} \"{}\"]", value.replace('"', "'"))?;
+        }
+        writeln!(f, "[Result \"{}\"]", result_token(self.result))?;
+        writeln!(f)?;
+        let mut turn = 1usize;
+        let mut line_open = false;
+        for action in &self.moves {
+            if !line_open {
+                write!(f, "{turn}.")?;
+                line_open = true;
+            }
+            write!(f, " {action}")?;
+            if !matches!(action, Action::Play { .. }) {
+                writeln!(f)?;
+                turn += 1;
+                line_open = false;
+            }
+        }
+        if line_open {
+            writeln!(f)?;
+        }
+        writeln!(f, "{}", result_token(self.result))
+    }
+}
+
+impl FromStr for GameRecord {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut players = None;
+        let mut seed = None;
+        let mut stock_size = None;
+        let mut hand_size = None;
+        let mut seat_rules = Vec::new();
+        let mut must_play_stock = false;
+        let mut stock_visibility = StockVisibility::Exact;
+        let mut loop_guard = LoopGuard::Off;
+        let mut max_actions_per_turn = None;
+        let mut tagged_result = None;
+        let mut tags = Vec::new();
+        let mut moves = Vec::new();
+        let mut final_token = None;
+        for (line_no, line) in s.lines().enumerate() {
+            let line = line.split(';').next().unwrap_or("").trim();
+            let at = |msg: String| format!("line {}: {msg}", line_no + 1);
+            if let Some(tag) = line.strip_prefix('[') {
+                let (key, value) = tag
+                    .strip_suffix(']')
+                    .and_then(|t| t.split_once(' '))
+                    .map(|(k, v)| (k.trim(), v.trim().trim_matches('"')))
+                    .ok_or_else(|| at(format!("malformed tag: {line}")))?;
+                let number = || {
+                    value
+                        .parse::<u64>()
+                        .map_err(|_| at(format!("{key} must be a number")))
+                };
+                match key {
+                    "Players" => players = Some(number()? as usize),
+                    "Seed" => seed = Some(number()?),
+                    "StockSize" => stock_size = Some(number()? as usize),
+                    "HandSize" => hand_size = Some(number()? as usize),
+                    "SeatRules" => {
+                        seat_rules = value
+                            .split_whitespace()
+                            .map(str::parse)
+                            .collect::<Result<_, _>>()
+                            .map_err(at)?
+                    }
+                    "Rules" => {
+                        for rule in value.split_whitespace() {
+                            match rule {
+                                "must-play-stock" => must_play_stock = true,
+                                _ if rule.starts_with("loop-") => {
+                                    loop_guard = rule["loop-".len()..]
+                                        .parse()
+                                        .map_err(|_| at(format!("unknown rule: {rule}")))?
+                                }
+                                _ if rule.starts_with("turn-actions=") => {
+                                    max_actions_per_turn = Some(
+                                        rule["turn-actions=".len()..]
+                                            .parse()
+                                            .map_err(|_| at(format!("unknown rule: {rule}")))?,
+                                    )
+                                }
+                                _ => {
+                                    stock_visibility = rule
+                                        .strip_prefix("stock-")
+                                        .and_then(|v| v.parse().ok())
+                                        .ok_or_else(|| at(format!("unknown rule: {rule}")))?
+                                }
+                            }
+                        }
+                    }
+                    "Result" => {
+                        tagged_result = Some(
+                            parse_result(value)
+                                .ok_or_else(|| at(format!("invalid result: {value}")))?,
+                        )
+                    }
+                    _ => tags.push((key.to_string(), value.to_string())),
+                }
+                continue;
+            }
+            for token in line.split_whitespace() {
+                if final_token.is_some() {
+                    return Err(at(format!("moves after the result: {token}")));
+                }
+                if token.ends_with('.') && token[..token.len() - 1].parse::<usize>().is_ok() {
+                    continue;
+                }
+                if let Some(result) = parse_result(token) {
+                    final_token = Some(result);
+                    continue;
+                }
+                moves.push(token.parse::<Action>().map_err(at)?);
+            }
+        }
+        let result = match (tagged_result, final_token) {
+            (Some(tagged), Some(last)) if tagged != last => {
+                return Err(String::from("result tag and final result disagree"));
+            }
+            (Some(result), _) | (None, Some(result)) => result,
+            (None, None) => return Err(String::from("missing result")),
+        };
+        Ok(Self {
+            players: players.ok_or("missing Players tag")?,
+            seed: seed.ok_or("missing Seed tag")?,
+            stock_size,
+            hand_size,
+            seat_rules,
+            must_play_stock,
+            stock_visibility,
+            loop_guard,
+            max_actions_per_turn,
+            tags,
+            moves,
+            result,
+        })
+    }
+}
+
+/// An interactive game in progress, saved so it can be resumed after a crash or an early
+/// quit.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SavedGame {
+    /// Bot spec per seat.
+    pub bots: Vec<String>,
+    /// Moves so far in portable notation, readable without the snapshot.
+    pub record: String,
+    /// Exact engine state, including the shuffle RNG.
+    pub game: Game,
+    /// Handicaps per seat, reapplied to the bots on resume.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub handicaps: Vec<Vec<Handicap>>,
+}
+
+impl SavedGame {
+    pub fn new(game: &Game, bots: &[String]) -> Result<Self, Box<dyn Error>> {
+        let record = GameRecord::from_game(game)?.with_tag("Bots", &bots.join(" "));
+        Ok(Self {
+            bots: bots.to_vec(),
+            record: record.to_string(),
+            game: game.clone(),
+            handicaps: Vec::new(),
+        })
+    }
+
+    /// Write the save through a temporary file and a rename, so a crash mid-write leaves
+    /// the previous save intact.
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        let partial = path.with_extension("partial");
+        fs::write(&partial, serde_json::to_string(self)?)?;
+        fs::rename(&partial, path)?;
+        Ok(())
+    }
+
+    /// Read a save and check that its record and snapshot agree.
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let text = fs::read_to_string(path)?;
+        let saved: Self =
+            serde_json::from_str(&text).map_err(|err| format!("{}: {err}", path.display()))?;
+        let record: GameRecord = saved
+            .record
+            .parse()
+            .map_err(|err| format!("{}: {err}", path.display()))?;
+        let history: Vec<Action> = saved
+            .game
+            .history()
+            .iter()
+            .map(|(_, a)| a.clone())
+            .collect();
+        if record.moves != history || saved.bots.len() != saved.game.settings().num_players {
+            return Err(format!("{}: record and snapshot disagree", path.display()).into());
+        }
+        Ok(saved)
+    }
+}
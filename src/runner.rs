@@ -0,0 +1,1311 @@
+//! Batch match runner shared by the CLI tools.
+//!
+//! [`MatchRunner`] plays a series of seeded games with rotating seats and produces a
+//! [`MatchReport`]: a single, versioned artifact holding the configuration, per-game
+//! results and per-bot metrics. Summaries and charts are rendered from the report, so a
+//! saved report can be re-rendered without re-running the simulation.
+
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io::Write;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use rand::seq::{IteratorRandom, SliceRandom};
+use serde::{Deserialize, Serialize};
+
+use crate::action::{Action, PlayerId};
+use crate::analysis::{BehaviorCounts, Fingerprint};
+use crate::archive::ArchiveWriter;
+use crate::bot::Bot;
+use crate::bots::{create_bot_from_spec, label_for_spec};
+use crate::error::GameError;
+use crate::game::{DeckStats, Game, SeatRules};
+use crate::interrupt::{Interrupted, Interrupter};
+use crate::reload::WeightReloader;
+use crate::replay::GameRecord;
+use crate::score::{PlayerScore, score_all};
+use crate::seeds::{SeedPool, SeedSplit};
+use crate::state::{GameSettings, GameStateView, GameStatus, StockVisibility};
+
+/// Version of the [`MatchReport`] JSON layout. Bump on incompatible changes.
+pub const MATCH_REPORT_VERSION: u32 = 2;
+/// Version of the [`RunState`] checkpoint layout.
+pub const RUN_STATE_VERSION: u32 = 2;
+
+/// Deterministically mix a base seed with two stream identifiers.
+pub fn mix_seed(base: u64, a: u64, b: u64) -> u64 {
+    // Simple reversible mixer (xorshift-like mix).
+    let mut z =
+        base ^ (a.wrapping_mul(0x9E37_79B97F4A7C15)) ^ (b.wrapping_mul(0xBF58_476D1CE4E5B9));
+    z ^= z >> 12;
+    z ^= z << 25;
+    z ^= z >> 27;
+    z
+}
+
+/// One decision reported by [`play_out`].
+#[derive(Clone, Copy)]
+pub struct Decision<'a> {
+    pub player: PlayerId,
+    pub state: &'a GameStateView,
+    pub legal: &'a [Action],
+    pub action: &'a Action,
+    /// Time the bot spent in `select_action`.
+    pub elapsed: Duration,
+    /// The game before the action, for observers that record what the deciding seat
+    /// cannot see (see [`Game::full_state_view`]).
+    pub game: &'a Game,
+}
+
+impl fmt::Debug for Decision<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Decision")
+            .field("player", &self.player)
+            .field("state", self.state)
+            .field("legal", &self.legal)
+            .field("action", self.action)
+            .field("elapsed", &self.elapsed)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Play `game` with one bot per seat until it finishes or `max_actions` actions have been
+/// taken, driving the bots' lifecycle hooks. `observe` sees every decision before its
+/// action is applied. Returns the number of actions taken.
+pub fn play_out<F>(
+    game: &mut Game,
+    bots: &mut [Box<dyn Bot>],
+    max_actions: usize,
+    observe: F,
+) -> Result<usize, Box<dyn Error>>
+where
+    F: FnMut(Decision<'_>),
+{
+    play_out_with(game, bots, max_actions, &Interrupter::new(), None, observe)
+}
+
+/// [`play_out`] under `interrupter`: every bot receives it before each decision, with a
+/// deadline `move_time` later if given. Once it is cancelled the game is abandoned
+/// (without `on_game_end`) and [`Interrupted`] is returned.
+pub fn play_out_with<F>(
+    game: &mut Game,
+    bots: &mut [Box<dyn Bot>],
+    max_actions: usize,
+    interrupter: &Interrupter,
+    move_time: Option<Duration>,
+    observe: F,
+) -> Result<usize, Box<dyn Error>>
+where
+    F: FnMut(Decision<'_>),
+{
+    drive(
+        game,
+        bots,
+        max_actions,
+        interrupter,
+        move_time,
+        None,
+        observe,
+    )
+    .map(|play| play.actions)
+}
+
+/// What [`play_out_guarded`] does when a bot faults.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FaultPolicy {
+    /// Stop with the [`Fault`] as the error, abandoning the game (and a batch with it).
+    #[default]
+    Abort,
+    /// End the game as a loss for the faulting seat. The opponent with the fewest stock
+    /// cards left wins, without points; stock counts and margins are scored as they stand.
+    Forfeit,
+    /// Play a random legal action for the faulting seat and go on.
+    RandomMove,
+}
+
+impl fmt::Display for FaultPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            FaultPolicy::Abort => "abort",
+            FaultPolicy::Forfeit => "forfeit",
+            FaultPolicy::RandomMove => "random-move",
+        })
+    }
+}
+
+impl FromStr for FaultPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "abort" => Ok(FaultPolicy::Abort),
+            "forfeit" => Ok(FaultPolicy::Forfeit),
+            "random-move" | "random" => Ok(FaultPolicy::RandomMove),
+            _ => Err(format!(
+                "invalid fault policy: {s} (expected abort, forfeit or random-move)"
+            )),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FaultKind {
+    /// `select_action` panicked.
+    Panic,
+    /// The bot chose an action that is not legal.
+    IllegalAction,
+    /// The decision took longer than the time limit.
+    Timeout,
+}
+
+/// A faulty decision of one seat.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Fault {
+    pub seat: PlayerId,
+    /// Actions applied before the decision.
+    pub ply: usize,
+    pub kind: FaultKind,
+    pub reason: String,
+}
+
+impl fmt::Display for Fault {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "seat {} at action {}: {}",
+            self.seat, self.ply, self.reason
+        )
+    }
+}
+
+impl Error for Fault {}
+
+/// Fault handling for [`play_out_guarded`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FaultGuard {
+    /// Policy for illegal actions and decisions over the time limit.
+    pub policy: FaultPolicy,
+    /// Policy for panics.
+    pub on_panic: FaultPolicy,
+    /// Longest a decision may take; no limit when unset.
+    pub time_limit: Option<Duration>,
+    /// Seed of the random moves of [`FaultPolicy::RandomMove`].
+    pub seed: u64,
+}
+
+/// How a [`play_out_guarded`] game went.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GuardedPlay {
+    /// Actions applied.
+    pub actions: usize,
+    /// Every fault, in order.
+    pub faults: Vec<Fault>,
+    /// The seat that lost by [`FaultPolicy::Forfeit`] and the seat awarded the win.
+    pub forfeit: Option<(PlayerId, PlayerId)>,
+}
+
+/// [`play_out_with`], catching bots that panic in `select_action`, choose an illegal
+/// action or overrun `guard.time_limit` and handling them by `guard.on_panic` or
+/// `guard.policy`. A bot keeps its seat after a panic, so it must not rely on state its
+/// interrupted `select_action` left half-updated. After a
+/// forfeit only the other seats get `on_game_end`, with the seat awarded the win.
+pub fn play_out_guarded<F>(
+    game: &mut Game,
+    bots: &mut [Box<dyn Bot>],
+    max_actions: usize,
+    interrupter: &Interrupter,
+    move_time: Option<Duration>,
+    guard: &FaultGuard,
+    observe: F,
+) -> Result<GuardedPlay, Box<dyn Error>>
+where
+    F: FnMut(Decision<'_>),
+{
+    drive(
+        game,
+        bots,
+        max_actions,
+        interrupter,
+        move_time,
+        Some(guard),
+        observe,
+    )
+}
+
+fn drive<F>(
+    game: &mut Game,
+    bots: &mut [Box<dyn Bot>],
+    max_actions: usize,
+    interrupter: &Interrupter,
+    move_time: Option<Duration>,
+    guard: Option<&FaultGuard>,
+    mut observe: F,
+) -> Result<GuardedPlay, Box<dyn Error>>
+where
+    F: FnMut(Decision<'_>),
+{
+    for (seat, bot) in PlayerId::all(bots.len()).zip(bots.iter_mut()) {
+        bot.on_game_start(&game.state_view(seat)?);
+    }
+    let mut play = GuardedPlay::default();
+    let mut rng = StdRng::seed_from_u64(guard.map_or(0, |guard| guard.seed));
+    let mut turn_player = None;
+    while !game.is_finished() && play.actions < max_actions {
+        interrupter.check()?;
+        let current = game.current_player();
+        let state = game.state_view(current)?;
+        let legal = game.legal_actions(current)?;
+        if legal.is_empty() {
+            return Err(GameError::InvalidConfiguration("no legal actions available").into());
+        }
+        // Every turn ends with a discard that passes play on, so a new player means a new turn.
+        if turn_player != Some(current) {
+            bots[current].on_turn_start(&state);
+            turn_player = Some(current);
+        }
+        if bots[current].wants_full_state() {
+            bots[current].observe_full_state(&game.full_state_view(current)?);
+        }
+        let t0 = Instant::now();
+        match move_time {
+            Some(budget) => bots[current].interrupt_with(&interrupter.with_budget(budget)),
+            None => bots[current].interrupt_with(interrupter),
+        }
+        let action = match guard {
+            None => bots[current].select_action(&state, &legal),
+            Some(guard) => {
+                let bot = &mut bots[current];
+                let chosen =
+                    panic::catch_unwind(AssertUnwindSafe(|| bot.select_action(&state, &legal)));
+                let elapsed = t0.elapsed();
+                let fault = match chosen {
+                    Err(payload) => Err((FaultKind::Panic, panic_reason(payload.as_ref()))),
+                    Ok(action) if !legal.contains(&action) => Err((
+                        FaultKind::IllegalAction,
+                        format!("chose the illegal action {action}"),
+                    )),
+                    Ok(_) if guard.time_limit.is_some_and(|limit| elapsed > limit) => Err((
+                        FaultKind::Timeout,
+                        format!(
+                            "took {elapsed:?}, over the limit of {:?}",
+                            guard.time_limit.unwrap_or_default()
+                        ),
+                    )),
+                    Ok(action) => Ok(action),
+                };
+                match fault {
+                    Ok(action) => action,
+                    Err((kind, reason)) => {
+                        let fault = Fault {
+                            seat: current,
+                            ply: play.actions,
+                            kind,
+                            reason,
+                        };
+                        play.faults.push(fault.clone());
+                        let policy = match kind {
+                            FaultKind::Panic => guard.on_panic,
+                            _ => guard.policy,
+                        };
+                        match policy {
+                            FaultPolicy::Abort => return Err(Box::new(fault)),
+                            FaultPolicy::Forfeit => {
+                                play.forfeit = Some((current, forfeit_winner(game, current)?));
+                                break;
+                            }
+                            FaultPolicy::RandomMove => legal
+                                .iter()
+                                .choose(&mut rng)
+                                .cloned()
+                                .expect("legal actions are not empty"),
+                        }
+                    }
+                }
+            }
+        };
+        observe(Decision {
+            player: current,
+            state: &state,
+            legal: &legal,
+            action: &action,
+            elapsed: t0.elapsed(),
+            game,
+        });
+        // Release the view's shares of the piles so the engine updates them in place.
+        drop(state);
+        game.apply_action(current, action)?;
+        play.actions += 1;
+    }
+    let winner = match play.forfeit {
+        Some((_, winner)) => Some(winner),
+        None => game.winner(),
+    };
+    for (seat, bot) in PlayerId::all(bots.len()).zip(bots.iter_mut()) {
+        if play.forfeit.is_none_or(|(loser, _)| loser != seat) {
+            bot.on_game_end(&game.state_view(seat)?, winner);
+        }
+    }
+    Ok(play)
+}
+
+/// The opponent of `loser` with the fewest stock cards left, the earliest seat on ties.
+fn forfeit_winner(game: &Game, loser: PlayerId) -> Result<PlayerId, GameError> {
+    let stocks = game.full_state_view(loser)?.stocks;
+    Ok(PlayerId::all(stocks.len())
+        .filter(|&seat| seat != loser)
+        .min_by_key(|&seat| stocks[seat.0].len())
+        .expect("games have at least two seats"))
+}
+
+/// Scores from the exact stock counts, which views may bin or hide. `winner` is credited
+/// even when the game was forfeited and so never reached its end, but a forfeit awards
+/// no points.
+fn final_scores(
+    game: &Game,
+    winner: Option<PlayerId>,
+    forfeited: bool,
+) -> Result<Vec<PlayerScore>, GameError> {
+    let full = game.full_state_view(winner.unwrap_or_default())?;
+    let mut view = full.view;
+    for (player, stock) in view.players.iter_mut().zip(&full.stocks) {
+        player.stock_count = stock.len();
+    }
+    if let Some(winner) = winner {
+        view.status = GameStatus::Finished { winner };
+    }
+    let mut scores = score_all(&view);
+    if forfeited {
+        for score in &mut scores {
+            score.points = 0;
+        }
+    }
+    Ok(scores)
+}
+
+fn panic_reason(payload: &(dyn std::any::Any + Send)) -> String {
+    let message = payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("no message");
+    format!("panicked: {message}")
+}
+
+/// Win rate of the bots `candidate` builds against `opponent` over `games` two-player
+/// games dealt from seeds `first_deal..` of `split`. Each deal is played twice, once from
+/// each seat; `candidate` receives the seat and the deal.
+pub fn head_to_head<F>(
+    mut candidate: F,
+    opponent: &str,
+    split: SeedSplit,
+    seed: u64,
+    first_deal: u64,
+    games: usize,
+    max_actions: usize,
+) -> Result<f64, Box<dyn Error>>
+where
+    F: FnMut(PlayerId, u64) -> Box<dyn Bot>,
+{
+    let pool = SeedPool::new(seed);
+    let mut wins = 0usize;
+    for game_index in 0..games {
+        let deal = first_deal + (game_index / 2) as u64;
+        let seat = PlayerId(game_index % 2);
+        let mut game = Game::builder(2)?
+            .with_seed(pool.seed(split, deal))
+            .build()?;
+        let ours = candidate(seat, deal);
+        let rival = create_bot_from_spec(opponent, PlayerId(1 - seat.0), mix_seed(seed, deal, 1))?;
+        let mut bots = if seat.0 == 0 {
+            vec![ours, rival]
+        } else {
+            vec![rival, ours]
+        };
+        play_out(&mut game, &mut bots, max_actions, |_| {})?;
+        if game.winner() == Some(seat) {
+            wins += 1;
+        }
+    }
+    Ok(wins as f64 / games.max(1) as f64)
+}
+
+/// Disadvantage given to one bot in every game of a match, e.g. to even out human vs.
+/// bot play.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Handicap {
+    /// Skip-Bo cards the bot draws are swapped out; see [`SeatRules::weak_draws`].
+    WeakDraws,
+    /// The bot's opponents each get a fifth discard pile.
+    OpponentExtraDiscard,
+    /// Search at most this many positions per decision; see [`Bot::limit_search`].
+    NodeBudget(usize),
+    /// The bot's stock holds exactly this many Skip-Bo cards; see
+    /// [`SeatRules::stock_wilds`].
+    StockWilds(usize),
+}
+
+impl Handicap {
+    /// Adjust the seat rules of a game where the handicapped bot sits at `seat`.
+    pub fn apply_rules(self, seat: PlayerId, rules: &mut [SeatRules]) {
+        match self {
+            Handicap::WeakDraws => rules[seat].weak_draws = true,
+            Handicap::OpponentExtraDiscard => {
+                for (other, rules) in rules.iter_mut().enumerate() {
+                    if other != seat.0 {
+                        rules.extra_discard_pile = true;
+                    }
+                }
+            }
+            Handicap::NodeBudget(_) => {}
+            Handicap::StockWilds(wilds) => rules[seat].stock_wilds = Some(wilds),
+        }
+    }
+
+    /// Apply the handicap to the bot itself.
+    pub fn apply_bot(self, bot: &mut dyn Bot) {
+        if let Handicap::NodeBudget(nodes) = self {
+            bot.limit_search(nodes);
+        }
+    }
+}
+
+impl fmt::Display for Handicap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Handicap::WeakDraws => f.write_str("weak-draws"),
+            Handicap::OpponentExtraDiscard => f.write_str("opponent-extra-discard"),
+            Handicap::NodeBudget(nodes) => write!(f, "nodes={nodes}"),
+            Handicap::StockWilds(wilds) => write!(f, "stock-wilds={wilds}"),
+        }
+    }
+}
+
+impl FromStr for Handicap {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().to_ascii_lowercase();
+        match s.as_str() {
+            "weak-draws" => Ok(Handicap::WeakDraws),
+            "opponent-extra-discard" => Ok(Handicap::OpponentExtraDiscard),
+            _ => s
+                .strip_prefix("nodes=")
+                .and_then(|n| n.parse::<usize>().ok())
+                .filter(|&n| n > 0)
+                .map(Handicap::NodeBudget)
+                .or_else(|| {
+                    s.strip_prefix("stock-wilds=")
+                        .and_then(|n| n.parse().ok())
+                        .map(Handicap::StockWilds)
+                })
+                .ok_or_else(|| {
+                    format!(
+                        "invalid handicap: {s} (expected weak-draws, opponent-extra-discard, nodes=N or stock-wilds=N)"
+                    )
+                }),
+        }
+    }
+}
+
+/// Parse `INDEX:HANDICAP`, e.g. `1:nodes=2000`, as used by the CLI tools.
+pub fn parse_handicap_entry(s: &str) -> Result<(usize, Handicap), String> {
+    let (index, handicap) = s
+        .split_once(':')
+        .ok_or_else(|| format!("expected INDEX:HANDICAP, got {s}"))?;
+    let index = index
+        .trim()
+        .parse::<usize>()
+        .map_err(|_| format!("invalid bot index: {index}"))?;
+    Ok((index, handicap.parse()?))
+}
+
+/// Settings for a batch of games.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MatchConfig {
+    /// Bot specs, one per seat (2-6).
+    pub bots: Vec<String>,
+    pub games: usize,
+    pub seed: u64,
+    /// Safety cap on actions per game; games exceeding it are aborted.
+    pub max_turns: usize,
+    pub stock_size: Option<usize>,
+    /// Draw deck seeds from this [`SeedPool`] universe instead of the unrestricted
+    /// seed space, keeping evaluation decks disjoint from training decks.
+    #[serde(default)]
+    pub seed_split: Option<SeedSplit>,
+    /// Handicaps per entry of `bots`; they follow the bot as seats rotate. May be shorter
+    /// than `bots`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub handicaps: Vec<Vec<Handicap>>,
+    /// Play under the "must play stock" house rule.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub must_play_stock: bool,
+    /// How precisely the bots see stock sizes.
+    #[serde(default, skip_serializing_if = "is_exact")]
+    pub stock_visibility: StockVisibility,
+    /// Time budget per decision in milliseconds; search bots settle for their best move
+    /// so far once it runs out. Results then depend on the machine's speed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub move_time_ms: Option<u64>,
+    /// Hard limit per decision in milliseconds; slower decisions are faults.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_limit_ms: Option<u64>,
+    /// What an illegal action or a decision over the time limit does to its game.
+    #[serde(default, skip_serializing_if = "is_abort")]
+    pub on_fault: FaultPolicy,
+    /// What a bot panicking in `select_action` does to its game. By default the seat
+    /// plays a random legal action instead, so one buggy bot cannot end a long run.
+    #[serde(default = "default_on_panic", skip_serializing_if = "is_random_move")]
+    pub on_panic: FaultPolicy,
+}
+
+fn is_exact(visibility: &StockVisibility) -> bool {
+    *visibility == StockVisibility::Exact
+}
+
+fn is_abort(policy: &FaultPolicy) -> bool {
+    *policy == FaultPolicy::Abort
+}
+
+fn default_on_panic() -> FaultPolicy {
+    FaultPolicy::RandomMove
+}
+
+fn is_random_move(policy: &FaultPolicy) -> bool {
+    *policy == FaultPolicy::RandomMove
+}
+
+impl MatchConfig {
+    pub fn new(bots: Vec<String>, games: usize, seed: u64) -> Self {
+        Self {
+            bots,
+            games,
+            seed,
+            max_turns: 2000,
+            stock_size: None,
+            seed_split: None,
+            handicaps: Vec::new(),
+            must_play_stock: false,
+            stock_visibility: StockVisibility::Exact,
+            move_time_ms: None,
+            time_limit_ms: None,
+            on_fault: FaultPolicy::Abort,
+            on_panic: FaultPolicy::RandomMove,
+        }
+    }
+
+    /// Handicap the bot at `index` of `bots`.
+    pub fn add_handicap(&mut self, index: usize, handicap: Handicap) {
+        if self.handicaps.len() <= index {
+            self.handicaps.resize(index + 1, Vec::new());
+        }
+        self.handicaps[index].push(handicap);
+    }
+}
+
+/// Outcome of a single game.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GameResult {
+    pub game_index: usize,
+    pub deck_seed: u64,
+    /// Bot label seated at each player index.
+    pub seats: Vec<String>,
+    pub winner: Option<PlayerId>,
+    /// Actions applied before the game ended or hit the action cap.
+    pub actions: usize,
+    /// Turns begun before the game ended, the last one included.
+    pub turns: usize,
+    pub scores: Vec<PlayerScore>,
+    pub duration_ms: f64,
+    /// Decision counts per player index, feeding [`BotMetrics::fingerprint`].
+    #[serde(default)]
+    pub behavior: Vec<BehaviorCounts>,
+    /// Reshuffles, draw pile sizes and starved refills of the game.
+    #[serde(default)]
+    pub deck: DeckStats,
+    /// Faulty decisions, handled by [`MatchConfig::on_fault`].
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub faults: Vec<Fault>,
+    /// The seat that forfeited the game; `winner` is then the seat awarded the win, with
+    /// no points.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub forfeited: Option<PlayerId>,
+    /// Weight files a [`WeightReloader`] read again before this game.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub reloaded: Vec<String>,
+}
+
+impl GameResult {
+    pub fn winner_label(&self) -> Option<&str> {
+        self.winner.map(|w| self.seats[w].as_str())
+    }
+}
+
+/// Aggregated metrics for one bot label across all games.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct BotMetrics {
+    pub label: String,
+    pub seats: usize,
+    pub wins: usize,
+    pub total_points: u64,
+    pub decisions: usize,
+    pub decision_time_ns: u128,
+    /// Sum of [`PlayerScore::margin`] over every seat occupied.
+    #[serde(default)]
+    pub total_margin: i64,
+    /// Sum of game lengths (in turns) over the games this bot won.
+    #[serde(default)]
+    pub win_turns: u64,
+    /// Sum of game lengths (in actions) over the games this bot won.
+    #[serde(default)]
+    pub win_actions: u64,
+    /// Seats occupied in games another player won.
+    #[serde(default)]
+    pub losses: usize,
+    /// Sum of stock cards left over those losses.
+    #[serde(default)]
+    pub loss_stock_left: u64,
+    /// Playing style summarized over every seat occupied.
+    #[serde(default)]
+    pub fingerprint: Fingerprint,
+    /// Faulty decisions over all games.
+    #[serde(default)]
+    pub faults: usize,
+    /// Those of them that panicked.
+    #[serde(default)]
+    pub panics: usize,
+    /// Games lost by forfeit.
+    #[serde(default)]
+    pub forfeits: usize,
+}
+
+impl BotMetrics {
+    /// Wins per seat occupied.
+    pub fn win_rate(&self) -> f64 {
+        if self.seats > 0 {
+            self.wins as f64 / self.seats as f64
+        } else {
+            0.0
+        }
+    }
+
+    pub fn avg_points(&self) -> f64 {
+        if self.seats > 0 {
+            self.total_points as f64 / self.seats as f64
+        } else {
+            0.0
+        }
+    }
+
+    /// Average stock margin against the best opponent, over all seats.
+    pub fn avg_margin(&self) -> f64 {
+        if self.seats > 0 {
+            self.total_margin as f64 / self.seats as f64
+        } else {
+            0.0
+        }
+    }
+
+    /// Average number of turns, by every player, in games this bot won.
+    pub fn avg_turns_to_win(&self) -> f64 {
+        if self.wins > 0 {
+            self.win_turns as f64 / self.wins as f64
+        } else {
+            0.0
+        }
+    }
+
+    /// Average number of actions, by every player, in games this bot won.
+    pub fn avg_actions_to_win(&self) -> f64 {
+        if self.wins > 0 {
+            self.win_actions as f64 / self.wins as f64
+        } else {
+            0.0
+        }
+    }
+
+    /// Average stock cards left in games another player won.
+    pub fn avg_stock_left_when_losing(&self) -> f64 {
+        if self.losses > 0 {
+            self.loss_stock_left as f64 / self.losses as f64
+        } else {
+            0.0
+        }
+    }
+
+    pub fn avg_decision_ms(&self) -> f64 {
+        if self.decisions > 0 {
+            self.decision_time_ns as f64 / 1.0e6 / self.decisions as f64
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Weights of the composite objective used to rank bots beyond raw win rate.
+///
+/// The composite is `win * win_rate + margin * avg_margin / stock - turns_to_win *
+/// turns_to_win_penalty - loss_stock * avg_stock_left_when_losing / stock`, so every term
+/// is roughly on the scale of a win rate; see [`MatchReport::turns_to_win_penalty`].
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ObjectiveWeights {
+    pub win: f64,
+    pub margin: f64,
+    pub turns_to_win: f64,
+    pub loss_stock: f64,
+}
+
+impl Default for ObjectiveWeights {
+    fn default() -> Self {
+        Self {
+            win: 1.0,
+            margin: 0.5,
+            turns_to_win: 0.1,
+            loss_stock: 0.25,
+        }
+    }
+}
+
+impl std::str::FromStr for ObjectiveWeights {
+    type Err = String;
+
+    /// Parse `key=value` pairs separated by commas, e.g. `win=1,margin=0.5`. Keys left
+    /// out keep their default weight.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut weights = Self::default();
+        for part in s.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let (key, value) = part
+                .split_once('=')
+                .ok_or_else(|| format!("expected key=value, got {part}"))?;
+            let value: f64 = value
+                .trim()
+                .parse()
+                .map_err(|_| format!("invalid weight for {key}: {value}"))?;
+            match key.trim() {
+                "win" => weights.win = value,
+                "margin" => weights.margin = value,
+                "turns_to_win" | "turns-to-win" => weights.turns_to_win = value,
+                "loss_stock" | "loss-stock" => weights.loss_stock = value,
+                other => return Err(format!("unknown objective: {other}")),
+            }
+        }
+        Ok(weights)
+    }
+}
+
+/// Everything produced by a batch run.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MatchReport {
+    pub version: u32,
+    pub config: MatchConfig,
+    pub games: Vec<GameResult>,
+    /// Per-bot metrics, sorted by win rate (descending) then label.
+    pub bots: Vec<BotMetrics>,
+    pub aborted_games: usize,
+    pub elapsed_ms: f64,
+}
+
+impl MatchReport {
+    /// Build a report from finished games, deriving the per-bot metrics.
+    pub fn from_games(
+        config: MatchConfig,
+        games: Vec<GameResult>,
+        decisions: &BTreeMap<String, (usize, u128)>,
+        elapsed_ms: f64,
+    ) -> Self {
+        let mut metrics: BTreeMap<String, BotMetrics> = BTreeMap::new();
+        let mut behavior: BTreeMap<String, BehaviorCounts> = BTreeMap::new();
+        let mut aborted_games = 0;
+        for game in &games {
+            for (label, counts) in game.seats.iter().zip(&game.behavior) {
+                behavior.entry(label.clone()).or_default().merge(counts);
+            }
+            for (seat, label) in PlayerId::all(game.seats.len()).zip(&game.seats) {
+                let entry = metrics.entry(label.clone()).or_default();
+                entry.label = label.clone();
+                entry.seats += 1;
+                let score = game.scores.iter().find(|s| s.player == seat);
+                entry.total_margin += score.map_or(0, |s| s.margin as i64);
+                if game.winner.is_some_and(|w| w != seat) {
+                    entry.losses += 1;
+                    entry.loss_stock_left += score.map_or(0, |s| s.stock_left as u64);
+                }
+            }
+            for fault in &game.faults {
+                let entry = metrics.entry(game.seats[fault.seat].clone()).or_default();
+                entry.faults += 1;
+                entry.panics += usize::from(fault.kind == FaultKind::Panic);
+            }
+            if let Some(seat) = game.forfeited {
+                metrics
+                    .entry(game.seats[seat].clone())
+                    .or_default()
+                    .forfeits += 1;
+            }
+            match game.winner {
+                Some(winner) => {
+                    let entry = metrics.entry(game.seats[winner].clone()).or_default();
+                    entry.wins += 1;
+                    entry.win_turns += game.turns as u64;
+                    entry.win_actions += game.actions as u64;
+                    entry.total_points += game
+                        .scores
+                        .iter()
+                        .find(|s| s.player == winner)
+                        .map_or(0, |s| s.points as u64);
+                }
+                None => aborted_games += 1,
+            }
+        }
+        for (label, counts) in &behavior {
+            if let Some(entry) = metrics.get_mut(label) {
+                entry.fingerprint = Fingerprint::from_counts(counts);
+            }
+        }
+        for (label, (count, ns)) in decisions {
+            if let Some(entry) = metrics.get_mut(label) {
+                entry.decisions = *count;
+                entry.decision_time_ns = *ns;
+            }
+        }
+        let mut bots: Vec<BotMetrics> = metrics.into_values().collect();
+        bots.sort_by(|a, b| {
+            b.win_rate()
+                .partial_cmp(&a.win_rate())
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.label.cmp(&b.label))
+        });
+        Self {
+            version: MATCH_REPORT_VERSION,
+            config,
+            games,
+            bots,
+            aborted_games,
+            elapsed_ms,
+        }
+    }
+
+    /// Draw pile statistics summed over every game.
+    pub fn deck_stats(&self) -> DeckStats {
+        let mut total = DeckStats::default();
+        for game in &self.games {
+            total.merge(&game.deck);
+        }
+        total
+    }
+
+    /// Stock size the games were played with, used to normalize stock-based objectives.
+    pub fn stock_size(&self) -> usize {
+        self.config.stock_size.unwrap_or_else(|| {
+            GameSettings::new(self.config.bots.len()).map_or(30, |settings| settings.stock_size)
+        })
+    }
+
+    /// Composite objective of `bot` under `weights`; see [`ObjectiveWeights`].
+    pub fn composite_score(&self, bot: &BotMetrics, weights: &ObjectiveWeights) -> f64 {
+        let stock = self.stock_size().max(1) as f64;
+        weights.win * bot.win_rate() + weights.margin * bot.avg_margin() / stock
+            - weights.turns_to_win * self.turns_to_win_penalty(bot)
+            - weights.loss_stock * bot.avg_stock_left_when_losing() / stock
+    }
+
+    /// `bot`'s average turns to win relative to the mean length of the report's games. A
+    /// bot without wins is charged the longest game instead, so never winning costs more
+    /// than winning slowly.
+    pub fn turns_to_win_penalty(&self, bot: &BotMetrics) -> f64 {
+        let games = self.games.len().max(1) as f64;
+        let mean_turns = self.games.iter().map(|game| game.turns).sum::<usize>() as f64 / games;
+        let turns = if bot.wins > 0 {
+            bot.avg_turns_to_win()
+        } else {
+            self.games.iter().map(|game| game.turns).max().unwrap_or(0) as f64
+        };
+        turns / mean_turns.max(1.0)
+    }
+
+    /// Bots with their composite score, best first (ties broken by label).
+    pub fn ranked_by(&self, weights: &ObjectiveWeights) -> Vec<(&BotMetrics, f64)> {
+        let mut ranked: Vec<(&BotMetrics, f64)> = self
+            .bots
+            .iter()
+            .map(|bot| (bot, self.composite_score(bot, weights)))
+            .collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.label.cmp(&b.0.label)));
+        ranked
+    }
+
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, Box<dyn Error>> {
+        let version = layout_version(json)?;
+        if version != MATCH_REPORT_VERSION {
+            return Err(format!(
+                "unsupported match report version {version} (expected {MATCH_REPORT_VERSION})"
+            )
+            .into());
+        }
+        let report: Self = serde_json::from_str(json)?;
+        // Re-derive metrics so reports written before a metric existed still carry it.
+        let decisions: BTreeMap<String, (usize, u128)> = report
+            .bots
+            .iter()
+            .map(|bot| (bot.label.clone(), (bot.decisions, bot.decision_time_ns)))
+            .collect();
+        Ok(Self::from_games(
+            report.config,
+            report.games,
+            &decisions,
+            report.elapsed_ms,
+        ))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        fs::write(path, self.to_json()?)?;
+        Ok(())
+    }
+
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        Self::from_json(&fs::read_to_string(path)?)
+    }
+}
+
+/// The `version` field of a versioned JSON layout, read before the rest so that an older
+/// layout is reported as such rather than as a missing field.
+fn layout_version(json: &str) -> Result<u32, serde_json::Error> {
+    #[derive(Deserialize)]
+    struct Versioned {
+        version: u32,
+    }
+    serde_json::from_str::<Versioned>(json).map(|versioned| versioned.version)
+}
+
+/// Progress of an interrupted batch, written periodically so a run can resume.
+///
+/// Every game is seeded from the base seed and its index, so the index of the next game
+/// is the only RNG cursor needed to continue exactly where the run stopped.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RunState {
+    pub version: u32,
+    pub config: MatchConfig,
+    pub next_game: usize,
+    pub games: Vec<GameResult>,
+    /// Decision count and total nanoseconds per bot label.
+    pub decisions: BTreeMap<String, (usize, u128)>,
+    /// Wall-clock time spent in earlier sessions.
+    pub elapsed_ms: f64,
+}
+
+impl RunState {
+    pub fn new(config: MatchConfig) -> Self {
+        Self {
+            version: RUN_STATE_VERSION,
+            config,
+            next_game: 0,
+            games: Vec::new(),
+            decisions: BTreeMap::new(),
+            elapsed_ms: 0.0,
+        }
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.next_game >= self.config.games
+    }
+
+    /// Write the state atomically (temp file + rename) so an interruption mid-write
+    /// never leaves a truncated checkpoint behind.
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, serde_json::to_string(self)?)?;
+        fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let json = fs::read_to_string(path)?;
+        let version = layout_version(&json)?;
+        if version != RUN_STATE_VERSION {
+            return Err(format!(
+                "unsupported run state version {version} (expected {RUN_STATE_VERSION})"
+            )
+            .into());
+        }
+        Ok(serde_json::from_str(&json)?)
+    }
+
+    pub fn into_report(self) -> MatchReport {
+        MatchReport::from_games(self.config, self.games, &self.decisions, self.elapsed_ms)
+    }
+}
+
+/// Runs batches of seeded games described by a [`MatchConfig`].
+///
+/// Once the runner's [`Interrupter`] is cancelled, the game in progress is abandoned and
+/// batch methods return [`Interrupted`]; games finished before stay in the [`RunState`]
+/// (and in the checkpoint of [`MatchRunner::run_resumable`]). Bots that panic, choose
+/// illegal actions or overrun [`MatchConfig::time_limit_ms`] are handled by
+/// [`MatchConfig::on_panic`] and [`MatchConfig::on_fault`], and their faults land in the
+/// [`GameResult`]s.
+pub struct MatchRunner {
+    config: MatchConfig,
+    interrupter: Interrupter,
+    reloader: Option<WeightReloader>,
+}
+
+impl MatchRunner {
+    pub fn new(config: MatchConfig) -> Result<Self, Box<dyn Error>> {
+        if config.bots.len() < 2 || config.bots.len() > 6 {
+            return Err(format!(
+                "expected between 2 and 6 players, received {}",
+                config.bots.len()
+            )
+            .into());
+        }
+        if config.stock_size == Some(0) {
+            return Err("stock-size must be positive".into());
+        }
+        if config.handicaps.len() > config.bots.len() {
+            return Err("more handicap entries than bots".into());
+        }
+        Ok(Self {
+            config,
+            interrupter: Interrupter::new(),
+            reloader: None,
+        })
+    }
+
+    /// Stop the batch when `interrupter` is cancelled.
+    pub fn with_interrupter(mut self, interrupter: Interrupter) -> Self {
+        self.interrupter = interrupter;
+        self
+    }
+
+    /// Read the weight files of the bots through `reloader`, which decides between games
+    /// whether they changed.
+    pub fn with_weight_reloader(mut self, reloader: WeightReloader) -> Self {
+        self.reloader = Some(reloader);
+        self
+    }
+
+    pub fn config(&self) -> &MatchConfig {
+        &self.config
+    }
+
+    /// Play every game and collect the report.
+    pub fn run(&self) -> Result<MatchReport, Box<dyn Error>> {
+        let mut state = RunState::new(self.config.clone());
+        self.advance(&mut state, self.config.games)?;
+        Ok(state.into_report())
+    }
+
+    /// Play up to `max_games` further games of the batch, updating `state` in place.
+    pub fn advance(&self, state: &mut RunState, max_games: usize) -> Result<(), Box<dyn Error>> {
+        if state.config != self.config {
+            return Err("run state was recorded with a different configuration".into());
+        }
+        let started = Instant::now();
+        let end = (state.next_game + max_games).min(self.config.games);
+        let mut outcome = Ok(());
+        while state.next_game < end {
+            match self.play_game(state.next_game, &mut state.decisions) {
+                Ok(result) => state.games.push(result),
+                Err(err) => {
+                    outcome = Err(err);
+                    break;
+                }
+            }
+            state.next_game += 1;
+        }
+        state.elapsed_ms += started.elapsed().as_secs_f64() * 1_000.0;
+        outcome
+    }
+
+    /// Run the batch, checkpointing to `path` every `every` games. When `path` already
+    /// holds a checkpoint for the same configuration, the run resumes from it.
+    pub fn run_resumable(&self, path: &Path, every: usize) -> Result<MatchReport, Box<dyn Error>> {
+        let mut state = if path.exists() {
+            RunState::load(path)?
+        } else {
+            RunState::new(self.config.clone())
+        };
+        if state.config != self.config {
+            return Err("run state was recorded with a different configuration".into());
+        }
+        while !state.is_complete() {
+            match self.advance(&mut state, every.max(1)) {
+                Ok(()) => state.save(path)?,
+                Err(err) if err.is::<Interrupted>() => {
+                    state.save(path)?;
+                    return Err(err);
+                }
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(state.into_report())
+    }
+
+    /// Play every game like [`MatchRunner::run`] and store each one in `archive`, tagged
+    /// with its game index and the bot labels by seat.
+    pub fn run_archived<W: Write>(
+        &self,
+        archive: &mut ArchiveWriter<W>,
+    ) -> Result<MatchReport, Box<dyn Error>> {
+        let mut state = RunState::new(self.config.clone());
+        let started = Instant::now();
+        while !state.is_complete() {
+            let (result, game) = self.play_recorded(state.next_game, &mut state.decisions)?;
+            let record = GameRecord::from_game(&game)?
+                .with_tag("Game", &result.game_index.to_string())
+                .with_tag("Bots", &result.seats.join(" "));
+            archive.push(&record)?;
+            state.games.push(result);
+            state.next_game += 1;
+        }
+        state.elapsed_ms = started.elapsed().as_secs_f64() * 1_000.0;
+        Ok(state.into_report())
+    }
+
+    /// Play a single game of the batch. Seating is permuted per game for fairness.
+    pub fn play_game(
+        &self,
+        game_index: usize,
+        decisions: &mut BTreeMap<String, (usize, u128)>,
+    ) -> Result<GameResult, Box<dyn Error>> {
+        Ok(self.play_recorded(game_index, decisions)?.0)
+    }
+
+    /// [`MatchRunner::play_game`], also handing back the finished game.
+    fn play_recorded(
+        &self,
+        game_index: usize,
+        decisions: &mut BTreeMap<String, (usize, u128)>,
+    ) -> Result<(GameResult, Game), Box<dyn Error>> {
+        let mut order: Vec<usize> = (0..self.config.bots.len()).collect();
+        let mut seat_rng =
+            StdRng::seed_from_u64(self.config.seed ^ 0x9E37_79B9 ^ (game_index as u64));
+        order.shuffle(&mut seat_rng);
+        self.play_seated(game_index, &order, decisions)
+    }
+
+    /// Play game `game_index` with `config.bots[order[seat]]` at each seat instead of a
+    /// shuffled seating, for schedulers that balance seats themselves.
+    pub fn play_seated(
+        &self,
+        game_index: usize,
+        order: &[usize],
+        decisions: &mut BTreeMap<String, (usize, u128)>,
+    ) -> Result<(GameResult, Game), Box<dyn Error>> {
+        let config = &self.config;
+        let base_seed = config.seed;
+        let players = config.bots.len();
+        let mut placed = order.to_vec();
+        placed.sort_unstable();
+        if !placed.iter().copied().eq(0..players) {
+            return Err("seating order must place every bot of the batch once".into());
+        }
+        let started = Instant::now();
+
+        let deck_seed = match config.seed_split {
+            Some(split) => SeedPool::new(base_seed).seed(split, game_index as u64),
+            None => mix_seed(base_seed, game_index as u64, 0x005E_ED15),
+        };
+        let handicaps = |src_idx: usize| config.handicaps.get(src_idx).into_iter().flatten();
+        let mut rules = vec![SeatRules::default(); players];
+        for (seat, &src_idx) in order.iter().enumerate() {
+            for handicap in handicaps(src_idx) {
+                handicap.apply_rules(PlayerId(seat), &mut rules);
+            }
+        }
+        let mut builder = Game::builder(players)?
+            .with_seed(deck_seed)
+            .with_must_play_stock(config.must_play_stock)
+            .with_stock_visibility(config.stock_visibility);
+        if let Some(stock) = config.stock_size {
+            builder = builder.with_stock_size(stock);
+        }
+        for (seat, rules) in rules.into_iter().enumerate() {
+            builder = builder.with_seat_rules(PlayerId(seat), rules);
+        }
+        let mut game = builder.build()?;
+
+        let (specs, reloaded) = match &self.reloader {
+            Some(reloader) => reloader.resolve(&config.bots)?,
+            None => (config.bots.clone(), Vec::new()),
+        };
+        let mut bots: Vec<Box<dyn Bot>> = Vec::with_capacity(players);
+        let mut seats: Vec<String> = Vec::with_capacity(players);
+        for (seat, &src_idx) in order.iter().enumerate() {
+            let spec = &config.bots[src_idx];
+            let bot_seed = mix_seed(base_seed, game_index as u64, seat as u64);
+            let mut bot = create_bot_from_spec(&specs[src_idx], PlayerId(seat), bot_seed)?;
+            for handicap in handicaps(src_idx) {
+                handicap.apply_bot(bot.as_mut());
+            }
+            bots.push(bot);
+            seats.push(label_for_spec(spec));
+        }
+
+        let mut behavior = vec![BehaviorCounts::default(); players];
+        let move_time = config.move_time_ms.map(Duration::from_millis);
+        // Timings only count once the game completes, so an abandoned game leaves none.
+        let mut timings: BTreeMap<String, (usize, u128)> = BTreeMap::new();
+        let guard = FaultGuard {
+            policy: config.on_fault,
+            on_panic: config.on_panic,
+            time_limit: config.time_limit_ms.map(Duration::from_millis),
+            seed: mix_seed(base_seed, game_index as u64, 0xFA_017),
+        };
+        let play = play_out_guarded(
+            &mut game,
+            &mut bots,
+            config.max_turns,
+            &self.interrupter,
+            move_time,
+            &guard,
+            |decision| {
+                let entry = timings.entry(seats[decision.player].clone()).or_default();
+                entry.0 += 1;
+                entry.1 += decision.elapsed.as_nanos();
+                behavior[decision.player].record(decision.state, decision.legal, decision.action);
+            },
+        )?;
+        for (label, (count, nanos)) in timings {
+            let entry = decisions.entry(label).or_default();
+            entry.0 += count;
+            entry.1 += nanos;
+        }
+
+        let winner = match play.forfeit {
+            Some((_, winner)) => Some(winner),
+            None => game.winner(),
+        };
+        let scores = final_scores(&game, winner, play.forfeit.is_some())?;
+        let result = GameResult {
+            game_index,
+            deck_seed,
+            seats,
+            winner,
+            actions: play.actions,
+            turns: game.turn_number() + 1,
+            scores,
+            duration_ms: started.elapsed().as_secs_f64() * 1_000.0,
+            behavior,
+            deck: game.deck_stats(),
+            faults: play.faults,
+            forfeited: play.forfeit.map(|(loser, _)| loser),
+            reloaded,
+        };
+        Ok((result, game))
+    }
+}
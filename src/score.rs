@@ -4,9 +4,31 @@
 //!   points = 25 (base win) + 5 * (sum of opponents' remaining stock cards)
 //! Non-winning players receive 0 points.
 //! Drawn / aborted games award no points.
+//!
+//! [`score_all`] extends this to a per-player view (stock progress and margin) that
+//! reports and reward functions can share.
+
+use serde::{Deserialize, Serialize};
 
 use crate::action::PlayerId;
-use crate::state::GameStateView;
+use crate::state::{GameStateView, GameStatus};
+
+/// Per-player scoring summary derived from a game state view.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlayerScore {
+    pub player: PlayerId,
+    /// Cards remaining in the player's stock pile.
+    pub stock_left: usize,
+    /// Stock cards the player has played so far.
+    pub cards_played: usize,
+    /// Points awarded (non-zero only for the winner of a finished game).
+    pub points: usize,
+    /// Fewest stock cards left among the opponents minus the player's own stock left.
+    /// Positive when the player is ahead; the winner's margin equals the runner-up's
+    /// remaining stock.
+    pub margin: isize,
+    pub is_winner: bool,
+}
 
 /// Compute winner's points for a finished game view.
 ///
@@ -22,23 +44,71 @@ pub fn winner_points(state: &GameStateView, winner: PlayerId) -> usize {
     25 + 5 * opponents_stock_total
 }
 
+/// Final result for `seat` of a game won by `winner`, as the training sets label it: 1.0 a
+/// win, -1.0 a loss, 0.0 a draw or a game cut off without a winner.
+pub fn seat_outcome(winner: Option<PlayerId>, seat: PlayerId) -> f32 {
+    match winner {
+        Some(winner) if winner == seat => 1.0,
+        Some(_) => -1.0,
+        None => 0.0,
+    }
+}
+
+/// Compute a [`PlayerScore`] for every player in the view, ordered by player id.
+pub fn score_all(state: &GameStateView) -> Vec<PlayerScore> {
+    let winner = match state.status {
+        GameStatus::Finished { winner } => Some(winner),
+        _ => None,
+    };
+    let mut scores: Vec<PlayerScore> = state
+        .players
+        .iter()
+        .map(|p| {
+            let best_opponent = state
+                .players
+                .iter()
+                .filter(|o| o.id != p.id)
+                .map(|o| o.stock_count)
+                .min()
+                .unwrap_or(0);
+            let is_winner = winner == Some(p.id);
+            PlayerScore {
+                player: p.id,
+                stock_left: p.stock_count,
+                cards_played: state.settings.stock_size.saturating_sub(p.stock_count),
+                points: if is_winner {
+                    winner_points(state, p.id)
+                } else {
+                    0
+                },
+                margin: best_opponent as isize - p.stock_count as isize,
+                is_winner,
+            }
+        })
+        .collect();
+    scores.sort_by_key(|s| s.player);
+    scores
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::state::{BuildPileView, GameSettings, GameStatus, PlayerPublicState, TurnPhase};
+    use crate::state::{
+        BuildPileView, DEFAULT_MAX_ACTIONS_PER_TURN, GameSettings, PlayerPublicState, TurnPhase,
+    };
 
     fn dummy_state(stock_counts: &[usize], winner: PlayerId) -> GameStateView {
         let players: Vec<PlayerPublicState> = stock_counts
             .iter()
             .enumerate()
             .map(|(i, &c)| PlayerPublicState {
-                id: i,
+                id: PlayerId(i),
                 stock_count: c,
                 stock_top: None,
-                discard_piles: [vec![], vec![], vec![], vec![]],
+                discard_piles: vec![vec![], vec![], vec![], vec![]].into(),
                 hand_size: 0,
                 is_current: false,
-                has_won: i == winner,
+                has_won: i == winner.0,
             })
             .collect();
         GameStateView {
@@ -48,6 +118,9 @@ mod tests {
                 hand_size: 5,
                 discard_piles: 4,
                 build_piles: 4,
+                must_play_stock: false,
+                stock_visibility: Default::default(),
+                max_actions_per_turn: DEFAULT_MAX_ACTIONS_PER_TURN,
             },
             phase: TurnPhase::GameOver,
             status: GameStatus::Finished { winner },
@@ -62,28 +135,65 @@ mod tests {
                 BuildPileView::empty(),
             ],
             players,
-            hand: Vec::new(),
+            hand: Vec::new().into(),
+            recent_actions: Vec::new(),
+            turn_number: 0,
+            actions_taken_this_turn: 0,
+            turn_events: Vec::new(),
+            forbidden_plays: Vec::new(),
         }
     }
 
     #[test]
     fn test_winner_points_three_players() {
         // Winner index 1, opponents have 10 and 3 stock cards => 25 + 5*(13) = 90
-        let state = dummy_state(&[10, 0, 3], 1);
-        assert_eq!(winner_points(&state, 1), 90);
+        let state = dummy_state(&[10, 0, 3], PlayerId(1));
+        assert_eq!(winner_points(&state, PlayerId(1)), 90);
     }
 
     #[test]
     fn test_winner_points_two_players() {
         // Winner index 0, opponent has 7 => 25 + 5*(7) = 60
-        let state = dummy_state(&[0, 7], 0);
-        assert_eq!(winner_points(&state, 0), 60);
+        let state = dummy_state(&[0, 7], PlayerId(0));
+        assert_eq!(winner_points(&state, PlayerId(0)), 60);
+    }
+
+    #[test]
+    fn test_score_all_reports_margin_and_progress() {
+        let state = dummy_state(&[10, 0, 3], PlayerId(1));
+        let scores = score_all(&state);
+        assert_eq!(scores.len(), 3);
+        assert_eq!(scores[1].points, 90);
+        assert!(scores[1].is_winner);
+        assert_eq!(scores[1].margin, 3);
+        assert_eq!(scores[1].cards_played, 30);
+        assert_eq!(scores[0].points, 0);
+        assert_eq!(scores[0].stock_left, 10);
+        assert_eq!(scores[0].cards_played, 20);
+        assert_eq!(scores[0].margin, -10);
+        assert_eq!(scores[2].margin, -3);
+    }
+
+    #[test]
+    fn test_score_all_draw_awards_no_points() {
+        let mut state = dummy_state(&[4, 6], PlayerId(0));
+        state.status = GameStatus::Draw;
+        let scores = score_all(&state);
+        assert!(scores.iter().all(|s| s.points == 0 && !s.is_winner));
+        assert_eq!(scores[0].margin, 2);
     }
 
     #[test]
     fn test_winner_points_all_opponents_empty() {
         // Winner index 2, opponents have 0 stock => base 25
-        let state = dummy_state(&[0, 0, 0, 0], 2);
-        assert_eq!(winner_points(&state, 2), 25);
+        let state = dummy_state(&[0, 0, 0, 0], PlayerId(2));
+        assert_eq!(winner_points(&state, PlayerId(2)), 25);
+    }
+
+    #[test]
+    fn test_seat_outcome() {
+        assert_eq!(seat_outcome(Some(PlayerId(1)), PlayerId(1)), 1.0);
+        assert_eq!(seat_outcome(Some(PlayerId(1)), PlayerId(0)), -1.0);
+        assert_eq!(seat_outcome(None, PlayerId(0)), 0.0);
     }
 }
@@ -0,0 +1,614 @@
+//! Self-play data collection with a single bot shared by every seat.
+//!
+//! Parameter sharing: one bot instance, built once from its spec, makes the decisions
+//! of every seat through [`SharedBot`] handles and keeps whatever it learns across the
+//! whole run. Each decision is recorded as a [`SelfPlaySample`] with the encoded view
+//! (including the `role.*` seat features of [`StateEncoder`], so a shared policy can
+//! tell its seats apart) and labelled with the game's outcome for that seat.
+//!
+//! With `freeze_opponents` the shared bot only plays one seat per game (rotating), and
+//! the other seats get a fresh bot from the same spec every game: a frozen copy of the
+//! starting policy that does not follow the learner's updates.
+//!
+//! Actor and critic inputs are kept apart: [`SelfPlaySample::features`] is always the
+//! deciding seat's standard view, what a policy may act on. With a [`CriticView`] other
+//! than `none` every sample also carries [`SelfPlaySample::critic_features`] for
+//! training a value function on information the actor never sees: the opponents' hands
+//! (`open-hands`) or every seat's privileged view at once (`joint`, for a centralized
+//! critic as in MAPPO). The bots still decide from their standard views.
+//!
+//! [`RuleRandomization`] varies the rules from game to game (domain randomization): stock
+//! and hand sizes and the number of Skip-Bo cards in the deck are drawn from ranges, and
+//! every sample records the drawn rules as [`SelfPlaySample::rule_features`], so a
+//! policy trained on them can tell configurations apart instead of overfitting one.
+//!
+//! A [`StockCurriculum`] schedules the stock size instead: the run is split into stages
+//! of equal length whose stock sizes climb from a small start (short games, a dense win
+//! signal) to the full size. The stages follow the game count rather than the learner's
+//! results, as a bot sharing every seat wins a fixed share of its games however well it
+//! plays. [`SelfPlayConfig::curriculum_schedule`] lists the stages for the run's metadata.
+
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::action::{Action, PlayerId};
+use crate::bot::Bot;
+use crate::bots::create_bot_from_spec;
+use crate::card::{Card, HAND_SIZE, SKIP_BO_COUNT, full_deck};
+use crate::compat::FeatureSample;
+use crate::dataset::LabelledSample;
+use crate::encoder::{JOINT_FEATURES, PRIVILEGED_FEATURES, StateEncoder};
+use crate::error::GameError;
+use crate::game::{Game, LoopGuard};
+use crate::interrupt::Interrupter;
+use crate::runner::{mix_seed, play_out};
+use crate::score::seat_outcome;
+use crate::seeds::{SeedPool, SeedSplit};
+use crate::state::{FullStateView, GameSettings, GameStateView, StockVisibility};
+
+/// Handle to a bot shared between seats. Every clone drives the same instance.
+pub struct SharedBot<B: Bot> {
+    inner: Arc<Mutex<B>>,
+}
+
+impl<B: Bot> SharedBot<B> {
+    pub fn new(bot: B) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(bot)),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, B> {
+        // A panic mid-decision leaves nothing half-updated that later calls rely on.
+        self.inner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl<B: Bot> Clone for SharedBot<B> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+}
+
+impl<B: Bot> Bot for SharedBot<B> {
+    fn on_game_start(&mut self, state: &GameStateView) {
+        self.lock().on_game_start(state);
+    }
+
+    fn on_turn_start(&mut self, state: &GameStateView) {
+        self.lock().on_turn_start(state);
+    }
+
+    fn select_action(&mut self, state: &GameStateView, legal_actions: &[Action]) -> Action {
+        self.lock().select_action(state, legal_actions)
+    }
+
+    fn rank_actions(
+        &mut self,
+        state: &GameStateView,
+        legal_actions: &[Action],
+    ) -> Vec<(Action, f32)> {
+        self.lock().rank_actions(state, legal_actions)
+    }
+
+    fn limit_search(&mut self, nodes: usize) {
+        self.lock().limit_search(nodes);
+    }
+
+    fn interrupt_with(&mut self, interrupter: &Interrupter) {
+        self.lock().interrupt_with(interrupter);
+    }
+
+    fn wants_full_state(&self) -> bool {
+        self.lock().wants_full_state()
+    }
+
+    fn observe_full_state(&mut self, state: &FullStateView) {
+        self.lock().observe_full_state(state);
+    }
+
+    fn on_game_end(&mut self, state: &GameStateView, winner: Option<PlayerId>) {
+        self.lock().on_game_end(state, winner);
+    }
+}
+
+/// Settings for [`collect_self_play`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SelfPlayConfig {
+    /// Spec of the shared bot (and of the frozen opponents).
+    pub spec: String,
+    pub players: usize,
+    pub games: usize,
+    pub seed: u64,
+    /// Safety cap on actions per game; capped games are recorded as draws.
+    pub max_actions: usize,
+    /// Let the shared bot play one seat per game against frozen copies.
+    pub freeze_opponents: bool,
+    /// How precisely every seat sees stock sizes.
+    #[serde(default)]
+    pub stock_visibility: StockVisibility,
+    /// Privileged input recorded as [`SelfPlaySample::critic_features`]. Training only.
+    #[serde(default)]
+    pub critic: CriticView,
+    /// Ranges each game's rules are drawn from; off by default.
+    #[serde(default, skip_serializing_if = "RuleRandomization::is_off")]
+    pub randomize: RuleRandomization,
+    /// How the engine breaks loops within a turn that an exploring policy runs into.
+    #[serde(default)]
+    pub loop_guard: LoopGuard,
+    /// Stock sizes scheduled over the run; replaces a randomized stock size.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub curriculum: Option<StockCurriculum>,
+}
+
+impl SelfPlayConfig {
+    pub fn new(spec: impl Into<String>, players: usize, games: usize, seed: u64) -> Self {
+        Self {
+            spec: spec.into(),
+            players,
+            games,
+            seed,
+            max_actions: 2000,
+            freeze_opponents: false,
+            stock_visibility: StockVisibility::Exact,
+            critic: CriticView::None,
+            randomize: RuleRandomization::default(),
+            loop_guard: LoopGuard::Off,
+            curriculum: None,
+        }
+    }
+
+    /// The stages of [`SelfPlayConfig::curriculum`] over this run's games; empty without
+    /// one.
+    pub fn curriculum_schedule(&self) -> Vec<CurriculumStage> {
+        self.curriculum
+            .map(|curriculum| curriculum.schedule(self.games))
+            .unwrap_or_default()
+    }
+
+    /// Whether games are dealt with other than the standard rules.
+    fn varies_rules(&self) -> bool {
+        !self.randomize.is_off() || self.curriculum.is_some()
+    }
+
+    /// [`RuleRandomization::validate`], with the curriculum's stock sizes in place of a
+    /// randomized one.
+    fn validate_rules(&self) -> Result<(), String> {
+        let Some(curriculum) = self.curriculum else {
+            return self.randomize.validate(self.players);
+        };
+        if self.randomize.stock_size.is_some() {
+            return Err("a stock curriculum replaces the randomized stock size".into());
+        }
+        if curriculum.stages == 0 {
+            return Err("a stock curriculum needs at least one stage".into());
+        }
+        RuleRandomization {
+            stock_size: Some(curriculum.stock_size),
+            ..self.randomize.clone()
+        }
+        .validate(self.players)
+    }
+}
+
+/// Stock sizes rising from `stock_size.min` to `stock_size.max` in `stages` equal shares
+/// of a run's games.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StockCurriculum {
+    pub stock_size: RuleRange,
+    pub stages: usize,
+}
+
+impl StockCurriculum {
+    /// Stock size of `stage`, evenly spaced from the minimum to the maximum.
+    pub fn stage_stock_size(&self, stage: usize) -> usize {
+        let RuleRange { min, max } = self.stock_size;
+        match self.stages {
+            0 | 1 => max,
+            stages => min + (max - min) * stage.min(stages - 1) / (stages - 1),
+        }
+    }
+
+    /// Stage of game `game` (zero-based) out of `games`.
+    pub fn stage(&self, game: usize, games: usize) -> usize {
+        let stages = self.stages.max(1);
+        (game * stages / games.max(1)).min(stages - 1)
+    }
+
+    /// Every stage that gets at least one of `games` games.
+    pub fn schedule(&self, games: usize) -> Vec<CurriculumStage> {
+        let mut stages: Vec<CurriculumStage> = Vec::new();
+        for game in 0..games {
+            let stage = self.stage(game, games);
+            match stages.last_mut() {
+                Some(last) if last.stage == stage => last.games += 1,
+                _ => stages.push(CurriculumStage {
+                    stage,
+                    first_game: game,
+                    games: 1,
+                    stock_size: self.stage_stock_size(stage),
+                }),
+            }
+        }
+        stages
+    }
+}
+
+/// One step of a [`StockCurriculum`] as scheduled over a run.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CurriculumStage {
+    pub stage: usize,
+    pub first_game: usize,
+    pub games: usize,
+    pub stock_size: usize,
+}
+
+/// What a self-play critic sees besides the actor's standard view.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "kebab-case")]
+pub enum CriticView {
+    /// No critic features.
+    #[default]
+    None,
+    /// [`StateEncoder::encode_privileged`]: the deciding seat's view plus the opponents'
+    /// hands.
+    OpenHands,
+    /// [`StateEncoder::encode_joint`]: every seat's privileged view.
+    Joint,
+}
+
+impl CriticView {
+    /// Length of the critic features, 0 for `none`.
+    pub fn features(self) -> usize {
+        match self {
+            CriticView::None => 0,
+            CriticView::OpenHands => PRIVILEGED_FEATURES,
+            CriticView::Joint => JOINT_FEATURES,
+        }
+    }
+
+    /// Names of the critic features, in order.
+    pub fn feature_names(self) -> Vec<&'static str> {
+        match self {
+            CriticView::None => Vec::new(),
+            CriticView::OpenHands => StateEncoder::privileged_feature_names(),
+            CriticView::Joint => StateEncoder::joint_feature_names(),
+        }
+    }
+
+    /// Critic features of `player`'s decision in `game`, if any.
+    pub fn encode(self, game: &Game, player: PlayerId) -> Result<Option<Vec<f32>>, GameError> {
+        Ok(match self {
+            CriticView::None => None,
+            CriticView::OpenHands => {
+                Some(StateEncoder::encode_privileged(&game.full_state_view(player)?).to_vec())
+            }
+            CriticView::Joint => Some(StateEncoder::encode_joint(game, player)?.to_vec()),
+        })
+    }
+}
+
+impl fmt::Display for CriticView {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            CriticView::None => "none",
+            CriticView::OpenHands => "open-hands",
+            CriticView::Joint => "joint",
+        })
+    }
+}
+
+impl FromStr for CriticView {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "none" => Ok(CriticView::None),
+            "open-hands" => Ok(CriticView::OpenHands),
+            "joint" => Ok(CriticView::Joint),
+            _ => Err(format!(
+                "invalid critic view: {s} (expected none, open-hands or joint)"
+            )),
+        }
+    }
+}
+
+/// Inclusive range of a randomized rule, written `N` or `MIN-MAX`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct RuleRange {
+    pub min: usize,
+    pub max: usize,
+}
+
+impl RuleRange {
+    pub fn sample<R: Rng + ?Sized>(self, rng: &mut R) -> usize {
+        rng.gen_range(self.min..=self.max)
+    }
+}
+
+impl fmt::Display for RuleRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.min == self.max {
+            write!(f, "{}", self.min)
+        } else {
+            write!(f, "{}-{}", self.min, self.max)
+        }
+    }
+}
+
+impl FromStr for RuleRange {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (min, max) = s.split_once('-').unwrap_or((s, s));
+        let parse = |n: &str| {
+            n.trim()
+                .parse::<usize>()
+                .map_err(|_| format!("invalid range: {s} (expected N or MIN-MAX)"))
+        };
+        let (min, max) = (parse(min)?, parse(max)?);
+        if min > max {
+            return Err(format!("invalid range: {s} (MIN is above MAX)"));
+        }
+        Ok(Self { min, max })
+    }
+}
+
+/// Domain randomization for self-play: ranges every game's rules are drawn from. Rules
+/// left unset keep their standard value.
+///
+/// A deck with fewer Skip-Bo cards is injected, so those games cannot be recorded, and
+/// bots that sample determinizations (which assume the standard deck) cannot search them.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RuleRandomization {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stock_size: Option<RuleRange>,
+    /// At most the standard [`HAND_SIZE`], which [`Action::index`] is laid out for.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hand_size: Option<RuleRange>,
+    /// Skip-Bo cards left in the deck, at most the standard [`SKIP_BO_COUNT`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub skip_bo_cards: Option<RuleRange>,
+}
+
+impl RuleRandomization {
+    pub fn is_off(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Check that every draw deals a playable game for `players`: positive sizes, hands
+    /// no larger than the action space indexes, no more Skip-Bo cards than the deck has,
+    /// and enough cards for every stock and hand.
+    pub fn validate(&self, players: usize) -> Result<(), String> {
+        let standard = GameSettings::new(players).map_err(|err| err.to_string())?;
+        let stock = self.stock_size.map_or(standard.stock_size, |r| r.max);
+        let hand = self.hand_size.map_or(HAND_SIZE, |r| r.max);
+        let skip_bos = self.skip_bo_cards.map_or(SKIP_BO_COUNT, |r| r.min);
+        if self.stock_size.is_some_and(|r| r.min == 0) {
+            return Err("stock size must be positive".into());
+        }
+        if self
+            .hand_size
+            .is_some_and(|r| r.min == 0 || r.max > HAND_SIZE)
+        {
+            return Err(format!("hand size must be between 1 and {HAND_SIZE}"));
+        }
+        if self.skip_bo_cards.is_some_and(|r| r.max > SKIP_BO_COUNT) {
+            return Err(format!(
+                "the deck holds at most {SKIP_BO_COUNT} Skip-Bo cards"
+            ));
+        }
+        let deck = full_deck().len() - SKIP_BO_COUNT + skip_bos;
+        if (stock + hand) * players > deck {
+            return Err(format!(
+                "{players} stocks of {stock} and hands of {hand} need more than {deck} cards"
+            ));
+        }
+        Ok(())
+    }
+
+    /// Draw one game's rules for `players`.
+    pub fn sample<R: Rng + ?Sized>(&self, players: usize, rng: &mut R) -> GameRules {
+        let standard = GameSettings::new(players).map_or(30, |settings| settings.stock_size);
+        GameRules {
+            stock_size: self.stock_size.map_or(standard, |r| r.sample(rng)),
+            hand_size: self.hand_size.map_or(HAND_SIZE, |r| r.sample(rng)),
+            skip_bo_cards: self.skip_bo_cards.map_or(SKIP_BO_COUNT, |r| r.sample(rng)),
+        }
+    }
+}
+
+/// Stock size treated as "large" when scaling rule features.
+const STOCK_SIZE_SCALE: f32 = 50.0;
+/// Hand size treated as "large" when scaling rule features.
+const HAND_SIZE_SCALE: f32 = 10.0;
+
+/// The rules one randomized game was played with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct GameRules {
+    pub stock_size: usize,
+    pub hand_size: usize,
+    pub skip_bo_cards: usize,
+}
+
+impl GameRules {
+    /// Names of [`GameRules::features`], in order.
+    pub const FEATURE_NAMES: [&'static str; 3] =
+        ["rules.stock_size", "rules.hand_size", "rules.skip_bo_cards"];
+
+    /// The rules scaled to `[0, 1]`, as recorded in [`SelfPlaySample::rule_features`].
+    pub fn features(&self) -> Vec<f32> {
+        vec![
+            (self.stock_size as f32 / STOCK_SIZE_SCALE).min(1.0),
+            (self.hand_size as f32 / HAND_SIZE_SCALE).min(1.0),
+            self.skip_bo_cards as f32 / SKIP_BO_COUNT as f32,
+        ]
+    }
+
+    /// The standard deck with surplus Skip-Bo cards taken out, shuffled.
+    fn deck<R: Rng + ?Sized>(&self, rng: &mut R) -> Vec<Card> {
+        let mut surplus = SKIP_BO_COUNT.saturating_sub(self.skip_bo_cards);
+        let mut deck: Vec<Card> = full_deck()
+            .into_iter()
+            .filter(|card| {
+                let drop = card.is_skip_bo() && surplus > 0;
+                surplus -= usize::from(drop);
+                !drop
+            })
+            .collect();
+        deck.shuffle(rng);
+        deck
+    }
+}
+
+/// One recorded decision.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SelfPlaySample {
+    pub game: usize,
+    pub seat: PlayerId,
+    /// Whether the shared bot made this decision (always true without frozen opponents).
+    pub learner: bool,
+    /// Actor input: [`StateEncoder::encode`] of the deciding seat's view.
+    pub features: Vec<f32>,
+    /// [`Action::index`] of every legal action and of the chosen one.
+    pub legal: Vec<usize>,
+    pub action: usize,
+    /// Final result for `seat`; see [`seat_outcome`].
+    pub outcome: f32,
+    /// Critic input for the same decision, as chosen by [`SelfPlayConfig::critic`];
+    /// never shown to the bots.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub critic_features: Option<Vec<f32>>,
+    /// [`GameRules::features`] of the game's randomized or scheduled rules; absent
+    /// without [`SelfPlayConfig::randomize`] or [`SelfPlayConfig::curriculum`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rule_features: Option<Vec<f32>>,
+}
+
+impl FeatureSample for SelfPlaySample {
+    fn features_mut(&mut self) -> &mut Vec<f32> {
+        &mut self.features
+    }
+}
+
+impl LabelledSample for SelfPlaySample {
+    /// `learner` for the shared bot, `frozen` for the frozen opponents.
+    fn teacher(&self) -> &str {
+        if self.learner { "learner" } else { "frozen" }
+    }
+
+    fn game(&self) -> usize {
+        self.game
+    }
+
+    fn seat(&self) -> PlayerId {
+        self.seat
+    }
+
+    fn legal(&self) -> &[usize] {
+        &self.legal
+    }
+
+    fn action(&self) -> usize {
+        self.action
+    }
+
+    fn outcome(&self) -> f32 {
+        self.outcome
+    }
+}
+
+/// Play `config.games` self-play games on [`SeedSplit::Train`] deals, handing every
+/// recorded decision to `sink` once its game is over. Returns the number of samples.
+pub fn collect_self_play<F>(config: &SelfPlayConfig, mut sink: F) -> Result<usize, Box<dyn Error>>
+where
+    F: FnMut(SelfPlaySample),
+{
+    if config.players < 2 {
+        return Err("self-play needs at least 2 players".into());
+    }
+    config.validate_rules()?;
+    let shared = SharedBot::new(create_bot_from_spec(
+        &config.spec,
+        PlayerId(0),
+        mix_seed(config.seed, 0, 0x5E1F),
+    )?);
+    let pool = SeedPool::new(config.seed);
+    let mut total = 0;
+    for game_index in 0..config.games {
+        let learner_seat = PlayerId(game_index % config.players);
+        let mut bots: Vec<Box<dyn Bot>> = Vec::with_capacity(config.players);
+        for seat in PlayerId::all(config.players) {
+            if config.freeze_opponents && seat != learner_seat {
+                let seed = mix_seed(config.seed, game_index as u64, seat.0 as u64);
+                bots.push(create_bot_from_spec(&config.spec, seat, seed)?);
+            } else {
+                bots.push(Box::new(shared.clone()));
+            }
+        }
+        let learner = |seat: PlayerId| !config.freeze_opponents || seat == learner_seat;
+
+        let seed = pool.seed(SeedSplit::Train, game_index as u64);
+        let mut builder = Game::builder(config.players)?
+            .with_seed(seed)
+            .with_stock_visibility(config.stock_visibility)
+            .with_loop_guard(config.loop_guard);
+        let mut rule_features = None;
+        if config.varies_rules() {
+            let mut rng = StdRng::seed_from_u64(mix_seed(seed, game_index as u64, 0xD0_3A14));
+            let mut rules = config.randomize.sample(config.players, &mut rng);
+            if let Some(curriculum) = config.curriculum {
+                let stage = curriculum.stage(game_index, config.games);
+                rules.stock_size = curriculum.stage_stock_size(stage);
+            }
+            builder = builder
+                .with_stock_size(rules.stock_size)
+                .with_hand_size(rules.hand_size);
+            if rules.skip_bo_cards != SKIP_BO_COUNT {
+                builder = builder.with_deck(rules.deck(&mut rng));
+            }
+            rule_features = Some(rules.features());
+        }
+        let mut game = builder.build()?;
+        let mut samples = Vec::new();
+        play_out(&mut game, &mut bots, config.max_actions, |decision| {
+            let critic_features = config
+                .critic
+                .encode(decision.game, decision.player)
+                .expect("the deciding seat is at the table");
+            samples.push(SelfPlaySample {
+                game: game_index,
+                seat: decision.player,
+                learner: learner(decision.player),
+                features: StateEncoder::encode(decision.state).to_vec(),
+                legal: decision.legal.iter().filter_map(Action::index).collect(),
+                action: decision
+                    .action
+                    .index()
+                    .expect("legal actions have an index"),
+                outcome: 0.0,
+                critic_features,
+                rule_features: rule_features.clone(),
+            });
+        })?;
+
+        let winner = game.winner();
+        for mut sample in samples {
+            sample.outcome = seat_outcome(winner, sample.seat);
+            sink(sample);
+            total += 1;
+        }
+    }
+    Ok(total)
+}
@@ -1,17 +1,44 @@
+use std::collections::hash_map::DefaultHasher;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+use std::sync::Arc;
+
 use serde::{Deserialize, Serialize};
 
-use crate::action::PlayerId;
-use crate::card::{BUILD_PILE_COUNT, Card, DISCARD_PILE_COUNT, HAND_SIZE, MAX_PLAYERS};
+use crate::action::{Action, BuildPileId, PlayerId};
+use crate::card::{
+    BUILD_PILE_COUNT, Card, DISCARD_PILE_COUNT, HAND_SIZE, MAX_CARD_VALUE, MAX_PLAYERS,
+};
 use crate::error::GameError;
+use crate::game::enumerate_actions;
 
 /// Global constants for a running game.
-#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub struct GameSettings {
     pub num_players: usize,
     pub stock_size: usize,
     pub hand_size: usize,
     pub discard_piles: usize,
     pub build_piles: usize,
+    /// House rule: while the stock top can be played, stock plays are the only legal
+    /// actions.
+    #[serde(default)]
+    pub must_play_stock: bool,
+    /// How precisely views show stock sizes while the game is running.
+    #[serde(default)]
+    pub stock_visibility: StockVisibility,
+    /// Actions a turn may take before plays stop being legal and the player has to
+    /// discard (or end the turn with an empty hand). A safety cap far above any real turn.
+    #[serde(default = "default_max_actions_per_turn")]
+    pub max_actions_per_turn: usize,
+}
+
+/// Default for [`GameSettings::max_actions_per_turn`].
+pub const DEFAULT_MAX_ACTIONS_PER_TURN: usize = 500;
+
+fn default_max_actions_per_turn() -> usize {
+    DEFAULT_MAX_ACTIONS_PER_TURN
 }
 
 impl GameSettings {
@@ -28,41 +55,119 @@ impl GameSettings {
             hand_size: HAND_SIZE,
             discard_piles: DISCARD_PILE_COUNT,
             build_piles: BUILD_PILE_COUNT,
+            must_play_stock: false,
+            stock_visibility: StockVisibility::Exact,
+            max_actions_per_turn: DEFAULT_MAX_ACTIONS_PER_TURN,
+        })
+    }
+}
+
+/// Largest stock the [`StockVisibility::Binned`] variant reports as "few".
+pub const FEW_STOCK_CARDS: usize = 5;
+
+/// Information variant for [`PlayerPublicState::stock_count`], applied to every seat's
+/// stock (the viewer's own included) until the game is over.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "kebab-case")]
+pub enum StockVisibility {
+    /// The exact count, as in the standard game.
+    #[default]
+    Exact,
+    /// Only "last" (1), "few" (up to [`FEW_STOCK_CARDS`]) or "many". Each bin reports
+    /// its largest count, so a bot never expects to empty a stock sooner than it can.
+    Binned,
+    /// Only whether the stock is empty; any other stock reports the full stock size.
+    Hidden,
+}
+
+impl StockVisibility {
+    /// The count a view shows for a stock of `count` cards in a game dealing
+    /// `stock_size`.
+    pub fn observe(self, count: usize, stock_size: usize) -> usize {
+        match self {
+            StockVisibility::Exact => count,
+            StockVisibility::Binned => match count {
+                0 | 1 => count,
+                _ if count <= FEW_STOCK_CARDS => FEW_STOCK_CARDS.min(stock_size),
+                _ => stock_size,
+            },
+            StockVisibility::Hidden if count == 0 => 0,
+            StockVisibility::Hidden => stock_size,
+        }
+    }
+}
+
+impl fmt::Display for StockVisibility {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            StockVisibility::Exact => "exact",
+            StockVisibility::Binned => "binned",
+            StockVisibility::Hidden => "hidden",
         })
     }
 }
 
+impl FromStr for StockVisibility {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "exact" => Ok(StockVisibility::Exact),
+            "binned" => Ok(StockVisibility::Binned),
+            "hidden" => Ok(StockVisibility::Hidden),
+            _ => Err(format!(
+                "invalid stock visibility: {s} (expected exact, binned or hidden)"
+            )),
+        }
+    }
+}
+
 /// Public information regarding a build pile.
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub struct BuildPileView {
-    pub cards: Vec<Card>,
+    pub cards: Arc<Vec<Card>>,
     pub next_value: u8,
 }
 
 impl BuildPileView {
     pub fn empty() -> Self {
         Self {
-            cards: Vec::new(),
+            cards: Arc::default(),
             next_value: 1,
         }
     }
 }
 
+/// Build steps before `stock` can be played on one of the build piles requiring
+/// `build_next`, wrapping after 12; 0 when it fits already or is a Skip-Bo.
+pub fn unlock_distance(build_next: &[u8; BUILD_PILE_COUNT], stock: Card) -> u8 {
+    match stock {
+        Card::SkipBo => 0,
+        Card::Number(value) => build_next
+            .iter()
+            .map(|&next| (value + MAX_CARD_VALUE - next) % MAX_CARD_VALUE)
+            .min()
+            .unwrap_or(0),
+    }
+}
+
 /// Public portion of a player's state that all opponents may observe.
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub struct PlayerPublicState {
     pub id: PlayerId,
+    /// Cards left in the stock, coarsened by [`GameSettings::stock_visibility`].
     pub stock_count: usize,
     pub stock_top: Option<Card>,
-    /// Full contents of each discard pile (bottom..top order).
-    pub discard_piles: [Vec<Card>; DISCARD_PILE_COUNT],
+    /// Full contents of each discard pile (bottom..top order). Seats with an extra
+    /// discard pile have one more than [`DISCARD_PILE_COUNT`].
+    pub discard_piles: Arc<Vec<Vec<Card>>>,
     pub hand_size: usize,
     pub is_current: bool,
     pub has_won: bool,
 }
 
 /// Status of the entire game.
-#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum GameStatus {
     Ongoing,
     Finished { winner: PlayerId },
@@ -70,14 +175,27 @@ pub enum GameStatus {
 }
 
 /// Current phase of the active turn.
-#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum TurnPhase {
     AwaitingAction,
     GameOver,
 }
 
+/// Something the engine did on its own during a turn, beyond applying the actions.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum TurnEvent {
+    /// `player` drew `cards` cards: the refill at the start of the turn, or after playing
+    /// the last hand card.
+    Drew { player: PlayerId, cards: usize },
+    /// `pile` reached 12 and its cards went to the recycle pile.
+    PileCompleted { pile: BuildPileId },
+    /// The draw pile ran out and `cards` recycled cards were shuffled into it, right
+    /// before the draw that needed them.
+    Reshuffled { cards: usize },
+}
+
 /// Game state snapshot tailored for bots and ML agents.
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub struct GameStateView {
     pub settings: GameSettings,
     pub phase: TurnPhase,
@@ -88,5 +206,325 @@ pub struct GameStateView {
     pub recycle_pile_count: usize,
     pub build_piles: [BuildPileView; BUILD_PILE_COUNT],
     pub players: Vec<PlayerPublicState>,
-    pub hand: Vec<Card>,
+    pub hand: Arc<Vec<Card>>,
+    /// Actions taken since the viewing player last acted, oldest first. At the start of
+    /// a turn these are the opponents' moves since the viewer's previous turn.
+    #[serde(default)]
+    pub recent_actions: Vec<(PlayerId, Action)>,
+    /// Zero-based index of the current turn, counted over all players.
+    #[serde(default)]
+    pub turn_number: usize,
+    /// Actions the current player has already taken in this turn.
+    #[serde(default)]
+    pub actions_taken_this_turn: usize,
+    /// What the engine did so far in the current turn, oldest first, so completed piles
+    /// and reshuffles need not be inferred from changing counts.
+    #[serde(default)]
+    pub turn_events: Vec<TurnEvent>,
+    /// Plays that [`LoopGuard::Forbid`] has ruled out in this position. Only the current
+    /// player's view lists them.
+    ///
+    /// [`LoopGuard::Forbid`]: crate::game::LoopGuard::Forbid
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub forbidden_plays: Vec<Action>,
+}
+
+/// A [`GameStateView`] plus every card hidden from it. Only for cheating baselines that
+/// measure what hidden information costs; see [`Bot::observe_full_state`].
+///
+/// [`Bot::observe_full_state`]: crate::bot::Bot::observe_full_state
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct FullStateView {
+    pub view: GameStateView,
+    /// Every player's hand, indexed by player.
+    pub hands: Vec<Vec<Card>>,
+    /// Every player's stock pile from the top down, indexed by player.
+    pub stocks: Vec<Vec<Card>>,
+    /// The draw pile in draw order (next card first). Cards drawn after it runs out come
+    /// from a reshuffle of the recycle pile and cannot be foreseen, nor can the swaps of
+    /// seats with weak draws.
+    pub draw_pile: Vec<Card>,
+    pub recycle_pile: Vec<Card>,
+}
+
+/// Read access shared by the owned [`GameStateView`] and the borrowed
+/// [`GameStateViewRef`], so consumers such as [`StateEncoder`] accept either. Players are
+/// addressed by seat.
+///
+/// [`GameStateViewRef`]: crate::game::GameStateViewRef
+/// [`StateEncoder`]: crate::encoder::StateEncoder
+pub trait StateView {
+    fn settings(&self) -> GameSettings;
+    fn status(&self) -> GameStatus;
+    fn self_player(&self) -> PlayerId;
+    fn current_player(&self) -> PlayerId;
+    fn draw_pile_count(&self) -> usize;
+    fn recycle_pile_count(&self) -> usize;
+    fn turn_number(&self) -> usize;
+    fn actions_taken_this_turn(&self) -> usize;
+    /// The viewing player's hand.
+    fn hand(&self) -> &[Card];
+    /// Cards on build pile `pile`, bottom first.
+    fn build_pile(&self, pile: BuildPileId) -> &[Card];
+    /// Value the build pile `pile` needs next.
+    fn build_next(&self, pile: BuildPileId) -> u8 {
+        (self.build_pile(pile).len() as u8 % MAX_CARD_VALUE) + 1
+    }
+    /// Stock size of `player` as the view shows it; see [`GameSettings::stock_visibility`].
+    fn stock_count(&self, player: PlayerId) -> usize;
+    fn stock_top(&self, player: PlayerId) -> Option<Card>;
+    /// Discard piles of `player`, each bottom first.
+    fn discard_piles(&self, player: PlayerId) -> &[Vec<Card>];
+    /// Number of cards `player` holds.
+    fn hand_size(&self, player: PlayerId) -> usize;
+    /// Actions taken since the viewing player last acted, oldest first.
+    fn recent_actions(&self) -> &[(PlayerId, Action)];
+    /// Engine events of the current turn, oldest first.
+    fn turn_events(&self) -> &[TurnEvent];
+    /// Plays [`LoopGuard::Forbid`] has ruled out in this position, in the current
+    /// player's view only.
+    ///
+    /// [`LoopGuard::Forbid`]: crate::game::LoopGuard::Forbid
+    fn forbidden_plays(&self) -> Vec<Action>;
+}
+
+impl StateView for GameStateView {
+    fn settings(&self) -> GameSettings {
+        self.settings
+    }
+
+    fn status(&self) -> GameStatus {
+        self.status
+    }
+
+    fn self_player(&self) -> PlayerId {
+        self.self_player
+    }
+
+    fn current_player(&self) -> PlayerId {
+        self.current_player
+    }
+
+    fn draw_pile_count(&self) -> usize {
+        self.draw_pile_count
+    }
+
+    fn recycle_pile_count(&self) -> usize {
+        self.recycle_pile_count
+    }
+
+    fn turn_number(&self) -> usize {
+        self.turn_number
+    }
+
+    fn actions_taken_this_turn(&self) -> usize {
+        self.actions_taken_this_turn
+    }
+
+    fn hand(&self) -> &[Card] {
+        &self.hand
+    }
+
+    fn build_pile(&self, pile: BuildPileId) -> &[Card] {
+        &self.build_piles[pile.0].cards
+    }
+
+    fn build_next(&self, pile: BuildPileId) -> u8 {
+        self.build_piles[pile.0].next_value
+    }
+
+    fn stock_count(&self, player: PlayerId) -> usize {
+        self.players[player.0].stock_count
+    }
+
+    fn stock_top(&self, player: PlayerId) -> Option<Card> {
+        self.players[player.0].stock_top
+    }
+
+    fn discard_piles(&self, player: PlayerId) -> &[Vec<Card>] {
+        &self.players[player.0].discard_piles
+    }
+
+    fn hand_size(&self, player: PlayerId) -> usize {
+        self.players[player.0].hand_size
+    }
+
+    fn recent_actions(&self) -> &[(PlayerId, Action)] {
+        &self.recent_actions
+    }
+
+    fn turn_events(&self) -> &[TurnEvent] {
+        &self.turn_events
+    }
+
+    fn forbidden_plays(&self) -> Vec<Action> {
+        self.forbidden_plays.clone()
+    }
+}
+
+impl GameStateView {
+    /// Hash of everything visible in this view, the recent actions, turn counters and
+    /// events included. Stable within a process, not across Rust releases. Transposition
+    /// tables want [`position_hash`](Self::position_hash) instead.
+    pub fn state_hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Hash of the game position alone: settings, build piles, hand, every seat's stock
+    /// and discard piles, the pile sizes and the forbidden plays. How and when the
+    /// position was reached (the recent actions, turn counters and events) is left out,
+    /// so transpositions share a key, except whether the turn may still play. Stable
+    /// within a process, not across Rust releases.
+    pub fn position_hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.settings.hash(&mut hasher);
+        self.status.hash(&mut hasher);
+        self.self_player.hash(&mut hasher);
+        self.current_player.hash(&mut hasher);
+        self.draw_pile_count.hash(&mut hasher);
+        self.recycle_pile_count.hash(&mut hasher);
+        self.build_piles.hash(&mut hasher);
+        self.hand.hash(&mut hasher);
+        for player in &self.players {
+            player.id.hash(&mut hasher);
+            player.stock_count.hash(&mut hasher);
+            player.stock_top.hash(&mut hasher);
+            player.discard_piles.hash(&mut hasher);
+            player.hand_size.hash(&mut hasher);
+            player.has_won.hash(&mut hasher);
+        }
+        (self.actions_taken_this_turn < self.settings.max_actions_per_turn).hash(&mut hasher);
+        self.forbidden_plays.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Legal actions for the viewing player, identical to [`Game::legal_actions`] when it
+    /// is their turn. Empty once the game is over.
+    ///
+    /// [`Game::legal_actions`]: crate::game::Game::legal_actions
+    pub fn legal_actions(&self) -> Vec<Action> {
+        if !matches!(self.status, GameStatus::Ongoing) {
+            return Vec::new();
+        }
+        let Some(me) = self.self_player_state() else {
+            return Vec::new();
+        };
+        let mut actions = enumerate_actions(
+            &self.hand,
+            me.stock_top,
+            &me.discard_piles,
+            std::array::from_fn(|i| self.build_piles[i].next_value),
+            self.settings.must_play_stock,
+            self.actions_taken_this_turn < self.settings.max_actions_per_turn,
+        );
+        actions.retain(|action| !self.forbidden_plays.contains(action));
+        actions
+    }
+
+    fn player(&self, player: PlayerId) -> Option<&PlayerPublicState> {
+        self.players.iter().find(|p| p.id == player)
+    }
+
+    /// Public state of the viewing player; `None` only for a malformed view.
+    pub fn self_player_state(&self) -> Option<&PlayerPublicState> {
+        self.player(self.self_player)
+    }
+
+    /// Public state of the player who acts after the current one.
+    pub fn next_player_state(&self) -> Option<&PlayerPublicState> {
+        self.player(self.current_player.offset(1, self.settings.num_players))
+    }
+
+    /// Share of `player`'s stock already played, from 0.0 for a full stock to 1.0 for an
+    /// empty one, as precisely as [`GameSettings::stock_visibility`] shows it. `None` for
+    /// a player not in the view.
+    pub fn stock_pressure(&self, player: PlayerId) -> Option<f64> {
+        let stock_count = self.player(player)?.stock_count;
+        let stock_size = self.settings.stock_size.max(1);
+        Some(1.0 - stock_count.min(stock_size) as f64 / stock_size as f64)
+    }
+
+    /// [`unlock_distance`] of `player`'s stock top on build piles requiring `piles` (pass
+    /// the view's own next values, or those of a simulated line). `None` when the player
+    /// has no visible stock top.
+    pub fn unlock_distance(&self, player: PlayerId, piles: &[u8; BUILD_PILE_COUNT]) -> Option<u8> {
+        Some(unlock_distance(piles, self.player(player)?.stock_top?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn view(stocks: &[(usize, Option<Card>)]) -> GameStateView {
+        let players = stocks
+            .iter()
+            .enumerate()
+            .map(|(i, &(stock_count, stock_top))| PlayerPublicState {
+                id: PlayerId(i),
+                stock_count,
+                stock_top,
+                discard_piles: vec![vec![]; DISCARD_PILE_COUNT].into(),
+                hand_size: HAND_SIZE,
+                is_current: i == 0,
+                has_won: false,
+            })
+            .collect();
+        GameStateView {
+            settings: GameSettings::new(stocks.len()).unwrap(),
+            phase: TurnPhase::AwaitingAction,
+            status: GameStatus::Ongoing,
+            self_player: PlayerId(0),
+            current_player: PlayerId(0),
+            draw_pile_count: 100,
+            recycle_pile_count: 0,
+            build_piles: std::array::from_fn(|_| BuildPileView::empty()),
+            players,
+            hand: Arc::default(),
+            recent_actions: Vec::new(),
+            turn_number: 0,
+            actions_taken_this_turn: 0,
+            turn_events: Vec::new(),
+            forbidden_plays: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn stock_pressure_is_the_share_played() {
+        let state = view(&[(30, None), (15, None), (0, None)]);
+        assert_eq!(state.stock_pressure(PlayerId(0)), Some(0.0));
+        assert_eq!(state.stock_pressure(PlayerId(1)), Some(0.5));
+        assert_eq!(state.stock_pressure(PlayerId(2)), Some(1.0));
+        assert_eq!(state.stock_pressure(PlayerId(3)), None);
+    }
+
+    #[test]
+    fn unlock_distance_wraps_after_twelve() {
+        let state = view(&[
+            (30, Some(Card::Number(2))),
+            (30, Some(Card::SkipBo)),
+            (30, None),
+        ]);
+        assert_eq!(state.unlock_distance(PlayerId(0), &[12, 6, 6, 6]), Some(2));
+        assert_eq!(state.unlock_distance(PlayerId(0), &[1, 6, 6, 6]), Some(1));
+        assert_eq!(state.unlock_distance(PlayerId(0), &[2, 6, 6, 6]), Some(0));
+        assert_eq!(state.unlock_distance(PlayerId(1), &[12, 6, 6, 6]), Some(0));
+        assert_eq!(state.unlock_distance(PlayerId(2), &[12, 6, 6, 6]), None);
+        assert_eq!(unlock_distance(&[3, 3, 3, 3], Card::Number(2)), 11);
+    }
+
+    #[test]
+    fn self_and_next_player_states_follow_the_seats() {
+        let mut state = view(&[(30, None), (20, None), (10, None)]);
+        state.self_player = PlayerId(2);
+        state.current_player = PlayerId(2);
+        assert_eq!(state.self_player_state().map(|p| p.stock_count), Some(10));
+        assert_eq!(state.next_player_state().map(|p| p.id), Some(PlayerId(0)));
+
+        state.players.truncate(2);
+        assert_eq!(state.self_player_state(), None);
+        assert_eq!(state.legal_actions(), Vec::new());
+    }
 }
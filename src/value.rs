@@ -0,0 +1,917 @@
+//! Supervised win-probability model.
+//!
+//! [`ValueNet`] is a small multilayer perceptron (one `tanh` hidden layer, sigmoid
+//! output) mapping a [`StateEncoder`] vector to the probability that the viewing seat
+//! wins. [`train_value_net`] fits it with Adam on the cross-entropy against final game
+//! outcomes, taken from samples mined from replays ([`PolicyDataset`]) or self-play: any
+//! JSON lines with `game`, `features` and `outcome` fields load as [`ValueSample`]s.
+//!
+//! Whole games are held out for validation, since positions of one game share their
+//! outcome. [`Calibration`] reports how trustworthy the probabilities are: Brier score,
+//! log loss and reliability-diagram bins of predicted against observed win rates. The net
+//! is meant for an eval bar and as a search evaluation: it implements [`EvalFn`], so a
+//! beam search bot can play with it.
+//!
+//! Validation loss says little about playing strength, so training can also play a few
+//! games with the current net every few epochs ([`ValueTrainConfig::eval_games`]) and
+//! record the win rate against a fixed opponent in each epoch's [`EpochMetrics`]. With
+//! [`ValueTrainConfig::best_by`] set, training returns the best epoch's net instead of the
+//! last one, chosen by validation loss or by that win rate, and records both criteria in a
+//! [`CheckpointMetadata`].
+//!
+//! Training runs leave several checkpoints of about equal quality; [`ValueEnsemble`]
+//! evaluates a set of them as one, averaging their logits, and is what the beam search
+//! bot plays with when its spec lists `value=PATH,PATH,...`.
+//!
+//! Training is seeded throughout (validation split, initial weights, shuffling and
+//! evaluation games), but the shuffle permutes samples in the order they are given, so by
+//! default the result also depends on the order of the input files and of their lines.
+//! [`ValueTrainConfig::deterministic`] first puts the samples in [`canonical_order`]: two
+//! runs with the same seed on the same samples then produce identical checkpoints,
+//! however the data was collected or merged.
+//!
+//! [`PolicyDataset`]: crate::dataset::PolicyDataset
+
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use rand::Rng;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+
+use crate::bots::beam_search::BeamSearchBot;
+use crate::bots::eval::{EvalFn, WIN_SCORE};
+use crate::bots::planning::TurnPosition;
+use crate::compat::{FeatureSample, FeatureShim, Representation, read_samples};
+use crate::encoder::{STATE_FEATURES, StateEncoder};
+use crate::interrupt::Interrupter;
+use crate::runner::{head_to_head, mix_seed};
+use crate::seeds::SeedSplit;
+use crate::state::{GameStateView, StateView};
+
+/// One position labelled with how its game ended for the viewing seat.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ValueSample {
+    /// Game the position comes from; used to hold out whole games.
+    pub game: usize,
+    pub features: Vec<f32>,
+    /// 1.0 win, -1.0 loss, 0.0 draw or capped game.
+    pub outcome: f32,
+}
+
+impl ValueSample {
+    /// Win probability the net is trained towards (draws count as half a win).
+    pub fn target(&self) -> f32 {
+        (self.outcome + 1.0) / 2.0
+    }
+}
+
+impl FeatureSample for ValueSample {
+    fn features_mut(&mut self) -> &mut Vec<f32> {
+        &mut self.features
+    }
+}
+
+/// Read samples from JSON lines, e.g. the output of `dataset` or `selfplay`.
+pub fn load_value_samples(path: &Path) -> Result<Vec<ValueSample>, Box<dyn Error>> {
+    load_value_samples_with(path, FeatureShim::Strict)
+}
+
+/// [`load_value_samples`], adapting features of another encoder version with `shim`.
+pub fn load_value_samples_with(
+    path: &Path,
+    shim: FeatureShim,
+) -> Result<Vec<ValueSample>, Box<dyn Error>> {
+    read_samples(path, shim)
+}
+
+/// Win-probability network: `sigmoid(w2 · tanh(W1 x + b1) + b2)`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ValueNet {
+    pub inputs: usize,
+    pub hidden: usize,
+    /// Hidden weights, row-major: `hidden` rows of `inputs`.
+    pub w1: Vec<f32>,
+    pub b1: Vec<f32>,
+    pub w2: Vec<f32>,
+    pub b2: f32,
+}
+
+impl ValueNet {
+    /// Randomly initialized net over [`STATE_FEATURES`] inputs.
+    pub fn new(hidden: usize, seed: u64) -> Self {
+        Self::with_inputs(STATE_FEATURES, hidden, seed)
+    }
+
+    pub fn with_inputs(inputs: usize, hidden: usize, seed: u64) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let scale1 = (6.0 / (inputs + hidden) as f32).sqrt();
+        let scale2 = (6.0 / (hidden + 1) as f32).sqrt();
+        Self {
+            inputs,
+            hidden,
+            w1: (0..inputs * hidden)
+                .map(|_| rng.gen_range(-scale1..scale1))
+                .collect(),
+            b1: vec![0.0; hidden],
+            w2: (0..hidden)
+                .map(|_| rng.gen_range(-scale2..scale2))
+                .collect(),
+            b2: 0.0,
+        }
+    }
+
+    /// Probability that the seat the features were encoded for wins.
+    pub fn predict(&self, features: &[f32]) -> f32 {
+        sigmoid(self.logit(features))
+    }
+
+    /// Output before the sigmoid, the log-odds of [`ValueNet::predict`].
+    pub fn logit(&self, features: &[f32]) -> f32 {
+        let mut activations = vec![0.0; self.hidden];
+        self.forward(features, &mut activations)
+    }
+
+    /// Gradient of [`ValueNet::logit`] with respect to each input feature.
+    pub fn input_gradient(&self, features: &[f32]) -> Vec<f32> {
+        let mut activations = vec![0.0; self.hidden];
+        self.forward(features, &mut activations);
+        let mut gradient = vec![0.0; self.inputs];
+        for (h, &activation) in activations.iter().enumerate() {
+            let delta = self.w2[h] * (1.0 - activation * activation);
+            let row = &self.w1[h * self.inputs..(h + 1) * self.inputs];
+            for (g, w) in gradient.iter_mut().zip(row) {
+                *g += delta * w;
+            }
+        }
+        gradient
+    }
+
+    /// [`ValueNet::predict`] for a view, from its viewing player's perspective.
+    pub fn evaluate<V: StateView + ?Sized>(&self, state: &V) -> f32 {
+        self.predict(&StateEncoder::encode(state))
+    }
+
+    /// Output logit, leaving the hidden activations in `activations`.
+    fn forward(&self, features: &[f32], activations: &mut [f32]) -> f32 {
+        let mut logit = self.b2;
+        for (h, activation) in activations.iter_mut().enumerate() {
+            let row = &self.w1[h * self.inputs..(h + 1) * self.inputs];
+            let sum: f32 = row.iter().zip(features).map(|(w, x)| w * x).sum();
+            *activation = (sum + self.b1[h]).tanh();
+            logit += self.w2[h] * *activation;
+        }
+        logit
+    }
+
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        Self::load_with(path, FeatureShim::Strict)
+    }
+
+    /// [`ValueNet::load`], adapting a net over another encoder version with `shim`:
+    /// weights of features the encoder no longer produces are dropped and new features
+    /// get zero weights.
+    pub fn load_with(path: &Path, shim: FeatureShim) -> Result<Self, Box<dyn Error>> {
+        let checkpoint: ValueCheckpoint = serde_json::from_str(&fs::read_to_string(path)?)?;
+        let mut net = checkpoint.net;
+        if net.w1.len() != net.inputs * net.hidden
+            || net.b1.len() != net.hidden
+            || net.w2.len() != net.hidden
+        {
+            return Err(format!("{}: weight shapes do not match", path.display()).into());
+        }
+        if let Some(representation) = checkpoint.representation {
+            representation
+                .check(shim)
+                .map_err(|err| format!("{}: {err}", path.display()))?;
+            if shim == FeatureShim::Resize {
+                net.resize_inputs(STATE_FEATURES);
+            }
+        }
+        Ok(net)
+    }
+
+    /// Save the net stamped with the current [`Representation`].
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        let checkpoint = ValueCheckpoint {
+            representation: Some(Representation::with_features(self.inputs)),
+            net: self.clone(),
+        };
+        fs::write(path, serde_json::to_string(&checkpoint)?)?;
+        Ok(())
+    }
+
+    /// Pad (with zero weights) or truncate the input layer to `inputs` features.
+    fn resize_inputs(&mut self, inputs: usize) {
+        let mut w1 = vec![0.0; inputs * self.hidden];
+        let kept = inputs.min(self.inputs);
+        for h in 0..self.hidden {
+            w1[h * inputs..h * inputs + kept]
+                .copy_from_slice(&self.w1[h * self.inputs..h * self.inputs + kept]);
+        }
+        self.w1 = w1;
+        self.inputs = inputs;
+    }
+}
+
+/// File layout of a saved [`ValueNet`]: the net's fields plus the stamp, which nets saved
+/// before stamping lack.
+#[derive(Serialize, Deserialize)]
+struct ValueCheckpoint {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    representation: Option<Representation>,
+    #[serde(flatten)]
+    net: ValueNet,
+}
+
+impl EvalFn for ValueNet {
+    /// Win probability of the view as it looks at the end of the line, with the next
+    /// opponent's stock reduced by the cards the modelled response played.
+    fn evaluate(
+        &self,
+        state: &GameStateView,
+        position: &TurnPosition,
+        opponent_unlocks: usize,
+    ) -> f32 {
+        if position.stock_count == 0 {
+            return WIN_SCORE;
+        }
+        self.predict(&StateEncoder::encode(&line_view(
+            state,
+            position,
+            opponent_unlocks,
+        )))
+    }
+}
+
+/// `state` as it looks at the end of a searched line. Only the fields the encoder reads
+/// follow the line.
+fn line_view(
+    state: &GameStateView,
+    position: &TurnPosition,
+    opponent_unlocks: usize,
+) -> GameStateView {
+    let mut view = state.clone();
+    for (pile, &next) in view.build_piles.iter_mut().zip(&position.build_next) {
+        pile.next_value = next;
+    }
+    view.hand = Arc::new(position.hand.clone());
+    let seats = view.players.len();
+    let me = state.self_player.0;
+    let player = &mut view.players[me];
+    player.stock_count = position.stock_count;
+    player.stock_top = position.stock_top;
+    player.discard_piles = Arc::new(position.discard_piles.clone());
+    player.hand_size = position.hand.len();
+    if seats > 1 {
+        let next = &mut view.players[(me + 1) % seats];
+        next.stock_count = next.stock_count.saturating_sub(opponent_unlocks);
+    }
+    view
+}
+
+/// Several [`ValueNet`]s evaluated as one: the sigmoid of the mean of their logits, which
+/// weighs a confident net more than averaging probabilities would.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ValueEnsemble {
+    nets: Vec<ValueNet>,
+}
+
+impl ValueEnsemble {
+    /// Ensemble of `nets`, which must be at least one and share their input size.
+    pub fn new(nets: Vec<ValueNet>) -> Result<Self, String> {
+        let Some(first) = nets.first() else {
+            return Err("an ensemble needs at least one net".into());
+        };
+        if let Some(other) = nets.iter().find(|net| net.inputs != first.inputs) {
+            return Err(format!(
+                "ensemble nets take {} and {} inputs",
+                first.inputs, other.inputs
+            ));
+        }
+        Ok(Self { nets })
+    }
+
+    /// Load every checkpoint in `paths` with [`ValueNet::load`].
+    pub fn load<P: AsRef<Path>>(paths: &[P]) -> Result<Self, Box<dyn Error>> {
+        let nets = paths
+            .iter()
+            .map(|path| ValueNet::load(path.as_ref()))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self::new(nets)?)
+    }
+
+    pub fn nets(&self) -> &[ValueNet] {
+        &self.nets
+    }
+
+    pub fn inputs(&self) -> usize {
+        self.nets[0].inputs
+    }
+
+    /// Probability that the seat the features were encoded for wins.
+    pub fn predict(&self, features: &[f32]) -> f32 {
+        let sum: f32 = self.nets.iter().map(|net| net.logit(features)).sum();
+        sigmoid(sum / self.nets.len() as f32)
+    }
+
+    /// [`ValueEnsemble::predict`] for a view, from its viewing player's perspective.
+    pub fn evaluate<V: StateView + ?Sized>(&self, state: &V) -> f32 {
+        self.predict(&StateEncoder::encode(state))
+    }
+}
+
+impl EvalFn for ValueEnsemble {
+    /// As [`ValueNet`]'s evaluation, encoding the line's view once for every net.
+    fn evaluate(
+        &self,
+        state: &GameStateView,
+        position: &TurnPosition,
+        opponent_unlocks: usize,
+    ) -> f32 {
+        if position.stock_count == 0 {
+            return WIN_SCORE;
+        }
+        self.predict(&StateEncoder::encode(&line_view(
+            state,
+            position,
+            opponent_unlocks,
+        )))
+    }
+}
+
+fn sigmoid(x: f32) -> f32 {
+    1.0 / (1.0 + (-x).exp())
+}
+
+/// Settings for [`train_value_net`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ValueTrainConfig {
+    pub hidden: usize,
+    pub epochs: usize,
+    pub batch_size: usize,
+    pub learning_rate: f32,
+    /// Share of games held out for validation.
+    pub validation_fraction: f64,
+    /// Bins of the reliability diagram.
+    pub bins: usize,
+    pub seed: u64,
+    /// Games the current net plays against `eval_opponent` on evaluation epochs; 0 turns
+    /// game-play evaluation off. Needs nets over [`StateEncoder`] features.
+    #[serde(default)]
+    pub eval_games: usize,
+    /// Play evaluation games every this many epochs, and after the last one.
+    #[serde(default = "default_eval_every")]
+    pub eval_every: usize,
+    #[serde(default = "default_eval_opponent")]
+    pub eval_opponent: String,
+    /// Return the best epoch's net rather than the last one's.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub best_by: Option<Selection>,
+    /// Fewest evaluation games per epoch that selection by [`Selection::WinRate`] accepts.
+    #[serde(default = "default_min_eval_games")]
+    pub min_eval_games: usize,
+    /// Train on the samples in [`canonical_order`] rather than as given.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub deterministic: bool,
+}
+
+fn default_min_eval_games() -> usize {
+    20
+}
+
+fn default_eval_every() -> usize {
+    5
+}
+
+fn default_eval_opponent() -> String {
+    "heuristic13".to_string()
+}
+
+impl Default for ValueTrainConfig {
+    fn default() -> Self {
+        Self {
+            hidden: 32,
+            epochs: 20,
+            batch_size: 64,
+            learning_rate: 1e-3,
+            validation_fraction: 0.2,
+            bins: 10,
+            seed: 0,
+            eval_games: 0,
+            eval_every: default_eval_every(),
+            eval_opponent: default_eval_opponent(),
+            best_by: None,
+            min_eval_games: default_min_eval_games(),
+            deterministic: false,
+        }
+    }
+}
+
+/// One bin of a reliability diagram.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ReliabilityBin {
+    /// Predicted probabilities in `[lower, upper)` (the last bin includes 1).
+    pub lower: f64,
+    pub upper: f64,
+    pub count: usize,
+    pub mean_predicted: f64,
+    /// Mean target of the bin's samples: for a value net the win rate, draws counting
+    /// half.
+    pub observed: f64,
+}
+
+/// How well predicted probabilities match outcomes: a value net's win probabilities, or
+/// a policy's confidence in its top move ([`crate::metrics::PolicyDecisions::calibration`]).
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Calibration {
+    pub samples: usize,
+    /// Mean squared error of the probabilities.
+    pub brier: f64,
+    pub log_loss: f64,
+    /// Expected calibration error: bin gaps weighted by bin size.
+    pub ece: f64,
+    pub bins: Vec<ReliabilityBin>,
+}
+
+impl Calibration {
+    /// Measure `net` on `samples` with `bins` equal-width probability bins.
+    pub fn measure(net: &ValueNet, samples: &[ValueSample], bins: usize) -> Self {
+        Self::from_predictions(
+            samples.iter().map(|sample| {
+                (
+                    f64::from(net.predict(&sample.features)),
+                    f64::from(sample.target()),
+                )
+            }),
+            bins,
+        )
+    }
+
+    /// Calibration of `(predicted probability, target)` pairs, targets between 0 and 1,
+    /// with `bins` equal-width probability bins.
+    pub fn from_predictions(
+        predictions: impl IntoIterator<Item = (f64, f64)>,
+        bins: usize,
+    ) -> Self {
+        let bins = bins.max(1);
+        let mut totals = vec![(0usize, 0.0f64, 0.0f64); bins];
+        let (mut samples, mut brier, mut log_loss) = (0, 0.0, 0.0);
+        for (p, y) in predictions {
+            samples += 1;
+            brier += (p - y).powi(2);
+            let clamped = p.clamp(1e-7, 1.0 - 1e-7);
+            log_loss -= y * clamped.ln() + (1.0 - y) * (1.0 - clamped).ln();
+            let bin = ((p * bins as f64) as usize).min(bins - 1);
+            totals[bin].0 += 1;
+            totals[bin].1 += p;
+            totals[bin].2 += y;
+        }
+        let n = samples.max(1) as f64;
+        let bins: Vec<ReliabilityBin> = totals
+            .into_iter()
+            .enumerate()
+            .map(|(i, (count, predicted, observed))| {
+                let denominator = count.max(1) as f64;
+                ReliabilityBin {
+                    lower: i as f64 / bins as f64,
+                    upper: (i + 1) as f64 / bins as f64,
+                    count,
+                    mean_predicted: predicted / denominator,
+                    observed: observed / denominator,
+                }
+            })
+            .collect();
+        let ece = bins
+            .iter()
+            .map(|bin| bin.count as f64 / n * (bin.mean_predicted - bin.observed).abs())
+            .sum();
+        Self {
+            samples,
+            brier: brier / n,
+            log_loss: log_loss / n,
+            ece,
+            bins,
+        }
+    }
+}
+
+/// Metrics of one training epoch.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct EpochMetrics {
+    /// One-based epoch number.
+    pub epoch: usize,
+    /// Mean training cross-entropy over the epoch.
+    pub loss: f64,
+    /// Cross-entropy on the held-out games after the epoch (0 when none are held out).
+    pub validation_loss: f64,
+    /// Learning rate of the epoch's steps.
+    #[serde(default)]
+    pub learning_rate: f64,
+    /// Win rate of the net's beam search bot against the evaluation opponent, on epochs
+    /// with evaluation games.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub win_rate: Option<f64>,
+    /// The epoch is the best so far under [`ValueTrainConfig::best_by`].
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub best: bool,
+}
+
+/// How [`train_value_net`] picks the best epoch.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Selection {
+    /// Lowest cross-entropy on the held-out games.
+    ValidationLoss,
+    /// Highest evaluation win rate, among epochs with evaluation games; ties go to the
+    /// lower validation loss.
+    WinRate,
+}
+
+impl fmt::Display for Selection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Selection::ValidationLoss => "validation-loss",
+            Selection::WinRate => "win-rate",
+        })
+    }
+}
+
+impl FromStr for Selection {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "validation-loss" => Ok(Selection::ValidationLoss),
+            "win-rate" => Ok(Selection::WinRate),
+            _ => Err(format!(
+                "invalid selection: {s} (expected validation-loss or win-rate)"
+            )),
+        }
+    }
+}
+
+impl Selection {
+    /// Whether `candidate` beats `best`. Epochs without a win rate never win under
+    /// [`Selection::WinRate`].
+    fn prefers(self, candidate: &EpochMetrics, best: Option<&CheckpointMetadata>) -> bool {
+        let win_rate = match self {
+            Selection::ValidationLoss => None,
+            Selection::WinRate => match candidate.win_rate {
+                Some(rate) => Some(rate),
+                None => return false,
+            },
+        };
+        let Some(best) = best else {
+            return true;
+        };
+        match (win_rate, best.win_rate) {
+            (Some(rate), Some(best_rate)) if rate != best_rate => rate > best_rate,
+            _ => candidate.validation_loss < best.validation_loss,
+        }
+    }
+}
+
+/// Where the returned net comes from when [`ValueTrainConfig::best_by`] is set; every
+/// criterion is recorded, not just the one the choice was made by.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CheckpointMetadata {
+    pub epoch: usize,
+    pub selected_by: Selection,
+    pub validation_loss: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub win_rate: Option<f64>,
+    /// Evaluation games behind `win_rate` (0 without one).
+    pub eval_games: usize,
+    pub eval_opponent: String,
+}
+
+/// Result of [`train_value_net`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ValueTraining {
+    /// The last epoch's net, or the best one's when selecting.
+    pub net: ValueNet,
+    pub epochs: Vec<EpochMetrics>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub best: Option<CheckpointMetadata>,
+    pub train: Calibration,
+    pub validation: Calibration,
+    /// Training was interrupted; `epochs` holds only the epochs that finished.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub interrupted: bool,
+}
+
+impl ValueTraining {
+    /// Training loss after each epoch.
+    pub fn losses(&self) -> Vec<f64> {
+        self.epochs.iter().map(|epoch| epoch.loss).collect()
+    }
+}
+
+/// Beam width and depth of the bot that plays evaluation games with the net; small, so
+/// evaluation stays quick.
+const EVAL_BEAM: (usize, usize) = (4, 6);
+const EVAL_MAX_ACTIONS: usize = 2000;
+
+/// Win rate of a beam search bot evaluating with `net` against `opponent` over `games`
+/// validation deals (each played from both seats).
+pub fn play_strength(
+    net: &ValueNet,
+    opponent: &str,
+    seed: u64,
+    games: usize,
+) -> Result<f64, Box<dyn Error>> {
+    if net.inputs != STATE_FEATURES {
+        return Err(format!(
+            "game-play evaluation needs a net over {STATE_FEATURES} encoder features, not {}",
+            net.inputs
+        )
+        .into());
+    }
+    let shared: Arc<dyn EvalFn> = Arc::new(net.clone());
+    head_to_head(
+        |seat, deal| {
+            let rng = StdRng::seed_from_u64(mix_seed(seed, deal, seat.0 as u64 + 2));
+            Box::new(
+                BeamSearchBot::new(rng, EVAL_BEAM.0, EVAL_BEAM.1)
+                    .with_shared_evaluation(Arc::clone(&shared)),
+            )
+        },
+        opponent,
+        SeedSplit::Validation,
+        seed,
+        0,
+        games,
+        EVAL_MAX_ACTIONS,
+    )
+}
+
+/// Fit a [`ValueNet`] to `samples`, holding out whole games for validation.
+pub fn train_value_net(
+    samples: &[ValueSample],
+    config: &ValueTrainConfig,
+) -> Result<ValueTraining, Box<dyn Error>> {
+    train_value_net_with(samples, config, |_| {})
+}
+
+/// Sort samples by game, then by features and outcome, so any merge of the same samples
+/// ends up in the same order.
+pub fn canonical_order(samples: &mut [ValueSample]) {
+    samples.sort_by(|a, b| {
+        a.game
+            .cmp(&b.game)
+            .then_with(|| {
+                a.features
+                    .iter()
+                    .zip(&b.features)
+                    .map(|(x, y)| x.total_cmp(y))
+                    .find(|order| order.is_ne())
+                    .unwrap_or_else(|| a.features.len().cmp(&b.features.len()))
+            })
+            .then_with(|| a.outcome.total_cmp(&b.outcome))
+    });
+}
+
+/// [`train_value_net`], passing each epoch's metrics to `on_epoch` as soon as they are
+/// known.
+pub fn train_value_net_with<F>(
+    samples: &[ValueSample],
+    config: &ValueTrainConfig,
+    on_epoch: F,
+) -> Result<ValueTraining, Box<dyn Error>>
+where
+    F: FnMut(&EpochMetrics),
+{
+    train_value_net_until(samples, config, &Interrupter::new(), on_epoch)
+}
+
+/// [`train_value_net_with`], stopping after the epoch in progress once `interrupter` is
+/// cancelled. The result is then marked [`ValueTraining::interrupted`].
+pub fn train_value_net_until<F>(
+    samples: &[ValueSample],
+    config: &ValueTrainConfig,
+    interrupter: &Interrupter,
+    mut on_epoch: F,
+) -> Result<ValueTraining, Box<dyn Error>>
+where
+    F: FnMut(&EpochMetrics),
+{
+    let mut sorted = Vec::new();
+    let samples = if config.deterministic {
+        sorted.extend_from_slice(samples);
+        canonical_order(&mut sorted);
+        &sorted[..]
+    } else {
+        samples
+    };
+    let inputs = samples
+        .first()
+        .ok_or("no samples to train on")?
+        .features
+        .len();
+    if samples.iter().any(|s| s.features.len() != inputs) {
+        return Err("samples have different feature lengths".into());
+    }
+    let held_out = |game: usize| {
+        let draw = mix_seed(config.seed, game as u64, 0x7A11) as f64 / u64::MAX as f64;
+        draw < config.validation_fraction
+    };
+    let (validation, train): (Vec<&ValueSample>, Vec<&ValueSample>) =
+        samples.iter().partition(|s| held_out(s.game));
+    if train.is_empty() {
+        return Err("every game was held out for validation".into());
+    }
+    if config.eval_games > 0 && inputs != STATE_FEATURES {
+        return Err("game-play evaluation needs samples of StateEncoder features".into());
+    }
+    if config.best_by == Some(Selection::WinRate) && config.eval_games < config.min_eval_games {
+        return Err(format!(
+            "selecting by win rate needs at least {} evaluation games per epoch, not {}",
+            config.min_eval_games, config.eval_games
+        )
+        .into());
+    }
+    let train: Vec<ValueSample> = train.into_iter().cloned().collect();
+    let validation: Vec<ValueSample> = validation.into_iter().cloned().collect();
+
+    let mut net = ValueNet::with_inputs(inputs, config.hidden, config.seed);
+    let mut adam = Adam::new(&net, config.learning_rate);
+    let mut grad = Gradient::zeros(&net);
+    let mut activations = vec![0.0; net.hidden];
+    let mut order: Vec<usize> = (0..train.len()).collect();
+    let mut rng = StdRng::seed_from_u64(mix_seed(config.seed, 0, 0x0BA7));
+    let mut epochs = Vec::with_capacity(config.epochs);
+    let mut best: Option<(CheckpointMetadata, ValueNet)> = None;
+    let mut interrupted = false;
+    for epoch in 1..=config.epochs {
+        order.shuffle(&mut rng);
+        let mut loss = 0.0f64;
+        for batch in order.chunks(config.batch_size.max(1)) {
+            grad.clear();
+            for &i in batch {
+                let sample = &train[i];
+                let p = sigmoid(net.forward(&sample.features, &mut activations));
+                let y = sample.target();
+                let clamped = f64::from(p).clamp(1e-7, 1.0 - 1e-7);
+                loss -= f64::from(y) * clamped.ln() + f64::from(1.0 - y) * (1.0 - clamped).ln();
+                grad.add(&net, &sample.features, &activations, p - y);
+            }
+            adam.step(&mut net, &grad, batch.len());
+        }
+        let evaluate = config.eval_games > 0
+            && (epoch % config.eval_every.max(1) == 0 || epoch == config.epochs);
+        let mut metrics = EpochMetrics {
+            epoch,
+            loss: loss / train.len() as f64,
+            validation_loss: Calibration::measure(&net, &validation, 1).log_loss,
+            learning_rate: f64::from(config.learning_rate),
+            win_rate: if evaluate {
+                Some(play_strength(
+                    &net,
+                    &config.eval_opponent,
+                    config.seed,
+                    config.eval_games,
+                )?)
+            } else {
+                None
+            },
+            best: false,
+        };
+        if let Some(selection) = config.best_by
+            && selection.prefers(&metrics, best.as_ref().map(|(meta, _)| meta))
+        {
+            metrics.best = true;
+            let meta = CheckpointMetadata {
+                epoch,
+                selected_by: selection,
+                validation_loss: metrics.validation_loss,
+                win_rate: metrics.win_rate,
+                eval_games: if metrics.win_rate.is_some() {
+                    config.eval_games
+                } else {
+                    0
+                },
+                eval_opponent: config.eval_opponent.clone(),
+            };
+            best = Some((meta, net.clone()));
+        }
+        on_epoch(&metrics);
+        epochs.push(metrics);
+        if epoch < config.epochs && interrupter.is_cancelled() {
+            interrupted = true;
+            break;
+        }
+    }
+
+    let (best, net) = match best {
+        Some((meta, best_net)) => (Some(meta), best_net),
+        None => (None, net),
+    };
+    Ok(ValueTraining {
+        train: Calibration::measure(&net, &train, config.bins),
+        validation: Calibration::measure(&net, &validation, config.bins),
+        net,
+        epochs,
+        best,
+        interrupted,
+    })
+}
+
+/// Summed gradients of a batch, shaped like the net.
+struct Gradient {
+    w1: Vec<f32>,
+    b1: Vec<f32>,
+    w2: Vec<f32>,
+    b2: f32,
+}
+
+impl Gradient {
+    fn zeros(net: &ValueNet) -> Self {
+        Self {
+            w1: vec![0.0; net.w1.len()],
+            b1: vec![0.0; net.b1.len()],
+            w2: vec![0.0; net.w2.len()],
+            b2: 0.0,
+        }
+    }
+
+    fn clear(&mut self) {
+        self.w1.fill(0.0);
+        self.b1.fill(0.0);
+        self.w2.fill(0.0);
+        self.b2 = 0.0;
+    }
+
+    /// Backpropagate one sample whose output error (`p - y`) is `error`.
+    fn add(&mut self, net: &ValueNet, features: &[f32], activations: &[f32], error: f32) {
+        self.b2 += error;
+        for (h, &activation) in activations.iter().enumerate() {
+            self.w2[h] += error * activation;
+            let delta = error * net.w2[h] * (1.0 - activation * activation);
+            self.b1[h] += delta;
+            let row = &mut self.w1[h * net.inputs..(h + 1) * net.inputs];
+            for (g, x) in row.iter_mut().zip(features) {
+                *g += delta * x;
+            }
+        }
+    }
+}
+
+/// Adam optimizer state for every parameter of a [`ValueNet`].
+struct Adam {
+    learning_rate: f32,
+    steps: i32,
+    moments: [(Vec<f32>, Vec<f32>); 4],
+}
+
+impl Adam {
+    const BETA1: f32 = 0.9;
+    const BETA2: f32 = 0.999;
+    const EPSILON: f32 = 1e-8;
+
+    fn new(net: &ValueNet, learning_rate: f32) -> Self {
+        let zeros = |len: usize| (vec![0.0; len], vec![0.0; len]);
+        Self {
+            learning_rate,
+            steps: 0,
+            moments: [
+                zeros(net.w1.len()),
+                zeros(net.b1.len()),
+                zeros(net.w2.len()),
+                zeros(1),
+            ],
+        }
+    }
+
+    fn step(&mut self, net: &mut ValueNet, grad: &Gradient, batch: usize) {
+        self.steps += 1;
+        let scale = 1.0 / batch.max(1) as f32;
+        let correction1 = 1.0 - Self::BETA1.powi(self.steps);
+        let correction2 = 1.0 - Self::BETA2.powi(self.steps);
+        let rate = self.learning_rate;
+        let mut b2 = [net.b2];
+        let params: [(&mut [f32], &[f32]); 4] = [
+            (&mut net.w1, &grad.w1),
+            (&mut net.b1, &grad.b1),
+            (&mut net.w2, &grad.w2),
+            (&mut b2, std::slice::from_ref(&grad.b2)),
+        ];
+        for ((values, grads), (m, v)) in params.into_iter().zip(&mut self.moments) {
+            for i in 0..values.len() {
+                let g = grads[i] * scale;
+                m[i] = Self::BETA1 * m[i] + (1.0 - Self::BETA1) * g;
+                v[i] = Self::BETA2 * v[i] + (1.0 - Self::BETA2) * g * g;
+                let m_hat = m[i] / correction1;
+                let v_hat = v[i] / correction2;
+                values[i] -= rate * m_hat / (v_hat.sqrt() + Self::EPSILON);
+            }
+        }
+        net.b2 = b2[0];
+    }
+}
@@ -1,14 +1,30 @@
 use std::fmt::Write;
 
 use crate::action::{Action, CardSource};
-use crate::card::Card;
-use crate::state::{GameStateView, GameStatus};
+use crate::bots::completion::CompletionEffect;
+use crate::card::{Card, MAX_PLAYERS};
+use crate::state::{GameStateView, GameStatus, TurnEvent};
 
 /// Customize state rendering for CLI visualization.
 #[derive(Clone, Copy, Debug)]
 pub struct VisualOptions {
     pub show_build_sequences: bool,
     pub show_discard_sizes: bool,
+    /// Win probability per player id; renders an evaluation bar when set.
+    pub win_probabilities: Option<[f64; MAX_PLAYERS]>,
+}
+
+impl VisualOptions {
+    /// Show an evaluation bar with `probabilities[i]` as player `i`'s win chance, e.g.
+    /// from [`WinEstimate`](crate::analysis::WinEstimate).
+    pub fn with_win_probabilities(mut self, probabilities: &[f64]) -> Self {
+        let mut bar = [0.0; MAX_PLAYERS];
+        for (slot, p) in bar.iter_mut().zip(probabilities) {
+            *slot = p.clamp(0.0, 1.0);
+        }
+        self.win_probabilities = Some(bar);
+        self
+    }
 }
 
 impl Default for VisualOptions {
@@ -16,6 +32,7 @@ impl Default for VisualOptions {
         Self {
             show_build_sequences: true,
             show_discard_sizes: true,
+            win_probabilities: None,
         }
     }
 }
@@ -66,6 +83,18 @@ pub fn render_state_with_options(state: &GameStateView, options: VisualOptions)
         "Draw pile: {}  |  Recycle pile: {}",
         state.draw_pile_count, state.recycle_pile_count
     );
+    if !state.turn_events.is_empty() {
+        let events: Vec<String> = state
+            .turn_events
+            .iter()
+            .map(|event| match event {
+                TurnEvent::Drew { player, cards } => format!("Player {player} drew {cards}"),
+                TurnEvent::PileCompleted { pile } => format!("build pile [{pile}] completed"),
+                TurnEvent::Reshuffled { cards } => format!("{cards} recycled cards reshuffled"),
+            })
+            .collect();
+        let _ = writeln!(out, "This turn: {}", events.join(", "));
+    }
     let _ = writeln!(out, "Build piles:");
     for (idx, pile) in state.build_piles.iter().enumerate() {
         let sequence = if options.show_build_sequences && !pile.cards.is_empty() {
@@ -93,9 +122,8 @@ pub fn render_state_with_options(state: &GameStateView, options: VisualOptions)
             .stock_top
             .map(format_card)
             .unwrap_or_else(|| String::from("--"));
-        let mut discard_parts = Vec::with_capacity(state.settings.discard_piles);
-        for idx in 0..state.settings.discard_piles {
-            let pile = &player.discard_piles[idx];
+        let mut discard_parts = Vec::with_capacity(player.discard_piles.len());
+        for (idx, pile) in player.discard_piles.iter().enumerate() {
             let top = pile
                 .last()
                 .map(|c| format_card(*c))
@@ -128,9 +156,48 @@ pub fn render_state_with_options(state: &GameStateView, options: VisualOptions)
             let _ = writeln!(out, "    Hand size: {}", player.hand_size);
         }
     }
+    if let Some(probabilities) = options.win_probabilities {
+        let _ = writeln!(out, "Evaluation:");
+        for player in &state.players {
+            let p = probabilities.get(player.id.0).copied().unwrap_or(0.0);
+            let filled = (p * EVAL_BAR_WIDTH as f64).round() as usize;
+            let _ = writeln!(
+                out,
+                "  Player {} [{}{}] {:>5.1}%",
+                player.id,
+                "#".repeat(filled),
+                "-".repeat(EVAL_BAR_WIDTH - filled),
+                p * 100.0
+            );
+        }
+    }
     out
 }
 
+/// Character width of the evaluation bar.
+const EVAL_BAR_WIDTH: usize = 20;
+
+/// One-line summary of a pile completion: each player's unlock distance before and after
+/// the pile starts over, and the change in unlock balance.
+pub fn describe_completion(effect: &CompletionEffect) -> String {
+    let changes: Vec<String> = effect
+        .changes
+        .iter()
+        .map(|change| {
+            format!(
+                "Player {} {} -> {}",
+                change.player, change.before, change.after
+            )
+        })
+        .collect();
+    format!(
+        "completes build pile {} (unlock distance {}; balance {:+.1})",
+        effect.build_pile,
+        changes.join(", "),
+        effect.value
+    )
+}
+
 pub fn describe_action(state: &GameStateView, action: &Action) -> String {
     describe_action_with_options(state, action, DescribeOptions::default())
 }
@@ -144,7 +211,7 @@ pub fn describe_action_with_options(
         Action::Play { source, build_pile } => {
             let pile_info = state
                 .build_piles
-                .get(*build_pile)
+                .get(build_pile.0)
                 .map(|pile| pile.next_value)
                 .unwrap_or(0);
             let source_desc = match source {
@@ -160,10 +227,7 @@ pub fn describe_action_with_options(
                     }
                 }
                 CardSource::Stock => {
-                    let self_player = state
-                        .players
-                        .iter()
-                        .find(|player| player.id == state.self_player);
+                    let self_player = state.self_player_state();
                     if let Some(player) = self_player {
                         if let Some(card) = player.stock_top {
                             if options.include_card_details {
@@ -179,14 +243,11 @@ pub fn describe_action_with_options(
                     }
                 }
                 CardSource::Discard(index) => {
-                    let self_player = state
-                        .players
-                        .iter()
-                        .find(|player| player.id == state.self_player);
+                    let self_player = state.self_player_state();
                     if let Some(player) = self_player {
                         let top = player
                             .discard_piles
-                            .get(*index)
+                            .get(index.0)
                             .and_then(|pile| pile.last())
                             .copied();
                         if options.include_card_details {
@@ -238,17 +299,17 @@ fn format_card(card: Card) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::action::Action;
+    use crate::action::{Action, DiscardPileId, PlayerId};
     use crate::game::GameBuilder;
 
     #[test]
     fn render_and_describe_include_expected_phrases() {
         let game = GameBuilder::new(2).expect("builder").build().expect("game");
-        let view = game.state_view(0).expect("state view");
+        let view = game.state_view(PlayerId(0)).expect("state view");
         let text = render_state(&view);
         assert!(text.contains("Player 0 (You)"));
         assert!(text.contains("Hand:"));
-        let actions = game.legal_actions(0).expect("actions available");
+        let actions = game.legal_actions(PlayerId(0)).expect("actions available");
         if let Some(play_action) = actions
             .iter()
             .find(|action| matches!(action, Action::Play { .. }))
@@ -259,7 +320,7 @@ mod tests {
         // Ensure we can describe a discard action as well.
         let discard_action = Action::Discard {
             hand_index: 0,
-            discard_pile: 0,
+            discard_pile: DiscardPileId(0),
         };
         let discard_desc = describe_action(&view, &discard_action);
         assert!(discard_desc.contains("Discard"));
@@ -0,0 +1,136 @@
+use std::io::Cursor;
+
+use skipbot::archive::{
+    ArchiveReader, ArchiveWriter, BLOCK_RECORDS, Compression, decode_record, encode_record,
+};
+use skipbot::runner::{Handicap, MatchConfig, MatchRunner, play_out};
+use skipbot::{
+    Bot, Game, GameRecord, LoopGuard, PlayerId, SeatRules, StockVisibility, create_bot_from_spec,
+};
+
+fn played_record(seed: u64) -> GameRecord {
+    let mut game = Game::builder(3)
+        .unwrap()
+        .with_seed(seed)
+        .with_stock_size(8)
+        .with_hand_size(4)
+        .with_stock_visibility(StockVisibility::Binned)
+        .with_loop_guard(LoopGuard::EndTurn)
+        .with_max_actions_per_turn(100)
+        .with_seat_rules(
+            PlayerId(2),
+            SeatRules {
+                extra_discard_pile: true,
+                weak_draws: false,
+                stock_wilds: Some(1),
+            },
+        )
+        .build()
+        .unwrap();
+    let mut bots: Vec<Box<dyn Bot>> = PlayerId::all(3)
+        .map(|seat| create_bot_from_spec("heuristic13", seat, seed).unwrap())
+        .collect();
+    play_out(&mut game, &mut bots, 2000, |_| {}).unwrap();
+    GameRecord::from_game(&game)
+        .unwrap()
+        .with_tag("Event", "archive test")
+}
+
+#[test]
+fn binary_records_round_trip_and_beat_the_text_format() {
+    let record = played_record(5);
+    let bytes = encode_record(&record).unwrap();
+    assert_eq!(decode_record(&bytes).unwrap(), record);
+    assert!(bytes.len() * 2 < record.to_string().len());
+
+    assert!(decode_record(&bytes[..bytes.len() - 1]).is_err());
+    let mut trailing = bytes.clone();
+    trailing.push(0);
+    assert!(decode_record(&trailing).is_err());
+}
+
+#[test]
+fn archives_give_random_access_to_their_games() {
+    let records: Vec<GameRecord> = (0..6).map(played_record).collect();
+    let mut writer = ArchiveWriter::new(Vec::new(), Compression::None).unwrap();
+    for record in &records {
+        writer.push(record).unwrap();
+    }
+    assert_eq!(writer.len(), 6);
+    let bytes = writer.finish().unwrap();
+
+    let mut reader = ArchiveReader::new(Cursor::new(bytes)).unwrap();
+    assert_eq!(reader.len(), 6);
+    assert_eq!(reader.get(4).unwrap(), records[4]);
+    assert_eq!(reader.get(1).unwrap(), records[1]);
+    assert!(reader.get(6).is_err());
+    let all: Vec<GameRecord> = reader.records().collect::<Result<_, _>>().unwrap();
+    assert_eq!(all, records);
+    let replayed = all[3].replay().unwrap();
+    assert_eq!(replayed.status(), records[3].result);
+}
+
+#[test]
+fn records_spanning_several_blocks_stay_addressable() {
+    let record = played_record(7);
+    let count = BLOCK_RECORDS * 2 + 3;
+    let mut writer = ArchiveWriter::new(Vec::new(), Compression::None).unwrap();
+    for game in 0..count {
+        writer
+            .push(&record.clone().with_tag("Game", &game.to_string()))
+            .unwrap();
+    }
+    let mut reader = ArchiveReader::new(Cursor::new(writer.finish().unwrap())).unwrap();
+    assert_eq!(reader.len(), count);
+    for game in [
+        count - 1,
+        0,
+        BLOCK_RECORDS,
+        BLOCK_RECORDS - 1,
+        2 * BLOCK_RECORDS + 1,
+    ] {
+        let read = reader.get(game).unwrap();
+        assert_eq!(read.tag("Game"), Some(game.to_string().as_str()));
+        assert_eq!(read.moves, record.moves);
+    }
+}
+
+#[test]
+fn unfinished_archives_are_rejected() {
+    let mut writer = ArchiveWriter::new(Vec::new(), Compression::None).unwrap();
+    writer.push(&played_record(1)).unwrap();
+    let mut bytes = writer.finish().unwrap();
+    bytes.truncate(bytes.len() - 4);
+    assert!(ArchiveReader::new(Cursor::new(bytes)).is_err());
+}
+
+#[test]
+fn zstd_archives_need_the_feature() {
+    let result = ArchiveWriter::new(Vec::new(), Compression::Zstd(3))
+        .and_then(|mut writer| writer.push(&played_record(2)).and_then(|_| writer.finish()));
+    if cfg!(feature = "zstd") {
+        let bytes = result.unwrap();
+        let mut reader = ArchiveReader::new(Cursor::new(bytes)).unwrap();
+        assert_eq!(reader.get(0).unwrap(), played_record(2));
+    } else {
+        assert!(result.is_err());
+    }
+}
+
+#[test]
+fn the_runner_archives_every_game_with_its_seats() {
+    let mut config = MatchConfig::new(vec!["heuristic13".into(), "random".into()], 4, 9);
+    config.stock_size = Some(6);
+    config.add_handicap(1, Handicap::StockWilds(0));
+    let runner = MatchRunner::new(config).unwrap();
+    let mut writer = ArchiveWriter::new(Vec::new(), Compression::None).unwrap();
+    let report = runner.run_archived(&mut writer).unwrap();
+    let mut reader = ArchiveReader::new(Cursor::new(writer.finish().unwrap())).unwrap();
+    assert_eq!(reader.len(), 4);
+    for (game, result) in report.games.iter().enumerate() {
+        let record = reader.get(game).unwrap();
+        assert_eq!(record.tag("Game"), Some(game.to_string().as_str()));
+        assert_eq!(record.tag("Bots"), Some(result.seats.join(" ").as_str()));
+        assert_eq!(record.replay().unwrap().winner(), result.winner);
+    }
+}
@@ -0,0 +1,124 @@
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use skipbot::action::{Action, BuildPileId, CardSource, DiscardPileId};
+use skipbot::bot::Bot;
+use skipbot::bots::beam_search::BeamSearchBot;
+use skipbot::bots::planning::TurnPosition;
+use skipbot::card::Card;
+use skipbot::interrupt::Interrupter;
+use skipbot::state::{BuildPileView, GameStateView};
+use skipbot::{Game, GameStatus as Status};
+
+mod common;
+use common::{pile_at, player, view};
+
+fn base_state(
+    hand: Vec<Card>,
+    stock_top: Option<Card>,
+    build_piles: [BuildPileView; 4],
+) -> GameStateView {
+    let mut state = view(
+        hand,
+        build_piles,
+        vec![player(0, stock_top, 30), player(1, None, 30)],
+    );
+    state.draw_pile_count = 40;
+    state
+}
+
+#[test]
+fn beam_search_plays_towards_stock() {
+    let state = base_state(
+        vec![Card::Number(4), Card::Number(10), Card::Number(11)],
+        Some(Card::Number(5)),
+        [pile_at(4), pile_at(8), pile_at(8), pile_at(8)],
+    );
+    let legal = vec![
+        Action::Play {
+            source: CardSource::Hand(0),
+            build_pile: BuildPileId(0),
+        },
+        Action::Discard {
+            hand_index: 1,
+            discard_pile: DiscardPileId(0),
+        },
+    ];
+    let mut bot = BeamSearchBot::new(StdRng::seed_from_u64(7), 4, 6);
+    assert_eq!(bot.select_action(&state, &legal), legal[0]);
+}
+
+#[test]
+fn beam_search_uses_custom_evaluation() {
+    fn prefer_full_hand(_state: &GameStateView, position: &TurnPosition, _: usize) -> f32 {
+        position.hand.len() as f32
+    }
+    let state = base_state(
+        vec![Card::Number(4), Card::Number(10)],
+        Some(Card::Number(9)),
+        [pile_at(4), pile_at(8), pile_at(8), pile_at(8)],
+    );
+    let legal = vec![
+        Action::Play {
+            source: CardSource::Hand(0),
+            build_pile: BuildPileId(0),
+        },
+        Action::Discard {
+            hand_index: 1,
+            discard_pile: DiscardPileId(0),
+        },
+    ];
+    let mut bot = BeamSearchBot::new(StdRng::seed_from_u64(7), 4, 1)
+        .with_samples(1)
+        .with_evaluation(prefer_full_hand);
+    assert_eq!(bot.select_action(&state, &legal), legal[1]);
+}
+
+#[test]
+fn beam_search_completes_a_game() {
+    let mut game = Game::builder(2)
+        .unwrap()
+        .with_seed(11)
+        .with_stock_size(5)
+        .build()
+        .unwrap();
+    let mut bots: Vec<Box<dyn Bot>> = vec![
+        Box::new(BeamSearchBot::new(StdRng::seed_from_u64(1), 4, 8)),
+        Box::new(BeamSearchBot::new(StdRng::seed_from_u64(2), 4, 8)),
+    ];
+    for _ in 0..5_000 {
+        if game.is_finished() {
+            break;
+        }
+        let current = game.current_player();
+        let view = game.state_view(current).unwrap();
+        let legal = game.legal_actions(current).unwrap();
+        let action = bots[current].select_action(&view, &legal);
+        game.apply_action(current, action).unwrap();
+    }
+    assert!(!matches!(game.status(), Status::Ongoing));
+}
+
+#[test]
+fn beam_search_plays_legal_moves_without_time() {
+    let mut game = Game::builder(2)
+        .unwrap()
+        .with_seed(11)
+        .with_stock_size(5)
+        .build()
+        .unwrap();
+    let mut bot = BeamSearchBot::new(StdRng::seed_from_u64(1), 8, 12).with_samples(8);
+    let expired = Interrupter::new().with_deadline(std::time::Instant::now());
+    for _ in 0..200 {
+        if game.is_finished() {
+            break;
+        }
+        let current = game.current_player();
+        let view = game.state_view(current).unwrap();
+        let legal = game.legal_actions(current).unwrap();
+        bot.interrupt_with(&expired);
+        let action = bot.select_action(&view, &legal);
+        assert!(legal.contains(&action));
+        drop(view);
+        game.apply_action(current, action).unwrap();
+    }
+}
@@ -0,0 +1,17 @@
+use skipbot::bench::{EngineBench, bench_engine};
+
+#[test]
+fn engine_bench_counts_games_and_compares_to_a_baseline() {
+    let bench = bench_engine(5, 3, 11).unwrap();
+    assert_eq!((bench.games, bench.players), (5, 3));
+    assert!(bench.actions > 0);
+    assert_eq!(bench_engine(5, 3, 11).unwrap().actions, bench.actions);
+
+    let baseline = EngineBench {
+        seconds: bench.seconds * 2.0,
+        ..bench.clone()
+    };
+    let table = bench.compare(&baseline);
+    assert!(table.contains("games/sec"));
+    assert!(table.contains("+100.0%  better"));
+}
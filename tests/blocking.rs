@@ -0,0 +1,145 @@
+use skipbot::action::{Action, BuildPileId, CardSource, DiscardPileId};
+use skipbot::bots::blocking::{BlockAnalysis, DiscardSafety};
+use skipbot::bots::planning::TurnPosition;
+use skipbot::card::Card;
+use skipbot::state::GameStateView;
+
+mod common;
+use common::{pile_at, player, view};
+
+/// Three players, seat 0 to move, holding a 5 while one build pile needs a 5.
+fn state(opponents: [(Option<Card>, usize, Vec<Vec<Card>>); 2]) -> GameStateView {
+    let mut players = vec![player(0, Some(Card::Number(12)), 20)];
+    for (seat, (stock_top, stock_count, discards)) in opponents.into_iter().enumerate() {
+        let mut opponent = player(seat + 1, stock_top, stock_count);
+        opponent.discard_piles = discards.into();
+        players.push(opponent);
+    }
+    let mut state = view(
+        vec![Card::Number(5)],
+        [pile_at(5), pile_at(1), pile_at(1), pile_at(1)],
+        players,
+    );
+    state.players[0].hand_size = 5;
+    state
+}
+
+fn play_five() -> Action {
+    Action::Play {
+        source: CardSource::Hand(0),
+        build_pile: BuildPileId(0),
+    }
+}
+
+#[test]
+fn later_opponents_count_less_than_the_next_player() {
+    let analysis = BlockAnalysis::default();
+    // Only the player two seats on has a 6: the next-player check would miss it.
+    let far = state([
+        (Some(Card::Number(9)), 20, vec![vec![]; 4]),
+        (Some(Card::Number(6)), 20, vec![vec![]; 4]),
+    ]);
+    let near = state([
+        (Some(Card::Number(6)), 20, vec![vec![]; 4]),
+        (Some(Card::Number(9)), 20, vec![vec![]; 4]),
+    ]);
+    assert_eq!(analysis.current_risk(&far).score(), 0.0);
+    let far_risk = analysis.play_risk(&far, &play_five());
+    let near_risk = analysis.play_risk(&near, &play_five());
+    assert_eq!(far_risk, 0.5);
+    assert_eq!(near_risk, 1.0);
+
+    // A nearly empty stock doubles the threat.
+    let urgent = state([
+        (Some(Card::Number(9)), 20, vec![vec![]; 4]),
+        (Some(Card::Number(6)), 3, vec![vec![]; 4]),
+    ]);
+    assert_eq!(analysis.play_risk(&urgent, &play_five()), 1.0);
+    assert_eq!(analysis.play_risk(&urgent, &Action::EndTurn), 0.0);
+}
+
+#[test]
+fn opponents_may_chain_visible_discards_before_their_stock() {
+    // The next player reaches a 7 through the 6 on their discard pile once the pile needs
+    // a 6, and through the 6 buried under a 5 only with a second discard play.
+    let chained = state([
+        (
+            Some(Card::Number(7)),
+            20,
+            vec![vec![Card::Number(6)], vec![], vec![], vec![]],
+        ),
+        (None, 20, vec![vec![]; 4]),
+    ]);
+    let risk = BlockAnalysis::default().current_risk(&chained);
+    assert_eq!(
+        risk.threats.len(),
+        1,
+        "players without a stock card are no threat"
+    );
+    assert_eq!(risk.threats[0].helpers, None);
+    assert_eq!(
+        BlockAnalysis::default().play_risk(&chained, &play_five()),
+        0.5
+    );
+
+    let buried = state([
+        (
+            Some(Card::Number(7)),
+            20,
+            vec![
+                vec![Card::Number(6), Card::Number(5)],
+                vec![],
+                vec![],
+                vec![],
+            ],
+        ),
+        (None, 20, vec![vec![]; 4]),
+    ]);
+    let threat = &BlockAnalysis::default().current_risk(&buried).threats[0];
+    assert_eq!(threat.helpers, Some(2));
+    assert!((threat.risk() - 1.0 / 3.0).abs() < 1e-9);
+    assert_eq!(
+        BlockAnalysis::new(1).current_risk(&buried).threats[0].helpers,
+        None
+    );
+}
+
+#[test]
+fn wild_stock_cards_are_always_playable() {
+    let wild = state([
+        (Some(Card::SkipBo), 20, vec![vec![]; 4]),
+        (None, 20, vec![vec![]; 4]),
+    ]);
+    let analysis = BlockAnalysis::default();
+    assert_eq!(analysis.current_risk(&wild).score(), 1.0);
+    assert_eq!(analysis.play_risk(&wild, &play_five()), 0.0);
+}
+
+#[test]
+fn discard_safety_samples_opponent_hands_over_the_round() {
+    let safety = DiscardSafety::default();
+    let view = state([
+        (Some(Card::Number(6)), 20, vec![vec![]; 4]),
+        (None, 20, vec![vec![]; 4]),
+    ]);
+    let discard = Action::Discard {
+        hand_index: 0,
+        discard_pile: DiscardPileId(0),
+    };
+    // Discarding the 5 leaves the pile on 5: the next player needs a 5 or a wild in hand.
+    let scored = safety.score_discards(&view, &[play_five(), discard.clone()]);
+    assert_eq!(scored.len(), 1, "only discards are scored");
+    let (action, kept_back) = scored[0].clone();
+    assert_eq!(action, discard);
+    assert!(kept_back > 0.0 && kept_back < 1.0, "{kept_back}");
+
+    // After playing it, the 6 fits outright.
+    let mut position = TurnPosition::from_view(&view).unwrap();
+    assert!(position.apply(&play_five()));
+    position.hand.push(Card::Number(9));
+    assert_eq!(safety.discard_risk(&view, &position, &discard), Some(1.0));
+    assert_eq!(safety.discard_risk(&view, &position, &play_five()), None);
+
+    let nobody = state([(None, 20, vec![vec![]; 4]), (None, 20, vec![vec![]; 4])]);
+    assert_eq!(safety.unlock_chance(&nobody, &[6, 1, 1, 1]), 0.0);
+}
@@ -0,0 +1,124 @@
+use std::fs;
+
+use skipbot::bots::{BotsConfig, PlanWeights, available_specs, human_name};
+use skipbot::runner::{MatchConfig, MatchRunner};
+use skipbot::{Bot, Game, PlayerId, create_bot_from_spec, label_for_spec};
+
+const TOML: &str = r#"
+[[bots]]
+type = "heuristic19"
+label = "h19-tuned"
+strength = 7
+checkpoint = "cem.json"
+
+[[bots]]
+type = "beam"
+params = ["4", "6"]
+options = { eval = "tempo" }
+seed = 42
+"#;
+
+#[test]
+fn entries_compile_to_spec_strings() {
+    let config = BotsConfig::from_toml(TOML).unwrap();
+    assert_eq!(
+        config.specs(),
+        [
+            "heuristic19:strength=7:checkpoint=cem.json:label=h19-tuned",
+            "beam:4:6:eval=tempo:seed=42",
+        ]
+    );
+    let json = serde_json::to_string(&config).unwrap();
+    assert_eq!(BotsConfig::from_json(&json).unwrap(), config);
+
+    assert!(BotsConfig::from_toml("[[bots]]\ntype = \"beam\"\ndepth = 3\n").is_err());
+    assert!(BotsConfig::from_toml("[[bots]]\ntype = \"beam\"\nparams = [\"4:6\"]\n").is_err());
+    assert!(BotsConfig::from_toml("bots = []\n").is_err());
+}
+
+#[test]
+fn checkpoints_resolve_next_to_the_config_file() {
+    let dir = std::env::temp_dir().join("skipbot-bots-config-test");
+    fs::create_dir_all(&dir).unwrap();
+    let weights = PlanWeights {
+        stock_card: 5_000.0,
+        ..PlanWeights::default()
+    };
+    fs::write(
+        dir.join("cem.json"),
+        serde_json::to_string(&weights).unwrap(),
+    )
+    .unwrap();
+    fs::write(dir.join("bots.toml"), TOML).unwrap();
+
+    let specs = BotsConfig::load(&dir.join("bots.toml")).unwrap().specs();
+    assert!(specs[0].contains(&dir.join("cem.json").display().to_string()));
+    for (seat, spec) in specs.iter().enumerate() {
+        create_bot_from_spec(spec, PlayerId(seat), 1).unwrap();
+    }
+    let labels: Vec<String> = specs.iter().map(|spec| label_for_spec(spec)).collect();
+    assert_eq!(labels, ["h19-tuned", "beam"]);
+
+    let mut config = MatchConfig::new(specs, 2, 5);
+    config.stock_size = Some(3);
+    let report = MatchRunner::new(config).unwrap().run().unwrap();
+    assert!(report.bots.iter().any(|bot| bot.label == "h19-tuned"));
+
+    fs::remove_dir_all(&dir).unwrap();
+    assert!(
+        create_bot_from_spec("heuristic19:checkpoint=/nonexistent.json", PlayerId(0), 1).is_err()
+    );
+}
+
+#[test]
+fn inline_weights_and_fixed_seeds() {
+    let weights = PlanWeights::default().to_vec();
+    let list: Vec<String> = weights.iter().map(f64::to_string).collect();
+    let spec = format!("heuristic19:weights={}", list.join(","));
+    create_bot_from_spec(&spec, PlayerId(0), 1).unwrap();
+    assert!(create_bot_from_spec("heuristic19:weights=1,2", PlayerId(0), 1).is_err());
+
+    // A fixed seed overrides the seed the caller derives per game.
+    let game = Game::builder(2).unwrap().with_seed(4).build().unwrap();
+    let state = game.state_view(PlayerId(0)).unwrap();
+    let legal = game.legal_actions(PlayerId(0)).unwrap();
+    let picks = |seed: u64| {
+        let mut bot = create_bot_from_spec("random:seed=9", PlayerId(0), seed).unwrap();
+        (0..8)
+            .map(|_| bot.select_action(&state, &legal))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(picks(1), picks(2));
+    assert!(create_bot_from_spec("random:seed=x", PlayerId(0), 1).is_err());
+}
+
+#[test]
+fn listed_specs_build_with_their_defaults() {
+    let specs = available_specs();
+    for (index, spec) in specs.iter().enumerate() {
+        assert!(specs[..index].iter().all(|other| other.name != spec.name));
+        assert!(spec.to_string().starts_with(spec.name));
+        assert!(spec.to_string().ends_with(spec.description));
+        if spec.params.is_empty() || spec.params.ends_with(']') {
+            create_bot_from_spec(spec.name, PlayerId(0), 1).unwrap();
+        }
+    }
+    let heuristics = specs
+        .iter()
+        .filter(|spec| spec.name.starts_with("heuristic"));
+    assert_eq!(heuristics.count(), 19);
+}
+
+#[test]
+fn human_specs_name_their_seat() {
+    assert_eq!(
+        human_name("human:Alice", PlayerId(0)).as_deref(),
+        Some("Alice")
+    );
+    assert_eq!(human_name("human", PlayerId(2)).as_deref(), Some("Human 2"));
+    assert_eq!(
+        human_name("human:", PlayerId(1)).as_deref(),
+        Some("Human 1")
+    );
+    assert_eq!(human_name("heuristic19", PlayerId(0)), None);
+}
@@ -0,0 +1,73 @@
+use skipbot::SeedSplit;
+use skipbot::bots::PlanWeights;
+use skipbot::cem::{CemConfig, CemTrainer, win_rate};
+
+fn small_config() -> CemConfig {
+    CemConfig {
+        population: 4,
+        games: 4,
+        ..CemConfig::new("heuristic13", 7)
+    }
+}
+
+#[test]
+fn plan_weights_round_trip_through_vectors() {
+    let weights = PlanWeights::default();
+    let values = weights.to_vec();
+    assert_eq!(values.len(), PlanWeights::LEN);
+    assert_eq!(PlanWeights::from_slice(&values), Some(weights));
+    assert_eq!(PlanWeights::from_slice(&values[1..]), None);
+}
+
+#[test]
+fn trainer_rejects_degenerate_settings() {
+    let start = PlanWeights::default();
+    for config in [
+        CemConfig {
+            population: 1,
+            ..small_config()
+        },
+        CemConfig {
+            elite_fraction: 0.0,
+            ..small_config()
+        },
+        CemConfig {
+            games: 0,
+            ..small_config()
+        },
+    ] {
+        assert!(CemTrainer::new(config, start).is_err());
+    }
+}
+
+#[test]
+fn generations_are_reproducible() {
+    let run = || {
+        let mut trainer = CemTrainer::new(small_config(), PlanWeights::default()).unwrap();
+        let report = trainer.step().unwrap();
+        assert_eq!(trainer.generation(), 1);
+        assert_eq!(report.mean, trainer.mean());
+        assert!(report.best_win_rate >= report.elite_win_rate);
+        assert!(report.elite_win_rate >= report.mean_win_rate);
+        report
+    };
+    assert_eq!(run(), run());
+}
+
+#[test]
+fn win_rate_is_seeded() {
+    let rate = |seed| {
+        win_rate(
+            PlanWeights::default(),
+            "heuristic13",
+            SeedSplit::Validation,
+            seed,
+            0,
+            6,
+            2000,
+        )
+        .unwrap()
+    };
+    assert_eq!(rate(3), rate(3));
+    assert!((0.0..=1.0).contains(&rate(3)));
+}
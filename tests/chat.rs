@@ -0,0 +1,146 @@
+#![cfg(feature = "chat-bridge")]
+
+use std::collections::VecDeque;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use skipbot::chat::{ChatBot, ChatCommand, ChatMessage, ChatTransport, IrcTransport};
+use skipbot::{Action, Bot, BuildPileId, CardSource, Game};
+
+/// Replays scripted messages and keeps what was sent.
+#[derive(Default)]
+struct Script {
+    incoming: VecDeque<ChatMessage>,
+    sent: Vec<(Option<String>, String)>,
+}
+
+impl ChatTransport for Script {
+    fn send(&mut self, to: Option<&str>, text: &str) -> io::Result<()> {
+        self.sent.push((to.map(String::from), text.to_string()));
+        Ok(())
+    }
+
+    fn receive(&mut self) -> io::Result<Option<ChatMessage>> {
+        Ok(self.incoming.pop_front())
+    }
+}
+
+fn message(from: &str, text: &str) -> ChatMessage {
+    ChatMessage {
+        from: from.into(),
+        text: text.into(),
+    }
+}
+
+#[test]
+fn commands_parse_in_action_notation() {
+    assert_eq!(
+        ChatCommand::parse("!play h3>b1"),
+        Some(Ok(ChatCommand::Play(Action::Play {
+            source: CardSource::Hand(3),
+            build_pile: BuildPileId(1),
+        })))
+    );
+    assert_eq!(
+        ChatCommand::parse(" !PLAY end "),
+        Some(Ok(ChatCommand::Play(Action::EndTurn)))
+    );
+    assert_eq!(
+        ChatCommand::parse("!play 4"),
+        Some(Ok(ChatCommand::Pick(4)))
+    );
+    assert_eq!(ChatCommand::parse("!moves"), Some(Ok(ChatCommand::Moves)));
+    assert_eq!(ChatCommand::parse("good luck all"), None);
+    assert!(matches!(ChatCommand::parse("!play"), Some(Err(_))));
+    assert!(matches!(ChatCommand::parse("!play H9>Q"), Some(Err(_))));
+    assert!(matches!(ChatCommand::parse("!undo"), Some(Err(_))));
+}
+
+#[test]
+fn chat_bot_waits_for_a_legal_move_from_its_player() {
+    let game = Game::builder(2).unwrap().with_seed(3).build().unwrap();
+    let seat = game.current_player();
+    let state = game.state_view(seat).unwrap();
+    let legal = game.legal_actions(seat).unwrap();
+    let illegal = Action::Play {
+        source: CardSource::Hand(0),
+        build_pile: BuildPileId(9),
+    };
+    assert!(!legal.contains(&illegal));
+
+    let script = Arc::new(Mutex::new(Script {
+        incoming: VecDeque::from([
+            message("bob", &format!("!play {}", legal[0])),
+            message("alice", "hello"),
+            message("alice", &format!("!play {illegal}")),
+            message("alice", &format!("!play {}", legal.len())),
+            message("Alice", "!play 1"),
+        ]),
+        ..Script::default()
+    }));
+    let mut bot = ChatBot::new("alice", script.clone());
+    let action = bot.select_action(&state, &legal);
+    assert_eq!(action, legal[1]);
+
+    let script = script.lock().unwrap();
+    assert!(script.incoming.is_empty());
+    // The view, then one complaint per illegal move, all in private.
+    assert_eq!(script.sent.len(), 3);
+    assert!(
+        script
+            .sent
+            .iter()
+            .all(|(to, _)| to.as_deref() == Some("alice"))
+    );
+    assert!(script.sent[0].1.contains(&format!("[0] {}", legal[0])));
+}
+
+#[test]
+fn irc_transport_relays_privmsgs_and_answers_pings() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap().to_string();
+    let server = thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let mut writer = stream;
+        let mut lines = Vec::new();
+        let mut read = |lines: &mut Vec<String>| {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            lines.push(line.trim_end().to_string());
+        };
+        for _ in 0..3 {
+            read(&mut lines);
+        }
+        write!(
+            writer,
+            "PING :irc.test\r\n:alice!a@host PRIVMSG #skipbo :!play END\r\n"
+        )
+        .unwrap();
+        read(&mut lines);
+        read(&mut lines);
+        read(&mut lines);
+        lines
+    });
+
+    let mut irc = IrcTransport::connect(&address, "skipbot", "#skipbo").unwrap();
+    let received = irc.receive().unwrap().unwrap();
+    assert_eq!(received, message("alice", "!play END"));
+    irc.send(Some("alice"), "```\nhand\n```\nmoves").unwrap();
+    drop(irc);
+
+    let lines = server.join().unwrap();
+    assert_eq!(
+        lines,
+        vec![
+            "NICK skipbot",
+            "USER skipbot 0 * :skipbot",
+            "JOIN #skipbo",
+            "PONG :irc.test",
+            "PRIVMSG alice :hand",
+            "PRIVMSG alice :moves",
+        ]
+    );
+}
@@ -0,0 +1,126 @@
+use skipbot::analysis::{HighlightConfig, HighlightKind, find_highlights};
+use skipbot::commentary::{Commentary, Pattern};
+use skipbot::runner::play_out;
+use skipbot::{Bot, Game, GameRecord, GameStatus, PlayerId, create_bot_from_spec};
+
+fn recorded_game(seed: u64) -> GameRecord {
+    let mut game = Game::builder(2)
+        .unwrap()
+        .with_seed(seed)
+        .with_stock_size(20)
+        .build()
+        .unwrap();
+    let mut bots: Vec<Box<dyn Bot>> = ["heuristic13", "heuristic2"]
+        .iter()
+        .enumerate()
+        .map(|(seat, spec)| create_bot_from_spec(spec, PlayerId(seat), seed).unwrap())
+        .collect();
+    play_out(&mut game, &mut bots, 2000, |_| {}).unwrap();
+    GameRecord::from_game(&game).unwrap()
+}
+
+#[test]
+fn commentary_narrates_every_turn() {
+    let record = recorded_game(0);
+    let commentary = Commentary::from_record(&record).unwrap();
+    let turns = record
+        .to_string()
+        .lines()
+        .filter(|line| line.contains(". "))
+        .count();
+    assert_eq!(commentary.turns.len(), turns);
+    let GameStatus::Finished { winner } = record.result else {
+        panic!("game did not finish");
+    };
+    assert_eq!(commentary.result, record.result);
+
+    let last = commentary.turns.last().unwrap();
+    assert_eq!(last.player, winner);
+    assert!(last.patterns.contains(&Pattern::Win));
+    assert!(last.text.starts_with(&format!("Player {winner} ")));
+    assert!(
+        commentary
+            .to_string()
+            .ends_with(&format!("Player {winner} wins.\n"))
+    );
+
+    let unlocks: usize = commentary
+        .turns
+        .iter()
+        .map(|turn| {
+            turn.patterns
+                .iter()
+                .filter(|p| **p == Pattern::Unlock)
+                .count()
+        })
+        .sum();
+    let stock_plays = record
+        .moves
+        .iter()
+        .filter(|action| action.to_string().starts_with("S>"))
+        .count();
+    assert!(unlocks >= 1 && unlocks <= stock_plays);
+
+    let patterns: Vec<Pattern> = commentary
+        .turns
+        .iter()
+        .flat_map(|turn| turn.patterns.clone())
+        .collect();
+    assert!(patterns.contains(&Pattern::Dump));
+    assert!(
+        patterns
+            .iter()
+            .any(|pattern| matches!(pattern, Pattern::Completion { .. }))
+    );
+    let block = commentary
+        .turns
+        .iter()
+        .find(|turn| {
+            turn.patterns
+                .iter()
+                .any(|p| matches!(p, Pattern::Block { .. }))
+        })
+        .unwrap();
+    assert!(block.text.contains("keeping it blocked"));
+}
+
+#[test]
+fn unreplayable_records_are_rejected() {
+    let mut record = recorded_game(1);
+    record.result = GameStatus::Draw;
+    assert!(Commentary::from_record(&record).is_err());
+}
+
+#[test]
+fn highlights_pick_the_swing_the_longest_chain_and_held_blocks() {
+    let record = recorded_game(0);
+    let config = HighlightConfig {
+        rollout_bots: vec![String::from("heuristic13"), String::from("heuristic2")],
+        samples: 4,
+        ..HighlightConfig::default()
+    };
+    let highlights = find_highlights(&record, &config).unwrap();
+    assert!(
+        highlights
+            .windows(2)
+            .all(|pair| pair[0].turn <= pair[1].turn)
+    );
+    let count = |matches: fn(&HighlightKind) -> bool| {
+        highlights.iter().filter(|h| matches(&h.kind)).count()
+    };
+    assert_eq!(count(|kind| matches!(kind, HighlightKind::Swing { .. })), 1);
+    assert_eq!(count(|kind| matches!(kind, HighlightKind::Chain { .. })), 1);
+
+    let commentary = Commentary::from_record(&record).unwrap();
+    for highlight in &highlights {
+        let turn = &commentary.turns[highlight.turn - 1];
+        assert_eq!(highlight.first_move, turn.first_move);
+        assert_eq!(highlight.snippet, turn.text);
+        if let HighlightKind::Block { opponent } = highlight.kind {
+            assert!(turn.patterns.contains(&Pattern::Block { opponent }));
+        }
+    }
+
+    let without_rollouts = find_highlights(&record, &HighlightConfig::default()).unwrap();
+    assert_eq!(without_rollouts.len(), highlights.len() - 1);
+}
@@ -0,0 +1,56 @@
+//! Hand-built views shared by the bot and planning tests.
+#![allow(dead_code)]
+
+use skipbot::action::PlayerId;
+use skipbot::card::Card;
+use skipbot::state::{
+    BuildPileView, GameSettings, GameStateView, GameStatus, PlayerPublicState, TurnPhase,
+};
+
+/// A build pile holding 1.. up to `next_value`, which it needs next.
+pub fn pile_at(next_value: u8) -> BuildPileView {
+    BuildPileView {
+        cards: (1..next_value).map(Card::Number).collect::<Vec<_>>().into(),
+        next_value,
+    }
+}
+
+/// Seat `id` with `stock_count` stock cards under `stock_top`, empty discard piles and
+/// five cards in hand; seat 0 is the one to move.
+pub fn player(id: usize, stock_top: Option<Card>, stock_count: usize) -> PlayerPublicState {
+    PlayerPublicState {
+        id: PlayerId(id),
+        stock_count,
+        stock_top,
+        discard_piles: vec![vec![]; 4].into(),
+        hand_size: 5,
+        is_current: id == 0,
+        has_won: false,
+    }
+}
+
+/// Seat 0 to move holding `hand`, at the start of a turn with 50 cards left to draw.
+pub fn view(
+    hand: Vec<Card>,
+    build_piles: [BuildPileView; 4],
+    mut players: Vec<PlayerPublicState>,
+) -> GameStateView {
+    players[0].hand_size = hand.len();
+    GameStateView {
+        settings: GameSettings::new(players.len()).unwrap(),
+        phase: TurnPhase::AwaitingAction,
+        status: GameStatus::Ongoing,
+        self_player: PlayerId(0),
+        current_player: PlayerId(0),
+        draw_pile_count: 50,
+        recycle_pile_count: 0,
+        build_piles,
+        players,
+        hand: hand.into(),
+        recent_actions: Vec::new(),
+        turn_number: 0,
+        actions_taken_this_turn: 0,
+        turn_events: Vec::new(),
+        forbidden_plays: Vec::new(),
+    }
+}
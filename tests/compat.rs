@@ -0,0 +1,86 @@
+use std::fs;
+
+use skipbot::compat::{FeatureShim, Representation};
+use skipbot::value::{ValueNet, load_value_samples, load_value_samples_with};
+use skipbot::{ENCODER_VERSION, Game, PlayerId, STATE_FEATURES};
+
+fn stamped(representation: Representation, features: usize) -> String {
+    let header = serde_json::json!({ "representation": representation });
+    let sample = serde_json::json!({ "game": 0, "features": vec![0.5; features], "outcome": 1.0 });
+    format!("{header}\n{sample}\n")
+}
+
+#[test]
+fn datasets_of_another_encoder_need_a_shim() {
+    let dir = std::env::temp_dir().join("skipbot-compat-datasets");
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("samples.jsonl");
+
+    fs::write(&path, stamped(Representation::current(), STATE_FEATURES)).unwrap();
+    assert_eq!(load_value_samples(&path).unwrap().len(), 1);
+
+    let older = Representation {
+        encoder_version: ENCODER_VERSION + 1,
+        features: STATE_FEATURES - 3,
+        ..Representation::current()
+    };
+    fs::write(&path, stamped(older, STATE_FEATURES - 3)).unwrap();
+    assert!(load_value_samples(&path).is_err());
+    let resized = load_value_samples_with(&path, FeatureShim::Resize).unwrap();
+    assert_eq!(resized[0].features.len(), STATE_FEATURES);
+    assert_eq!(resized[0].features[STATE_FEATURES - 1], 0.0);
+
+    // Action indices cannot be remapped.
+    let other_actions = Representation {
+        action_space_version: 0,
+        ..Representation::current()
+    };
+    fs::write(&path, stamped(other_actions, STATE_FEATURES)).unwrap();
+    assert!(load_value_samples_with(&path, FeatureShim::Resize).is_err());
+
+    // Files written before stamping load as they are.
+    fs::write(&path, "{\"game\":3,\"features\":[1.0],\"outcome\":-1.0}\n").unwrap();
+    assert_eq!(load_value_samples(&path).unwrap()[0].features, [1.0]);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn value_checkpoints_carry_their_representation() {
+    let dir = std::env::temp_dir().join("skipbot-compat-checkpoints");
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("value.json");
+    let net = ValueNet::new(3, 7);
+    net.save(&path).unwrap();
+    let saved: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(saved["representation"]["encoder_version"], ENCODER_VERSION);
+    assert_eq!(ValueNet::load(&path).unwrap(), net);
+
+    // A net over fewer features gets zero weights for the new ones.
+    let mut old = ValueNet::with_inputs(STATE_FEATURES - 2, 3, 7);
+    old.save(&path).unwrap();
+    assert!(ValueNet::load(&path).is_err());
+    let shimmed = ValueNet::load_with(&path, FeatureShim::Resize).unwrap();
+    assert_eq!(shimmed.inputs, STATE_FEATURES);
+    assert_eq!(shimmed.w1[STATE_FEATURES - 1], 0.0);
+    assert_eq!(
+        shimmed.w1[..STATE_FEATURES - 2],
+        old.w1[..STATE_FEATURES - 2]
+    );
+    let game = Game::builder(2).unwrap().with_seed(1).build().unwrap();
+    assert!((0.0..=1.0).contains(&shimmed.evaluate(&game.state_view(PlayerId(0)).unwrap())));
+
+    // Nets saved before stamping have no representation field.
+    old.inputs = STATE_FEATURES;
+    old.w1.resize(STATE_FEATURES * 3, 0.0);
+    fs::write(&path, serde_json::to_string(&old).unwrap()).unwrap();
+    assert_eq!(ValueNet::load(&path).unwrap(), old);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn shims_parse_from_their_names() {
+    assert_eq!("resize".parse(), Ok(FeatureShim::Resize));
+    assert_eq!(FeatureShim::default().to_string(), "strict");
+    assert!("pad".parse::<FeatureShim>().is_err());
+}
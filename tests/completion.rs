@@ -0,0 +1,89 @@
+use skipbot::action::{Action, BuildPileId, CardSource, PlayerId};
+use skipbot::bots::completion::{
+    CompletionEffect, completion_effects, unlock_balance, unlock_distance,
+};
+use skipbot::bots::eval::{EvalFn, Evaluator};
+use skipbot::bots::planning::TurnPosition;
+use skipbot::card::Card;
+use skipbot::state::GameStateView;
+use skipbot::visualize::describe_completion;
+
+mod common;
+use common::{pile_at, player, view};
+
+/// Two players holding a 12 in hand, the first build pile one card from completion.
+fn state(own_stock: u8, opponent_stock: u8) -> GameStateView {
+    let mut state = view(
+        vec![Card::Number(12)],
+        [pile_at(12), pile_at(6), pile_at(6), pile_at(6)],
+        vec![
+            player(0, Some(Card::Number(own_stock)), 20),
+            player(1, Some(Card::Number(opponent_stock)), 20),
+        ],
+    );
+    state.players[0].hand_size = 5;
+    state
+}
+
+fn complete() -> Action {
+    Action::Play {
+        source: CardSource::Hand(0),
+        build_pile: BuildPileId(0),
+    }
+}
+
+#[test]
+fn unlock_distance_wraps_and_treats_wilds_as_playable() {
+    assert_eq!(unlock_distance(&[12, 6, 6, 6], Card::Number(2)), 2);
+    assert_eq!(unlock_distance(&[1, 6, 6, 6], Card::Number(2)), 1);
+    assert_eq!(unlock_distance(&[12, 6, 6, 6], Card::Number(5)), 5);
+    assert_eq!(unlock_distance(&[12, 6, 6, 6], Card::SkipBo), 0);
+}
+
+#[test]
+fn completing_a_pile_helps_whoever_waits_for_a_low_card() {
+    // The opponent waits for a 1, which the reset offers straight away, while our 9 stays
+    // three steps from the 6 piles: avoid it.
+    let feeds_opponent = CompletionEffect::of_action(&state(9, 1), &complete()).unwrap();
+    assert_eq!(feeds_opponent.build_pile, BuildPileId(0));
+    let opponent = feeds_opponent.changes[1];
+    assert_eq!(
+        (opponent.player, opponent.before, opponent.after),
+        (PlayerId(1), 1, 0)
+    );
+    assert_eq!(feeds_opponent.value, -1.0);
+
+    // We wait for a 2 and the opponent for a 12 the pile would have taken next.
+    let helps_us = CompletionEffect::of_action(&state(2, 12), &complete()).unwrap();
+    assert_eq!(helps_us.value, 6.0 + 1.0);
+    assert_eq!(
+        describe_completion(&helps_us),
+        "completes build pile 0 (unlock distance Player 0 2 -> 1, Player 1 0 -> 6; balance +7.0)"
+    );
+
+    // Only piles requiring 12 can complete.
+    let other_pile = Action::Play {
+        source: CardSource::Hand(0),
+        build_pile: BuildPileId(1),
+    };
+    assert_eq!(
+        CompletionEffect::of_action(&state(2, 12), &other_pile),
+        None
+    );
+    assert_eq!(completion_effects(&state(2, 12), &[12, 12, 6, 6]).len(), 2);
+}
+
+#[test]
+fn recycle_evaluator_scores_the_unlock_balance() {
+    let view = state(2, 12);
+    let before = TurnPosition::from_view(&view).unwrap();
+    let mut after = before.clone();
+    assert!(after.apply(&complete()));
+    let balance =
+        |position: &TurnPosition| unlock_balance(&view, &position.build_next, position.stock_top);
+    assert_eq!(balance(&after) - balance(&before), 7.0);
+    assert!(
+        Evaluator::Recycle.evaluate(&view, &after, 0)
+            > Evaluator::Recycle.evaluate(&view, &before, 0)
+    );
+}
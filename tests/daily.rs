@@ -0,0 +1,90 @@
+use skipbot::daily::{CHALLENGER, DailyChallenge, DailyResult, Date, TurnKind, daily_seed};
+use skipbot::runner::play_out;
+use skipbot::{Bot, PlayerId, create_bot_from_spec};
+
+fn date(text: &str) -> Date {
+    text.parse().unwrap()
+}
+
+#[test]
+fn dates_parse_and_count_days() {
+    assert_eq!(date("1970-01-01").days_since_epoch(), 0);
+    assert_eq!(date("2000-03-01").days_since_epoch(), 11_017);
+    assert_eq!(date("2026-10-18").days_since_epoch(), 20_744);
+    assert_eq!(date("2024-02-29").to_string(), "2024-02-29");
+    for days in [-1, 0, 59, 11_016, 20_744, 100_000] {
+        assert_eq!(Date::from_days(days).days_since_epoch(), days);
+    }
+    for invalid in [
+        "2025-02-29",
+        "2026-13-01",
+        "2026-10",
+        "18-10-2026x",
+        "today",
+    ] {
+        assert!(invalid.parse::<Date>().is_err(), "{invalid}");
+    }
+}
+
+#[test]
+fn daily_seeds_are_canonical() {
+    // Results are only comparable while every build derives the same seed for a day.
+    assert_eq!(daily_seed(date("2026-10-18")), 0xcf71_1f45_24bf_ebe3);
+    assert_ne!(
+        daily_seed(date("2026-10-18")),
+        daily_seed(date("2026-10-19"))
+    );
+    let challenge = DailyChallenge::for_date(date("2026-10-18"));
+    assert_eq!(challenge.number(), 20_744);
+    let a = challenge.game().unwrap();
+    let b = challenge.game().unwrap();
+    assert_eq!(
+        a.state_view(CHALLENGER).unwrap(),
+        b.state_view(CHALLENGER).unwrap()
+    );
+    assert_eq!(a.current_player(), CHALLENGER);
+}
+
+fn play(challenge: &DailyChallenge, player: &str) -> DailyResult {
+    let mut game = challenge.game().unwrap();
+    let mut bots: Vec<Box<dyn Bot>> = vec![
+        create_bot_from_spec(player, CHALLENGER, challenge.seed).unwrap(),
+        challenge.opponent().unwrap(),
+    ];
+    play_out(&mut game, &mut bots, 2000, |_| {}).unwrap();
+    DailyResult::from_game(challenge, &game).unwrap()
+}
+
+#[test]
+fn results_summarize_the_challengers_turns() {
+    let challenge = DailyChallenge::for_date(date("2026-10-18"));
+    let result = play(&challenge, "heuristic19");
+    assert_eq!(result, play(&challenge, "heuristic19"));
+    assert!(result.finished);
+    assert!(result.turns.contains(&TurnKind::Stock));
+    if result.won {
+        assert_eq!(result.stock_played, challenge.stock_size);
+        assert!(result.points > 0);
+    }
+
+    let share = result.share();
+    let lines: Vec<&str> = share.lines().collect();
+    assert_eq!(lines[0], "Skip-Bo daily #20744 (2026-10-18)");
+    let squares: usize = lines[2..].iter().map(|line| line.chars().count()).sum();
+    assert_eq!(squares, result.turns.len());
+    assert_eq!(share.ends_with("🏆"), result.won);
+}
+
+#[test]
+fn unfinished_challenges_say_so() {
+    let challenge = DailyChallenge::for_date(date("2026-10-19"));
+    let mut game = challenge.game().unwrap();
+    let mut bots: Vec<Box<dyn Bot>> = vec![
+        create_bot_from_spec("heuristic13", PlayerId(0), challenge.seed).unwrap(),
+        challenge.opponent().unwrap(),
+    ];
+    play_out(&mut game, &mut bots, 12, |_| {}).unwrap();
+    let result = DailyResult::from_game(&challenge, &game).unwrap();
+    assert!(!result.finished && !result.won);
+    assert!(result.share().contains("Unfinished after"));
+}
@@ -0,0 +1,64 @@
+use std::fs;
+
+use skipbot::dashboard::{CheckpointReport, Dashboard, ReportKind};
+use skipbot::league::{League, LeagueConfig};
+use skipbot::runner::{MatchConfig, MatchRunner};
+
+#[test]
+fn reports_become_rating_and_win_rate_timeseries() {
+    let dir = std::env::temp_dir().join("skipbot-dashboard-test");
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+
+    let mut first = MatchConfig::new(vec!["heuristic13".into(), "random".into()], 6, 3);
+    first.stock_size = Some(4);
+    let first = MatchRunner::new(first).unwrap().run().unwrap();
+    first.save(&dir.join("01-match.json")).unwrap();
+    let bots = ["heuristic13", "heuristic19", "random"].map(String::from);
+    let mut league = LeagueConfig::new(bots.to_vec(), 2, 9, 3);
+    league.stock_size = Some(4);
+    let league = League::new(league).unwrap().run().unwrap();
+    league.save(&dir.join("02-league.json")).unwrap();
+    fs::write(dir.join("notes.txt"), "not a report").unwrap();
+
+    let checkpoints = CheckpointReport::load_all(std::slice::from_ref(&dir)).unwrap();
+    assert_eq!(checkpoints.len(), 2);
+    assert_eq!(checkpoints[0].kind, ReportKind::Match);
+    assert_eq!(checkpoints[1].kind, ReportKind::League);
+    assert_eq!(checkpoints[1].name, "02-league");
+
+    let dashboard = Dashboard::build(&checkpoints, &["random".to_string()], None);
+    assert_eq!(dashboard.anchor.as_deref(), Some("random"));
+    assert_eq!(dashboard.checkpoints[1].games, 9);
+    let series = |bot: &str| {
+        dashboard
+            .ratings
+            .iter()
+            .find(|series| series.bot == bot)
+            .unwrap()
+    };
+    // The anchor sits at 0 everywhere; heuristic19 only joined the league.
+    assert!(series("random").points.iter().all(|p| p.rating == 0.0));
+    assert_eq!(series("heuristic19").points.len(), 1);
+    assert_eq!(series("heuristic19").points[0].checkpoint, 1);
+    assert_eq!(series("heuristic13").points[0].games, 6);
+
+    let versus = dashboard
+        .win_rates
+        .iter()
+        .find(|series| series.bot == "heuristic13" && series.baseline == "random")
+        .unwrap();
+    assert_eq!(versus.points.len(), 2);
+    let wins = first
+        .bots
+        .iter()
+        .find(|b| b.label == "heuristic13")
+        .unwrap()
+        .wins;
+    assert_eq!(versus.points[0].wins, wins);
+    assert_eq!(versus.points[0].win_rate, wins as f64 / 6.0);
+    assert!(dashboard.win_rates.iter().all(|s| s.bot != "random"));
+
+    fs::write(dir.join("03-broken.json"), "{}").unwrap();
+    assert!(CheckpointReport::load_all(&[dir]).is_err());
+}
@@ -0,0 +1,188 @@
+use std::collections::HashSet;
+use std::io::Cursor;
+
+use skipbot::archive::{ArchiveReader, ArchiveWriter, Compression};
+use skipbot::dataset::{PolicyDataset, QualityReport};
+use skipbot::runner::{MatchConfig, MatchReport, MatchRunner, ObjectiveWeights};
+
+/// `games` games of heuristic13 against random, with their archive.
+fn tournament(games: usize) -> (MatchReport, Vec<u8>) {
+    let mut config = MatchConfig::new(vec!["heuristic13".into(), "random".into()], games, 3);
+    config.stock_size = Some(5);
+    let runner = MatchRunner::new(config).unwrap();
+    let mut writer = ArchiveWriter::new(Vec::new(), Compression::None).unwrap();
+    let report = runner.run_archived(&mut writer).unwrap();
+    (report, writer.finish().unwrap())
+}
+
+#[test]
+fn every_archived_move_is_labelled_with_outcome_and_rating() {
+    let (report, archive) = tournament(4);
+    let mut archive = ArchiveReader::new(Cursor::new(archive)).unwrap();
+    let weights = ObjectiveWeights::default();
+    let dataset = PolicyDataset::from_tournament(&report, &mut archive, &weights).unwrap();
+
+    let moves: usize = report.games.iter().map(|game| game.actions).sum();
+    assert_eq!(dataset.len(), moves);
+    for sample in &dataset.samples {
+        let game = &report.games[sample.game];
+        assert_eq!(sample.bot, game.seats[sample.seat.0]);
+        assert!(sample.legal.contains(&sample.action));
+        let won = game.winner == Some(sample.seat);
+        assert_eq!(sample.outcome, if won { 1.0 } else { -1.0 });
+        let bot = report.bots.iter().find(|b| b.label == sample.bot).unwrap();
+        assert_eq!(sample.rating, report.composite_score(bot, &weights));
+    }
+}
+
+#[test]
+fn top_rated_keeps_the_strongest_bots_winning_moves() {
+    let (report, archive) = tournament(4);
+    let mut archive = ArchiveReader::new(Cursor::new(archive)).unwrap();
+    let dataset =
+        PolicyDataset::from_tournament(&report, &mut archive, &ObjectiveWeights::default())
+            .unwrap();
+    let best = dataset.top_rated(1, true);
+    assert!(!best.is_empty());
+    assert!(best.samples.iter().all(|s| s.bot == "heuristic13"));
+    assert!(best.samples.iter().all(|s| s.outcome == 1.0));
+
+    let path = std::env::temp_dir().join("skipbot-dataset-test.jsonl");
+    best.save(&path).unwrap();
+    assert_eq!(PolicyDataset::load(&path).unwrap(), best);
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn archives_from_another_tournament_are_rejected() {
+    let (report, _) = tournament(4);
+    let mut config = MatchConfig::new(vec!["random".into(), "heuristic2".into()], 4, 3);
+    config.stock_size = Some(5);
+    let mut writer = ArchiveWriter::new(Vec::new(), Compression::None).unwrap();
+    MatchRunner::new(config)
+        .unwrap()
+        .run_archived(&mut writer)
+        .unwrap();
+    let mut archive = ArchiveReader::new(Cursor::new(writer.finish().unwrap())).unwrap();
+    assert!(
+        PolicyDataset::from_tournament(&report, &mut archive, &ObjectiveWeights::default())
+            .is_err()
+    );
+}
+
+#[test]
+fn quality_report_summarizes_actions_teachers_and_weights() {
+    let (report, archive) = tournament(4);
+    let mut archive = ArchiveReader::new(Cursor::new(archive)).unwrap();
+    let dataset =
+        PolicyDataset::from_tournament(&report, &mut archive, &ObjectiveWeights::default())
+            .unwrap();
+    let quality = QualityReport::from_samples(&dataset.samples);
+
+    assert_eq!(quality.samples, dataset.len());
+    assert_eq!(quality.action_types.values().sum::<usize>(), dataset.len());
+    assert!(quality.action_types["stock play"] > 0);
+    assert!(quality.mean_legal() >= 1.0);
+    for bot in &report.bots {
+        let teacher = quality
+            .teachers
+            .iter()
+            .find(|t| t.teacher == bot.label)
+            .unwrap();
+        assert_eq!((teacher.seats, teacher.wins), (bot.seats, bot.wins));
+    }
+    // Two bots, two distinct ratings: the extremes land in the first and last bin.
+    let histogram = quality.weight_histogram(4);
+    assert_eq!(histogram.len(), 4);
+    assert_eq!(
+        histogram.iter().map(|(_, _, count)| count).sum::<usize>(),
+        dataset.len()
+    );
+    assert!(histogram[0].2 > 0 && histogram[3].2 > 0);
+    assert!(quality.to_string().contains("heuristic13"));
+
+    let winners = QualityReport::from_samples(&dataset.top_rated(2, true).samples);
+    assert!(winners.warnings().is_empty(), "{:?}", winners.warnings());
+    assert_eq!(
+        QualityReport::from_samples(&dataset.top_rated(0, false).samples).warnings(),
+        ["no samples"]
+    );
+}
+
+fn games_of(dataset: &PolicyDataset) -> HashSet<usize> {
+    dataset.samples.iter().map(|sample| sample.game).collect()
+}
+
+#[test]
+fn splits_keep_whole_games_together() {
+    let (report, archive) = tournament(10);
+    let mut archive = ArchiveReader::new(Cursor::new(archive)).unwrap();
+    let dataset =
+        PolicyDataset::from_tournament(&report, &mut archive, &ObjectiveWeights::default())
+            .unwrap();
+
+    let split = dataset.split(0.2, 0.1, 7).unwrap();
+    let (train, validation, test) = (
+        games_of(&split.train),
+        games_of(&split.validation),
+        games_of(&split.test),
+    );
+    assert_eq!((train.len(), validation.len(), test.len()), (7, 2, 1));
+    assert!(train.is_disjoint(&validation) && train.is_disjoint(&test));
+    assert!(validation.is_disjoint(&test));
+    assert_eq!(
+        split.train.len() + split.validation.len() + split.test.len(),
+        dataset.len()
+    );
+    assert_eq!(dataset.split(0.2, 0.1, 7).unwrap(), split);
+    assert!(dataset.split(0.6, 0.4, 7).is_err());
+
+    let folds = dataset.k_fold(3, 7).unwrap();
+    assert_eq!(folds.len(), 3);
+    let mut validated = HashSet::new();
+    for (train, validation) in &folds {
+        assert!(games_of(train).is_disjoint(&games_of(validation)));
+        assert_eq!(train.len() + validation.len(), dataset.len());
+        assert!((3..=4).contains(&games_of(validation).len()));
+        validated.extend(games_of(validation));
+    }
+    assert_eq!(validated, games_of(&dataset));
+    assert!(dataset.k_fold(1, 7).is_err());
+    assert!(dataset.k_fold(11, 7).is_err());
+}
+
+#[test]
+fn provenance_traces_moves_to_their_deal_and_turn() {
+    let (report, archive) = tournament(4);
+    let mut archive = ArchiveReader::new(Cursor::new(archive)).unwrap();
+    let dataset =
+        PolicyDataset::from_tournament(&report, &mut archive, &ObjectiveWeights::default())
+            .unwrap();
+    let mut last: Option<(usize, usize, usize)> = None;
+    for sample in &dataset.samples {
+        let provenance = sample.provenance.as_ref().expect("tournament moves");
+        let game = &report.games[sample.game];
+        assert_eq!(provenance.deck_seed, game.deck_seed);
+        assert!(provenance.turn < game.turns);
+        match last {
+            Some((index, ply, turn)) if index == sample.game => {
+                assert_eq!(provenance.ply, ply + 1);
+                assert!(provenance.turn >= turn);
+            }
+            _ => assert_eq!((provenance.ply, provenance.turn), (0, 0)),
+        }
+        last = Some((sample.game, provenance.ply, provenance.turn));
+    }
+
+    let late = dataset.from_turn(4);
+    assert!(!late.is_empty() && late.len() < dataset.len());
+    assert!(
+        late.samples
+            .iter()
+            .all(|s| s.provenance.as_ref().unwrap().turn >= 4)
+    );
+    let path = std::env::temp_dir().join("skipbot-dataset-provenance-test.jsonl");
+    late.save(&path).unwrap();
+    assert_eq!(PolicyDataset::load(&path).unwrap(), late);
+    std::fs::remove_file(path).unwrap();
+}
@@ -0,0 +1,124 @@
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use skipbot::analysis::sample_determinization;
+use skipbot::card::full_deck;
+use skipbot::state::StockVisibility;
+use skipbot::{Card, Game, GameStateView, PlayerId, create_bot_from_spec};
+
+fn midgame(visibility: StockVisibility) -> Game {
+    let mut game = Game::builder(3)
+        .unwrap()
+        .with_seed(21)
+        .with_stock_visibility(visibility)
+        .build()
+        .unwrap();
+    let mut bots: Vec<_> = (0..3)
+        .map(|seat| create_bot_from_spec("heuristic13", PlayerId(seat), seat as u64).unwrap())
+        .collect();
+    for _ in 0..60 {
+        let current = game.current_player();
+        let state = game.state_view(current).unwrap();
+        let legal = game.legal_actions(current).unwrap();
+        let action = bots[current].select_action(&state, &legal);
+        game.apply_action(current, action).unwrap();
+    }
+    assert!(!game.is_finished());
+    game
+}
+
+/// Every card of a game: hands, stocks, discard piles, build piles, draw and recycle
+/// piles, sorted.
+fn all_cards(game: &Game) -> Vec<Card> {
+    let full = game.full_state_view(PlayerId(0)).unwrap();
+    let mut cards: Vec<Card> = full.hands.concat();
+    cards.extend(full.stocks.concat());
+    cards.extend(full.draw_pile);
+    cards.extend(full.recycle_pile);
+    for player in &full.view.players {
+        cards.extend(player.discard_piles.concat());
+    }
+    for pile in &full.view.build_piles {
+        cards.extend(pile.cards.iter());
+    }
+    cards.sort_by_key(Card::value);
+    cards
+}
+
+fn without_history(mut view: GameStateView) -> GameStateView {
+    view.recent_actions.clear();
+    view
+}
+
+#[test]
+fn samples_keep_the_view_and_conserve_the_deck() {
+    let game = midgame(StockVisibility::Exact);
+    let me = PlayerId(1);
+    let view = game.state_view(me).unwrap();
+    let mut deck = full_deck();
+    deck.sort_by_key(Card::value);
+
+    let mut rng = StdRng::seed_from_u64(4);
+    let mut opponent_hands = Vec::new();
+    for _ in 0..8 {
+        let sample = sample_determinization(&view, &mut rng).unwrap();
+        assert_eq!(all_cards(&sample), deck);
+        assert_eq!(
+            without_history(sample.state_view(me).unwrap()),
+            without_history(view.clone())
+        );
+        let full = sample.full_state_view(me).unwrap();
+        let real = game.full_state_view(me).unwrap();
+        for (stock, real) in full.stocks.iter().zip(&real.stocks) {
+            assert_eq!(stock.len(), real.len());
+            assert_eq!(stock.first(), real.first(), "stock tops stay put");
+        }
+        opponent_hands.push(full.hands[0].clone());
+    }
+    opponent_hands.dedup();
+    assert!(opponent_hands.len() > 1, "hidden cards are redealt");
+
+    // Samples are complete games the engine plays on; the player to move sees their own
+    // hand, so the legal actions match.
+    let current = game.current_player();
+    let view = game.state_view(current).unwrap();
+    let mut sample = sample_determinization(&view, &mut rng).unwrap();
+    let legal = sample.legal_actions(current).unwrap();
+    assert_eq!(legal, game.legal_actions(current).unwrap());
+    sample.apply_action(current, legal[0].clone()).unwrap();
+}
+
+#[test]
+fn binned_stock_counts_are_drawn_within_their_bins() {
+    let game = midgame(StockVisibility::Binned);
+    let me = game.current_player();
+    let view = game.state_view(me).unwrap();
+    let real = game.full_state_view(me).unwrap();
+    let real_total: usize = real.stocks.iter().map(Vec::len).sum();
+    let mut deck = full_deck();
+    deck.sort_by_key(Card::value);
+
+    let mut rng = StdRng::seed_from_u64(9);
+    for _ in 0..8 {
+        let sample = sample_determinization(&view, &mut rng).unwrap();
+        assert_eq!(all_cards(&sample), deck);
+        let stocks = sample.full_state_view(me).unwrap().stocks;
+        assert_eq!(stocks.iter().map(Vec::len).sum::<usize>(), real_total);
+        for (stock, player) in stocks.iter().zip(&view.players) {
+            let observed = StockVisibility::Binned.observe(stock.len(), view.settings.stock_size);
+            assert_eq!(observed, player.stock_count);
+        }
+        assert_eq!(
+            without_history(sample.state_view(me).unwrap()),
+            without_history(view.clone())
+        );
+    }
+}
+
+#[test]
+fn views_the_deck_cannot_explain_are_rejected() {
+    let game = midgame(StockVisibility::Exact);
+    let mut view = game.state_view(PlayerId(0)).unwrap();
+    view.draw_pile_count += 1;
+    let mut rng = StdRng::seed_from_u64(0);
+    assert!(sample_determinization(&view, &mut rng).is_err());
+}
@@ -0,0 +1,194 @@
+use std::collections::HashSet;
+
+use skipbot::{
+    Card, Game, JOINT_FEATURES, PRIVILEGED_FEATURES, PlayerId, STATE_FEATURES, SeatRules,
+    StateEncoder,
+};
+
+#[test]
+fn feature_names_match_layout() {
+    let names = StateEncoder::feature_names();
+    assert_eq!(names.len(), STATE_FEATURES);
+    let unique: HashSet<_> = names.iter().collect();
+    assert_eq!(unique.len(), names.len(), "feature names must be unique");
+}
+
+#[test]
+fn encodes_named_features_in_range() {
+    let game = Game::builder(3).unwrap().with_seed(7).build().unwrap();
+    let state = game.state_view(PlayerId(1)).unwrap();
+    let features = StateEncoder::encode(&state);
+    assert!(features.iter().all(|f| (0.0..=1.0).contains(f)));
+
+    let at = |name: &str| features[StateEncoder::feature_index(name).unwrap()];
+    assert_eq!(at("build0.next_value"), 1.0 / 12.0);
+    assert_eq!(at("turn_number"), 0.0);
+    assert_eq!(at("actions_taken_this_turn"), 0.0);
+    assert_eq!(at("seat0.present"), 1.0);
+    assert_eq!(at("seat2.present"), 1.0);
+    assert_eq!(at("seat3.present"), 0.0);
+    assert_eq!(at("seat0.stock_count"), 1.0);
+
+    let hand_total: f32 = StateEncoder::feature_names()
+        .iter()
+        .zip(features)
+        .filter(|(name, _)| name.starts_with("hand."))
+        .map(|(_, value)| value)
+        .sum();
+    assert!((hand_total * state.settings.hand_size as f32 - state.hand.len() as f32).abs() < 1e-4);
+
+    // Seat 1 relative to the viewer is player 2.
+    let top = state.players[2].stock_top.unwrap();
+    let bucket = match top {
+        Card::SkipBo => String::from("skip_bo"),
+        Card::Number(v) => v.to_string(),
+    };
+    assert_eq!(at(&format!("seat1.stock_top.{bucket}")), 1.0);
+}
+
+#[test]
+fn role_features_give_the_absolute_seat() {
+    let game = Game::builder(3).unwrap().with_seed(7).build().unwrap();
+    for seat in 0..3 {
+        let features = StateEncoder::encode(&game.state_view(PlayerId(seat)).unwrap());
+        let at = |name: &str| features[StateEncoder::feature_index(name).unwrap()];
+        for other in 0..6 {
+            let expected = if other == seat { 1.0 } else { 0.0 };
+            assert_eq!(at(&format!("role.seat{other}")), expected);
+        }
+        assert_eq!(at("role.after_dealer"), (seat + 1) as f32 / 3.0);
+    }
+}
+
+#[test]
+fn settings_features_tell_configurations_apart() {
+    let settings = |game: &Game, seat: usize| {
+        let features = StateEncoder::encode(&game.state_view(PlayerId(seat)).unwrap());
+        let at = |name: &str| features[StateEncoder::feature_index(name).unwrap()];
+        [
+            at("num_players"),
+            at("settings.stock_size"),
+            at("settings.hand_size"),
+            at("settings.discard_piles"),
+            at("settings.must_play_stock"),
+        ]
+    };
+    let standard = Game::builder(2).unwrap().build().unwrap();
+    assert_eq!(settings(&standard, 0), [2.0 / 6.0, 0.6, 1.0, 0.8, 0.0]);
+
+    let variant = Game::builder(5)
+        .unwrap()
+        .with_stock_size(10)
+        .with_hand_size(4)
+        .with_must_play_stock(true)
+        .with_seat_rules(
+            PlayerId(1),
+            SeatRules {
+                extra_discard_pile: true,
+                ..SeatRules::default()
+            },
+        )
+        .build()
+        .unwrap();
+    assert_eq!(settings(&variant, 0), [5.0 / 6.0, 0.2, 0.8, 0.8, 1.0]);
+    assert_eq!(settings(&variant, 1)[3], 1.0);
+    // Stock counts are relative to the stock size, so only the settings differ.
+    assert_eq!(
+        StateEncoder::encode(&variant.state_view(PlayerId(0)).unwrap())
+            [StateEncoder::feature_index("seat0.stock_count").unwrap()],
+        1.0
+    );
+}
+
+#[test]
+fn borrowed_views_encode_like_owned_views() {
+    use skipbot::{StateView, StockVisibility, create_bot_from_spec};
+
+    let mut game = Game::builder(3)
+        .unwrap()
+        .with_seed(21)
+        .with_stock_size(6)
+        .with_stock_visibility(StockVisibility::Binned)
+        .build()
+        .unwrap();
+    let mut bots: Vec<_> = (0..3)
+        .map(|seat| create_bot_from_spec("heuristic13", PlayerId(seat), 5).unwrap())
+        .collect();
+    for _ in 0..400 {
+        if game.is_finished() {
+            break;
+        }
+        for seat in 0..3 {
+            let owned = game.state_view(PlayerId(seat)).unwrap();
+            let borrowed = game.state_view_ref(PlayerId(seat)).unwrap();
+            assert_eq!(
+                StateEncoder::encode(&borrowed),
+                StateEncoder::encode(&owned)
+            );
+            assert_eq!(borrowed.recent_actions(), owned.recent_actions.as_slice());
+            assert_eq!(borrowed.to_owned_view(), owned);
+        }
+        let current = game.current_player();
+        let state = game.state_view(current).unwrap();
+        let legal = game.legal_actions(current).unwrap();
+        let action = bots[current.0].select_action(&state, &legal);
+        game.apply_action(current, action).unwrap();
+    }
+    assert!(game.state_view_ref(PlayerId(3)).is_err());
+}
+
+#[test]
+fn privileged_features_reveal_the_opponents_hands() {
+    let game = Game::builder(3).unwrap().with_seed(7).build().unwrap();
+    let full = game.full_state_view(PlayerId(1)).unwrap();
+    let features = StateEncoder::encode_privileged(&full);
+    let names = StateEncoder::privileged_feature_names();
+    assert_eq!(names.len(), PRIVILEGED_FEATURES);
+    assert_eq!(names[..STATE_FEATURES], StateEncoder::feature_names());
+    assert_eq!(features[..STATE_FEATURES], StateEncoder::encode(&full.view));
+
+    // Seat 1 relative to the viewer is player 2; seats past the table stay empty.
+    let hand_size = full.view.settings.hand_size as f32;
+    for (seat, player) in [(1, 2), (2, 0)] {
+        let mut expected = [0.0f32; 13];
+        for card in &full.hands[player] {
+            let bucket = card.value().map_or(0, usize::from);
+            expected[bucket] += 1.0 / hand_size;
+        }
+        let start = names
+            .iter()
+            .position(|n| *n == format!("seat{seat}.hand.skip_bo"))
+            .unwrap();
+        assert_eq!(features[start..start + 13], expected);
+    }
+    let empty = names
+        .iter()
+        .position(|n| *n == "seat3.hand.skip_bo")
+        .unwrap();
+    assert!(features[empty..].iter().all(|f| *f == 0.0));
+}
+
+#[test]
+fn joint_features_rotate_every_seats_privileged_view() {
+    let game = Game::builder(3).unwrap().with_seed(7).build().unwrap();
+    let names = StateEncoder::joint_feature_names();
+    assert_eq!(names.len(), JOINT_FEATURES);
+    assert_eq!(names[PRIVILEGED_FEATURES], "view1.draw_pile.count");
+    let block = |features: &[f32], view: usize| {
+        features[view * PRIVILEGED_FEATURES..(view + 1) * PRIVILEGED_FEATURES].to_vec()
+    };
+    let joint: Vec<_> = (0..3)
+        .map(|seat| StateEncoder::encode_joint(&game, PlayerId(seat)).unwrap())
+        .collect();
+    for seat in 0..3 {
+        let own = StateEncoder::encode_privileged(&game.full_state_view(PlayerId(seat)).unwrap());
+        assert_eq!(block(&joint[seat], 0), own);
+        assert_eq!(block(&joint[seat], 1), block(&joint[(seat + 1) % 3], 0));
+        assert!(
+            joint[seat][3 * PRIVILEGED_FEATURES..]
+                .iter()
+                .all(|f| *f == 0.0)
+        );
+    }
+    assert!(StateEncoder::encode_joint(&game, PlayerId(3)).is_err());
+}
@@ -0,0 +1,147 @@
+use skipbot::action::{Action, BuildPileId, CardSource, PlayerId};
+use skipbot::bots::TranspositionTable;
+use skipbot::bots::endgame::EndgameSolver;
+use skipbot::card::Card;
+use skipbot::state::GameStateView;
+
+mod common;
+use common::{pile_at, player, view};
+
+fn endgame_state(hand: Vec<Card>, stock_top: Card, stock_count: usize) -> GameStateView {
+    let mut state = view(
+        hand,
+        [pile_at(3), pile_at(9), pile_at(9), pile_at(9)],
+        vec![
+            player(0, Some(stock_top), stock_count),
+            player(1, Some(Card::Number(12)), 10),
+        ],
+    );
+    state.draw_pile_count = 60;
+    state
+}
+
+fn plays_for(state: &GameStateView) -> Vec<Action> {
+    let mut actions = Vec::new();
+    for h in 0..state.hand.len() {
+        for b in 0..4 {
+            actions.push(Action::Play {
+                source: CardSource::Hand(h),
+                build_pile: BuildPileId(b),
+            });
+        }
+    }
+    for b in 0..4 {
+        actions.push(Action::Play {
+            source: CardSource::Stock,
+            build_pile: BuildPileId(b),
+        });
+    }
+    actions
+}
+
+#[test]
+fn solver_finds_certain_win() {
+    // Hand 3 then stock 4 on pile 0 empties a one-card stock.
+    let state = endgame_state(vec![Card::Number(3), Card::Number(7)], Card::Number(4), 1);
+    let mut solver = EndgameSolver::default();
+    let solution = solver.solve(&state, &plays_for(&state)).expect("solvable");
+    assert_eq!(solution.win_probability, 1.0);
+    assert_eq!(
+        solution.action,
+        Action::Play {
+            source: CardSource::Hand(0),
+            build_pile: BuildPileId(0)
+        }
+    );
+    assert_eq!(solver.cached(), 1);
+}
+
+#[test]
+fn solvers_share_a_table() {
+    let state = endgame_state(vec![Card::Number(3), Card::Number(7)], Card::Number(4), 1);
+    let table = TranspositionTable::new(64).shared();
+    let mut first = EndgameSolver::default().with_table(table.clone());
+    let mut second = EndgameSolver::default().with_table(table.clone());
+    let solved = first.solve(&state, &plays_for(&state));
+    assert_eq!(second.cached(), 1);
+    assert_eq!(second.solve(&state, &plays_for(&state)), solved);
+
+    let stats = table.lock().unwrap().stats();
+    assert_eq!((stats.probes, stats.hits, stats.stores), (2, 1, 1));
+    second.clear_cache();
+    assert_eq!(first.cached(), 0);
+}
+
+#[test]
+fn exhausted_budgets_are_not_stored() {
+    let state = endgame_state(vec![Card::Number(1)], Card::Number(9), 2);
+    let table = TranspositionTable::new(64).shared();
+    let mut small = EndgameSolver::default().with_table(table.clone());
+    small.node_limit = 1;
+    let mut full = EndgameSolver::default().with_table(table);
+    assert!(small.solve(&state, &plays_for(&state)).is_none());
+    assert_eq!(full.cached(), 0);
+    let solved = full.solve(&state, &plays_for(&state)).expect("solvable");
+    assert!(solved.win_probability > 0.0);
+    assert_eq!(small.solve(&state, &plays_for(&state)), Some(solved));
+}
+
+#[test]
+fn solver_reports_chance_of_second_stock_card() {
+    // The stock 9 plays immediately; the second stock card is unknown. Holding a Skip-Bo
+    // widens the set of reveals that can still be reached.
+    let state = endgame_state(vec![Card::Number(1)], Card::Number(9), 2);
+    let plain = EndgameSolver::default()
+        .solve(&state, &plays_for(&state))
+        .expect("solvable");
+    assert!(plain.win_probability > 0.0 && plain.win_probability < 1.0);
+    let state = endgame_state(vec![Card::SkipBo], Card::Number(9), 2);
+    let wild = EndgameSolver::default()
+        .solve(&state, &plays_for(&state))
+        .expect("solvable");
+    assert!(wild.win_probability > plain.win_probability);
+}
+
+#[test]
+fn solver_skips_large_stocks() {
+    let state = endgame_state(vec![Card::Number(3)], Card::Number(4), 10);
+    assert!(
+        EndgameSolver::default()
+            .solve(&state, &plays_for(&state))
+            .is_none()
+    );
+}
+
+#[test]
+fn transpositions_share_a_table_entry() {
+    let first = endgame_state(vec![Card::Number(3), Card::Number(7)], Card::Number(4), 1);
+    // The same position, reached on a later turn after different opponent moves.
+    let mut second = first.clone();
+    second.turn_number = 6;
+    second.actions_taken_this_turn = 2;
+    second.recent_actions = vec![(PlayerId(1), Action::EndTurn)];
+    assert_ne!(first.state_hash(), second.state_hash());
+    assert_eq!(first.position_hash(), second.position_hash());
+
+    let table = TranspositionTable::new(64).shared();
+    let mut solver = EndgameSolver::default().with_table(table.clone());
+    let solved = solver.solve(&first, &plays_for(&first));
+    solver.new_search();
+    assert_eq!(solver.solve(&second, &plays_for(&second)), solved);
+    assert_eq!(solver.cached(), 1);
+    assert_eq!(table.lock().unwrap().stats().hits, 1);
+}
+
+#[test]
+fn restricted_legal_sets_get_their_own_solves() {
+    // Hand 3 then stock 4 on pile 0 wins, unless the hand play is not allowed.
+    let state = endgame_state(vec![Card::Number(3), Card::Number(7)], Card::Number(4), 1);
+    let mut solver = EndgameSolver::default();
+    let full = solver.solve(&state, &plays_for(&state)).expect("solvable");
+    let restricted: Vec<Action> = plays_for(&state)
+        .into_iter()
+        .filter(|action| *action != full.action)
+        .collect();
+    assert!(solver.solve(&state, &restricted).is_none());
+    assert_eq!(solver.cached(), 2);
+}
@@ -0,0 +1,86 @@
+use std::sync::Arc;
+
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use skipbot::bots::beam_search::BeamSearchBot;
+use skipbot::bots::eval::{EvalFn, Evaluator, WIN_SCORE};
+use skipbot::bots::planning::TurnPosition;
+use skipbot::card::Card;
+use skipbot::runner::play_out;
+use skipbot::{Bot, Game, PlayerId, create_bot_from_spec};
+
+#[test]
+fn evaluators_parse_from_their_names() {
+    for evaluator in Evaluator::ALL {
+        assert_eq!(evaluator.to_string().parse::<Evaluator>(), Ok(evaluator));
+    }
+    assert_eq!("Tempo".parse::<Evaluator>(), Ok(Evaluator::Tempo));
+    assert!("greedy".parse::<Evaluator>().is_err());
+}
+
+#[test]
+fn every_evaluator_prefers_stock_progress_and_wins() {
+    let game = Game::builder(2).unwrap().with_seed(3).build().unwrap();
+    let state = game.state_view(PlayerId(0)).unwrap();
+    let start = TurnPosition::from_view(&state).unwrap();
+    let mut played = start.clone();
+    played.stock_played = 1;
+    played.stock_count -= 1;
+    played.stock_top = Some(Card::Number(12));
+    let mut won = start.clone();
+    won.stock_count = 0;
+
+    for evaluator in Evaluator::ALL {
+        let before = evaluator.evaluate(&state, &start, 0);
+        assert!(
+            evaluator.evaluate(&state, &played, 0) > before,
+            "{evaluator}"
+        );
+        assert!(
+            evaluator.evaluate(&state, &start, 1) < before,
+            "{evaluator}"
+        );
+        assert_eq!(evaluator.evaluate(&state, &won, 3), WIN_SCORE);
+    }
+}
+
+#[test]
+fn beam_specs_choose_an_evaluator() {
+    for evaluator in Evaluator::ALL {
+        let spec = format!("beam:4:6:eval={evaluator}");
+        let mut game = Game::builder(2)
+            .unwrap()
+            .with_seed(8)
+            .with_stock_size(4)
+            .build()
+            .unwrap();
+        let mut bots: Vec<Box<dyn Bot>> = vec![
+            create_bot_from_spec(&spec, PlayerId(0), 1).unwrap(),
+            create_bot_from_spec("heuristic13", PlayerId(1), 1).unwrap(),
+        ];
+        assert!(play_out(&mut game, &mut bots, 3000, |_| {}).unwrap() > 0);
+    }
+    assert!(create_bot_from_spec("beam:eval=greedy", PlayerId(0), 1).is_err());
+    assert!(create_bot_from_spec("heuristic19:eval=tempo", PlayerId(0), 1).is_err());
+}
+
+#[test]
+fn search_bots_can_share_one_evaluator() {
+    let shared: Arc<dyn EvalFn> = Arc::new(Evaluator::StockDifferential);
+    let mut bots: Vec<Box<dyn Bot>> = (0..2)
+        .map(|seat| {
+            Box::new(
+                BeamSearchBot::new(StdRng::seed_from_u64(seat), 4, 6)
+                    .with_shared_evaluation(Arc::clone(&shared)),
+            ) as Box<dyn Bot>
+        })
+        .collect();
+    let mut game = Game::builder(2)
+        .unwrap()
+        .with_seed(5)
+        .with_stock_size(4)
+        .build()
+        .unwrap();
+    play_out(&mut game, &mut bots, 3000, |_| {}).unwrap();
+    assert_eq!(Arc::strong_count(&shared), 3);
+}
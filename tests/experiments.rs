@@ -0,0 +1,131 @@
+use std::fs;
+
+use skipbot::experiments::{Artifact, Experiment, ExperimentConfig, RunSummary, Spread};
+use skipbot::runner::MatchConfig;
+use skipbot::value::EpochMetrics;
+
+fn config(name: &str, bots: &[&str], seed: u64) -> ExperimentConfig {
+    let bots: Vec<String> = bots.iter().map(|bot| bot.to_string()).collect();
+    let settings = MatchConfig::new(bots.clone(), 10, seed);
+    ExperimentConfig::new(name, bots, vec![seed])
+        .with_settings(&settings)
+        .unwrap()
+}
+
+#[test]
+fn ids_follow_the_configuration() {
+    let base = config("H19 vs H13", &["heuristic19", "heuristic13"], 1);
+    let id = base.id();
+    assert!(id.starts_with("h19-vs-h13-"), "{id}");
+    assert_eq!(id.len(), "h19-vs-h13-".len() + 12);
+    assert_eq!(
+        config("H19 vs H13", &["heuristic19", "heuristic13"], 1).id(),
+        id
+    );
+
+    // The name only labels the directory; the hash covers everything else.
+    assert_eq!(
+        config("other", &["heuristic19", "heuristic13"], 1).hash(),
+        base.hash()
+    );
+    assert_ne!(
+        config("H19 vs H13", &["heuristic19", "heuristic13"], 2).id(),
+        id
+    );
+    assert_ne!(config("H19 vs H13", &["heuristic19", "random"], 1).id(), id);
+    let mut newer = base.clone();
+    newer.encoder_version += 1;
+    assert_ne!(newer.id(), id);
+}
+
+#[test]
+fn experiments_never_overwrite_artifacts() {
+    let root = std::env::temp_dir().join("skipbot-experiments-test");
+    let _ = fs::remove_dir_all(&root);
+    let experiment = Experiment::open(&root, config("run", &["random", "random"], 3)).unwrap();
+    assert_eq!(experiment.dir(), root.join(experiment.id()));
+    for artifact in Artifact::ALL {
+        assert!(experiment.dir().join(artifact.to_string()).is_dir());
+    }
+
+    let report = experiment
+        .new_artifact(Artifact::Report, "report.json")
+        .unwrap();
+    fs::write(&report, "{}").unwrap();
+    assert!(
+        experiment
+            .new_artifact(Artifact::Report, "report.json")
+            .is_err()
+    );
+
+    let first = experiment.next_checkpoint("weights", "json");
+    assert!(first.ends_with("checkpoints/weights-0001.json"));
+    fs::write(&first, "[]").unwrap();
+    assert!(
+        experiment
+            .next_checkpoint("weights", "json")
+            .ends_with("weights-0002.json")
+    );
+
+    // Reopening the same configuration finds its artifacts again.
+    let reopened = Experiment::open(&root, experiment.config().clone()).unwrap();
+    assert!(reopened.path(Artifact::Report, "report.json").exists());
+
+    // A directory written by a different configuration is not reused.
+    let mut tampered = experiment.config().clone();
+    tampered.settings = serde_json::json!({ "games": 11 });
+    fs::write(
+        experiment.dir().join("config.json"),
+        serde_json::to_string(&tampered).unwrap(),
+    )
+    .unwrap();
+    assert!(Experiment::open(&root, experiment.config().clone()).is_err());
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+fn epoch(epoch: usize, loss: f64, win_rate: Option<f64>) -> EpochMetrics {
+    EpochMetrics {
+        epoch,
+        loss,
+        validation_loss: loss + 0.1,
+        learning_rate: 1e-3,
+        win_rate,
+        best: false,
+    }
+}
+
+#[test]
+fn spreads_use_the_sample_standard_deviation() {
+    let spread = Spread::of([0.4, 0.5, 0.6]).unwrap();
+    assert!((spread.mean - 0.5).abs() < 1e-12);
+    assert!((spread.stdev - 0.1).abs() < 1e-12);
+    assert!((spread.stderr() - 0.1 / 3f64.sqrt()).abs() < 1e-12);
+    assert_eq!(Spread::of([0.7]).unwrap().stdev, 0.0);
+    assert_eq!(Spread::of([]), None);
+}
+
+#[test]
+fn run_summaries_aggregate_each_epoch_over_the_runs() {
+    let runs = vec![
+        vec![epoch(1, 0.7, None), epoch(2, 0.5, Some(0.40))],
+        vec![epoch(1, 0.6, None), epoch(2, 0.4, Some(0.60))],
+        vec![epoch(1, 0.8, None)],
+    ];
+    let summary = RunSummary::new(&[1, 2, 3], &runs).unwrap();
+    assert_eq!(summary.seeds, vec![1, 2, 3]);
+    assert_eq!(summary.epochs.len(), 2);
+    let first = &summary.epochs[0];
+    assert_eq!(first.loss.runs, 3);
+    assert!((first.loss.mean - 0.7).abs() < 1e-12);
+    assert!((first.validation_loss.mean - 0.8).abs() < 1e-12);
+    assert_eq!(first.win_rate, None);
+    let second = &summary.epochs[1];
+    assert_eq!(second.loss.runs, 2);
+    let win_rate = second.win_rate.unwrap();
+    assert!((win_rate.mean - 0.5).abs() < 1e-12);
+    assert_eq!(summary.final_win_rate, Some(win_rate));
+    assert!(summary.to_string().contains("Final win rate: 50.0%"));
+
+    assert!(RunSummary::new(&[1], &runs).is_err());
+}
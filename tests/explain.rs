@@ -0,0 +1,51 @@
+use skipbot::explain::{saliency, saliency_of};
+use skipbot::value::ValueNet;
+use skipbot::{Game, PlayerId, STATE_FEATURES, StateEncoder};
+
+#[test]
+fn input_gradients_match_finite_differences() {
+    let net = ValueNet::with_inputs(3, 4, 7);
+    let features = [0.3, 0.8, 0.1];
+    let gradient = net.input_gradient(&features);
+    for i in 0..3 {
+        let mut up = features;
+        let mut down = features;
+        up[i] += 1e-3;
+        down[i] -= 1e-3;
+        let numeric = (net.logit(&up) - net.logit(&down)) / 2e-3;
+        assert!((gradient[i] - numeric).abs() < 1e-2, "{i}: {gradient:?}");
+    }
+
+    let explained = saliency_of(&net, &features);
+    assert_eq!(explained.win_probability, net.predict(&features));
+    assert!(explained.features.iter().any(|f| f.name == "feature1"));
+    assert!(
+        explained
+            .features
+            .windows(2)
+            .all(|w| w[0].contribution.abs() >= w[1].contribution.abs())
+    );
+}
+
+#[test]
+fn saliency_names_the_encoder_features_the_net_reads() {
+    // A net that only looks at where build pile 2 stands.
+    let watched = StateEncoder::feature_index("build2.next_value").unwrap();
+    let mut net = ValueNet::with_inputs(STATE_FEATURES, 1, 0);
+    net.w1.fill(0.0);
+    net.w1[watched] = 2.0;
+    net.w2[0] = 1.5;
+
+    let game = Game::builder(2).unwrap().with_seed(4).build().unwrap();
+    let view = game.state_view(PlayerId(0)).unwrap();
+    let explained = saliency(&net, &view);
+    let top = &explained.features[0];
+    assert_eq!(top.name, "build2.next_value");
+    assert!(top.contribution > 0.0);
+    assert!(explained.features[1..].iter().all(|f| f.gradient == 0.0));
+
+    let report = explained.report(3);
+    assert!(report.starts_with("win probability "));
+    assert!(report.contains("build2.next_value"));
+    assert_eq!(report.lines().count(), 2 + 3);
+}
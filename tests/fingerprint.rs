@@ -0,0 +1,54 @@
+use skipbot::analysis::{BehaviorCounts, Fingerprint};
+use skipbot::runner::{MatchConfig, MatchRunner};
+
+#[test]
+fn fingerprint_rates_from_counts() {
+    let counts = BehaviorCounts {
+        decisions: 20,
+        turns: 8,
+        plays: 12,
+        stock_chances: 4,
+        stock_plays: 3,
+        discards: [2, 2, 2, 2],
+        block_chances: 5,
+        blocks: 1,
+    };
+    let fp = Fingerprint::from_counts(&counts);
+    assert_eq!(fp.stock_aggressiveness, 0.75);
+    assert!((fp.discard_entropy - 1.0).abs() < 1e-12);
+    assert_eq!(fp.blocking_frequency, 0.2);
+    assert_eq!(fp.plays_per_turn, 1.5);
+
+    let one_pile = BehaviorCounts {
+        discards: [5, 0, 0, 0],
+        ..BehaviorCounts::default()
+    };
+    assert_eq!(Fingerprint::from_counts(&one_pile).discard_entropy, 0.0);
+}
+
+#[test]
+fn match_report_carries_fingerprints() {
+    let mut config = MatchConfig::new(vec!["heuristic13".into(), "heuristic2".into()], 4, 9);
+    config.stock_size = Some(5);
+    let report = MatchRunner::new(config).unwrap().run().unwrap();
+    for game in &report.games {
+        assert_eq!(game.behavior.len(), game.seats.len());
+        let decisions: u64 = game.behavior.iter().map(|b| b.decisions).sum();
+        assert_eq!(decisions, game.actions as u64);
+    }
+    for bot in &report.bots {
+        let fp = &bot.fingerprint;
+        for rate in [
+            fp.stock_aggressiveness,
+            fp.discard_entropy,
+            fp.blocking_frequency,
+        ] {
+            assert!(
+                (0.0..=1.0).contains(&rate),
+                "{} rate out of range",
+                bot.label
+            );
+        }
+        assert!(fp.plays_per_turn > 0.0);
+    }
+}
@@ -1,11 +1,15 @@
 use skipbot::Bot;
-use skipbot::action::Action;
+use skipbot::action::{Action, BuildPileId, CardSource, DiscardPileId, PlayerId};
+use skipbot::bots::heuristic_11::Heuristic11Bot;
 use skipbot::bots::heuristic_12::Heuristic12Bot;
 use skipbot::card::Card;
 use skipbot::state::{
     BuildPileView, GameSettings, GameStateView, GameStatus, PlayerPublicState, TurnPhase,
 };
 
+mod common;
+use common::{pile_at, player, view};
+
 #[test]
 fn heuristic12_prefers_one_below_discard() {
     // Build minimal game state snapshot with only discard actions legal.
@@ -20,24 +24,25 @@ fn heuristic12_prefers_one_below_discard() {
     // Self player discard tops: pile 0 has a 5 (so discarding 4 gets one-below bonus),
     // pile 1 has a 10 (irrelevant), others empty.
     let self_player = PlayerPublicState {
-        id: 0,
+        id: PlayerId(0),
         stock_count: 30,
         stock_top: None,
-        discard_piles: [
+        discard_piles: vec![
             vec![Card::Number(5)],
             vec![Card::Number(10)],
             vec![],
             vec![],
-        ],
+        ]
+        .into(),
         hand_size: 2,
         is_current: true,
         has_won: false,
     };
     let other_player = PlayerPublicState {
-        id: 1,
+        id: PlayerId(1),
         stock_count: 30,
         stock_top: None,
-        discard_piles: [vec![], vec![], vec![], vec![]],
+        discard_piles: vec![vec![], vec![], vec![], vec![]].into(),
         hand_size: 0,
         is_current: false,
         has_won: false,
@@ -47,28 +52,33 @@ fn heuristic12_prefers_one_below_discard() {
         settings,
         phase: TurnPhase::AwaitingAction,
         status: GameStatus::Ongoing,
-        self_player: 0,
-        current_player: 0,
+        self_player: PlayerId(0),
+        current_player: PlayerId(0),
         draw_pile_count: 0,
         recycle_pile_count: 0,
         build_piles,
         players: vec![self_player, other_player],
-        hand: vec![Card::Number(4), Card::Number(9)],
+        hand: vec![Card::Number(4), Card::Number(9)].into(),
+        recent_actions: Vec::new(),
+        turn_number: 0,
+        actions_taken_this_turn: 0,
+        turn_events: Vec::new(),
+        forbidden_plays: Vec::new(),
     };
 
     // Legal discard actions: choose where to place each hand card.
     let legal_actions: Vec<Action> = vec![
         Action::Discard {
             hand_index: 0,
-            discard_pile: 0,
+            discard_pile: DiscardPileId(0),
         }, // one-below bonus (4 under 5)
         Action::Discard {
             hand_index: 0,
-            discard_pile: 2,
+            discard_pile: DiscardPileId(2),
         }, // no bonus
         Action::Discard {
             hand_index: 1,
-            discard_pile: 1,
+            discard_pile: DiscardPileId(1),
         }, // no bonus
     ];
 
@@ -78,8 +88,47 @@ fn heuristic12_prefers_one_below_discard() {
         chosen,
         Action::Discard {
             hand_index: 0,
-            discard_pile: 0
+            discard_pile: DiscardPileId(0)
         },
         "Bot should prefer discarding the one-below card onto pile with top 5"
     );
 }
+
+#[test]
+fn hand_empty_plans_play_out_over_several_decisions() {
+    let play = |hand_index| Action::Play {
+        source: CardSource::Hand(hand_index),
+        build_pile: BuildPileId(0),
+    };
+    // The 1, then the 2 (shifted down to index 1), then the 3 empty the hand.
+    let steps = [
+        (
+            vec![Card::Number(3), Card::Number(1), Card::Number(2)],
+            1,
+            1,
+        ),
+        (vec![Card::Number(3), Card::Number(2)], 2, 1),
+        (vec![Card::Number(3)], 3, 0),
+    ];
+    let bots: [Box<dyn Bot>; 2] = [
+        Box::new(Heuristic11Bot::new()),
+        Box::new(Heuristic12Bot::new()),
+    ];
+    for mut bot in bots {
+        let players = vec![player(0, Some(Card::Number(12)), 30), player(1, None, 30)];
+        bot.on_turn_start(&view(
+            steps[0].0.clone(),
+            std::array::from_fn(|_| pile_at(1)),
+            players.clone(),
+        ));
+        for (hand, next, expected) in &steps {
+            let mut piles = std::array::from_fn(|_| pile_at(1));
+            piles[0] = pile_at(*next);
+            let state = view(hand.clone(), piles, players.clone());
+            assert_eq!(
+                bot.select_action(&state, &state.legal_actions()),
+                play(*expected)
+            );
+        }
+    }
+}
@@ -1,5 +1,5 @@
 use skipbot::Bot;
-use skipbot::action::{Action, CardSource};
+use skipbot::action::{Action, BuildPileId, CardSource, DiscardPileId, PlayerId};
 use skipbot::bots::heuristic_14::Heuristic14Bot;
 use skipbot::card::Card;
 use skipbot::state::{
@@ -16,26 +16,26 @@ fn base_state(
     let build_piles = [
         BuildPileView::empty(),
         BuildPileView {
-            cards: vec![Card::Number(1)],
+            cards: vec![Card::Number(1)].into(),
             next_value: 2,
         },
         BuildPileView::empty(),
         BuildPileView::empty(),
     ];
     let self_player = PlayerPublicState {
-        id: 0,
+        id: PlayerId(0),
         stock_count: 30,
         stock_top,
-        discard_piles: discard_tops,
+        discard_piles: discard_tops.to_vec().into(),
         hand_size: hand.len(),
         is_current: true,
         has_won: false,
     };
     let other_player = PlayerPublicState {
-        id: 1,
+        id: PlayerId(1),
         stock_count: 30,
         stock_top: None,
-        discard_piles: [vec![], vec![], vec![], vec![]],
+        discard_piles: vec![vec![], vec![], vec![], vec![]].into(),
         hand_size: 0,
         is_current: false,
         has_won: false,
@@ -44,13 +44,18 @@ fn base_state(
         settings,
         phase: TurnPhase::AwaitingAction,
         status: GameStatus::Ongoing,
-        self_player: 0,
-        current_player: 0,
+        self_player: PlayerId(0),
+        current_player: PlayerId(0),
         draw_pile_count: 0,
         recycle_pile_count: 0,
         build_piles,
         players: vec![self_player, other_player],
-        hand,
+        hand: hand.into(),
+        recent_actions: Vec::new(),
+        turn_number: 0,
+        actions_taken_this_turn: 0,
+        turn_events: Vec::new(),
+        forbidden_plays: Vec::new(),
     }
 }
 
@@ -65,15 +70,15 @@ fn heuristic14_prefers_stock_play() {
     let legal_actions = vec![
         Action::Play {
             source: CardSource::Stock,
-            build_pile: 1,
+            build_pile: BuildPileId(1),
         },
         Action::Play {
             source: CardSource::Hand(0),
-            build_pile: 1,
+            build_pile: BuildPileId(1),
         },
         Action::Discard {
             hand_index: 0,
-            discard_pile: 0,
+            discard_pile: DiscardPileId(0),
         },
     ];
     let mut bot = Heuristic14Bot::new();
@@ -82,7 +87,7 @@ fn heuristic14_prefers_stock_play() {
         chosen,
         Action::Play {
             source: CardSource::Stock,
-            build_pile: 1
+            build_pile: BuildPileId(1)
         }
     );
 }
@@ -97,16 +102,16 @@ fn heuristic14_prefers_discard_play_over_hand() {
     );
     let legal_actions = vec![
         Action::Play {
-            source: CardSource::Discard(0),
-            build_pile: 1,
+            source: CardSource::Discard(DiscardPileId(0)),
+            build_pile: BuildPileId(1),
         },
         Action::Play {
             source: CardSource::Hand(0),
-            build_pile: 1,
+            build_pile: BuildPileId(1),
         },
         Action::Discard {
             hand_index: 1,
-            discard_pile: 0,
+            discard_pile: DiscardPileId(0),
         },
     ];
     let mut bot = Heuristic14Bot::new();
@@ -114,8 +119,8 @@ fn heuristic14_prefers_discard_play_over_hand() {
     assert_eq!(
         chosen,
         Action::Play {
-            source: CardSource::Discard(0),
-            build_pile: 1
+            source: CardSource::Discard(DiscardPileId(0)),
+            build_pile: BuildPileId(1)
         }
     );
 }
@@ -130,11 +135,11 @@ fn heuristic14_plays_hand_when_only_option() {
     let legal_actions = vec![
         Action::Play {
             source: CardSource::Hand(0),
-            build_pile: 1,
+            build_pile: BuildPileId(1),
         },
         Action::Discard {
             hand_index: 0,
-            discard_pile: 0,
+            discard_pile: DiscardPileId(0),
         },
     ];
     let mut bot = Heuristic14Bot::new();
@@ -143,7 +148,7 @@ fn heuristic14_plays_hand_when_only_option() {
         chosen,
         Action::Play {
             source: CardSource::Hand(0),
-            build_pile: 1
+            build_pile: BuildPileId(1)
         }
     );
 }
@@ -168,7 +173,7 @@ fn heuristic14_discards_when_no_play_and_hand_not_empty() {
     );
     let legal_actions = vec![Action::Discard {
         hand_index: 0,
-        discard_pile: 2,
+        discard_pile: DiscardPileId(2),
     }];
     let mut bot = Heuristic14Bot::new();
     let chosen = bot.select_action(&state, &legal_actions);
@@ -176,7 +181,7 @@ fn heuristic14_discards_when_no_play_and_hand_not_empty() {
         chosen,
         Action::Discard {
             hand_index: 0,
-            discard_pile: 2
+            discard_pile: DiscardPileId(2)
         }
     );
 }
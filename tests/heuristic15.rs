@@ -1,5 +1,5 @@
 use skipbot::Bot;
-use skipbot::action::{Action, CardSource};
+use skipbot::action::{Action, BuildPileId, CardSource, DiscardPileId, PlayerId};
 use skipbot::bots::heuristic_15::Heuristic15Bot;
 use skipbot::card::Card;
 use skipbot::state::{
@@ -15,26 +15,26 @@ fn base_state(
     let build_piles = [
         BuildPileView::empty(),
         BuildPileView {
-            cards: vec![Card::Number(1)],
+            cards: vec![Card::Number(1)].into(),
             next_value: 2,
         },
         BuildPileView::empty(),
         BuildPileView::empty(),
     ];
     let self_player = PlayerPublicState {
-        id: 0,
+        id: PlayerId(0),
         stock_count: 30,
         stock_top,
-        discard_piles: discard_tops,
+        discard_piles: discard_tops.to_vec().into(),
         hand_size: hand.len(),
         is_current: true,
         has_won: false,
     };
     let other_player = PlayerPublicState {
-        id: 1,
+        id: PlayerId(1),
         stock_count: 30,
         stock_top: None,
-        discard_piles: [vec![], vec![], vec![], vec![]],
+        discard_piles: vec![vec![], vec![], vec![], vec![]].into(),
         hand_size: 0,
         is_current: false,
         has_won: false,
@@ -43,13 +43,18 @@ fn base_state(
         settings,
         phase: TurnPhase::AwaitingAction,
         status: GameStatus::Ongoing,
-        self_player: 0,
-        current_player: 0,
+        self_player: PlayerId(0),
+        current_player: PlayerId(0),
         draw_pile_count: 0,
         recycle_pile_count: 0,
         build_piles,
         players: vec![self_player, other_player],
-        hand,
+        hand: hand.into(),
+        recent_actions: Vec::new(),
+        turn_number: 0,
+        actions_taken_this_turn: 0,
+        turn_events: Vec::new(),
+        forbidden_plays: Vec::new(),
     }
 }
 
@@ -63,15 +68,15 @@ fn heuristic15_prefers_stock_play() {
     let legal_actions = vec![
         Action::Play {
             source: CardSource::Stock,
-            build_pile: 1,
+            build_pile: BuildPileId(1),
         },
         Action::Play {
             source: CardSource::Hand(0),
-            build_pile: 1,
+            build_pile: BuildPileId(1),
         },
         Action::Play {
-            source: CardSource::Discard(0),
-            build_pile: 1,
+            source: CardSource::Discard(DiscardPileId(0)),
+            build_pile: BuildPileId(1),
         },
     ];
     let mut bot = Heuristic15Bot::new();
@@ -80,7 +85,7 @@ fn heuristic15_prefers_stock_play() {
         chosen,
         Action::Play {
             source: CardSource::Stock,
-            build_pile: 1
+            build_pile: BuildPileId(1)
         }
     );
 }
@@ -94,12 +99,12 @@ fn heuristic15_prefers_hand_over_discard() {
     );
     let legal_actions = vec![
         Action::Play {
-            source: CardSource::Discard(0),
-            build_pile: 1,
+            source: CardSource::Discard(DiscardPileId(0)),
+            build_pile: BuildPileId(1),
         },
         Action::Play {
             source: CardSource::Hand(0),
-            build_pile: 1,
+            build_pile: BuildPileId(1),
         },
     ];
     let mut bot = Heuristic15Bot::new();
@@ -108,7 +113,7 @@ fn heuristic15_prefers_hand_over_discard() {
         chosen,
         Action::Play {
             source: CardSource::Hand(0),
-            build_pile: 1
+            build_pile: BuildPileId(1)
         }
     );
 }
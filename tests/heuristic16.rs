@@ -1,4 +1,4 @@
-use skipbot::action::{Action, CardSource};
+use skipbot::action::{Action, BuildPileId, CardSource, DiscardPileId, PlayerId};
 use skipbot::bot::Bot;
 use skipbot::bots::heuristic_16::Heuristic16Bot;
 use skipbot::card::Card;
@@ -14,19 +14,19 @@ fn base_state(
 ) -> GameStateView {
     let settings = GameSettings::new(2).unwrap();
     let self_player = PlayerPublicState {
-        id: 0,
+        id: PlayerId(0),
         stock_count: 30,
         stock_top,
-        discard_piles,
+        discard_piles: discard_piles.to_vec().into(),
         hand_size: hand.len(),
         is_current: true,
         has_won: false,
     };
     let other_player = PlayerPublicState {
-        id: 1,
+        id: PlayerId(1),
         stock_count: 30,
         stock_top: None,
-        discard_piles: [vec![], vec![], vec![], vec![]],
+        discard_piles: vec![vec![], vec![], vec![], vec![]].into(),
         hand_size: 0,
         is_current: false,
         has_won: false,
@@ -35,13 +35,18 @@ fn base_state(
         settings,
         phase: TurnPhase::AwaitingAction,
         status: GameStatus::Ongoing,
-        self_player: 0,
-        current_player: 0,
+        self_player: PlayerId(0),
+        current_player: PlayerId(0),
         draw_pile_count: 0,
         recycle_pile_count: 0,
         build_piles,
         players: vec![self_player, other_player],
-        hand,
+        hand: hand.into(),
+        recent_actions: Vec::new(),
+        turn_number: 0,
+        actions_taken_this_turn: 0,
+        turn_events: Vec::new(),
+        forbidden_plays: Vec::new(),
     }
 }
 
@@ -61,11 +66,11 @@ fn heuristic16_prefers_duplicate_discard() {
     let legal_actions = vec![
         Action::Discard {
             hand_index: 0,
-            discard_pile: 1,
+            discard_pile: DiscardPileId(1),
         },
         Action::Discard {
             hand_index: 0,
-            discard_pile: 0,
+            discard_pile: DiscardPileId(0),
         },
     ];
     let mut bot = Heuristic16Bot::new();
@@ -74,7 +79,7 @@ fn heuristic16_prefers_duplicate_discard() {
         chosen,
         Action::Discard {
             hand_index: 0,
-            discard_pile: 0
+            discard_pile: DiscardPileId(0)
         }
     );
 }
@@ -82,7 +87,7 @@ fn heuristic16_prefers_duplicate_discard() {
 #[test]
 fn heuristic16_still_prioritizes_stock_play() {
     let mut build_two = BuildPileView::empty();
-    build_two.cards.push(Card::Number(1));
+    build_two.cards = vec![Card::Number(1)].into();
     build_two.next_value = 2;
     let state = base_state(
         vec![Card::Number(5)],
@@ -98,11 +103,11 @@ fn heuristic16_still_prioritizes_stock_play() {
     let legal_actions = vec![
         Action::Play {
             source: CardSource::Stock,
-            build_pile: 1,
+            build_pile: BuildPileId(1),
         },
         Action::Discard {
             hand_index: 0,
-            discard_pile: 0,
+            discard_pile: DiscardPileId(0),
         },
     ];
     let mut bot = Heuristic16Bot::new();
@@ -111,7 +116,7 @@ fn heuristic16_still_prioritizes_stock_play() {
         chosen,
         Action::Play {
             source: CardSource::Stock,
-            build_pile: 1
+            build_pile: BuildPileId(1)
         }
     );
 }
@@ -1,4 +1,4 @@
-use skipbot::action::{Action, CardSource};
+use skipbot::action::{Action, BuildPileId, CardSource, DiscardPileId, PlayerId};
 use skipbot::bot::Bot;
 use skipbot::bots::heuristic_17::Heuristic17Bot;
 use skipbot::card::Card;
@@ -14,19 +14,19 @@ fn base_state(
 ) -> GameStateView {
     let settings = GameSettings::new(2).unwrap();
     let self_player = PlayerPublicState {
-        id: 0,
+        id: PlayerId(0),
         stock_count: 30,
         stock_top,
-        discard_piles,
+        discard_piles: discard_piles.to_vec().into(),
         hand_size: hand.len(),
         is_current: true,
         has_won: false,
     };
     let other_player = PlayerPublicState {
-        id: 1,
+        id: PlayerId(1),
         stock_count: 30,
         stock_top: None,
-        discard_piles: [vec![], vec![], vec![], vec![]],
+        discard_piles: vec![vec![], vec![], vec![], vec![]].into(),
         hand_size: 0,
         is_current: false,
         has_won: false,
@@ -35,22 +35,25 @@ fn base_state(
         settings,
         phase: TurnPhase::AwaitingAction,
         status: GameStatus::Ongoing,
-        self_player: 0,
-        current_player: 0,
+        self_player: PlayerId(0),
+        current_player: PlayerId(0),
         draw_pile_count: 0,
         recycle_pile_count: 0,
         build_piles,
         players: vec![self_player, other_player],
-        hand,
+        hand: hand.into(),
+        recent_actions: Vec::new(),
+        turn_number: 0,
+        actions_taken_this_turn: 0,
+        turn_events: Vec::new(),
+        forbidden_plays: Vec::new(),
     }
 }
 
 #[test]
 fn heuristic17_ranks_stock_targets() {
     let mut pile_one = BuildPileView::empty();
-    pile_one
-        .cards
-        .extend_from_slice(&[Card::Number(1), Card::Number(2), Card::Number(3)]);
+    pile_one.cards = vec![Card::Number(1), Card::Number(2), Card::Number(3)].into();
     pile_one.next_value = 4;
     let state = base_state(
         vec![],
@@ -66,11 +69,11 @@ fn heuristic17_ranks_stock_targets() {
     let legal_actions = vec![
         Action::Play {
             source: CardSource::Stock,
-            build_pile: 0,
+            build_pile: BuildPileId(0),
         },
         Action::Play {
             source: CardSource::Stock,
-            build_pile: 1,
+            build_pile: BuildPileId(1),
         },
     ];
     let mut bot = Heuristic17Bot::new();
@@ -79,7 +82,7 @@ fn heuristic17_ranks_stock_targets() {
         chosen,
         Action::Play {
             source: CardSource::Stock,
-            build_pile: 1
+            build_pile: BuildPileId(1)
         }
     );
 }
@@ -87,7 +90,7 @@ fn heuristic17_ranks_stock_targets() {
 #[test]
 fn heuristic17_prefers_better_hand_target() {
     let mut pile_zero = BuildPileView::empty();
-    pile_zero.cards.push(Card::Number(1));
+    pile_zero.cards = vec![Card::Number(1)].into();
     pile_zero.next_value = 2;
     let state = base_state(
         vec![Card::Number(2), Card::Number(2)],
@@ -103,11 +106,11 @@ fn heuristic17_prefers_better_hand_target() {
     let legal_actions = vec![
         Action::Play {
             source: CardSource::Hand(0),
-            build_pile: 0,
+            build_pile: BuildPileId(0),
         },
         Action::Play {
             source: CardSource::Hand(1),
-            build_pile: 1,
+            build_pile: BuildPileId(1),
         },
     ];
     let mut bot = Heuristic17Bot::new();
@@ -116,7 +119,7 @@ fn heuristic17_prefers_better_hand_target() {
         chosen,
         Action::Play {
             source: CardSource::Hand(0),
-            build_pile: 0
+            build_pile: BuildPileId(0)
         }
     );
 }
@@ -136,12 +139,12 @@ fn heuristic17_keeps_hand_over_discard_priority() {
     );
     let legal_actions = vec![
         Action::Play {
-            source: CardSource::Discard(0),
-            build_pile: 2,
+            source: CardSource::Discard(DiscardPileId(0)),
+            build_pile: BuildPileId(2),
         },
         Action::Play {
             source: CardSource::Hand(0),
-            build_pile: 2,
+            build_pile: BuildPileId(2),
         },
     ];
     let mut bot = Heuristic17Bot::new();
@@ -150,7 +153,7 @@ fn heuristic17_keeps_hand_over_discard_priority() {
         chosen,
         Action::Play {
             source: CardSource::Hand(0),
-            build_pile: 2
+            build_pile: BuildPileId(2)
         }
     );
 }
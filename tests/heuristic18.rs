@@ -1,4 +1,4 @@
-use skipbot::action::{Action, CardSource};
+use skipbot::action::{Action, BuildPileId, CardSource, DiscardPileId, PlayerId};
 use skipbot::bot::Bot;
 use skipbot::bots::heuristic_18::Heuristic18Bot;
 use skipbot::card::Card;
@@ -14,19 +14,19 @@ fn base_state(
 ) -> GameStateView {
     let settings = GameSettings::new(2).unwrap();
     let self_player = PlayerPublicState {
-        id: 0,
+        id: PlayerId(0),
         stock_count: 30,
         stock_top,
-        discard_piles,
+        discard_piles: discard_piles.to_vec().into(),
         hand_size: hand.len(),
         is_current: true,
         has_won: false,
     };
     let other_player = PlayerPublicState {
-        id: 1,
+        id: PlayerId(1),
         stock_count: 30,
         stock_top: None,
-        discard_piles: [vec![], vec![], vec![], vec![]],
+        discard_piles: vec![vec![], vec![], vec![], vec![]].into(),
         hand_size: 0,
         is_current: false,
         has_won: false,
@@ -35,20 +35,25 @@ fn base_state(
         settings,
         phase: TurnPhase::AwaitingAction,
         status: GameStatus::Ongoing,
-        self_player: 0,
-        current_player: 0,
+        self_player: PlayerId(0),
+        current_player: PlayerId(0),
         draw_pile_count: 0,
         recycle_pile_count: 0,
         build_piles,
         players: vec![self_player, other_player],
-        hand,
+        hand: hand.into(),
+        recent_actions: Vec::new(),
+        turn_number: 0,
+        actions_taken_this_turn: 0,
+        turn_events: Vec::new(),
+        forbidden_plays: Vec::new(),
     }
 }
 
 #[test]
 fn heuristic18_stock_prefers_followups() {
     let mut pile_zero = BuildPileView::empty();
-    pile_zero.cards.push(Card::Number(1));
+    pile_zero.cards = vec![Card::Number(1)].into();
     pile_zero.next_value = 2;
     let pile_one = BuildPileView::empty();
     let state = base_state(
@@ -65,11 +70,11 @@ fn heuristic18_stock_prefers_followups() {
     let legal_actions = vec![
         Action::Play {
             source: CardSource::Stock,
-            build_pile: 0,
+            build_pile: BuildPileId(0),
         },
         Action::Play {
             source: CardSource::Stock,
-            build_pile: 1,
+            build_pile: BuildPileId(1),
         },
     ];
     let mut bot = Heuristic18Bot::new();
@@ -78,7 +83,7 @@ fn heuristic18_stock_prefers_followups() {
         chosen,
         Action::Play {
             source: CardSource::Stock,
-            build_pile: 1
+            build_pile: BuildPileId(1)
         }
     );
 }
@@ -99,11 +104,11 @@ fn heuristic18_keeps_discard_smart() {
     let legal_actions = vec![
         Action::Discard {
             hand_index: 0,
-            discard_pile: 2,
+            discard_pile: DiscardPileId(2),
         },
         Action::Discard {
             hand_index: 0,
-            discard_pile: 0,
+            discard_pile: DiscardPileId(0),
         },
     ];
     let mut bot = Heuristic18Bot::new();
@@ -112,7 +117,7 @@ fn heuristic18_keeps_discard_smart() {
         chosen,
         Action::Discard {
             hand_index: 0,
-            discard_pile: 0
+            discard_pile: DiscardPileId(0)
         }
     );
 }
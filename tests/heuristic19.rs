@@ -0,0 +1,191 @@
+use skipbot::action::{Action, BuildPileId, CardSource, DiscardPileId, LegalSet};
+use skipbot::bot::Bot;
+use skipbot::bots::heuristic_19::{Heuristic19Bot, PlanWeights};
+use skipbot::bots::planning::{TurnContext, TurnEnd, TurnEnumerator};
+use skipbot::card::Card;
+use skipbot::state::{BuildPileView, GameStateView};
+
+mod common;
+use common::{pile_at, player, view};
+
+fn base_state(
+    hand: Vec<Card>,
+    discard_piles: [Vec<Card>; 4],
+    stock_top: Option<Card>,
+    opponent_stock_top: Option<Card>,
+    build_piles: [BuildPileView; 4],
+) -> GameStateView {
+    let mut me = player(0, stock_top, 30);
+    me.discard_piles = discard_piles.to_vec().into();
+    let mut opponent = player(1, opponent_stock_top, 30);
+    opponent.hand_size = 0;
+    view(hand, build_piles, vec![me, opponent])
+}
+
+fn all_legal(state: &GameStateView) -> Vec<Action> {
+    let mut actions = Vec::new();
+    for (h, _) in state.hand.iter().enumerate() {
+        for b in 0..4 {
+            actions.push(Action::Play {
+                source: CardSource::Hand(h),
+                build_pile: BuildPileId(b),
+            });
+        }
+    }
+    for d in 0..4 {
+        for b in 0..4 {
+            actions.push(Action::Play {
+                source: CardSource::Discard(DiscardPileId(d)),
+                build_pile: BuildPileId(b),
+            });
+        }
+        for h in 0..state.hand.len() {
+            actions.push(Action::Discard {
+                hand_index: h,
+                discard_pile: DiscardPileId(d),
+            });
+        }
+    }
+    for b in 0..4 {
+        actions.push(Action::Play {
+            source: CardSource::Stock,
+            build_pile: BuildPileId(b),
+        });
+    }
+    actions
+}
+
+#[test]
+fn heuristic19_chains_towards_stock() {
+    // Pile 0 needs 3; hand 3 then discard 4 unlock the stock 5.
+    let state = base_state(
+        vec![Card::Number(3), Card::Number(11), Card::Number(9)],
+        [vec![Card::Number(4)], vec![], vec![], vec![]],
+        Some(Card::Number(5)),
+        None,
+        [pile_at(3), pile_at(8), pile_at(8), pile_at(8)],
+    );
+    let mut bot = Heuristic19Bot::new();
+    let chosen = bot.select_action(&state, &all_legal(&state));
+    assert_eq!(
+        chosen,
+        Action::Play {
+            source: CardSource::Hand(0),
+            build_pile: BuildPileId(0)
+        }
+    );
+}
+
+#[test]
+fn heuristic19_avoids_unlocking_opponent_without_gain() {
+    // Playing the 7 would leave a pile on 8, the opponent's stock value.
+    let state = base_state(
+        vec![Card::Number(7), Card::Number(2)],
+        [vec![], vec![], vec![], vec![]],
+        Some(Card::Number(11)),
+        Some(Card::Number(8)),
+        [pile_at(7), pile_at(10), pile_at(10), pile_at(10)],
+    );
+    let mut bot = Heuristic19Bot::new();
+    let chosen = bot.select_action(&state, &all_legal(&state));
+    assert!(matches!(chosen, Action::Discard { .. }), "{chosen:?}");
+}
+
+#[test]
+fn enumerator_reports_stock_reveal_and_discards() {
+    let state = base_state(
+        vec![Card::Number(1), Card::Number(6)],
+        [vec![], vec![], vec![], vec![]],
+        Some(Card::Number(2)),
+        None,
+        [pile_at(1), pile_at(5), pile_at(5), pile_at(5)],
+    );
+    let mut stock_plans = 0;
+    let mut discard_plans = 0;
+    TurnEnumerator::default().for_each_plan(&state, |plan| match plan.end {
+        TurnEnd::StockRevealed => {
+            stock_plans += 1;
+            assert_eq!(plan.position.stock_played, 1);
+        }
+        TurnEnd::Discard => discard_plans += 1,
+        _ => {}
+    });
+    assert!(stock_plans >= 1);
+    assert!(discard_plans >= 8);
+}
+
+#[test]
+fn turn_context_replays_plan_until_view_diverges() {
+    let play_first = Action::Play {
+        source: CardSource::Hand(0),
+        build_pile: BuildPileId(0),
+    };
+    let state = base_state(
+        vec![
+            Card::Number(1),
+            Card::Number(2),
+            Card::Number(3),
+            Card::Number(9),
+            Card::Number(10),
+        ],
+        [vec![], vec![], vec![], vec![]],
+        Some(Card::Number(12)),
+        Some(Card::Number(11)),
+        [pile_at(1), pile_at(5), pile_at(5), pile_at(5)],
+    );
+    let plan = vec![play_first.clone(), play_first.clone(), play_first.clone()];
+    let mut context = TurnContext::new();
+    assert_eq!(context.start_plan(&state, plan), Some(play_first.clone()));
+    assert!(context.has_plan());
+
+    // The view after the first play matches the prediction.
+    let mut next = state.clone();
+    std::sync::Arc::make_mut(&mut next.hand).remove(0);
+    next.players[0].hand_size -= 1;
+    next.build_piles[0] = pile_at(2);
+    let legal = LegalSet::from_actions(&all_legal(&next));
+    assert_eq!(context.next_action(&next, &legal), Some(play_first));
+    assert_eq!(context.hits(), 1);
+
+    // Asking again from the same view means the prediction failed; the plan is dropped.
+    assert_eq!(context.next_action(&next, &legal), None);
+    assert!(!context.has_plan());
+}
+
+#[test]
+fn wild_hold_weight_charges_plans_that_waste_a_skip_bo() {
+    // A wild on any pile leaves the stock 12 eight steps away: no stock progress.
+    let state = base_state(
+        vec![Card::SkipBo, Card::Number(7)],
+        [vec![], vec![], vec![], vec![]],
+        Some(Card::Number(12)),
+        None,
+        [pile_at(3), pile_at(3), pile_at(3), pile_at(3)],
+    );
+    let wild_play = Action::Play {
+        source: CardSource::Hand(0),
+        build_pile: BuildPileId(0),
+    };
+    let discard = Action::Discard {
+        hand_index: 1,
+        discard_pile: DiscardPileId(0),
+    };
+    let score = |weights: PlanWeights, action: &Action| {
+        Heuristic19Bot::new()
+            .with_weights(weights)
+            .rank_actions(&state, &all_legal(&state))
+            .into_iter()
+            .find(|(a, _)| a == action)
+            .unwrap()
+            .1
+    };
+    let holding = PlanWeights {
+        wild_hold: 1000.0,
+        ..PlanWeights::default()
+    };
+    assert!(score(holding, &wild_play) < score(PlanWeights::default(), &wild_play));
+    assert_eq!(
+        score(holding, &discard),
+        score(PlanWeights::default(), &discard)
+    );
+}
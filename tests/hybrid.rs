@@ -0,0 +1,130 @@
+use skipbot::bots::HybridBot;
+use skipbot::bots::hybrid::{Confidence, entropy, softmax};
+use skipbot::{Action, Bot, Game, GameStateView, PlayerId, create_bot_from_spec};
+
+/// Picks the first legal action and ranks it `margin` above every other one.
+struct Leads {
+    margin: f32,
+}
+
+impl Bot for Leads {
+    fn select_action(&mut self, _state: &GameStateView, legal_actions: &[Action]) -> Action {
+        legal_actions[0].clone()
+    }
+
+    fn rank_actions(
+        &mut self,
+        _state: &GameStateView,
+        legal_actions: &[Action],
+    ) -> Vec<(Action, f32)> {
+        legal_actions
+            .iter()
+            .enumerate()
+            .map(|(i, action)| (action.clone(), if i == 0 { self.margin } else { 0.0 }))
+            .collect()
+    }
+}
+
+/// Always picks the last legal action.
+struct Last;
+
+impl Bot for Last {
+    fn select_action(&mut self, _state: &GameStateView, legal_actions: &[Action]) -> Action {
+        legal_actions.last().unwrap().clone()
+    }
+}
+
+fn opening() -> (GameStateView, Vec<Action>) {
+    let game = Game::builder(2).unwrap().with_seed(8).build().unwrap();
+    let player = game.current_player();
+    (
+        game.state_view(player).unwrap(),
+        game.legal_actions(player).unwrap(),
+    )
+}
+
+#[test]
+fn softmax_and_entropy_measure_how_sure_a_ranking_is() {
+    let flat = softmax(&[0.0, 0.0, 0.0, 0.0], 1.0);
+    assert_eq!(flat, vec![0.25; 4]);
+    assert!((entropy(&flat) - 4f64.ln()).abs() < 1e-12);
+
+    let sharp = softmax(&[10.0, 0.0], 1.0);
+    assert!(sharp[0] > 0.999);
+    assert!(entropy(&sharp) < 0.01);
+    // A higher temperature flattens the same scores.
+    assert!(softmax(&[10.0, 0.0], 10.0)[0] < sharp[0]);
+}
+
+#[test]
+fn confident_rankings_stand_and_uncertain_ones_defer() {
+    let (state, legal) = opening();
+    assert!(legal.len() > 2);
+    let first = legal[0].clone();
+    let last = legal.last().unwrap().clone();
+
+    let mut sure = HybridBot::new(Leads { margin: 10.0 }, Last, Confidence::default());
+    assert_eq!(sure.select_action(&state, &legal), first);
+    assert_eq!(sure.deferrals(), (1, 0));
+
+    let mut unsure = HybridBot::new(Leads { margin: 0.5 }, Last, Confidence::default());
+    assert_eq!(unsure.select_action(&state, &legal), last);
+    assert_eq!(unsure.deferrals(), (1, 1));
+
+    // The same margin passes once the scores count for more.
+    let mut sharpened =
+        HybridBot::new(Leads { margin: 0.5 }, Last, Confidence::default()).with_temperature(0.01);
+    assert_eq!(sharpened.select_action(&state, &legal), first);
+
+    // Entropy thresholds: a flat ranking has ln(n) nats.
+    let n = legal.len() as f64;
+    let mut lenient = HybridBot::new(
+        Leads { margin: 0.0 },
+        Last,
+        Confidence::MaxEntropy(n.ln() + 1e-9),
+    );
+    assert_eq!(lenient.select_action(&state, &legal), first);
+    let mut strict = HybridBot::new(Leads { margin: 0.0 }, Last, Confidence::MaxEntropy(1.0));
+    assert_eq!(strict.select_action(&state, &legal), last);
+
+    // Forced moves never defer.
+    let forced = [last.clone()];
+    assert_eq!(unsure.select_action(&state, &forced), last);
+    assert_eq!(unsure.deferrals(), (2, 1));
+}
+
+#[test]
+fn hybrid_specs_parse_thresholds_and_both_bots() {
+    assert_eq!(
+        "min_prob=0.7".parse::<Confidence>(),
+        Ok(Confidence::MinProb(0.7))
+    );
+    assert_eq!(
+        "max_entropy=0.5".parse::<Confidence>(),
+        Ok(Confidence::MaxEntropy(0.5))
+    );
+    assert!("min_prob=1.5".parse::<Confidence>().is_err());
+    assert!("max_prob=0.5".parse::<Confidence>().is_err());
+
+    let (state, legal) = opening();
+    for spec in [
+        "hybrid:random|heuristic19",
+        "hybrid:min_prob=0.9:temperature=0.5:noisy:0.1:heuristic19|heuristic",
+        "hybrid:max_entropy=1.2:beam:2:1|heuristic19:strength=5",
+    ] {
+        let mut bot =
+            create_bot_from_spec(spec, PlayerId(0), 3).unwrap_or_else(|e| panic!("{spec}: {e}"));
+        assert!(legal.contains(&bot.select_action(&state, &legal)), "{spec}");
+    }
+    for spec in [
+        "hybrid:heuristic19",
+        "hybrid:min_prob=2:heuristic19|heuristic",
+        "hybrid:temperature=0:heuristic19|heuristic",
+        "hybrid:min_prob=0.5|heuristic",
+    ] {
+        assert!(
+            create_bot_from_spec(spec, PlayerId(0), 3).is_err(),
+            "{spec}"
+        );
+    }
+}
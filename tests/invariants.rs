@@ -0,0 +1,51 @@
+//! Engine invariants under real play. Run with `cargo test --features invariants`.
+#![cfg(feature = "invariants")]
+
+use skipbot::runner::play_out;
+use skipbot::{Bot, Game, PlayerId, SeatRules, create_bot_from_spec};
+
+fn play(mut game: Game, specs: &[&str], seed: u64) {
+    let mut bots: Vec<Box<dyn Bot>> = specs
+        .iter()
+        .enumerate()
+        .map(|(seat, spec)| create_bot_from_spec(spec, PlayerId(seat), seed + seat as u64).unwrap())
+        .collect();
+    play_out(&mut game, &mut bots, 3000, |_| {}).unwrap();
+}
+
+#[test]
+fn invariants_hold_through_full_games() {
+    for seed in 0..20 {
+        for players in 2..=4 {
+            let game = Game::builder(players)
+                .unwrap()
+                .with_seed(seed)
+                .build()
+                .unwrap();
+            let specs = ["random", "heuristic13", "heuristic19", "random"];
+            play(game, &specs[..players], seed);
+        }
+    }
+}
+
+#[test]
+fn invariants_hold_under_house_rules() {
+    for seed in 0..10 {
+        let game = Game::builder(3)
+            .unwrap()
+            .with_seed(seed)
+            .with_stock_size(10)
+            .with_must_play_stock(true)
+            .with_seat_rules(
+                PlayerId(1),
+                SeatRules {
+                    extra_discard_pile: true,
+                    weak_draws: true,
+                    stock_wilds: Some(0),
+                },
+            )
+            .build()
+            .unwrap();
+        play(game, &["heuristic13", "random", "heuristic19"], seed);
+    }
+}
@@ -0,0 +1,99 @@
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use skipbot::action::CardSource;
+use skipbot::bots::IsMctsBot;
+use skipbot::interrupt::Interrupter;
+use skipbot::{Action, Bot, Card, Game, GameStateView, PlayerId, create_bot_from_spec};
+
+fn decision(game: &Game) -> (GameStateView, Vec<Action>) {
+    let player = game.current_player();
+    (
+        game.state_view(player).unwrap(),
+        game.legal_actions(player).unwrap(),
+    )
+}
+
+fn plays_stock(action: &Action) -> bool {
+    matches!(
+        action,
+        Action::Play {
+            source: CardSource::Stock,
+            ..
+        }
+    )
+}
+
+#[test]
+fn ranks_every_legal_action_by_visit_share() {
+    let game = Game::builder(2).unwrap().with_seed(8).build().unwrap();
+    let (state, legal) = decision(&game);
+    let mut bot = IsMctsBot::new(StdRng::seed_from_u64(1), 40);
+    let ranked = bot.rank_actions(&state, &legal);
+    assert_eq!(ranked.len(), legal.len());
+    assert!(legal.contains(&ranked[0].0));
+    let total: f32 = ranked.iter().map(|(_, share)| share).sum();
+    assert!((total - 1.0).abs() < 1e-4);
+    assert!(ranked.iter().all(|(_, share)| *share <= ranked[0].1));
+
+    // A cancelled search still plays a legal move from its first iteration.
+    let interrupter = Interrupter::new();
+    interrupter.cancel();
+    bot.interrupt_with(&interrupter);
+    assert!(legal.contains(&bot.select_action(&state, &legal)));
+}
+
+#[test]
+fn prefers_the_winning_stock_play_over_discarding() {
+    // Both players hold a one-card stock that fits an empty build pile: playing it wins
+    // on the spot, while a discard hands the opponent the win. Other plays still win once
+    // the rollout plays the stock.
+    let game = (0..200)
+        .map(|seed| {
+            Game::builder(2)
+                .unwrap()
+                .with_seed(seed)
+                .with_stock_size(1)
+                .build()
+                .unwrap()
+        })
+        .find(|game| {
+            let stocks = game.full_state_view(PlayerId(0)).unwrap().stocks;
+            stocks
+                .iter()
+                .all(|stock| matches!(stock[0], Card::Number(1) | Card::SkipBo))
+        })
+        .expect("some seed deals both players a playable stock card");
+    let (state, legal) = decision(&game);
+    assert!(legal.len() > 1);
+    let mut bot = IsMctsBot::new(StdRng::seed_from_u64(2), 10 * legal.len());
+    let ranked = bot.rank_actions(&state, &legal);
+    let share = |wanted: fn(&Action) -> bool| {
+        ranked
+            .iter()
+            .filter(|(action, _)| wanted(action))
+            .map(|(_, share)| *share)
+            .fold(0.0f32, f32::max)
+    };
+    let discards = share(|action| matches!(action, Action::Discard { .. }));
+    assert!(share(plays_stock) > discards);
+}
+
+#[test]
+fn specs_configure_the_search() {
+    let game = Game::builder(2).unwrap().with_seed(8).build().unwrap();
+    let (state, legal) = decision(&game);
+    let mut bot =
+        create_bot_from_spec("ismcts:10:exploration=1.2:eval=stock-diff", PlayerId(0), 3).unwrap();
+    assert!(legal.contains(&bot.select_action(&state, &legal)));
+    for spec in [
+        "ismcts:many",
+        "ismcts:exploration=-1",
+        "ismcts:depth=3",
+        "ismcts:eval=none",
+    ] {
+        assert!(
+            create_bot_from_spec(spec, PlayerId(0), 3).is_err(),
+            "{spec}"
+        );
+    }
+}
@@ -0,0 +1,119 @@
+use skipbot::league::{
+    Attribution, League, LeagueConfig, Matchmaking, Ranking, ScheduleBalance, Scheduler,
+    fit_plackett_luce, plackett_luce_errors, schedule_tables,
+};
+
+#[test]
+fn schedules_balance_games_seats_and_opponents() {
+    for (bots, table_size, tables) in [(8, 4, 80), (7, 3, 70), (9, 6, 60)] {
+        let schedule = schedule_tables(bots, table_size, tables, 5).unwrap();
+        assert_eq!(schedule.len(), tables);
+        for table in &schedule {
+            let mut seats = table.seats.clone();
+            seats.sort_unstable();
+            seats.dedup();
+            assert_eq!(seats.len(), table_size);
+        }
+        let balance = ScheduleBalance::of(&schedule, bots, table_size);
+        assert!(
+            balance.game_spread() <= 1,
+            "{bots}/{table_size}: {balance:?}"
+        );
+        assert!(
+            balance.seat_spread() <= 3,
+            "{bots}/{table_size}: {balance:?}"
+        );
+        assert!(
+            balance.pair_spread() <= 3,
+            "{bots}/{table_size}: {balance:?}"
+        );
+    }
+    assert_eq!(
+        schedule_tables(6, 4, 10, 1).unwrap(),
+        schedule_tables(6, 4, 10, 1).unwrap()
+    );
+    assert!(schedule_tables(3, 4, 10, 1).is_err());
+    assert!(schedule_tables(9, 7, 10, 1).is_err());
+}
+
+#[test]
+fn plackett_luce_recovers_the_strength_order() {
+    // Bot 0 wins most tables, bot 2 never does.
+    let mut rankings = Vec::new();
+    for game in 0..60 {
+        let winner = if game % 3 == 0 { 1 } else { 0 };
+        rankings.push(Ranking {
+            players: vec![0, 1, 2],
+            order: vec![winner],
+        });
+    }
+    let strengths = fit_plackett_luce(&rankings, 3, 200);
+    assert!(strengths[0] > strengths[1] && strengths[1] > strengths[2]);
+    assert!(strengths.iter().all(|s| s.is_finite() && *s > 0.0));
+    // Without results every bot matches the reference.
+    assert_eq!(fit_plackett_luce(&[], 2, 10), [1.0, 1.0]);
+
+    // More results, smaller errors; bot 2 never played.
+    let errors = plackett_luce_errors(&rankings[..6], &strengths);
+    let more = plackett_luce_errors(&rankings, &strengths);
+    assert!(more[0] < errors[0] && more[1] < errors[1]);
+    let unplayed = plackett_luce_errors(&[], &[1.0; 3]);
+    assert!(unplayed.iter().all(|e| (e - 2f64.sqrt()).abs() < 1e-12));
+}
+
+#[test]
+fn active_tables_gather_uncertain_bots_of_similar_rating() {
+    let mut scheduler = Scheduler::new(6, 3, 1).unwrap();
+    let ratings = [0.0, 10.0, 20.0, 30.0, 1500.0, -1500.0];
+    let errors = [30.0, 30.0, 200.0, 200.0, 100.0, 100.0];
+    let table = scheduler.next_active(&ratings, &errors);
+    let mut seats = table.seats.clone();
+    seats.sort_unstable();
+    // Bots 2 and 3 are the least certain; bot 4 would win the table whoever it sits with.
+    assert!(seats.contains(&2) && seats.contains(&3), "{seats:?}");
+    assert!(!seats.contains(&4), "{seats:?}");
+    assert_eq!(scheduler.balance().games.iter().sum::<usize>(), 3);
+}
+
+#[test]
+fn active_leagues_stop_at_the_target_error() {
+    let bots = ["random", "heuristic13", "heuristic19", "heuristic7"];
+    let mut config = LeagueConfig::new(bots.map(String::from).to_vec(), 2, 400, 9);
+    config.stock_size = Some(4);
+    config.matchmaking = Matchmaking::Active;
+    config.target_error = Some(250.0);
+    let report = League::new(config).unwrap().run().unwrap();
+    assert!(report.games.len() < 400, "{}", report.games.len());
+    assert!(report.ratings.iter().all(|r| r.error <= 250.0));
+    assert_eq!("active".parse(), Ok(Matchmaking::Active));
+    assert!("swiss".parse::<Matchmaking>().is_err());
+}
+
+#[test]
+fn leagues_rate_every_bot_of_the_pool() {
+    let bots = [
+        "random",
+        "heuristic13",
+        "heuristic19",
+        "heuristic7",
+        "heuristic",
+    ];
+    let mut config = LeagueConfig::new(bots.map(String::from).to_vec(), 3, 20, 4);
+    config.stock_size = Some(5);
+    config.attribution = Attribution::Finish;
+    let report = League::new(config.clone()).unwrap().run().unwrap();
+    assert_eq!(report.games.len(), 20);
+    assert_eq!(report.ratings.len(), bots.len());
+    assert_eq!(report.ratings.iter().map(|r| r.games).sum::<usize>(), 60);
+    for (game, table) in report.games.iter().zip(&report.schedule) {
+        let labels: Vec<&str> = table.seats.iter().map(|&bot| bots[bot]).collect();
+        assert_eq!(game.seats, labels);
+    }
+    assert_eq!(report.ratings.last().unwrap().label, "random");
+    let again = League::new(config).unwrap().run().unwrap();
+    assert_eq!(again.schedule, report.schedule);
+    assert_eq!(again.ratings, report.ratings);
+
+    let duplicate = LeagueConfig::new(vec!["random".into(), "random".into()], 2, 1, 0);
+    assert!(League::new(duplicate).is_err());
+}
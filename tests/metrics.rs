@@ -0,0 +1,156 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use skipbot::bots::hybrid::softmax;
+use skipbot::metrics::{
+    MetricsConfig, PolicyDecisions, PolicyMetrics, ScoredDecision, TemperatureFit, evaluate_policy,
+    metadata_path, record_decisions,
+};
+use skipbot::runner::play_out;
+use skipbot::value::ValueNet;
+use skipbot::{
+    Action, Bot, BuildPileId, CardSource, DiscardPileId, Game, GameRecord, PlayerId,
+    create_bot_from_spec,
+};
+
+fn recorded_games(specs: [&str; 2], games: u64) -> Vec<GameRecord> {
+    (0..games)
+        .map(|seed| {
+            let mut game = Game::builder(2)
+                .unwrap()
+                .with_seed(seed)
+                .with_stock_size(10)
+                .build()
+                .unwrap();
+            let mut bots: Vec<Box<dyn Bot>> = specs
+                .iter()
+                .enumerate()
+                .map(|(seat, spec)| create_bot_from_spec(spec, PlayerId(seat), seed).unwrap())
+                .collect();
+            play_out(&mut game, &mut bots, 2000, |_| {}).unwrap();
+            GameRecord::from_game(&game)
+                .unwrap()
+                .with_tag("Bots", &specs.join(" "))
+        })
+        .collect()
+}
+
+#[test]
+fn metrics_score_the_recorded_move() {
+    let stock = Action::Play {
+        source: CardSource::Stock,
+        build_pile: BuildPileId(0),
+    };
+    let hand = Action::Play {
+        source: CardSource::Hand(0),
+        build_pile: BuildPileId(1),
+    };
+    let discard = Action::Discard {
+        hand_index: 0,
+        discard_pile: DiscardPileId(0),
+    };
+    let legal = [stock.clone(), hand.clone(), discard.clone()];
+    let ranked = [
+        (hand.clone(), 1.0),
+        (stock.clone(), 1.0),
+        (discard.clone(), 1.0),
+    ];
+
+    let mut metrics = PolicyMetrics::new();
+    metrics.add(&ranked, &legal, &hand, 1.0);
+    metrics.add(&ranked, &legal, &discard, 1.0);
+    metrics.add(&ranked, &legal[2..], &discard, 1.0);
+    assert_eq!((metrics.decisions, metrics.forced), (2, 1));
+    assert_eq!((metrics.top1, metrics.top3), (1, 2));
+    assert_eq!(metrics.by_type["hand play"].top1, 1);
+    assert_eq!(metrics.by_type["discard"].top1, 0);
+    // Equal scores over three legal actions: every recorded move had probability 1/3.
+    assert!((metrics.perplexity() - 3.0).abs() < 1e-9);
+
+    // A recorded move the bot does not rank misses both tops but keeps a finite perplexity.
+    let mut unranked = PolicyMetrics::new();
+    unranked.add(&ranked[..1], &legal, &stock, 1.0);
+    assert_eq!((unranked.top1, unranked.top3), (0, 0));
+    assert!(unranked.perplexity().is_finite());
+}
+
+#[test]
+fn a_deterministic_bot_agrees_with_its_own_games() {
+    let records = recorded_games(["heuristic19", "random"], 4);
+    let config = MetricsConfig {
+        teacher: Some(String::from("heuristic19")),
+        ..MetricsConfig::default()
+    };
+    let own = evaluate_policy("heuristic19", &records, &config).unwrap();
+    assert!(own.decisions > 0);
+    assert_eq!(own.top1, own.decisions);
+    let types: usize = own
+        .by_type
+        .values()
+        .map(|agreement| agreement.decisions)
+        .sum();
+    assert_eq!(types, own.decisions);
+
+    let other = evaluate_policy("random", &records, &config).unwrap();
+    assert_eq!(other.decisions + other.forced, own.decisions + own.forced);
+    assert!(other.top1 < own.top1);
+    assert!(other.top3 >= other.top1);
+
+    let everyone = evaluate_policy("heuristic19", &records, &MetricsConfig::default()).unwrap();
+    assert!(everyone.decisions > own.decisions);
+    assert!(evaluate_policy("human", &records, &config).is_err());
+}
+
+#[test]
+fn fitting_recovers_the_temperature_moves_were_sampled_at() {
+    let mut rng = StdRng::seed_from_u64(7);
+    let mut decisions = PolicyDecisions::default();
+    for _ in 0..4000 {
+        let mut scores: Vec<f32> = (0..5).map(|_| rng.gen_range(0.0..10.0)).collect();
+        scores.sort_by(|a, b| b.total_cmp(a));
+        let probs = softmax(&scores, 2.5);
+        let mut draw: f64 = rng.r#gen();
+        let recorded = probs
+            .iter()
+            .position(|p| {
+                draw -= p;
+                draw < 0.0
+            })
+            .unwrap_or(probs.len() - 1);
+        decisions.decisions.push(ScoredDecision {
+            scores,
+            recorded: Some(recorded),
+            kind: "hand play",
+        });
+    }
+    let temperature = decisions.fit_temperature();
+    assert!((temperature - 2.5).abs() < 0.25, "{temperature}");
+    let fit = TemperatureFit::fit("test", &decisions, 0.1, 10);
+    assert!(fit.log_loss_after < fit.log_loss_before);
+    assert!(fit.ece_after < fit.ece_before);
+    assert!(fit.ece_after < 0.05);
+}
+
+#[test]
+fn fitted_temperatures_are_stored_with_the_checkpoint() {
+    let dir = std::env::temp_dir().join(format!("skipbot-calibration-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let checkpoint = dir.join("value.json");
+    ValueNet::new(4, 1).save(&checkpoint).unwrap();
+    let metadata = metadata_path(&checkpoint);
+    std::fs::write(&metadata, r#"{"epoch": 3}"#).unwrap();
+    assert_eq!(TemperatureFit::load(&checkpoint).unwrap(), None);
+
+    let spec = format!("beam:1:1:value={}", checkpoint.display());
+    let records = recorded_games(["heuristic13", "heuristic2"], 2);
+    let decisions = record_decisions(&spec, &records, &MetricsConfig::default()).unwrap();
+    let fit = TemperatureFit::fit(&spec, &decisions, 1.0, 10);
+    assert_eq!(fit.save(&checkpoint).unwrap(), metadata);
+    assert_eq!(TemperatureFit::load(&checkpoint).unwrap(), Some(fit));
+    let stored: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&metadata).unwrap()).unwrap();
+    assert_eq!(stored["epoch"], 3);
+
+    assert!(create_bot_from_spec(&format!("hybrid:{spec}|heuristic"), PlayerId(0), 0).is_ok());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,86 @@
+use skipbot::bots::NoisyBot;
+use skipbot::{Action, Bot, Game, GameStateView, PlayerId, create_bot_from_spec};
+
+/// Always picks the last legal action and ranks the list back to front.
+struct Backwards;
+
+impl Bot for Backwards {
+    fn select_action(&mut self, _state: &GameStateView, legal_actions: &[Action]) -> Action {
+        legal_actions.last().unwrap().clone()
+    }
+
+    fn rank_actions(
+        &mut self,
+        _state: &GameStateView,
+        legal_actions: &[Action],
+    ) -> Vec<(Action, f32)> {
+        legal_actions
+            .iter()
+            .rev()
+            .enumerate()
+            .map(|(rank, action)| (action.clone(), -(rank as f32)))
+            .collect()
+    }
+}
+
+/// Picks the first legal action and keeps the default ranking.
+struct First;
+
+impl Bot for First {
+    fn select_action(&mut self, _state: &GameStateView, legal_actions: &[Action]) -> Action {
+        legal_actions[0].clone()
+    }
+}
+
+fn opening() -> (GameStateView, Vec<Action>) {
+    let game = Game::builder(2).unwrap().with_seed(8).build().unwrap();
+    let player = game.current_player();
+    (
+        game.state_view(player).unwrap(),
+        game.legal_actions(player).unwrap(),
+    )
+}
+
+#[test]
+fn default_ranking_puts_the_selected_action_first() {
+    let (state, legal) = opening();
+    let ranked = First.rank_actions(&state, &legal);
+    assert_eq!(ranked.len(), legal.len());
+    assert_eq!(ranked[0], (legal[0].clone(), 1.0));
+    assert!(ranked[1..].iter().all(|(_, score)| *score == 0.0));
+}
+
+#[test]
+fn noise_swaps_in_second_and_third_choices() {
+    let (state, legal) = opening();
+    assert!(legal.len() > 3);
+    let n = legal.len();
+
+    let mut exact = NoisyBot::new(Backwards, 0.0, 1);
+    for _ in 0..20 {
+        assert_eq!(exact.select_action(&state, &legal), legal[n - 1]);
+    }
+
+    let mut noisy = NoisyBot::new(Backwards, 1.0, 1);
+    let mut seen = Vec::new();
+    for _ in 0..50 {
+        let action = noisy.select_action(&state, &legal);
+        assert!(action == legal[n - 2] || action == legal[n - 3]);
+        if !seen.contains(&action) {
+            seen.push(action);
+        }
+    }
+    assert_eq!(seen.len(), 2);
+
+    let mut second_only = NoisyBot::new(Backwards, 1.0, 1).with_max_rank(2);
+    assert_eq!(second_only.select_action(&state, &legal), legal[n - 2]);
+}
+
+#[test]
+fn noisy_spec_wraps_any_bot() {
+    let (state, legal) = opening();
+    let mut bot = create_bot_from_spec("noisy:0.2:heuristic19:strength=5", PlayerId(0), 3).unwrap();
+    assert!(legal.contains(&bot.select_action(&state, &legal)));
+    assert!(create_bot_from_spec("noisy:1.5:heuristic13", PlayerId(0), 3).is_err());
+    assert!(create_bot_from_spec("noisy:0.2", PlayerId(0), 3).is_err());
+}
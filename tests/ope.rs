@@ -0,0 +1,115 @@
+use std::io::Cursor;
+
+use skipbot::archive::{ArchiveReader, ArchiveWriter, Compression};
+use skipbot::bots::sampling_temperature;
+use skipbot::ope::{BehaviorLog, OpeConfig, estimate_policy};
+use skipbot::runner::{MatchConfig, MatchReport, MatchRunner};
+use skipbot::value::{ValueEnsemble, ValueNet};
+use skipbot::{GameRecord, create_bot_from_spec};
+
+const BEHAVIOR: &str = "softmax:2:heuristic19:label=sampled";
+
+fn tournament(games: usize) -> (MatchReport, Vec<GameRecord>, BehaviorLog) {
+    let mut config = MatchConfig::new(vec![BEHAVIOR.into(), "heuristic13".into()], games, 7);
+    config.stock_size = Some(5);
+    let runner = MatchRunner::new(config).unwrap();
+    let mut writer = ArchiveWriter::new(Vec::new(), Compression::None).unwrap();
+    let report = runner.run_archived(&mut writer).unwrap();
+    let mut archive = ArchiveReader::new(Cursor::new(writer.finish().unwrap())).unwrap();
+    let log = BehaviorLog::from_tournament(&report, &mut archive).unwrap();
+    let records = archive.records().collect::<Result<Vec<_>, _>>().unwrap();
+    (report, records, log)
+}
+
+fn config(temperature: f64) -> OpeConfig {
+    OpeConfig {
+        behavior: "sampled".into(),
+        temperature,
+        seed: 11,
+    }
+}
+
+#[test]
+fn sampling_temperature_is_read_from_softmax_specs_only() {
+    assert_eq!(sampling_temperature("softmax:0.5:heuristic19"), Some(0.5));
+    assert_eq!(sampling_temperature(BEHAVIOR), Some(2.0));
+    assert_eq!(sampling_temperature("softmax:0:heuristic19"), None);
+    assert_eq!(sampling_temperature("noisy:0.5:heuristic19"), None);
+    assert!(create_bot_from_spec("softmax:hot:heuristic19", Default::default(), 0).is_err());
+}
+
+#[test]
+fn every_move_of_the_sampling_seat_is_logged() {
+    let (report, records, log) = tournament(4);
+    let mut moves = 0;
+    for (record, game) in records.iter().zip(&report.games) {
+        let seat = game
+            .seats
+            .iter()
+            .position(|label| label == "sampled")
+            .unwrap();
+        record
+            .replay_with(|_, acting, _| moves += usize::from(acting.0 == seat))
+            .unwrap();
+    }
+    assert_eq!(log.len(), moves);
+    assert_eq!(log.bots().get("sampled"), Some(&moves));
+    for decision in &log.decisions {
+        let game = &report.games[decision.game];
+        assert_eq!(game.seats[decision.seat.0], "sampled");
+        assert!(decision.propensity > 0.0 && decision.propensity <= 1.0 + 1e-9);
+        let won = game.winner == Some(decision.seat);
+        assert_eq!(decision.outcome, if won { 1.0 } else { -1.0 });
+    }
+
+    let path = std::env::temp_dir().join(format!("ope-log-{}.jsonl", std::process::id()));
+    log.save(&path).unwrap();
+    let loaded = BehaviorLog::load(&path).unwrap();
+    assert_eq!(loaded.len(), log.len());
+    for (loaded, logged) in loaded.decisions.iter().zip(&log.decisions) {
+        assert_eq!(
+            (loaded.game, loaded.ply, loaded.action),
+            (logged.game, logged.ply, logged.action)
+        );
+        assert!((loaded.propensity - logged.propensity).abs() < 1e-12);
+    }
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn tournaments_without_sampling_bots_cannot_be_logged() {
+    let config = MatchConfig::new(vec!["heuristic13".into(), "random".into()], 1, 3);
+    let runner = MatchRunner::new(config).unwrap();
+    let mut writer = ArchiveWriter::new(Vec::new(), Compression::None).unwrap();
+    let report = runner.run_archived(&mut writer).unwrap();
+    let mut archive = ArchiveReader::new(Cursor::new(writer.finish().unwrap())).unwrap();
+    assert!(BehaviorLog::from_tournament(&report, &mut archive).is_err());
+}
+
+#[test]
+fn the_behavior_policy_estimates_its_own_win_rate() {
+    let (_, records, log) = tournament(6);
+    let estimate = estimate_policy("heuristic19", &log, &records, &config(2.0), None).unwrap();
+    assert_eq!(estimate.games, 6);
+    assert_eq!(estimate.decisions, log.len());
+    assert!((estimate.max_weight - 1.0).abs() < 1e-6);
+    assert!((estimate.effective_games - 6.0).abs() < 1e-6);
+    assert!((estimate.importance_sampling - estimate.behavior_win_rate).abs() < 1e-6);
+    assert!((estimate.weighted_importance_sampling - estimate.behavior_win_rate).abs() < 1e-6);
+    assert_eq!(estimate.doubly_robust, None);
+}
+
+#[test]
+fn other_candidates_reweigh_the_logged_games() {
+    let (_, records, log) = tournament(6);
+    let value = ValueEnsemble::new(vec![ValueNet::new(8, 5)]).unwrap();
+    let estimate =
+        estimate_policy("heuristic19", &log, &records, &config(0.5), Some(&value)).unwrap();
+    assert!(estimate.effective_games < 6.0);
+    assert!((0.0..=1.0).contains(&estimate.weighted_importance_sampling));
+    assert!(estimate.doubly_robust.is_some_and(f64::is_finite));
+
+    let mut other = config(2.0);
+    other.behavior = "heuristic13".into();
+    assert!(estimate_policy("heuristic19", &log, &records, &other, None).is_err());
+}
@@ -0,0 +1,156 @@
+use std::sync::Arc;
+
+use skipbot::bots::opening::OpeningKey;
+use skipbot::bots::planning::TurnPosition;
+use skipbot::bots::{Heuristic19Bot, OpeningBook};
+use skipbot::runner::{mix_seed, play_out};
+use skipbot::{
+    Action, Bot, Game, GameRecord, GameStateView, LegalSet, PlayerId, create_bot_from_spec,
+};
+
+const SEED: u64 = 11;
+
+fn warmed_book(games: usize) -> OpeningBook {
+    let mut book = OpeningBook::new();
+    let mut planner = Heuristic19Bot::new();
+    let added = book
+        .warm(
+            games,
+            2,
+            SEED,
+            |_| Box::new(Heuristic19Bot::new()),
+            |state, legal| planner.plan_turn(state, legal),
+        )
+        .unwrap();
+    assert_eq!(added, book.len());
+    book
+}
+
+fn opening(game_index: u64) -> Game {
+    Game::builder(2)
+        .unwrap()
+        .with_seed(mix_seed(SEED, game_index, 0))
+        .build()
+        .unwrap()
+}
+
+/// Where `plan` leaves the turn, with the hand sorted so plans that pick a different
+/// copy of the same card compare equal.
+fn end_of_plan(state: &GameStateView, plan: &[Action]) -> TurnPosition {
+    let mut position = TurnPosition::from_view(state).unwrap();
+    for action in plan {
+        assert!(position.apply(action), "{action:?} does not apply");
+    }
+    position.hand.sort_by_key(|card| card.value().unwrap_or(0));
+    position
+}
+
+#[test]
+fn book_plans_match_the_search() {
+    let book = warmed_book(40);
+    assert!(book.len() >= 40);
+    let mut planner = Heuristic19Bot::new();
+    for game_index in 0..40 {
+        let game = opening(game_index);
+        let player = game.current_player();
+        let state = game.state_view(player).unwrap();
+        let legal = game.legal_actions(player).unwrap();
+        let searched = planner.plan_turn(&state, &legal).unwrap();
+
+        let booked = book
+            .lookup(&state, &LegalSet::from_actions(&legal))
+            .unwrap();
+        assert_eq!(end_of_plan(&state, &booked), end_of_plan(&state, &searched));
+
+        // The book stores cards rather than slots, so a reordered hand finds the entry too.
+        let mut reordered = state.clone();
+        Arc::make_mut(&mut reordered.hand).reverse();
+        assert_eq!(
+            OpeningKey::from_view(&reordered),
+            OpeningKey::from_view(&state)
+        );
+    }
+}
+
+#[test]
+fn only_first_turns_have_keys() {
+    let mut game = opening(0);
+    let player = game.current_player();
+    assert!(OpeningKey::from_view(&game.state_view(player).unwrap()).is_some());
+    let mut bot = Heuristic19Bot::new();
+    let state = game.state_view(player).unwrap();
+    let legal = game.legal_actions(player).unwrap();
+    let action = bot.select_action(&state, &legal);
+    game.apply_action(player, action).unwrap();
+    if game.current_player() == player {
+        assert!(OpeningKey::from_view(&game.state_view(player).unwrap()).is_none());
+    }
+    let other = PlayerId(1 - player.0);
+    assert!(OpeningKey::from_view(&game.state_view(other).unwrap()).is_none());
+}
+
+#[test]
+fn saved_books_load_and_bots_play_the_same_games() {
+    let book = warmed_book(20);
+    let path = std::env::temp_dir().join(format!("skipbot-book-{}.json", std::process::id()));
+    book.save(&path).unwrap();
+    assert_eq!(OpeningBook::load(&path).unwrap(), book);
+    // Saving the loaded copy, with its own map order, writes the same file.
+    let saved = std::fs::read(&path).unwrap();
+    OpeningBook::load(&path).unwrap().save(&path).unwrap();
+    assert_eq!(std::fs::read(&path).unwrap(), saved);
+
+    let spec = format!("heuristic19:book={}", path.display());
+    for game_index in 0..3 {
+        let records: Vec<GameRecord> = [spec.as_str(), "heuristic19"]
+            .iter()
+            .map(|spec| {
+                let mut game = opening(game_index);
+                let mut bots: Vec<Box<dyn Bot>> = PlayerId::all(2)
+                    .map(|seat| create_bot_from_spec(spec, seat, game_index).unwrap())
+                    .collect();
+                play_out(&mut game, &mut bots, 2000, |_| {}).unwrap();
+                GameRecord::from_game(&game).unwrap()
+            })
+            .collect();
+        assert_eq!(records[0].moves, records[1].moves);
+    }
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn rewritten_books_reach_newly_built_bots_under_any_path_spelling() {
+    let dir = std::env::temp_dir().join(format!("skipbot-book-reload-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("book.json");
+    OpeningBook::new().save(&path).unwrap();
+
+    let game = opening(0);
+    let player = game.current_player();
+    let state = game.state_view(player).unwrap();
+    let legal = game.legal_actions(player).unwrap();
+    let choose = |path: &std::path::Path| {
+        let spec = format!("heuristic19:book={}", path.display());
+        create_bot_from_spec(&spec, player, 0)
+            .unwrap()
+            .select_action(&state, &legal)
+    };
+    let searched = choose(&path);
+    assert_eq!(
+        searched,
+        Heuristic19Bot::new().select_action(&state, &legal)
+    );
+
+    // Book a discard the search does not pick, then read the file through another path.
+    let discard = legal
+        .iter()
+        .find(|action| matches!(action, Action::Discard { .. }) && **action != searched)
+        .unwrap()
+        .clone();
+    let mut book = OpeningBook::new();
+    assert!(book.insert(&state, std::slice::from_ref(&discard)));
+    book.save(&path).unwrap();
+    assert_eq!(choose(&dir.join(".").join("book.json")), discard);
+    assert_eq!(choose(&path), discard);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,66 @@
+use skipbot::bots::OracleBot;
+use skipbot::runner::play_out;
+use skipbot::{Action, Bot, DiscardPileId, Game, PlayerId, create_bot_from_spec};
+
+#[test]
+fn full_state_view_shows_hidden_cards_in_draw_order() {
+    let mut game = Game::builder(2).unwrap().with_seed(4).build().unwrap();
+    let full = game.full_state_view(PlayerId(0)).unwrap();
+    assert_eq!(full.view, game.state_view(PlayerId(0)).unwrap());
+    assert_eq!(full.draw_pile.len(), full.view.draw_pile_count);
+    for player in &full.view.players {
+        let stock = &full.stocks[player.id.0];
+        assert_eq!(stock.len(), player.stock_count);
+        assert_eq!(stock.first().copied(), player.stock_top);
+        assert_eq!(full.hands[player.id.0].len(), player.hand_size);
+    }
+    let cards: usize = full
+        .hands
+        .iter()
+        .chain(&full.stocks)
+        .map(Vec::len)
+        .sum::<usize>()
+        + full.draw_pile.len()
+        + full.recycle_pile.len();
+    assert_eq!(cards, 162);
+
+    // Ending the turn deals the next player the front of the draw pile.
+    let hand = full.hands[1].clone();
+    game.apply_action(
+        PlayerId(0),
+        Action::Discard {
+            hand_index: 0,
+            discard_pile: DiscardPileId(0),
+        },
+    )
+    .unwrap();
+    let mut expected = hand;
+    expected.extend(&full.draw_pile[..5 - expected.len()]);
+    assert_eq!(*game.state_view(PlayerId(1)).unwrap().hand, expected);
+}
+
+#[test]
+fn oracle_plays_legal_moves_with_and_without_the_full_state() {
+    let game = Game::builder(2).unwrap().with_seed(9).build().unwrap();
+    let state = game.state_view(PlayerId(0)).unwrap();
+    let legal = game.legal_actions(PlayerId(0)).unwrap();
+
+    let mut blind = OracleBot::with_node_limit(2_000);
+    assert!(blind.wants_full_state());
+    assert!(legal.contains(&blind.select_action(&state, &legal)));
+
+    let mut oracle = OracleBot::with_node_limit(2_000);
+    oracle.observe_full_state(&game.full_state_view(PlayerId(0)).unwrap());
+    assert!(legal.contains(&oracle.select_action(&state, &legal)));
+}
+
+#[test]
+fn drivers_feed_the_oracle() {
+    let mut game = Game::builder(2).unwrap().with_seed(12).build().unwrap();
+    let mut bots = vec![
+        create_bot_from_spec("oracle:2000", PlayerId(0), 1).unwrap(),
+        create_bot_from_spec("heuristic13", PlayerId(1), 2).unwrap(),
+    ];
+    let actions = play_out(&mut game, &mut bots, 150, |_| {}).unwrap();
+    assert!(actions > 0);
+}
@@ -0,0 +1,248 @@
+use skipbot::action::{Action, BuildPileId, CardSource, DiscardPileId};
+use skipbot::bots::planning::{
+    HandEmptyOutcome, HandEmptySolver, RefillLookahead, SourceIndex, StockPlan, TurnPosition,
+    required_values_for_pile,
+};
+use skipbot::card::Card;
+use skipbot::state::{BuildPileView, GameStateView};
+
+mod common;
+use common::{pile_at, player, view};
+
+fn state(
+    hand: Vec<Card>,
+    discard_piles: [Vec<Card>; 4],
+    stock_top: Option<Card>,
+    build_piles: [BuildPileView; 4],
+) -> GameStateView {
+    let mut me = player(0, stock_top, 30);
+    me.discard_piles = discard_piles.to_vec().into();
+    view(hand, build_piles, vec![me, player(1, None, 30)])
+}
+
+fn play(source: CardSource, pile: usize) -> Action {
+    Action::Play {
+        source,
+        build_pile: BuildPileId(pile),
+    }
+}
+
+#[test]
+fn required_values_wrap_after_twelve() {
+    assert_eq!(required_values_for_pile(3, Card::Number(6)), [3, 4, 5]);
+    assert_eq!(
+        required_values_for_pile(6, Card::Number(6)),
+        Vec::<u8>::new()
+    );
+    assert_eq!(required_values_for_pile(11, Card::Number(2)), [11, 12, 1]);
+    assert_eq!(required_values_for_pile(7, Card::SkipBo), Vec::<u8>::new());
+}
+
+#[test]
+fn source_index_lists_hand_before_discards_and_wilds_the_other_way() {
+    let index = SourceIndex::new(
+        &[Card::Number(4), Card::SkipBo, Card::Number(4)],
+        &[
+            vec![Card::Number(9), Card::Number(4)],
+            vec![Card::SkipBo],
+            vec![],
+        ],
+    );
+    assert_eq!(
+        index.numbers(4),
+        [
+            CardSource::Hand(0),
+            CardSource::Hand(2),
+            CardSource::Discard(DiscardPileId(0)),
+        ]
+    );
+    assert!(index.numbers(9).is_empty(), "only discard tops count");
+    assert_eq!(
+        index.skip_bos().collect::<Vec<_>>(),
+        [CardSource::Discard(DiscardPileId(1)), CardSource::Hand(1)]
+    );
+}
+
+#[test]
+fn stock_plan_prefers_discards_then_hand_then_wilds() {
+    // The pile needs 3, 4 and 5 before the stock 6: the 3 is on a discard pile and in the
+    // hand, the 4 only in the hand and the 5 nowhere.
+    let view = state(
+        vec![Card::Number(3), Card::Number(4), Card::SkipBo],
+        [vec![Card::Number(3)], vec![], vec![], vec![]],
+        Some(Card::Number(6)),
+        [pile_at(3), pile_at(1), pile_at(1), pile_at(1)],
+    );
+    let plan = StockPlan::best(&view).unwrap();
+    assert_eq!(plan.build_pile, BuildPileId(0));
+    assert_eq!(plan.cost, 3);
+    assert_eq!(plan.wilds, 1);
+    assert_eq!(
+        plan.actions,
+        [
+            play(CardSource::Discard(DiscardPileId(0)), 0),
+            play(CardSource::Hand(1), 0),
+            play(CardSource::Hand(2), 0),
+            play(CardSource::Stock, 0),
+        ]
+    );
+    assert_eq!(plan.first_action(), &plan.actions[0]);
+}
+
+#[test]
+fn stock_plan_takes_the_cheapest_pile_and_the_first_on_ties() {
+    let view = state(
+        vec![Card::Number(5), Card::Number(6)],
+        [vec![], vec![], vec![], vec![]],
+        Some(Card::Number(7)),
+        [pile_at(2), pile_at(6), pile_at(5), pile_at(6)],
+    );
+    let plan = StockPlan::best(&view).unwrap();
+    assert_eq!(plan.build_pile, BuildPileId(1));
+    assert_eq!(plan.cost, 1);
+    assert_eq!(plan.wilds, 0);
+}
+
+#[test]
+fn stock_plan_fails_without_sources_or_when_a_play_is_refused() {
+    let view = state(
+        vec![Card::Number(3)],
+        [vec![], vec![], vec![], vec![]],
+        Some(Card::Number(5)),
+        [pile_at(3), pile_at(1), pile_at(1), pile_at(1)],
+    );
+    assert_eq!(StockPlan::best(&view), None);
+
+    let view = state(
+        vec![Card::Number(3), Card::Number(4)],
+        [vec![], vec![], vec![], vec![]],
+        Some(Card::Number(5)),
+        [pile_at(3), pile_at(1), pile_at(1), pile_at(1)],
+    );
+    assert!(StockPlan::best(&view).is_some());
+    let no_hand = |action: &Action| {
+        !matches!(
+            action,
+            Action::Play {
+                source: CardSource::Hand(_),
+                ..
+            }
+        )
+    };
+    assert_eq!(StockPlan::best_with(&view, no_hand), None);
+
+    let no_stock = state(
+        vec![Card::Number(3)],
+        [vec![], vec![], vec![], vec![]],
+        None,
+        [pile_at(3), pile_at(1), pile_at(1), pile_at(1)],
+    );
+    assert_eq!(StockPlan::best(&no_stock), None);
+}
+
+#[test]
+fn hand_empty_solver_digs_into_discard_piles_only_when_allowed() {
+    // The hand 4, 6 needs the 3 and the 5 buried on the first discard pile.
+    let view = state(
+        vec![Card::Number(4), Card::Number(6)],
+        [
+            vec![Card::Number(5), Card::Number(3)],
+            vec![],
+            vec![],
+            vec![],
+        ],
+        Some(Card::Number(10)),
+        [pile_at(3), pile_at(9), pile_at(9), pile_at(9)],
+    );
+    let discard = CardSource::Discard(DiscardPileId(0));
+    assert_eq!(
+        HandEmptySolver::default().solve(&view),
+        HandEmptyOutcome::Plan(vec![
+            play(discard, 0),
+            play(CardSource::Hand(0), 0),
+            play(discard, 0),
+            // The 6 has moved to the front of the hand.
+            play(CardSource::Hand(0), 0),
+        ])
+    );
+    assert_eq!(
+        HandEmptySolver::default()
+            .with_deep_discards(false)
+            .solve(&view),
+        HandEmptyOutcome::Infeasible
+    );
+}
+
+#[test]
+fn hand_empty_solver_prefers_plans_with_fewer_discard_plays() {
+    // The 5 can follow the discarded 4 on the first pile or go straight onto the second.
+    let view = state(
+        vec![Card::Number(5)],
+        [vec![Card::Number(4)], vec![], vec![], vec![]],
+        Some(Card::Number(10)),
+        [pile_at(4), pile_at(5), pile_at(9), pile_at(9)],
+    );
+    let solver = HandEmptySolver::default();
+    assert_eq!(
+        solver.first_action(&view),
+        Some(play(CardSource::Hand(0), 1))
+    );
+    assert!(solver.is_feasible(&view));
+}
+
+#[test]
+fn hand_empty_solver_proves_infeasibility_and_reports_exhausted_budgets() {
+    let view = state(
+        vec![Card::Number(4), Card::Number(7)],
+        [vec![Card::Number(5)], vec![Card::Number(3)], vec![], vec![]],
+        Some(Card::Number(10)),
+        [pile_at(3), pile_at(9), pile_at(9), pile_at(9)],
+    );
+    assert_eq!(
+        HandEmptySolver::default().solve(&view),
+        HandEmptyOutcome::Infeasible
+    );
+    assert_eq!(
+        HandEmptySolver::new(1).solve(&view),
+        HandEmptyOutcome::Unknown
+    );
+    assert_eq!(HandEmptySolver::new(1).first_action(&view), None);
+}
+
+#[test]
+fn refill_lookahead_prefers_discards_that_keep_a_chain_open() {
+    // Every pile needs a 3, which tops the first discard pile; the 4 in hand follows it.
+    // Discarding the 9 onto the 3 buries it.
+    let view = state(
+        vec![Card::Number(4), Card::Number(9)],
+        [vec![Card::Number(3)], vec![], vec![], vec![]],
+        Some(Card::Number(11)),
+        [pile_at(3), pile_at(3), pile_at(3), pile_at(3)],
+    );
+    let discard = |discard_pile| Action::Discard {
+        hand_index: 1,
+        discard_pile: DiscardPileId(discard_pile),
+    };
+    let lookahead = RefillLookahead::default();
+    let legal = [play(CardSource::Hand(0), 0), discard(0), discard(1)];
+    let scores = lookahead.score_discards(&view, &legal);
+    assert_eq!(scores.len(), 2);
+    let (buried, open) = (scores[0].1, scores[1].1);
+    assert!(
+        open.playable >= buried.playable + 1.0,
+        "{open:?} vs {buried:?}"
+    );
+    assert_eq!(lookahead.score_discards(&view, &legal[1..2])[0].1, buried);
+
+    // A stock card that fits is always reached.
+    let view = state(
+        vec![Card::Number(9)],
+        [vec![], vec![], vec![], vec![]],
+        Some(Card::Number(3)),
+        [pile_at(3), pile_at(3), pile_at(3), pile_at(3)],
+    );
+    let position = TurnPosition::from_view(&view).unwrap();
+    let outlook = lookahead.outlook(&view, &position);
+    assert_eq!(outlook.stock_chance, 1.0);
+    assert!(outlook.playable >= 1.0);
+}
@@ -0,0 +1,54 @@
+use std::path::Path;
+
+use skipbot::analysis::{Criterion, CriticalPosition, load_positions};
+use skipbot::{Action, create_bot_from_spec};
+
+/// Bots expected to find an acceptable move in every curated position.
+const STRONG_BOTS: [&str; 2] = ["heuristic13", "heuristic19"];
+
+fn curated() -> Vec<CriticalPosition> {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("scenarios/critical.jsonl");
+    load_positions(&path).expect("curated positions load")
+}
+
+#[test]
+fn curated_positions_are_consistent() {
+    let positions = curated();
+    assert!(!positions.is_empty());
+    for criterion in Criterion::ALL {
+        assert!(
+            positions
+                .iter()
+                .any(|p| p.tags.iter().any(|t| t == criterion.tag())),
+            "no {criterion} positions curated"
+        );
+    }
+    for position in &positions {
+        let legal = position.state.legal_actions();
+        assert!(
+            position.acceptable.iter().all(|a| legal.contains(a)),
+            "{}: acceptable move is not legal",
+            position.name
+        );
+        assert!(
+            position.acceptable.len() < legal.len(),
+            "{}: trivial",
+            position.name
+        );
+    }
+}
+
+#[test]
+fn strong_bots_find_acceptable_moves() {
+    let positions = curated();
+    let mut failures: Vec<(String, String, Action)> = Vec::new();
+    for spec in STRONG_BOTS {
+        for position in &positions {
+            let mut bot = create_bot_from_spec(spec, position.state.self_player, 0).unwrap();
+            if let Err(action) = position.check(bot.as_mut()) {
+                failures.push((spec.to_string(), position.name.clone(), action));
+            }
+        }
+    }
+    assert!(failures.is_empty(), "missed positions: {failures:?}");
+}
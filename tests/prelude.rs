@@ -0,0 +1,45 @@
+use skipbot::prelude::*;
+
+/// A bot written the way a downstream crate would: against the prelude alone. Plays from
+/// the stock whenever it can, otherwise the first legal action.
+struct StockFirst;
+
+impl Bot for StockFirst {
+    fn select_action(&mut self, _state: &GameStateView, legal_actions: &[Action]) -> Action {
+        legal_actions
+            .iter()
+            .find(|action| {
+                matches!(
+                    action,
+                    Action::Play {
+                        source: CardSource::Stock,
+                        ..
+                    }
+                )
+            })
+            .unwrap_or(&legal_actions[0])
+            .clone()
+    }
+}
+
+#[test]
+fn prelude_bot_plays_a_full_game() {
+    let mut game = Game::builder(2)
+        .unwrap()
+        .with_seed(3)
+        .with_stock_size(5)
+        .build()
+        .unwrap();
+    let mut bots: Vec<Box<dyn Bot>> = vec![
+        Box::new(StockFirst),
+        create_bot_from_spec("heuristic", PlayerId(1), 3).unwrap(),
+    ];
+    while !game.is_finished() {
+        let player = game.current_player();
+        let state = game.state_view(player).unwrap();
+        let legal = game.legal_actions(player).unwrap();
+        let action = bots[player.0].select_action(&state, &legal);
+        game.apply_action(player, action).unwrap();
+    }
+    assert!(!matches!(game.status(), GameStatus::Ongoing));
+}
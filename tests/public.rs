@@ -0,0 +1,106 @@
+use std::io::Cursor;
+
+use skipbot::archive::{ArchiveReader, ArchiveWriter, Compression};
+use skipbot::compat::FeatureShim;
+use skipbot::public::{HUMAN_LABEL, anonymize, load_public_dataset, public_legal, save_public};
+use skipbot::runner::{MatchConfig, MatchRunner};
+use skipbot::{Action, Game, GameRecord, PlayerId, StateEncoder};
+
+fn records(games: usize) -> Vec<GameRecord> {
+    let mut config = MatchConfig::new(vec!["heuristic19".into(), "heuristic13".into()], games, 3);
+    config.stock_size = Some(5);
+    let runner = MatchRunner::new(config).unwrap();
+    let mut writer = ArchiveWriter::new(Vec::new(), Compression::None).unwrap();
+    runner.run_archived(&mut writer).unwrap();
+    let mut archive = ArchiveReader::new(Cursor::new(writer.finish().unwrap())).unwrap();
+    archive.records().collect::<Result<_, _>>().unwrap()
+}
+
+#[test]
+fn public_encoding_hides_only_the_hand() {
+    let game = Game::builder(3).unwrap().with_seed(7).build().unwrap();
+    let state = game.state_view(PlayerId(0)).unwrap();
+    let full = StateEncoder::encode(&state);
+    let public = StateEncoder::encode_public(&state);
+    for (index, name) in StateEncoder::feature_names().iter().enumerate() {
+        if name.starts_with("hand.") {
+            assert_eq!(public[index], 0.0, "{name}");
+        } else {
+            assert_eq!(public[index], full[index], "{name}");
+        }
+    }
+    assert!(full.iter().zip(&public).any(|(a, b)| a != b));
+}
+
+#[test]
+fn spectator_legal_moves_cover_the_real_ones() {
+    let game = Game::builder(2).unwrap().with_seed(11).build().unwrap();
+    let seat = game.current_player();
+    let state = game.state_view(seat).unwrap();
+    let legal = game.legal_actions(seat).unwrap();
+    let public = public_legal(&state, &legal);
+    assert!(public.windows(2).all(|pair| pair[0] < pair[1]));
+    for action in &legal {
+        assert!(public.contains(&action.index().unwrap()), "{action:?}");
+    }
+    let end_turn = Action::EndTurn.index().unwrap();
+    assert_eq!(public.contains(&end_turn), legal.contains(&Action::EndTurn));
+}
+
+#[test]
+fn anonymized_games_keep_moves_and_outcomes_only() {
+    let records = records(3);
+    let samples = anonymize(&records).unwrap();
+    let moves: usize = records.iter().map(|record| record.moves.len()).sum();
+    assert_eq!(samples.len(), moves);
+    for sample in &samples {
+        let record = &records[sample.game];
+        assert_eq!(record.moves[sample.ply].index(), Some(sample.action));
+        assert!(sample.legal.contains(&sample.action));
+        assert!(sample.player == "heuristic19" || sample.player == "heuristic13");
+        let winner = record.replay().unwrap().winner();
+        let expected = match winner {
+            Some(seat) if seat == sample.seat => 1.0,
+            Some(_) => -1.0,
+            None => 0.0,
+        };
+        assert_eq!(sample.outcome, expected);
+    }
+
+    let path = std::env::temp_dir().join("skipbot-public-test.jsonl");
+    save_public(&samples, &path).unwrap();
+    let text = std::fs::read_to_string(&path).unwrap();
+    assert!(
+        !text.contains("seed"),
+        "exports must not carry the deal's seed"
+    );
+    let dataset = load_public_dataset(&path, FeatureShim::Strict).unwrap();
+    assert_eq!(dataset.samples.len(), samples.len());
+    assert_eq!(dataset.samples[0].features, samples[0].features);
+    assert_eq!(dataset.samples[0].bot, samples[0].player);
+}
+
+#[test]
+fn human_seats_lose_their_names() {
+    let record = records(1)
+        .remove(0)
+        .with_tag("Bots", "human:Alice heuristic13");
+    let samples = anonymize(&[record]).unwrap();
+    assert!(samples.iter().any(|sample| sample.player == HUMAN_LABEL));
+    assert!(
+        samples
+            .iter()
+            .all(|sample| !sample.player.contains("Alice"))
+    );
+
+    let untagged = GameRecord {
+        tags: Vec::new(),
+        ..records(1).remove(0)
+    };
+    let samples = anonymize(&[untagged]).unwrap();
+    assert!(
+        samples
+            .iter()
+            .all(|sample| sample.player.starts_with("seat"))
+    );
+}
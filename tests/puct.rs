@@ -0,0 +1,106 @@
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use skipbot::action::CardSource;
+use skipbot::bots::PuctBot;
+use skipbot::interrupt::Interrupter;
+use skipbot::value::{ValueEnsemble, ValueNet};
+use skipbot::{
+    Action, Bot, Card, Game, GameStateView, HeuristicBot, PlayerId, RandomBot, create_bot_from_spec,
+};
+
+fn decision(game: &Game) -> (GameStateView, Vec<Action>) {
+    let player = game.current_player();
+    (
+        game.state_view(player).unwrap(),
+        game.legal_actions(player).unwrap(),
+    )
+}
+
+#[test]
+fn ranks_every_legal_action_by_visit_share() {
+    let game = Game::builder(2).unwrap().with_seed(8).build().unwrap();
+    let (state, legal) = decision(&game);
+    let mut bot = PuctBot::new(StdRng::seed_from_u64(1), HeuristicBot, 40);
+    let ranked = bot.rank_actions(&state, &legal);
+    assert_eq!(ranked.len(), legal.len());
+    assert!(legal.contains(&ranked[0].0));
+    let total: f32 = ranked.iter().map(|(_, share)| share).sum();
+    assert!((total - 1.0).abs() < 1e-4);
+    assert!(ranked.iter().all(|(_, share)| *share <= ranked[0].1));
+
+    // A cancelled search still plays a legal move from its first simulation.
+    let interrupter = Interrupter::new();
+    interrupter.cancel();
+    bot.interrupt_with(&interrupter);
+    assert!(legal.contains(&bot.select_action(&state, &legal)));
+}
+
+#[test]
+fn finds_the_winning_stock_play_whatever_the_prior() {
+    // Both players hold a one-card stock that fits an empty build pile: playing it wins
+    // on the spot. A random prior ranks it anywhere, yet the win wins the visits.
+    let game = (0..200)
+        .map(|seed| {
+            Game::builder(2)
+                .unwrap()
+                .with_seed(seed)
+                .with_stock_size(1)
+                .build()
+                .unwrap()
+        })
+        .find(|game| {
+            let stocks = game.full_state_view(PlayerId(0)).unwrap().stocks;
+            stocks
+                .iter()
+                .all(|stock| matches!(stock[0], Card::Number(1) | Card::SkipBo))
+        })
+        .expect("some seed deals both players a playable stock card");
+    let (state, legal) = decision(&game);
+    let prior = RandomBot::new(StdRng::seed_from_u64(3));
+    let mut bot = PuctBot::new(StdRng::seed_from_u64(2), prior, 5 * legal.len())
+        .with_temperature(1.0)
+        .with_value(ValueEnsemble::new(vec![ValueNet::new(8, 0)]).unwrap());
+    let action = bot.select_action(&state, &legal);
+    assert!(matches!(
+        action,
+        Action::Play {
+            source: CardSource::Stock,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn specs_configure_priors_and_value_nets() {
+    let dir = std::env::temp_dir().join("skipbot-puct-test");
+    std::fs::create_dir_all(&dir).unwrap();
+    let model = dir.join("model.bin");
+    ValueNet::new(8, 5).save(&model).unwrap();
+    let model = model.display();
+
+    let game = Game::builder(2).unwrap().with_seed(8).build().unwrap();
+    let (state, legal) = decision(&game);
+    for spec in [
+        format!("puct:{model}:sims=10"),
+        format!("puct:{model}:sims=10:prior=heuristic13:temperature=1:cpuct=2"),
+        "puct:sims=10:eval=stock-diff".to_string(),
+    ] {
+        let mut bot = create_bot_from_spec(&spec, PlayerId(0), 3).unwrap();
+        assert!(legal.contains(&bot.select_action(&state, &legal)), "{spec}");
+    }
+    for spec in [
+        format!("puct:{model}:eval=tempo"),
+        format!("puct:{model}:{model}"),
+        "puct:sims=lots".to_string(),
+        "puct:cpuct=-1".to_string(),
+        "puct:temperature=0".to_string(),
+        "puct:prior=nobody".to_string(),
+        "puct:depth=3".to_string(),
+        format!("puct:{}", dir.join("missing.bin").display()),
+    ] {
+        assert!(
+            create_bot_from_spec(&spec, PlayerId(0), 3).is_err(),
+            "{spec}"
+        );
+    }
+}
@@ -0,0 +1,58 @@
+use skipbot::{Action, Bot, Game, PlayerId, create_bot_from_spec};
+
+/// Plays `decisions` moves with one ranking and one selecting copy of `spec` per seat,
+/// checking that each ranking covers the legal actions once and leads with the selection.
+fn ranking_matches_selection(spec: &str, decisions: usize) {
+    let mut game = Game::builder(2).unwrap().with_seed(21).build().unwrap();
+    let mut ranking: Vec<Box<dyn Bot>> = (0..2)
+        .map(|seat| create_bot_from_spec(spec, PlayerId(seat), 5).unwrap())
+        .collect();
+    let mut selecting: Vec<Box<dyn Bot>> = (0..2)
+        .map(|seat| create_bot_from_spec(spec, PlayerId(seat), 5).unwrap())
+        .collect();
+    let mut turn = None;
+    for _ in 0..decisions {
+        if game.is_finished() {
+            break;
+        }
+        let player = game.current_player();
+        let state = game.state_view(player).unwrap();
+        let legal = game.legal_actions(player).unwrap();
+        if turn != Some(game.turn_number()) {
+            turn = Some(game.turn_number());
+            ranking[player.0].on_turn_start(&state);
+            selecting[player.0].on_turn_start(&state);
+        }
+
+        let ranked = ranking[player.0].rank_actions(&state, &legal);
+        let chosen = selecting[player.0].select_action(&state, &legal);
+        assert_eq!(ranked[0].0, chosen, "{spec} ranked another action first");
+        assert_eq!(ranked.len(), legal.len());
+        assert!(legal.iter().all(|a| ranked.iter().any(|(r, _)| r == a)));
+        game.apply_action(player, chosen).unwrap();
+    }
+}
+
+#[test]
+fn heuristic_rankings_lead_with_the_selected_action() {
+    ranking_matches_selection("heuristic", 200);
+    ranking_matches_selection("heuristic19", 200);
+    ranking_matches_selection("heuristic19:strength=4", 200);
+}
+
+#[test]
+fn beam_ranking_leads_with_the_selected_action() {
+    ranking_matches_selection("beam:4:2", 60);
+}
+
+#[test]
+fn heuristic_ranking_orders_by_score() {
+    let game = Game::builder(2).unwrap().with_seed(3).build().unwrap();
+    let player = game.current_player();
+    let state = game.state_view(player).unwrap();
+    let legal: Vec<Action> = game.legal_actions(player).unwrap();
+    let mut bot = create_bot_from_spec("heuristic", player, 0).unwrap();
+    let ranked = bot.rank_actions(&state, &legal);
+    assert!(ranked[1..].windows(2).all(|pair| pair[0].1 >= pair[1].1));
+    assert!(ranked[1..].iter().all(|(_, score)| *score <= ranked[0].1));
+}
@@ -0,0 +1,120 @@
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use skipbot::bots::PlanWeights;
+use skipbot::reload::{ReloadTrigger, WeightReloader};
+use skipbot::runner::{MatchConfig, MatchRunner, RunState};
+
+/// Write `weights` to `name` in the temp directory, dated `age` seconds in the past.
+fn write_weights(name: &str, weights: &PlanWeights, age: u64) -> PathBuf {
+    let path = std::env::temp_dir().join(name);
+    fs::write(&path, serde_json::to_string(weights).unwrap()).unwrap();
+    File::options()
+        .write(true)
+        .open(&path)
+        .unwrap()
+        .set_modified(SystemTime::now() - Duration::from_secs(age))
+        .unwrap();
+    path
+}
+
+fn tuned(stock_card: f64) -> PlanWeights {
+    PlanWeights {
+        stock_card,
+        ..PlanWeights::default()
+    }
+}
+
+fn spec(path: &Path) -> String {
+    format!("heuristic19:checkpoint={}", path.display())
+}
+
+fn inlined(spec: &str) -> f64 {
+    let list = spec.split_once("weights=").unwrap().1;
+    list.split(',').next().unwrap().parse().unwrap()
+}
+
+#[test]
+fn modified_files_are_read_again() {
+    let path = write_weights("skipbot-reload-modified.json", &tuned(1.0), 60);
+    let specs = vec![spec(&path), "heuristic13".to_string()];
+    let reloader = WeightReloader::new(ReloadTrigger::Modified);
+
+    let (resolved, reloaded) = reloader.resolve(&specs).unwrap();
+    assert!(reloaded.is_empty(), "the first read is not a reload");
+    assert_eq!(inlined(&resolved[0]), 1.0);
+    assert_eq!(resolved[1], "heuristic13");
+
+    write_weights("skipbot-reload-modified.json", &tuned(2.0), 30);
+    let (resolved, reloaded) = reloader.resolve(&specs).unwrap();
+    assert_eq!(reloaded, vec![path.display().to_string()]);
+    assert_eq!(inlined(&resolved[0]), 2.0);
+    let (_, reloaded) = reloader.resolve(&specs).unwrap();
+    assert!(reloaded.is_empty());
+}
+
+#[test]
+fn signalled_reloads_wait_for_a_request() {
+    let path = write_weights("skipbot-reload-signal.json", &tuned(1.0), 60);
+    let specs = vec![spec(&path)];
+    let reloader = WeightReloader::new(ReloadTrigger::Signal);
+    reloader.resolve(&specs).unwrap();
+
+    write_weights("skipbot-reload-signal.json", &tuned(3.0), 30);
+    let (resolved, _) = reloader.resolve(&specs).unwrap();
+    assert_eq!(inlined(&resolved[0]), 1.0);
+    reloader.request();
+    let (resolved, reloaded) = reloader.resolve(&specs).unwrap();
+    assert_eq!(reloaded.len(), 1);
+    assert_eq!(inlined(&resolved[0]), 3.0);
+}
+
+#[test]
+fn unreadable_files_keep_the_previous_weights() {
+    let path = write_weights("skipbot-reload-broken.json", &tuned(1.0), 60);
+    let specs = vec![spec(&path)];
+    let reloader = WeightReloader::new(ReloadTrigger::Signal);
+    reloader.resolve(&specs).unwrap();
+
+    fs::write(&path, "{\"stock_card\":").unwrap();
+    reloader.request();
+    let (resolved, reloaded) = reloader.resolve(&specs).unwrap();
+    assert!(reloaded.is_empty());
+    assert_eq!(inlined(&resolved[0]), 1.0);
+
+    // The failed reload stays pending until the file is whole again.
+    write_weights("skipbot-reload-broken.json", &tuned(4.0), 30);
+    let (resolved, reloaded) = reloader.resolve(&specs).unwrap();
+    assert_eq!(reloaded.len(), 1);
+    assert_eq!(inlined(&resolved[0]), 4.0);
+
+    let missing = vec!["heuristic19:checkpoint=/nonexistent/weights.json".to_string()];
+    assert!(reloader.resolve(&missing).is_err());
+}
+
+#[test]
+fn runner_records_reloads_between_games() {
+    let path = write_weights("skipbot-reload-runner.json", &tuned(1.0), 60);
+    let mut config = MatchConfig::new(vec![spec(&path), "heuristic13".into()], 3, 5);
+    config.stock_size = Some(5);
+    let reloader = WeightReloader::new(ReloadTrigger::Signal);
+    let runner = MatchRunner::new(config)
+        .unwrap()
+        .with_weight_reloader(reloader.clone());
+    let mut state = RunState::new(runner.config().clone());
+    runner.advance(&mut state, 1).unwrap();
+
+    write_weights("skipbot-reload-runner.json", &tuned(2.0), 30);
+    reloader.request();
+    runner.advance(&mut state, 2).unwrap();
+
+    let reloaded: Vec<bool> = state
+        .games
+        .iter()
+        .map(|game| !game.reloaded.is_empty())
+        .collect();
+    assert_eq!(reloaded, vec![false, true, false]);
+    let report = state.into_report();
+    assert!(report.bots.iter().any(|bot| bot.label == "heuristic19"));
+}
@@ -0,0 +1,189 @@
+use skipbot::runner::play_out;
+use skipbot::{
+    Bot, Game, GameRecord, GameStatus, PlayerId, SavedGame, SeatRules, create_bot_from_spec,
+};
+
+const SPECS: [&str; 2] = ["heuristic13", "heuristic2"];
+
+fn play(game: &mut Game, seed: u64, max_actions: usize) {
+    let mut bots: Vec<Box<dyn Bot>> = SPECS
+        .iter()
+        .enumerate()
+        .map(|(seat, spec)| create_bot_from_spec(spec, PlayerId(seat), seed).unwrap())
+        .collect();
+    play_out(game, &mut bots, max_actions, |_| {}).unwrap();
+}
+
+fn new_game(seed: u64) -> Game {
+    Game::builder(2)
+        .unwrap()
+        .with_seed(seed)
+        .with_stock_size(5)
+        .build()
+        .unwrap()
+}
+
+fn played_game(seed: u64) -> Game {
+    let mut game = new_game(seed);
+    play(&mut game, seed, 2000);
+    game
+}
+
+#[test]
+fn record_round_trips_and_replays() {
+    let game = played_game(7);
+    let record = GameRecord::from_game(&game)
+        .unwrap()
+        .with_tag("Bots", "heuristic13 heuristic2");
+    assert_eq!(record.stock_size, Some(5));
+    assert_eq!(record.moves.len(), game.history().len());
+
+    let text = record.to_string();
+    let parsed: GameRecord = text.parse().unwrap();
+    assert_eq!(parsed, record);
+    assert_eq!(parsed.tag("Bots"), Some("heuristic13 heuristic2"));
+
+    let replayed = parsed.replay().unwrap();
+    assert_eq!(replayed.status(), game.status());
+    assert_eq!(replayed.history(), game.history());
+}
+
+#[test]
+fn parser_accepts_comments_and_rejects_bad_records() {
+    let text =
+        "[Players \"2\"] ; two seats\n[Seed \"3\"]\n[Result \"*\"]\n\n1. h0>b0 ; opening\n*\n";
+    let record: GameRecord = text.parse().unwrap();
+    assert_eq!(record.players, 2);
+    assert_eq!(record.result, GameStatus::Ongoing);
+    assert_eq!(record.moves.len(), 1);
+
+    assert!("[Seed \"3\"]\n*\n".parse::<GameRecord>().is_err());
+    assert!(
+        "[Players \"2\"]\n[Seed \"3\"]\n1. Q9\n*\n"
+            .parse::<GameRecord>()
+            .is_err()
+    );
+    assert!(
+        "[Players \"2\"]\n[Seed \"3\"]\n[Result \"draw\"]\n*\n"
+            .parse::<GameRecord>()
+            .is_err()
+    );
+}
+
+#[test]
+fn replay_rejects_wrong_result_and_unreproducible_games() {
+    let game = played_game(11);
+    let mut record = GameRecord::from_game(&game).unwrap();
+    record.result = GameStatus::Finished {
+        winner: PlayerId(1 - game.winner().map_or(0, |w| w.0)),
+    };
+    assert!(record.replay().is_err());
+
+    let mut redealt = played_game(11);
+    redealt.redeal_hidden(PlayerId(0), 1).unwrap();
+    assert!(GameRecord::from_game(&redealt).is_err());
+}
+
+#[test]
+fn saved_game_resumes_with_identical_play() {
+    let mut game = new_game(5);
+    play(&mut game, 5, 40);
+    let specs: Vec<String> = SPECS.iter().map(|s| s.to_string()).collect();
+    let path = std::env::temp_dir().join(format!("skipbot-save-{}.json", std::process::id()));
+    SavedGame::new(&game, &specs).unwrap().save(&path).unwrap();
+    let saved = SavedGame::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(saved.bots, specs);
+    assert_eq!(saved.game.history(), game.history());
+
+    let mut resumed = saved.game;
+    play(&mut game, 5, 2000);
+    play(&mut resumed, 5, 2000);
+    assert_eq!(resumed.history(), game.history());
+    assert_eq!(resumed.status(), game.status());
+}
+
+#[test]
+fn record_keeps_seat_rules() {
+    let rules = SeatRules {
+        extra_discard_pile: true,
+        weak_draws: false,
+        ..SeatRules::default()
+    };
+    let mut game = Game::builder(2)
+        .unwrap()
+        .with_seed(3)
+        .with_stock_size(5)
+        .with_seat_rules(PlayerId(0), rules)
+        .build()
+        .unwrap();
+    play(&mut game, 3, 2000);
+    let text = GameRecord::from_game(&game).unwrap().to_string();
+    assert!(text.contains("[SeatRules \"extra-discard standard\"]"));
+    let record: GameRecord = text.parse().unwrap();
+    assert_eq!(record.seat_rules, vec![rules, SeatRules::default()]);
+    assert_eq!(record.replay().unwrap().history(), game.history());
+}
+
+#[test]
+fn record_keeps_the_must_play_stock_rule() {
+    let mut game = Game::builder(2)
+        .unwrap()
+        .with_seed(6)
+        .with_stock_size(5)
+        .with_must_play_stock(true)
+        .build()
+        .unwrap();
+    play(&mut game, 6, 2000);
+    let text = GameRecord::from_game(&game).unwrap().to_string();
+    assert!(text.contains("[Rules \"must-play-stock\"]"));
+    let record: GameRecord = text.parse().unwrap();
+    assert!(record.must_play_stock);
+    assert_eq!(record.replay().unwrap().history(), game.history());
+}
+
+#[test]
+fn record_keeps_the_stock_visibility() {
+    use skipbot::StockVisibility;
+
+    let mut game = Game::builder(2)
+        .unwrap()
+        .with_seed(6)
+        .with_stock_size(5)
+        .with_must_play_stock(true)
+        .with_stock_visibility(StockVisibility::Binned)
+        .build()
+        .unwrap();
+    play(&mut game, 6, 2000);
+    let text = GameRecord::from_game(&game).unwrap().to_string();
+    assert!(text.contains("[Rules \"must-play-stock stock-binned\"]"));
+    let record: GameRecord = text.parse().unwrap();
+    assert_eq!(record.stock_visibility, StockVisibility::Binned);
+    assert_eq!(record.replay().unwrap().history(), game.history());
+}
+
+#[test]
+fn record_keeps_turn_safety_rules() {
+    use skipbot::LoopGuard;
+
+    let mut game = Game::builder(2)
+        .unwrap()
+        .with_seed(6)
+        .with_stock_size(5)
+        .with_loop_guard(LoopGuard::Forbid)
+        .with_max_actions_per_turn(40)
+        .build()
+        .unwrap();
+    play(&mut game, 6, 2000);
+    let text = GameRecord::from_game(&game).unwrap().to_string();
+    assert!(text.contains("[Rules \"loop-forbid turn-actions=40\"]"));
+    let record: GameRecord = text.parse().unwrap();
+    assert_eq!(record.loop_guard, LoopGuard::Forbid);
+    assert_eq!(record.max_actions_per_turn, Some(40));
+    assert_eq!(record.replay().unwrap().history(), game.history());
+    assert!(
+        text.replace("loop-forbid", "loop-sometimes")
+            .parse::<GameRecord>()
+            .is_err()
+    );
+}
@@ -0,0 +1,75 @@
+use skipbot::analysis::{RolloutEstimator, estimate_win_probability};
+use skipbot::card::full_deck;
+use skipbot::{Card, Game, PlayerId, create_bot_from_spec};
+
+fn midgame() -> Game {
+    let mut game = Game::builder(2)
+        .unwrap()
+        .with_seed(11)
+        .with_stock_size(6)
+        .build()
+        .unwrap();
+    let mut bots = [
+        create_bot_from_spec("heuristic13", PlayerId(0), 1).unwrap(),
+        create_bot_from_spec("heuristic2", PlayerId(1), 2).unwrap(),
+    ];
+    for _ in 0..20 {
+        let current = game.current_player();
+        let state = game.state_view(current).unwrap();
+        let legal = game.legal_actions(current).unwrap();
+        let action = bots[current].select_action(&state, &legal);
+        game.apply_action(current, action).unwrap();
+    }
+    assert!(!game.is_finished());
+    game
+}
+
+#[test]
+fn redeal_keeps_the_perspective_view() {
+    let game = midgame();
+    let me = game.current_player();
+    let mut redealt = game.clone();
+    redealt.redeal_hidden(me, 99).unwrap();
+    assert_eq!(
+        game.state_view(me).unwrap(),
+        redealt.state_view(me).unwrap()
+    );
+    assert!(redealt.redeal_hidden(PlayerId(7), 0).is_err());
+}
+
+#[test]
+fn estimate_is_a_distribution_with_intervals() {
+    let game = midgame();
+    let bots = vec![String::from("heuristic13"), String::from("heuristic2")];
+    let estimate = estimate_win_probability(&game, &bots, 12).unwrap();
+    let total: f64 = estimate.players.iter().map(|p| p.probability).sum();
+    let unfinished = estimate.unfinished as f64 / estimate.samples as f64;
+    assert!((total + unfinished - 1.0).abs() < 1e-9);
+    for player in &estimate.players {
+        assert!(player.ci_low <= player.probability && player.probability <= player.ci_high);
+    }
+    assert!(
+        RolloutEstimator::new(4)
+            .estimate(&game, &bots[..1])
+            .is_err()
+    );
+}
+
+#[test]
+fn playable_last_stock_card_is_a_certain_win() {
+    // Player 0's single stock card is dealt from the end of the deck.
+    let mut deck = full_deck();
+    let one = deck.iter().position(|c| *c == Card::Number(1)).unwrap();
+    let last = deck.len() - 1;
+    deck.swap(one, last);
+    let game = Game::builder(2)
+        .unwrap()
+        .with_deck(deck)
+        .with_stock_size(1)
+        .build()
+        .unwrap();
+    let bots = vec![String::from("heuristic13"), String::from("random")];
+    let estimate = estimate_win_probability(&game, &bots, 8).unwrap();
+    assert_eq!(estimate.probability(PlayerId(0)), 1.0);
+    assert!(estimate.players[0].ci_low > 0.6);
+}
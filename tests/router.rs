@@ -0,0 +1,125 @@
+use std::fs;
+
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use skipbot::bots::router::Route;
+use skipbot::bots::{PlanWeights, PolicyRouterBot, RouterManifest};
+use skipbot::{Bot, Game, HeuristicBot, PlayerId, RandomBot, create_bot_from_spec};
+
+const MANIFEST: &str = r#"
+[[routes]]
+players = 2
+bot = { type = "heuristic13" }
+
+[[routes]]
+players = 4
+stock_size = [1, 15]
+bot = { type = "heuristic19", checkpoint = "cem.json" }
+
+[default]
+type = "heuristic"
+"#;
+
+fn game(players: usize, stock_size: usize) -> Game {
+    Game::builder(players)
+        .unwrap()
+        .with_seed(3)
+        .with_stock_size(stock_size)
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn manifests_map_configurations_to_bots() {
+    let manifest = RouterManifest::from_toml(MANIFEST).unwrap();
+    let routes: Vec<Route> = manifest.routes.iter().map(|r| r.route()).collect();
+    assert_eq!(
+        routes.iter().map(ToString::to_string).collect::<Vec<_>>(),
+        ["players=2", "players=4 stock=1-15"]
+    );
+    assert!(routes[0].matches(&game(2, 30).settings()));
+    assert!(!routes[1].matches(&game(4, 20).settings()));
+    assert!(routes[1].matches(&game(4, 15).settings()));
+    assert_eq!(Route::default().to_string(), "any");
+
+    let json = serde_json::to_string(&manifest).unwrap();
+    assert_eq!(RouterManifest::from_json(&json).unwrap(), manifest);
+
+    assert!(
+        RouterManifest::from_toml("[[routes]]\nplayers = 2\nbot = { type = \"beam\" }\n").is_err()
+    );
+    let reversed = MANIFEST.replace("[1, 15]", "[15, 1]");
+    assert!(RouterManifest::from_toml(&reversed).is_err());
+    let misspelled = MANIFEST.replace("players = 2", "seats = 2");
+    assert!(RouterManifest::from_toml(&misspelled).is_err());
+}
+
+#[test]
+fn each_game_goes_to_its_routed_bot() {
+    let mut router = PolicyRouterBot::new(Box::new(HeuristicBot))
+        .with_route(
+            Route {
+                players: Some(2),
+                stock_size: None,
+            },
+            Box::new(RandomBot::new(StdRng::seed_from_u64(1))),
+        )
+        .with_route(
+            Route {
+                players: None,
+                stock_size: Some((1, 10)),
+            },
+            create_bot_from_spec("heuristic13", PlayerId(0), 1).unwrap(),
+        );
+    for game in [
+        game(2, 30),
+        game(3, 5),
+        game(3, 30),
+        game(2, 5),
+        game(4, 10),
+    ] {
+        let state = game.state_view(PlayerId(0)).unwrap();
+        let legal = game.legal_actions(PlayerId(0)).unwrap();
+        router.on_game_start(&state);
+        assert!(legal.contains(&router.select_action(&state, &legal)));
+        router.on_game_end(&state, None);
+    }
+    // Two-player games take the first route whatever their stock size.
+    assert_eq!(router.routed_games(), [2, 2, 1]);
+    assert_eq!(router.routes().count(), 2);
+}
+
+#[test]
+fn router_specs_load_manifests_next_to_their_checkpoints() {
+    let dir = std::env::temp_dir().join("skipbot-router-test");
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(
+        dir.join("cem.json"),
+        serde_json::to_string(&PlanWeights::default()).unwrap(),
+    )
+    .unwrap();
+    fs::write(dir.join("router.toml"), MANIFEST).unwrap();
+
+    let manifest = RouterManifest::load(&dir.join("router.toml")).unwrap();
+    assert_eq!(
+        manifest.routes[1].bot.checkpoint.as_deref(),
+        Some(dir.join("cem.json").as_path())
+    );
+    let spec = format!("router:{}", dir.join("router.toml").display());
+    for players in [2, 4] {
+        let game = game(players, 10);
+        let mut bot = create_bot_from_spec(&spec, PlayerId(0), 1).unwrap();
+        let legal = game.legal_actions(PlayerId(0)).unwrap();
+        let state = game.state_view(PlayerId(0)).unwrap();
+        assert!(legal.contains(&bot.select_action(&state, &legal)));
+    }
+    assert!(create_bot_from_spec("router", PlayerId(0), 1).is_err());
+    assert!(
+        create_bot_from_spec(
+            &format!("router:{}", dir.join("missing.toml").display()),
+            PlayerId(0),
+            1
+        )
+        .is_err()
+    );
+}
@@ -1,6 +1,7 @@
-use skipbot::action::{Action, CardSource};
-use skipbot::{Card, GameStatus};
-use skipbot::{GameBuilder, GameError, card};
+use skipbot::action::{Action, BuildPileId, CardSource, DiscardPileId, PlayerId};
+use skipbot::error::InvalidAction;
+use skipbot::{Card, GameStatus, StateView, TurnEvent};
+use skipbot::{GameBuilder, GameError, LoopGuard, card};
 
 fn build_deck(
     num_players: usize,
@@ -16,7 +17,10 @@ fn build_deck(
         if stock.len() > settings.stock_size {
             panic!("stock prefix exceeds stock size");
         }
-        stock.extend(std::iter::repeat(Card::Number(12)).take(settings.stock_size - stock.len()));
+        stock.extend(std::iter::repeat_n(
+            Card::Number(12),
+            settings.stock_size - stock.len(),
+        ));
         deck.extend_from_slice(&stock);
     }
     deck
@@ -27,7 +31,7 @@ fn initial_setup_two_players() -> Result<(), GameError> {
     let deck = card::full_deck();
     let deck_len = deck.len();
     let game = GameBuilder::new(2)?.with_deck(deck).build()?;
-    let view0 = game.state_view(0)?;
+    let view0 = game.state_view(PlayerId(0))?;
     assert_eq!(view0.settings.num_players, 2);
     assert_eq!(view0.settings.stock_size, 30);
     assert_eq!(view0.hand.len(), 5);
@@ -44,7 +48,7 @@ fn stock_size_six_players() -> Result<(), GameError> {
     let deck = card::full_deck();
     let deck_len = deck.len();
     let game = GameBuilder::new(6)?.with_deck(deck).build()?;
-    let view0 = game.state_view(0)?;
+    let view0 = game.state_view(PlayerId(0))?;
     assert_eq!(view0.settings.stock_size, 20);
     for player in &view0.players {
         assert_eq!(player.stock_count, 20);
@@ -63,7 +67,7 @@ fn configurable_stock_size_override() -> Result<(), GameError> {
         .with_stock_size(5)
         .with_deck(deck)
         .build()?;
-    let view0 = game.state_view(0)?;
+    let view0 = game.state_view(PlayerId(0))?;
     assert_eq!(view0.settings.stock_size, 5);
     assert_eq!(view0.players[0].stock_count, 5);
     assert_eq!(view0.players[1].stock_count, 5);
@@ -95,13 +99,13 @@ fn build_pile_cycles_and_recycles() -> Result<(), GameError> {
     let deck = build_deck(2, &draw_sequence, &[stock_p0, stock_p1]);
     let mut game = GameBuilder::new(2)?.with_deck(deck).build()?;
     let current = game.current_player();
-    assert_eq!(current, 0);
+    assert_eq!(current, PlayerId(0));
     for _ in 0..5 {
         game.apply_action(
             current,
             Action::Play {
                 source: CardSource::Hand(0),
-                build_pile: 0,
+                build_pile: BuildPileId(0),
             },
         )?;
     }
@@ -110,23 +114,59 @@ fn build_pile_cycles_and_recycles() -> Result<(), GameError> {
             current,
             Action::Play {
                 source: CardSource::Stock,
-                build_pile: 0,
+                build_pile: BuildPileId(0),
             },
         )?;
     }
-    let view = game.state_view(0)?;
+    let view = game.state_view(PlayerId(0))?;
     assert_eq!(view.build_piles[0].cards.len(), 0);
     assert_eq!(view.build_piles[0].next_value, 1);
     assert_eq!(view.recycle_pile_count, 12);
     assert_eq!(view.players[0].stock_count, view.settings.stock_size - 7);
     assert!(matches!(view.status, GameStatus::Ongoing));
     assert_eq!(view.hand.len(), 0);
+    // Emptying the hand drew nothing: both piles were empty.
+    assert_eq!(
+        view.turn_events,
+        [
+            TurnEvent::Drew {
+                player: PlayerId(0),
+                cards: 5
+            },
+            TurnEvent::PileCompleted {
+                pile: BuildPileId(0)
+            },
+        ]
+    );
     game.apply_action(current, Action::EndTurn)?;
-    assert_eq!(game.current_player(), 1);
-    let view1 = game.state_view(1)?;
+    assert_eq!(game.current_player(), PlayerId(1));
+    let view1 = game.state_view(PlayerId(1))?;
     assert_eq!(view1.hand.len(), 5);
     assert_eq!(view1.draw_pile_count, 7);
     assert_eq!(view1.recycle_pile_count, 0);
+    assert_eq!(
+        view1.turn_events,
+        [
+            TurnEvent::Reshuffled { cards: 12 },
+            TurnEvent::Drew {
+                player: PlayerId(1),
+                cards: 5
+            },
+        ]
+    );
+    assert_eq!(
+        game.state_view_ref(PlayerId(0))?.turn_events(),
+        view1.turn_events
+    );
+    // Player 0's refill after emptying the hand came up five cards short.
+    let deck = game.deck_stats();
+    assert_eq!((deck.reshuffles, deck.reshuffled_cards), (1, 12));
+    assert_eq!(
+        (deck.turns, deck.draw_pile_total, deck.draw_pile_min),
+        (2, 5, 0)
+    );
+    assert_eq!((deck.starved_refills, deck.starved_cards), (1, 5));
+    assert_eq!(deck.avg_draw_pile(), 2.5);
     Ok(())
 }
 
@@ -148,34 +188,34 @@ fn skipbo_wildcard_support() -> Result<(), GameError> {
         current,
         Action::Play {
             source: CardSource::Hand(0),
-            build_pile: 0,
+            build_pile: BuildPileId(0),
         },
     )?; // plays 1
     game.apply_action(
         current,
         Action::Play {
             source: CardSource::Hand(0),
-            build_pile: 0,
+            build_pile: BuildPileId(0),
         },
     )?; // plays Skip-Bo as 2
-    let view = game.state_view(0)?;
+    let view = game.state_view(PlayerId(0))?;
     assert_eq!(view.build_piles[0].cards.len(), 2);
     assert_eq!(view.build_piles[0].next_value, 3);
     game.apply_action(
         current,
         Action::Play {
             source: CardSource::Hand(0),
-            build_pile: 0,
+            build_pile: BuildPileId(0),
         },
     )?; // plays 3
     game.apply_action(
         current,
         Action::Play {
             source: CardSource::Hand(0),
-            build_pile: 0,
+            build_pile: BuildPileId(0),
         },
     )?; // plays 4
-    let view = game.state_view(0)?;
+    let view = game.state_view(PlayerId(0))?;
     assert_eq!(view.build_piles[0].next_value, 5);
     Ok(())
 }
@@ -212,7 +252,7 @@ fn refills_hand_after_emptying_during_play() -> Result<(), GameError> {
             current,
             Action::Play {
                 source: CardSource::Hand(0),
-                build_pile: 0,
+                build_pile: BuildPileId(0),
             },
         )?;
     }
@@ -263,3 +303,509 @@ fn detects_stalemate_draw_when_no_draws_and_no_plays() -> Result<(), GameError>
     assert!(game.winner().is_none());
     Ok(())
 }
+
+#[test]
+fn cached_legal_actions_follow_state_changes() -> Result<(), GameError> {
+    let mut game = GameBuilder::new(2)?.with_seed(3).build()?;
+    let first = game.legal_actions(PlayerId(0))?;
+    assert_eq!(game.legal_actions(PlayerId(0))?, first);
+    assert_eq!(first, game.state_view(PlayerId(0))?.legal_actions());
+
+    let snapshot = game.clone();
+    let action = first[0].clone();
+    game.apply_action(PlayerId(0), action)?;
+    let current = game.current_player();
+    assert_eq!(
+        game.legal_actions(current)?,
+        game.state_view(current)?.legal_actions()
+    );
+    // The clone keeps its own cache of the untouched position.
+    assert_eq!(snapshot.legal_actions(PlayerId(0))?, first);
+    Ok(())
+}
+
+#[test]
+fn legal_set_matches_legal_actions() -> Result<(), GameError> {
+    use skipbot::LegalSet;
+    use skipbot::action::ACTION_SPACE;
+
+    for index in 0..ACTION_SPACE {
+        let action = Action::from_index(index).expect("index in range");
+        assert_eq!(action.index(), Some(index));
+    }
+    assert_eq!(Action::from_index(ACTION_SPACE), None);
+    assert_eq!(
+        Action::Play {
+            source: CardSource::Discard(DiscardPileId(5)),
+            build_pile: BuildPileId(0)
+        }
+        .index(),
+        None
+    );
+
+    let mut game = GameBuilder::new(3)?.with_seed(17).build()?;
+    for _ in 0..40 {
+        let current = game.current_player();
+        let legal = game.legal_actions(current)?;
+        let set = game.legal_set(current)?;
+        assert_eq!(set, LegalSet::from_actions(&legal));
+        assert_eq!(set.len(), legal.len());
+        for index in 0..ACTION_SPACE {
+            let action = Action::from_index(index).unwrap();
+            assert_eq!(game.is_legal(&action), legal.contains(&action));
+        }
+        assert!(game.legal_set(current.offset(1, 3)).is_err());
+        let action = legal.last().unwrap().clone();
+        game.apply_action(current, action)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn recent_actions_cover_moves_since_viewer_last_acted() -> Result<(), GameError> {
+    let mut game = GameBuilder::new(3)?.with_seed(11).build()?;
+    assert!(game.state_view(PlayerId(0))?.recent_actions.is_empty());
+
+    // Always take the first legal action for a few turns.
+    let mut applied = Vec::new();
+    while applied.len() < 40 && !game.is_finished() {
+        let player = game.current_player();
+        let action = game.legal_actions(player)?[0].clone();
+        game.apply_action(player, action.clone())?;
+        applied.push((player, action));
+
+        // The actor has just acted, so nothing is new to them.
+        assert!(game.state_view(player)?.recent_actions.is_empty());
+        for viewer in 0..3 {
+            let since = applied
+                .iter()
+                .rposition(|(p, _)| *p == PlayerId(viewer))
+                .map_or(0, |i| i + 1);
+            assert_eq!(
+                game.state_view(PlayerId(viewer))?.recent_actions,
+                applied[since..]
+            );
+        }
+    }
+    assert_eq!(game.history(), applied.as_slice());
+    Ok(())
+}
+
+#[test]
+fn turn_and_action_counters_follow_play() -> Result<(), GameError> {
+    let mut game = GameBuilder::new(2)?.with_seed(5).build()?;
+    let mut turns = 0usize;
+    let mut actions_this_turn = 0usize;
+    for _ in 0..60 {
+        if game.is_finished() {
+            break;
+        }
+        let player = game.current_player();
+        let view = game.state_view(player.offset(1, 2))?;
+        assert_eq!(view.turn_number, turns);
+        assert_eq!(view.actions_taken_this_turn, actions_this_turn);
+
+        let action = game.legal_actions(player)?[0].clone();
+        let ends_turn = !matches!(action, Action::Play { .. });
+        game.apply_action(player, action)?;
+        if ends_turn && !game.is_finished() {
+            turns += 1;
+            actions_this_turn = 0;
+        } else {
+            actions_this_turn += 1;
+        }
+        assert_eq!(game.turn_number(), turns);
+    }
+    assert!(turns > 0);
+    Ok(())
+}
+
+#[test]
+fn typed_ids_keep_numeric_json() {
+    let action = Action::Play {
+        source: CardSource::Discard(DiscardPileId(2)),
+        build_pile: BuildPileId(3),
+    };
+    let json = serde_json::to_string(&action).unwrap();
+    assert_eq!(json, r#"{"Play":{"source":{"Discard":2},"build_pile":3}}"#);
+    assert_eq!(serde_json::from_str::<Action>(&json).unwrap(), action);
+
+    assert_eq!(usize::from(PlayerId(4)), 4);
+    assert_eq!(PlayerId::from(1).offset(2, 3), PlayerId(0));
+    assert_eq!(PlayerId(2).seats_until(PlayerId(0), 3), 1);
+    assert_eq!(BuildPileId::ALL[3], BuildPileId(3));
+    let piles = [10, 20, 30, 40];
+    assert_eq!(piles[DiscardPileId(1)], 20);
+}
+
+#[test]
+fn action_notation_round_trips() {
+    for index in 0..skipbot::action::ACTION_SPACE {
+        let action = Action::from_index(index).unwrap();
+        let text = action.to_string();
+        assert_eq!(text.parse::<Action>(), Ok(action), "{text}");
+    }
+    let cases = [
+        ("H3>B1", "H3>B1"),
+        (" d2 > b0 ", "D2>B0"),
+        ("S>B3", "S>B3"),
+        ("x4>d2", "X4>D2"),
+        ("end", "END"),
+    ];
+    for (input, canonical) in cases {
+        assert_eq!(input.parse::<Action>().unwrap().to_string(), canonical);
+    }
+    for bad in [
+        "", "H>B1", "S2>B1", "H1>D2", "X1>B2", "H1B2", "Q1>B0", "END1",
+    ] {
+        assert!(bad.parse::<Action>().is_err(), "{bad:?} should not parse");
+    }
+}
+
+#[test]
+fn seat_rules_add_discard_pile_and_weaken_draws() -> Result<(), GameError> {
+    use skipbot::SeatRules;
+
+    let rules = SeatRules {
+        extra_discard_pile: true,
+        weak_draws: true,
+        ..SeatRules::default()
+    };
+    assert_eq!(rules.to_string(), "extra-discard+weak-draws");
+    assert_eq!("extra-discard+weak-draws".parse::<SeatRules>(), Ok(rules));
+    assert!(
+        GameBuilder::new(2)?
+            .with_seat_rules(PlayerId(2), rules)
+            .build()
+            .is_err()
+    );
+
+    let mut game = GameBuilder::new(2)?
+        .with_seed(23)
+        .with_seat_rules(PlayerId(1), rules)
+        .build()?;
+    let view = game.state_view(PlayerId(0))?;
+    assert_eq!(view.players[0].discard_piles.len(), 4);
+    assert_eq!(view.players[1].discard_piles.len(), 5);
+
+    let mut used_extra_pile = false;
+    for _ in 0..300 {
+        if game.is_finished() {
+            break;
+        }
+        let current = game.current_player();
+        if current == PlayerId(1) {
+            // With more cards left than there are Skip-Bo cards, every draw had a choice.
+            let view = game.state_view(current)?;
+            if view.draw_pile_count >= card::SKIP_BO_COUNT {
+                assert!(!view.hand.iter().any(Card::is_skip_bo));
+            }
+        }
+        // The last legal action discards onto the seat's highest pile.
+        let action = game.legal_actions(current)?.last().unwrap().clone();
+        if let Action::Discard { discard_pile, .. } = action {
+            assert_eq!(discard_pile.0, if current == PlayerId(1) { 4 } else { 3 });
+            used_extra_pile |= current == PlayerId(1);
+        }
+        game.apply_action(current, action)?;
+    }
+    assert!(used_extra_pile);
+    Ok(())
+}
+
+#[test]
+fn must_play_stock_prunes_other_actions() -> Result<(), GameError> {
+    let draw_sequence = vec![
+        Card::Number(5),
+        Card::Number(4),
+        Card::Number(3),
+        Card::Number(2),
+        Card::Number(1),
+    ];
+    let deck = build_deck(2, &draw_sequence, &[vec![Card::Number(1)], vec![]]);
+    let mut game = GameBuilder::new(2)?
+        .with_deck(deck)
+        .with_must_play_stock(true)
+        .build()?;
+    let me = PlayerId(0);
+    let legal = game.legal_actions(me)?;
+    assert_eq!(legal.len(), 4);
+    assert!(legal.iter().all(|a| matches!(
+        a,
+        Action::Play {
+            source: CardSource::Stock,
+            ..
+        }
+    )));
+    assert_eq!(game.state_view(me)?.legal_actions(), legal);
+
+    let hand_play = Action::Play {
+        source: CardSource::Hand(0),
+        build_pile: BuildPileId(0),
+    };
+    let err = game.apply_action(me, hand_play).unwrap_err();
+    assert!(matches!(
+        err,
+        GameError::InvalidAction(skipbot::InvalidAction::MustPlayStock)
+    ));
+
+    // With the stock top (12) stuck, the rest of the hand is free again.
+    game.apply_action(me, legal[0].clone())?;
+    assert!(game.legal_actions(me)?.len() > 4);
+    game.apply_action(
+        me,
+        Action::Play {
+            source: CardSource::Hand(1),
+            build_pile: BuildPileId(0),
+        },
+    )?;
+    Ok(())
+}
+
+#[test]
+fn stock_wilds_are_guaranteed_or_excluded_when_dealing() -> Result<(), GameError> {
+    use skipbot::SeatRules;
+
+    let rules = |wilds| SeatRules {
+        stock_wilds: Some(wilds),
+        ..SeatRules::default()
+    };
+    assert_eq!(rules(3).to_string(), "stock-wilds=3");
+    assert_eq!(
+        "weak-draws+stock-wilds=0"
+            .parse::<SeatRules>()
+            .map(|r| r.stock_wilds),
+        Ok(Some(0))
+    );
+
+    for seed in 0..20 {
+        let game = GameBuilder::new(3)?
+            .with_seed(seed)
+            .with_stock_size(20)
+            .with_seat_rules(PlayerId(0), rules(4))
+            .with_seat_rules(PlayerId(1), rules(0))
+            .build()?;
+        let full = game.full_state_view(PlayerId(0))?;
+        let wilds = |seat: usize| full.stocks[seat].iter().filter(|c| c.is_skip_bo()).count();
+        assert_eq!(wilds(0), 4);
+        assert_eq!(wilds(1), 0);
+        assert!(full.stocks.iter().all(|stock| stock.len() == 20));
+    }
+
+    // Dealing with the default rules is unchanged.
+    let plain = GameBuilder::new(2)?.with_seed(5).build()?;
+    let unset = GameBuilder::new(2)?
+        .with_seed(5)
+        .with_seat_rules(PlayerId(1), SeatRules::default())
+        .build()?;
+    assert_eq!(
+        plain.full_state_view(PlayerId(0))?,
+        unset.full_state_view(PlayerId(0))?
+    );
+
+    assert!(
+        GameBuilder::new(2)?
+            .with_stock_size(5)
+            .with_seat_rules(PlayerId(0), rules(6))
+            .build()
+            .is_err()
+    );
+    Ok(())
+}
+
+#[test]
+fn stock_visibility_coarsens_stock_counts_in_views() -> Result<(), GameError> {
+    use skipbot::{StateEncoder, StockVisibility, create_bot_from_spec};
+
+    assert_eq!(StockVisibility::Binned.observe(1, 30), 1);
+    assert_eq!(StockVisibility::Binned.observe(3, 30), 5);
+    assert_eq!(StockVisibility::Binned.observe(6, 30), 30);
+    assert_eq!(StockVisibility::Hidden.observe(2, 30), 30);
+    assert_eq!(StockVisibility::Hidden.observe(0, 30), 0);
+    assert_eq!("Binned".parse(), Ok(StockVisibility::Binned));
+
+    let mut game = GameBuilder::new(2)?
+        .with_seed(8)
+        .with_stock_size(4)
+        .with_stock_visibility(StockVisibility::Hidden)
+        .build()?;
+    let mut bots = [1, 2]
+        .map(|seed| create_bot_from_spec(&format!("random:{seed}"), PlayerId(0), seed).unwrap());
+    let mut actions = 0;
+    while !game.is_finished() && actions < 5000 {
+        let current = game.current_player();
+        let view = game.state_view(current)?;
+        for player in &view.players {
+            assert!(player.stock_count == 0 || player.stock_count == 4);
+        }
+        let encoded = StateEncoder::encode(&view);
+        assert_eq!(
+            encoded[StateEncoder::feature_index("stock_visibility").unwrap()],
+            1.0
+        );
+        let legal = game.legal_actions(current)?;
+        game.apply_action(current, bots[current.0].select_action(&view, &legal))?;
+        actions += 1;
+    }
+    // Finished games show the real counts again.
+    assert!(game.is_finished());
+    let full = game.full_state_view(PlayerId(0))?;
+    for (player, stock) in full.view.players.iter().zip(&full.stocks) {
+        assert_eq!(player.stock_count, stock.len());
+    }
+    Ok(())
+}
+
+#[test]
+fn loop_guard_breaks_turns_that_revisit_a_position() -> Result<(), GameError> {
+    // Twelve Skip-Bo cards and nothing else to draw: playing them all on one pile
+    // completes it, and the refill reshuffles the same twelve cards back, leaving every
+    // pile as it was at the start of the turn.
+    let mut deck = vec![Card::SkipBo; 12];
+    deck.extend([Card::Number(12); 2]);
+    let play = Action::Play {
+        source: CardSource::Hand(0),
+        build_pile: BuildPileId(0),
+    };
+    let game = |guard| {
+        GameBuilder::new(2)?
+            .with_stock_size(1)
+            .with_deck(deck.clone())
+            .with_loop_guard(guard)
+            .build()
+    };
+
+    let mut off = game(LoopGuard::Off)?;
+    for _ in 0..24 {
+        off.apply_action(PlayerId(0), play.clone())?;
+    }
+    assert_eq!(off.current_player(), PlayerId(0));
+    assert_eq!(off.loops_broken(), 0);
+
+    let mut end_turn = game(LoopGuard::EndTurn)?;
+    for _ in 0..12 {
+        end_turn.apply_action(PlayerId(0), play.clone())?;
+    }
+    assert_eq!(end_turn.current_player(), PlayerId(1));
+    assert_eq!(end_turn.loops_broken(), 1);
+
+    let mut forbid = game(LoopGuard::Forbid)?;
+    for _ in 0..23 {
+        forbid.apply_action(PlayerId(0), play.clone())?;
+    }
+    assert_eq!(forbid.loops_broken(), 1);
+    // The play that closed the loop is out, from that position only.
+    assert!(!forbid.legal_actions(PlayerId(0))?.contains(&play));
+    assert!(!forbid.is_legal(&play));
+    assert!(matches!(
+        forbid.apply_action(PlayerId(0), play.clone()),
+        Err(GameError::InvalidAction(_))
+    ));
+    // The current player's view lists exactly the engine's legal actions.
+    let view = forbid.state_view(PlayerId(0))?;
+    assert_eq!(view.forbidden_plays, vec![play.clone()]);
+    assert_eq!(view.legal_actions(), forbid.legal_actions(PlayerId(0))?);
+    assert!(forbid.state_view(PlayerId(1))?.forbidden_plays.is_empty());
+    // The borrowed view shows the same.
+    let borrowed = forbid.state_view_ref(PlayerId(0))?;
+    assert_eq!(borrowed.forbidden_plays(), view.forbidden_plays);
+    assert_eq!(borrowed.to_owned_view(), view);
+    assert!(
+        forbid
+            .state_view_ref(PlayerId(1))?
+            .forbidden_plays()
+            .is_empty()
+    );
+    forbid.apply_action(
+        PlayerId(0),
+        Action::Play {
+            source: CardSource::Hand(0),
+            build_pile: BuildPileId(1),
+        },
+    )?;
+    assert_eq!(forbid.current_player(), PlayerId(0));
+
+    assert_eq!("end-turn".parse::<LoopGuard>(), Ok(LoopGuard::EndTurn));
+    assert_eq!(LoopGuard::Forbid.to_string(), "forbid");
+    assert!("sometimes".parse::<LoopGuard>().is_err());
+    Ok(())
+}
+
+#[test]
+fn looping_bots_finish_games_under_the_forbid_guard() -> Result<(), GameError> {
+    // The deck of the test above, played by a bot that always makes the first play its
+    // view lists, which on its own would repeat the same loop forever.
+    let mut deck = vec![Card::SkipBo; 12];
+    deck.extend([Card::Number(12); 2]);
+    let mut game = GameBuilder::new(2)?
+        .with_stock_size(1)
+        .with_deck(deck)
+        .with_loop_guard(LoopGuard::Forbid)
+        .build()?;
+    let mut actions = 0;
+    while !game.is_finished() {
+        let player = game.current_player();
+        let legal = game.state_view(player)?.legal_actions();
+        let action = legal
+            .iter()
+            .find(|action| matches!(action, Action::Play { .. }))
+            .unwrap_or(&legal[0])
+            .clone();
+        game.apply_action(player, action)?;
+        actions += 1;
+        assert!(actions < 1_000, "the guard lets the loop go on");
+    }
+    assert!(game.loops_broken() > 0);
+    Ok(())
+}
+
+#[test]
+fn turns_stop_playing_at_the_action_cap() -> Result<(), GameError> {
+    let mut deck = vec![Card::SkipBo; 12];
+    deck.extend([Card::Number(12); 2]);
+    let mut game = GameBuilder::new(2)?
+        .with_stock_size(1)
+        .with_deck(deck)
+        .with_max_actions_per_turn(3)
+        .build()?;
+    assert_eq!(game.settings().max_actions_per_turn, 3);
+    let play = Action::Play {
+        source: CardSource::Hand(0),
+        build_pile: BuildPileId(0),
+    };
+    for _ in 0..3 {
+        game.apply_action(PlayerId(0), play.clone())?;
+    }
+    let legal = game.legal_actions(PlayerId(0))?;
+    assert!(!legal.is_empty());
+    assert!(legal.iter().all(|a| matches!(a, Action::Discard { .. })));
+    assert_eq!(game.state_view(PlayerId(0))?.legal_actions(), legal);
+    assert!(matches!(
+        game.apply_action(PlayerId(0), play.clone()),
+        Err(GameError::InvalidAction(InvalidAction::TurnActionLimit {
+            limit: 3
+        }))
+    ));
+    game.apply_action(PlayerId(0), legal[0].clone())?;
+    assert_eq!(game.current_player(), PlayerId(1));
+    assert!(
+        game.legal_actions(PlayerId(1))?
+            .iter()
+            .any(|a| matches!(a, Action::Play { .. }))
+    );
+
+    assert_eq!(
+        GameBuilder::new(2)?
+            .build()?
+            .settings()
+            .max_actions_per_turn,
+        skipbot::state::DEFAULT_MAX_ACTIONS_PER_TURN
+    );
+    assert!(
+        GameBuilder::new(2)?
+            .with_max_actions_per_turn(0)
+            .build()
+            .is_err()
+    );
+    Ok(())
+}
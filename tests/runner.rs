@@ -0,0 +1,512 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use skipbot::interrupt::{Interrupted, Interrupter};
+use skipbot::runner::{
+    FaultGuard, FaultKind, FaultPolicy, MATCH_REPORT_VERSION, MatchConfig, MatchReport,
+    MatchRunner, ObjectiveWeights, RunState, play_out, play_out_guarded, play_out_with,
+};
+use skipbot::{
+    Action, Bot, Game, GameStateView, Heuristic2Bot, PlayerId, SeedPool, SeedSplit, StockVisibility,
+};
+
+fn small_config() -> MatchConfig {
+    let mut config = MatchConfig::new(vec!["heuristic13".into(), "random".into()], 6, 42);
+    config.stock_size = Some(5);
+    config
+}
+
+#[test]
+fn report_aggregates_games() {
+    let report = MatchRunner::new(small_config()).unwrap().run().unwrap();
+    assert_eq!(report.version, MATCH_REPORT_VERSION);
+    assert_eq!(report.games.len(), 6);
+    let seats: usize = report.bots.iter().map(|b| b.seats).sum();
+    assert_eq!(seats, 12);
+    let wins: usize = report.bots.iter().map(|b| b.wins).sum();
+    assert_eq!(wins + report.aborted_games, 6);
+    for game in &report.games {
+        assert_eq!(game.scores.len(), 2);
+        if let Some(winner) = game.winner {
+            assert_eq!(game.scores[winner].stock_left, 0);
+        }
+        assert!(game.deck.turns > 0);
+    }
+    let deck = report.deck_stats();
+    assert_eq!(
+        deck.turns,
+        report.games.iter().map(|g| g.deck.turns).sum::<usize>()
+    );
+    assert!(deck.draw_pile_min <= deck.avg_draw_pile() as usize);
+}
+
+#[test]
+fn runs_are_reproducible_and_round_trip() {
+    let first = MatchRunner::new(small_config()).unwrap().run().unwrap();
+    let second = MatchRunner::new(small_config()).unwrap().run().unwrap();
+    let winners = |r: &MatchReport| r.games.iter().map(|g| g.winner).collect::<Vec<_>>();
+    assert_eq!(winners(&first), winners(&second));
+
+    let parsed = MatchReport::from_json(&first.to_json().unwrap()).unwrap();
+    assert_eq!(parsed.config, first.config);
+    assert_eq!(winners(&parsed), winners(&first));
+
+    let older = r#"{"version": 1, "games": []}"#;
+    let err = MatchReport::from_json(older).unwrap_err().to_string();
+    assert!(err.contains("unsupported match report version 1"), "{err}");
+}
+
+#[test]
+fn rejects_invalid_configs() {
+    assert!(MatchRunner::new(MatchConfig::new(vec!["random".into()], 1, 0)).is_err());
+    let mut config = small_config();
+    config.stock_size = Some(0);
+    assert!(MatchRunner::new(config).is_err());
+}
+
+#[test]
+fn interrupted_run_resumes_exactly() {
+    let path = std::env::temp_dir().join(format!("skipbot-resume-{}.json", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let runner = MatchRunner::new(small_config()).unwrap();
+
+    // Simulate an interruption after the first checkpoint.
+    let mut state = RunState::new(small_config());
+    runner.advance(&mut state, 2).unwrap();
+    state.save(&path).unwrap();
+    assert_eq!(RunState::load(&path).unwrap().next_game, 2);
+
+    let resumed = runner.run_resumable(&path, 2).unwrap();
+    let fresh = runner.run().unwrap();
+    let winners = |r: &MatchReport| r.games.iter().map(|g| g.winner).collect::<Vec<_>>();
+    assert_eq!(winners(&resumed), winners(&fresh));
+    assert!(RunState::load(&path).unwrap().is_complete());
+
+    let mut other = small_config();
+    other.seed += 1;
+    assert!(
+        MatchRunner::new(other)
+            .unwrap()
+            .run_resumable(&path, 2)
+            .is_err()
+    );
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn cancelled_runs_keep_their_finished_games() {
+    let interrupter = Interrupter::new();
+    let runner = MatchRunner::new(small_config())
+        .unwrap()
+        .with_interrupter(interrupter.clone());
+    let mut state = RunState::new(small_config());
+    runner.advance(&mut state, 2).unwrap();
+    let decisions = state.decisions.clone();
+    interrupter.cancel();
+    assert!(
+        runner
+            .advance(&mut state, 4)
+            .unwrap_err()
+            .is::<Interrupted>()
+    );
+    assert_eq!((state.next_game, state.games.len()), (2, 2));
+    assert_eq!(state.decisions, decisions);
+
+    // The checkpoint survives the interruption and resumes without it.
+    let path = std::env::temp_dir().join(format!("skipbot-cancel-{}.json", std::process::id()));
+    state.save(&path).unwrap();
+    let err = runner.run_resumable(&path, 2).unwrap_err();
+    assert!(err.is::<Interrupted>());
+    assert_eq!(RunState::load(&path).unwrap().next_game, 2);
+    let plain = MatchRunner::new(small_config()).unwrap();
+    let resumed = plain.run_resumable(&path, 2).unwrap();
+    let winners = |r: &MatchReport| r.games.iter().map(|g| g.winner).collect::<Vec<_>>();
+    assert_eq!(winners(&resumed), winners(&plain.run().unwrap()));
+    std::fs::remove_file(&path).unwrap();
+}
+
+/// Cancels the run on its `remaining`-th decision and records the budgets it was given.
+struct Canceller {
+    inner: Heuristic2Bot,
+    interrupter: Interrupter,
+    remaining: usize,
+    expired: Arc<Mutex<Vec<bool>>>,
+}
+
+impl Bot for Canceller {
+    fn interrupt_with(&mut self, interrupter: &Interrupter) {
+        self.expired.lock().unwrap().push(interrupter.should_stop());
+    }
+
+    fn select_action(&mut self, state: &GameStateView, legal_actions: &[Action]) -> Action {
+        self.remaining -= 1;
+        if self.remaining == 0 {
+            self.interrupter.cancel();
+        }
+        self.inner.select_action(state, legal_actions)
+    }
+
+    fn on_game_end(&mut self, _state: &GameStateView, _winner: Option<PlayerId>) {
+        panic!("abandoned games do not end");
+    }
+}
+
+#[test]
+fn play_out_abandons_cancelled_games() {
+    for move_time in [None, Some(Duration::ZERO)] {
+        let mut game = Game::builder(2).unwrap().with_seed(3).build().unwrap();
+        let interrupter = Interrupter::new();
+        let expired = Arc::new(Mutex::new(Vec::new()));
+        let mut bots: Vec<Box<dyn Bot>> = (0..2)
+            .map(|_| {
+                Box::new(Canceller {
+                    inner: Heuristic2Bot::new(),
+                    interrupter: interrupter.clone(),
+                    remaining: 5,
+                    expired: Arc::clone(&expired),
+                }) as Box<dyn Bot>
+            })
+            .collect();
+        let err =
+            play_out_with(&mut game, &mut bots, 2000, &interrupter, move_time, |_| {}).unwrap_err();
+        assert!(err.is::<Interrupted>());
+        assert!(!game.is_finished());
+        let expired = expired.lock().unwrap();
+        assert!(expired.len() >= 5);
+        assert!(expired.iter().all(|&stop| stop == move_time.is_some()));
+    }
+}
+
+#[test]
+fn seed_split_draws_decks_from_its_universe() {
+    let mut config = small_config();
+    config.seed_split = Some(SeedSplit::Test);
+    let report = MatchRunner::new(config).unwrap().run().unwrap();
+    for game in &report.games {
+        assert!(SeedPool::contains(SeedSplit::Test, game.deck_seed));
+        assert!(!SeedPool::contains(SeedSplit::Train, game.deck_seed));
+    }
+}
+
+#[test]
+fn secondary_objectives_and_composite_ranking() {
+    let report = MatchRunner::new(small_config()).unwrap().run().unwrap();
+    let margin: i64 = report.bots.iter().map(|b| b.total_margin).sum();
+    let finished = report.games.iter().filter(|g| g.winner.is_some()).count();
+    let losses: usize = report.bots.iter().map(|b| b.losses).sum();
+    assert_eq!(losses, finished);
+    assert!(margin.unsigned_abs() <= report.games.len() as u64 * 5);
+    for game in &report.games {
+        assert!(game.turns <= game.actions);
+    }
+    for bot in report.bots.iter().filter(|b| b.wins > 0) {
+        assert!(bot.avg_turns_to_win() > 0.0);
+        assert!(bot.avg_turns_to_win() <= bot.avg_actions_to_win());
+    }
+
+    let only_wins: ObjectiveWeights = "margin=0,turns_to_win=0,loss_stock=0".parse().unwrap();
+    let ranked = report.ranked_by(&only_wins);
+    assert_eq!(ranked[0].0.label, report.bots[0].label);
+    assert!((ranked[0].1 - report.bots[0].win_rate()).abs() < 1e-12);
+    assert!("speed=1".parse::<ObjectiveWeights>().is_err());
+    assert!("turns=1".parse::<ObjectiveWeights>().is_err());
+
+    // Slow winners pay around the mean game length; bots that never win pay the most.
+    let longest = report.games.iter().map(|g| g.turns).max().unwrap() as f64;
+    let mean =
+        report.games.iter().map(|g| g.turns).sum::<usize>() as f64 / report.games.len() as f64;
+    for bot in &report.bots {
+        let penalty = report.turns_to_win_penalty(bot);
+        assert!(penalty > 0.0 && penalty <= longest / mean + 1e-12);
+    }
+    let mut winless = report.bots[0].clone();
+    winless.wins = 0;
+    assert!((report.turns_to_win_penalty(&winless) - longest / mean).abs() < 1e-12);
+}
+
+/// Wraps a bot and logs the lifecycle hooks it receives.
+struct HookRecorder {
+    inner: Heuristic2Bot,
+    log: Arc<Mutex<Vec<String>>>,
+}
+
+impl Bot for HookRecorder {
+    fn on_game_start(&mut self, state: &GameStateView) {
+        self.log
+            .lock()
+            .unwrap()
+            .push(format!("start {}", state.self_player));
+    }
+
+    fn on_turn_start(&mut self, state: &GameStateView) {
+        self.log
+            .lock()
+            .unwrap()
+            .push(format!("turn {}", state.self_player));
+    }
+
+    fn select_action(&mut self, state: &GameStateView, legal_actions: &[Action]) -> Action {
+        self.log
+            .lock()
+            .unwrap()
+            .push(format!("act {}", state.self_player));
+        self.inner.select_action(state, legal_actions)
+    }
+
+    fn on_game_end(&mut self, state: &GameStateView, winner: Option<PlayerId>) {
+        self.log
+            .lock()
+            .unwrap()
+            .push(format!("end {} {winner:?}", state.self_player));
+    }
+}
+
+#[test]
+fn play_out_drives_lifecycle_hooks() {
+    let mut game = Game::builder(2)
+        .unwrap()
+        .with_seed(7)
+        .with_stock_size(5)
+        .build()
+        .unwrap();
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let mut bots: Vec<Box<dyn Bot>> = (0..2)
+        .map(|_| {
+            Box::new(HookRecorder {
+                inner: Heuristic2Bot::new(),
+                log: Arc::clone(&log),
+            }) as Box<dyn Bot>
+        })
+        .collect();
+    let mut observed = 0usize;
+    let actions = play_out(&mut game, &mut bots, 2000, |_| observed += 1).unwrap();
+    assert!(game.is_finished());
+    assert_eq!(observed, actions);
+
+    let log = log.lock().unwrap();
+    assert_eq!(log[..2], ["start 0", "start 1"]);
+    let winner = game.winner();
+    assert_eq!(
+        log[log.len() - 2..],
+        [format!("end 0 {winner:?}"), format!("end 1 {winner:?}")]
+    );
+    // Each turn starts exactly when the acting player changes.
+    let mut last = None;
+    for pair in log[2..log.len() - 2].windows(2) {
+        if let Some(player) = pair[1].strip_prefix("act ") {
+            let expected_turn = last != Some(player.to_string());
+            assert_eq!(pair[0] == format!("turn {player}"), expected_turn);
+            last = Some(player.to_string());
+        }
+    }
+    assert_eq!(log.iter().filter(|e| e.starts_with("act")).count(), actions);
+}
+
+#[test]
+fn handicaps_parse_and_travel_with_the_config() {
+    use skipbot::runner::{Handicap, parse_handicap_entry};
+
+    assert_eq!(
+        parse_handicap_entry("1:nodes=500"),
+        Ok((1, Handicap::NodeBudget(500)))
+    );
+    assert_eq!(
+        parse_handicap_entry("0:Weak-Draws"),
+        Ok((0, Handicap::WeakDraws))
+    );
+    assert_eq!(
+        parse_handicap_entry("1:stock-wilds=0"),
+        Ok((1, Handicap::StockWilds(0)))
+    );
+    assert!(parse_handicap_entry("nodes=500").is_err());
+    assert!(parse_handicap_entry("0:nodes=0").is_err());
+
+    let mut config = small_config();
+    config.add_handicap(1, Handicap::OpponentExtraDiscard);
+    config.add_handicap(1, Handicap::WeakDraws);
+    config.add_handicap(0, Handicap::NodeBudget(50));
+    let report = MatchRunner::new(config.clone()).unwrap().run().unwrap();
+    assert_eq!(report.config, config);
+    let parsed = MatchReport::from_json(&report.to_json().unwrap()).unwrap();
+    assert_eq!(parsed.config.handicaps, config.handicaps);
+
+    config.add_handicap(2, Handicap::WeakDraws);
+    assert!(MatchRunner::new(config).is_err());
+}
+
+/// Plays like [`Heuristic2Bot`] but for its `remaining`-th decision, which panics or, with
+/// `illegal`, ends the turn with cards in hand.
+struct Faulty {
+    inner: Heuristic2Bot,
+    remaining: usize,
+    illegal: bool,
+}
+
+impl Bot for Faulty {
+    fn select_action(&mut self, state: &GameStateView, legal_actions: &[Action]) -> Action {
+        self.remaining = self.remaining.saturating_sub(1);
+        match self.remaining {
+            0 if self.illegal => Action::EndTurn,
+            0 => {
+                self.remaining = usize::MAX;
+                panic!("out of ideas")
+            }
+            _ => self.inner.select_action(state, legal_actions),
+        }
+    }
+}
+
+#[test]
+fn faulty_bots_are_handled_by_the_fault_policy() {
+    let play = |policy, illegal| {
+        let mut game = Game::builder(2).unwrap().with_seed(3).build().unwrap();
+        let mut bots: Vec<Box<dyn Bot>> = vec![
+            Box::new(Faulty {
+                inner: Heuristic2Bot::new(),
+                remaining: 4,
+                illegal,
+            }),
+            Box::new(Heuristic2Bot::new()),
+        ];
+        let guard = FaultGuard {
+            policy,
+            on_panic: policy,
+            ..FaultGuard::default()
+        };
+        let played = play_out_guarded(
+            &mut game,
+            &mut bots,
+            2000,
+            &Interrupter::new(),
+            None,
+            &guard,
+            |_| {},
+        );
+        (played, game)
+    };
+
+    let (played, game) = play(FaultPolicy::Abort, false);
+    let err = played.unwrap_err();
+    assert!(err.to_string().contains("panicked: out of ideas"), "{err}");
+    assert!(!game.is_finished());
+
+    let (played, game) = play(FaultPolicy::Forfeit, true);
+    let played = played.unwrap();
+    assert_eq!(played.forfeit, Some((PlayerId(0), PlayerId(1))));
+    assert_eq!(played.faults.len(), 1);
+    assert_eq!(played.faults[0].kind, FaultKind::IllegalAction);
+    assert_eq!(played.faults[0].ply, played.actions);
+    assert!(!game.is_finished());
+
+    let (played, game) = play(FaultPolicy::RandomMove, false);
+    let played = played.unwrap();
+    assert_eq!(played.faults.len(), 1);
+    assert_eq!(played.faults[0].kind, FaultKind::Panic);
+    assert!(played.forfeit.is_none());
+    assert!(game.is_finished() || played.actions == 2000);
+
+    assert_eq!("forfeit".parse::<FaultPolicy>(), Ok(FaultPolicy::Forfeit));
+    assert_eq!(FaultPolicy::RandomMove.to_string(), "random-move");
+    assert!("retry".parse::<FaultPolicy>().is_err());
+}
+
+#[test]
+fn reports_record_faults_per_game_and_bot() {
+    let mut config = small_config();
+    config.games = 2;
+    config.time_limit_ms = Some(0);
+    config.on_fault = FaultPolicy::RandomMove;
+    let report = MatchRunner::new(config.clone()).unwrap().run().unwrap();
+    let faults: usize = report.games.iter().map(|g| g.faults.len()).sum();
+    assert!(faults > 0);
+    assert!(
+        report.games[0]
+            .faults
+            .iter()
+            .all(|f| f.kind == FaultKind::Timeout && f.reason.contains("limit of 0ns"))
+    );
+    assert_eq!(report.bots.iter().map(|b| b.faults).sum::<usize>(), faults);
+    let json = report.to_json().unwrap();
+    let loaded = MatchReport::from_json(&json).unwrap();
+    assert_eq!(loaded.games[1].faults, report.games[1].faults);
+
+    config.on_fault = FaultPolicy::Forfeit;
+    let report = MatchRunner::new(config).unwrap().run().unwrap();
+    for game in &report.games {
+        let loser = game.forfeited.expect("the first decision already overruns");
+        assert_eq!(game.faults.len(), 1);
+        assert_eq!(game.winner, Some(PlayerId(1 - loser.0)));
+        // The awarded winner counts as one, but a forfeit earns no points.
+        let winner = &game.scores[1 - loser.0];
+        assert!(winner.is_winner);
+        assert!(game.scores.iter().all(|score| score.points == 0));
+    }
+    assert_eq!(report.bots.iter().map(|b| b.forfeits).sum::<usize>(), 2);
+    assert!(report.bots.iter().all(|b| b.total_points == 0));
+}
+
+#[test]
+fn scores_count_stocks_exactly_under_hidden_visibility() {
+    let mut config = small_config();
+    config.max_turns = 30;
+    config.stock_visibility = StockVisibility::Hidden;
+    let runner = MatchRunner::new(config).unwrap();
+    let (result, game) = runner
+        .play_seated(0, &[0, 1], &mut BTreeMap::new())
+        .unwrap();
+    assert!(result.winner.is_none(), "the game is cut off");
+    let stocks = game.full_state_view(PlayerId(0)).unwrap().stocks;
+    for (score, stock) in result.scores.iter().zip(&stocks) {
+        assert_eq!(score.stock_left, stock.len());
+        assert_eq!(score.cards_played, 5 - stock.len());
+        assert!(!score.is_winner && score.points == 0);
+    }
+    assert!(stocks.iter().any(|stock| stock.len() < 5));
+}
+
+#[test]
+fn panics_fall_back_to_a_random_move_by_default() {
+    let config = small_config();
+    assert_eq!(config.on_panic, FaultPolicy::RandomMove);
+    let mut json: serde_json::Value = serde_json::to_value(&config).unwrap();
+    json.as_object_mut().unwrap().remove("on_panic");
+    let loaded: MatchConfig = serde_json::from_value(json).unwrap();
+    assert_eq!(loaded, config);
+
+    let mut game = Game::builder(2).unwrap().with_seed(3).build().unwrap();
+    let mut bots: Vec<Box<dyn Bot>> = vec![
+        Box::new(Heuristic2Bot::new()),
+        Box::new(Faulty {
+            inner: Heuristic2Bot::new(),
+            remaining: 2,
+            illegal: false,
+        }),
+    ];
+    let guard = FaultGuard {
+        on_panic: config.on_panic,
+        ..FaultGuard::default()
+    };
+    let played = play_out_guarded(
+        &mut game,
+        &mut bots,
+        2000,
+        &Interrupter::new(),
+        None,
+        &guard,
+        |_| {},
+    )
+    .unwrap();
+    assert_eq!(played.faults.len(), 1);
+    assert_eq!(played.faults[0].seat, PlayerId(1));
+    assert!(game.is_finished() || played.actions == 2000);
+
+    // Panics are counted per bot in the report.
+    let report = MatchRunner::new(config.clone()).unwrap().run().unwrap();
+    let mut games = report.games.clone();
+    games[0].faults = played.faults;
+    let label = games[0].seats[1].clone();
+    let report = MatchReport::from_games(config, games, &Default::default(), 0.0);
+    let bot = report.bots.iter().find(|b| b.label == label).unwrap();
+    assert_eq!((bot.faults, bot.panics), (1, 1));
+}
@@ -0,0 +1,60 @@
+use skipbot::bots::{ReplacementPolicy, TranspositionTable};
+
+// With four slots, keys 1, 5 and 9 all map to slot 1.
+const CAPACITY: usize = 4;
+
+#[test]
+fn colliding_keys_never_read_each_others_values() {
+    let mut table = TranspositionTable::new(CAPACITY);
+    assert_eq!(table.probe(1), None);
+    assert!(table.store(1, 3, "one"));
+    assert_eq!(table.probe(5), None);
+    assert_eq!(table.probe(1), Some(&"one"));
+
+    let stats = table.stats();
+    assert_eq!((stats.probes, stats.hits, stats.collisions), (3, 1, 1));
+    assert!((stats.hit_rate() - 1.0 / 3.0).abs() < 1e-9);
+
+    assert!(table.store(2, 0, "two"));
+    assert!(table.store(1, 4, "one again"));
+    assert_eq!(table.len(), 2);
+    assert_eq!(table.stats().evictions, 0);
+}
+
+#[test]
+fn depth_preferred_keeps_deeper_entries_of_the_current_search() {
+    let mut table = TranspositionTable::new(CAPACITY);
+    assert_eq!(table.policy(), ReplacementPolicy::DepthPreferred);
+    table.store(1, 5, 'a');
+    assert!(!table.store(5, 2, 'b'));
+    assert_eq!(table.probe(1), Some(&'a'));
+    assert!(table.store(5, 7, 'c'));
+    assert_eq!(table.probe(1), None);
+    assert_eq!(table.probe(5), Some(&'c'));
+
+    // Entries of an earlier search stay readable but give way to any new one.
+    table.new_search();
+    assert_eq!(table.probe(5), Some(&'c'));
+    assert!(table.store(9, 3, 'd'));
+    assert_eq!(table.probe(9), Some(&'d'));
+    assert!(!table.store(5, 1, 'e'));
+
+    let stats = table.stats();
+    assert_eq!((stats.stores, stats.evictions, stats.rejected), (3, 2, 2));
+    assert_eq!(table.len(), 1);
+}
+
+#[test]
+fn always_policy_replaces_regardless_of_depth() {
+    let mut table = TranspositionTable::new(CAPACITY).with_policy("always".parse().unwrap());
+    table.store(1, 9, 'a');
+    assert!(table.store(5, 0, 'b'));
+    assert_eq!(table.probe(5), Some(&'b'));
+    assert_eq!(table.stats().evictions, 1);
+
+    table.clear();
+    assert!(table.is_empty());
+    assert_eq!(table.probe(5), None);
+    assert_eq!(table.stats().probes, 1);
+    assert!("newest".parse::<ReplacementPolicy>().is_err());
+}
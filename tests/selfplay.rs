@@ -0,0 +1,265 @@
+use std::collections::HashSet;
+
+use skipbot::card::HAND_SIZE;
+use skipbot::replay::GameRecord;
+use skipbot::runner::play_out;
+use skipbot::selfplay::{
+    CriticView, CurriculumStage, GameRules, RuleRandomization, RuleRange, SelfPlayConfig,
+    SelfPlaySample, StockCurriculum, collect_self_play,
+};
+use skipbot::{
+    Bot, Game, GameError, JOINT_FEATURES, PRIVILEGED_FEATURES, PlayerId, STATE_FEATURES,
+    StateEncoder, create_bot_from_spec,
+};
+
+fn collect(config: &SelfPlayConfig) -> Vec<SelfPlaySample> {
+    let mut samples = Vec::new();
+    let total = collect_self_play(config, |sample| samples.push(sample)).unwrap();
+    assert_eq!(total, samples.len());
+    samples
+}
+
+#[test]
+fn shared_bot_plays_every_seat() {
+    let samples = collect(&SelfPlayConfig::new("heuristic13", 3, 2, 11));
+    assert!(samples.iter().all(|s| s.learner));
+    let seats: HashSet<PlayerId> = samples.iter().map(|s| s.seat).collect();
+    assert_eq!(seats.len(), 3);
+    for sample in &samples {
+        assert_eq!(sample.features.len(), STATE_FEATURES);
+        assert!(sample.legal.contains(&sample.action));
+    }
+    // Each finished game has exactly one winning seat.
+    for game in 0..2 {
+        let winners: HashSet<PlayerId> = samples
+            .iter()
+            .filter(|s| s.game == game && s.outcome == 1.0)
+            .map(|s| s.seat)
+            .collect();
+        assert_eq!(winners.len(), 1);
+    }
+}
+
+#[test]
+fn frozen_opponents_leave_one_learner_seat_per_game() {
+    let config = SelfPlayConfig {
+        freeze_opponents: true,
+        ..SelfPlayConfig::new("heuristic13", 2, 4, 11)
+    };
+    let samples = collect(&config);
+    for sample in &samples {
+        assert_eq!(sample.learner, sample.seat.0 == sample.game % 2);
+    }
+    assert_eq!(samples, collect(&config));
+}
+
+#[test]
+fn open_hands_add_critic_features_without_changing_play() {
+    let config = SelfPlayConfig::new("heuristic13", 3, 2, 11);
+    let open = SelfPlayConfig {
+        critic: CriticView::OpenHands,
+        ..config.clone()
+    };
+    let standard = collect(&config);
+    let privileged = collect(&open);
+    assert_eq!(standard.len(), privileged.len());
+    let hand = StateEncoder::privileged_feature_names()
+        .iter()
+        .position(|name| *name == "seat1.hand.skip_bo")
+        .unwrap();
+    assert_eq!(hand, STATE_FEATURES);
+    for (plain, sample) in standard.iter().zip(&privileged) {
+        assert!(plain.critic_features.is_none());
+        let critic = sample.critic_features.as_ref().unwrap();
+        assert_eq!(critic.len(), PRIVILEGED_FEATURES);
+        // The actor's features are the critic's prefix.
+        assert_eq!(sample.features, critic[..STATE_FEATURES]);
+        assert_eq!(
+            (sample.action, &sample.features),
+            (plain.action, &plain.features)
+        );
+        assert!(
+            critic[STATE_FEATURES..]
+                .iter()
+                .all(|f| (0.0..=1.0).contains(f))
+        );
+    }
+    // A hand may be empty after its last card is discarded, but not every time.
+    let shown: f32 = privileged
+        .iter()
+        .map(|s| {
+            s.critic_features.as_ref().unwrap()[STATE_FEATURES..]
+                .iter()
+                .sum::<f32>()
+        })
+        .sum();
+    assert!(shown > 0.0);
+}
+
+#[test]
+fn joint_critics_see_every_seat_while_actors_keep_their_view() {
+    let config = SelfPlayConfig {
+        critic: CriticView::Joint,
+        ..SelfPlayConfig::new("heuristic13", 2, 1, 11)
+    };
+    let samples = collect(&config);
+    assert_eq!(CriticView::Joint.feature_names().len(), JOINT_FEATURES);
+    for sample in &samples {
+        let critic = sample.critic_features.as_ref().unwrap();
+        assert_eq!(critic.len(), CriticView::Joint.features());
+        assert_eq!(sample.features.len(), STATE_FEATURES);
+        assert_eq!(sample.features, critic[..STATE_FEATURES]);
+        // Blocks past the two seats are padding.
+        assert!(critic[2 * PRIVILEGED_FEATURES..].iter().all(|f| *f == 0.0));
+    }
+    assert_eq!("open-hands".parse(), Ok(CriticView::OpenHands));
+    assert!("oracle".parse::<CriticView>().is_err());
+}
+
+#[test]
+fn randomized_rules_vary_per_game_and_are_recorded() {
+    let randomize = RuleRandomization {
+        stock_size: Some("5-15".parse().unwrap()),
+        hand_size: Some("2-5".parse().unwrap()),
+        skip_bo_cards: Some("0-10".parse().unwrap()),
+    };
+    let config = SelfPlayConfig {
+        randomize,
+        ..SelfPlayConfig::new("heuristic13", 3, 6, 11)
+    };
+    let samples = collect(&config);
+    assert_eq!(samples, collect(&config));
+    let mut per_game: Vec<Vec<f32>> = Vec::new();
+    for game in 0..6 {
+        let rules: HashSet<Vec<u32>> = samples
+            .iter()
+            .filter(|s| s.game == game)
+            .map(|s| {
+                let features = s.rule_features.as_ref().unwrap();
+                assert_eq!(features.len(), GameRules::FEATURE_NAMES.len());
+                assert!(features.iter().all(|f| (0.0..=1.0).contains(f)));
+                features.iter().map(|f| f.to_bits()).collect()
+            })
+            .collect();
+        assert_eq!(rules.len(), 1, "one draw per game");
+        let features = rules.into_iter().next().unwrap();
+        per_game.push(features.into_iter().map(f32::from_bits).collect());
+    }
+    per_game.dedup();
+    assert!(per_game.len() > 1, "rules change between games");
+    assert!(
+        collect(&SelfPlayConfig::new("heuristic13", 2, 1, 11))
+            .iter()
+            .all(|s| s.rule_features.is_none())
+    );
+}
+
+#[test]
+fn stock_curriculum_raises_the_stock_size_stage_by_stage() {
+    let config = SelfPlayConfig {
+        curriculum: Some(StockCurriculum {
+            stock_size: "3-9".parse().unwrap(),
+            stages: 3,
+        }),
+        ..SelfPlayConfig::new("heuristic13", 2, 6, 11)
+    };
+    let stage = |stage, first_game, stock_size| CurriculumStage {
+        stage,
+        first_game,
+        games: 2,
+        stock_size,
+    };
+    assert_eq!(
+        config.curriculum_schedule(),
+        [stage(0, 0, 3), stage(1, 2, 6), stage(2, 4, 9)]
+    );
+    let samples = collect(&config);
+    let stock_sizes: Vec<usize> = (0..6)
+        .map(|game| {
+            let sample = samples.iter().find(|s| s.game == game).unwrap();
+            let features = sample.rule_features.as_ref().unwrap();
+            (features[0] * 50.0).round() as usize
+        })
+        .collect();
+    assert_eq!(stock_sizes, [3, 3, 6, 6, 9, 9]);
+
+    // The curriculum owns the stock size.
+    let both = SelfPlayConfig {
+        randomize: RuleRandomization {
+            stock_size: Some("5-10".parse().unwrap()),
+            ..RuleRandomization::default()
+        },
+        ..config.clone()
+    };
+    assert!(collect_self_play(&both, |_| {}).is_err());
+    assert!(
+        SelfPlayConfig::new("heuristic13", 2, 6, 11)
+            .curriculum_schedule()
+            .is_empty()
+    );
+}
+
+#[test]
+fn rule_ranges_must_deal_a_playable_game() {
+    assert_eq!("4-9".parse::<RuleRange>(), Ok(RuleRange { min: 4, max: 9 }));
+    assert_eq!("7".parse::<RuleRange>().unwrap().to_string(), "7");
+    assert!("9-4".parse::<RuleRange>().is_err());
+    assert!("few".parse::<RuleRange>().is_err());
+    for randomize in [
+        RuleRandomization {
+            hand_size: Some("0-3".parse().unwrap()),
+            ..RuleRandomization::default()
+        },
+        RuleRandomization {
+            hand_size: Some("4-6".parse().unwrap()),
+            ..RuleRandomization::default()
+        },
+        RuleRandomization {
+            skip_bo_cards: Some("10-19".parse().unwrap()),
+            ..RuleRandomization::default()
+        },
+        RuleRandomization {
+            stock_size: Some("20-90".parse().unwrap()),
+            ..RuleRandomization::default()
+        },
+    ] {
+        assert!(randomize.validate(2).is_err(), "{randomize:?}");
+        let config = SelfPlayConfig {
+            randomize,
+            ..SelfPlayConfig::new("heuristic13", 2, 1, 11)
+        };
+        assert!(collect_self_play(&config, |_| {}).is_err());
+    }
+
+    // A custom hand size deals and refills to that size, and its record replays.
+    let mut game = Game::builder(2)
+        .unwrap()
+        .with_seed(3)
+        .with_hand_size(3)
+        .build()
+        .unwrap();
+    assert_eq!(game.state_view(PlayerId(0)).unwrap().hand.len(), 3);
+    let mut bots: Vec<Box<dyn Bot>> = PlayerId::all(2)
+        .map(|seat| create_bot_from_spec("heuristic13", seat, 3).unwrap())
+        .collect();
+    play_out(&mut game, &mut bots, 2000, |_| {}).unwrap();
+    let record = GameRecord::from_game(&game).unwrap();
+    assert_eq!(record.hand_size, Some(3));
+    let parsed: GameRecord = record.to_string().parse().unwrap();
+    assert_eq!(parsed, record);
+    let replayed = parsed.replay().unwrap();
+    assert_eq!(
+        replayed.state_view(PlayerId(0)).unwrap(),
+        game.state_view(PlayerId(0)).unwrap()
+    );
+    assert!(Game::builder(2).unwrap().with_hand_size(0).build().is_err());
+    // Hand slots past HAND_SIZE have no action index.
+    assert!(matches!(
+        Game::builder(2)
+            .unwrap()
+            .with_seed(1)
+            .with_hand_size(HAND_SIZE + 2)
+            .build(),
+        Err(GameError::InvalidConfiguration(_))
+    ));
+}
@@ -0,0 +1,84 @@
+//! Action snapshots of every heuristic over a fixed set of positions; see
+//! `skipbot::analysis::snapshots`. After an intended behavior change, rerun with
+//! `SKIPBOT_UPDATE_SNAPSHOTS=1` and review the diff of `tests/snapshots/`.
+
+use std::fs;
+use std::path::PathBuf;
+
+use skipbot::analysis::snapshots::{
+    diff_snapshots, load_positions, sample_positions, save_positions, take_snapshot,
+};
+
+/// Bots whose moves are snapshotted. Search bots are left out: their moves depend on
+/// time budgets and sampling.
+const HEURISTICS: [&str; 19] = [
+    "heuristic",
+    "heuristic2",
+    "heuristic3",
+    "heuristic4",
+    "heuristic5",
+    "heuristic6",
+    "heuristic7",
+    "heuristic8",
+    "heuristic9",
+    "heuristic10",
+    "heuristic11",
+    "heuristic12",
+    "heuristic13",
+    "heuristic14",
+    "heuristic15",
+    "heuristic16",
+    "heuristic17",
+    "heuristic18",
+    "heuristic19",
+];
+
+fn snapshot_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/snapshots")
+}
+
+fn updating() -> bool {
+    std::env::var_os("SKIPBOT_UPDATE_SNAPSHOTS").is_some_and(|v| v == "1")
+}
+
+#[test]
+fn heuristics_match_their_action_snapshots() {
+    let positions_path = snapshot_dir().join("positions.jsonl");
+    if updating() && !positions_path.exists() {
+        let positions = sample_positions("heuristic13", 4, 16, 0x5A4B).unwrap();
+        save_positions(&positions_path, &positions).unwrap();
+    }
+    let positions = load_positions(&positions_path).unwrap();
+    assert!(positions.len() >= 40, "{} positions", positions.len());
+
+    let mut changed = Vec::new();
+    for spec in HEURISTICS {
+        let path = snapshot_dir().join(format!("{spec}.txt"));
+        let actual = take_snapshot(spec, &positions).unwrap();
+        if updating() {
+            fs::write(&path, &actual).unwrap();
+            continue;
+        }
+        let expected = fs::read_to_string(&path).unwrap_or_default();
+        for (expected, actual) in diff_snapshots(&expected, &actual) {
+            changed.push(format!("{spec}: expected `{expected}`, got `{actual}`"));
+        }
+    }
+    assert!(
+        changed.is_empty(),
+        "moves changed (rerun with SKIPBOT_UPDATE_SNAPSHOTS=1 if intended):\n{}",
+        changed.join("\n")
+    );
+}
+
+#[test]
+fn snapshot_diffs_list_changed_and_missing_lines() {
+    let diff = diff_snapshots("a H0>B0\nb END\n", "a H0>B0\nb X1>D2\nc END\n");
+    assert_eq!(
+        diff,
+        [
+            ("b END".to_string(), "b X1>D2".to_string()),
+            (String::new(), "c END".to_string()),
+        ]
+    );
+}
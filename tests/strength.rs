@@ -0,0 +1,59 @@
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+use skipbot::runner::play_out;
+use skipbot::{Bot, Game, PlayerId, Strength, create_bot_from_spec};
+
+fn play(specs: [&str; 2], seed: u64) -> Game {
+    let mut game = Game::builder(2)
+        .unwrap()
+        .with_seed(seed)
+        .with_stock_size(8)
+        .build()
+        .unwrap();
+    let mut bots: Vec<Box<dyn Bot>> = specs
+        .iter()
+        .enumerate()
+        .map(|(seat, spec)| create_bot_from_spec(spec, PlayerId(seat), seed).unwrap())
+        .collect();
+    play_out(&mut game, &mut bots, 2000, |_| {}).unwrap();
+    game
+}
+
+#[test]
+fn strength_levels_scale_the_knobs() {
+    assert!(Strength::new(0).is_err());
+    assert!(Strength::new(11).is_err());
+    assert_eq!("4".parse::<Strength>(), Strength::new(4));
+    assert_eq!(Strength::default(), Strength::MAX);
+
+    assert_eq!(Strength::MAX.node_budget(20_000), 20_000);
+    assert_eq!(Strength::MIN.node_budget(20_000), 200);
+    assert_eq!(Strength::MAX.blunder_rate(), 0.0);
+    assert_eq!(Strength::MAX.temperature(300.0), 0.0);
+    assert!(Strength::MIN.blunder_rate() > Strength::new(5).unwrap().blunder_rate());
+
+    let mut rng = StdRng::seed_from_u64(1);
+    let scores = [1.0, 5.0, 5.0, -3.0];
+    assert_eq!(Strength::MAX.choose(&mut rng, &scores, 1.0), Some(2));
+    assert_eq!(Strength::MIN.choose(&mut rng, &[], 1.0), None);
+    let weak = Strength::new(3).unwrap();
+    for _ in 0..50 {
+        assert!(weak.choose(&mut rng, &scores, 1.0).unwrap() < scores.len());
+    }
+}
+
+#[test]
+fn strength_spec_option() {
+    let full = play(["heuristic19", "heuristic13"], 4);
+    let explicit = play(["heuristic19:strength=10", "heuristic13"], 4);
+    assert_eq!(full.history(), explicit.history());
+
+    let weak = play(["heuristic19:strength=2", "beam:2:4:strength=2"], 4);
+    let again = play(["heuristic19:strength=2", "beam:2:4:strength=2"], 4);
+    assert_eq!(weak.history(), again.history());
+
+    assert!(create_bot_from_spec("heuristic19:strength=0", PlayerId(0), 0).is_err());
+    assert!(create_bot_from_spec("beam:strength=x", PlayerId(0), 0).is_err());
+    assert!(create_bot_from_spec("beam:depth=3", PlayerId(0), 0).is_err());
+}
@@ -0,0 +1,306 @@
+use skipbot::bots::create_bot_from_spec;
+use skipbot::interrupt::Interrupter;
+use skipbot::value::{
+    Calibration, Selection, ValueEnsemble, ValueNet, ValueSample, ValueTrainConfig,
+    canonical_order, play_strength, train_value_net, train_value_net_until, train_value_net_with,
+};
+use skipbot::{Game, PlayerId, STATE_FEATURES};
+
+/// Positions whose outcome is a noisy function of the first feature.
+fn synthetic(games: usize) -> Vec<ValueSample> {
+    (0..games)
+        .flat_map(|game| {
+            (0..10).map(move |ply| {
+                let x = ((game * 7 + ply * 3) % 20) as f32 / 20.0;
+                let won = (game + ply) % 10 < (x * 10.0) as usize;
+                ValueSample {
+                    game,
+                    features: vec![x, 1.0 - x, 0.5],
+                    outcome: if won { 1.0 } else { -1.0 },
+                }
+            })
+        })
+        .collect()
+}
+
+#[test]
+fn training_learns_a_calibrated_win_probability() {
+    let samples = synthetic(300);
+    let config = ValueTrainConfig {
+        hidden: 8,
+        epochs: 30,
+        learning_rate: 1e-2,
+        seed: 3,
+        ..ValueTrainConfig::default()
+    };
+    let training = train_value_net(&samples, &config).unwrap();
+    let losses = training.losses();
+    assert!(losses.last() < losses.first());
+    assert!(training.epochs.iter().all(|epoch| epoch.win_rate.is_none()));
+
+    let validation = &training.validation;
+    assert!(validation.samples > 0 && validation.samples < samples.len());
+    assert_eq!(validation.samples + training.train.samples, samples.len());
+    assert_eq!(
+        validation.bins.iter().map(|bin| bin.count).sum::<usize>(),
+        validation.samples
+    );
+    // A constant 0.5 scores a Brier of 0.25; the model must beat it.
+    assert!(validation.brier < 0.22, "{validation:?}");
+    assert!(validation.ece < 0.1, "{validation:?}");
+    assert!(training.net.predict(&[0.9, 0.1, 0.5]) > training.net.predict(&[0.1, 0.9, 0.5]));
+}
+
+#[test]
+fn deterministic_training_ignores_the_sample_order() {
+    let samples = synthetic(60);
+    // The same samples as two collectors might interleave them.
+    let mut merged = samples.clone();
+    merged.reverse();
+    merged.rotate_left(17);
+    let config = ValueTrainConfig {
+        hidden: 4,
+        epochs: 3,
+        seed: 5,
+        ..ValueTrainConfig::default()
+    };
+    let as_given = |samples: &[ValueSample]| train_value_net(samples, &config).unwrap().net;
+    assert_ne!(as_given(&samples), as_given(&merged));
+
+    let deterministic = ValueTrainConfig {
+        deterministic: true,
+        ..config.clone()
+    };
+    let first = train_value_net(&samples, &deterministic).unwrap();
+    let second = train_value_net(&merged, &deterministic).unwrap();
+    assert_eq!(first.net, second.net);
+    assert_eq!(first.epochs, second.epochs);
+
+    canonical_order(&mut merged);
+    let mut sorted = samples;
+    canonical_order(&mut sorted);
+    assert_eq!(merged, sorted);
+}
+
+#[test]
+fn a_constant_even_forecast_is_calibrated_but_uninformative() {
+    let net = ValueNet {
+        inputs: 1,
+        hidden: 1,
+        w1: vec![0.0],
+        b1: vec![0.0],
+        w2: vec![0.0],
+        b2: 0.0,
+    };
+    let samples: Vec<ValueSample> = (0..4)
+        .map(|game| ValueSample {
+            game,
+            features: vec![0.0],
+            outcome: if game % 2 == 0 { 1.0 } else { -1.0 },
+        })
+        .collect();
+    let calibration = Calibration::measure(&net, &samples, 4);
+    assert!((calibration.brier - 0.25).abs() < 1e-9);
+    assert!(calibration.ece < 1e-9);
+    assert_eq!(calibration.bins[2].count, 4);
+}
+
+#[test]
+fn untrained_nets_evaluate_views() {
+    let game = Game::builder(2).unwrap().with_seed(1).build().unwrap();
+    let net = ValueNet::new(4, 1);
+    assert_eq!(net.inputs, STATE_FEATURES);
+    let owned = net.evaluate(&game.state_view(PlayerId(0)).unwrap());
+    let borrowed = net.evaluate(&game.state_view_ref(PlayerId(0)).unwrap());
+    assert_eq!(owned, borrowed);
+    assert!((0.0..=1.0).contains(&owned));
+}
+
+#[test]
+fn training_can_track_game_play_strength() {
+    let game = Game::builder(2).unwrap().with_seed(2).build().unwrap();
+    let features = skipbot::StateEncoder::encode(&game.state_view(PlayerId(0)).unwrap());
+    let samples: Vec<ValueSample> = (0..20)
+        .map(|game| ValueSample {
+            game,
+            features: features.to_vec(),
+            outcome: if game % 2 == 0 { 1.0 } else { -1.0 },
+        })
+        .collect();
+    let config = ValueTrainConfig {
+        hidden: 4,
+        epochs: 3,
+        eval_games: 2,
+        eval_every: 2,
+        ..ValueTrainConfig::default()
+    };
+    let mut seen = Vec::new();
+    let training =
+        train_value_net_with(&samples, &config, |metrics| seen.push(metrics.clone())).unwrap();
+    assert_eq!(seen, training.epochs);
+    let evaluated: Vec<usize> = seen
+        .iter()
+        .filter(|epoch| epoch.win_rate.is_some())
+        .map(|epoch| epoch.epoch)
+        .collect();
+    assert_eq!(evaluated, [2, 3]);
+    assert!(
+        seen.iter()
+            .flat_map(|epoch| epoch.win_rate)
+            .all(|rate| (0.0..=1.0).contains(&rate))
+    );
+
+    // Nets over other features cannot drive a bot.
+    assert!(play_strength(&ValueNet::with_inputs(3, 2, 0), "random", 0, 2).is_err());
+    let evaluating = ValueTrainConfig {
+        eval_games: 2,
+        ..ValueTrainConfig::default()
+    };
+    assert!(train_value_net(&synthetic(20), &evaluating).is_err());
+}
+
+#[test]
+fn training_returns_the_best_epoch_when_selecting() {
+    let samples = synthetic(100);
+    let config = ValueTrainConfig {
+        hidden: 4,
+        epochs: 8,
+        learning_rate: 5e-2,
+        seed: 1,
+        best_by: Some(Selection::ValidationLoss),
+        ..ValueTrainConfig::default()
+    };
+    let training = train_value_net(&samples, &config).unwrap();
+    let best = training.best.as_ref().unwrap();
+    let lowest = training
+        .epochs
+        .iter()
+        .min_by(|a, b| a.validation_loss.total_cmp(&b.validation_loss))
+        .unwrap();
+    assert_eq!(best.epoch, lowest.epoch);
+    assert!(training.epochs[best.epoch - 1].best);
+    assert_eq!(best.win_rate, None);
+    assert!((training.validation.log_loss - best.validation_loss).abs() < 1e-9);
+
+    // Win rates from too few games are not trusted.
+    let by_win_rate = ValueTrainConfig {
+        best_by: Some(Selection::WinRate),
+        eval_games: 4,
+        min_eval_games: 10,
+        ..config
+    };
+    assert!(train_value_net(&samples, &by_win_rate).is_err());
+    assert_eq!("win-rate".parse(), Ok(Selection::WinRate));
+}
+
+#[test]
+fn interrupted_training_keeps_the_finished_epochs() {
+    let samples = synthetic(50);
+    let config = ValueTrainConfig {
+        hidden: 4,
+        epochs: 5,
+        ..ValueTrainConfig::default()
+    };
+    let interrupter = Interrupter::new();
+    let training = train_value_net_until(&samples, &config, &interrupter, |metrics| {
+        if metrics.epoch == 2 {
+            interrupter.cancel();
+        }
+    })
+    .unwrap();
+    assert!(training.interrupted);
+    assert_eq!(training.losses().len(), 2);
+    assert_eq!(
+        training.epochs,
+        train_value_net(&samples, &config).unwrap().epochs[..2]
+    );
+
+    // An epoch always finishes, and cancelling during the last one changes nothing.
+    let first = train_value_net_until(&samples, &config, &interrupter, |_| {}).unwrap();
+    assert_eq!(first.epochs.len(), 1);
+    let late = Interrupter::new();
+    let finished = train_value_net_until(&samples, &config, &late, |metrics| {
+        if metrics.epoch == 5 {
+            late.cancel();
+        }
+    })
+    .unwrap();
+    assert!(!finished.interrupted);
+}
+
+#[test]
+fn training_charts_are_drawn_from_the_epoch_metrics() {
+    use skipbot::charts::render_training_charts;
+
+    let config = ValueTrainConfig {
+        hidden: 4,
+        epochs: 4,
+        learning_rate: 1e-2,
+        ..ValueTrainConfig::default()
+    };
+    let mut epochs = train_value_net(&synthetic(40), &config).unwrap().epochs;
+    assert!(
+        epochs
+            .iter()
+            .all(|epoch| epoch.learning_rate == 1e-2f32 as f64)
+    );
+
+    let dir = std::env::temp_dir().join("skipbot-training-charts");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let written = render_training_charts(&dir, &epochs).unwrap();
+    let names: Vec<_> = written.iter().map(|p| p.file_name().unwrap()).collect();
+    assert_eq!(names, ["loss.png", "learning-rate.png"]);
+
+    epochs[1].win_rate = Some(0.4);
+    epochs[3].win_rate = Some(0.55);
+    let written = render_training_charts(&dir, &epochs).unwrap();
+    assert_eq!(written.len(), 3);
+    assert!(written.iter().all(|p| p.metadata().unwrap().len() > 0));
+    assert!(render_training_charts(&dir, &[]).is_err());
+}
+
+#[test]
+fn ensembles_average_checkpoint_logits() {
+    let features = [0.2, 0.7, 0.5];
+    let nets = [
+        ValueNet::with_inputs(3, 4, 1),
+        ValueNet::with_inputs(3, 4, 2),
+    ];
+    let ensemble = ValueEnsemble::new(nets.to_vec()).unwrap();
+    let mean = (nets[0].logit(&features) + nets[1].logit(&features)) / 2.0;
+    assert!((ensemble.predict(&features) - 1.0 / (1.0 + (-mean).exp())).abs() < 1e-6);
+    let single = ValueEnsemble::new(vec![nets[0].clone()]).unwrap();
+    assert_eq!(single.predict(&features), nets[0].predict(&features));
+
+    assert!(ValueEnsemble::new(Vec::new()).is_err());
+    assert!(ValueEnsemble::new(vec![nets[0].clone(), ValueNet::with_inputs(5, 4, 3)]).is_err());
+}
+
+#[test]
+fn beam_bots_play_with_an_ensemble_of_checkpoints() {
+    let dir = std::env::temp_dir().join("skipbot-value-ensemble-test");
+    std::fs::create_dir_all(&dir).unwrap();
+    let paths: Vec<String> = (0..2)
+        .map(|seed| {
+            let path = dir.join(format!("value-{seed}.json"));
+            ValueNet::new(4, seed).save(&path).unwrap();
+            path.display().to_string()
+        })
+        .collect();
+    let spec = format!("beam:2:2:value={}", paths.join(","));
+    let mut bot = create_bot_from_spec(&spec, PlayerId(0), 1).unwrap();
+    let game = Game::builder(2).unwrap().with_seed(3).build().unwrap();
+    let view = game.state_view(PlayerId(0)).unwrap();
+    let legal = view.legal_actions();
+    assert!(legal.contains(&bot.select_action(&view, &legal)));
+
+    assert!(create_bot_from_spec(&format!("{spec}:eval=tempo"), PlayerId(0), 1).is_err());
+    let missing = dir.join("missing.json").display().to_string();
+    assert!(create_bot_from_spec(&format!("beam:value={missing}"), PlayerId(0), 1).is_err());
+    let small = dir.join("small.json");
+    ValueNet::with_inputs(3, 4, 0).save(&small).unwrap();
+    let small = format!("beam:value={}", small.display());
+    assert!(create_bot_from_spec(&small, PlayerId(0), 1).is_err());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,17 @@
+use skipbot::visualize::render_state_with_options;
+use skipbot::{Game, PlayerId, VisualOptions, render_state};
+
+#[test]
+fn evaluation_bar_is_rendered_only_when_requested() {
+    let game = Game::builder(3).unwrap().with_seed(5).build().unwrap();
+    let state = game.state_view(PlayerId(0)).unwrap();
+    assert!(!render_state(&state).contains("Evaluation:"));
+
+    let options = VisualOptions::default().with_win_probabilities(&[0.5, 0.25, 1.5]);
+    let rendered = render_state_with_options(&state, options);
+    assert!(rendered.contains("Evaluation:"));
+    assert!(rendered.contains("  Player 0 [##########----------]  50.0%"));
+    assert!(rendered.contains("  Player 1 [#####---------------]  25.0%"));
+    // Out-of-range inputs are clamped.
+    assert!(rendered.contains("  Player 2 [####################] 100.0%"));
+}
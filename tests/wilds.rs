@@ -0,0 +1,72 @@
+use skipbot::action::{Action, BuildPileId, CardSource, DiscardPileId};
+use skipbot::bots::planning::TurnPosition;
+use skipbot::bots::wilds::WildPolicy;
+use skipbot::card::Card;
+
+fn position(build_next: [u8; 4], hand: Vec<Card>, stock: u8) -> TurnPosition {
+    TurnPosition {
+        build_next,
+        hand,
+        discard_piles: vec![vec![]; 4],
+        stock_top: Some(Card::Number(stock)),
+        stock_count: 20,
+        stock_played: 0,
+        piles_completed: 0,
+        wilds_spent: 0,
+    }
+}
+
+fn play(source: CardSource, pile: usize) -> Action {
+    Action::Play {
+        source,
+        build_pile: BuildPileId(pile),
+    }
+}
+
+#[test]
+fn holding_is_worth_more_close_to_the_stock_and_on_tight_piles() {
+    let policy = WildPolicy::new();
+    let spread = |stock| policy.hold_value(&position([2, 4, 6, 8], vec![], stock));
+    assert_eq!(
+        WildPolicy::stock_distance(&position([2, 4, 6, 8], vec![], 9)),
+        1
+    );
+    assert_eq!(spread(9), 1.0);
+    assert_eq!(spread(12), 0.25, "four steps from the 8");
+    assert_eq!(spread(8), 0.25, "the stock card fits already");
+
+    let tight = policy.hold_value(&position([8, 8, 8, 8], vec![], 9));
+    assert_eq!(tight, 1.75);
+    let with_wild = policy.hold_value(&position([2, 4, 6, 8], vec![Card::SkipBo], 9));
+    assert_eq!(with_wild, 0.5, "a second wild is worth less");
+}
+
+#[test]
+fn spending_a_wild_costs_its_hold_value_unless_it_reaches_the_stock() {
+    let policy = WildPolicy::new();
+    let here = position([2, 4, 6, 8], vec![Card::SkipBo, Card::Number(2)], 10);
+    // On the 8 pile the wild still leaves the stock 10 a step away: no stock progress.
+    assert_eq!(
+        policy.spend_penalty(&here, &play(CardSource::Hand(0), 3)),
+        0.5
+    );
+    // Number cards and non-plays cost nothing.
+    assert_eq!(
+        policy.spend_penalty(&here, &play(CardSource::Hand(1), 0)),
+        0.0
+    );
+    assert_eq!(policy.spend_penalty(&here, &Action::EndTurn), 0.0);
+
+    // One step short, the wild makes the stock card fit: stock progress is free.
+    let short = position([2, 4, 6, 9], vec![Card::SkipBo], 10);
+    assert_eq!(
+        policy.spend_penalty(&short, &play(CardSource::Hand(0), 3)),
+        0.0
+    );
+
+    // A wild on a discard pile counts the same way.
+    let mut discard = position([2, 4, 6, 8], vec![], 10);
+    discard.discard_piles[1].push(Card::SkipBo);
+    let from_discard = play(CardSource::Discard(DiscardPileId(1)), 0);
+    assert_eq!(policy.spend_penalty(&discard, &from_discard), 0.5);
+}